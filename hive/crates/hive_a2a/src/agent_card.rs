@@ -142,6 +142,7 @@ pub fn build_hive_agent_info(config: &A2aConfig) -> SimpleAgentInfo {
         )
         .with_version(env!("CARGO_PKG_VERSION").to_string())
         .with_streaming()
+        .with_push_notifications()
         .with_state_transition_history()
         .with_skills(build_skills())
 }
@@ -165,7 +166,7 @@ pub fn build_hive_agent_card(config: &A2aConfig) -> AgentCard {
         documentation_url: Some("https://hivecode.app/docs/a2a".to_string()),
         capabilities: AgentCapabilities {
             streaming: true,
-            push_notifications: false,
+            push_notifications: true,
             state_transition_history: true,
         },
         security_schemes: None,
@@ -222,7 +223,7 @@ mod tests {
         // Capabilities
         assert!(card.capabilities.streaming);
         assert!(card.capabilities.state_transition_history);
-        assert!(!card.capabilities.push_notifications);
+        assert!(card.capabilities.push_notifications);
 
         // Skills
         assert_eq!(card.skills.len(), 4);
@@ -1,9 +1,11 @@
 //! Authentication and URL validation middleware for the A2A protocol.
 //!
 //! Provides API key validation for inbound requests and URL security
-//! validation for outbound connections to remote agents.
+//! validation for outbound connections to remote agents and webhooks.
 
-use url::Url;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use url::{Host, Url};
 
 use crate::error::A2aError;
 
@@ -33,61 +35,164 @@ pub fn validate_api_key_optional(
     }
 }
 
-/// Returns `true` if the host is a localhost address.
-fn is_localhost(host: &str) -> bool {
-    host == "localhost" || host == "127.0.0.1" || host == "::1" || host == "[::1]"
+/// Returns `true` if the host name refers to the local machine.
+fn is_localhost_name(host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    host == "localhost" || host.ends_with(".localhost")
+}
+
+/// Returns `true` if `ip` is not a public unicast address: loopback,
+/// private, link-local, shared (CGNAT), multicast, documentation or
+/// otherwise reserved. Outbound connections to these are blocked (SSRF
+/// protection).
+pub fn is_blocked_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_blocked_ipv4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_blocked_ipv4(mapped);
+            }
+            let segments = ip.segments();
+            // 64:ff9b::/96 (NAT64) embeds an IPv4 address.
+            if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                let [a, b] = segments[6].to_be_bytes();
+                let [c, d] = segments[7].to_be_bytes();
+                return is_blocked_ipv4(Ipv4Addr::new(a, b, c, d));
+            }
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || (segments[0] & 0xfe00) == 0xfc00 // fc00::/7 (unique local)
+                || (segments[0] & 0xffc0) == 0xfe80 // fe80::/10 (link-local)
+                || (segments[0] == 0x2001 && segments[1] == 0x0db8) // 2001:db8::/32
+        }
+    }
 }
 
-/// Returns `true` if the host is a private/reserved IP that should be blocked
-/// for outbound connections (SSRF protection).
-fn is_private_ip(host: &str) -> bool {
-    // Parse as IPv4 and check private ranges
-    if let Ok(ip) = host.parse::<std::net::Ipv4Addr>() {
-        let octets = ip.octets();
-        return octets[0] == 10 // 10.0.0.0/8
-            || (octets[0] == 192 && octets[1] == 168) // 192.168.0.0/16
-            || (octets[0] == 169 && octets[1] == 254) // 169.254.0.0/16 (link-local)
-            || ip.is_unspecified(); // 0.0.0.0
-    }
-    false
+fn is_blocked_ipv4(ip: Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    ip.is_private() // 10/8, 172.16/12, 192.168/16
+        || ip.is_loopback() // 127/8
+        || ip.is_link_local() // 169.254/16
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || octets[0] == 0 // 0/8, including 0.0.0.0
+        || (octets[0] == 100 && (octets[1] & 0xc0) == 64) // 100.64/10 (CGNAT)
+        || (octets[0] == 192 && octets[1] == 0 && octets[2] == 0) // 192.0.0/24
+        || (octets[0] == 198 && (octets[1] & 0xfe) == 18) // 198.18/15
+        || octets[0] >= 240 // 240/4 (reserved)
 }
 
 /// Validate that an outbound URL is safe to connect to.
 ///
 /// Rules:
-/// - Localhost addresses (127.0.0.1, localhost, ::1) are allowed with any scheme
-/// - Non-localhost URLs must use HTTPS
-/// - Private IPs (10.x, 192.168.x, 169.254.x, 0.0.0.0) are blocked
+/// - The URL must use HTTPS
+/// - Localhost names (`localhost`, `*.localhost`) are blocked
+/// - Literal loopback, private and reserved IPs are blocked (see
+///   [`is_blocked_ip`])
+///
+/// This only inspects the URL itself; use [`resolve_outbound_url`] to also
+/// check the addresses a host name resolves to.
 pub fn validate_outbound_url(url: &str) -> Result<(), A2aError> {
     let parsed = Url::parse(url).map_err(|e| A2aError::Security(format!("Invalid URL: {e}")))?;
 
-    let host = parsed
-        .host_str()
-        .ok_or_else(|| A2aError::Security("URL has no host".into()))?;
-
-    // Localhost is always allowed (any scheme)
-    if is_localhost(host) {
-        return Ok(());
-    }
-
-    // Non-localhost must be HTTPS
     if parsed.scheme() != "https" {
         return Err(A2aError::Security(format!(
-            "Non-localhost URL must use HTTPS, got {}://",
+            "Outbound URL must use HTTPS, got {}://",
             parsed.scheme()
         )));
     }
 
-    // Block private IPs (SSRF protection)
-    if is_private_ip(host) {
+    let ip = match parsed.host() {
+        None => return Err(A2aError::Security("URL has no host".into())),
+        Some(Host::Domain(domain)) => {
+            if is_localhost_name(domain) {
+                return Err(A2aError::Security(format!(
+                    "Outbound connections to {domain} are blocked"
+                )));
+            }
+            return Ok(());
+        }
+        Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+    };
+
+    if is_blocked_ip(ip) {
         return Err(A2aError::Security(format!(
-            "Outbound connections to private IP {host} are blocked"
+            "Outbound connections to non-public IP {ip} are blocked"
         )));
     }
-
     Ok(())
 }
 
+/// [`validate_outbound_url`], then resolve the host and reject it if any of
+/// its addresses is not public.
+///
+/// Returns the resolved addresses.
+pub async fn resolve_outbound_url(url: &str) -> Result<Vec<SocketAddr>, A2aError> {
+    validate_outbound_url(url)?;
+    let parsed = Url::parse(url).map_err(|e| A2aError::Security(format!("Invalid URL: {e}")))?;
+    let port = parsed.port_or_known_default().unwrap_or(443);
+    match parsed.host() {
+        Some(Host::Domain(domain)) => resolve_public(domain, port).await,
+        Some(Host::Ipv4(ip)) => Ok(vec![SocketAddr::new(IpAddr::V4(ip), port)]),
+        Some(Host::Ipv6(ip)) => Ok(vec![SocketAddr::new(IpAddr::V6(ip), port)]),
+        None => Err(A2aError::Security("URL has no host".into())),
+    }
+}
+
+/// Resolve `host` and fail if any address is not public.
+async fn resolve_public(host: &str, port: u16) -> Result<Vec<SocketAddr>, A2aError> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| A2aError::Network(format!("Failed to resolve {host}: {e}")))?
+        .collect();
+    if let Some(blocked) = addrs.iter().find(|addr| is_blocked_ip(addr.ip())) {
+        return Err(A2aError::Security(format!(
+            "{host} resolves to non-public address {}",
+            blocked.ip()
+        )));
+    }
+    Ok(addrs)
+}
+
+/// DNS resolver for HTTP clients that talk to untrusted URLs.
+///
+/// Checking a host once at registration is not enough: its DNS record can
+/// be re-pointed at the internal network afterwards. This resolver repeats
+/// the check on every connection.
+pub struct PublicOnlyResolver;
+
+impl reqwest::dns::Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs = resolve_public(&host, 0).await?;
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Validate the URL of a remote agent the user configured in `a2a.toml`.
+///
+/// Same as [`validate_outbound_url`], except that agents on the local
+/// machine (`localhost`, `127.0.0.1`, `::1`) are allowed over any scheme:
+/// the user chose them, unlike webhook URLs supplied by remote callers.
+pub fn validate_agent_url(url: &str) -> Result<(), A2aError> {
+    let parsed = Url::parse(url).map_err(|e| A2aError::Security(format!("Invalid URL: {e}")))?;
+    let local = match parsed.host() {
+        Some(Host::Domain(domain)) => domain == "localhost",
+        Some(Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    };
+    if local {
+        return Ok(());
+    }
+    validate_outbound_url(url)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,26 +244,30 @@ mod tests {
     }
 
     #[test]
-    fn test_outbound_url_localhost_allowed() {
-        assert!(validate_outbound_url("http://localhost:7420").is_ok());
-        assert!(validate_outbound_url("http://127.0.0.1:8080").is_ok());
-    }
-
-    #[test]
-    fn test_outbound_url_ipv6_localhost_allowed() {
-        assert!(validate_outbound_url("http://[::1]:7420").is_ok());
+    fn test_outbound_url_localhost_blocked() {
+        assert!(validate_outbound_url("http://localhost:7420").is_err());
+        assert!(validate_outbound_url("https://localhost:7420").is_err());
+        assert!(validate_outbound_url("https://api.localhost").is_err());
+        assert!(validate_outbound_url("https://127.0.0.1:8080").is_err());
+        assert!(validate_outbound_url("https://[::1]:7420").is_err());
     }
 
     #[test]
     fn test_outbound_url_private_ips_blocked() {
         assert!(validate_outbound_url("https://10.0.0.1").is_err());
+        assert!(validate_outbound_url("https://172.16.5.4").is_err());
         assert!(validate_outbound_url("https://192.168.1.1").is_err());
-        assert!(validate_outbound_url("https://169.254.1.1").is_err());
+        assert!(validate_outbound_url("https://169.254.169.254").is_err());
+        assert!(validate_outbound_url("https://100.64.0.1").is_err());
+        assert!(validate_outbound_url("https://[fd00::1]").is_err());
+        assert!(validate_outbound_url("https://[fe80::1]").is_err());
+        assert!(validate_outbound_url("https://[::ffff:10.0.0.1]").is_err());
     }
 
     #[test]
     fn test_outbound_url_zero_blocked() {
         assert!(validate_outbound_url("https://0.0.0.0").is_err());
+        assert!(validate_outbound_url("https://[::]").is_err());
     }
 
     #[test]
@@ -172,5 +281,33 @@ mod tests {
         assert!(
             validate_outbound_url("https://remote-agent.fly.dev/.well-known/agent.json").is_ok()
         );
+        assert!(validate_outbound_url("https://93.184.216.34/hook").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_outbound_url_rejects_names_for_loopback() {
+        // `localhost.` with a trailing dot is still a localhost name.
+        assert!(resolve_outbound_url("https://localhost./hook")
+            .await
+            .is_err());
+        let addrs = resolve_outbound_url("https://93.184.216.34:8443/hook")
+            .await
+            .unwrap();
+        assert_eq!(addrs, vec!["93.184.216.34:8443".parse().unwrap()]);
+    }
+
+    #[tokio::test]
+    async fn test_public_only_resolver_rejects_loopback() {
+        let err = resolve_public("localhost", 0).await.unwrap_err();
+        assert!(matches!(err, A2aError::Security(_)));
+    }
+
+    #[test]
+    fn test_agent_url_allows_local_agents() {
+        assert!(validate_agent_url("http://localhost:7420").is_ok());
+        assert!(validate_agent_url("http://127.0.0.1:8080").is_ok());
+        assert!(validate_agent_url("http://[::1]:7420").is_ok());
+        assert!(validate_agent_url("http://agent.example.com").is_err());
+        assert!(validate_agent_url("https://10.0.0.1").is_err());
     }
 }
@@ -11,7 +11,7 @@ use std::time::{Duration, Instant};
 
 use a2a_rs::AgentCard;
 
-use crate::auth::validate_outbound_url;
+use crate::auth::validate_agent_url;
 use crate::error::A2aError;
 
 // ---------------------------------------------------------------------------
@@ -94,7 +94,7 @@ pub async fn discover_agent(base_url: &str, cache: &DiscoveryCache) -> Result<Ag
 
     // 2. Build and validate URL
     let card_url = agent_card_url(base_url);
-    validate_outbound_url(&card_url)?;
+    validate_agent_url(&card_url)?;
 
     // 3. Fetch
     let response = reqwest::get(&card_url).await.map_err(|e| {
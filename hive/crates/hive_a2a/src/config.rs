@@ -1,10 +1,12 @@
 //! A2A configuration types.
 
 use std::path::Path;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
 use crate::error::A2aError;
+use crate::store::{RetentionPolicy, DEFAULT_SPILL_THRESHOLD_BYTES};
 
 /// Top-level A2A configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,6 +83,8 @@ pub struct ServerConfig {
     pub rate_limit_rpm: u32,
     #[serde(default)]
     pub defaults: ServerDefaults,
+    #[serde(default)]
+    pub task_store: TaskStoreConfig,
 }
 
 impl Default for ServerConfig {
@@ -93,6 +97,7 @@ impl Default for ServerConfig {
             max_concurrent_tasks: 10,
             rate_limit_rpm: 60,
             defaults: ServerDefaults::default(),
+            task_store: TaskStoreConfig::default(),
         }
     }
 }
@@ -118,6 +123,57 @@ impl Default for ServerDefaults {
     }
 }
 
+/// Where the server keeps A2A tasks and for how long.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskStoreConfig {
+    #[serde(default)]
+    pub backend: TaskStoreBackend,
+    /// Days to keep finished tasks; `0` keeps them until the per-client
+    /// limit pushes them out.
+    #[serde(default = "default_retention_days")]
+    pub retention_days: u64,
+    /// Finished tasks kept per client; `0` means no limit.
+    #[serde(default = "default_max_tasks_per_client")]
+    pub max_tasks_per_client: usize,
+    /// Artifacts larger than this many bytes are stored as files.
+    #[serde(default = "default_spill_threshold_bytes")]
+    pub spill_threshold_bytes: usize,
+}
+
+impl Default for TaskStoreConfig {
+    fn default() -> Self {
+        Self {
+            backend: TaskStoreBackend::default(),
+            retention_days: 30,
+            max_tasks_per_client: 1000,
+            spill_threshold_bytes: DEFAULT_SPILL_THRESHOLD_BYTES,
+        }
+    }
+}
+
+impl TaskStoreConfig {
+    /// The retention policy these settings describe.
+    pub fn retention_policy(&self) -> RetentionPolicy {
+        RetentionPolicy {
+            max_age: (self.retention_days > 0)
+                .then(|| Duration::from_secs(self.retention_days * 24 * 60 * 60)),
+            max_tasks_per_client: (self.max_tasks_per_client > 0)
+                .then_some(self.max_tasks_per_client),
+        }
+    }
+}
+
+/// Task store implementation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStoreBackend {
+    /// SQLite database next to `a2a.toml`; survives restarts.
+    #[default]
+    Sqlite,
+    /// In-memory only; tasks are lost when the server stops.
+    Memory,
+}
+
 /// Client-side configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClientConfig {
@@ -170,6 +226,15 @@ fn default_max_time_seconds() -> u64 {
 fn default_skill() -> String {
     "hivemind".to_string()
 }
+fn default_retention_days() -> u64 {
+    30
+}
+fn default_max_tasks_per_client() -> usize {
+    1000
+}
+fn default_spill_threshold_bytes() -> usize {
+    DEFAULT_SPILL_THRESHOLD_BYTES
+}
 fn default_discovery_cache_ttl() -> u64 {
     300
 }
@@ -196,6 +261,9 @@ mod tests {
         assert_eq!(config.client.request_timeout_seconds, 60);
         assert!(config.agents.is_empty());
         assert!(config.server.api_key.is_none());
+        assert_eq!(config.server.task_store.backend, TaskStoreBackend::Sqlite);
+        assert_eq!(config.server.task_store.retention_days, 30);
+        assert_eq!(config.server.task_store.max_tasks_per_client, 1000);
     }
 
     #[test]
@@ -214,6 +282,11 @@ max_budget_usd = 5.0
 max_time_seconds = 600
 default_skill = "coordinator"
 
+[server.task_store]
+backend = "memory"
+retention_days = 0
+max_tasks_per_client = 10
+
 [client]
 discovery_cache_ttl_seconds = 600
 request_timeout_seconds = 120
@@ -237,6 +310,10 @@ url = "http://localhost:8080/.well-known/agent.json"
         assert_eq!(config.server.defaults.max_budget_usd, 5.0);
         assert_eq!(config.server.defaults.max_time_seconds, 600);
         assert_eq!(config.server.defaults.default_skill, "coordinator");
+        assert_eq!(config.server.task_store.backend, TaskStoreBackend::Memory);
+        let policy = config.server.task_store.retention_policy();
+        assert!(policy.max_age.is_none());
+        assert_eq!(policy.max_tasks_per_client, Some(10));
         assert_eq!(config.client.discovery_cache_ttl_seconds, 600);
         assert_eq!(config.client.request_timeout_seconds, 120);
         assert_eq!(config.agents.len(), 2);
@@ -12,6 +12,9 @@ pub enum A2aError {
     #[error("Task not found: {0}")]
     TaskNotFound(String),
 
+    #[error("Task cannot be canceled: {0}")]
+    NotCancelable(String),
+
     #[error("Unsupported skill: {0}")]
     UnsupportedSkill(String),
 
@@ -35,6 +38,9 @@ pub enum A2aError {
 
     #[error("Security validation failed: {0}")]
     Security(String),
+
+    #[error("Task store error: {0}")]
+    Storage(String),
 }
 
 #[cfg(test)]
@@ -54,6 +60,7 @@ mod tests {
             A2aError::Config("cfg".into()),
             A2aError::Auth("auth".into()),
             A2aError::TaskNotFound("task-123".into()),
+            A2aError::NotCancelable("task-123".into()),
             A2aError::UnsupportedSkill("unknown".into()),
             A2aError::BudgetExceeded {
                 limit: 1.0,
@@ -65,9 +72,10 @@ mod tests {
             A2aError::Network("connection refused".into()),
             A2aError::RateLimited,
             A2aError::Security("blocked path".into()),
+            A2aError::Storage("disk full".into()),
         ];
-        // All 11 variants must be constructible
-        assert_eq!(variants.len(), 11);
+        // All 13 variants must be constructible
+        assert_eq!(variants.len(), 13);
     }
 
     #[test]
@@ -10,9 +10,11 @@ pub mod bridge;
 pub mod client;
 pub mod config;
 pub mod error;
+pub mod push;
 pub mod remote_agent;
 pub mod server;
 pub mod service;
+pub mod store;
 pub mod streaming;
 pub mod task_handler;
 
@@ -20,7 +22,9 @@ pub mod task_handler;
 pub use client::{discover_agent, DiscoveryCache};
 pub use config::A2aConfig;
 pub use error::A2aError;
+pub use push::{PushNotificationConfig, PushNotifier};
 pub use remote_agent::RemoteAgent;
 pub use server::{start_server, start_server_with_handler, TaskHandlerAdapter};
 pub use service::{A2aClientService, RemoteAgentRunResult, RemoteAgentSummary};
+pub use store::{open_task_store, MemoryTaskStore, SqliteTaskStore, TaskStore};
 pub use task_handler::{HiveTaskHandler, ProviderExecutor};
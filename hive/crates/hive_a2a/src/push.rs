@@ -0,0 +1,648 @@
+//! Push notifications — webhook delivery of A2A task updates.
+//!
+//! Clients register a [`PushNotificationConfig`] for a task, either through
+//! `tasks/pushNotificationConfig/set` or inline in `message/send`, and the
+//! server POSTs the full [`Task`] to every registered URL whenever the task's
+//! status changes.
+//!
+//! Registering a config issues a fresh `signingSecret`, returned to the
+//! client in the registration response (and on lookup) but never sent with
+//! a delivery. Each delivery carries:
+//! - `Authorization: Bearer …` — when the config lists the `Bearer` scheme
+//!   with credentials
+//! - `X-Hive-Timestamp` / `X-Hive-Signature` —
+//!   `sha256=<hex HMAC-SHA256(signingSecret, "{timestamp}.{body}")>` so the
+//!   receiver can verify the payload and reject replays
+//!
+//! The client's `token` is stored and echoed back on lookup only; it is not
+//! sent to the webhook, where anyone able to read one delivery could replay
+//! it.
+//!
+//! Webhook URLs come from remote callers, so they must be public HTTPS
+//! endpoints: loopback and private targets are refused at registration and
+//! again at connect time, and redirects are not followed.
+//!
+//! Failed deliveries (network errors, `429` and `5xx`) are retried with
+//! exponential backoff; other client errors are not retried.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use a2a_rs::Task;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::auth::{resolve_outbound_url, validate_outbound_url, PublicOnlyResolver};
+use crate::error::A2aError;
+
+/// Header carrying the Unix timestamp the payload was signed at.
+pub const TIMESTAMP_HEADER: &str = "X-Hive-Timestamp";
+
+/// Header carrying the payload signature.
+pub const SIGNATURE_HEADER: &str = "X-Hive-Signature";
+
+// ---------------------------------------------------------------------------
+// Config types
+// ---------------------------------------------------------------------------
+
+/// Where and how to deliver push notifications for a task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushNotificationConfig {
+    /// Assigned by the server when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authentication: Option<PushNotificationAuthenticationInfo>,
+    /// Key for verifying `X-Hive-Signature`. Issued by the server on
+    /// registration; any value the client sends is replaced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_secret: Option<String>,
+}
+
+/// Credentials the server presents to the webhook.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushNotificationAuthenticationInfo {
+    pub schemes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<String>,
+}
+
+/// A [`PushNotificationConfig`] bound to a task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskPushNotificationConfig {
+    pub task_id: String,
+    pub push_notification_config: PushNotificationConfig,
+}
+
+/// Retry schedule for failed deliveries.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts per delivery, including the first.
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled after each failure.
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between attempts.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PushNotifier
+// ---------------------------------------------------------------------------
+
+/// Outcome of a single delivery attempt that did not succeed.
+enum DeliveryError {
+    Retryable(String),
+    Permanent(String),
+}
+
+/// Registry of push notification configs and webhook sender.
+pub struct PushNotifier {
+    client: reqwest::Client,
+    retry: RetryPolicy,
+    allow_private_targets: bool,
+    configs: Mutex<HashMap<String, Vec<PushNotificationConfig>>>,
+    watched: Mutex<HashSet<String>>,
+}
+
+impl Default for PushNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PushNotifier {
+    /// Create a notifier with the default retry policy.
+    pub fn new() -> Self {
+        Self {
+            client: webhook_client(true),
+            retry: RetryPolicy::default(),
+            allow_private_targets: false,
+            configs: Mutex::new(HashMap::new()),
+            watched: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Accept webhooks on loopback and private networks, over plain HTTP.
+    ///
+    /// Only for tests and single-user setups: with this set, anyone who can
+    /// register a config can make the server send requests into its own
+    /// network.
+    pub fn allow_private_targets(mut self) -> Self {
+        self.client = webhook_client(false);
+        self.allow_private_targets = true;
+        self
+    }
+
+    /// Override the retry policy.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Register `config` for `task_id`, replacing any config with the same
+    /// ID. Returns the stored config with its ID and a fresh signing secret
+    /// filled in.
+    pub async fn set(
+        &self,
+        task_id: &str,
+        mut config: PushNotificationConfig,
+    ) -> Result<PushNotificationConfig, A2aError> {
+        self.check_target(&config.url).await?;
+        let config_id = config
+            .id
+            .get_or_insert_with(|| Uuid::new_v4().to_string())
+            .clone();
+        config.signing_secret = Some(hex::encode(rand::random::<[u8; 32]>()));
+
+        let mut configs = self.configs.lock().unwrap_or_else(|e| e.into_inner());
+        let entries = configs.entry(task_id.to_string()).or_default();
+        entries.retain(|existing| existing.id.as_deref() != Some(config_id.as_str()));
+        entries.push(config.clone());
+        Ok(config)
+    }
+
+    /// Refuse webhook URLs that are not public HTTPS endpoints.
+    pub async fn check_target(&self, url: &str) -> Result<(), A2aError> {
+        if self.allow_private_targets {
+            url::Url::parse(url).map_err(|e| A2aError::Security(format!("Invalid URL: {e}")))?;
+            return Ok(());
+        }
+        resolve_outbound_url(url).await.map(|_| ())
+    }
+
+    /// Look up a config for `task_id`: the one with `config_id`, or the
+    /// first registered when no ID is given.
+    pub fn get(&self, task_id: &str, config_id: Option<&str>) -> Option<PushNotificationConfig> {
+        let configs = self.configs.lock().unwrap_or_else(|e| e.into_inner());
+        let entries = configs.get(task_id)?;
+        match config_id {
+            Some(id) => entries
+                .iter()
+                .find(|config| config.id.as_deref() == Some(id))
+                .cloned(),
+            None => entries.first().cloned(),
+        }
+    }
+
+    /// All configs registered for `task_id`.
+    pub fn list(&self, task_id: &str) -> Vec<PushNotificationConfig> {
+        let configs = self.configs.lock().unwrap_or_else(|e| e.into_inner());
+        configs.get(task_id).cloned().unwrap_or_default()
+    }
+
+    /// Remove one config. Returns `false` if it was not registered.
+    pub fn delete(&self, task_id: &str, config_id: &str) -> bool {
+        let mut configs = self.configs.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(entries) = configs.get_mut(task_id) else {
+            return false;
+        };
+        let before = entries.len();
+        entries.retain(|config| config.id.as_deref() != Some(config_id));
+        let removed = entries.len() != before;
+        if entries.is_empty() {
+            configs.remove(task_id);
+        }
+        removed
+    }
+
+    /// Forget every config for `task_id` (used when the task is purged).
+    pub fn remove_task(&self, task_id: &str) {
+        self.configs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(task_id);
+    }
+
+    /// Mark `task_id` as having a delivery loop. Returns `false` if one is
+    /// already running.
+    pub fn begin_watch(&self, task_id: &str) -> bool {
+        self.watched
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(task_id.to_string())
+    }
+
+    /// Clear the mark set by [`Self::begin_watch`].
+    pub fn end_watch(&self, task_id: &str) {
+        self.watched
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(task_id);
+    }
+
+    /// POST `task` to every webhook registered for it, retrying failures.
+    ///
+    /// Returns the number of webhooks that accepted the notification.
+    pub async fn deliver(&self, task: &Task) -> usize {
+        self.deliver_to(task, &self.list(&task.id)).await
+    }
+
+    /// POST `task` to the given webhooks, retrying failures.
+    pub async fn deliver_to(&self, task: &Task, configs: &[PushNotificationConfig]) -> usize {
+        if configs.is_empty() {
+            return 0;
+        }
+        let body = match serde_json::to_string(task) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::warn!("Failed to serialize task {} for push: {e}", task.id);
+                return 0;
+            }
+        };
+
+        let results = futures::future::join_all(
+            configs
+                .iter()
+                .map(|config| self.deliver_with_retry(config, &body)),
+        )
+        .await;
+
+        let mut delivered = 0;
+        for (config, result) in configs.iter().zip(results) {
+            match result {
+                Ok(()) => delivered += 1,
+                Err(e) => tracing::warn!(
+                    "Push notification for task {} to {} failed: {e}",
+                    task.id,
+                    config.url
+                ),
+            }
+        }
+        delivered
+    }
+
+    async fn deliver_with_retry(
+        &self,
+        config: &PushNotificationConfig,
+        body: &str,
+    ) -> Result<(), A2aError> {
+        let mut backoff = self.retry.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            match self.send(config, body).await {
+                Ok(()) => return Ok(()),
+                Err(DeliveryError::Permanent(message)) => return Err(A2aError::Network(message)),
+                Err(DeliveryError::Retryable(message)) if attempt >= self.retry.max_attempts => {
+                    return Err(A2aError::Network(format!(
+                        "{message} (after {attempt} attempts)"
+                    )));
+                }
+                Err(DeliveryError::Retryable(_)) => {
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(self.retry.max_backoff);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    async fn send(&self, config: &PushNotificationConfig, body: &str) -> Result<(), DeliveryError> {
+        // Literal IPs never reach the resolver, so check them here too.
+        if !self.allow_private_targets {
+            validate_outbound_url(&config.url)
+                .map_err(|e| DeliveryError::Permanent(e.to_string()))?;
+        }
+
+        let mut request = self
+            .client
+            .post(&config.url)
+            .header("content-type", "application/json")
+            .body(body.to_string());
+
+        if let Some(ref secret) = config.signing_secret {
+            let timestamp = chrono::Utc::now().timestamp();
+            request = request
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .header(SIGNATURE_HEADER, sign_payload(secret, timestamp, body));
+        }
+
+        if let Some(credentials) = config.authentication.as_ref().and_then(|auth| {
+            auth.schemes
+                .iter()
+                .any(|scheme| scheme.eq_ignore_ascii_case("bearer"))
+                .then_some(auth.credentials.as_deref())
+                .flatten()
+        }) {
+            request = request.bearer_auth(credentials);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| DeliveryError::Retryable(format!("request failed: {e}")))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            Err(DeliveryError::Retryable(format!(
+                "webhook returned {status}"
+            )))
+        } else {
+            Err(DeliveryError::Permanent(format!(
+                "webhook returned {status}"
+            )))
+        }
+    }
+}
+
+/// HTTP client for webhook deliveries. Redirects are never followed, so a
+/// public webhook cannot bounce the request to an internal address.
+fn webhook_client(public_only: bool) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .redirect(reqwest::redirect::Policy::none());
+    if public_only {
+        builder = builder.dns_resolver(Arc::new(PublicOnlyResolver));
+    }
+    builder.build().unwrap_or_default()
+}
+
+/// Sign a push payload: `sha256=<hex HMAC-SHA256(secret, "{timestamp}.{body}")>`.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use a2a_rs::{TaskState, TaskStatus};
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+
+    fn config(url: &str) -> PushNotificationConfig {
+        PushNotificationConfig {
+            id: None,
+            url: url.into(),
+            token: Some("client-token".into()),
+            authentication: None,
+            signing_secret: None,
+        }
+    }
+
+    fn task(id: &str) -> Task {
+        Task {
+            id: id.into(),
+            context_id: "ctx".into(),
+            status: TaskStatus {
+                state: TaskState::Completed,
+                message: None,
+                timestamp: None,
+            },
+            artifacts: None,
+            history: None,
+            metadata: None,
+            kind: "task".into(),
+        }
+    }
+
+    fn fast_retry() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+        }
+    }
+
+    fn local_notifier() -> PushNotifier {
+        PushNotifier::new()
+            .allow_private_targets()
+            .with_retry_policy(fast_retry())
+    }
+
+    /// Spawn a webhook that answers with `statuses` in turn (repeating the
+    /// last) and checks every request's signature against the secret
+    /// published in `secret`.
+    async fn spawn_webhook(
+        statuses: Vec<StatusCode>,
+        secret: Arc<Mutex<String>>,
+    ) -> (String, Arc<AtomicUsize>) {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: String| {
+                let counter = counter.clone();
+                let statuses = statuses.clone();
+                let secret = secret.lock().unwrap().clone();
+                async move {
+                    let timestamp: i64 =
+                        headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+                    assert_eq!(
+                        headers[SIGNATURE_HEADER].to_str().unwrap(),
+                        sign_payload(&secret, timestamp, &body)
+                    );
+                    assert!(headers.get("x-a2a-notification-token").is_none());
+                    assert!(!headers
+                        .values()
+                        .any(|value| value.to_str().unwrap_or_default().contains(&secret)));
+                    let hit = counter.fetch_add(1, Ordering::SeqCst);
+                    statuses[hit.min(statuses.len() - 1)]
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (format!("http://{addr}/hook"), hits)
+    }
+
+    /// Register a config for a webhook spawned with `statuses`.
+    async fn register_webhook(
+        notifier: &PushNotifier,
+        statuses: Vec<StatusCode>,
+    ) -> Arc<AtomicUsize> {
+        let secret = Arc::new(Mutex::new(String::new()));
+        let (url, hits) = spawn_webhook(statuses, secret.clone()).await;
+        let stored = notifier.set("t1", config(&url)).await.unwrap();
+        *secret.lock().unwrap() = stored.signing_secret.unwrap();
+        hits
+    }
+
+    #[tokio::test]
+    async fn test_set_assigns_id_and_replaces_same_id() {
+        let notifier = local_notifier();
+        let stored = notifier
+            .set("t1", config("http://127.0.0.1:9/hook"))
+            .await
+            .unwrap();
+        let id = stored.id.clone().unwrap();
+
+        let mut updated = config("http://127.0.0.1:9/other");
+        updated.id = Some(id.clone());
+        notifier.set("t1", updated).await.unwrap();
+
+        let configs = notifier.list("t1");
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].url, "http://127.0.0.1:9/other");
+        assert_eq!(notifier.get("t1", Some(&id)).unwrap().url, configs[0].url);
+        assert!(notifier.get("t1", Some("missing")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_set_rejects_unsafe_urls() {
+        let notifier = PushNotifier::new();
+        for url in [
+            "http://example.com/hook",
+            "https://10.0.0.1/hook",
+            "https://localhost/hook",
+            "https://127.0.0.1:8443/hook",
+            "https://[::1]/hook",
+        ] {
+            assert!(notifier.set("t1", config(url)).await.is_err(), "{url}");
+        }
+        assert!(notifier.list("t1").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_set_issues_a_fresh_signing_secret() {
+        let notifier = local_notifier();
+        let mut forged = config("http://127.0.0.1:9/hook");
+        forged.signing_secret = Some("chosen-by-client".into());
+
+        let first = notifier.set("t1", forged).await.unwrap();
+        let secret = first.signing_secret.clone().unwrap();
+        assert_ne!(secret, "chosen-by-client");
+        assert_eq!(secret.len(), 64);
+
+        let second = notifier.set("t1", first).await.unwrap();
+        assert_ne!(second.signing_secret.unwrap(), secret);
+    }
+
+    #[tokio::test]
+    async fn test_delete_and_remove_task() {
+        let notifier = local_notifier();
+        let id = notifier
+            .set("t1", config("http://127.0.0.1:9/hook"))
+            .await
+            .unwrap()
+            .id
+            .unwrap();
+        notifier
+            .set("t1", config("http://127.0.0.1:9/second"))
+            .await
+            .unwrap();
+
+        assert!(notifier.delete("t1", &id));
+        assert!(!notifier.delete("t1", &id));
+        assert_eq!(notifier.list("t1").len(), 1);
+
+        notifier.remove_task("t1");
+        assert!(notifier.list("t1").is_empty());
+    }
+
+    #[test]
+    fn test_sign_payload_depends_on_all_inputs() {
+        let signature = sign_payload("token", 100, "{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(signature, sign_payload("token", 100, "{}"));
+        assert_ne!(signature, sign_payload("other", 100, "{}"));
+        assert_ne!(signature, sign_payload("token", 101, "{}"));
+        assert_ne!(signature, sign_payload("token", 100, "[]"));
+    }
+
+    #[tokio::test]
+    async fn test_deliver_retries_server_errors() {
+        let notifier = local_notifier();
+        let hits = register_webhook(
+            &notifier,
+            vec![
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::OK,
+            ],
+        )
+        .await;
+
+        assert_eq!(notifier.deliver(&task("t1")).await, 1);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_gives_up_after_max_attempts() {
+        let notifier = local_notifier();
+        let hits = register_webhook(&notifier, vec![StatusCode::BAD_GATEWAY]).await;
+
+        assert_eq!(notifier.deliver(&task("t1")).await, 0);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_does_not_retry_client_errors() {
+        let notifier = local_notifier();
+        let hits = register_webhook(&notifier, vec![StatusCode::GONE]).await;
+
+        assert_eq!(notifier.deliver(&task("t1")).await, 0);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_redirects_are_not_followed() {
+        let followed = Arc::new(AtomicUsize::new(0));
+        let counter = followed.clone();
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(|| async {
+                    (
+                        StatusCode::TEMPORARY_REDIRECT,
+                        [(axum::http::header::LOCATION, "/internal")],
+                    )
+                }),
+            )
+            .route(
+                "/internal",
+                post(move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    async { StatusCode::OK }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let notifier = local_notifier();
+        notifier
+            .set("t1", config(&format!("http://{addr}/hook")))
+            .await
+            .unwrap();
+        assert_eq!(notifier.deliver(&task("t1")).await, 0);
+        assert_eq!(followed.load(Ordering::SeqCst), 0);
+    }
+}
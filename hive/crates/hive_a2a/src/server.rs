@@ -2,11 +2,20 @@
 //!
 //! Provides:
 //! - `GET /.well-known/agent-card.json` — Agent Card discovery
-//! - `POST /a2a` — JSON-RPC message handler (`message/send`)
+//! - `POST /a2a` — JSON-RPC handler for the A2A methods:
+//!   - `message/send` — run a task (blocking unless `blocking: false`)
+//!   - `message/stream` — run a task, streaming updates over SSE
+//!   - `tasks/get`, `tasks/list`, `tasks/cancel`, `tasks/resubscribe`
+//!   - `tasks/pushNotificationConfig/{set,get,list,delete}`
 //! - `GET /a2a/tasks/{task_id}` — Task status lookup
 //! - `GET /a2a/tasks/{task_id}/events` — SSE task status updates
 //!
-//! The server validates `X-Hive-Key` when an API key is configured.
+//! The server validates `X-Hive-Key` when an API key is configured. Tasks
+//! are recorded against a digest of the caller's key, which is what
+//! `tasks/list` filters on.
+//!
+//! [`start_server_with_handler`] resumes tasks that were still running when
+//! the server last stopped and purges expired tasks once an hour.
 
 use std::collections::HashMap;
 use std::convert::Infallible;
@@ -14,20 +23,23 @@ use std::future::Future;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use a2a_rs::{Message, MessageSendParams, Task, TaskState, TaskStatusUpdateEvent};
+use a2a_rs::{Message, MessageSendParams, Task, TaskStatusUpdateEvent};
 use axum::extract::{Path, State};
 use axum::http::HeaderName;
 use axum::http::{HeaderMap, Method, StatusCode};
 use axum::response::sse::{Event, KeepAlive, Sse};
-use axum::response::{IntoResponse, Json};
+use axum::response::{IntoResponse, Json, Response};
 use axum::routing::{get, post};
 use axum::Router;
 use futures::stream::{self, BoxStream};
 use futures::StreamExt;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use tokio::net::TcpListener;
-use tokio::sync::{broadcast, Mutex, Semaphore};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, Mutex, OwnedSemaphorePermit, Semaphore};
 use tower_http::cors::{Any, CorsLayer};
 
 use hive_agents::hivemind::AiExecutor;
@@ -36,7 +48,12 @@ use crate::agent_card::build_hive_agent_card;
 use crate::auth::validate_api_key_optional;
 use crate::config::A2aConfig;
 use crate::error::A2aError;
-use crate::task_handler::HiveTaskHandler;
+use crate::push::{PushNotificationConfig, PushNotifier, TaskPushNotificationConfig};
+use crate::store::{trim_history, RetentionPolicy, TaskListQuery, TaskPage, ANONYMOUS_CLIENT};
+use crate::task_handler::{task_is_final, AcceptedTask, HiveTaskHandler};
+
+/// How often finished tasks are checked against the retention policy.
+const RETENTION_SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 // ---------------------------------------------------------------------------
 // Shared state
@@ -52,13 +69,30 @@ struct RateLimitState {
 /// without requiring its async futures to be `Send`.
 pub trait TaskHandlerAdapter: Send + Sync {
     fn handle_message_blocking(&self, task_id: String, message: Message) -> Result<Task, A2aError>;
+    /// Store a task for client `owner` in the `Working` state without
+    /// running it.
+    fn accept_message_blocking(
+        &self,
+        owner: String,
+        task_id: String,
+        message: Message,
+    ) -> Result<AcceptedTask, A2aError>;
+    /// Run a task previously returned by `accept_message_blocking`.
+    fn run_accepted_blocking(&self, accepted: AcceptedTask) -> Result<Task, A2aError>;
     fn get_task_blocking(&self, task_id: &str) -> Result<Task, A2aError>;
+    fn list_tasks_blocking(&self, owner: &str, query: &TaskListQuery)
+        -> Result<TaskPage, A2aError>;
+    fn cancel_task_blocking(&self, task_id: &str) -> Result<Task, A2aError>;
     fn subscribe_blocking(
         &self,
         task_id: &str,
     ) -> Result<broadcast::Receiver<TaskStatusUpdateEvent>, A2aError>;
-    /// Remove a task from the active tasks map (used for deferred purge).
+    /// Evict a task from memory (used for deferred purge).
     fn remove_task_blocking(&self, task_id: &str);
+    /// Re-queue tasks interrupted by the last shutdown.
+    fn recover_tasks_blocking(&self) -> Result<Vec<AcceptedTask>, A2aError>;
+    /// Delete stored tasks that `policy` no longer keeps.
+    fn purge_tasks_blocking(&self, policy: &RetentionPolicy) -> Result<usize, A2aError>;
 }
 
 impl<E: AiExecutor + 'static> TaskHandlerAdapter for HiveTaskHandler<E> {
@@ -66,10 +100,35 @@ impl<E: AiExecutor + 'static> TaskHandlerAdapter for HiveTaskHandler<E> {
         self.block_on(self.handle_message(&task_id, &message))
     }
 
+    fn accept_message_blocking(
+        &self,
+        owner: String,
+        task_id: String,
+        message: Message,
+    ) -> Result<AcceptedTask, A2aError> {
+        self.block_on(self.accept_message(&owner, &task_id, &message))
+    }
+
+    fn run_accepted_blocking(&self, accepted: AcceptedTask) -> Result<Task, A2aError> {
+        self.block_on(self.run_accepted(accepted))
+    }
+
     fn get_task_blocking(&self, task_id: &str) -> Result<Task, A2aError> {
         self.block_on(self.get_task(task_id))
     }
 
+    fn list_tasks_blocking(
+        &self,
+        owner: &str,
+        query: &TaskListQuery,
+    ) -> Result<TaskPage, A2aError> {
+        self.list_tasks(owner, query)
+    }
+
+    fn cancel_task_blocking(&self, task_id: &str) -> Result<Task, A2aError> {
+        self.block_on(self.cancel_task(task_id))
+    }
+
     fn subscribe_blocking(
         &self,
         task_id: &str,
@@ -84,6 +143,14 @@ impl<E: AiExecutor + 'static> TaskHandlerAdapter for HiveTaskHandler<E> {
         })
         .ok();
     }
+
+    fn recover_tasks_blocking(&self) -> Result<Vec<AcceptedTask>, A2aError> {
+        self.block_on(self.recover_tasks())
+    }
+
+    fn purge_tasks_blocking(&self, policy: &RetentionPolicy) -> Result<usize, A2aError> {
+        self.purge_tasks(policy)
+    }
 }
 
 impl<E: AiExecutor + 'static> HiveTaskHandler<E> {
@@ -111,6 +178,7 @@ impl<E: AiExecutor + 'static> HiveTaskHandler<E> {
 pub struct AppState {
     pub config: A2aConfig,
     pub task_handler: Option<Arc<dyn TaskHandlerAdapter>>,
+    pub push: Arc<PushNotifier>,
     rate_limits: Arc<Mutex<HashMap<String, RateLimitState>>>,
     concurrent_tasks: Arc<Semaphore>,
 }
@@ -152,6 +220,32 @@ pub struct JsonRpcError {
     pub message: String,
 }
 
+/// Params for `tasks/cancel`, `tasks/resubscribe` and
+/// `tasks/pushNotificationConfig/list`.
+#[derive(Debug, Deserialize)]
+pub struct TaskIdParams {
+    pub id: String,
+}
+
+/// Params for `tasks/get`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskQueryParams {
+    pub id: String,
+    /// Only return the most recent `history_length` history messages.
+    #[serde(default)]
+    pub history_length: Option<usize>,
+}
+
+/// Params for `tasks/pushNotificationConfig/get` and `.../delete`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushNotificationConfigParams {
+    pub id: String,
+    #[serde(default)]
+    pub push_notification_config_id: Option<String>,
+}
+
 // ---------------------------------------------------------------------------
 // Router
 // ---------------------------------------------------------------------------
@@ -166,16 +260,37 @@ pub fn build_router_with_handler(
     config: A2aConfig,
     task_handler: Option<Arc<dyn TaskHandlerAdapter>>,
 ) -> Router {
-    let state = AppState {
+    build_router_with_push(config, task_handler, Arc::new(PushNotifier::new()))
+}
+
+/// Build the Axum router with a live task handler and a custom push
+/// notifier (e.g. one that accepts local webhooks in tests).
+pub fn build_router_with_push(
+    config: A2aConfig,
+    task_handler: Option<Arc<dyn TaskHandlerAdapter>>,
+    push: Arc<PushNotifier>,
+) -> Router {
+    router(app_state(config, task_handler, push))
+}
+
+fn app_state(
+    config: A2aConfig,
+    task_handler: Option<Arc<dyn TaskHandlerAdapter>>,
+    push: Arc<PushNotifier>,
+) -> AppState {
+    AppState {
         concurrent_tasks: Arc::new(Semaphore::new(config.server.max_concurrent_tasks.max(1))),
         rate_limits: Arc::new(Mutex::new(HashMap::new())),
         task_handler,
+        push,
         config,
-    };
+    }
+}
 
+fn router(state: AppState) -> Router {
     Router::new()
         .route("/.well-known/agent-card.json", get(agent_card_handler))
-        .route("/a2a", post(send_message_handler))
+        .route("/a2a", post(rpc_handler))
         .route("/a2a/tasks/:task_id", get(get_task_handler))
         .route("/a2a/tasks/:task_id/events", get(task_events_handler))
         .layer(
@@ -201,13 +316,14 @@ async fn agent_card_handler(State(state): State<AppState>) -> impl IntoResponse
 }
 
 /// `POST /a2a`
-async fn send_message_handler(
+async fn rpc_handler(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(rpc_request): Json<JsonRpcRequest>,
-) -> impl IntoResponse {
+) -> Response {
     if let Err(message) = validate_request_auth(&state, &headers) {
-        return rpc_error(StatusCode::UNAUTHORIZED, rpc_request.id, -32001, message);
+        return rpc_error(StatusCode::UNAUTHORIZED, rpc_request.id, -32001, message)
+            .into_response();
     }
 
     if rpc_request.jsonrpc != "2.0" {
@@ -216,7 +332,8 @@ async fn send_message_handler(
             rpc_request.id,
             -32600,
             "Invalid JSON-RPC version",
-        );
+        )
+        .into_response();
     }
 
     if let Err(message) = enforce_rate_limit(&state, &headers).await {
@@ -225,108 +342,291 @@ async fn send_message_handler(
             rpc_request.id,
             -32029,
             message,
-        );
+        )
+        .into_response();
     }
 
-    match rpc_request.method.as_str() {
-        "message/send" => {
-            let params: MessageSendParams = match serde_json::from_value(rpc_request.params) {
-                Ok(params) => params,
-                Err(e) => {
-                    return rpc_error(
-                        StatusCode::BAD_REQUEST,
-                        rpc_request.id,
-                        -32602,
-                        format!("Invalid message/send params: {e}"),
-                    );
-                }
-            };
-
-            let Some(task_handler) = state.task_handler.clone() else {
-                return rpc_error(
-                    StatusCode::SERVICE_UNAVAILABLE,
-                    rpc_request.id,
-                    -32000,
-                    "Task execution is not configured for this server",
-                );
-            };
-
-            let permit = match state.concurrent_tasks.clone().try_acquire_owned() {
-                Ok(permit) => permit,
-                Err(_) => {
-                    return rpc_error(
-                        StatusCode::TOO_MANY_REQUESTS,
-                        rpc_request.id,
-                        -32002,
-                        "Max concurrent tasks reached",
-                    );
-                }
-            };
-
-            let task_id = params
-                .message
-                .task_id
-                .clone()
-                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-            let message = params.message;
-
-            let purge_handler = task_handler.clone();
-            let purge_task_id = task_id.clone();
-            let result = tokio::task::spawn_blocking(move || {
-                let _permit = permit;
-                task_handler.handle_message_blocking(task_id, message)
-            })
-            .await;
-
-            match result {
-                Ok(Ok(ref task)) if task_is_final(&task.status.state) => {
-                    // Schedule removal of completed/failed task after 5 minutes
-                    // to prevent unbounded memory growth in active_tasks.
-                    tokio::spawn(async move {
-                        tokio::time::sleep(Duration::from_secs(300)).await;
-                        tokio::task::spawn_blocking(move || {
-                            purge_handler.remove_task_blocking(&purge_task_id);
-                        })
-                        .await
-                        .ok();
-                    });
-                    rpc_success(
-                        rpc_request.id,
-                        serde_json::to_value(task).unwrap_or_default(),
-                    )
-                }
-                Ok(Ok(task)) => rpc_success(
-                    rpc_request.id,
-                    serde_json::to_value(task).unwrap_or_default(),
-                ),
-                Ok(Err(error)) => {
-                    let (status, code) = match error {
-                        A2aError::TaskNotFound(_) => (StatusCode::NOT_FOUND, -32004),
-                        A2aError::UnsupportedSkill(_) | A2aError::Bridge(_) => {
-                            (StatusCode::BAD_REQUEST, -32602)
-                        }
-                        _ => (StatusCode::INTERNAL_SERVER_ERROR, -32010),
-                    };
-                    rpc_error(status, rpc_request.id, code, error.to_string())
-                }
-                Err(e) => rpc_error(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    rpc_request.id,
-                    -32011,
-                    format!("Task execution thread failed: {e}"),
-                ),
-            }
-        }
+    let JsonRpcRequest {
+        id, method, params, ..
+    } = rpc_request;
+    let client = client_identity(&headers);
+    let result = match method.as_str() {
+        "message/send" => message_send(&state, &id, client, params).await,
+        "message/stream" => message_stream(&state, &id, client, params).await,
+        "tasks/get" => tasks_get(&state, &id, params).await,
+        "tasks/list" => tasks_list(&state, &id, client, params).await,
+        "tasks/cancel" => tasks_cancel(&state, &id, params).await,
+        "tasks/resubscribe" => tasks_resubscribe(&state, &id, params).await,
+        "tasks/pushNotificationConfig/set" => push_config_set(&state, &id, params).await,
+        "tasks/pushNotificationConfig/get" => push_config_get(&state, &id, params),
+        "tasks/pushNotificationConfig/list" => push_config_list(&state, &id, params),
+        "tasks/pushNotificationConfig/delete" => push_config_delete(&state, &id, params),
         _ => {
-            let method_preview: String = rpc_request.method.chars().take(64).collect();
-            rpc_error(
+            let method_preview: String = method.chars().take(64).collect();
+            Err(rpc_error(
                 StatusCode::OK,
-                rpc_request.id,
+                id.clone(),
                 -32601,
                 format!("Method not found: {}", method_preview),
             )
+            .into_response()
+            .into())
         }
+    };
+    result.unwrap_or_else(|response| *response)
+}
+
+/// `message/send` — run a task and return it.
+///
+/// Blocks until the task finishes unless the request sets
+/// `configuration.blocking` to `false`, in which case the `Working` task is
+/// returned immediately and the result is delivered through
+/// `tasks/get`, `tasks/resubscribe` or push notifications.
+async fn message_send(
+    state: &AppState,
+    id: &Value,
+    client: String,
+    params: Value,
+) -> RpcResult<Response> {
+    let params: MessageSendParams = parse_params(id, "message/send", params)?;
+    let task_handler = require_task_handler(state, id)?;
+    let permit = acquire_task_permit(state, id)?;
+
+    let blocking = params
+        .configuration
+        .as_ref()
+        .and_then(|configuration| configuration.blocking)
+        .unwrap_or(true);
+    let accepted = accept_message(state, id, &task_handler, client, params).await?;
+
+    if !blocking {
+        let task = accepted.task.clone();
+        spawn_accepted(state, task_handler, accepted, permit);
+        return Ok(rpc_success(id.clone(), to_value(&task)).into_response());
+    }
+
+    let task_id = accepted.task.id.clone();
+    let run_handler = task_handler.clone();
+    let task = call_handler(id, move || {
+        let _permit = permit;
+        run_handler.run_accepted_blocking(accepted)
+    })
+    .await?;
+
+    if task_is_final(&task.status.state) {
+        schedule_purge(state, task_handler, task_id);
+    }
+    Ok(rpc_success(id.clone(), to_value(&task)).into_response())
+}
+
+/// `message/stream` — run a task, streaming the `Working` task, its status
+/// updates and the final task as SSE events.
+async fn message_stream(
+    state: &AppState,
+    id: &Value,
+    client: String,
+    params: Value,
+) -> RpcResult<Response> {
+    let params: MessageSendParams = parse_params(id, "message/stream", params)?;
+    let task_handler = require_task_handler(state, id)?;
+    let permit = acquire_task_permit(state, id)?;
+
+    let accepted = accept_message(state, id, &task_handler, client, params).await?;
+    let task = accepted.task.clone();
+    let subscribe_handler = task_handler.clone();
+    let subscribe_task_id = task.id.clone();
+    let receiver = call_handler(id, move || {
+        subscribe_handler.subscribe_blocking(&subscribe_task_id)
+    })
+    .await?;
+    spawn_accepted(state, task_handler.clone(), accepted, permit);
+
+    Ok(sse_response(
+        vec![rpc_event(id, &task)],
+        rpc_update_stream(id.clone(), task_handler, receiver),
+    ))
+}
+
+/// `tasks/get` — look up a task, optionally trimming its history.
+async fn tasks_get(state: &AppState, id: &Value, params: Value) -> RpcResult<Response> {
+    let TaskQueryParams {
+        id: task_id,
+        history_length,
+    } = parse_params(id, "tasks/get", params)?;
+    let task_handler = require_task_handler(state, id)?;
+
+    let mut task = call_handler(id, move || task_handler.get_task_blocking(&task_id)).await?;
+    trim_history(&mut task, history_length);
+    Ok(rpc_success(id.clone(), to_value(&task)).into_response())
+}
+
+/// `tasks/list` — page through the calling client's tasks, most recently
+/// updated first.
+async fn tasks_list(
+    state: &AppState,
+    id: &Value,
+    client: String,
+    params: Value,
+) -> RpcResult<Response> {
+    // All params are optional, so the params object may be left out.
+    let params = if params.is_null() {
+        Value::Object(Default::default())
+    } else {
+        params
+    };
+    let query: TaskListQuery = parse_params(id, "tasks/list", params)?;
+    let task_handler = require_task_handler(state, id)?;
+
+    let page = call_handler(id, move || {
+        task_handler.list_tasks_blocking(&client, &query)
+    })
+    .await?;
+    Ok(rpc_success(id.clone(), to_value(&page)).into_response())
+}
+
+/// `tasks/cancel` — stop a running task's orchestration.
+async fn tasks_cancel(state: &AppState, id: &Value, params: Value) -> RpcResult<Response> {
+    let TaskIdParams { id: task_id } = parse_params(id, "tasks/cancel", params)?;
+    let task_handler = require_task_handler(state, id)?;
+
+    let task = call_handler(id, move || task_handler.cancel_task_blocking(&task_id)).await?;
+    Ok(rpc_success(id.clone(), to_value(&task)).into_response())
+}
+
+/// `tasks/resubscribe` — reattach to a task's update stream.
+///
+/// Streams the task as it is now, then its updates until the final one. A
+/// task that already finished gets its final status update straight away.
+async fn tasks_resubscribe(state: &AppState, id: &Value, params: Value) -> RpcResult<Response> {
+    let TaskIdParams { id: task_id } = parse_params(id, "tasks/resubscribe", params)?;
+    let task_handler = require_task_handler(state, id)?;
+
+    // Subscribe before reading the task so no update slips in between.
+    let subscribe_handler = task_handler.clone();
+    let subscribe_task_id = task_id.clone();
+    let receiver = call_handler(id, move || {
+        subscribe_handler.subscribe_blocking(&subscribe_task_id)
+    })
+    .await?;
+    let lookup_handler = task_handler.clone();
+    let task = call_handler(id, move || lookup_handler.get_task_blocking(&task_id)).await?;
+
+    if task_is_final(&task.status.state) {
+        return Ok(sse_response(
+            vec![
+                rpc_event(id, &task),
+                rpc_event(id, &status_update_from_task(&task)),
+            ],
+            stream::empty().boxed(),
+        ));
     }
+    Ok(sse_response(
+        vec![rpc_event(id, &task)],
+        rpc_update_stream(id.clone(), task_handler, receiver),
+    ))
+}
+
+/// `tasks/pushNotificationConfig/set` — register a webhook for a task.
+async fn push_config_set(state: &AppState, id: &Value, params: Value) -> RpcResult<Response> {
+    let TaskPushNotificationConfig {
+        task_id,
+        push_notification_config,
+    } = parse_params(id, "tasks/pushNotificationConfig/set", params)?;
+    let task_handler = require_task_handler(state, id)?;
+
+    let lookup_handler = task_handler.clone();
+    let lookup_task_id = task_id.clone();
+    let task = call_handler(id, move || {
+        lookup_handler.get_task_blocking(&lookup_task_id)
+    })
+    .await?;
+
+    let stored = if task_is_final(&task.status.state) {
+        // The task will not change again, so deliver its final state once.
+        let stored = state
+            .push
+            .set(&task_id, push_notification_config)
+            .await
+            .map_err(|e| task_error_response(id, e))?;
+        let push = state.push.clone();
+        let config = stored.clone();
+        tokio::spawn(async move {
+            push.deliver_to(&task, &[config]).await;
+        });
+        stored
+    } else {
+        register_push(state, id, &task_handler, &task_id, push_notification_config).await?
+    };
+
+    Ok(rpc_success(
+        id.clone(),
+        to_value(&TaskPushNotificationConfig {
+            task_id,
+            push_notification_config: stored,
+        }),
+    )
+    .into_response())
+}
+
+/// `tasks/pushNotificationConfig/get`
+fn push_config_get(state: &AppState, id: &Value, params: Value) -> RpcResult<Response> {
+    let PushNotificationConfigParams {
+        id: task_id,
+        push_notification_config_id,
+    } = parse_params(id, "tasks/pushNotificationConfig/get", params)?;
+
+    let config = state
+        .push
+        .get(&task_id, push_notification_config_id.as_deref())
+        .ok_or_else(|| push_config_not_found(id, &task_id))?;
+    Ok(rpc_success(
+        id.clone(),
+        to_value(&TaskPushNotificationConfig {
+            task_id,
+            push_notification_config: config,
+        }),
+    )
+    .into_response())
+}
+
+/// `tasks/pushNotificationConfig/list`
+fn push_config_list(state: &AppState, id: &Value, params: Value) -> RpcResult<Response> {
+    let TaskIdParams { id: task_id } =
+        parse_params(id, "tasks/pushNotificationConfig/list", params)?;
+
+    let configs: Vec<TaskPushNotificationConfig> = state
+        .push
+        .list(&task_id)
+        .into_iter()
+        .map(|config| TaskPushNotificationConfig {
+            task_id: task_id.clone(),
+            push_notification_config: config,
+        })
+        .collect();
+    Ok(rpc_success(id.clone(), to_value(&configs)).into_response())
+}
+
+/// `tasks/pushNotificationConfig/delete`
+fn push_config_delete(state: &AppState, id: &Value, params: Value) -> RpcResult<Response> {
+    let PushNotificationConfigParams {
+        id: task_id,
+        push_notification_config_id,
+    } = parse_params(id, "tasks/pushNotificationConfig/delete", params)?;
+    let Some(config_id) = push_notification_config_id else {
+        return Err(rpc_error(
+            StatusCode::BAD_REQUEST,
+            id.clone(),
+            -32602,
+            "Invalid tasks/pushNotificationConfig/delete params: missing pushNotificationConfigId",
+        )
+        .into_response()
+        .into());
+    };
+
+    if !state.push.delete(&task_id, &config_id) {
+        return Err(push_config_not_found(id, &task_id));
+    }
+    Ok(rpc_success(id.clone(), Value::Null).into_response())
 }
 
 /// `GET /a2a/tasks/{task_id}`
@@ -477,7 +777,12 @@ pub async fn start_server_with_handler(
     }
 
     let addr = config.bind_addr();
-    let router = build_router_with_handler(config, task_handler);
+    let state = app_state(config, task_handler, Arc::new(PushNotifier::new()));
+    if let Some(task_handler) = state.task_handler.clone() {
+        resume_interrupted_tasks(&state, task_handler.clone()).await;
+        spawn_retention_sweep(&state, task_handler);
+    }
+    let router = router(state);
 
     let listener = TcpListener::bind(&addr)
         .await
@@ -490,10 +795,402 @@ pub async fn start_server_with_handler(
     Ok(())
 }
 
+/// Run again every task that was still working when the server stopped.
+///
+/// Each task waits for a concurrency permit, so a large backlog does not
+/// crowd out new requests beyond the configured limit.
+async fn resume_interrupted_tasks(state: &AppState, task_handler: Arc<dyn TaskHandlerAdapter>) {
+    let recover_handler = task_handler.clone();
+    let recovered =
+        match tokio::task::spawn_blocking(move || recover_handler.recover_tasks_blocking()).await {
+            Ok(Ok(recovered)) => recovered,
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to recover interrupted A2A tasks: {e}");
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("A2A task recovery thread failed: {e}");
+                return;
+            }
+        };
+
+    for accepted in recovered {
+        tracing::info!("Resuming interrupted A2A task {}", accepted.task.id);
+        let state = state.clone();
+        let task_handler = task_handler.clone();
+        tokio::spawn(async move {
+            if let Ok(permit) = state.concurrent_tasks.clone().acquire_owned().await {
+                spawn_accepted(&state, task_handler, accepted, permit);
+            }
+        });
+    }
+}
+
+/// Purge finished tasks outside the configured retention policy every
+/// [`RETENTION_SWEEP_INTERVAL`].
+fn spawn_retention_sweep(state: &AppState, task_handler: Arc<dyn TaskHandlerAdapter>) {
+    let policy = state.config.server.task_store.retention_policy();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RETENTION_SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            let purge_handler = task_handler.clone();
+            let purge_policy = policy.clone();
+            match tokio::task::spawn_blocking(move || {
+                purge_handler.purge_tasks_blocking(&purge_policy)
+            })
+            .await
+            {
+                Ok(Ok(0)) => {}
+                Ok(Ok(purged)) => tracing::info!("Purged {purged} expired A2A tasks"),
+                Ok(Err(e)) => tracing::warn!("Failed to purge expired A2A tasks: {e}"),
+                Err(e) => tracing::warn!("A2A task purge thread failed: {e}"),
+            }
+        }
+    });
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
 
+/// Result of a JSON-RPC method; the error is the response to send back,
+/// boxed so the happy path stays small.
+type RpcResult<T> = Result<T, Box<Response>>;
+
+fn parse_params<T: DeserializeOwned>(id: &Value, method: &str, params: Value) -> RpcResult<T> {
+    serde_json::from_value(params).map_err(|e| {
+        rpc_error(
+            StatusCode::BAD_REQUEST,
+            id.clone(),
+            -32602,
+            format!("Invalid {method} params: {e}"),
+        )
+        .into_response()
+        .into()
+    })
+}
+
+fn require_task_handler(state: &AppState, id: &Value) -> RpcResult<Arc<dyn TaskHandlerAdapter>> {
+    state.task_handler.clone().ok_or_else(|| {
+        rpc_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            id.clone(),
+            -32000,
+            "Task execution is not configured for this server",
+        )
+        .into_response()
+        .into()
+    })
+}
+
+fn acquire_task_permit(state: &AppState, id: &Value) -> RpcResult<OwnedSemaphorePermit> {
+    state
+        .concurrent_tasks
+        .clone()
+        .try_acquire_owned()
+        .map_err(|_| {
+            rpc_error(
+                StatusCode::TOO_MANY_REQUESTS,
+                id.clone(),
+                -32002,
+                "Max concurrent tasks reached",
+            )
+            .into_response()
+            .into()
+        })
+}
+
+/// Run a blocking [`TaskHandlerAdapter`] call off the async runtime,
+/// turning failures into JSON-RPC error responses.
+async fn call_handler<T, F>(id: &Value, call: F) -> RpcResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, A2aError> + Send + 'static,
+{
+    match tokio::task::spawn_blocking(call).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(error)) => Err(task_error_response(id, error)),
+        Err(e) => Err(rpc_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            id.clone(),
+            -32011,
+            format!("Task execution thread failed: {e}"),
+        )
+        .into_response()
+        .into()),
+    }
+}
+
+fn task_error_response(id: &Value, error: A2aError) -> Box<Response> {
+    let (status, code) = match error {
+        A2aError::TaskNotFound(_) => (StatusCode::NOT_FOUND, -32004),
+        A2aError::NotCancelable(_) => (StatusCode::CONFLICT, -32002),
+        A2aError::UnsupportedSkill(_) | A2aError::Bridge(_) | A2aError::Security(_) => {
+            (StatusCode::BAD_REQUEST, -32602)
+        }
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, -32010),
+    };
+    Box::new(rpc_error(status, id.clone(), code, error.to_string()).into_response())
+}
+
+fn push_config_not_found(id: &Value, task_id: &str) -> Box<Response> {
+    Box::new(
+        rpc_error(
+            StatusCode::NOT_FOUND,
+            id.clone(),
+            -32004,
+            format!("No push notification config for task {task_id}"),
+        )
+        .into_response(),
+    )
+}
+
+/// Accept the message in `params`, registering any push notification
+/// config sent along with it.
+async fn accept_message(
+    state: &AppState,
+    id: &Value,
+    task_handler: &Arc<dyn TaskHandlerAdapter>,
+    client: String,
+    params: MessageSendParams,
+) -> RpcResult<AcceptedTask> {
+    let push_config = match params
+        .configuration
+        .and_then(|configuration| configuration.push_notification_config)
+    {
+        Some(config) => {
+            let config: PushNotificationConfig = serde_json::to_value(config)
+                .and_then(serde_json::from_value)
+                .map_err(|e| {
+                    rpc_error(
+                        StatusCode::BAD_REQUEST,
+                        id.clone(),
+                        -32602,
+                        format!("Invalid push notification config: {e}"),
+                    )
+                    .into_response()
+                })?;
+            state
+                .push
+                .check_target(&config.url)
+                .await
+                .map_err(|e| task_error_response(id, e))?;
+            Some(config)
+        }
+        None => None,
+    };
+
+    let task_id = params
+        .message
+        .task_id
+        .clone()
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let message = params.message;
+    let accept_handler = task_handler.clone();
+    let accepted = call_handler(id, move || {
+        accept_handler.accept_message_blocking(client, task_id, message)
+    })
+    .await?;
+
+    if let Some(config) = push_config {
+        register_push(state, id, task_handler, &accepted.task.id, config).await?;
+    }
+    Ok(accepted)
+}
+
+/// Register a push config for a running task and make sure a delivery loop
+/// is following its updates.
+async fn register_push(
+    state: &AppState,
+    id: &Value,
+    task_handler: &Arc<dyn TaskHandlerAdapter>,
+    task_id: &str,
+    config: PushNotificationConfig,
+) -> RpcResult<PushNotificationConfig> {
+    let stored = state
+        .push
+        .set(task_id, config)
+        .await
+        .map_err(|e| task_error_response(id, e))?;
+
+    if state.push.begin_watch(task_id) {
+        let subscribe_handler = task_handler.clone();
+        let subscribe_task_id = task_id.to_string();
+        match call_handler(id, move || {
+            subscribe_handler.subscribe_blocking(&subscribe_task_id)
+        })
+        .await
+        {
+            Ok(receiver) => {
+                spawn_push_delivery(state, task_handler.clone(), task_id.to_string(), receiver)
+            }
+            Err(response) => {
+                state.push.end_watch(task_id);
+                return Err(response);
+            }
+        }
+    }
+    Ok(stored)
+}
+
+/// POST the task to its webhooks after every status update, until the
+/// final one.
+fn spawn_push_delivery(
+    state: &AppState,
+    task_handler: Arc<dyn TaskHandlerAdapter>,
+    task_id: String,
+    mut receiver: broadcast::Receiver<TaskStatusUpdateEvent>,
+) {
+    let push = state.push.clone();
+    tokio::spawn(async move {
+        loop {
+            let update = match receiver.recv().await {
+                Ok(update) => update,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            let lookup_handler = task_handler.clone();
+            let lookup_task_id = task_id.clone();
+            if let Ok(Ok(task)) = tokio::task::spawn_blocking(move || {
+                lookup_handler.get_task_blocking(&lookup_task_id)
+            })
+            .await
+            {
+                push.deliver(&task).await;
+            }
+            if update.final_ {
+                break;
+            }
+        }
+        push.end_watch(&task_id);
+    });
+}
+
+/// Run an accepted task in the background, holding its concurrency permit
+/// until it finishes.
+fn spawn_accepted(
+    state: &AppState,
+    task_handler: Arc<dyn TaskHandlerAdapter>,
+    accepted: AcceptedTask,
+    permit: OwnedSemaphorePermit,
+) {
+    let state = state.clone();
+    let task_id = accepted.task.id.clone();
+    tokio::spawn(async move {
+        let run_handler = task_handler.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            run_handler.run_accepted_blocking(accepted)
+        })
+        .await;
+        match result {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => tracing::warn!("A2A task {task_id} failed to run: {e}"),
+            Err(e) => tracing::warn!("A2A task {task_id} execution thread failed: {e}"),
+        }
+        schedule_purge(&state, task_handler, task_id);
+    });
+}
+
+/// Evict a finished task (and drop its push configs) after 5 minutes to
+/// prevent unbounded memory growth in `active_tasks`. The task itself stays
+/// in the task store.
+fn schedule_purge(state: &AppState, task_handler: Arc<dyn TaskHandlerAdapter>, task_id: String) {
+    let push = state.push.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(300)).await;
+        push.remove_task(&task_id);
+        tokio::task::spawn_blocking(move || {
+            task_handler.remove_task_blocking(&task_id);
+        })
+        .await
+        .ok();
+    });
+}
+
+/// An SSE event carrying a JSON-RPC success response for request `id`.
+fn rpc_event(id: &Value, result: &impl Serialize) -> Event {
+    Event::default().data(
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": result,
+        })
+        .to_string(),
+    )
+}
+
+/// A task's status updates as JSON-RPC SSE events, ending after the final
+/// update. The finished task (with its artifacts) is sent just before the
+/// final update.
+fn rpc_update_stream(
+    id: Value,
+    task_handler: Arc<dyn TaskHandlerAdapter>,
+    receiver: broadcast::Receiver<TaskStatusUpdateEvent>,
+) -> BoxStream<'static, Result<Event, Infallible>> {
+    stream::unfold(Some(receiver), move |receiver| {
+        let id = id.clone();
+        let task_handler = task_handler.clone();
+        async move {
+            let mut receiver = receiver?;
+            loop {
+                match tokio::time::timeout(Duration::from_secs(300), receiver.recv()).await {
+                    Ok(Ok(update)) if !update.final_ => {
+                        return Some((vec![rpc_event(&id, &update)], Some(receiver)));
+                    }
+                    Ok(Ok(update)) => {
+                        let task_id = update.task_id.clone();
+                        let mut events = Vec::new();
+                        if let Ok(Ok(task)) = tokio::task::spawn_blocking(move || {
+                            task_handler.get_task_blocking(&task_id)
+                        })
+                        .await
+                        {
+                            events.push(rpc_event(&id, &task));
+                        }
+                        events.push(rpc_event(&id, &update));
+                        return Some((events, None));
+                    }
+                    Ok(Err(RecvError::Lagged(_))) => continue,
+                    Ok(Err(RecvError::Closed)) | Err(_) => return None,
+                }
+            }
+        }
+    })
+    .flat_map(|events| stream::iter(events.into_iter().map(Ok::<Event, Infallible>)))
+    .boxed()
+}
+
+/// An SSE response sending `initial` and then everything from `rest`.
+fn sse_response(
+    initial: Vec<Event>,
+    rest: BoxStream<'static, Result<Event, Infallible>>,
+) -> Response {
+    let stream = stream::iter(initial.into_iter().map(Ok::<Event, Infallible>))
+        .chain(rest)
+        .boxed();
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+fn to_value(value: &impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+/// The identity a caller's tasks are recorded under: a digest of its
+/// `X-Hive-Key`, so keys are never written to the task store.
+fn client_identity(headers: &HeaderMap) -> String {
+    match headers.get("x-hive-key").and_then(|v| v.to_str().ok()) {
+        Some(key) => {
+            let digest = hex::encode(Sha256::digest(key.as_bytes()));
+            format!("key:{}", &digest[..16])
+        }
+        None => ANONYMOUS_CLIENT.to_string(),
+    }
+}
+
 fn validate_request_auth(state: &AppState, headers: &HeaderMap) -> Result<(), String> {
     let provided_key = headers.get("x-hive-key").and_then(|v| v.to_str().ok());
     let expected_key = state.config.server.api_key.as_deref();
@@ -542,10 +1239,6 @@ fn status_update_from_task(task: &Task) -> TaskStatusUpdateEvent {
     }
 }
 
-fn task_is_final(state: &TaskState) -> bool {
-    !matches!(state, TaskState::Working | TaskState::Submitted)
-}
-
 fn rpc_success(id: Value, result: Value) -> (StatusCode, Json<Value>) {
     (
         StatusCode::OK,
@@ -685,6 +1378,7 @@ mod tests {
             .unwrap();
         let card: Value = serde_json::from_slice(&body).unwrap();
         assert_eq!(card["capabilities"]["streaming"], true);
+        assert_eq!(card["capabilities"]["pushNotifications"], true);
         assert_eq!(card["capabilities"]["stateTransitionHistory"], true);
     }
 
@@ -909,6 +1603,316 @@ mod tests {
         assert_eq!(second_resp.status(), StatusCode::TOO_MANY_REQUESTS);
     }
 
+    async fn rpc(app: &Router, body: Value) -> (StatusCode, Value) {
+        rpc_with_key(app, None, body).await
+    }
+
+    async fn rpc_with_key(app: &Router, key: Option<&str>, body: Value) -> (StatusCode, Value) {
+        let mut req = Request::builder()
+            .method("POST")
+            .uri("/a2a")
+            .header("content-type", "application/json");
+        if let Some(key) = key {
+            req = req.header("x-hive-key", key);
+        }
+        let req = req
+            .body(Body::from(serde_json::to_string(&body).unwrap()))
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        let status = resp.status();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn rpc_body(method: &str, params: Value) -> Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": "rpc",
+            "method": method,
+            "params": params,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_tasks_get_returns_task_and_trims_history() {
+        let app = build_router_with_handler(A2aConfig::default(), Some(task_handler()));
+        rpc(&app, message_send_body("1", "task-get")).await;
+
+        let (status, json) = rpc(
+            &app,
+            rpc_body("tasks/get", serde_json::json!({"id": "task-get"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["result"]["id"], "task-get");
+        assert_eq!(json["result"]["status"]["state"], "completed");
+        assert!(json["result"]["history"].as_array().unwrap().len() > 1);
+
+        let (_, json) = rpc(
+            &app,
+            rpc_body(
+                "tasks/get",
+                serde_json::json!({"id": "task-get", "historyLength": 1}),
+            ),
+        )
+        .await;
+        assert_eq!(json["result"]["history"].as_array().unwrap().len(), 1);
+
+        let (status, json) = rpc(
+            &app,
+            rpc_body("tasks/get", serde_json::json!({"id": "missing"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(json["error"]["code"], -32004);
+    }
+
+    #[tokio::test]
+    async fn test_tasks_list_only_returns_callers_tasks() {
+        let app = build_router_with_handler(A2aConfig::default(), Some(task_handler()));
+        for task_id in ["list-a1", "list-a2"] {
+            rpc_with_key(&app, Some("key-a"), message_send_body("1", task_id)).await;
+        }
+        rpc_with_key(&app, Some("key-b"), message_send_body("1", "list-b1")).await;
+
+        let (status, json) = rpc_with_key(
+            &app,
+            Some("key-a"),
+            rpc_body("tasks/list", serde_json::json!({"pageSize": 1})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let tasks = json["result"]["tasks"].as_array().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert!(tasks[0].get("artifacts").is_none_or(Value::is_null));
+        let token = json["result"]["nextPageToken"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let (_, json) = rpc_with_key(
+            &app,
+            Some("key-a"),
+            rpc_body("tasks/list", serde_json::json!({"pageToken": token})),
+        )
+        .await;
+        assert_eq!(json["result"]["tasks"].as_array().unwrap().len(), 1);
+        assert!(json["result"].get("nextPageToken").is_none());
+
+        // Params are optional.
+        let (_, json) =
+            rpc_with_key(&app, Some("key-b"), rpc_body("tasks/list", Value::Null)).await;
+        let tasks = json["result"]["tasks"].as_array().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0]["id"], "list-b1");
+
+        let (_, json) = rpc(&app, rpc_body("tasks/list", Value::Null)).await;
+        assert!(json["result"]["tasks"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tasks_cancel_rejects_finished_task() {
+        let app = build_router_with_handler(A2aConfig::default(), Some(task_handler()));
+        rpc(&app, message_send_body("1", "task-cancel")).await;
+
+        let (status, json) = rpc(
+            &app,
+            rpc_body("tasks/cancel", serde_json::json!({"id": "task-cancel"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(json["error"]["code"], -32002);
+    }
+
+    #[tokio::test]
+    async fn test_message_send_non_blocking_returns_working_task() {
+        let app = build_router_with_handler(A2aConfig::default(), Some(task_handler()));
+        let mut body = message_send_body("1", "task-async");
+        body["params"]["configuration"]["blocking"] = Value::Bool(false);
+
+        let (status, json) = rpc(&app, body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["result"]["status"]["state"], "working");
+
+        let mut state = Value::Null;
+        for _ in 0..50 {
+            let (_, json) = rpc(
+                &app,
+                rpc_body("tasks/get", serde_json::json!({"id": "task-async"})),
+            )
+            .await;
+            state = json["result"]["status"]["state"].clone();
+            if state == "completed" {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(state, "completed");
+    }
+
+    #[tokio::test]
+    async fn test_message_stream_sends_task_then_final_update() {
+        let app = build_router_with_handler(A2aConfig::default(), Some(task_handler()));
+        let mut body = message_send_body("stream-1", "task-stream");
+        body["method"] = Value::String("message/stream".into());
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/a2a")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_string(&body).unwrap()))
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        let results: Vec<Value> = text
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .map(|data| serde_json::from_str::<Value>(data).unwrap()["result"].clone())
+            .collect();
+
+        assert_eq!(results.first().unwrap()["status"]["state"], "working");
+        let last = results.last().unwrap();
+        assert_eq!(last["kind"], "status-update");
+        assert_eq!(last["final"], true);
+        assert_eq!(last["status"]["state"], "completed");
+        assert!(results
+            .iter()
+            .any(|result| result["kind"] == "task" && result["artifacts"].is_array()));
+    }
+
+    #[tokio::test]
+    async fn test_tasks_resubscribe_finished_task_sends_final_update() {
+        let app = build_router_with_handler(A2aConfig::default(), Some(task_handler()));
+        rpc(&app, message_send_body("1", "task-resub")).await;
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/a2a")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::to_string(&rpc_body(
+                    "tasks/resubscribe",
+                    serde_json::json!({"id": "task-resub"}),
+                ))
+                .unwrap(),
+            ))
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("\"final\":true"));
+        assert!(text.contains("completed"));
+    }
+
+    #[tokio::test]
+    async fn test_push_notification_config_lifecycle() {
+        let app = build_router_with_push(
+            A2aConfig::default(),
+            Some(task_handler()),
+            Arc::new(PushNotifier::new().allow_private_targets()),
+        );
+        rpc(&app, message_send_body("1", "task-push")).await;
+
+        let (status, json) = rpc(
+            &app,
+            rpc_body(
+                "tasks/pushNotificationConfig/set",
+                serde_json::json!({
+                    "taskId": "task-push",
+                    "pushNotificationConfig": {
+                        "url": "http://127.0.0.1:9/hook",
+                        "token": "secret"
+                    }
+                }),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let config_id = json["result"]["pushNotificationConfig"]["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            json["result"]["pushNotificationConfig"]["signingSecret"]
+                .as_str()
+                .unwrap()
+                .len(),
+            64
+        );
+
+        let (_, json) = rpc(
+            &app,
+            rpc_body(
+                "tasks/pushNotificationConfig/get",
+                serde_json::json!({"id": "task-push"}),
+            ),
+        )
+        .await;
+        assert_eq!(
+            json["result"]["pushNotificationConfig"]["url"],
+            "http://127.0.0.1:9/hook"
+        );
+
+        let (_, json) = rpc(
+            &app,
+            rpc_body(
+                "tasks/pushNotificationConfig/list",
+                serde_json::json!({"id": "task-push"}),
+            ),
+        )
+        .await;
+        assert_eq!(json["result"].as_array().unwrap().len(), 1);
+
+        let delete = serde_json::json!({"id": "task-push", "pushNotificationConfigId": config_id});
+        let (status, _) = rpc(
+            &app,
+            rpc_body("tasks/pushNotificationConfig/delete", delete.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = rpc(
+            &app,
+            rpc_body("tasks/pushNotificationConfig/delete", delete),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_push_notification_config_rejects_unsafe_url() {
+        let app = build_router_with_handler(A2aConfig::default(), Some(task_handler()));
+        rpc(&app, message_send_body("1", "task-push-unsafe")).await;
+
+        for url in [
+            "http://169.254.169.254/latest",
+            "https://127.0.0.1/hook",
+            "https://localhost/hook",
+        ] {
+            let (status, json) = rpc(
+                &app,
+                rpc_body(
+                    "tasks/pushNotificationConfig/set",
+                    serde_json::json!({
+                        "taskId": "task-push-unsafe",
+                        "pushNotificationConfig": {"url": url}
+                    }),
+                ),
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{url}");
+            assert_eq!(json["error"]["code"], -32602);
+        }
+    }
+
     #[tokio::test]
     async fn test_start_server_disabled() {
         let mut config = A2aConfig::default();
@@ -56,7 +56,7 @@ impl A2aClientService {
         let config = A2aConfig::load_or_create(&path)?;
         // Validate each agent URL at load time
         for agent in &config.agents {
-            crate::auth::validate_outbound_url(&agent.url).map_err(|e| {
+            crate::auth::validate_agent_url(&agent.url).map_err(|e| {
                 A2aError::Config(format!("Agent '{}' has invalid URL: {e}", agent.name))
             })?;
         }
@@ -83,7 +83,7 @@ impl A2aClientService {
         let config = A2aConfig::load_or_create(&self.config_path)?;
         // Validate each agent URL on reload
         for agent in &config.agents {
-            crate::auth::validate_outbound_url(&agent.url).map_err(|e| {
+            crate::auth::validate_agent_url(&agent.url).map_err(|e| {
                 A2aError::Config(format!("Agent '{}' has invalid URL: {e}", agent.name))
             })?;
         }
@@ -0,0 +1,1099 @@
+//! Task store — durable persistence for A2A tasks.
+//!
+//! [`HiveTaskHandler`](crate::task_handler::HiveTaskHandler) keeps running
+//! tasks in memory and writes each accepted task and every status change
+//! through a [`TaskStore`]. Finished tasks are evicted from memory shortly
+//! after they complete and served from the store until a
+//! [`RetentionPolicy`] purges them. Tasks still `working` when the server
+//! stops are found again with [`TaskStore::unfinished`] on the next start.
+//!
+//! Two stores are provided:
+//! - [`SqliteTaskStore`] — the default. Status history, messages and
+//!   artifacts live in SQLite; artifacts larger than the spill threshold are
+//!   written to files in a directory next to the database.
+//! - [`MemoryTaskStore`] — non-durable, used when no store is configured.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use a2a_rs::{Artifact, Message, Task, TaskStatus};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::config::{TaskStoreBackend, TaskStoreConfig};
+use crate::error::A2aError;
+use crate::task_handler::task_is_final;
+
+/// Owner recorded for tasks created without an `X-Hive-Key`.
+pub const ANONYMOUS_CLIENT: &str = "anonymous";
+
+/// Page size used by [`TaskStore::list`] when the query does not set one.
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Largest page [`TaskStore::list`] will return.
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// Artifacts whose JSON encoding is larger than this are spilled to disk.
+pub const DEFAULT_SPILL_THRESHOLD_BYTES: usize = 64 * 1024;
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/// A task as kept by a [`TaskStore`], with what is needed to run it again.
+#[derive(Debug, Clone)]
+pub struct StoredTask {
+    /// The A2A task, including its message history and artifacts.
+    pub task: Task,
+    /// Client identity that created the task.
+    pub owner: String,
+    /// Hive skill the task was routed to.
+    pub skill_id: String,
+    /// Text the skill was invoked with.
+    pub task_text: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Params for `tasks/list` and [`TaskStore::list`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskListQuery {
+    /// Only list tasks in this context.
+    #[serde(default)]
+    pub context_id: Option<String>,
+    #[serde(default)]
+    pub page_size: Option<usize>,
+    /// `next_page_token` from the previous page.
+    #[serde(default)]
+    pub page_token: Option<String>,
+    /// Only include the most recent `history_length` history messages.
+    #[serde(default)]
+    pub history_length: Option<usize>,
+    /// Artifacts are left out of listings unless this is set.
+    #[serde(default)]
+    pub include_artifacts: bool,
+}
+
+impl TaskListQuery {
+    fn page_size(&self) -> usize {
+        self.page_size
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE)
+    }
+}
+
+/// One page of tasks, most recently updated first.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskPage {
+    pub tasks: Vec<Task>,
+    /// Pass as `page_token` to fetch the next page; absent on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+}
+
+/// How long finished tasks are kept. Running tasks are never purged.
+#[derive(Debug, Clone, Default)]
+pub struct RetentionPolicy {
+    /// Purge finished tasks last updated longer ago than this.
+    pub max_age: Option<Duration>,
+    /// Keep at most this many finished tasks per client, newest first.
+    pub max_tasks_per_client: Option<usize>,
+}
+
+/// Persistence backend for A2A tasks.
+///
+/// Calls are synchronous; the task handler only uses the store from the
+/// blocking threads that run its futures.
+pub trait TaskStore: Send + Sync {
+    /// Insert or update a task. A status that differs from the last one
+    /// saved is appended to the task's status history. History messages
+    /// and artifacts are append-only: entries beyond those already stored
+    /// are added.
+    fn save(&self, stored: &StoredTask) -> Result<(), A2aError>;
+
+    /// Load a task with its full history and artifacts.
+    fn load(&self, task_id: &str) -> Result<Option<StoredTask>, A2aError>;
+
+    /// Every status the task has been saved with, oldest first.
+    fn status_history(&self, task_id: &str) -> Result<Vec<TaskStatus>, A2aError>;
+
+    /// List `owner`'s tasks, most recently updated first.
+    fn list(&self, owner: &str, query: &TaskListQuery) -> Result<TaskPage, A2aError>;
+
+    /// Tasks that had not reached a final state when last saved.
+    fn unfinished(&self) -> Result<Vec<StoredTask>, A2aError>;
+
+    /// Delete a task and everything stored for it. Returns `false` if the
+    /// task was not stored.
+    fn delete(&self, task_id: &str) -> Result<bool, A2aError>;
+
+    /// Delete finished tasks outside `policy`, returning how many went.
+    fn purge(&self, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<usize, A2aError>;
+}
+
+/// Open the task store described by `config`, keeping its files in `dir`.
+pub fn open_task_store(
+    config: &TaskStoreConfig,
+    dir: &Path,
+) -> Result<Arc<dyn TaskStore>, A2aError> {
+    match config.backend {
+        TaskStoreBackend::Memory => Ok(Arc::new(MemoryTaskStore::new())),
+        TaskStoreBackend::Sqlite => {
+            let store =
+                SqliteTaskStore::open(&dir.join("a2a_tasks.db"), &dir.join("a2a_artifacts"))?
+                    .with_spill_threshold(config.spill_threshold_bytes);
+            Ok(Arc::new(store))
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MemoryTaskStore
+// ---------------------------------------------------------------------------
+
+struct MemoryEntry {
+    stored: StoredTask,
+    statuses: Vec<TaskStatus>,
+}
+
+/// Non-durable [`TaskStore`] backed by a `HashMap`.
+#[derive(Default)]
+pub struct MemoryTaskStore {
+    tasks: Mutex<HashMap<String, MemoryEntry>>,
+}
+
+impl MemoryTaskStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, MemoryEntry>>, A2aError> {
+        self.tasks
+            .lock()
+            .map_err(|_| A2aError::Storage("Task store lock poisoned".into()))
+    }
+}
+
+impl TaskStore for MemoryTaskStore {
+    fn save(&self, stored: &StoredTask) -> Result<(), A2aError> {
+        let mut tasks = self.lock()?;
+        let status = stored.task.status.clone();
+        match tasks.get_mut(&stored.task.id) {
+            Some(entry) => {
+                let last = entry.statuses.last().map(to_json).transpose()?;
+                if last != Some(to_json(&status)?) {
+                    entry.statuses.push(status);
+                }
+                entry.stored = stored.clone();
+            }
+            None => {
+                tasks.insert(
+                    stored.task.id.clone(),
+                    MemoryEntry {
+                        stored: stored.clone(),
+                        statuses: vec![status],
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn load(&self, task_id: &str) -> Result<Option<StoredTask>, A2aError> {
+        Ok(self.lock()?.get(task_id).map(|entry| entry.stored.clone()))
+    }
+
+    fn status_history(&self, task_id: &str) -> Result<Vec<TaskStatus>, A2aError> {
+        Ok(self
+            .lock()?
+            .get(task_id)
+            .map(|entry| entry.statuses.clone())
+            .unwrap_or_default())
+    }
+
+    fn list(&self, owner: &str, query: &TaskListQuery) -> Result<TaskPage, A2aError> {
+        let cursor = query.page_token.as_deref().map(parse_cursor).transpose()?;
+        let tasks = self.lock()?;
+        let mut matching: Vec<&StoredTask> = tasks
+            .values()
+            .map(|entry| &entry.stored)
+            .filter(|stored| stored.owner == owner)
+            .filter(|stored| {
+                query
+                    .context_id
+                    .as_ref()
+                    .is_none_or(|context_id| &stored.task.context_id == context_id)
+            })
+            .filter(|stored| {
+                cursor.as_ref().is_none_or(|(updated_at, id)| {
+                    (&format_timestamp(stored.updated_at), &stored.task.id) < (updated_at, id)
+                })
+            })
+            .collect();
+        matching.sort_by_cached_key(|stored| {
+            std::cmp::Reverse((format_timestamp(stored.updated_at), stored.task.id.clone()))
+        });
+        matching.truncate(query.page_size() + 1);
+
+        Ok(build_page(matching.into_iter().cloned().collect(), query))
+    }
+
+    fn unfinished(&self) -> Result<Vec<StoredTask>, A2aError> {
+        Ok(self
+            .lock()?
+            .values()
+            .filter(|entry| !task_is_final(&entry.stored.task.status.state))
+            .map(|entry| entry.stored.clone())
+            .collect())
+    }
+
+    fn delete(&self, task_id: &str) -> Result<bool, A2aError> {
+        Ok(self.lock()?.remove(task_id).is_some())
+    }
+
+    fn purge(&self, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<usize, A2aError> {
+        let mut tasks = self.lock()?;
+        let finished: Vec<(String, String, DateTime<Utc>)> = tasks
+            .values()
+            .filter(|entry| task_is_final(&entry.stored.task.status.state))
+            .map(|entry| {
+                (
+                    entry.stored.task.id.clone(),
+                    entry.stored.owner.clone(),
+                    entry.stored.updated_at,
+                )
+            })
+            .collect();
+
+        let expired = expired_tasks(finished, policy, now);
+        for task_id in &expired {
+            tasks.remove(task_id);
+        }
+        Ok(expired.len())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SqliteTaskStore
+// ---------------------------------------------------------------------------
+
+/// SQLite-backed [`TaskStore`].
+pub struct SqliteTaskStore {
+    conn: Mutex<Connection>,
+    /// Where large artifacts are spilled; `None` keeps everything inline.
+    artifact_dir: Option<PathBuf>,
+    spill_threshold: usize,
+}
+
+impl SqliteTaskStore {
+    /// Open (or create) the store at `path`, spilling large artifacts into
+    /// `artifact_dir`.
+    pub fn open(path: &Path, artifact_dir: &Path) -> Result<Self, A2aError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                A2aError::Storage(format!("Failed to create {}: {e}", parent.display()))
+            })?;
+        }
+        std::fs::create_dir_all(artifact_dir).map_err(|e| {
+            A2aError::Storage(format!("Failed to create {}: {e}", artifact_dir.display()))
+        })?;
+
+        let conn = Connection::open(path).map_err(storage_error)?;
+        Self::with_connection(conn, Some(artifact_dir.to_path_buf()))
+    }
+
+    /// Open an in-memory database that keeps every artifact inline (for
+    /// testing).
+    pub fn open_in_memory() -> Result<Self, A2aError> {
+        let conn = Connection::open_in_memory().map_err(storage_error)?;
+        Self::with_connection(conn, None)
+    }
+
+    /// Spill artifacts whose JSON is larger than `bytes`.
+    pub fn with_spill_threshold(mut self, bytes: usize) -> Self {
+        self.spill_threshold = bytes;
+        self
+    }
+
+    fn with_connection(conn: Connection, artifact_dir: Option<PathBuf>) -> Result<Self, A2aError> {
+        let store = Self {
+            conn: Mutex::new(conn),
+            artifact_dir,
+            spill_threshold: DEFAULT_SPILL_THRESHOLD_BYTES,
+        };
+        store.init_schema()?;
+        Ok(store)
+    }
+
+    fn init_schema(&self) -> Result<(), A2aError> {
+        let conn = self.lock()?;
+        conn.execute_batch(
+            "PRAGMA foreign_keys = ON;
+            CREATE TABLE IF NOT EXISTS a2a_tasks (
+                id             TEXT    PRIMARY KEY,
+                owner          TEXT    NOT NULL,
+                context_id     TEXT    NOT NULL,
+                skill_id       TEXT    NOT NULL,
+                task_text      TEXT    NOT NULL,
+                is_final       INTEGER NOT NULL,
+                status_json    TEXT    NOT NULL,
+                metadata_json  TEXT,
+                created_at     TEXT    NOT NULL,
+                updated_at     TEXT    NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS a2a_task_statuses (
+                task_id      TEXT    NOT NULL REFERENCES a2a_tasks(id) ON DELETE CASCADE,
+                seq          INTEGER NOT NULL,
+                status_json  TEXT    NOT NULL,
+                PRIMARY KEY (task_id, seq)
+            );
+            CREATE TABLE IF NOT EXISTS a2a_task_messages (
+                task_id       TEXT    NOT NULL REFERENCES a2a_tasks(id) ON DELETE CASCADE,
+                seq           INTEGER NOT NULL,
+                message_json  TEXT    NOT NULL,
+                PRIMARY KEY (task_id, seq)
+            );
+            CREATE TABLE IF NOT EXISTS a2a_task_artifacts (
+                task_id        TEXT    NOT NULL REFERENCES a2a_tasks(id) ON DELETE CASCADE,
+                seq            INTEGER NOT NULL,
+                artifact_json  TEXT,
+                spill_path     TEXT,
+                size_bytes     INTEGER NOT NULL,
+                PRIMARY KEY (task_id, seq)
+            );
+            CREATE INDEX IF NOT EXISTS idx_a2a_tasks_owner   ON a2a_tasks(owner, updated_at);
+            CREATE INDEX IF NOT EXISTS idx_a2a_tasks_final   ON a2a_tasks(is_final);",
+        )
+        .map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, A2aError> {
+        self.conn
+            .lock()
+            .map_err(|_| A2aError::Storage("Task store lock poisoned".into()))
+    }
+
+    /// Write an artifact either inline or to a spill file, returning the
+    /// `(artifact_json, spill_path)` columns.
+    fn store_artifact(
+        &self,
+        task_id: &str,
+        seq: usize,
+        json: String,
+    ) -> Result<(Option<String>, Option<String>), A2aError> {
+        let Some(dir) = self
+            .artifact_dir
+            .as_ref()
+            .filter(|_| json.len() > self.spill_threshold)
+        else {
+            return Ok((Some(json), None));
+        };
+
+        // Task ids come from clients, so file names are derived from a
+        // digest rather than the id itself.
+        let digest = hex::encode(Sha256::digest(task_id.as_bytes()));
+        let path = dir.join(format!("{}-{seq}.json", &digest[..32]));
+        std::fs::write(&path, json)
+            .map_err(|e| A2aError::Storage(format!("Failed to write {}: {e}", path.display())))?;
+        Ok((None, Some(path.to_string_lossy().into_owned())))
+    }
+
+    fn load_locked(conn: &Connection, task_id: &str) -> Result<Option<StoredTask>, A2aError> {
+        let row = conn
+            .query_row(
+                "SELECT owner, context_id, skill_id, task_text, status_json, metadata_json, created_at, updated_at
+                 FROM a2a_tasks WHERE id = ?1",
+                params![task_id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, String>(4)?,
+                        row.get::<_, Option<String>>(5)?,
+                        row.get::<_, String>(6)?,
+                        row.get::<_, String>(7)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(storage_error)?;
+        let Some((owner, context_id, skill_id, task_text, status, metadata, created, updated)) =
+            row
+        else {
+            return Ok(None);
+        };
+
+        let history: Vec<Message> = query_strings(
+            conn,
+            "SELECT message_json FROM a2a_task_messages WHERE task_id = ?1 ORDER BY seq",
+            task_id,
+        )?
+        .iter()
+        .map(|json| from_json(json))
+        .collect::<Result<_, _>>()?;
+
+        let mut artifacts: Vec<Artifact> = Vec::new();
+        let mut stmt = conn
+            .prepare(
+                "SELECT artifact_json, spill_path FROM a2a_task_artifacts
+                 WHERE task_id = ?1 ORDER BY seq",
+            )
+            .map_err(storage_error)?;
+        let rows = stmt
+            .query_map(params![task_id], |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                ))
+            })
+            .map_err(storage_error)?;
+        for row in rows {
+            let json = match row.map_err(storage_error)? {
+                (Some(json), _) => json,
+                (None, Some(path)) => std::fs::read_to_string(&path).map_err(|e| {
+                    A2aError::Storage(format!("Failed to read spilled artifact {path}: {e}"))
+                })?,
+                (None, None) => continue,
+            };
+            artifacts.push(from_json(&json)?);
+        }
+
+        let metadata: Option<Map<String, Value>> =
+            metadata.as_deref().map(from_json).transpose()?;
+
+        Ok(Some(StoredTask {
+            task: Task {
+                id: task_id.to_string(),
+                context_id,
+                status: from_json(&status)?,
+                artifacts: (!artifacts.is_empty()).then_some(artifacts),
+                history: (!history.is_empty()).then_some(history),
+                metadata,
+                kind: "task".to_string(),
+            },
+            owner,
+            skill_id,
+            task_text,
+            created_at: parse_timestamp(&created)?,
+            updated_at: parse_timestamp(&updated)?,
+        }))
+    }
+
+    fn delete_locked(conn: &Connection, task_id: &str) -> Result<bool, A2aError> {
+        let spilled = query_strings(
+            conn,
+            "SELECT spill_path FROM a2a_task_artifacts WHERE task_id = ?1 AND spill_path IS NOT NULL",
+            task_id,
+        )?;
+        let deleted = conn
+            .execute("DELETE FROM a2a_tasks WHERE id = ?1", params![task_id])
+            .map_err(storage_error)?;
+        for path in spilled {
+            let _ = std::fs::remove_file(path);
+        }
+        Ok(deleted > 0)
+    }
+}
+
+impl TaskStore for SqliteTaskStore {
+    fn save(&self, stored: &StoredTask) -> Result<(), A2aError> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction().map_err(storage_error)?;
+        let task = &stored.task;
+        let status_json = to_json(&task.status)?;
+        let metadata_json = task.metadata.as_ref().map(to_json).transpose()?;
+
+        tx.execute(
+            "INSERT INTO a2a_tasks (id, owner, context_id, skill_id, task_text, is_final, status_json, metadata_json, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(id) DO UPDATE SET
+                context_id = excluded.context_id,
+                is_final = excluded.is_final,
+                status_json = excluded.status_json,
+                metadata_json = excluded.metadata_json,
+                updated_at = excluded.updated_at",
+            params![
+                task.id,
+                stored.owner,
+                task.context_id,
+                stored.skill_id,
+                stored.task_text,
+                task_is_final(&task.status.state),
+                status_json,
+                metadata_json,
+                format_timestamp(stored.created_at),
+                format_timestamp(stored.updated_at),
+            ],
+        )
+        .map_err(storage_error)?;
+
+        let last_status: Option<String> = tx
+            .query_row(
+                "SELECT status_json FROM a2a_task_statuses WHERE task_id = ?1 ORDER BY seq DESC LIMIT 1",
+                params![task.id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(storage_error)?;
+        if last_status.as_deref() != Some(status_json.as_str()) {
+            tx.execute(
+                "INSERT INTO a2a_task_statuses (task_id, seq, status_json)
+                 VALUES (?1, (SELECT COALESCE(MAX(seq), -1) + 1 FROM a2a_task_statuses WHERE task_id = ?1), ?2)",
+                params![task.id, status_json],
+            )
+            .map_err(storage_error)?;
+        }
+
+        let stored_messages = count_rows(&tx, "a2a_task_messages", &task.id)?;
+        let new_messages = task.history.iter().flatten().enumerate();
+        for (seq, message) in new_messages.skip(stored_messages) {
+            tx.execute(
+                "INSERT INTO a2a_task_messages (task_id, seq, message_json) VALUES (?1, ?2, ?3)",
+                params![task.id, seq as i64, to_json(message)?],
+            )
+            .map_err(storage_error)?;
+        }
+
+        let stored_artifacts = count_rows(&tx, "a2a_task_artifacts", &task.id)?;
+        let new_artifacts = task.artifacts.iter().flatten().enumerate();
+        for (seq, artifact) in new_artifacts.skip(stored_artifacts) {
+            let json = to_json(artifact)?;
+            let size = json.len() as i64;
+            let (inline, spill_path) = self.store_artifact(&task.id, seq, json)?;
+            tx.execute(
+                "INSERT INTO a2a_task_artifacts (task_id, seq, artifact_json, spill_path, size_bytes)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![task.id, seq as i64, inline, spill_path, size],
+            )
+            .map_err(storage_error)?;
+        }
+
+        tx.commit().map_err(storage_error)
+    }
+
+    fn load(&self, task_id: &str) -> Result<Option<StoredTask>, A2aError> {
+        let conn = self.lock()?;
+        Self::load_locked(&conn, task_id)
+    }
+
+    fn status_history(&self, task_id: &str) -> Result<Vec<TaskStatus>, A2aError> {
+        let conn = self.lock()?;
+        query_strings(
+            &conn,
+            "SELECT status_json FROM a2a_task_statuses WHERE task_id = ?1 ORDER BY seq",
+            task_id,
+        )?
+        .iter()
+        .map(|json| from_json(json))
+        .collect()
+    }
+
+    fn list(&self, owner: &str, query: &TaskListQuery) -> Result<TaskPage, A2aError> {
+        let (cursor_updated_at, cursor_id) =
+            match query.page_token.as_deref().map(parse_cursor).transpose()? {
+                Some((updated_at, id)) => (Some(updated_at), Some(id)),
+                None => (None, None),
+            };
+
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id FROM a2a_tasks
+                 WHERE owner = ?1
+                   AND (?2 IS NULL OR context_id = ?2)
+                   AND (?3 IS NULL OR updated_at < ?3 OR (updated_at = ?3 AND id < ?4))
+                 ORDER BY updated_at DESC, id DESC
+                 LIMIT ?5",
+            )
+            .map_err(storage_error)?;
+        let ids = stmt
+            .query_map(
+                params![
+                    owner,
+                    query.context_id,
+                    cursor_updated_at,
+                    cursor_id,
+                    (query.page_size() + 1) as i64,
+                ],
+                |row| row.get::<_, String>(0),
+            )
+            .map_err(storage_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(storage_error)?;
+
+        let mut tasks = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(stored) = Self::load_locked(&conn, &id)? {
+                tasks.push(stored);
+            }
+        }
+        Ok(build_page(tasks, query))
+    }
+
+    fn unfinished(&self) -> Result<Vec<StoredTask>, A2aError> {
+        let conn = self.lock()?;
+        let ids = {
+            let mut stmt = conn
+                .prepare("SELECT id FROM a2a_tasks WHERE is_final = 0 ORDER BY created_at")
+                .map_err(storage_error)?;
+            let rows = stmt
+                .query_map([], |row| row.get::<_, String>(0))
+                .map_err(storage_error)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(storage_error)?
+        };
+
+        let mut tasks = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(stored) = Self::load_locked(&conn, &id)? {
+                tasks.push(stored);
+            }
+        }
+        Ok(tasks)
+    }
+
+    fn delete(&self, task_id: &str) -> Result<bool, A2aError> {
+        let conn = self.lock()?;
+        Self::delete_locked(&conn, task_id)
+    }
+
+    fn purge(&self, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<usize, A2aError> {
+        let conn = self.lock()?;
+        let finished = {
+            let mut stmt = conn
+                .prepare("SELECT id, owner, updated_at FROM a2a_tasks WHERE is_final = 1")
+                .map_err(storage_error)?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                    ))
+                })
+                .map_err(storage_error)?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(storage_error)?;
+            rows.into_iter()
+                .map(|(id, owner, updated_at)| Ok((id, owner, parse_timestamp(&updated_at)?)))
+                .collect::<Result<Vec<_>, A2aError>>()?
+        };
+
+        let expired = expired_tasks(finished, policy, now);
+        for task_id in &expired {
+            Self::delete_locked(&conn, task_id)?;
+        }
+        Ok(expired.len())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Drop all but the most recent `limit` history messages.
+pub fn trim_history(task: &mut Task, limit: Option<usize>) {
+    if let (Some(limit), Some(history)) = (limit, task.history.as_mut()) {
+        let excess = history.len().saturating_sub(limit);
+        history.drain(..excess);
+    }
+}
+
+/// Shape up to `page_size + 1` tasks (newest first) into a page, using the
+/// extra task only to tell whether another page follows.
+fn build_page(mut stored: Vec<StoredTask>, query: &TaskListQuery) -> TaskPage {
+    let page_size = query.page_size();
+    let next_page_token = if stored.len() > page_size {
+        stored.truncate(page_size);
+        stored
+            .last()
+            .map(|last| format!("{}|{}", format_timestamp(last.updated_at), last.task.id))
+    } else {
+        None
+    };
+
+    let tasks = stored
+        .into_iter()
+        .map(|stored| {
+            let mut task = stored.task;
+            trim_history(&mut task, query.history_length);
+            if !query.include_artifacts {
+                task.artifacts = None;
+            }
+            task
+        })
+        .collect();
+    TaskPage {
+        tasks,
+        next_page_token,
+    }
+}
+
+/// Split a page token into its normalised `updated_at` timestamp and task
+/// id. Comparing formatted timestamps keeps both stores consistent with
+/// SQLite's text ordering.
+fn parse_cursor(token: &str) -> Result<(String, String), A2aError> {
+    token
+        .split_once('|')
+        .and_then(|(updated_at, id)| {
+            let updated_at = DateTime::parse_from_rfc3339(updated_at).ok()?;
+            Some((
+                format_timestamp(updated_at.with_timezone(&Utc)),
+                id.to_string(),
+            ))
+        })
+        .ok_or_else(|| A2aError::Bridge(format!("Invalid page token: {token}")))
+}
+
+/// Ids of the finished tasks `(id, owner, updated_at)` that `policy` no
+/// longer keeps.
+fn expired_tasks(
+    mut finished: Vec<(String, String, DateTime<Utc>)>,
+    policy: &RetentionPolicy,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    // Newest first, so the per-client cap keeps the most recent tasks.
+    finished.sort_by(|a, b| (b.2, &b.0).cmp(&(a.2, &a.0)));
+    let cutoff = policy
+        .max_age
+        .and_then(|max_age| chrono::Duration::from_std(max_age).ok())
+        .map(|max_age| now - max_age);
+
+    let mut kept_per_client: HashMap<String, usize> = HashMap::new();
+    let mut expired = Vec::new();
+    for (id, owner, updated_at) in finished {
+        if cutoff.is_some_and(|cutoff| updated_at < cutoff) {
+            expired.push(id);
+            continue;
+        }
+        let kept = kept_per_client.entry(owner).or_default();
+        if policy.max_tasks_per_client.is_some_and(|max| *kept >= max) {
+            expired.push(id);
+        } else {
+            *kept += 1;
+        }
+    }
+    expired
+}
+
+fn count_rows(conn: &Connection, table: &str, task_id: &str) -> Result<usize, A2aError> {
+    conn.query_row(
+        &format!("SELECT COUNT(*) FROM {table} WHERE task_id = ?1"),
+        params![task_id],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|count| count as usize)
+    .map_err(storage_error)
+}
+
+fn query_strings(conn: &Connection, sql: &str, task_id: &str) -> Result<Vec<String>, A2aError> {
+    let mut stmt = conn.prepare(sql).map_err(storage_error)?;
+    let rows = stmt
+        .query_map(params![task_id], |row| row.get::<_, String>(0))
+        .map_err(storage_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(storage_error)?;
+    Ok(rows)
+}
+
+/// Fixed-width timestamps so they sort correctly as SQLite text.
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, A2aError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|e| A2aError::Storage(format!("Invalid stored timestamp {value}: {e}")))
+}
+
+fn to_json(value: &impl Serialize) -> Result<String, A2aError> {
+    serde_json::to_string(value).map_err(|e| A2aError::Storage(format!("Failed to encode: {e}")))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(json: &str) -> Result<T, A2aError> {
+    serde_json::from_str(json).map_err(|e| A2aError::Storage(format!("Failed to decode: {e}")))
+}
+
+fn storage_error(error: rusqlite::Error) -> A2aError {
+    A2aError::Storage(error.to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use a2a_rs::{Part, TaskState};
+    use uuid::Uuid;
+
+    fn stored_task(
+        id: &str,
+        owner: &str,
+        state: TaskState,
+        updated_at: DateTime<Utc>,
+    ) -> StoredTask {
+        StoredTask {
+            task: Task {
+                id: id.to_string(),
+                context_id: "ctx-1".to_string(),
+                status: TaskStatus {
+                    state,
+                    message: None,
+                    timestamp: Some(updated_at),
+                },
+                artifacts: None,
+                history: Some(vec![Message::user_text(
+                    format!("run {id}"),
+                    Uuid::new_v4().to_string(),
+                )]),
+                metadata: None,
+                kind: "task".to_string(),
+            },
+            owner: owner.to_string(),
+            skill_id: "single".to_string(),
+            task_text: format!("run {id}"),
+            created_at: updated_at,
+            updated_at,
+        }
+    }
+
+    fn complete(stored: &mut StoredTask, artifact_text: String) {
+        stored.task.artifacts = Some(vec![Artifact {
+            artifact_id: format!("single-{}", Uuid::new_v4()),
+            name: Some("Result".into()),
+            description: None,
+            parts: vec![Part::text(artifact_text)],
+            metadata: None,
+        }]);
+        stored
+            .task
+            .history
+            .get_or_insert_with(Vec::new)
+            .push(Message::agent_text(
+                "Task completed".into(),
+                Uuid::new_v4().to_string(),
+            ));
+        stored.task.status.state = TaskState::Completed;
+        stored.updated_at += chrono::Duration::seconds(1);
+    }
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("hive_a2a_store_{}", Uuid::new_v4()))
+    }
+
+    fn check_round_trip(store: &dyn TaskStore) {
+        let mut stored = stored_task("task-1", "client-a", TaskState::Working, Utc::now());
+        store.save(&stored).unwrap();
+        store.save(&stored).unwrap();
+        complete(&mut stored, "result".into());
+        store.save(&stored).unwrap();
+
+        let loaded = store.load("task-1").unwrap().unwrap();
+        assert_eq!(loaded.owner, "client-a");
+        assert_eq!(loaded.skill_id, "single");
+        assert_eq!(loaded.task.status.state, TaskState::Completed);
+        assert_eq!(loaded.task.history.unwrap().len(), 2);
+        assert_eq!(loaded.task.artifacts.unwrap().len(), 1);
+
+        // Saving an unchanged status does not repeat it in the history.
+        let states: Vec<TaskState> = store
+            .status_history("task-1")
+            .unwrap()
+            .into_iter()
+            .map(|status| status.state)
+            .collect();
+        assert_eq!(states, vec![TaskState::Working, TaskState::Completed]);
+
+        assert!(store.load("missing").unwrap().is_none());
+        assert!(store.delete("task-1").unwrap());
+        assert!(!store.delete("task-1").unwrap());
+        assert!(store.load("task-1").unwrap().is_none());
+    }
+
+    fn check_listing(store: &dyn TaskStore) {
+        let start = Utc::now();
+        for i in 0..3 {
+            let mut stored = stored_task(
+                &format!("a-{i}"),
+                "client-a",
+                TaskState::Working,
+                start + chrono::Duration::seconds(i),
+            );
+            complete(&mut stored, format!("result {i}"));
+            store.save(&stored).unwrap();
+        }
+        store
+            .save(&stored_task("b-0", "client-b", TaskState::Working, start))
+            .unwrap();
+
+        let query = TaskListQuery {
+            page_size: Some(2),
+            history_length: Some(1),
+            ..TaskListQuery::default()
+        };
+        let first = store.list("client-a", &query).unwrap();
+        let ids: Vec<&str> = first.tasks.iter().map(|task| task.id.as_str()).collect();
+        assert_eq!(ids, vec!["a-2", "a-1"]);
+        assert!(first.tasks.iter().all(|task| task.artifacts.is_none()));
+        assert!(first
+            .tasks
+            .iter()
+            .all(|task| task.history.as_ref().unwrap().len() == 1));
+
+        let second = store
+            .list(
+                "client-a",
+                &TaskListQuery {
+                    page_token: first.next_page_token.clone(),
+                    include_artifacts: true,
+                    ..query.clone()
+                },
+            )
+            .unwrap();
+        assert_eq!(second.tasks.len(), 1);
+        assert_eq!(second.tasks[0].id, "a-0");
+        assert!(second.tasks[0].artifacts.is_some());
+        assert!(second.next_page_token.is_none());
+
+        let other = store.list("client-b", &TaskListQuery::default()).unwrap();
+        assert_eq!(other.tasks.len(), 1);
+        assert_eq!(other.tasks[0].id, "b-0");
+
+        let bad_token = TaskListQuery {
+            page_token: Some("not-a-token".into()),
+            ..TaskListQuery::default()
+        };
+        assert!(store.list("client-a", &bad_token).is_err());
+    }
+
+    fn check_unfinished_and_purge(store: &dyn TaskStore) {
+        let now = Utc::now();
+        let old = now - chrono::Duration::days(40);
+        for (id, updated_at) in [("old", old), ("new-1", now), ("new-2", now)] {
+            let mut stored = stored_task(id, "client-a", TaskState::Working, updated_at);
+            complete(&mut stored, "done".into());
+            store.save(&stored).unwrap();
+        }
+        store
+            .save(&stored_task("running", "client-a", TaskState::Working, old))
+            .unwrap();
+
+        let unfinished = store.unfinished().unwrap();
+        assert_eq!(unfinished.len(), 1);
+        assert_eq!(unfinished[0].task.id, "running");
+
+        let policy = RetentionPolicy {
+            max_age: Some(Duration::from_secs(30 * 24 * 60 * 60)),
+            max_tasks_per_client: Some(1),
+        };
+        assert_eq!(store.purge(&policy, now).unwrap(), 2);
+        assert!(store.load("old").unwrap().is_none());
+        assert!(store.load("running").unwrap().is_some());
+        let remaining = store.list("client-a", &TaskListQuery::default()).unwrap();
+        assert_eq!(remaining.tasks.len(), 2);
+    }
+
+    #[test]
+    fn test_memory_store_round_trip() {
+        check_round_trip(&MemoryTaskStore::new());
+    }
+
+    #[test]
+    fn test_sqlite_store_round_trip() {
+        check_round_trip(&SqliteTaskStore::open_in_memory().unwrap());
+    }
+
+    #[test]
+    fn test_memory_store_listing() {
+        check_listing(&MemoryTaskStore::new());
+    }
+
+    #[test]
+    fn test_sqlite_store_listing() {
+        check_listing(&SqliteTaskStore::open_in_memory().unwrap());
+    }
+
+    #[test]
+    fn test_memory_store_unfinished_and_purge() {
+        check_unfinished_and_purge(&MemoryTaskStore::new());
+    }
+
+    #[test]
+    fn test_sqlite_store_unfinished_and_purge() {
+        check_unfinished_and_purge(&SqliteTaskStore::open_in_memory().unwrap());
+    }
+
+    #[test]
+    fn test_sqlite_store_persists_across_reopen() {
+        let dir = temp_dir();
+        let db = dir.join("tasks.db");
+        let artifacts = dir.join("artifacts");
+        {
+            let store = SqliteTaskStore::open(&db, &artifacts).unwrap();
+            store
+                .save(&stored_task(
+                    "task-1",
+                    "client-a",
+                    TaskState::Working,
+                    Utc::now(),
+                ))
+                .unwrap();
+        }
+
+        let store = SqliteTaskStore::open(&db, &artifacts).unwrap();
+        let unfinished = store.unfinished().unwrap();
+        assert_eq!(unfinished.len(), 1);
+        assert_eq!(unfinished[0].task_text, "run task-1");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sqlite_store_spills_large_artifacts() {
+        let dir = temp_dir();
+        let artifacts = dir.join("artifacts");
+        let store = SqliteTaskStore::open(&dir.join("tasks.db"), &artifacts)
+            .unwrap()
+            .with_spill_threshold(1024);
+
+        let mut small = stored_task("small", "client-a", TaskState::Working, Utc::now());
+        complete(&mut small, "tiny".into());
+        store.save(&small).unwrap();
+        let mut large = stored_task("large", "client-a", TaskState::Working, Utc::now());
+        complete(&mut large, "x".repeat(4096));
+        store.save(&large).unwrap();
+
+        let spilled = || std::fs::read_dir(&artifacts).unwrap().count();
+        assert_eq!(spilled(), 1);
+
+        let loaded = store.load("large").unwrap().unwrap();
+        let artifact = &loaded.task.artifacts.unwrap()[0];
+        assert_eq!(crate::bridge::artifact_to_text(artifact), "x".repeat(4096));
+
+        assert!(store.delete("large").unwrap());
+        assert_eq!(spilled(), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_trim_history_keeps_most_recent() {
+        let mut stored = stored_task("task-1", "client-a", TaskState::Working, Utc::now());
+        complete(&mut stored, "done".into());
+        let mut task = stored.task;
+        trim_history(&mut task, Some(1));
+        let history = task.history.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].role, a2a_rs::Role::Agent);
+    }
+}
@@ -8,18 +8,25 @@
 //!
 //! The [`AiExecutor`] trait uses `async fn` whose future is not guaranteed
 //! `Send`, which prevents `tokio::spawn`. Orchestration is therefore
-//! awaited inline within [`HiveTaskHandler::handle_message`]. The task is
-//! stored in `active_tasks` *before* execution begins (in `Working` state)
-//! so concurrent callers can observe it, and updated in-place when
+//! awaited inline within [`HiveTaskHandler::run_accepted`]. The task is
+//! stored in `active_tasks` by [`HiveTaskHandler::accept_message`] *before*
+//! execution begins (in `Working` state) so concurrent callers can observe
+//! it, subscribe to its updates or cancel it, and updated in-place when
 //! orchestration completes.
+//!
+//! Every accepted task and each state change is also written to the
+//! handler's [`TaskStore`]. Only running tasks (and recently finished ones)
+//! are held in memory; older tasks are served from the store, and tasks
+//! that were still running when the server stopped are picked up again by
+//! [`HiveTaskHandler::recover_tasks`].
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use a2a_rs::{Artifact, Message, Part, Task, TaskState, TaskStatus, TaskStatusUpdateEvent};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde_json::{Map, Value};
-use tokio::sync::{broadcast, Mutex};
+use tokio::sync::{broadcast, watch, Mutex};
 use uuid::Uuid;
 
 use hive_agents::coordinator::{Coordinator, CoordinatorConfig};
@@ -36,6 +43,10 @@ use crate::agent_card::{
 use crate::bridge;
 use crate::config::ServerDefaults;
 use crate::error::A2aError;
+use crate::store::{
+    MemoryTaskStore, RetentionPolicy, StoredTask, TaskListQuery, TaskPage, TaskStore,
+    ANONYMOUS_CLIENT,
+};
 
 // ---------------------------------------------------------------------------
 // ArcExecutor — bridge to pass Arc<E> where E: AiExecutor is expected by value
@@ -95,6 +106,70 @@ pub struct ActiveTask {
     pub a2a_task: Task,
     /// Broadcast sender for status update events.
     pub event_tx: broadcast::Sender<TaskStatusUpdateEvent>,
+    /// Set to `true` by [`HiveTaskHandler::cancel_task`]; the running
+    /// orchestration is dropped as soon as it changes.
+    pub cancel_tx: watch::Sender<bool>,
+    /// Client identity that created the task.
+    pub owner: String,
+    skill_id: String,
+    task_text: String,
+    created_at: DateTime<Utc>,
+}
+
+impl ActiveTask {
+    fn new(a2a_task: Task, owner: &str, skill_id: &str, task_text: &str) -> Self {
+        let (event_tx, _) = broadcast::channel::<TaskStatusUpdateEvent>(64);
+        let (cancel_tx, _) = watch::channel(false);
+        Self {
+            a2a_task,
+            event_tx,
+            cancel_tx,
+            owner: owner.to_string(),
+            skill_id: skill_id.to_string(),
+            task_text: task_text.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// The task as it should be written to the [`TaskStore`].
+    fn stored(&self) -> StoredTask {
+        StoredTask {
+            task: self.a2a_task.clone(),
+            owner: self.owner.clone(),
+            skill_id: self.skill_id.clone(),
+            task_text: self.task_text.clone(),
+            created_at: self.created_at,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Move the task into a final `state` and send the final status update.
+    fn finish(&mut self, state: TaskState, text: String) {
+        let message = Message::agent_text(text, Uuid::new_v4().to_string());
+        self.a2a_task.update_status(state, Some(message));
+
+        let _ = self.event_tx.send(TaskStatusUpdateEvent {
+            task_id: self.a2a_task.id.clone(),
+            context_id: self.a2a_task.context_id.clone(),
+            kind: "status-update".to_string(),
+            status: self.a2a_task.status.clone(),
+            final_: true,
+            metadata: None,
+        });
+    }
+}
+
+/// A task accepted by [`HiveTaskHandler::accept_message`] and stored in the
+/// `Working` state, ready to be executed by [`HiveTaskHandler::run_accepted`].
+///
+/// Accepting and running are separate steps so callers can subscribe to the
+/// task's status updates before the first one is sent.
+#[derive(Clone)]
+pub struct AcceptedTask {
+    /// The task as stored when it was accepted.
+    pub task: Task,
+    skill_id: String,
+    task_text: String,
 }
 
 // ---------------------------------------------------------------------------
@@ -110,20 +185,31 @@ pub struct HiveTaskHandler<E: AiExecutor + 'static> {
     executor: Arc<E>,
     defaults: ServerDefaults,
     active_tasks: Arc<Mutex<HashMap<String, ActiveTask>>>,
+    store: Arc<dyn TaskStore>,
     rag: Option<Arc<std::sync::Mutex<hive_ai::rag::RagService>>>,
 }
 
 impl<E: AiExecutor + 'static> HiveTaskHandler<E> {
     /// Create a new task handler with the given executor and server defaults.
+    ///
+    /// Tasks are kept in a [`MemoryTaskStore`] until [`Self::with_store`]
+    /// supplies a durable one.
     pub fn new(executor: Arc<E>, defaults: ServerDefaults) -> Self {
         Self {
             executor,
             defaults,
             active_tasks: Arc::new(Mutex::new(HashMap::new())),
+            store: Arc::new(MemoryTaskStore::new()),
             rag: None,
         }
     }
 
+    /// Persist tasks in `store`.
+    pub fn with_store(mut self, store: Arc<dyn TaskStore>) -> Self {
+        self.store = store;
+        self
+    }
+
     /// Attach a RAG service for context-aware Queen orchestration.
     pub fn with_rag(mut self, rag: Arc<std::sync::Mutex<hive_ai::rag::RagService>>) -> Self {
         self.rag = Some(rag);
@@ -132,14 +218,31 @@ impl<E: AiExecutor + 'static> HiveTaskHandler<E> {
 
     /// Handle an incoming A2A message for the given task ID.
     ///
+    /// Accepts the message with [`Self::accept_message`] on behalf of an
+    /// anonymous client and runs it to completion with
+    /// [`Self::run_accepted`], returning the final `Task`.
+    pub async fn handle_message(&self, task_id: &str, message: &Message) -> Result<Task, A2aError> {
+        let accepted = self
+            .accept_message(ANONYMOUS_CLIENT, task_id, message)
+            .await?;
+        self.run_accepted(accepted).await
+    }
+
+    /// Accept an incoming A2A message from client `owner` without
+    /// executing it.
+    ///
     /// 1. Extracts text from the message.
     /// 2. Resolves which Hive skill to invoke.
     /// 3. Creates an A2A `Task` in the `Working` state and stores it.
-    /// 4. Awaits orchestration inline (AiExecutor futures are not `Send`).
-    /// 5. Updates the task to `Completed` or `Failed` and sends a final
-    ///    status update event.
-    /// 6. Returns the completed `Task`.
-    pub async fn handle_message(&self, task_id: &str, message: &Message) -> Result<Task, A2aError> {
+    ///
+    /// A task ID already used by another client is rejected; reusing one of
+    /// the client's own task IDs starts that task afresh.
+    pub async fn accept_message(
+        &self,
+        owner: &str,
+        task_id: &str,
+        message: &Message,
+    ) -> Result<AcceptedTask, A2aError> {
         let task_text = bridge::extract_message_text(message);
         if task_text.trim().is_empty() {
             return Err(A2aError::Bridge("Message contains no text content".into()));
@@ -168,7 +271,7 @@ impl<E: AiExecutor + 'static> HiveTaskHandler<E> {
 
         let a2a_task = Task {
             id: task_id.to_string(),
-            context_id: context_id.clone(),
+            context_id,
             status: TaskStatus {
                 state: TaskState::Working,
                 message: Some(status_msg),
@@ -180,103 +283,192 @@ impl<E: AiExecutor + 'static> HiveTaskHandler<E> {
             kind: "task".to_string(),
         };
 
-        let (event_tx, _) = broadcast::channel::<TaskStatusUpdateEvent>(64);
-
         // Store the task in Working state before execution begins.
         {
             let mut tasks = self.active_tasks.lock().await;
-            tasks.insert(
-                task_id.to_string(),
-                ActiveTask {
-                    a2a_task: a2a_task.clone(),
-                    event_tx: event_tx.clone(),
-                },
-            );
+            if let Some(previous) = self.store.load(task_id)? {
+                if previous.owner != owner {
+                    return Err(A2aError::Security(format!(
+                        "Task {task_id} belongs to another client"
+                    )));
+                }
+                self.store.delete(task_id)?;
+            }
+
+            let active = ActiveTask::new(a2a_task.clone(), owner, &skill_id, &task_text);
+            self.store.save(&active.stored())?;
+            tasks.insert(task_id.to_string(), active);
         }
 
+        Ok(AcceptedTask {
+            task: a2a_task,
+            skill_id,
+            task_text,
+        })
+    }
+
+    /// Execute an accepted task and return it in its final state.
+    ///
+    /// 1. Awaits orchestration inline (AiExecutor futures are not `Send`).
+    /// 2. Updates the task to `Completed` or `Failed` and sends a final
+    ///    status update event.
+    ///
+    /// If the task is canceled while running, the orchestration future is
+    /// dropped (stopping any in-flight swarm teams and provider calls) and
+    /// the `Canceled` task is returned.
+    pub async fn run_accepted(&self, accepted: AcceptedTask) -> Result<Task, A2aError> {
+        let task_id = accepted.task.id.clone();
+        let mut canceled = {
+            let tasks = self.active_tasks.lock().await;
+            tasks
+                .get(&task_id)
+                .map(|active| active.cancel_tx.subscribe())
+                .ok_or_else(|| A2aError::TaskNotFound(task_id.clone()))?
+        };
+
         // Execute the skill inline (AiExecutor futures are not Send).
-        let result = execute_skill(
-            &skill_id,
-            &task_text,
-            Arc::clone(&self.executor),
-            &self.defaults,
-            self.rag.clone(),
-        )
-        .await;
+        let result = tokio::select! {
+            result = execute_skill(
+                &accepted.skill_id,
+                &accepted.task_text,
+                Arc::clone(&self.executor),
+                &self.defaults,
+                self.rag.clone(),
+            ) => Some(result),
+            _ = canceled.wait_for(|canceled| *canceled) => None,
+        };
 
         // Update the stored task with the result.
-        let final_task = {
-            let mut tasks = self.active_tasks.lock().await;
-            if let Some(active) = tasks.get_mut(task_id) {
-                match result {
-                    Ok(artifact) => {
-                        active.a2a_task.add_artifact(artifact);
-                        let done_msg = Message::agent_text(
-                            "Task completed successfully".into(),
-                            Uuid::new_v4().to_string(),
-                        );
-                        active
-                            .a2a_task
-                            .update_status(TaskState::Completed, Some(done_msg.clone()));
-
-                        let _ = event_tx.send(TaskStatusUpdateEvent {
-                            task_id: task_id.to_string(),
-                            context_id: context_id.clone(),
-                            kind: "status-update".to_string(),
-                            status: TaskStatus {
-                                state: TaskState::Completed,
-                                message: Some(done_msg),
-                                timestamp: Some(Utc::now()),
-                            },
-                            final_: true,
-                            metadata: None,
-                        });
-                    }
-                    Err(ref e) => {
-                        let err_msg = Message::agent_text(
-                            format!("Task failed: {}", e),
-                            Uuid::new_v4().to_string(),
-                        );
-                        active
-                            .a2a_task
-                            .update_status(TaskState::Failed, Some(err_msg.clone()));
-
-                        let _ = event_tx.send(TaskStatusUpdateEvent {
-                            task_id: task_id.to_string(),
-                            context_id: context_id.clone(),
-                            kind: "status-update".to_string(),
-                            status: TaskStatus {
-                                state: TaskState::Failed,
-                                message: Some(err_msg),
-                                timestamp: Some(Utc::now()),
-                            },
-                            final_: true,
-                            metadata: None,
-                        });
-                    }
-                }
-                active.a2a_task.clone()
-            } else {
-                a2a_task
+        let mut tasks = self.active_tasks.lock().await;
+        let Some(active) = tasks.get_mut(&task_id) else {
+            return Ok(accepted.task);
+        };
+        // Canceled: `cancel_task` has already finalised the task, possibly
+        // just as the orchestration completed.
+        let Some(result) = result else {
+            return Ok(active.a2a_task.clone());
+        };
+        if task_is_final(&active.a2a_task.status.state) {
+            return Ok(active.a2a_task.clone());
+        }
+
+        match result {
+            Ok(artifact) => {
+                active.a2a_task.add_artifact(artifact);
+                active.finish(TaskState::Completed, "Task completed successfully".into());
             }
+            Err(e) => active.finish(TaskState::Failed, format!("Task failed: {}", e)),
+        }
+        self.persist(active);
+        Ok(active.a2a_task.clone())
+    }
+
+    /// Cancel a task that is still running.
+    ///
+    /// Signals the running orchestration to stop, marks the task `Canceled`
+    /// and sends a final status update. Tasks already in a final state
+    /// cannot be canceled.
+    pub async fn cancel_task(&self, task_id: &str) -> Result<Task, A2aError> {
+        let mut tasks = self.active_tasks.lock().await;
+        let Some(active) = tasks.get_mut(task_id) else {
+            // Only running tasks are in memory, so a stored task is final.
+            return match self.store.load(task_id)? {
+                Some(stored) => Err(A2aError::NotCancelable(format!(
+                    "{} is already {:?}",
+                    task_id, stored.task.status.state
+                ))),
+                None => Err(A2aError::TaskNotFound(task_id.to_string())),
+            };
         };
 
-        Ok(final_task)
+        if task_is_final(&active.a2a_task.status.state) {
+            return Err(A2aError::NotCancelable(format!(
+                "{} is already {:?}",
+                task_id, active.a2a_task.status.state
+            )));
+        }
+
+        active.cancel_tx.send_replace(true);
+        active.finish(TaskState::Canceled, "Task canceled".into());
+        self.persist(active);
+        Ok(active.a2a_task.clone())
     }
 
-    /// Retrieve a task by ID from the active tasks map.
+    /// Retrieve a task by ID, from memory if it is still held there and
+    /// from the task store otherwise.
     pub async fn get_task(&self, task_id: &str) -> Result<Task, A2aError> {
-        let tasks = self.active_tasks.lock().await;
-        tasks
-            .get(task_id)
-            .map(|active| active.a2a_task.clone())
+        if let Some(active) = self.active_tasks.lock().await.get(task_id) {
+            return Ok(active.a2a_task.clone());
+        }
+        self.store
+            .load(task_id)?
+            .map(|stored| stored.task)
             .ok_or_else(|| A2aError::TaskNotFound(task_id.to_string()))
     }
 
-    /// Remove a task from the active tasks map.
+    /// List the tasks created by client `owner`, most recently updated first.
+    pub fn list_tasks(&self, owner: &str, query: &TaskListQuery) -> Result<TaskPage, A2aError> {
+        self.store.list(owner, query)
+    }
+
+    /// Every status a task has passed through, oldest first.
+    pub fn status_history(&self, task_id: &str) -> Result<Vec<TaskStatus>, A2aError> {
+        let history = self.store.status_history(task_id)?;
+        if history.is_empty() {
+            return Err(A2aError::TaskNotFound(task_id.to_string()));
+        }
+        Ok(history)
+    }
+
+    /// Re-queue the tasks that were still running when the server last
+    /// stopped.
+    ///
+    /// Each task is put back in memory in the `Working` state and returned
+    /// so the caller can run it again with [`Self::run_accepted`].
+    /// Orchestration restarts from the beginning; nothing from the
+    /// interrupted run is kept.
+    pub async fn recover_tasks(&self) -> Result<Vec<AcceptedTask>, A2aError> {
+        let unfinished = self.store.unfinished()?;
+        let mut tasks = self.active_tasks.lock().await;
+        let mut recovered = Vec::with_capacity(unfinished.len());
+
+        for stored in unfinished {
+            if tasks.contains_key(&stored.task.id) {
+                continue;
+            }
+            let mut a2a_task = stored.task;
+            a2a_task.update_status(
+                TaskState::Working,
+                Some(Message::agent_text(
+                    "Resuming after server restart".into(),
+                    Uuid::new_v4().to_string(),
+                )),
+            );
+
+            let mut active =
+                ActiveTask::new(a2a_task, &stored.owner, &stored.skill_id, &stored.task_text);
+            active.created_at = stored.created_at;
+            self.store.save(&active.stored())?;
+
+            recovered.push(AcceptedTask {
+                task: active.a2a_task.clone(),
+                skill_id: stored.skill_id,
+                task_text: stored.task_text,
+            });
+            tasks.insert(active.a2a_task.id.clone(), active);
+        }
+        Ok(recovered)
+    }
+
+    /// Delete finished tasks from the store that `policy` no longer keeps.
+    pub fn purge_tasks(&self, policy: &RetentionPolicy) -> Result<usize, A2aError> {
+        self.store.purge(policy, Utc::now())
+    }
+
+    /// Evict a task from memory.
     ///
     /// Used for deferred cleanup of completed/failed tasks to prevent
-    /// unbounded memory growth.
+    /// unbounded memory growth; they stay available from the task store.
     pub async fn remove_task(&self, task_id: &str) {
         self.active_tasks.lock().await.remove(task_id);
     }
@@ -292,6 +484,19 @@ impl<E: AiExecutor + 'static> HiveTaskHandler<E> {
             .map(|active| active.event_tx.subscribe())
             .ok_or_else(|| A2aError::TaskNotFound(task_id.to_string()))
     }
+
+    /// Write a task's latest state to the store. The in-memory task stays
+    /// authoritative while it is held, so a failed write is only logged.
+    fn persist(&self, active: &ActiveTask) {
+        if let Err(e) = self.store.save(&active.stored()) {
+            tracing::warn!("Failed to persist A2A task {}: {e}", active.a2a_task.id);
+        }
+    }
+}
+
+/// Returns `true` once a task can no longer change state.
+pub fn task_is_final(state: &TaskState) -> bool {
+    !matches!(state, TaskState::Working | TaskState::Submitted)
 }
 
 // ---------------------------------------------------------------------------
@@ -669,6 +874,141 @@ mod tests {
         assert!(artifact.artifact_id.starts_with("single-"));
     }
 
+    struct SlowExecutor;
+
+    impl AiExecutor for SlowExecutor {
+        async fn execute(&self, request: &ChatRequest) -> Result<ChatResponse, String> {
+            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+            MockExecutor.execute(request).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_task_stops_running_orchestration() {
+        let handler = HiveTaskHandler::new(Arc::new(SlowExecutor), ServerDefaults::default());
+        let message = a2a_rs::Message::user_text("Hello".into(), Uuid::new_v4().to_string());
+        let accepted = handler
+            .accept_message(ANONYMOUS_CLIENT, "task-cancel", &message)
+            .await
+            .unwrap();
+        let mut events = handler.subscribe("task-cancel").await.unwrap();
+
+        let (ran, canceled) = tokio::join!(handler.run_accepted(accepted), async {
+            tokio::task::yield_now().await;
+            handler.cancel_task("task-cancel").await
+        });
+
+        assert_eq!(canceled.unwrap().status.state, TaskState::Canceled);
+        assert_eq!(ran.unwrap().status.state, TaskState::Canceled);
+
+        let update = events.recv().await.unwrap();
+        assert!(update.final_);
+        assert_eq!(update.status.state, TaskState::Canceled);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_finished_task_rejected() {
+        let handler = HiveTaskHandler::new(Arc::new(MockExecutor), ServerDefaults::default());
+        let message = a2a_rs::Message::user_text("Hello".into(), Uuid::new_v4().to_string());
+        handler.handle_message("task-done", &message).await.unwrap();
+
+        match handler.cancel_task("task-done").await.unwrap_err() {
+            A2aError::NotCancelable(_) => {}
+            other => panic!("Expected NotCancelable, got: {:?}", other),
+        }
+        assert!(matches!(
+            handler.cancel_task("missing").await.unwrap_err(),
+            A2aError::TaskNotFound(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_finished_task_served_from_store_after_eviction() {
+        let handler = HiveTaskHandler::new(Arc::new(MockExecutor), ServerDefaults::default());
+        let message = a2a_rs::Message::user_text("Hello".into(), Uuid::new_v4().to_string());
+        handler
+            .handle_message("task-evict", &message)
+            .await
+            .unwrap();
+        handler.remove_task("task-evict").await;
+
+        let task = handler.get_task("task-evict").await.unwrap();
+        assert_eq!(task.status.state, TaskState::Completed);
+        assert!(task.artifacts.is_some());
+
+        let states: Vec<TaskState> = handler
+            .status_history("task-evict")
+            .unwrap()
+            .into_iter()
+            .map(|status| status.state)
+            .collect();
+        assert_eq!(states, vec![TaskState::Working, TaskState::Completed]);
+
+        assert!(matches!(
+            handler.cancel_task("task-evict").await.unwrap_err(),
+            A2aError::NotCancelable(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_recover_tasks_resumes_interrupted_work() {
+        let store: Arc<dyn TaskStore> =
+            Arc::new(crate::store::SqliteTaskStore::open_in_memory().unwrap());
+        let message = a2a_rs::Message::user_text("Hello".into(), Uuid::new_v4().to_string());
+
+        // Accepted but never run: the server "stopped" mid-task.
+        {
+            let handler = HiveTaskHandler::new(Arc::new(MockExecutor), ServerDefaults::default())
+                .with_store(store.clone());
+            handler
+                .accept_message("client-a", "task-resume", &message)
+                .await
+                .unwrap();
+        }
+
+        let handler = HiveTaskHandler::new(Arc::new(MockExecutor), ServerDefaults::default())
+            .with_store(store.clone());
+        let recovered = handler.recover_tasks().await.unwrap();
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].task.status.state, TaskState::Working);
+
+        let task = handler
+            .run_accepted(recovered.into_iter().next().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(task.status.state, TaskState::Completed);
+        assert!(store.unfinished().unwrap().is_empty());
+        assert!(handler.recover_tasks().await.unwrap().is_empty());
+
+        let page = handler
+            .list_tasks("client-a", &TaskListQuery::default())
+            .unwrap();
+        assert_eq!(page.tasks.len(), 1);
+        assert!(handler
+            .list_tasks("client-b", &TaskListQuery::default())
+            .unwrap()
+            .tasks
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_accept_rejects_task_id_owned_by_other_client() {
+        let handler = HiveTaskHandler::new(Arc::new(MockExecutor), ServerDefaults::default());
+        let message = a2a_rs::Message::user_text("Hello".into(), Uuid::new_v4().to_string());
+        handler
+            .accept_message("client-a", "task-owned", &message)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            handler
+                .accept_message("client-b", "task-owned", &message)
+                .await
+                .err(),
+            Some(A2aError::Security(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_handle_message_preserves_history() {
         let handler = HiveTaskHandler::new(Arc::new(MockExecutor), ServerDefaults::default());
@@ -280,3 +280,108 @@ async fn test_task_events_endpoint_streams_completed_snapshot_over_http() {
     assert!(body.contains("events-http-task"));
     assert!(body.contains("completed"));
 }
+
+#[tokio::test]
+async fn test_a2a_rs_client_interop() {
+    use a2a_rs::application::{A2ARequest, SendMessageRequest};
+    use a2a_rs::services::AsyncA2AClient;
+    use a2a_rs::HttpClient;
+
+    let base = spawn_server(A2aConfig::default()).await;
+    let client = HttpClient::new(format!("{}/a2a", base));
+    let message =
+        a2a_rs::Message::user_text("Hello from a2a-rs".into(), uuid::Uuid::new_v4().to_string());
+
+    // `send_task_message` speaks the legacy `tasks/send`; drive `message/send` instead.
+    let request = A2ARequest::SendMessage(SendMessageRequest::new(a2a_rs::MessageSendParams {
+        message,
+        configuration: None,
+        metadata: None,
+    }));
+    let response = client.send_request(&request).await.unwrap();
+    assert!(response.error.is_none());
+    let task: a2a_rs::Task = serde_json::from_value(response.result.unwrap()).unwrap();
+    assert_eq!(task.status.state, a2a_rs::TaskState::Completed);
+
+    let fetched = client.get_task(&task.id, Some(1)).await.unwrap();
+    assert_eq!(fetched.id, task.id);
+    assert_eq!(fetched.history.unwrap().len(), 1);
+
+    // Finished tasks cannot be canceled.
+    assert!(client.cancel_task(&task.id).await.is_err());
+}
+
+#[tokio::test]
+async fn test_push_notification_delivered_for_non_blocking_send() {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<(axum::http::HeaderMap, String)>();
+    let webhook = axum::Router::new().route(
+        "/hook",
+        axum::routing::post(move |headers: axum::http::HeaderMap, body: String| {
+            let tx = tx.clone();
+            async move {
+                let _ = tx.send((headers, body));
+                axum::http::StatusCode::OK
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let hook_url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, webhook).await.unwrap();
+    });
+
+    // The webhook listens on loopback, which a default server refuses.
+    let router = hive_a2a::server::build_router_with_push(
+        A2aConfig::default(),
+        Some(task_handler()),
+        Arc::new(hive_a2a::PushNotifier::new().allow_private_targets()),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+
+    let resp: serde_json::Value = reqwest::Client::new()
+        .post(format!("{}/a2a", base))
+        .json(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": "push-1",
+            "method": "message/send",
+            "params": {
+                "message": {
+                    "role": "user",
+                    "parts": [{ "kind": "text", "text": "notify me" }],
+                    "messageId": "msg-push",
+                    "taskId": "push-task",
+                    "kind": "message"
+                },
+                "configuration": {
+                    "acceptedOutputModes": ["text"],
+                    "blocking": false,
+                    "pushNotificationConfig": { "url": hook_url, "token": "hook-secret" }
+                }
+            }
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(resp["result"]["status"]["state"], "working");
+
+    let (headers, body) = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    let task: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(task["id"], "push-task");
+    assert_eq!(task["status"]["state"], "completed");
+    let signature = headers[hive_a2a::push::SIGNATURE_HEADER].to_str().unwrap();
+    assert!(signature.starts_with("sha256="));
+    // The client's token stays between the client and the server.
+    assert!(headers
+        .values()
+        .all(|value| !value.to_str().unwrap_or_default().contains("hook-secret")));
+}
@@ -1,6 +1,7 @@
 use anyhow::{anyhow, Context};
 use chrono::{DateTime, Utc};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 pub struct ApiClient {
     pub server_url: String,
@@ -77,6 +78,47 @@ pub struct RelayStats {
     pub rooms: Vec<RelayRoom>,
 }
 
+/// Relay counters scraped from the server's Prometheus `/metrics` endpoint.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RelayMetrics {
+    pub connections: u64,
+    pub frames_total: u64,
+    pub bytes_forwarded: u64,
+    pub quota_rejections: u64,
+    pub rooms_expired: u64,
+    pub backplane_messages: u64,
+}
+
+impl RelayMetrics {
+    /// Pick the relay series out of Prometheus text, summing across labels.
+    pub fn from_prometheus(text: &str) -> Self {
+        let mut totals: BTreeMap<&str, f64> = BTreeMap::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((series, value)) = line.rsplit_once(' ') else {
+                continue;
+            };
+            let Ok(value) = value.parse::<f64>() else {
+                continue;
+            };
+            let name = series.split('{').next().unwrap_or(series);
+            *totals.entry(name).or_default() += value;
+        }
+
+        let get = |name: &str| totals.get(name).copied().unwrap_or_default() as u64;
+        Self {
+            connections: get("hive_relay_connections"),
+            frames_total: get("hive_relay_frames_total"),
+            bytes_forwarded: get("hive_relay_bytes_forwarded_total"),
+            quota_rejections: get("hive_relay_quota_rejections_total"),
+            rooms_expired: get("hive_relay_rooms_expired_total"),
+            backplane_messages: get("hive_relay_backplane_messages_total"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncBlobRecord {
     pub user_email: String,
@@ -145,6 +187,23 @@ impl ApiClient {
         self.get_json("relay").await
     }
 
+    pub async fn fetch_relay_metrics(&self) -> anyhow::Result<RelayMetrics> {
+        let url = format!("{}/metrics", self.server_url);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("failed to fetch {url}"))?;
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+
+        if !status.is_success() {
+            return Err(anyhow!("request to {url} failed ({status}): {body}"));
+        }
+        Ok(RelayMetrics::from_prometheus(&body))
+    }
+
     pub async fn fetch_sync(&self) -> anyhow::Result<SyncStats> {
         self.get_json("sync").await
     }
@@ -242,6 +301,17 @@ mod tests {
                     }
                 }),
             )
+            .route(
+                "/metrics",
+                get(|| async {
+                    "# TYPE hive_relay_connections gauge\n\
+                     hive_relay_connections 3\n\
+                     hive_relay_frames_total{type=\"forward\"} 10\n\
+                     hive_relay_frames_total{type=\"ping\"} 5\n\
+                     hive_relay_bytes_forwarded_total 2048\n\
+                     hive_relay_quota_rejections_total 1\n"
+                }),
+            )
             .route(
                 "/admin/sync",
                 get({
@@ -279,6 +349,12 @@ mod tests {
             api.fetch_relay().await.unwrap().active_rooms,
             relay.active_rooms
         );
+        let metrics = api.fetch_relay_metrics().await.unwrap();
+        assert_eq!(metrics.connections, 3);
+        assert_eq!(metrics.frames_total, 15);
+        assert_eq!(metrics.bytes_forwarded, 2048);
+        assert_eq!(metrics.quota_rejections, 1);
+        assert_eq!(metrics.rooms_expired, 0);
         assert_eq!(
             api.fetch_sync().await.unwrap().total_blobs,
             sync.total_blobs
@@ -1,5 +1,6 @@
 use crate::api::{
-    ApiClient, DashboardStats, GatewayStats, RelayStats, SyncStats, TeamRecord, UserRecord,
+    ApiClient, DashboardStats, GatewayStats, RelayMetrics, RelayStats, SyncStats, TeamRecord,
+    UserRecord,
 };
 use ratatui::widgets::TableState;
 
@@ -44,6 +45,7 @@ pub struct App {
     pub users: Vec<UserRecord>,
     pub gateway: Option<GatewayStats>,
     pub relay: Option<RelayStats>,
+    pub relay_metrics: Option<RelayMetrics>,
     pub sync_stats: Option<SyncStats>,
     pub teams: Vec<TeamRecord>,
     api: ApiClient,
@@ -60,6 +62,7 @@ impl App {
             users: Vec::new(),
             gateway: None,
             relay: None,
+            relay_metrics: None,
             sync_stats: None,
             teams: Vec::new(),
             api,
@@ -79,6 +82,9 @@ impl App {
         if let Ok(r) = self.api.fetch_relay().await {
             self.relay = Some(r);
         }
+        if let Ok(m) = self.api.fetch_relay_metrics().await {
+            self.relay_metrics = Some(m);
+        }
         if let Ok(s) = self.api.fetch_sync().await {
             self.sync_stats = Some(s);
         }
@@ -33,16 +33,26 @@ pub fn draw(frame: &mut Frame, app: &mut App, area: Rect) {
         .constraints([Constraint::Length(3), Constraint::Min(0)])
         .split(area);
 
-    let summary = Paragraph::new(format!(
+    let mut summary_text = format!(
         "Active Rooms: {}  |  Connected Devices: {}",
         stats.active_rooms, stats.connected_devices
-    ))
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title(" Relay Status "),
-    )
-    .style(Style::default().fg(CYAN));
+    );
+    if let Some(metrics) = &app.relay_metrics {
+        summary_text.push_str(&format!(
+            "  |  Connections: {}  |  Forwarded: {}  |  Quota Rejections: {}  |  Expired: {}",
+            metrics.connections,
+            format_bytes(metrics.bytes_forwarded),
+            metrics.quota_rejections,
+            metrics.rooms_expired
+        ));
+    }
+    let summary = Paragraph::new(summary_text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Relay Status "),
+        )
+        .style(Style::default().fg(CYAN));
     frame.render_widget(summary, chunks[0]);
 
     let header = Row::new(vec![
@@ -6,8 +6,15 @@ use tokio::sync::oneshot;
 use uuid::Uuid;
 
 use super::OperationType;
+use super::policy::{ApprovalPolicy, PolicyContext, PolicyDecision, PolicyOutcome};
 use super::rules::ApprovalRule;
 
+/// Approver identity for decisions made by the user at the desktop app.
+pub const LOCAL_APPROVER: &str = "local";
+
+/// Approver identity for decisions Hive makes itself (timeouts, hard denies).
+pub const SYSTEM_APPROVER: &str = "system";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApprovalRequest {
     pub id: String,
@@ -18,6 +25,28 @@ pub struct ApprovalRequest {
     pub matched_rule: String,
     pub estimated_cost: Option<f64>,
     pub timeout_secs: Option<u64>,
+    /// Distinct approvals needed before the operation may proceed.
+    #[serde(default = "default_required_approvals")]
+    pub required_approvals: u32,
+    /// Approvers who have signed off so far.
+    #[serde(default)]
+    pub approvals: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Set when a policy denied the operation outright. Such requests are
+    /// never pending; their receiver already holds the `Denied` decision.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub denial: Option<String>,
+}
+
+fn default_required_approvals() -> u32 {
+    1
+}
+
+impl ApprovalRequest {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| now >= at)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -27,8 +56,24 @@ pub enum ApprovalDecision {
     Timeout,
 }
 
+/// Result of recording one approval with [`ApprovalGate::approve`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApprovalProgress {
+    /// Enough distinct approvers signed off; the operation was released.
+    Granted,
+    /// Still waiting on this many more approvers.
+    Waiting { remaining: u32 },
+    /// The request expired before it was fully approved.
+    Expired,
+    /// A denial or timeout resolved the request.
+    Rejected,
+    /// No pending request with that id.
+    Unknown,
+}
+
 pub struct ApprovalGate {
     rules: Vec<ApprovalRule>,
+    policy: Option<ApprovalPolicy>,
     pending: Mutex<HashMap<String, ApprovalRequest>>,
     response_channels: Mutex<HashMap<String, oneshot::Sender<ApprovalDecision>>>,
 }
@@ -38,6 +83,7 @@ impl std::fmt::Debug for ApprovalGate {
         let pending_count = self.pending.lock().map(|p| p.len()).unwrap_or(0);
         f.debug_struct("ApprovalGate")
             .field("rules", &self.rules.len())
+            .field("policy_rules", &self.policy.as_ref().map(|p| p.rules.len()))
             .field("pending", &pending_count)
             .finish()
     }
@@ -48,34 +94,103 @@ impl ApprovalGate {
         rules.sort_by(|a, b| b.priority.cmp(&a.priority));
         Self {
             rules,
+            policy: None,
             pending: Mutex::new(HashMap::new()),
             response_channels: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Attach a declarative policy. Policy rules are consulted first; the
+    /// built-in rules still apply to operations no policy rule matches, and
+    /// the policy default covers everything else.
+    pub fn with_policy(mut self, policy: ApprovalPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    pub fn policy(&self) -> Option<&ApprovalPolicy> {
+        self.policy.as_ref()
+    }
+
+    /// Decide what should happen to an operation without registering a request.
+    pub fn evaluate(&self, ctx: &PolicyContext) -> PolicyDecision {
+        if let Some(policy) = &self.policy {
+            let decision = policy.evaluate(ctx);
+            if decision.rule.is_some() {
+                return decision;
+            }
+        }
+        if let Some(rule) = self.rules.iter().find(|r| r.matches(&ctx.operation)) {
+            return PolicyDecision {
+                outcome: PolicyOutcome::RequireApproval,
+                rule: Some(rule.name.clone()),
+                expires_after_secs: self.policy.as_ref().and_then(|p| p.approval_ttl_secs),
+            };
+        }
+        self.policy
+            .as_ref()
+            .map(|p| p.evaluate(ctx))
+            .unwrap_or(PolicyDecision {
+                outcome: PolicyOutcome::Allow,
+                rule: None,
+                expires_after_secs: None,
+            })
+    }
+
     pub fn check_with_channel(
         &self,
         agent_id: &str,
         operation: &OperationType,
     ) -> Option<(ApprovalRequest, oneshot::Receiver<ApprovalDecision>)> {
-        let matched_rule = self.rules.iter().find(|r| r.matches(operation))?;
+        self.check_with_context(&PolicyContext::new(agent_id, operation.clone()))
+    }
 
+    /// Evaluate an operation with full policy context. Returns `None` when the
+    /// operation is allowed. A denied operation returns a request whose
+    /// `denial` is set and whose receiver already resolved to `Denied`.
+    pub fn check_with_context(
+        &self,
+        ctx: &PolicyContext,
+    ) -> Option<(ApprovalRequest, oneshot::Receiver<ApprovalDecision>)> {
+        let decision = self.evaluate(ctx);
+        if decision.outcome == PolicyOutcome::Allow {
+            return None;
+        }
+
+        let operation = &ctx.operation;
+        let now = Utc::now();
+        let matched_rule = decision.rule.unwrap_or_else(|| "policy-default".into());
+        let denial = (decision.outcome == PolicyOutcome::Deny)
+            .then(|| format!("Denied by approval policy rule '{matched_rule}'"));
         let request = ApprovalRequest {
             id: Uuid::new_v4().to_string(),
-            agent_id: agent_id.into(),
-            timestamp: Utc::now(),
+            agent_id: ctx.agent_id.clone(),
+            timestamp: now,
             operation: operation.clone(),
             context: format!("{operation:?}"),
-            matched_rule: matched_rule.name.clone(),
+            matched_rule,
             estimated_cost: match operation {
                 OperationType::AiCall { estimated_cost, .. } => Some(*estimated_cost),
                 _ => None,
             },
-            timeout_secs: Some(300),
+            timeout_secs: Some(decision.expires_after_secs.unwrap_or(300)),
+            required_approvals: decision.outcome.required_approvals().unwrap_or(1),
+            approvals: Vec::new(),
+            expires_at: decision
+                .expires_after_secs
+                .map(|secs| now + chrono::Duration::seconds(secs as i64)),
+            denial,
         };
 
         let (tx, rx) = oneshot::channel();
 
+        if let Some(reason) = &request.denial {
+            let _ = tx.send(ApprovalDecision::Denied {
+                reason: Some(reason.clone()),
+            });
+            return Some((request, rx));
+        }
+
         self.pending
             .lock()
             .unwrap()
@@ -93,8 +208,79 @@ impl ApprovalGate {
             .map(|(req, _rx)| req)
     }
 
-    pub fn respond(&self, request_id: &str, decision: ApprovalDecision) {
-        self.pending.lock().unwrap().remove(request_id);
+    /// Record an approval from `approver`. Repeat approvals by the same
+    /// approver count once; the operation is released once the request has
+    /// `required_approvals` distinct approvers.
+    pub fn approve(&self, request_id: &str, approver: &str) -> ApprovalProgress {
+        let mut pending = self.pending.lock().unwrap();
+        let Some(request) = pending.get_mut(request_id) else {
+            return ApprovalProgress::Unknown;
+        };
+
+        if request.is_expired(Utc::now()) {
+            pending.remove(request_id);
+            drop(pending);
+            self.resolve(request_id, ApprovalDecision::Timeout);
+            return ApprovalProgress::Expired;
+        }
+
+        if !request.approvals.iter().any(|a| a == approver) {
+            request.approvals.push(approver.to_string());
+        }
+        let approved = request.approvals.len() as u32;
+        if approved < request.required_approvals {
+            return ApprovalProgress::Waiting {
+                remaining: request.required_approvals - approved,
+            };
+        }
+
+        pending.remove(request_id);
+        drop(pending);
+        self.resolve(request_id, ApprovalDecision::Approved);
+        ApprovalProgress::Granted
+    }
+
+    /// Apply `approver`'s decision. `Approved` counts as one approval toward
+    /// the request's quorum (see [`approve`](Self::approve)); a denial or
+    /// timeout resolves the request at once.
+    pub fn respond(
+        &self,
+        request_id: &str,
+        approver: &str,
+        decision: ApprovalDecision,
+    ) -> ApprovalProgress {
+        if decision == ApprovalDecision::Approved {
+            return self.approve(request_id, approver);
+        }
+        if self.pending.lock().unwrap().remove(request_id).is_none() {
+            return ApprovalProgress::Unknown;
+        }
+        self.resolve(request_id, decision);
+        ApprovalProgress::Rejected
+    }
+
+    /// Time out every pending request past its expiry. Returns their ids.
+    pub fn expire_stale(&self) -> Vec<String> {
+        let now = Utc::now();
+        let expired: Vec<String> = {
+            let mut pending = self.pending.lock().unwrap();
+            let ids: Vec<String> = pending
+                .values()
+                .filter(|r| r.is_expired(now))
+                .map(|r| r.id.clone())
+                .collect();
+            for id in &ids {
+                pending.remove(id);
+            }
+            ids
+        };
+        for id in &expired {
+            self.resolve(id, ApprovalDecision::Timeout);
+        }
+        expired
+    }
+
+    fn resolve(&self, request_id: &str, decision: ApprovalDecision) {
         if let Some(tx) = self.response_channels.lock().unwrap().remove(request_id) {
             let _ = tx.send(decision);
         }
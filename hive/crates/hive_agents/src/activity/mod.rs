@@ -2,13 +2,21 @@ pub mod approval;
 pub mod budget;
 pub mod log;
 pub mod notification;
+pub mod policy;
 pub mod rules;
 pub mod types;
 
-pub use approval::{ApprovalDecision, ApprovalGate, ApprovalRequest};
+pub use approval::{
+    ApprovalDecision, ApprovalGate, ApprovalProgress, ApprovalRequest, LOCAL_APPROVER,
+    SYSTEM_APPROVER,
+};
 pub use budget::{BudgetConfig, BudgetDecision, BudgetEnforcer, ExhaustAction};
 pub use log::{ActivityEntry, ActivityFilter, ActivityLog, CostSummary};
 pub use notification::{Notification, NotificationKind, NotificationService};
+pub use policy::{
+    ApprovalPolicy, OperationKind, PolicyCondition, PolicyContext, PolicyDecision, PolicyOutcome,
+    PolicyRule, PolicyTestCase, PolicyTestResult, TimeWindow,
+};
 pub use rules::{ApprovalRule, RuleTrigger};
 use std::sync::Arc;
 use tokio::sync::broadcast;
@@ -51,6 +59,34 @@ impl ActivityService {
         Self { tx }
     }
 
+    /// Mirror approval decisions and tool executions into a tamper-evident
+    /// audit log. Other event categories are not audited.
+    pub fn attach_audit_log(&self, audit: Arc<hive_core::AuditLog>) {
+        let mut rx = self.tx.subscribe();
+        spawn_background(async move {
+            loop {
+                let record = match rx.recv().await {
+                    Ok(event) => to_audit_event(&event),
+                    // A gap in the audit trail must itself be on the record.
+                    Err(broadcast::error::RecvError::Lagged(missed)) => Some(
+                        hive_core::AuditEvent::new(
+                            hive_core::AuditCategory::Security,
+                            "audit_events_dropped",
+                        )
+                        .actor("activity_service")
+                        .details(serde_json::json!({ "missed": missed })),
+                    ),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if let Some(record) = record
+                    && let Err(e) = audit.append(record)
+                {
+                    tracing::warn!("AuditLog write failed: {e}");
+                }
+            }
+        });
+    }
+
     /// Emit an event to all listeners. Fire-and-forget.
     pub fn emit(&self, event: ActivityEvent) {
         // Ignore send errors (no receivers = that's fine)
@@ -95,6 +131,34 @@ where
     }
 }
 
+/// Map an activity event onto an audit record, for the categories that are
+/// audited (approvals and tool/shell/file execution).
+pub fn to_audit_event(event: &ActivityEvent) -> Option<hive_core::AuditEvent> {
+    use hive_core::{AuditCategory, AuditEvent};
+
+    let category = match event.category() {
+        "approval" => AuditCategory::Approval,
+        "tool" => AuditCategory::ToolExecution,
+        _ => return None,
+    };
+    let resource = match event {
+        ActivityEvent::ApprovalRequested { request_id, .. }
+        | ActivityEvent::ApprovalGranted { request_id }
+        | ActivityEvent::ApprovalDenied { request_id, .. } => request_id.clone(),
+        ActivityEvent::ToolCalled { tool_name, .. } => tool_name.clone(),
+        ActivityEvent::FileModified { path, .. } => path.clone(),
+        ActivityEvent::ShellExecuted { command, .. } => command.clone(),
+        _ => String::new(),
+    };
+
+    Some(
+        AuditEvent::new(category, event.event_type())
+            .actor(event.agent_id().unwrap_or("user"))
+            .resource(resource)
+            .details(serde_json::to_value(event).unwrap_or_default()),
+    )
+}
+
 fn translate_task_event(event: TaskEvent) -> Option<ActivityEvent> {
     match event {
         TaskEvent::TaskStarted {
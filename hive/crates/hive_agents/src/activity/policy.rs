@@ -0,0 +1,627 @@
+//! Declarative approval policies.
+//!
+//! A policy file (TOML or YAML) is an ordered set of rules. Each rule pairs a
+//! `when` condition with an outcome: allow, deny, require approval, or require
+//! approval from N distinct approvers. Conditions compose with `all`, `any`
+//! and `not`, and match on agent role, persona, model, workspace, time window
+//! and operation type. Policy files may embed `[[test]]` cases, which
+//! `hive policy test` evaluates so that policy changes can be reviewed like
+//! any other code.
+//!
+//! ```toml
+//! default = "allow"
+//! approval_ttl_secs = 900
+//!
+//! [[rule]]
+//! name = "no-prod-push-after-hours"
+//! priority = 100
+//! outcome = "deny"
+//! [rule.when]
+//! operation = "git_push"
+//! workspace = "*prod*"
+//! not = { time = { days = ["mon", "tue", "wed", "thu", "fri"], from = "09:00", to = "18:00" } }
+//!
+//! [[rule]]
+//! name = "expensive-models"
+//! outcome = { require_approvers = 2 }
+//! expires_after_secs = 600
+//! [rule.when]
+//! any = [{ model = "*opus*" }, { cost_above = 5.0 }]
+//! ```
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::OperationType;
+use super::rules::{glob_match, scope_file_count, security_gateway_blocks};
+
+// ---------------------------------------------------------------------------
+// Outcomes
+// ---------------------------------------------------------------------------
+
+/// What a policy decides for an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyOutcome {
+    #[default]
+    Allow,
+    Deny,
+    RequireApproval,
+    /// Approval from this many distinct approvers.
+    RequireApprovers(u32),
+}
+
+impl PolicyOutcome {
+    /// Number of distinct approvals needed, or `None` when no approval
+    /// round-trip happens (allow / deny).
+    pub fn required_approvals(&self) -> Option<u32> {
+        match self {
+            Self::Allow | Self::Deny => None,
+            Self::RequireApproval => Some(1),
+            Self::RequireApprovers(n) => Some((*n).max(1)),
+        }
+    }
+}
+
+impl std::fmt::Display for PolicyOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allow"),
+            Self::Deny => write!(f, "deny"),
+            Self::RequireApproval => write!(f, "require_approval"),
+            Self::RequireApprovers(n) => write!(f, "require_approvers({n})"),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Conditions
+// ---------------------------------------------------------------------------
+
+/// Coarse operation type, as written in policy files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    ShellCommand,
+    FileDelete,
+    FileModify,
+    GitPush,
+    AiCall,
+    Custom,
+}
+
+impl OperationKind {
+    pub fn of(op: &OperationType) -> Self {
+        match op {
+            OperationType::ShellCommand(_) => Self::ShellCommand,
+            OperationType::FileDelete(_) => Self::FileDelete,
+            OperationType::FileModify { .. } => Self::FileModify,
+            OperationType::GitPush { .. } => Self::GitPush,
+            OperationType::AiCall { .. } => Self::AiCall,
+            OperationType::Custom(_) => Self::Custom,
+        }
+    }
+}
+
+/// A recurring time window in the host's local time.
+///
+/// `days` restricts the window to the given weekdays (any day when empty).
+/// `from`/`to` are `HH:MM`; a window whose `to` is earlier than `from` wraps
+/// past midnight. Either bound may be omitted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimeWindow {
+    #[serde(deserialize_with = "one_or_many")]
+    pub days: Vec<Weekday>,
+    #[serde(deserialize_with = "clock_time")]
+    pub from: Option<NaiveTime>,
+    #[serde(deserialize_with = "clock_time")]
+    pub to: Option<NaiveTime>,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        if !self.days.is_empty() && !self.days.contains(&at.weekday()) {
+            return false;
+        }
+        let t = at.time();
+        match (self.from, self.to) {
+            (None, None) => true,
+            (Some(from), None) => t >= from,
+            (None, Some(to)) => t < to,
+            (Some(from), Some(to)) if from <= to => t >= from && t < to,
+            (Some(from), Some(to)) => t >= from || t < to,
+        }
+    }
+}
+
+/// A policy condition. Every field that is set must hold; a condition with
+/// nothing set matches everything.
+///
+/// String matchers accept a single glob or a list of globs (`*` wildcard,
+/// case-insensitive) and match when any of them does.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolicyCondition {
+    /// Every sub-condition must match.
+    pub all: Vec<PolicyCondition>,
+    /// At least one sub-condition must match (ignored when empty).
+    pub any: Vec<PolicyCondition>,
+    /// The sub-condition must not match.
+    pub not: Option<Box<PolicyCondition>>,
+
+    #[serde(deserialize_with = "one_or_many")]
+    pub agent: Vec<String>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub role: Vec<String>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub persona: Vec<String>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub model: Vec<String>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub workspace: Vec<String>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub operation: Vec<OperationKind>,
+
+    /// Shell command text (git pushes match as `git push <remote> <branch>`).
+    #[serde(deserialize_with = "one_or_many")]
+    pub command: Vec<String>,
+    /// Path of a modified or deleted file.
+    #[serde(deserialize_with = "one_or_many")]
+    pub path: Vec<String>,
+    /// Estimated cost of an AI call, in USD.
+    pub cost_above: Option<f64>,
+    /// File count of a bulk modification.
+    pub files_above: Option<usize>,
+    pub time: Option<TimeWindow>,
+    /// Whether `hive_core::SecurityGateway` refuses the operation.
+    pub security_gateway_block: Option<bool>,
+}
+
+impl PolicyCondition {
+    pub fn matches(&self, ctx: &PolicyContext) -> bool {
+        let op = &ctx.operation;
+
+        self.all.iter().all(|c| c.matches(ctx))
+            && (self.any.is_empty() || self.any.iter().any(|c| c.matches(ctx)))
+            && self.not.as_ref().is_none_or(|c| !c.matches(ctx))
+            && globs_match(&self.agent, Some(&ctx.agent_id))
+            && globs_match(&self.role, ctx.role.as_deref())
+            && globs_match(&self.persona, ctx.persona.as_deref())
+            && globs_match(&self.model, ctx.model())
+            && globs_match(&self.workspace, ctx.workspace.as_deref())
+            && (self.operation.is_empty() || self.operation.contains(&OperationKind::of(op)))
+            && globs_match(&self.command, command_text(op).as_deref())
+            && globs_match(&self.path, op_path(op))
+            && self.cost_above.is_none_or(|usd| {
+                matches!(op, OperationType::AiCall { estimated_cost, .. } if *estimated_cost > usd)
+            })
+            && self.files_above.is_none_or(|count| {
+                matches!(op, OperationType::FileModify { scope, .. }
+                    if scope_file_count(scope).is_some_and(|n| n > count))
+            })
+            && self.time.as_ref().is_none_or(|w| w.contains(ctx.at))
+            && self
+                .security_gateway_block
+                .is_none_or(|expected| security_gateway_blocks(op) == expected)
+    }
+}
+
+/// An empty pattern list always matches; otherwise the value must be present
+/// and match at least one pattern.
+fn globs_match(patterns: &[String], value: Option<&str>) -> bool {
+    if patterns.is_empty() {
+        return true;
+    }
+    let Some(value) = value else {
+        return false;
+    };
+    let value = value.to_lowercase();
+    patterns
+        .iter()
+        .any(|p| glob_match(&p.to_lowercase(), &value))
+}
+
+fn command_text(op: &OperationType) -> Option<String> {
+    match op {
+        OperationType::ShellCommand(cmd) | OperationType::Custom(cmd) => Some(cmd.clone()),
+        OperationType::GitPush { remote, branch } => Some(format!("git push {remote} {branch}")),
+        _ => None,
+    }
+}
+
+fn op_path(op: &OperationType) -> Option<&str> {
+    match op {
+        OperationType::FileModify { path, .. } | OperationType::FileDelete(path) => Some(path),
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Context & decisions
+// ---------------------------------------------------------------------------
+
+/// Everything a policy can look at when deciding on an operation.
+#[derive(Debug, Clone)]
+pub struct PolicyContext {
+    pub agent_id: String,
+    pub role: Option<String>,
+    pub persona: Option<String>,
+    pub model: Option<String>,
+    pub workspace: Option<String>,
+    pub operation: OperationType,
+    /// Local wall-clock time of the operation.
+    pub at: NaiveDateTime,
+}
+
+impl PolicyContext {
+    pub fn new(agent_id: impl Into<String>, operation: OperationType) -> Self {
+        Self {
+            agent_id: agent_id.into(),
+            role: None,
+            persona: None,
+            model: None,
+            workspace: None,
+            operation,
+            at: chrono::Local::now().naive_local(),
+        }
+    }
+
+    pub fn with_role(mut self, role: impl Into<String>) -> Self {
+        self.role = Some(role.into());
+        self
+    }
+
+    pub fn with_persona(mut self, persona: impl Into<String>) -> Self {
+        self.persona = Some(persona.into());
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn with_workspace(mut self, workspace: impl Into<String>) -> Self {
+        self.workspace = Some(workspace.into());
+        self
+    }
+
+    pub fn at(mut self, at: NaiveDateTime) -> Self {
+        self.at = at;
+        self
+    }
+
+    /// The model in play: the explicit one, or the model of an AI call.
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref().or(match &self.operation {
+            OperationType::AiCall { model, .. } => Some(model.as_str()),
+            _ => None,
+        })
+    }
+}
+
+/// Result of evaluating a policy.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PolicyDecision {
+    pub outcome: PolicyOutcome,
+    /// Name of the deciding rule; `None` when the policy default applied.
+    pub rule: Option<String>,
+    /// How long an approval request stays open before it expires.
+    pub expires_after_secs: Option<u64>,
+}
+
+// ---------------------------------------------------------------------------
+// Policy
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyRule {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Higher priority wins when several rules match; ties go to the rule
+    /// listed first.
+    #[serde(default)]
+    pub priority: u8,
+    #[serde(default)]
+    pub when: PolicyCondition,
+    pub outcome: PolicyOutcome,
+    /// Overrides the policy-wide `approval_ttl_secs` for this rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_after_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApprovalPolicy {
+    /// Outcome when no rule matches.
+    #[serde(default)]
+    pub default: PolicyOutcome,
+    /// Default lifetime of approval requests; `None` means they never expire.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_ttl_secs: Option<u64>,
+    #[serde(default, rename = "rule", alias = "rules")]
+    pub rules: Vec<PolicyRule>,
+    #[serde(
+        default,
+        rename = "test",
+        alias = "tests",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub tests: Vec<PolicyTestCase>,
+}
+
+impl ApprovalPolicy {
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let policy: Self = toml::from_str(content).context("Failed to parse policy TOML")?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    pub fn from_yaml(content: &str) -> Result<Self> {
+        let policy: Self = serde_yaml::from_str(content).context("Failed to parse policy YAML")?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    /// Load a policy file; `.yaml`/`.yml` files are parsed as YAML, anything
+    /// else as TOML.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read policy file {}", path.display()))?;
+        if is_yaml(path) {
+            Self::from_yaml(&content)
+        } else {
+            Self::from_toml(&content)
+        }
+        .with_context(|| format!("Invalid policy file {}", path.display()))
+    }
+
+    /// The policy file Hive enforces: `approval_policy_path` from the config,
+    /// or `~/.hive/approval_policy.toml` if it exists.
+    pub fn configured_path(config: &hive_core::HiveConfig) -> Option<PathBuf> {
+        config
+            .approval_policy_path
+            .as_ref()
+            .map(PathBuf::from)
+            .or_else(|| {
+                hive_core::HiveConfig::base_dir()
+                    .ok()
+                    .map(|d| d.join("approval_policy.toml"))
+                    .filter(|p| p.exists())
+            })
+    }
+
+    /// SHA-256 of a policy file, recorded in the audit log when it is loaded.
+    pub fn file_digest(path: &Path) -> Result<String> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read policy file {}", path.display()))?;
+        Ok(format!("{:x}", Sha256::digest(&bytes)))
+    }
+
+    /// Load test cases kept outside the policy file (`[[test]]` entries only).
+    pub fn load_test_cases(path: &Path) -> Result<Vec<PolicyTestCase>> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Suite {
+            #[serde(default, rename = "test", alias = "tests")]
+            tests: Vec<PolicyTestCase>,
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read test cases {}", path.display()))?;
+        let suite: Suite = if is_yaml(path) {
+            serde_yaml::from_str(&content).context("Failed to parse test case YAML")?
+        } else {
+            toml::from_str(&content).context("Failed to parse test case TOML")?
+        };
+        Ok(suite.tests)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for rule in &self.rules {
+            if rule.name.trim().is_empty() {
+                bail!("Policy rule names must not be empty");
+            }
+            if !names.insert(rule.name.as_str()) {
+                bail!("Duplicate policy rule name: {}", rule.name);
+            }
+            if rule.outcome == PolicyOutcome::RequireApprovers(0) {
+                bail!("Rule '{}' requires zero approvers", rule.name);
+            }
+        }
+        if self.default == PolicyOutcome::RequireApprovers(0) {
+            bail!("Policy default requires zero approvers");
+        }
+        Ok(())
+    }
+
+    /// The highest-priority matching rule, if any.
+    pub fn matching_rule(&self, ctx: &PolicyContext) -> Option<&PolicyRule> {
+        let mut best: Option<&PolicyRule> = None;
+        for rule in self.rules.iter().filter(|r| r.when.matches(ctx)) {
+            if best.is_none_or(|b| rule.priority > b.priority) {
+                best = Some(rule);
+            }
+        }
+        best
+    }
+
+    pub fn evaluate(&self, ctx: &PolicyContext) -> PolicyDecision {
+        match self.matching_rule(ctx) {
+            Some(rule) => PolicyDecision {
+                outcome: rule.outcome,
+                rule: Some(rule.name.clone()),
+                expires_after_secs: rule.expires_after_secs.or(self.approval_ttl_secs),
+            },
+            None => PolicyDecision {
+                outcome: self.default,
+                rule: None,
+                expires_after_secs: self.approval_ttl_secs,
+            },
+        }
+    }
+
+    /// Run the embedded test cases.
+    pub fn run_tests(&self) -> Vec<PolicyTestResult> {
+        self.run_cases(&self.tests)
+    }
+
+    pub fn run_cases(&self, cases: &[PolicyTestCase]) -> Vec<PolicyTestResult> {
+        cases
+            .iter()
+            .map(|case| {
+                let decision = self.evaluate(&case.context());
+                let passed = decision.outcome == case.expect
+                    && case
+                        .expect_rule
+                        .as_ref()
+                        .is_none_or(|r| decision.rule.as_ref() == Some(r));
+                PolicyTestResult {
+                    name: case.name.clone(),
+                    expected: case.expect,
+                    expected_rule: case.expect_rule.clone(),
+                    decision,
+                    passed,
+                }
+            })
+            .collect()
+    }
+}
+
+fn is_yaml(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("yaml") || e.eq_ignore_ascii_case("yml"))
+}
+
+// ---------------------------------------------------------------------------
+// Test cases
+// ---------------------------------------------------------------------------
+
+/// A sample operation and the outcome the policy is expected to produce.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyTestCase {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persona: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    pub operation: OperationKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Local time of the sample operation (defaults to now).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<NaiveDateTime>,
+    pub expect: PolicyOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expect_rule: Option<String>,
+}
+
+impl PolicyTestCase {
+    pub fn operation(&self) -> OperationType {
+        let text = || self.command.clone().unwrap_or_default();
+        let path = || self.path.clone().unwrap_or_default();
+        match self.operation {
+            OperationKind::ShellCommand => OperationType::ShellCommand(text()),
+            OperationKind::FileDelete => OperationType::FileDelete(path()),
+            OperationKind::FileModify => OperationType::FileModify {
+                path: path(),
+                scope: format!("{} files", self.files.unwrap_or(1)),
+            },
+            OperationKind::GitPush => OperationType::GitPush {
+                remote: self.remote.clone().unwrap_or_else(|| "origin".into()),
+                branch: self.branch.clone().unwrap_or_else(|| "main".into()),
+            },
+            OperationKind::AiCall => OperationType::AiCall {
+                model: self.model.clone().unwrap_or_default(),
+                estimated_cost: self.cost.unwrap_or(0.0),
+            },
+            OperationKind::Custom => OperationType::Custom(text()),
+        }
+    }
+
+    pub fn context(&self) -> PolicyContext {
+        let mut ctx = PolicyContext::new(
+            self.agent.clone().unwrap_or_else(|| "policy-test".into()),
+            self.operation(),
+        );
+        ctx.role = self.role.clone();
+        ctx.persona = self.persona.clone();
+        ctx.model = self.model.clone();
+        ctx.workspace = self.workspace.clone();
+        if let Some(at) = self.at {
+            ctx.at = at;
+        }
+        ctx
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyTestResult {
+    pub name: String,
+    pub expected: PolicyOutcome,
+    pub expected_rule: Option<String>,
+    pub decision: PolicyDecision,
+    pub passed: bool,
+}
+
+// ---------------------------------------------------------------------------
+// Serde helpers
+// ---------------------------------------------------------------------------
+
+fn one_or_many<'de, D, T>(deserializer: D) -> std::result::Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+fn clock_time<'de, D>(deserializer: D) -> std::result::Result<Option<NaiveTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(raw) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    NaiveTime::parse_from_str(&raw, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(&raw, "%H:%M:%S"))
+        .map(Some)
+        .map_err(|_| serde::de::Error::custom(format!("invalid time '{raw}', expected HH:MM")))
+}
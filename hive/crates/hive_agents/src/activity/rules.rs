@@ -1,3 +1,7 @@
+use std::path::Path;
+use std::sync::LazyLock;
+
+use hive_core::SecurityGateway;
 use serde::{Deserialize, Serialize};
 
 use super::OperationType;
@@ -26,7 +30,7 @@ impl ApprovalRule {
             return false;
         }
         match &self.trigger {
-            RuleTrigger::SecurityGatewayBlock => false,
+            RuleTrigger::SecurityGatewayBlock => security_gateway_blocks(op),
             RuleTrigger::CostExceeds { usd } => {
                 if let OperationType::AiCall { estimated_cost, .. } = op {
                     *estimated_cost > *usd
@@ -48,12 +52,7 @@ impl ApprovalRule {
             }
             RuleTrigger::FilesExceed { count } => {
                 if let OperationType::FileModify { scope, .. } = op {
-                    scope
-                        .split_whitespace()
-                        .next()
-                        .and_then(|n| n.parse::<usize>().ok())
-                        .map(|n| n > *count)
-                        .unwrap_or(false)
+                    scope_file_count(scope).is_some_and(|n| n > *count)
                 } else {
                     false
                 }
@@ -101,8 +100,26 @@ impl ApprovalRule {
     }
 }
 
+/// Whether the default [`SecurityGateway`] refuses the operation: dangerous
+/// or risky shell commands, and file operations on sensitive paths.
+pub(crate) fn security_gateway_blocks(op: &OperationType) -> bool {
+    static GATEWAY: LazyLock<SecurityGateway> = LazyLock::new(SecurityGateway::new);
+    match op {
+        OperationType::ShellCommand(cmd) => GATEWAY.check_command(cmd).is_err(),
+        OperationType::FileModify { path, .. } | OperationType::FileDelete(path) => {
+            GATEWAY.check_path_lexical(Path::new(path)).is_err()
+        }
+        _ => false,
+    }
+}
+
+/// Leading file count of a `FileModify` scope such as `"12 files"`.
+pub(crate) fn scope_file_count(scope: &str) -> Option<usize> {
+    scope.split_whitespace().next()?.parse().ok()
+}
+
 /// Simple glob matching: `*` matches any sequence of characters.
-fn glob_match(pattern: &str, text: &str) -> bool {
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
     let parts: Vec<&str> = pattern.split('*').collect();
     if parts.len() == 1 {
         return pattern == text;
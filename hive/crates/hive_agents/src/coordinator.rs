@@ -14,8 +14,9 @@ use hive_ai::rag::RagService;
 use hive_ai::types::{ChatMessage, ChatRequest, MessageRole, ModelTier};
 
 use crate::activity::OperationType;
-use crate::activity::approval::{ApprovalDecision, ApprovalGate};
+use crate::activity::approval::{ApprovalDecision, ApprovalGate, SYSTEM_APPROVER};
 use crate::activity::budget::{BudgetDecision, BudgetEnforcer};
+use crate::activity::policy::PolicyContext;
 
 use crate::hivemind::{AiExecutor, default_model_for_tier};
 use crate::personas::{Persona, PersonaKind, PersonaRegistry, execute_with_persona_model};
@@ -119,6 +120,10 @@ pub struct CoordinatorConfig {
     /// `"auto"` model so the policy-aware router decides (default: true).
     #[serde(default = "default_true")]
     pub auto_routing: bool,
+    /// Workspace the tasks run in, matched by `workspace` conditions in
+    /// approval policies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
 }
 
 fn default_true() -> bool {
@@ -137,6 +142,7 @@ impl Default for CoordinatorConfig {
             budget: None,
             approval: None,
             auto_routing: true,
+            workspace: None,
         }
     }
 }
@@ -496,19 +502,50 @@ impl<E: AiExecutor + 'static> Coordinator<E> {
                 // await the user's response via the oneshot channel.
                 if let Some(ref gate) = self.config.approval {
                     let op = classify_task_operation(&task.description);
-                    if let Some((request, rx)) = gate.check_with_channel(&task.id, &op) {
-                        // Notify listeners that this task is pending approval.
-                        self.emit(TaskEvent::TaskApprovalPending {
-                            task_id: task.id.clone(),
-                            request_id: request.id.clone(),
-                            operation: format!("{:?}", request.operation),
-                            rule: request.matched_rule.clone(),
-                        });
-
-                        // Await the user's decision.
-                        let decision = match rx.await {
-                            Ok(d) => d,
-                            Err(_) => ApprovalDecision::Timeout,
+                    let mut ctx = PolicyContext::new(task.id.clone(), op)
+                        .with_role(task.persona.role())
+                        .with_persona(task.persona.to_string());
+                    if let Some(model) = &task.model_override {
+                        ctx = ctx.with_model(model.clone());
+                    }
+                    if let Some(workspace) = &self.config.workspace {
+                        ctx = ctx.with_workspace(workspace.clone());
+                    }
+                    if let Some((request, rx)) = gate.check_with_context(&ctx) {
+                        // Notify listeners that this task is pending approval
+                        // (policy denials resolve immediately and skip this).
+                        if request.denial.is_none() {
+                            self.emit(TaskEvent::TaskApprovalPending {
+                                task_id: task.id.clone(),
+                                request_id: request.id.clone(),
+                                operation: format!("{:?}", request.operation),
+                                rule: request.matched_rule.clone(),
+                            });
+                        }
+
+                        // Await the user's decision, up to the request's expiry.
+                        let decision = match request.expires_at {
+                            Some(expires_at) => {
+                                let remaining = (expires_at - chrono::Utc::now())
+                                    .to_std()
+                                    .unwrap_or_default();
+                                match tokio::time::timeout(remaining, rx).await {
+                                    Ok(Ok(d)) => d,
+                                    Ok(Err(_)) => ApprovalDecision::Timeout,
+                                    Err(_) => {
+                                        gate.respond(
+                                            &request.id,
+                                            SYSTEM_APPROVER,
+                                            ApprovalDecision::Timeout,
+                                        );
+                                        ApprovalDecision::Timeout
+                                    }
+                                }
+                            }
+                            None => match rx.await {
+                                Ok(d) => d,
+                                Err(_) => ApprovalDecision::Timeout,
+                            },
                         };
 
                         match decision {
@@ -805,6 +842,7 @@ fn parse_persona_kind(s: &str) -> PersonaKind {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::activity::LOCAL_APPROVER;
     use hive_ai::types::{ChatResponse, FinishReason, TokenUsage};
     use std::sync::atomic::{AtomicUsize, Ordering};
 
@@ -1398,6 +1436,7 @@ mod tests {
                     for req in requests {
                         gate_clone.respond(
                             &req.id,
+                            LOCAL_APPROVER,
                             ApprovalDecision::Denied {
                                 reason: Some("Too risky".into()),
                             },
@@ -1485,7 +1524,7 @@ mod tests {
                 if gate_clone.pending_count() > 0 {
                     let requests = gate_clone.pending_requests();
                     for req in requests {
-                        gate_clone.respond(&req.id, ApprovalDecision::Approved);
+                        gate_clone.respond(&req.id, LOCAL_APPROVER, ApprovalDecision::Approved);
                     }
                     break;
                 }
@@ -1501,6 +1540,58 @@ mod tests {
         assert_eq!(call_count.load(Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn execute_plan_policy_matches_role_and_workspace() {
+        use crate::activity::approval::ApprovalGate;
+        use crate::activity::policy::ApprovalPolicy;
+
+        let policy = ApprovalPolicy::from_toml(
+            r#"
+[[rule]]
+name = "implementers-keep-prod-files"
+outcome = "deny"
+[rule.when]
+role = "implementer"
+workspace = "*prod*"
+operation = "file_delete"
+"#,
+        )
+        .unwrap();
+        let gate = Arc::new(ApprovalGate::new(vec![]).with_policy(policy));
+
+        let executor = MockExecutor::new("Task output");
+        let call_count = executor.call_count.clone();
+        let config = CoordinatorConfig {
+            approval: Some(gate.clone()),
+            workspace: Some("/srv/acme-prod".into()),
+            ..CoordinatorConfig::default()
+        };
+        let coordinator = Coordinator::new(config, executor);
+
+        let task = |id: &str, persona: PersonaKind| PlannedTask {
+            id: id.into(),
+            description: "Delete old logs".into(),
+            persona,
+            dependencies: vec![],
+            priority: 1,
+            model_override: None,
+        };
+        let plan = TaskPlan {
+            tasks: vec![
+                task("impl", PersonaKind::Implement),
+                task("review", PersonaKind::CodeReview),
+            ],
+        };
+
+        let result = coordinator.execute_plan(&plan).await;
+
+        let by_id = |id: &str| result.results.iter().find(|r| r.task_id == id).unwrap();
+        assert!(!by_id("impl").success);
+        assert!(by_id("review").success);
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+        assert_eq!(gate.pending_count(), 0);
+    }
+
     #[tokio::test]
     async fn execute_plan_no_approval_gate_runs_normally() {
         // Without an approval gate, tasks should run as before.
@@ -0,0 +1,194 @@
+//! Doctor checks for swarm state: worktrees left behind by interrupted runs.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use hive_core::diagnostics::{
+    CheckStatus, Diagnostic, DiagnosticContext, DiagnosticRegistry, DoctorCheck,
+};
+
+use crate::worktree::WorktreeManager;
+
+/// Register this crate's diagnostics.
+pub fn register(registry: &mut DiagnosticRegistry) -> Result<()> {
+    registry.register(LeftoverWorktreesCheck)?;
+    Ok(())
+}
+
+/// `.hive-worktrees/` slots in the workspace repository that git no longer
+/// tracks. Only slots with nothing uncommitted are offered for removal.
+struct LeftoverWorktreesCheck;
+
+impl LeftoverWorktreesCheck {
+    const NAME: &str = "Swarm Worktrees";
+    const DESCRIPTION: &str = "Check for worktrees left behind by interrupted swarm runs";
+
+    /// Root of the repository containing `workspace`, if any.
+    fn repo_root(workspace: &Path) -> Option<PathBuf> {
+        let repo = git2::Repository::discover(workspace).ok()?;
+        repo.workdir().map(Path::to_path_buf)
+    }
+
+    /// Leftover slots, split into those safe to remove and those that may
+    /// hold uncommitted work.
+    fn scan(workspace: PathBuf) -> Option<(WorktreeManager, Result<Leftovers, String>)> {
+        let manager = WorktreeManager::new(Self::repo_root(&workspace)?);
+        let leftovers = manager.leftover_worktrees().map(|slots| {
+            let (disposable, dirty) = slots
+                .into_iter()
+                .partition(|slot| manager.leftover_is_disposable(slot));
+            Leftovers { disposable, dirty }
+        });
+        Some((manager, leftovers))
+    }
+}
+
+struct Leftovers {
+    disposable: Vec<String>,
+    dirty: Vec<String>,
+}
+
+#[async_trait]
+impl Diagnostic for LeftoverWorktreesCheck {
+    fn id(&self) -> &'static str {
+        "agents.worktrees"
+    }
+
+    fn category(&self) -> &'static str {
+        "swarm"
+    }
+
+    async fn run(&self, ctx: &DiagnosticContext) -> DoctorCheck {
+        let workspace = ctx.workspace.clone();
+        let scan = tokio::task::spawn_blocking(move || Self::scan(workspace))
+            .await
+            .ok()
+            .flatten();
+        let leftovers = match scan {
+            None => {
+                return DoctorCheck::pass(
+                    Self::NAME,
+                    Self::DESCRIPTION,
+                    "Workspace is not a git repository; skipped",
+                );
+            }
+            Some((_, Err(e))) => return DoctorCheck::warn(Self::NAME, Self::DESCRIPTION, e),
+            Some((_, Ok(leftovers))) => leftovers,
+        };
+        if leftovers.disposable.is_empty() && leftovers.dirty.is_empty() {
+            return DoctorCheck::pass(Self::NAME, Self::DESCRIPTION, "No leftover worktrees");
+        }
+
+        let mut message = Vec::new();
+        if !leftovers.disposable.is_empty() {
+            message.push(format!(
+                "{} leftover worktree(s) in .hive-worktrees/: {}",
+                leftovers.disposable.len(),
+                leftovers.disposable.join(", ")
+            ));
+        }
+        if !leftovers.dirty.is_empty() {
+            message.push(format!(
+                "{} with uncommitted changes, left for you to review: {}",
+                leftovers.dirty.len(),
+                leftovers.dirty.join(", ")
+            ));
+        }
+        let check = DoctorCheck::warn(Self::NAME, Self::DESCRIPTION, message.join("; "));
+        if leftovers.disposable.is_empty() {
+            check
+        } else {
+            check.with_fix(
+                "Run 'hive doctor --fix' to remove the clean ones; their branches are kept",
+            )
+        }
+    }
+
+    fn can_fix(&self, check: &DoctorCheck) -> bool {
+        check.status == CheckStatus::Warn && check.fix_suggestion.is_some()
+    }
+
+    /// Removes only worktrees with nothing uncommitted, re-checked here in
+    /// case they changed since the scan.
+    async fn fix(&self, ctx: &DiagnosticContext) -> Result<String> {
+        let workspace = ctx.workspace.clone();
+        tokio::task::spawn_blocking(move || {
+            let (manager, leftovers) = Self::scan(workspace)
+                .ok_or_else(|| anyhow!("Workspace is not a git repository"))?;
+            let leftovers = leftovers.map_err(|e| anyhow!(e))?;
+            for slot in &leftovers.disposable {
+                manager
+                    .cleanup_worktree(slot, false)
+                    .map_err(|e| anyhow!("{slot}: {e}"))?;
+            }
+            let mut summary = format!(
+                "Removed {} leftover worktree(s)",
+                leftovers.disposable.len()
+            );
+            if !leftovers.dirty.is_empty() {
+                summary.push_str(&format!(
+                    "; kept {} with uncommitted changes: {}",
+                    leftovers.dirty.len(),
+                    leftovers.dirty.join(", ")
+                ));
+            }
+            Ok(summary)
+        })
+        .await?
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stray_worktree_dir_warns_and_is_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = DiagnosticContext::new(dir.path(), dir.path());
+        let check = LeftoverWorktreesCheck.run(&ctx).await;
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert!(check.message.contains("not a git repository"));
+
+        git2::Repository::init(dir.path()).unwrap();
+        std::fs::create_dir_all(dir.path().join(".hive-worktrees/stray")).unwrap();
+        let check = LeftoverWorktreesCheck.run(&ctx).await;
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(LeftoverWorktreesCheck.can_fix(&check));
+
+        LeftoverWorktreesCheck.fix(&ctx).await.unwrap();
+        assert!(!dir.path().join(".hive-worktrees/stray").exists());
+        assert_eq!(
+            LeftoverWorktreesCheck.run(&ctx).await.status,
+            CheckStatus::Pass
+        );
+    }
+
+    #[tokio::test]
+    async fn worktree_with_changes_is_reported_but_not_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = DiagnosticContext::new(dir.path(), dir.path());
+        git2::Repository::init(dir.path()).unwrap();
+        let dirty = dir.path().join(".hive-worktrees/dirty");
+        std::fs::create_dir_all(&dirty).unwrap();
+        std::fs::write(dirty.join("wip.rs"), "// unsaved").unwrap();
+
+        let check = LeftoverWorktreesCheck.run(&ctx).await;
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.message.contains("uncommitted changes"));
+        assert!(!LeftoverWorktreesCheck.can_fix(&check));
+
+        std::fs::create_dir_all(dir.path().join(".hive-worktrees/empty")).unwrap();
+        let check = LeftoverWorktreesCheck.run(&ctx).await;
+        assert!(LeftoverWorktreesCheck.can_fix(&check));
+        let summary = LeftoverWorktreesCheck.fix(&ctx).await.unwrap();
+        assert!(summary.contains("kept 1"));
+        assert!(!dir.path().join(".hive-worktrees/empty").exists());
+        assert!(dirty.join("wip.rs").exists());
+    }
+}
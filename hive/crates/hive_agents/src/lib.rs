@@ -3,7 +3,9 @@ pub mod auto_commit;
 pub mod automation;
 pub mod collective_memory;
 pub mod competence_detection;
+pub mod content_guard;
 pub mod coordinator;
+pub mod diagnostics;
 pub mod guardian;
 pub mod heartbeat;
 pub mod heartbeat_scheduler;
@@ -25,6 +27,7 @@ pub mod plugin_manager;
 pub mod plugin_types;
 pub mod prompt_template;
 pub mod queen;
+pub mod remote_team;
 pub mod repo_context;
 pub mod response_parser;
 pub mod skill_authoring;
@@ -42,9 +45,10 @@ pub mod worktree;
 
 pub use activity::{
     ActivityEntry, ActivityEvent, ActivityFilter, ActivityLog, ActivityService, ApprovalDecision,
-    ApprovalGate, ApprovalRequest, ApprovalRule, BudgetConfig, BudgetDecision, BudgetEnforcer,
-    CostSummary, ExhaustAction, FileOp, Notification, NotificationKind, NotificationService,
-    OperationType, PauseReason, RuleTrigger,
+    ApprovalGate, ApprovalPolicy, ApprovalProgress, ApprovalRequest, ApprovalRule, BudgetConfig,
+    BudgetDecision, BudgetEnforcer, CostSummary, ExhaustAction, FileOp, LOCAL_APPROVER,
+    Notification, NotificationKind, NotificationService, OperationType, PauseReason, PolicyContext,
+    PolicyDecision, PolicyOutcome, RuleTrigger, SYSTEM_APPROVER,
 };
 pub use auto_commit::{AutoCommitConfig, AutoCommitService, CommitResult};
 pub use automation::{
@@ -57,6 +61,7 @@ pub use competence_detection::{
     CompetenceAssessment, CompetenceConfig, CompetenceDetector, CompetenceGap, GapSeverity,
     GapType, SuggestedAction,
 };
+pub use content_guard::{ContentGuard, Provenance, TrustLevel};
 pub use coordinator::{
     Coordinator, CoordinatorConfig, CoordinatorResult, PlannedTask, TaskEvent, TaskEventInfo,
     TaskPlan, TaskResult,
@@ -96,6 +101,10 @@ pub use plugin_types::{
 };
 pub use prompt_template::PromptTemplate;
 pub use queen::Queen;
+pub use remote_team::{
+    NetworkTeamDispatcher, RemotePeer, RemoteTeamAssignment, RemoteTeamEvent, RemoteTeamWorker,
+    TeamDispatcher, WorkerCapacity, task_requirements,
+};
 pub use repo_context::{
     DEFAULT_TOKEN_BUDGET, EDIT_FORMAT_INSTRUCTION, assemble_repo_context, build_grounded_objective,
 };
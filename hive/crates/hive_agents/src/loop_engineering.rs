@@ -12,7 +12,7 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::activity::{ApprovalGate, OperationType};
+use crate::activity::{ApprovalDecision, ApprovalGate, OperationType, SYSTEM_APPROVER};
 use crate::automation::{ActionType, AutomationService, TriggerType, Workflow};
 use crate::collective_memory::{CollectiveMemory, MemoryCategory, MemoryEntry};
 use crate::hiveloop::{HiveLoop, LoopConfig, LoopStatus};
@@ -653,7 +653,12 @@ impl LoopRunner {
         let mut result = LoopRunResult::started(spec);
 
         if self.check_initial_approvals(spec, &mut result) {
-            result.finish(LoopStatus::Paused);
+            let status = if result.error.is_some() && result.approval_request_ids.is_empty() {
+                LoopStatus::Failed
+            } else {
+                LoopStatus::Paused
+            };
+            result.finish(status);
             return result;
         }
 
@@ -724,6 +729,20 @@ impl LoopRunner {
 
         for operation in operations {
             if let Some(request) = gate.check_sync(&spec.id, &operation) {
+                if let Some(denial) = request.denial {
+                    // A hard deny withdraws anything already queued for this loop.
+                    for id in result.approval_request_ids.drain(..) {
+                        gate.respond(
+                            &id,
+                            SYSTEM_APPROVER,
+                            ApprovalDecision::Denied {
+                                reason: Some(denial.clone()),
+                            },
+                        );
+                    }
+                    result.error = Some(denial);
+                    return true;
+                }
                 result.approval_request_ids.push(request.id);
             }
         }
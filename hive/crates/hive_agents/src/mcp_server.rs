@@ -7,7 +7,7 @@
 
 use crate::mcp_client::{JsonRpcError, JsonRpcRequest, JsonRpcResponse, McpTool, error_codes};
 use hive_fs::{FileService, GitService, SearchOptions, SearchService};
-use hive_terminal::CommandExecutor;
+use hive_terminal::{CommandExecutor, CommandHistory, CommandRecord, HistoryQuery};
 use serde_json::json;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -285,6 +285,76 @@ impl McpServer {
         );
     }
 
+    /// Register tools that let agents read the command history recorded by
+    /// Hive terminal sessions: `terminal_history` and `terminal_last_failed`.
+    pub fn wire_terminal_history(&mut self, history: Arc<CommandHistory>) {
+        // -- terminal_history -----------------------------------------------
+        {
+            let history = Arc::clone(&history);
+            self.register(
+                McpTool {
+                    name: "terminal_history".into(),
+                    description: "Search commands run in Hive terminal sessions, most recent first. Each entry has the command, working directory, exit code, duration and the tail of its output."
+                        .into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "query": { "type": "string", "description": "Case-insensitive text to find in the command or its output" },
+                            "failed_only": { "type": "boolean", "description": "Only commands that exited with a non-zero status" },
+                            "session_id": { "type": "string", "description": "Only commands from this terminal session" },
+                            "cwd": { "type": "string", "description": "Only commands run in this directory or below it" },
+                            "limit": { "type": "integer", "description": "Max results (default 10)" }
+                        }
+                    }),
+                },
+                Box::new(move |args| {
+                    let text = |key: &str| args.get(key).and_then(|v| v.as_str()).map(String::from);
+                    let query = HistoryQuery {
+                        text: text("query"),
+                        session_id: text("session_id"),
+                        cwd: text("cwd"),
+                        failed_only: args
+                            .get("failed_only")
+                            .and_then(|v| v.as_bool())
+                            .unwrap_or(false),
+                        limit: args.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize,
+                    };
+
+                    let records = history
+                        .search(&query)
+                        .map_err(|e| format!("terminal_history failed: {e}"))?;
+                    Ok(json!({
+                        "commands": records
+                            .iter()
+                            .map(|r| command_json(r, HISTORY_OUTPUT_CHARS))
+                            .collect::<Vec<_>>()
+                    }))
+                }),
+            );
+        }
+
+        // -- terminal_last_failed -------------------------------------------
+        self.register(
+            McpTool {
+                name: "terminal_last_failed".into(),
+                description: "Return the most recent command that failed in a Hive terminal session, with its exit code and output."
+                    .into(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {}
+                }),
+            },
+            Box::new(move |_args| {
+                let record = history
+                    .last_failed()
+                    .map_err(|e| format!("terminal_last_failed failed: {e}"))?;
+                Ok(json!({
+                    "command": record.map(|r| command_json(&r, usize::MAX))
+                }))
+            }),
+        );
+    }
+
     /// List all available tools.
     pub fn list_tools(&self) -> Vec<&McpTool> {
         let mut tools: Vec<_> = self.tools.values().map(|(def, _)| def).collect();
@@ -661,6 +731,68 @@ impl McpServer {
             );
         }
 
+        // -- git_blame -------------------------------------------------------
+        {
+            let root = Arc::clone(&root);
+            self.register(
+                McpTool {
+                    name: "git_blame".into(),
+                    description: "Show which commit last changed each line of a file, with author, date and summary. Uncommitted lines have no commit.".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "path": { "type": "string", "description": "File path relative to the repository root" },
+                            "start_line": { "type": "integer", "description": "First line to blame (1-based, optional)" },
+                            "end_line": { "type": "integer", "description": "Last line to blame (inclusive, optional)" },
+                            "cwd": { "type": "string", "description": "Repository working directory (optional)" }
+                        },
+                        "required": ["path"]
+                    }),
+                },
+                Box::new(move |args| {
+                    let path = args
+                        .get("path")
+                        .and_then(|v| v.as_str())
+                        .ok_or("Missing required argument 'path'")?;
+                    let repo_path = match args.get("cwd").and_then(|v| v.as_str()) {
+                        Some(dir) => resolve_path(&root, dir)?,
+                        None => root.as_ref().clone(),
+                    };
+                    let start = args.get("start_line").and_then(|v| v.as_u64());
+                    let end = args.get("end_line").and_then(|v| v.as_u64());
+                    let lines = match (start, end) {
+                        (None, None) => None,
+                        (start, end) => {
+                            Some(start.unwrap_or(1) as usize..=end.unwrap_or(u64::MAX) as usize)
+                        }
+                    };
+
+                    let git = GitService::open(&repo_path)
+                        .map_err(|e| format!("Failed to open git repo: {e}"))?;
+                    let blame = git
+                        .blame(Path::new(path), lines)
+                        .map_err(|e| format!("Failed to blame {path}: {e}"))?;
+
+                    let lines: Vec<serde_json::Value> = blame
+                        .iter()
+                        .map(|line| {
+                            json!({
+                                "line": line.line,
+                                "content": line.content,
+                                "commit": line.commit.as_ref().map(|c| c.hash.clone()),
+                                "author": line.commit.as_ref().map(|c| c.author.clone()),
+                                "email": line.commit.as_ref().map(|c| c.email.clone()),
+                                "timestamp": line.commit.as_ref().map(|c| c.timestamp),
+                                "summary": line.commit.as_ref().map(|c| c.summary.clone()),
+                            })
+                        })
+                        .collect();
+
+                    Ok(json!({ "path": path, "lines": lines }))
+                }),
+            );
+        }
+
         // -- click -----------------------------------------------------------
         {
             self.register(
@@ -751,6 +883,11 @@ impl McpServer {
                 }),
             );
         }
+
+        // -- terminal_history / terminal_last_failed -------------------------
+        if let Ok(history) = CommandHistory::open_default() {
+            self.wire_terminal_history(Arc::new(history));
+        }
     }
 }
 
@@ -758,6 +895,30 @@ impl McpServer {
 // Helpers
 // ---------------------------------------------------------------------------
 
+/// Output characters returned per command by `terminal_history`.
+const HISTORY_OUTPUT_CHARS: usize = 2_000;
+
+/// A recorded command as tool output, keeping the last `max_output` chars of
+/// its output.
+fn command_json(record: &CommandRecord, max_output: usize) -> serde_json::Value {
+    let chars = record.output.chars().count();
+    let output: String = record
+        .output
+        .chars()
+        .skip(chars.saturating_sub(max_output))
+        .collect();
+    json!({
+        "session_id": record.session_id,
+        "command": record.command,
+        "cwd": record.cwd,
+        "exit_code": record.exit_code,
+        "started_at": record.started_at.to_rfc3339(),
+        "duration_ms": record.duration_ms,
+        "output": output,
+        "output_truncated": record.output_truncated || chars > max_output,
+    })
+}
+
 /// Resolve a path string relative to the workspace root, or as absolute.
 ///
 /// Canonicalizes the result and validates that:
@@ -888,9 +1049,52 @@ mod tests {
         assert!(names.contains(&"search_files"));
         assert!(names.contains(&"list_files"));
         assert!(names.contains(&"git_status"));
+        assert!(names.contains(&"git_blame"));
         assert!(names.contains(&"a2a_list_agents"));
         assert!(names.contains(&"ollama_list_models"));
         assert!(names.contains(&"hue_discover_bridges"));
+        assert!(names.contains(&"terminal_history"));
+        assert!(names.contains(&"terminal_last_failed"));
+    }
+
+    #[test]
+    fn terminal_history_tools_query_recorded_commands() {
+        let (dir, mut server) = setup_workspace();
+        let history = Arc::new(CommandHistory::new(dir.path().join("history.jsonl")));
+        for (command, exit_code, output) in [
+            ("cargo build", 0, "Finished"),
+            ("cargo test", 101, "test parser ... FAILED"),
+            ("git status", 0, "nothing to commit"),
+        ] {
+            history
+                .append(&CommandRecord {
+                    session_id: "session-1".into(),
+                    command: command.into(),
+                    cwd: Some("/work".into()),
+                    exit_code: Some(exit_code),
+                    started_at: chrono::Utc::now(),
+                    duration_ms: 10,
+                    output: output.into(),
+                    output_truncated: false,
+                })
+                .unwrap();
+        }
+        server.wire_terminal_history(history);
+
+        let result = server
+            .call_tool_value("terminal_last_failed", json!({}))
+            .unwrap();
+        assert_eq!(result["command"]["command"], "cargo test");
+        assert_eq!(result["command"]["exit_code"], 101);
+        assert_eq!(result["command"]["output"], "test parser ... FAILED");
+
+        let result = server
+            .call_tool_value("terminal_history", json!({ "query": "cargo", "limit": 5 }))
+            .unwrap();
+        let commands = result["commands"].as_array().unwrap();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0]["command"], "cargo test");
+        assert_eq!(commands[1]["command"], "cargo build");
     }
 
     #[test]
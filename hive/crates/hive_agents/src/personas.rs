@@ -40,6 +40,19 @@ impl PersonaKind {
         Self::Debug,
         Self::CodeReview,
     ];
+
+    /// Agent role matched by `role` conditions in approval policies.
+    pub fn role(&self) -> &str {
+        match self {
+            Self::Investigate => "investigator",
+            Self::Implement => "implementer",
+            Self::Verify => "verifier",
+            Self::Critique => "critic",
+            Self::Debug => "debugger",
+            Self::CodeReview => "reviewer",
+            Self::Custom(name) => name,
+        }
+    }
 }
 
 impl std::fmt::Display for PersonaKind {
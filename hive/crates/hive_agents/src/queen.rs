@@ -4,12 +4,15 @@
 //! goal into team objectives, dispatches each team using the appropriate
 //! orchestration mode (HiveMind, Coordinator, NativeProvider, or SingleShot),
 //! enforces budget and time limits, shares cross-team insights, synthesizes
-//! a final output, and records learnings to collective memory.
+//! a final output, and records learnings to collective memory. With a
+//! [`TeamDispatcher`] attached, ready teams may be delegated to federated peers
+//! that advertise spare capacity, falling back to local execution if the peer
+//! rejects the team or disconnects.
 
 use std::collections::HashSet;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use hive_ai::types::{ChatMessage, ChatRequest, ChatResponse, MessageRole, ModelTier};
 
@@ -22,6 +25,9 @@ use crate::coordinator::{Coordinator, CoordinatorConfig, CoordinatorResult};
 use crate::hivemind::{
     AiExecutor, HiveMind, HiveMindConfig, OrchestrationResult, default_model_for_tier,
 };
+use crate::remote_team::{
+    RemotePeer, RemoteTeamAssignment, RemoteTeamEvent, TeamDispatcher, required_models,
+};
 use crate::swarm::{
     InnerResult, OrchestrationMode, SwarmConfig, SwarmPlan, SwarmResult, SwarmStatus,
     SwarmStatusCallback, TeamObjective, TeamResult, TeamStatus,
@@ -72,6 +78,8 @@ pub struct Queen<E: AiExecutor> {
     /// Optional tiered memory for cross-layer context retrieval.
     #[cfg(feature = "memory-tiering")]
     tiered: Option<Arc<tokio::sync::Mutex<crate::memory::TieredMemory>>>,
+    /// Optional dispatcher for delegating teams to federated peers.
+    dispatcher: Option<Arc<dyn TeamDispatcher>>,
     /// Accumulated cost stored as the bit-pattern of an f64 so we can use
     /// atomic operations without a mutex.
     accumulated_cost: AtomicU64,
@@ -93,6 +101,7 @@ impl<E: AiExecutor + 'static> Queen<E> {
             event_tx: None,
             #[cfg(feature = "memory-tiering")]
             tiered: None,
+            dispatcher: None,
             accumulated_cost: AtomicU64::new(0f64.to_bits()),
         }
     }
@@ -139,6 +148,12 @@ impl<E: AiExecutor + 'static> Queen<E> {
         self
     }
 
+    /// Attach a dispatcher so ready teams can run on federated peers.
+    pub fn with_dispatcher(mut self, dispatcher: Arc<dyn TeamDispatcher>) -> Self {
+        self.dispatcher = Some(dispatcher);
+        self
+    }
+
     /// Set the cortex event sender for publishing learning events.
     pub fn set_event_tx(&mut self, tx: hive_learn::cortex::event_bus::CortexEventSender) {
         self.event_tx = Some(tx);
@@ -579,15 +594,11 @@ impl<E: AiExecutor + 'static> Queen<E> {
                 );
             }
 
-            // Execute teams in this wave sequentially.
-            for objective in &batch {
-                self.emit_status(
-                    SwarmStatus::TeamStarted,
-                    &format!("Starting team '{}' ({})", objective.name, objective.id),
-                );
-
-                let result = self.execute_team(objective, &prior_results).await;
+            // Delegated teams run concurrently with the local ones, which
+            // execute sequentially.
+            let batch_results = self.execute_batch(&batch, &prior_results).await;
 
+            for (objective, result) in batch.iter().zip(batch_results) {
                 match result.status {
                     TeamStatus::Completed => {
                         completed_ids.insert(result.team_id.clone());
@@ -645,27 +656,252 @@ impl<E: AiExecutor + 'static> Queen<E> {
         Ok(results)
     }
 
-    /// Execute a single team objective, choosing the orchestration mode.
+    /// Execute one wave batch, returning results in batch order.
     ///
-    /// Builds enriched context from prior team results and dispatches to
-    /// the appropriate orchestrator.
-    async fn execute_team(
+    /// Each team is offered to the dispatcher first. Local teams reserve their
+    /// per-team limit from the remaining run budget; delegated teams get a
+    /// slice of what is left, so the run's budget holds across nodes.
+    async fn execute_batch(
+        &self,
+        batch: &[TeamObjective],
+        prior_results: &[TeamResult],
+    ) -> Vec<TeamResult> {
+        let mut placements: Vec<Option<RemotePeer>> = Vec::with_capacity(batch.len());
+        if let Some(ref dispatcher) = self.dispatcher {
+            for objective in batch {
+                placements.push(
+                    dispatcher
+                        .select_peer(&self.required_models(objective))
+                        .await,
+                );
+            }
+        } else {
+            placements.resize(batch.len(), None);
+        }
+
+        let per_team = self.config.per_team_cost_limit_usd;
+        let local_reserved = per_team * placements.iter().filter(|p| p.is_none()).count() as f64;
+        let mut available = self.config.total_cost_limit_usd - self.current_cost() - local_reserved;
+
+        let mut local = Vec::new();
+        let mut remote = Vec::new();
+        for (idx, placement) in placements.into_iter().enumerate() {
+            match placement {
+                Some(peer) if available > 0.0 => {
+                    let budget = per_team.min(available);
+                    available -= budget;
+                    remote.push((idx, peer, budget));
+                }
+                _ => local.push(idx),
+            }
+        }
+
+        let local_run = async {
+            let mut out = Vec::with_capacity(local.len());
+            for idx in local {
+                let objective = &batch[idx];
+                self.emit_status(
+                    SwarmStatus::TeamStarted,
+                    &format!("Starting team '{}' ({})", objective.name, objective.id),
+                );
+                out.push((idx, self.execute_team(objective, prior_results).await));
+            }
+            out
+        };
+        let remote_runs =
+            futures::future::join_all(remote.into_iter().map(|(idx, peer, budget)| async move {
+                let result = self
+                    .execute_team_remote(&batch[idx], prior_results, peer, budget)
+                    .await;
+                (idx, result)
+            }));
+
+        let (local_results, remote_results) = futures::future::join(local_run, remote_runs).await;
+        let mut indexed: Vec<(usize, TeamResult)> =
+            local_results.into_iter().chain(remote_results).collect();
+        indexed.sort_by_key(|(idx, _)| *idx);
+        indexed.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Models a peer must serve to run `objective` as this Queen would.
+    fn required_models(&self, objective: &TeamObjective) -> Vec<String> {
+        if objective.preferred_model.is_none()
+            && objective.orchestration_mode == OrchestrationMode::Fusion
+        {
+            return self.resolve_fusion_panel(objective);
+        }
+        required_models(objective)
+    }
+
+    /// Execute a single team objective on this node with no prior context.
+    ///
+    /// Used by [`RemoteTeamWorker`](crate::remote_team::RemoteTeamWorker) to run
+    /// teams delegated by a peer's Queen.
+    pub async fn run_team(&self, objective: &TeamObjective) -> TeamResult {
+        self.execute_team(objective, &[]).await
+    }
+
+    /// Delegate a team to `peer`, streaming its status back.
+    ///
+    /// Falls back to local execution if the team cannot be delivered, the
+    /// peer rejects it, or the peer disconnects before reporting a result.
+    async fn execute_team_remote(
         &self,
         objective: &TeamObjective,
         prior_results: &[TeamResult],
+        peer: RemotePeer,
+        budget_usd: f64,
     ) -> TeamResult {
+        let Some(ref dispatcher) = self.dispatcher else {
+            return self.execute_team(objective, prior_results).await;
+        };
         let team_start = Instant::now();
+        let task_id = uuid::Uuid::new_v4().to_string();
+        let assignment = RemoteTeamAssignment {
+            task_id: task_id.clone(),
+            objective: TeamObjective {
+                description: self.enriched_description(objective, prior_results),
+                ..objective.clone()
+            },
+            budget_usd,
+            time_limit_secs: self.config.per_team_time_limit_secs,
+            auto_routing: self.config.auto_routing,
+        };
 
-        // Build enriched context from prior team results.
+        self.emit_status(
+            SwarmStatus::TeamDelegated,
+            &format!(
+                "Team '{}' ({}) delegated to peer '{}' (budget ${budget_usd:.2})",
+                objective.name, objective.id, peer.name
+            ),
+        );
+
+        let mut events = match dispatcher.dispatch(&peer, assignment).await {
+            Ok(events) => events,
+            Err(err) => return self.fail_over(objective, prior_results, &err, 0.0).await,
+        };
+
+        // Allow some slack over the worker's own limit for transit.
+        let deadline = tokio::time::Instant::now()
+            + Duration::from_secs(self.config.per_team_time_limit_secs)
+            + Duration::from_secs(30);
+
+        // Spend the peer last reported, charged to the team if it fails over.
+        let mut remote_cost = 0.0;
+        loop {
+            let event = match tokio::time::timeout_at(deadline, events.recv()).await {
+                Ok(event) => event,
+                Err(_) => {
+                    dispatcher.cancel(&peer, &task_id).await;
+                    let reason = format!("peer '{}' timed out", peer.name);
+                    return self
+                        .fail_over(objective, prior_results, &reason, remote_cost)
+                        .await;
+                }
+            };
+
+            match event {
+                Some(RemoteTeamEvent::Status {
+                    status,
+                    detail,
+                    cost,
+                }) => {
+                    remote_cost = cost;
+                    self.emit_status(
+                        SwarmStatus::TeamProgress,
+                        &format!(
+                            "Team '{}' on '{}': {status:?} -- {detail}",
+                            objective.name, peer.name
+                        ),
+                    );
+                }
+                Some(RemoteTeamEvent::Finished { mut result }) => {
+                    if result.cost > budget_usd + f64::EPSILON {
+                        self.notify(
+                            NotificationKind::BudgetWarning,
+                            &format!(
+                                "Peer '{}' spent ${:.4} on team '{}', over its ${budget_usd:.2} slice",
+                                peer.name, result.cost, objective.name,
+                            ),
+                        );
+                    }
+                    result.team_id = objective.id.clone();
+                    result.team_name = objective.name.clone();
+                    result.duration_ms = team_start.elapsed().as_millis() as u64;
+                    return *result;
+                }
+                Some(RemoteTeamEvent::Rejected { reason }) => {
+                    return self
+                        .fail_over(
+                            objective,
+                            prior_results,
+                            &format!("peer '{}' rejected the team: {reason}", peer.name),
+                            remote_cost,
+                        )
+                        .await;
+                }
+                Some(RemoteTeamEvent::Disconnected) | None => {
+                    return self
+                        .fail_over(
+                            objective,
+                            prior_results,
+                            &format!("peer '{}' disconnected", peer.name),
+                            remote_cost,
+                        )
+                        .await;
+                }
+            }
+        }
+    }
+
+    /// Run a team locally after its delegation failed, charging it the
+    /// `remote_cost` the peer already spent.
+    async fn fail_over(
+        &self,
+        objective: &TeamObjective,
+        prior_results: &[TeamResult],
+        reason: &str,
+        remote_cost: f64,
+    ) -> TeamResult {
+        self.emit_status(
+            SwarmStatus::TeamFailover,
+            &format!("Running team '{}' locally: {reason}", objective.name),
+        );
+        let mut result = self.execute_team(objective, prior_results).await;
+        result.cost += remote_cost;
+        result
+    }
+
+    /// The objective's description with context from prior team results.
+    fn enriched_description(
+        &self,
+        objective: &TeamObjective,
+        prior_results: &[TeamResult],
+    ) -> String {
         let cross_team_context = self.build_cross_team_context(prior_results);
-        let enriched_description = if cross_team_context.is_empty() {
+        if cross_team_context.is_empty() {
             objective.description.clone()
         } else {
             format!(
                 "{}\n\nContext from prior teams:\n{}",
                 objective.description, cross_team_context
             )
-        };
+        }
+    }
+
+    /// Execute a single team objective, choosing the orchestration mode.
+    ///
+    /// Builds enriched context from prior team results and dispatches to
+    /// the appropriate orchestrator.
+    async fn execute_team(
+        &self,
+        objective: &TeamObjective,
+        prior_results: &[TeamResult],
+    ) -> TeamResult {
+        let team_start = Instant::now();
+
+        // Build enriched context from prior team results.
+        let enriched_description = self.enriched_description(objective, prior_results);
 
         let result = match objective.orchestration_mode {
             OrchestrationMode::HiveMind => {
@@ -793,6 +1029,7 @@ impl<E: AiExecutor + 'static> Queen<E> {
             budget: self.budget.clone(),
             approval: self.approval.clone(),
             auto_routing: self.config.auto_routing,
+            workspace: self.config.workspace.clone(),
         };
 
         // Build a simple TaskPlan from the objective description.
@@ -2477,4 +2714,233 @@ mod tests {
             OrchestrationMode::Fusion
         );
     }
+
+    // -- Remote teams ---------------------------------------------------------
+
+    /// Dispatcher that offers one peer and replays a fixed event script.
+    struct MockDispatcher {
+        peer: Option<RemotePeer>,
+        script: Vec<RemoteTeamEvent>,
+        dispatched: Mutex<Vec<RemoteTeamAssignment>>,
+        /// Senders held open so the peer goes silent instead of disconnecting.
+        silent: Option<Mutex<Vec<tokio::sync::mpsc::UnboundedSender<RemoteTeamEvent>>>>,
+    }
+
+    impl MockDispatcher {
+        fn new(script: Vec<RemoteTeamEvent>) -> Self {
+            Self {
+                peer: Some(RemotePeer {
+                    peer_id: "peer-gpu".into(),
+                    name: "gpu-box".into(),
+                }),
+                script,
+                dispatched: Mutex::new(Vec::new()),
+                silent: None,
+            }
+        }
+
+        fn silent(script: Vec<RemoteTeamEvent>) -> Self {
+            Self {
+                silent: Some(Mutex::new(Vec::new())),
+                ..Self::new(script)
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl TeamDispatcher for MockDispatcher {
+        async fn select_peer(&self, _required_models: &[String]) -> Option<RemotePeer> {
+            self.peer.clone()
+        }
+
+        async fn dispatch(
+            &self,
+            _peer: &RemotePeer,
+            assignment: RemoteTeamAssignment,
+        ) -> Result<tokio::sync::mpsc::UnboundedReceiver<RemoteTeamEvent>, String> {
+            self.dispatched.lock().unwrap().push(assignment);
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            for event in &self.script {
+                tx.send(event.clone()).unwrap();
+            }
+            if let Some(silent) = &self.silent {
+                silent.lock().unwrap().push(tx);
+            }
+            Ok(rx)
+        }
+
+        async fn cancel(&self, _peer: &RemotePeer, _task_id: &str) {}
+    }
+
+    fn remote_objective(id: &str) -> TeamObjective {
+        TeamObjective {
+            id: id.into(),
+            name: format!("Team {id}"),
+            description: "Summarize the module".into(),
+            dependencies: vec![],
+            orchestration_mode: OrchestrationMode::SingleShot,
+            scope_paths: vec![],
+            priority: 0,
+            preferred_model: None,
+        }
+    }
+
+    fn remote_result(id: &str, cost: f64) -> TeamResult {
+        TeamResult {
+            team_id: id.into(),
+            team_name: format!("Team {id}"),
+            status: TeamStatus::Completed,
+            inner: Some(InnerResult::SingleShot {
+                content: "remote output".into(),
+                model: "llama3".into(),
+            }),
+            cost,
+            duration_ms: 10,
+            insights: vec![],
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn delegated_team_returns_remote_result() {
+        let executor = Arc::new(MockExecutor::new("local output"));
+        let dispatcher = Arc::new(MockDispatcher::new(vec![
+            RemoteTeamEvent::Status {
+                status: TeamStatus::Running,
+                detail: "Running on 'gpu-box'".into(),
+                cost: 0.0,
+            },
+            RemoteTeamEvent::Finished {
+                result: Box::new(remote_result("team-1", 0.5)),
+            },
+        ]));
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&statuses);
+        let queen = Queen::new(SwarmConfig::default(), Arc::clone(&executor))
+            .with_dispatcher(dispatcher.clone())
+            .with_status_callback(Arc::new(move |status, _| seen.lock().unwrap().push(status)));
+
+        let plan = SwarmPlan {
+            teams: vec![remote_objective("team-1")],
+        };
+        let results = queen.execute_plan(&plan).await.unwrap();
+
+        assert_eq!(results[0].status, TeamStatus::Completed);
+        assert_eq!(executor.call_count.load(Ordering::SeqCst), 0);
+        assert!((queen.current_cost() - 0.5).abs() < f64::EPSILON);
+        let statuses = statuses.lock().unwrap();
+        assert!(statuses.contains(&SwarmStatus::TeamDelegated));
+        assert!(statuses.contains(&SwarmStatus::TeamProgress));
+    }
+
+    #[tokio::test]
+    async fn rejected_team_fails_over_to_local() {
+        let executor = Arc::new(MockExecutor::new("local output"));
+        let dispatcher = Arc::new(MockDispatcher::new(vec![RemoteTeamEvent::Rejected {
+            reason: "no free slots".into(),
+        }]));
+        let queen =
+            Queen::new(SwarmConfig::default(), Arc::clone(&executor)).with_dispatcher(dispatcher);
+
+        let result = queen
+            .execute_team_remote(
+                &remote_objective("team-1"),
+                &[],
+                RemotePeer {
+                    peer_id: "peer-gpu".into(),
+                    name: "gpu-box".into(),
+                },
+                1.0,
+            )
+            .await;
+
+        assert_eq!(result.status, TeamStatus::Completed);
+        assert_eq!(executor.call_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn disconnected_peer_fails_over_to_local() {
+        let executor = Arc::new(MockExecutor::new("local output"));
+        let dispatcher = Arc::new(MockDispatcher::new(vec![
+            RemoteTeamEvent::Status {
+                status: TeamStatus::Running,
+                detail: String::new(),
+                cost: 0.0,
+            },
+            RemoteTeamEvent::Disconnected,
+        ]));
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&statuses);
+        let queen = Queen::new(SwarmConfig::default(), Arc::clone(&executor))
+            .with_dispatcher(dispatcher)
+            .with_status_callback(Arc::new(move |status, _| seen.lock().unwrap().push(status)));
+
+        let plan = SwarmPlan {
+            teams: vec![remote_objective("team-1")],
+        };
+        let results = queen.execute_plan(&plan).await.unwrap();
+
+        assert_eq!(results[0].status, TeamStatus::Completed);
+        assert_eq!(executor.call_count.load(Ordering::SeqCst), 1);
+        assert!(
+            statuses
+                .lock()
+                .unwrap()
+                .contains(&SwarmStatus::TeamFailover)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn timed_out_peer_fails_over_with_reported_cost() {
+        let executor = Arc::new(MockExecutor::new("local output"));
+        let dispatcher = Arc::new(MockDispatcher::silent(vec![RemoteTeamEvent::Status {
+            status: TeamStatus::Running,
+            detail: String::new(),
+            cost: 0.25,
+        }]));
+        let queen =
+            Queen::new(SwarmConfig::default(), Arc::clone(&executor)).with_dispatcher(dispatcher);
+
+        let result = queen
+            .execute_team_remote(
+                &remote_objective("team-1"),
+                &[],
+                RemotePeer {
+                    peer_id: "peer-gpu".into(),
+                    name: "gpu-box".into(),
+                },
+                1.0,
+            )
+            .await;
+
+        assert_eq!(result.status, TeamStatus::Completed);
+        assert_eq!(executor.call_count.load(Ordering::SeqCst), 1);
+        assert!(result.cost >= 0.25);
+    }
+
+    #[tokio::test]
+    async fn delegated_teams_share_the_remaining_budget() {
+        let executor = Arc::new(MockExecutor::new("local output"));
+        let dispatcher = Arc::new(MockDispatcher::new(vec![RemoteTeamEvent::Finished {
+            result: Box::new(remote_result("team-1", 0.0)),
+        }]));
+        let config = SwarmConfig {
+            total_cost_limit_usd: 7.0,
+            per_team_cost_limit_usd: 5.0,
+            ..Default::default()
+        };
+        let queen = Queen::new(config, Arc::clone(&executor)).with_dispatcher(dispatcher.clone());
+
+        let plan = SwarmPlan {
+            teams: vec![remote_objective("team-1"), remote_objective("team-2")],
+        };
+        let results = queen.execute_plan(&plan).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].team_id, "team-1");
+        assert_eq!(results[1].team_id, "team-2");
+
+        let dispatched = dispatcher.dispatched.lock().unwrap();
+        let budgets: Vec<f64> = dispatched.iter().map(|a| a.budget_usd).collect();
+        assert_eq!(budgets, vec![5.0, 2.0]);
+    }
 }
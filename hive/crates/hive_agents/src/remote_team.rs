@@ -0,0 +1,911 @@
+//! Remote teams -- dispatch Queen team objectives to federated peers.
+//!
+//! A node running a [`RemoteTeamWorker`] reports its free team slots as the
+//! load in its signed capability document. A Queen with a [`TeamDispatcher`]
+//! attached (normally a [`NetworkTeamDispatcher`]) picks peers with
+//! `HiveNodeHandle::schedule`, ships ready team objectives to them as
+//! `TaskRequest` envelopes and receives status updates and the final
+//! [`TeamResult`] back as `TaskResult` envelopes.
+//!
+//! Both directions must be permitted in each side's trust store: the worker
+//! must accept `TaskRequest` from the requester, and the requester must accept
+//! `TaskResult` from the worker.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+use hive_ai::types::ModelCapability;
+use hive_network::{
+    CapabilityDocument, Envelope, HiveNodeHandle, LoadReport, MessageKind, PeerId, PeerInfo,
+    ScheduledPeer, TaskRequirements,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, warn};
+
+use crate::hivemind::AiExecutor;
+use crate::queen::Queen;
+use crate::swarm::{SwarmConfig, TeamObjective, TeamResult, TeamStatus};
+
+/// Default interval between capacity advertisements.
+const ADVERTISE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often a pending dispatch checks that its peer is still connected.
+const LIVENESS_INTERVAL: Duration = Duration::from_secs(1);
+
+// ---------------------------------------------------------------------------
+// Wire types
+// ---------------------------------------------------------------------------
+
+/// A team objective shipped to a peer for execution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteTeamAssignment {
+    /// Identifier for this dispatch, unique per attempt.
+    pub task_id: String,
+    /// The objective to run. Its description already carries cross-team
+    /// context, since the worker has no access to the other teams' results.
+    pub objective: TeamObjective,
+    /// Maximum the worker may spend on this team, carved out of the run budget.
+    pub budget_usd: f64,
+    /// Wall-clock limit for the team on the worker.
+    pub time_limit_secs: u64,
+    /// Mirrors [`SwarmConfig::auto_routing`] of the requesting run.
+    pub auto_routing: bool,
+}
+
+/// Payload of a `TaskRequest` envelope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RemoteTeamRequest {
+    /// Run a team objective.
+    Assign(RemoteTeamAssignment),
+    /// Stop a previously assigned team.
+    Cancel { task_id: String },
+}
+
+/// What a worker can currently take on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkerCapacity {
+    /// Teams the worker can start right now.
+    pub free_slots: usize,
+    /// Teams the worker runs at most concurrently.
+    pub max_slots: usize,
+    /// Model IDs the worker serves. Empty means the worker routes any model.
+    pub models: Vec<String>,
+}
+
+impl WorkerCapacity {
+    /// Capacity a peer reports in its signed capability document.
+    pub fn from_document(doc: &CapabilityDocument) -> Self {
+        let free_slots = if doc.load.has_spare_capacity() {
+            doc.load
+                .max_concurrent
+                .saturating_sub(doc.load.active_tasks) as usize
+        } else {
+            0
+        };
+        Self {
+            free_slots,
+            max_slots: doc.load.max_concurrent as usize,
+            models: doc
+                .providers
+                .iter()
+                .flat_map(|p| p.models.iter())
+                .map(|m| m.id.clone())
+                .collect(),
+        }
+    }
+
+    /// Whether the worker serves every model in `required`.
+    pub fn serves(&self, required: &[String]) -> bool {
+        serves_models(&self.models, required)
+    }
+}
+
+/// Whether a worker serving `models` can run a team requiring `required`.
+/// An empty `models` list routes anything; `"auto"` matches any worker.
+fn serves_models(models: &[String], required: &[String]) -> bool {
+    models.is_empty()
+        || required
+            .iter()
+            .filter(|m| m.as_str() != "auto")
+            .all(|m| models.contains(m))
+}
+
+/// Progress of a remote team, as seen by the requester.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RemoteTeamEvent {
+    /// The team moved to a new status on the worker.
+    Status {
+        status: TeamStatus,
+        detail: String,
+        /// Spend on the worker so far, in USD.
+        #[serde(default)]
+        cost: f64,
+    },
+    /// The team finished; the result carries its final status and cost.
+    Finished { result: Box<TeamResult> },
+    /// The worker declined the assignment.
+    Rejected { reason: String },
+    /// The connection to the worker was lost. Never sent over the wire.
+    Disconnected,
+}
+
+/// Payload of a `TaskResult` envelope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RemoteTeamReply {
+    /// Progress of an assigned team.
+    Update {
+        task_id: String,
+        event: RemoteTeamEvent,
+    },
+}
+
+/// A peer selected to run a team.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemotePeer {
+    pub peer_id: String,
+    pub name: String,
+}
+
+// ---------------------------------------------------------------------------
+// Dispatcher
+// ---------------------------------------------------------------------------
+
+/// Ships team objectives to remote peers on behalf of the Queen.
+///
+/// Uses [`async_trait`] so the Queen can hold an `Arc<dyn TeamDispatcher>`.
+#[async_trait]
+pub trait TeamDispatcher: Send + Sync {
+    /// Pick a connected peer with spare capacity that serves every model in
+    /// `required_models` (`"auto"` matches any), or `None` to run the team
+    /// locally.
+    async fn select_peer(&self, required_models: &[String]) -> Option<RemotePeer>;
+
+    /// Send `assignment` to `peer`. Events stream back on the returned
+    /// channel until a `Finished`, `Rejected` or `Disconnected` event.
+    async fn dispatch(
+        &self,
+        peer: &RemotePeer,
+        assignment: RemoteTeamAssignment,
+    ) -> Result<mpsc::UnboundedReceiver<RemoteTeamEvent>, String>;
+
+    /// Ask `peer` to stop a previously dispatched team.
+    async fn cancel(&self, peer: &RemotePeer, task_id: &str);
+}
+
+/// A dispatch awaiting its result.
+struct PendingDispatch {
+    peer_id: PeerId,
+    events: mpsc::UnboundedSender<RemoteTeamEvent>,
+}
+
+/// [`TeamDispatcher`] backed by a running [`hive_network::HiveNode`].
+pub struct NetworkTeamDispatcher {
+    node: HiveNodeHandle,
+    /// Runtime the node runs on, so dispatches from other executors can
+    /// still spawn their liveness watch.
+    runtime: tokio::runtime::Handle,
+    /// Unix times of slots claimed per peer, held until the peer publishes
+    /// a capability document that reflects them.
+    claims: Mutex<HashMap<String, Vec<i64>>>,
+    pending: Arc<Mutex<HashMap<String, PendingDispatch>>>,
+}
+
+impl NetworkTeamDispatcher {
+    /// Create a dispatcher and register it for `TaskResult` envelopes on `node`.
+    ///
+    /// Must be called on the runtime that drives `node`.
+    pub async fn attach(node: HiveNodeHandle) -> Arc<Self> {
+        let dispatcher = Arc::new(Self {
+            node: node.clone(),
+            runtime: tokio::runtime::Handle::current(),
+            claims: Mutex::new(HashMap::new()),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        });
+
+        let weak = Arc::downgrade(&dispatcher);
+        node.on_message(
+            MessageKind::TaskResult,
+            Arc::new(move |envelope: Envelope| {
+                if let Some(dispatcher) = weak.upgrade() {
+                    dispatcher.handle_reply(envelope);
+                }
+                Box::pin(async { None })
+            }),
+        )
+        .await;
+
+        dispatcher
+    }
+
+    /// Forward a team update to the dispatch awaiting it.
+    fn handle_reply(&self, envelope: Envelope) {
+        let reply: RemoteTeamReply = match serde_json::from_value(envelope.payload) {
+            Ok(reply) => reply,
+            Err(e) => {
+                warn!("Malformed remote team reply from {}: {e}", envelope.from);
+                return;
+            }
+        };
+
+        match reply {
+            RemoteTeamReply::Update { task_id, event } => {
+                let Ok(mut pending) = self.pending.lock() else {
+                    return;
+                };
+                let Some(dispatch) = pending.get(&task_id) else {
+                    debug!("Update for unknown remote team {task_id}");
+                    return;
+                };
+                // Only the peer the team was assigned to may report on it.
+                if dispatch.peer_id != envelope.from {
+                    warn!(
+                        "Peer {} reported on remote team {task_id} it does not own",
+                        envelope.from
+                    );
+                    return;
+                }
+                let terminal = matches!(
+                    event,
+                    RemoteTeamEvent::Finished { .. } | RemoteTeamEvent::Rejected { .. }
+                );
+                let _ = dispatch.events.send(event);
+                if terminal {
+                    pending.remove(&task_id);
+                }
+            }
+        }
+    }
+
+    /// Watch `peer_id` and report a disconnect for `task_id` if it goes away.
+    fn spawn_liveness_watch(&self, task_id: String, peer_id: PeerId) {
+        let node = self.node.clone();
+        let pending = Arc::clone(&self.pending);
+        self.runtime.spawn(async move {
+            loop {
+                tokio::time::sleep(LIVENESS_INTERVAL).await;
+                let still_pending = pending
+                    .lock()
+                    .map(|p| p.contains_key(&task_id))
+                    .unwrap_or(false);
+                if !still_pending {
+                    return;
+                }
+                if !node.is_connected(&peer_id).await {
+                    if let Ok(mut pending) = pending.lock()
+                        && let Some(dispatch) = pending.remove(&task_id)
+                    {
+                        let _ = dispatch.events.send(RemoteTeamEvent::Disconnected);
+                    }
+                    return;
+                }
+            }
+        });
+    }
+
+    async fn send_request(
+        &self,
+        peer_id: &PeerId,
+        request: &RemoteTeamRequest,
+    ) -> Result<(), String> {
+        let payload = serde_json::to_value(request).map_err(|e| e.to_string())?;
+        let envelope = Envelope::new(
+            self.node.peer_id().clone(),
+            Some(peer_id.clone()),
+            MessageKind::TaskRequest,
+            payload,
+        );
+        self.node
+            .send_to_peer(peer_id, &envelope)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl TeamDispatcher for NetworkTeamDispatcher {
+    async fn select_peer(&self, required_models: &[String]) -> Option<RemotePeer> {
+        let ranked = self
+            .node
+            .rank_peers(&team_requirements(required_models))
+            .await;
+        let mut claims = self.claims.lock().ok()?;
+        let best = claim_peer(
+            &mut claims,
+            ranked,
+            required_models,
+            chrono::Utc::now().timestamp(),
+        )?;
+        Some(RemotePeer {
+            peer_id: best.id.as_str().to_string(),
+            name: best.identity.name.clone(),
+        })
+    }
+
+    async fn dispatch(
+        &self,
+        peer: &RemotePeer,
+        assignment: RemoteTeamAssignment,
+    ) -> Result<mpsc::UnboundedReceiver<RemoteTeamEvent>, String> {
+        let peer_id = PeerId::from_string(&peer.peer_id);
+        let task_id = assignment.task_id.clone();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(
+                task_id.clone(),
+                PendingDispatch {
+                    peer_id: peer_id.clone(),
+                    events: tx,
+                },
+            );
+        }
+
+        if let Err(e) = self
+            .send_request(&peer_id, &RemoteTeamRequest::Assign(assignment))
+            .await
+        {
+            if let Ok(mut pending) = self.pending.lock() {
+                pending.remove(&task_id);
+            }
+            return Err(format!("Failed to reach peer '{}': {e}", peer.name));
+        }
+
+        self.spawn_liveness_watch(task_id, peer_id);
+        Ok(rx)
+    }
+
+    async fn cancel(&self, peer: &RemotePeer, task_id: &str) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(task_id);
+        }
+        let request = RemoteTeamRequest::Cancel {
+            task_id: task_id.to_string(),
+        };
+        if let Err(e) = self
+            .send_request(&PeerId::from_string(&peer.peer_id), &request)
+            .await
+        {
+            debug!("Cancel for remote team {task_id} not delivered: {e}");
+        }
+    }
+}
+
+/// The best of `ranked` that serves every model in `required_models` and
+/// has a slot not already claimed, with that slot claimed at `now` until the
+/// worker reports it taken. The scheduler matches any one model; a team
+/// needs all of them.
+fn claim_peer(
+    claims: &mut HashMap<String, Vec<i64>>,
+    ranked: Vec<ScheduledPeer>,
+    required_models: &[String],
+    now: i64,
+) -> Option<PeerInfo> {
+    let best = ranked.into_iter().find_map(|scheduled| {
+        let doc = scheduled.peer.capabilities.as_ref()?;
+        let capacity = WorkerCapacity::from_document(doc);
+        let claimed = claims
+            .entry(scheduled.peer.id.as_str().to_string())
+            .or_default();
+        claimed.retain(|&at| !claim_reflected(at, doc));
+        (capacity.free_slots > claimed.len() && capacity.serves(required_models))
+            .then_some(scheduled.peer)
+    })?;
+    claims
+        .entry(best.id.as_str().to_string())
+        .or_default()
+        .push(now);
+    Some(best)
+}
+
+/// Whether `doc` was issued long enough after a slot claimed at `claimed_at`
+/// that the worker's advertised load already counts that team.
+fn claim_reflected(claimed_at: i64, doc: &CapabilityDocument) -> bool {
+    doc.issued_at >= claimed_at + ADVERTISE_INTERVAL.as_secs() as i64
+}
+
+/// Scheduler requirements for a team needing `required_models`: a peer with
+/// a free slot, per its signed capability document, that offers at least
+/// one of them. [`WorkerCapacity::serves`] narrows this to peers offering
+/// all of them.
+fn team_requirements(required_models: &[String]) -> TaskRequirements {
+    let models = required_models
+        .iter()
+        .filter(|m| m.as_str() != "auto")
+        .cloned()
+        .collect();
+    TaskRequirements {
+        require_spare_capacity: true,
+        ..task_requirements(models, &[])
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Worker
+// ---------------------------------------------------------------------------
+
+/// Work handed from the network handler to the worker loop.
+enum Inbound {
+    Assign {
+        from: PeerId,
+        assignment: RemoteTeamAssignment,
+    },
+    Cancel {
+        from: PeerId,
+        task_id: String,
+    },
+}
+
+/// Runs team objectives assigned by peers using a local executor.
+///
+/// Call [`attach`](Self::attach) to start accepting assignments, then drive
+/// [`run`](Self::run) for the lifetime of the node. Executor futures need not
+/// be `Send`, so `run` should be awaited directly rather than spawned.
+pub struct RemoteTeamWorker<E: AiExecutor> {
+    node: HiveNodeHandle,
+    executor: Arc<E>,
+    config: SwarmConfig,
+    max_slots: usize,
+    models: Vec<String>,
+    active: Arc<AtomicUsize>,
+    inbox_tx: mpsc::UnboundedSender<Inbound>,
+    inbox_rx: Mutex<Option<mpsc::UnboundedReceiver<Inbound>>>,
+}
+
+impl<E: AiExecutor + 'static> RemoteTeamWorker<E> {
+    /// Create a worker with a single slot that routes any model.
+    ///
+    /// `config` supplies the defaults (queen model, Fusion panel) for teams
+    /// run here; budget and time limits come from each assignment.
+    pub fn new(node: HiveNodeHandle, executor: Arc<E>, config: SwarmConfig) -> Self {
+        let (inbox_tx, inbox_rx) = mpsc::unbounded_channel();
+        Self {
+            node,
+            executor,
+            config,
+            max_slots: 1,
+            models: Vec::new(),
+            active: Arc::new(AtomicUsize::new(0)),
+            inbox_tx,
+            inbox_rx: Mutex::new(Some(inbox_rx)),
+        }
+    }
+
+    /// Run up to `slots` teams concurrently.
+    pub fn with_slots(mut self, slots: usize) -> Self {
+        self.max_slots = slots.max(1);
+        self
+    }
+
+    /// Only accept teams that require a subset of `models`.
+    pub fn with_models(mut self, models: Vec<String>) -> Self {
+        self.models = models;
+        self
+    }
+
+    /// The capacity currently advertised to peers.
+    pub fn capacity(&self) -> WorkerCapacity {
+        WorkerCapacity {
+            free_slots: self
+                .max_slots
+                .saturating_sub(self.active.load(Ordering::SeqCst)),
+            max_slots: self.max_slots,
+            models: self.models.clone(),
+        }
+    }
+
+    /// Register the worker for `TaskRequest` envelopes on its node.
+    ///
+    /// Assignments that need an unserved model or arrive while every slot is
+    /// busy are rejected immediately so the requester can fall back.
+    pub async fn attach(&self) {
+        let inbox = self.inbox_tx.clone();
+        let active = Arc::clone(&self.active);
+        let max_slots = self.max_slots;
+        let models = self.models.clone();
+        let our_id = self.node.peer_id().clone();
+
+        self.node
+            .on_message(
+                MessageKind::TaskRequest,
+                Arc::new(move |envelope: Envelope| {
+                    let from = envelope.from.clone();
+                    let rejection = match serde_json::from_value(envelope.payload) {
+                        Ok(RemoteTeamRequest::Cancel { task_id }) => {
+                            let _ = inbox.send(Inbound::Cancel {
+                                from: from.clone(),
+                                task_id,
+                            });
+                            None
+                        }
+                        Ok(RemoteTeamRequest::Assign(assignment)) => {
+                            let required = required_models(&assignment.objective);
+                            if !serves_models(&models, &required) {
+                                Some((assignment.task_id, "required model not served"))
+                            } else if !claim_slot(&active, max_slots) {
+                                Some((assignment.task_id, "no free slots"))
+                            } else {
+                                let _ = inbox.send(Inbound::Assign {
+                                    from: from.clone(),
+                                    assignment,
+                                });
+                                None
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Malformed remote team request from {from}: {e}");
+                            None
+                        }
+                    };
+
+                    let response = rejection.and_then(|(task_id, reason)| {
+                        reply_envelope(
+                            &our_id,
+                            &from,
+                            &RemoteTeamReply::Update {
+                                task_id,
+                                event: RemoteTeamEvent::Rejected {
+                                    reason: reason.into(),
+                                },
+                            },
+                        )
+                    });
+                    Box::pin(async move { response })
+                }),
+            )
+            .await;
+    }
+
+    /// Execute assignments and advertise capacity for the lifetime of the node.
+    ///
+    /// Returns immediately if called more than once.
+    pub async fn run(&self) {
+        let Some(mut inbox) = self.inbox_rx.lock().ok().and_then(|mut rx| rx.take()) else {
+            return;
+        };
+        let mut running = FuturesUnordered::new();
+        let mut cancels: HashMap<String, (PeerId, oneshot::Sender<()>)> = HashMap::new();
+        let mut tick = tokio::time::interval(ADVERTISE_INTERVAL);
+
+        loop {
+            tokio::select! {
+                inbound = inbox.recv() => match inbound {
+                    Some(Inbound::Assign { from, assignment }) => {
+                        let (cancel_tx, cancel_rx) = oneshot::channel();
+                        cancels.insert(assignment.task_id.clone(), (from.clone(), cancel_tx));
+                        running.push(self.execute(from, assignment, cancel_rx));
+                        self.advertise().await;
+                    }
+                    Some(Inbound::Cancel { from, task_id }) => {
+                        // Only the requester that assigned a team may cancel it.
+                        if cancels.get(&task_id).is_some_and(|(owner, _)| *owner == from)
+                            && let Some((_, cancel)) = cancels.remove(&task_id)
+                        {
+                            let _ = cancel.send(());
+                        }
+                    }
+                    None => break,
+                },
+                Some(task_id) = running.next(), if !running.is_empty() => {
+                    cancels.remove(&task_id);
+                    self.advertise().await;
+                }
+                _ = tick.tick() => self.advertise().await,
+            }
+        }
+    }
+
+    /// Record the current capacity as the load in the node's signed
+    /// capability document, published with the next heartbeat.
+    pub async fn advertise(&self) {
+        let capacity = self.capacity();
+        self.node
+            .set_load(LoadReport {
+                active_tasks: (capacity.max_slots - capacity.free_slots) as u32,
+                queue_depth: 0,
+                max_concurrent: capacity.max_slots as u32,
+            })
+            .await;
+    }
+
+    /// Run one assignment to completion, streaming updates to `from`.
+    async fn execute(
+        &self,
+        from: PeerId,
+        assignment: RemoteTeamAssignment,
+        cancel: oneshot::Receiver<()>,
+    ) -> String {
+        let task_id = assignment.task_id.clone();
+        let objective = assignment.objective;
+
+        self.send_update(
+            &from,
+            &task_id,
+            RemoteTeamEvent::Status {
+                status: TeamStatus::Running,
+                detail: format!("Running on '{}'", self.node.identity().name),
+                cost: 0.0,
+            },
+        )
+        .await;
+
+        let config = SwarmConfig {
+            total_cost_limit_usd: assignment.budget_usd,
+            per_team_cost_limit_usd: assignment.budget_usd,
+            total_time_limit_secs: assignment.time_limit_secs,
+            per_team_time_limit_secs: assignment.time_limit_secs,
+            auto_routing: assignment.auto_routing,
+            ..self.config.clone()
+        };
+        let queen = Queen::new(config, Arc::clone(&self.executor));
+        let limit = Duration::from_secs(assignment.time_limit_secs);
+
+        let result = tokio::select! {
+            outcome = tokio::time::timeout(limit, queen.run_team(&objective)) => match outcome {
+                Ok(result) => result,
+                Err(_) => failed_result(&objective, "Team time limit reached on worker"),
+            },
+            _ = cancel => failed_result(&objective, "Cancelled by requester"),
+        };
+
+        self.active.fetch_sub(1, Ordering::SeqCst);
+        let finished = RemoteTeamEvent::Finished {
+            result: Box::new(result),
+        };
+        self.send_update(&from, &task_id, finished).await;
+        task_id
+    }
+
+    async fn send_update(&self, to: &PeerId, task_id: &str, event: RemoteTeamEvent) {
+        let reply = RemoteTeamReply::Update {
+            task_id: task_id.to_string(),
+            event,
+        };
+        if let Some(envelope) = reply_envelope(self.node.peer_id(), to, &reply)
+            && let Err(e) = self.node.send_to_peer(to, &envelope).await
+        {
+            warn!("Failed to report remote team {task_id} to {to}: {e}");
+        }
+    }
+}
+
+/// Scheduler requirements for a task that must run on one of `models` with
+/// every capability in `capabilities`.
+///
+/// Pass the result to `HiveNodeHandle::schedule` to pick a peer.
+pub fn task_requirements(
+    models: Vec<String>,
+    capabilities: &[ModelCapability],
+) -> TaskRequirements {
+    TaskRequirements {
+        models,
+        capabilities: capabilities
+            .iter()
+            .filter_map(|c| serde_json::to_value(c).ok())
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// Reserve a slot if one is free.
+fn claim_slot(active: &AtomicUsize, max_slots: usize) -> bool {
+    active
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+            (n < max_slots).then_some(n + 1)
+        })
+        .is_ok()
+}
+
+fn reply_envelope(from: &PeerId, to: &PeerId, reply: &RemoteTeamReply) -> Option<Envelope> {
+    let payload = serde_json::to_value(reply).ok()?;
+    Some(Envelope::new(
+        from.clone(),
+        Some(to.clone()),
+        MessageKind::TaskResult,
+        payload,
+    ))
+}
+
+fn failed_result(objective: &TeamObjective, error: &str) -> TeamResult {
+    TeamResult {
+        team_id: objective.id.clone(),
+        team_name: objective.name.clone(),
+        status: TeamStatus::Failed,
+        inner: None,
+        cost: 0.0,
+        duration_ms: 0,
+        insights: vec![],
+        error: Some(error.into()),
+    }
+}
+
+/// Models a worker must serve to run `objective`.
+pub fn required_models(objective: &TeamObjective) -> Vec<String> {
+    objective.preferred_model.iter().cloned().collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swarm::OrchestrationMode;
+    use hive_network::{ModelOffer, NodeIdentity, PeerState, ProviderOffer};
+
+    fn objective(model: Option<&str>) -> TeamObjective {
+        TeamObjective {
+            id: "team-1".into(),
+            name: "Research".into(),
+            description: "Investigate".into(),
+            dependencies: vec![],
+            orchestration_mode: OrchestrationMode::SingleShot,
+            scope_paths: vec![],
+            priority: 0,
+            preferred_model: model.map(Into::into),
+        }
+    }
+
+    fn capacity(free_slots: usize, models: &[&str]) -> WorkerCapacity {
+        WorkerCapacity {
+            free_slots,
+            max_slots: 4,
+            models: models.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn task_requirements_use_capability_names() {
+        let req = task_requirements(
+            vec!["llama3.1:8b".into()],
+            &[ModelCapability::ToolUse, ModelCapability::Vision],
+        );
+        assert_eq!(req.models, vec!["llama3.1:8b"]);
+        assert_eq!(req.capabilities, vec!["tool_use", "vision"]);
+    }
+
+    #[test]
+    fn capacity_serves_required_models() {
+        let cap = capacity(1, &["llama3", "qwen2.5-coder"]);
+        assert!(cap.serves(&[]));
+        assert!(cap.serves(&["llama3".into()]));
+        assert!(cap.serves(&["auto".into()]));
+        assert!(!cap.serves(&["gpt-4o".into()]));
+        assert!(capacity(1, &[]).serves(&["gpt-4o".into()]));
+    }
+
+    #[test]
+    fn team_requirements_need_a_free_slot() {
+        let req = team_requirements(&["auto".into(), "llama3:70b".into()]);
+        assert_eq!(req.models, vec!["llama3:70b"]);
+        assert!(req.require_spare_capacity);
+        assert!(team_requirements(&[]).models.is_empty());
+    }
+
+    fn scheduled(name: &str, models: &[&str], free: u32, issued_at: i64) -> ScheduledPeer {
+        let identity = NodeIdentity::generate(name);
+        let mut doc = CapabilityDocument::new(identity.peer_id.clone());
+        doc.providers = vec![ProviderOffer {
+            name: "ollama".into(),
+            local: true,
+            models: models.iter().map(|m| ModelOffer::local(*m)).collect(),
+        }];
+        doc.load = LoadReport {
+            active_tasks: 2 - free,
+            queue_depth: 0,
+            max_concurrent: 2,
+        };
+        doc.issued_at = issued_at;
+        ScheduledPeer {
+            peer: PeerInfo {
+                id: identity.peer_id.clone(),
+                identity,
+                addr: "127.0.0.1:9470".parse().unwrap(),
+                state: PeerState::Connected,
+                connected_at: None,
+                last_seen: chrono::Utc::now(),
+                latency_ms: None,
+                capabilities: Some(doc),
+            },
+            model: models[0].to_string(),
+            score: 0.0,
+        }
+    }
+
+    #[test]
+    fn claim_peer_needs_every_model() {
+        let partial = scheduled("partial", &["phi3"], 2, 0);
+        let full = scheduled("full", &["phi3", "llama3:70b"], 1, 0);
+        let panel = ["phi3".to_string(), "llama3:70b".to_string()];
+        let mut claims = HashMap::new();
+
+        let chosen = claim_peer(&mut claims, vec![partial.clone(), full.clone()], &panel, 0);
+        assert_eq!(chosen.unwrap().identity.name, "full");
+        let chosen = claim_peer(&mut claims, vec![partial], &["phi3".into()], 0);
+        assert_eq!(chosen.unwrap().identity.name, "partial");
+    }
+
+    #[test]
+    fn claim_peer_reserves_slots_until_reported() {
+        let peer = scheduled("gpu", &["llama3"], 1, 100);
+        let mut claims = HashMap::new();
+
+        assert!(claim_peer(&mut claims, vec![peer.clone()], &[], 100).is_some());
+        // The last slot is claimed; the same document cannot hand it out again.
+        assert!(claim_peer(&mut claims, vec![peer.clone()], &[], 101).is_none());
+
+        // A later document reports the team running and a slot free again.
+        let later = 100 + ADVERTISE_INTERVAL.as_secs() as i64;
+        let refreshed = scheduled("gpu", &["llama3"], 1, later);
+        let refreshed = ScheduledPeer {
+            peer: PeerInfo {
+                id: peer.peer.id.clone(),
+                ..refreshed.peer
+            },
+            ..refreshed
+        };
+        assert!(claim_peer(&mut claims, vec![refreshed], &[], later).is_some());
+    }
+
+    #[test]
+    fn claim_slot_respects_limit() {
+        let active = AtomicUsize::new(0);
+        assert!(claim_slot(&active, 2));
+        assert!(claim_slot(&active, 2));
+        assert!(!claim_slot(&active, 2));
+        assert_eq!(active.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn required_models_from_preferred_model() {
+        assert!(required_models(&objective(None)).is_empty());
+        assert_eq!(required_models(&objective(Some("llama3"))), vec!["llama3"]);
+    }
+
+    #[test]
+    fn wire_types_roundtrip() {
+        let request = RemoteTeamRequest::Assign(RemoteTeamAssignment {
+            task_id: "t-1".into(),
+            objective: objective(Some("llama3")),
+            budget_usd: 1.5,
+            time_limit_secs: 300,
+            auto_routing: true,
+        });
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["type"], "assign");
+        match serde_json::from_value::<RemoteTeamRequest>(json).unwrap() {
+            RemoteTeamRequest::Assign(a) => {
+                assert_eq!(a.task_id, "t-1");
+                assert_eq!(a.objective.preferred_model.as_deref(), Some("llama3"));
+            }
+            other => panic!("unexpected request: {other:?}"),
+        }
+
+        let reply = RemoteTeamReply::Update {
+            task_id: "t-1".into(),
+            event: RemoteTeamEvent::Status {
+                status: TeamStatus::Running,
+                detail: "Running on 'gpu'".into(),
+                cost: 0.0,
+            },
+        };
+        let json = serde_json::to_string(&reply).unwrap();
+        match serde_json::from_str::<RemoteTeamReply>(&json).unwrap() {
+            RemoteTeamReply::Update {
+                event: RemoteTeamEvent::Status { status, .. },
+                ..
+            } => assert_eq!(status, TeamStatus::Running),
+            other => panic!("unexpected reply: {other:?}"),
+        }
+    }
+}
@@ -107,6 +107,10 @@ pub struct SwarmConfig {
     /// the [`SwarmConfig::queen_model`] is used as the judge.
     #[serde(default)]
     pub fusion_judge: Option<String>,
+    /// Workspace the swarm runs in, propagated to each Coordinator team for
+    /// workspace-scoped approval policies.
+    #[serde(default)]
+    pub workspace: Option<String>,
 }
 
 impl Default for SwarmConfig {
@@ -121,6 +125,7 @@ impl Default for SwarmConfig {
             auto_routing: true,
             fusion_panel: Vec::new(),
             fusion_judge: None,
+            workspace: None,
         }
     }
 }
@@ -280,6 +285,12 @@ pub enum SwarmStatus {
     TeamCompleted,
     TeamFailed,
     CrossTeamSync,
+    /// A team was handed to a federated peer.
+    TeamDelegated,
+    /// A status update streamed back from a delegated team.
+    TeamProgress,
+    /// A delegated team is re-running locally after its peer went away.
+    TeamFailover,
 }
 
 /// Complete result of a swarm orchestration run.
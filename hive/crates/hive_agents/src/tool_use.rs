@@ -6,9 +6,12 @@ use hive_core::SecurityGateway;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
 use tracing::debug;
 
+use crate::content_guard::ContentGuard;
 use crate::message_queue::{AgentMessage, SharedMessageQueue};
+use hive_fs::{Checkpoint, CheckpointStore, CheckpointTurn};
 use hive_terminal::SharedSandbox;
 
 // ---------------------------------------------------------------------------
@@ -100,7 +103,29 @@ impl ToolHandler for ReadFileTool {
 }
 
 /// Writes content to a file given `path` and `content` arguments.
-pub struct WriteFileTool;
+pub struct WriteFileTool {
+    checkpoints: Option<Arc<CheckpointStore>>,
+}
+
+impl Default for WriteFileTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WriteFileTool {
+    pub fn new() -> Self {
+        Self { checkpoints: None }
+    }
+
+    /// Create a `WriteFileTool` that announces each write to `store`, so a
+    /// turn armed on it checkpoints the workspace before the first write.
+    pub fn with_checkpoints(store: Arc<CheckpointStore>) -> Self {
+        Self {
+            checkpoints: Some(store),
+        }
+    }
+}
 
 impl ToolHandler for WriteFileTool {
     fn name(&self) -> &str {
@@ -133,6 +158,9 @@ impl ToolHandler for WriteFileTool {
             .ok_or_else(|| "Missing required argument: content".to_string())?;
 
         let path = Path::new(path_str);
+        if let Some(store) = &self.checkpoints {
+            store.before_write(path).map_err(|e| format!("{e}"))?;
+        }
         hive_fs::FileService::write_file(path, content).map_err(|e| format!("{e}"))?;
         Ok(format!(
             "Successfully wrote {} bytes to {path_str}",
@@ -184,7 +212,44 @@ impl ToolHandler for ListDirectoryTool {
     }
 }
 
-/// Searches for a regex pattern across files in a directory.
+/// Schema properties shared by `search_files` and `replace_in_files`.
+fn search_option_properties() -> serde_json::Value {
+    serde_json::json!({
+        "path": { "type": "string", "description": "The directory to search in (default .)" },
+        "mode": {
+            "type": "string",
+            "enum": ["regex", "literal", "whole_word", "fuzzy_filename"],
+            "description": "How the pattern is matched (default regex)"
+        },
+        "case_sensitive": { "type": "boolean", "description": "Match case (default true)" },
+        "file_pattern": { "type": "string", "description": "Only search files matching this glob" }
+    })
+}
+
+/// Parse the arguments described by [`search_option_properties`].
+fn search_options_from_args(args: &serde_json::Value) -> Result<hive_fs::SearchOptions, String> {
+    let mode = match args.get("mode").and_then(|v| v.as_str()).unwrap_or("regex") {
+        "regex" => hive_fs::SearchMode::Regex,
+        "literal" => hive_fs::SearchMode::Literal,
+        "whole_word" => hive_fs::SearchMode::WholeWord,
+        "fuzzy_filename" => hive_fs::SearchMode::FuzzyFilename,
+        other => return Err(format!("Unknown search mode: {other}")),
+    };
+    Ok(hive_fs::SearchOptions {
+        mode,
+        case_sensitive: args
+            .get("case_sensitive")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true),
+        file_pattern: args
+            .get("file_pattern")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        ..Default::default()
+    })
+}
+
+/// Searches for a pattern across files in a directory.
 pub struct SearchFilesTool;
 
 impl ToolHandler for SearchFilesTool {
@@ -193,17 +258,24 @@ impl ToolHandler for SearchFilesTool {
     }
 
     fn description(&self) -> &str {
-        "Search for a regex pattern across files in a directory."
+        "Search for a pattern across files in a directory, or for file names with mode fuzzy_filename."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
+        let mut properties = search_option_properties();
+        properties["pattern"] =
+            serde_json::json!({ "type": "string", "description": "The search pattern" });
+        properties["max_results"] = serde_json::json!({
+            "type": "integer",
+            "description": "Maximum number of results (default 50)"
+        });
+        properties["context_lines"] = serde_json::json!({
+            "type": "integer",
+            "description": "Lines of context around each match (default 0)"
+        });
         serde_json::json!({
             "type": "object",
-            "properties": {
-                "pattern": { "type": "string", "description": "The search pattern (regex)" },
-                "path": { "type": "string", "description": "The directory to search in" },
-                "max_results": { "type": "integer", "description": "Maximum number of results (default 50)" }
-            },
+            "properties": properties,
             "required": ["pattern"]
         })
     }
@@ -214,28 +286,44 @@ impl ToolHandler for SearchFilesTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| "Missing required argument: pattern".to_string())?;
         let path_str = args.get("path").and_then(|v| v.as_str()).unwrap_or(".");
-        let max_results = args
-            .get("max_results")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(50) as usize;
-
-        let path = Path::new(path_str);
         let options = hive_fs::SearchOptions {
-            max_results,
-            ..Default::default()
+            max_results: args
+                .get("max_results")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(50) as usize,
+            context_lines: args
+                .get("context_lines")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as usize,
+            ..search_options_from_args(&args)?
         };
 
+        let path = Path::new(path_str);
         let results =
             hive_fs::SearchService::search(path, pattern, options).map_err(|e| format!("{e}"))?;
 
         let mut output = String::new();
         for result in &results {
+            if result.line_number == 0 {
+                // Fuzzy filename match.
+                output.push_str(&format!("{}\n", result.path.display()));
+                continue;
+            }
+            for (offset, line) in result.context_before.iter().enumerate() {
+                let number = result.line_number - result.context_before.len() + offset;
+                output.push_str(&format!("{}-{number}- {line}\n", result.path.display()));
+            }
             output.push_str(&format!(
                 "{}:{}: {}\n",
                 result.path.display(),
                 result.line_number,
                 result.line_content,
             ));
+            let last = result.line_number + result.line_content.lines().count().max(1) - 1;
+            for (offset, line) in result.context_after.iter().enumerate() {
+                let number = last + 1 + offset;
+                output.push_str(&format!("{}-{number}- {line}\n", result.path.display()));
+            }
         }
         if output.is_empty() {
             output.push_str("No matches found.\n");
@@ -244,6 +332,132 @@ impl ToolHandler for SearchFilesTool {
     }
 }
 
+/// Replaces a pattern across files in a directory. Previews by default;
+/// writes only when `apply` is set.
+pub struct ReplaceInFilesTool {
+    checkpoints: Option<Arc<CheckpointStore>>,
+}
+
+impl Default for ReplaceInFilesTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReplaceInFilesTool {
+    pub fn new() -> Self {
+        Self { checkpoints: None }
+    }
+
+    /// Create a `ReplaceInFilesTool` that checkpoints the workspace in
+    /// `store` before applying a replacement.
+    pub fn with_checkpoints(store: Arc<CheckpointStore>) -> Self {
+        Self {
+            checkpoints: Some(store),
+        }
+    }
+
+    /// Compute the replacement a call with `args` would make, without
+    /// writing anything.
+    pub fn preview(args: &serde_json::Value) -> Result<hive_fs::ReplacePreview, String> {
+        let pattern = args
+            .get("pattern")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| "Missing required argument: pattern".to_string())?;
+        let replacement = args
+            .get("replacement")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| "Missing required argument: replacement".to_string())?;
+        let path_str = args.get("path").and_then(|v| v.as_str()).unwrap_or(".");
+        let options = search_options_from_args(args)?;
+
+        hive_fs::SearchService::preview_replace(Path::new(path_str), pattern, replacement, options)
+            .map_err(|e| format!("{e}"))
+    }
+}
+
+/// Edits shown per file when previewing a replacement.
+const REPLACE_PREVIEW_EDITS: usize = 5;
+
+impl ToolHandler for ReplaceInFilesTool {
+    fn name(&self) -> &str {
+        "replace_in_files"
+    }
+
+    fn description(&self) -> &str {
+        "Replace a pattern across files in a directory. Shows a preview unless apply is true; \
+         in regex mode the replacement may use $1 or ${name} capture groups."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        let mut properties = search_option_properties();
+        properties["pattern"] =
+            serde_json::json!({ "type": "string", "description": "The pattern to replace" });
+        properties["replacement"] =
+            serde_json::json!({ "type": "string", "description": "The replacement text" });
+        properties["apply"] = serde_json::json!({
+            "type": "boolean",
+            "description": "Write the changes instead of previewing them (default false)"
+        });
+        serde_json::json!({
+            "type": "object",
+            "properties": properties,
+            "required": ["pattern", "replacement"]
+        })
+    }
+
+    fn execute(&self, args: serde_json::Value) -> Result<String, String> {
+        let apply = args.get("apply").and_then(|v| v.as_bool()).unwrap_or(false);
+        let preview = Self::preview(&args)?;
+        if preview.files.is_empty() {
+            return Ok("No matches found.\n".to_string());
+        }
+
+        if apply {
+            let outcome =
+                hive_fs::SearchService::apply_replace(&preview, self.checkpoints.as_deref())
+                    .map_err(|e| format!("{e}"))?;
+            let mut output = format!(
+                "Replaced {} match(es) in {} file(s).\n",
+                outcome.replacements, outcome.files_changed
+            );
+            if let Some(checkpoint) = outcome.checkpoint {
+                output.push_str(&format!("Checkpoint {} can undo this.\n", checkpoint.id));
+            }
+            return Ok(output);
+        }
+
+        let mut output = format!(
+            "Would replace {} match(es) in {} file(s):\n",
+            preview.total_replacements(),
+            preview.files.len()
+        );
+        for file in &preview.files {
+            output.push_str(&format!(
+                "\n{} ({} match(es))\n",
+                file.path.display(),
+                file.replacements
+            ));
+            for edit in file.edits.iter().take(REPLACE_PREVIEW_EDITS) {
+                for line in edit.before.lines() {
+                    output.push_str(&format!("{}- {line}\n", edit.line_number));
+                }
+                for line in edit.after.lines() {
+                    output.push_str(&format!("{}+ {line}\n", edit.line_number));
+                }
+            }
+            if file.edits.len() > REPLACE_PREVIEW_EDITS {
+                output.push_str(&format!(
+                    "... {} more\n",
+                    file.edits.len() - REPLACE_PREVIEW_EDITS
+                ));
+            }
+        }
+        output.push_str("\nCall again with apply: true to write these changes.\n");
+        Ok(output)
+    }
+}
+
 /// Executes a shell command through the SecurityGateway.
 pub struct ExecuteCommandTool {
     security: SecurityGateway,
@@ -885,9 +1099,10 @@ impl ToolRegistry {
 pub fn builtin_tool_definitions() -> Vec<ToolDefinition> {
     let handlers: Vec<Box<dyn ToolHandler>> = vec![
         Box::new(ReadFileTool),
-        Box::new(WriteFileTool),
+        Box::new(WriteFileTool::new()),
         Box::new(ListDirectoryTool),
         Box::new(SearchFilesTool),
+        Box::new(ReplaceInFilesTool::new()),
         Box::new(ExecuteCommandTool::new()),
         Box::new(GitStatusTool),
         Box::new(GitDiffTool),
@@ -910,9 +1125,10 @@ pub fn builtin_tool_definitions() -> Vec<ToolDefinition> {
 pub fn builtin_registry() -> ToolRegistry {
     let mut registry = ToolRegistry::new();
     registry.register_tool(Box::new(ReadFileTool));
-    registry.register_tool(Box::new(WriteFileTool));
+    registry.register_tool(Box::new(WriteFileTool::new()));
     registry.register_tool(Box::new(ListDirectoryTool));
     registry.register_tool(Box::new(SearchFilesTool));
+    registry.register_tool(Box::new(ReplaceInFilesTool::new()));
     registry.register_tool(Box::new(ExecuteCommandTool::new()));
     registry.register_tool(Box::new(GitStatusTool));
     registry.register_tool(Box::new(GitDiffTool));
@@ -929,9 +1145,10 @@ pub fn builtin_registry() -> ToolRegistry {
 pub fn builtin_registry_with_sandbox(sandbox: SharedSandbox) -> ToolRegistry {
     let mut registry = ToolRegistry::new();
     registry.register_tool(Box::new(ReadFileTool));
-    registry.register_tool(Box::new(WriteFileTool));
+    registry.register_tool(Box::new(WriteFileTool::new()));
     registry.register_tool(Box::new(ListDirectoryTool));
     registry.register_tool(Box::new(SearchFilesTool));
+    registry.register_tool(Box::new(ReplaceInFilesTool::new()));
     registry.register_tool(Box::new(ExecuteCommandTool::with_sandbox(sandbox)));
     registry.register_tool(Box::new(GitStatusTool));
     registry.register_tool(Box::new(GitDiffTool));
@@ -952,12 +1169,20 @@ pub fn builtin_registry_with_sandbox(sandbox: SharedSandbox) -> ToolRegistry {
 /// 2. Executes each tool via the registry
 /// 3. Returns formatted results ready to be sent back to the AI
 /// 4. Tracks iteration count to enforce `max_iterations`
+///
+/// With a [`ContentGuard`] attached, results are wrapped as untrusted data
+/// and high-risk calls made after untrusted content entered the context are
+/// held until [`release`](Self::release)d or [`reject`](Self::reject)ed.
 pub struct ToolExecutor {
     registry: ToolRegistry,
     max_iterations: usize,
     current_iteration: usize,
     total_calls: usize,
     message_queue: Option<SharedMessageQueue>,
+    content_guard: Option<ContentGuard>,
+    held: Vec<ToolCall>,
+    checkpoints: Option<(Arc<CheckpointStore>, String)>,
+    last_checkpoint: Option<Checkpoint>,
 }
 
 impl ToolExecutor {
@@ -968,7 +1193,84 @@ impl ToolExecutor {
             current_iteration: 0,
             total_calls: 0,
             message_queue: None,
+            content_guard: None,
+            held: Vec::new(),
+            checkpoints: None,
+            last_checkpoint: None,
+        }
+    }
+
+    /// Checkpoint the workspace before the first file write of each round of
+    /// tool calls, on the timeline of `conversation_id`. Registered
+    /// `write_file` and `replace_in_files` tools are replaced by ones that
+    /// announce their writes to `store`.
+    pub fn with_checkpoints(mut self, store: Arc<CheckpointStore>, conversation_id: &str) -> Self {
+        if self.registry.has_tool("write_file") {
+            let tool = WriteFileTool::with_checkpoints(Arc::clone(&store));
+            self.registry.register_tool(Box::new(tool));
+        }
+        if self.registry.has_tool("replace_in_files") {
+            let tool = ReplaceInFilesTool::with_checkpoints(Arc::clone(&store));
+            self.registry.register_tool(Box::new(tool));
+        }
+        self.checkpoints = Some((store, conversation_id.to_string()));
+        self
+    }
+
+    /// The checkpoint taken before the most recent round that wrote files.
+    pub fn last_checkpoint(&self) -> Option<&Checkpoint> {
+        self.last_checkpoint.as_ref()
+    }
+
+    fn begin_checkpoint_turn(&self, label: &str) -> Option<CheckpointTurn> {
+        let (store, conversation_id) = self.checkpoints.as_ref()?;
+        Some(store.begin_turn(conversation_id, label))
+    }
+
+    fn end_checkpoint_turn(&mut self, turn: Option<CheckpointTurn>) {
+        if let Some(checkpoint) = turn.and_then(|turn| turn.checkpoint()) {
+            self.last_checkpoint = Some(checkpoint);
+        }
+    }
+
+    /// Guard tool results against indirect prompt injection.
+    pub fn with_content_guard(mut self, guard: ContentGuard) -> Self {
+        self.content_guard = Some(guard);
+        self
+    }
+
+    /// The attached content guard, if any.
+    pub fn content_guard(&self) -> Option<&ContentGuard> {
+        self.content_guard.as_ref()
+    }
+
+    /// Calls held for approval because the context is tainted.
+    pub fn held_calls(&self) -> &[ToolCall] {
+        &self.held
+    }
+
+    /// Execute a held call after the user approved it.
+    pub fn release(&mut self, call_id: &str) -> Option<ToolResult> {
+        let pos = self.held.iter().position(|c| c.id == call_id)?;
+        let call = self.held.remove(pos);
+        let turn = self.begin_checkpoint_turn(&format!("Before approved {}", call.name));
+        let mut result = self.registry.execute(&call);
+        self.end_checkpoint_turn(turn);
+        if let Some(guard) = self.content_guard.as_mut() {
+            guard.guard_tool_result(&call, &mut result);
         }
+        Some(result)
+    }
+
+    /// Drop a held call after the user rejected it.
+    pub fn reject(&mut self, call_id: &str) -> Option<ToolResult> {
+        let pos = self.held.iter().position(|c| c.id == call_id)?;
+        let call = self.held.remove(pos);
+        Some(ToolResult {
+            tool_use_id: call.id,
+            content: format!("User rejected {}. Do not retry without asking.", call.name),
+            is_error: true,
+        })
     }
 
     /// Attach a shared message queue for steering/follow-up support.
@@ -1042,7 +1344,12 @@ impl ToolExecutor {
         if calls.is_empty() {
             return None;
         }
+        Some(self.execute_calls(calls))
+    }
 
+    /// Execute one round of tool calls that were already parsed, e.g. from
+    /// a streamed response. Does not check the iteration limit.
+    pub fn execute_calls(&mut self, calls: Vec<ToolCall>) -> Vec<ToolResult> {
         self.current_iteration += 1;
         self.total_calls += calls.len();
 
@@ -1053,8 +1360,35 @@ impl ToolExecutor {
             "Executing tool calls"
         );
 
-        let results = self.registry.execute_all(&calls);
-        Some(results)
+        let turn =
+            self.begin_checkpoint_turn(&format!("Before tool round {}", self.current_iteration));
+        let results = self.execute_round(calls);
+        self.end_checkpoint_turn(turn);
+        results
+    }
+
+    fn execute_round(&mut self, calls: Vec<ToolCall>) -> Vec<ToolResult> {
+        let Some(guard) = self.content_guard.as_mut() else {
+            return self.registry.execute_all(&calls);
+        };
+
+        let mut results = Vec::with_capacity(calls.len());
+        for call in calls {
+            if let Some(reason) = guard.approval_reason(&call.name) {
+                debug!(tool = %call.name, "Holding tool call for approval: {reason}");
+                results.push(ToolResult {
+                    tool_use_id: call.id.clone(),
+                    content: format!("Held for user approval: {reason}"),
+                    is_error: true,
+                });
+                self.held.push(call);
+                continue;
+            }
+            let mut result = self.registry.execute(&call);
+            guard.guard_tool_result(&call, &mut result);
+            results.push(result);
+        }
+        results
     }
 
     /// Format results for Anthropic's API (array of tool_result content blocks).
@@ -1086,6 +1420,11 @@ impl ToolExecutor {
     pub fn reset(&mut self) {
         self.current_iteration = 0;
         self.total_calls = 0;
+        self.held.clear();
+        self.last_checkpoint = None;
+        if let Some(guard) = self.content_guard.as_mut() {
+            guard.reset();
+        }
     }
 }
 
@@ -1588,12 +1927,13 @@ mod tests {
     #[test]
     fn test_builtin_definitions_count() {
         let defs = builtin_tool_definitions();
-        assert_eq!(defs.len(), 10);
+        assert_eq!(defs.len(), 11);
         let names: Vec<&str> = defs.iter().map(|d| d.name.as_str()).collect();
         assert!(names.contains(&"read_file"));
         assert!(names.contains(&"write_file"));
         assert!(names.contains(&"execute_command"));
         assert!(names.contains(&"search_files"));
+        assert!(names.contains(&"replace_in_files"));
         assert!(names.contains(&"list_directory"));
         assert!(names.contains(&"git_status"));
         assert!(names.contains(&"git_diff"));
@@ -1605,11 +1945,12 @@ mod tests {
     #[test]
     fn test_builtin_registry_has_all_tools() {
         let registry = builtin_registry();
-        assert_eq!(registry.len(), 10);
+        assert_eq!(registry.len(), 11);
         assert!(registry.has_tool("read_file"));
         assert!(registry.has_tool("write_file"));
         assert!(registry.has_tool("list_directory"));
         assert!(registry.has_tool("search_files"));
+        assert!(registry.has_tool("replace_in_files"));
         assert!(registry.has_tool("execute_command"));
         assert!(registry.has_tool("git_status"));
         assert!(registry.has_tool("git_diff"));
@@ -1713,6 +2054,83 @@ mod tests {
         assert!(results.is_some());
     }
 
+    #[test]
+    fn test_executor_holds_high_risk_calls_once_tainted() {
+        let mut registry = ToolRegistry::new();
+        registry.register_tool(Box::new(EchoTool));
+        registry.register(
+            ToolDefinition {
+                name: "execute_command".into(),
+                description: "Run a command".into(),
+                input_schema: serde_json::json!({ "type": "object" }),
+            },
+            |_| Ok("ran".to_string()),
+        );
+
+        let mut executor = ToolExecutor::new(registry, 10).with_content_guard(ContentGuard::new());
+        let response = serde_json::json!({
+            "content": [
+                { "type": "tool_use", "id": "t1", "name": "echo", "input": { "text": "page" } },
+                { "type": "tool_use", "id": "t2", "name": "execute_command", "input": {} }
+            ],
+            "stop_reason": "tool_use"
+        });
+
+        let results = executor.process_response(&response).unwrap();
+        assert!(
+            results[0]
+                .content
+                .starts_with("<untrusted_content source=\"echo\"")
+        );
+        assert!(results[1].is_error);
+        assert!(results[1].content.starts_with("Held for user approval"));
+        assert_eq!(executor.held_calls().len(), 1);
+        assert!(executor.content_guard().unwrap().is_tainted());
+
+        let released = executor.release("t2").unwrap();
+        assert!(!released.is_error);
+        assert!(executor.held_calls().is_empty());
+        assert!(executor.reject("t2").is_none());
+    }
+
+    #[test]
+    fn test_executor_checkpoints_before_writes() {
+        let workspace = tempfile::tempdir().unwrap();
+        let storage = tempfile::tempdir().unwrap();
+        let store = Arc::new(CheckpointStore::open_at(workspace.path(), storage.path()).unwrap());
+        let file = workspace.path().join("main.rs");
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+
+        let mut registry = ToolRegistry::new();
+        registry.register_tool(Box::new(EchoTool));
+        registry.register_tool(Box::new(WriteFileTool::new()));
+        let mut executor =
+            ToolExecutor::new(registry, 10).with_checkpoints(Arc::clone(&store), "conv-1");
+
+        // Rounds without writes take no checkpoint.
+        executor.process_response(&sample_anthropic_response());
+        assert!(executor.last_checkpoint().is_none());
+
+        let response = serde_json::json!({
+            "content": [{
+                "type": "tool_use",
+                "id": "w1",
+                "name": "write_file",
+                "input": { "path": file.to_str().unwrap(), "content": "broken" }
+            }],
+            "stop_reason": "tool_use"
+        });
+        let results = executor.process_response(&response).unwrap();
+        assert!(!results[0].is_error, "{}", results[0].content);
+
+        let checkpoint = executor.last_checkpoint().unwrap().clone();
+        assert_eq!(checkpoint.label, "Before tool round 2");
+        assert_eq!(store.timeline("conv-1").unwrap(), [checkpoint.clone()]);
+
+        store.restore(&checkpoint.id).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "fn main() {}\n");
+    }
+
     #[test]
     fn test_executor_format_results_anthropic() {
         let results = vec![
@@ -1934,7 +2352,7 @@ mod tests {
 
     #[test]
     fn test_write_file_tool_missing_args() {
-        let tool = WriteFileTool;
+        let tool = WriteFileTool::new();
         let result = tool.execute(serde_json::json!({}));
         assert!(result.is_err());
         assert!(
@@ -1976,6 +2394,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_search_files_tool_literal_mode_with_context() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "before\nfoo(1)\nafter\n").unwrap();
+        let result = SearchFilesTool
+            .execute(serde_json::json!({
+                "pattern": "foo(1)",
+                "path": dir.path().to_str().unwrap(),
+                "mode": "literal",
+                "context_lines": 1
+            }))
+            .unwrap();
+        assert!(result.contains(":2: foo(1)"));
+        assert!(result.contains("-1- before"));
+        assert!(result.contains("-3- after"));
+    }
+
+    #[test]
+    fn test_search_files_tool_rejects_unknown_mode() {
+        let result = SearchFilesTool.execute(serde_json::json!({
+            "pattern": "x",
+            "mode": "telepathy"
+        }));
+        assert!(result.unwrap_err().contains("Unknown search mode"));
+    }
+
+    #[test]
+    fn test_replace_in_files_tool_previews_then_applies() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.rs");
+        std::fs::write(&file, "let old = 1;\nold += 1;\n").unwrap();
+        let tool = ReplaceInFilesTool::new();
+        let args = serde_json::json!({
+            "pattern": "old",
+            "replacement": "new",
+            "path": dir.path().to_str().unwrap(),
+            "mode": "whole_word"
+        });
+
+        let preview = tool.execute(args.clone()).unwrap();
+        assert!(preview.contains("Would replace 2 match(es) in 1 file(s)"));
+        assert!(preview.contains("1+ let new = 1;"));
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "let old = 1;\nold += 1;\n"
+        );
+
+        let mut apply = args;
+        apply["apply"] = serde_json::json!(true);
+        let applied = tool.execute(apply).unwrap();
+        assert!(applied.contains("Replaced 2 match(es) in 1 file(s)"));
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "let new = 1;\nnew += 1;\n"
+        );
+    }
+
     #[test]
     fn test_execute_command_tool_missing_command() {
         let tool = ExecuteCommandTool::new();
@@ -6,8 +6,14 @@
 //!
 //! All worktrees are created under `.hive-worktrees/` in the repository root,
 //! which should be added to `.gitignore`.
+//!
+//! A team's sandbox ([`WorktreeManager::team_sandbox`]) runs the worktree's
+//! dev container environment, when the workspace is opted in, under a
+//! per-team compose project; those containers are torn down together with
+//! the worktree.
 
 use git2::{BranchType, Repository};
+use hive_terminal::{AgentSandbox, DockerSandbox, SandboxConfig, devcontainer};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use tracing::{info, warn};
@@ -27,6 +33,31 @@ pub struct TeamWorktree {
     pub worktree_path: PathBuf,
 }
 
+impl TeamWorktree {
+    /// The swarm run this worktree belongs to, from its branch name.
+    pub fn run_id(&self) -> Option<&str> {
+        self.branch_name
+            .strip_prefix("swarm/")?
+            .rsplit_once('/')
+            .map(|(run_id, _)| run_id)
+    }
+}
+
+/// Label carrying the swarm run id on team sandbox containers.
+pub const SWARM_RUN_LABEL: &str = "hive.swarm.run";
+/// Label carrying the team id on team sandbox containers.
+pub const SWARM_TEAM_LABEL: &str = "hive.swarm.team";
+
+/// Compose project name for a team's sandbox services. Compose only allows
+/// lowercase alphanumerics, dashes and underscores.
+pub fn swarm_compose_project(run_id: &str, team_id: &str) -> String {
+    format!("hive-swarm-{run_id}-{team_id}")
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect()
+}
+
 /// Result of merging a team's branch into a target branch.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MergeBranchResult {
@@ -529,10 +560,45 @@ impl WorktreeManager {
         Err("Merge analysis returned unhandled state".into())
     }
 
+    /// Build the sandbox a team's agents run in.
+    ///
+    /// The sandbox is mounted on the team's worktree, so with
+    /// `use_project_environment` set (see [`SandboxConfig::for_workspace`])
+    /// it picks up the worktree's `devcontainer.json`. Services run under a
+    /// compose project of their own and containers are labelled with the
+    /// run and team, which is how [`cleanup_swarm`](Self::cleanup_swarm)
+    /// finds them again.
+    pub fn team_sandbox(&self, worktree: &TeamWorktree, mut config: SandboxConfig) -> AgentSandbox {
+        let run_id = worktree.run_id().unwrap_or_default();
+        config.compose_project = Some(swarm_compose_project(run_id, &worktree.team_id));
+        config
+            .labels
+            .insert(SWARM_RUN_LABEL.into(), run_id.to_string());
+        config
+            .labels
+            .insert(SWARM_TEAM_LABEL.into(), worktree.team_id.clone());
+        AgentSandbox::new(&worktree.worktree_path, config)
+    }
+
+    /// Best-effort removal of a team's sandbox containers and compose
+    /// services.
+    fn teardown_team_environment(run_id: &str, team_id: &str) {
+        let project = swarm_compose_project(run_id, team_id);
+        if let Err(e) = devcontainer::compose_down(&project) {
+            warn!(project = %project, error = %e, "Failed to remove team compose project");
+        }
+        match devcontainer::remove_labeled_containers(&format!("{SWARM_TEAM_LABEL}={team_id}")) {
+            Ok(0) => {}
+            Ok(count) => info!(team_id, count, "Removed team sandbox containers"),
+            Err(e) => warn!(team_id, error = %e, "Failed to remove team sandbox containers"),
+        }
+    }
+
     /// Remove a team's worktree and optionally delete its branch.
     ///
-    /// This removes the worktree directory from disk, prunes stale worktree
-    /// references, and optionally deletes the associated branch.
+    /// This tears down the team's sandbox environment, removes the worktree
+    /// directory from disk, prunes stale worktree references, and optionally
+    /// deletes the associated branch.
     pub fn cleanup_worktree(&self, team_id: &str, delete_branch: bool) -> Result<(), String> {
         let safe_team_id = Self::sanitize_branch_component(team_id);
         if safe_team_id.is_empty() {
@@ -541,6 +607,23 @@ impl WorktreeManager {
 
         let worktree_path = self.worktrees_dir().join(&safe_team_id);
 
+        // Open repo to find the team's run and prune worktrees.
+        let repo = Repository::open(&self.repo_path)
+            .map_err(|e| format!("Failed to open repository: {e}"))?;
+
+        if let Some(branch_name) = self.find_branch_for_team(&repo, &safe_team_id)
+            && !DockerSandbox::new().is_simulation()
+        {
+            let worktree = TeamWorktree {
+                team_id: safe_team_id.clone(),
+                branch_name,
+                worktree_path: worktree_path.clone(),
+            };
+            if let Some(run_id) = worktree.run_id() {
+                Self::teardown_team_environment(run_id, &safe_team_id);
+            }
+        }
+
         info!(
             team_id = %safe_team_id,
             path = %worktree_path.display(),
@@ -559,10 +642,6 @@ impl WorktreeManager {
             info!(path = %worktree_path.display(), "Removed worktree directory");
         }
 
-        // Open repo and prune worktrees.
-        let repo = Repository::open(&self.repo_path)
-            .map_err(|e| format!("Failed to open repository: {e}"))?;
-
         // Prune the worktree reference by looking it up and pruning if valid.
         if let Ok(wt) = repo.find_worktree(&safe_team_id) {
             // Prune with flags to handle locked/valid worktrees.
@@ -620,9 +699,9 @@ impl WorktreeManager {
 
     /// Clean up all worktrees for a completed swarm run.
     ///
-    /// Finds all branches matching `swarm/{run_id}/*`, cleans up their
-    /// worktrees and directories, and deletes the branches.
-    /// Returns the number of worktrees cleaned.
+    /// Finds all branches matching `swarm/{run_id}/*`, tears down their
+    /// sandbox environments, cleans up their worktrees and directories, and
+    /// deletes the branches. Returns the number of worktrees cleaned.
     pub fn cleanup_swarm(&self, run_id: &str) -> Result<usize, String> {
         let safe_run_id = Self::sanitize_branch_component(run_id);
         if safe_run_id.is_empty() {
@@ -656,6 +735,9 @@ impl WorktreeManager {
                 .collect()
         };
 
+        // Only talk to Docker when it is actually there.
+        let docker_available = !branch_names.is_empty() && !DockerSandbox::new().is_simulation();
+
         for branch_name in &branch_names {
             // Extract team_id from branch name: swarm/{run_id}/{team_id}
             let team_id = match branch_name.strip_prefix(&prefix) {
@@ -663,6 +745,11 @@ impl WorktreeManager {
                 None => continue,
             };
 
+            // Stop the team's sandbox and compose services.
+            if docker_available {
+                Self::teardown_team_environment(&safe_run_id, &team_id);
+            }
+
             // Clean up the worktree directory.
             let worktree_path = self.worktrees_dir().join(&team_id);
             if worktree_path.exists()
@@ -703,6 +790,17 @@ impl WorktreeManager {
             cleaned += 1;
         }
 
+        // Catch sandboxes whose team branch is already gone.
+        if docker_available {
+            match devcontainer::remove_labeled_containers(&format!(
+                "{SWARM_RUN_LABEL}={safe_run_id}"
+            )) {
+                Ok(0) => {}
+                Ok(count) => info!(count, "Removed leftover swarm sandbox containers"),
+                Err(e) => warn!(error = %e, "Failed to remove swarm sandbox containers"),
+            }
+        }
+
         // Try to remove the worktrees base directory if empty.
         let worktrees_dir = self.worktrees_dir();
         if worktrees_dir.exists() {
@@ -765,6 +863,88 @@ impl WorktreeManager {
         Ok(worktrees)
     }
 
+    /// Slots under `.hive-worktrees/` that git no longer tracks.
+    ///
+    /// These are directories with no valid registered worktree, and
+    /// registered worktrees whose directory is gone. Interrupted runs leave
+    /// both behind. Each slot can be passed to
+    /// [`WorktreeManager::cleanup_worktree`].
+    pub fn leftover_worktrees(&self) -> Result<Vec<String>, String> {
+        let repo = Repository::open(&self.repo_path)
+            .map_err(|e| format!("Failed to open repository: {e}"))?;
+        let worktrees_dir = self.worktrees_dir();
+        let mut leftovers = Vec::new();
+
+        if worktrees_dir.exists() {
+            let entries = std::fs::read_dir(&worktrees_dir)
+                .map_err(|e| format!("Failed to read worktrees directory: {e}"))?;
+            for entry in entries {
+                let entry = entry.map_err(|e| format!("Failed to read directory entry: {e}"))?;
+                let path = entry.path();
+                let Some(slot) = path.file_name().and_then(|n| n.to_str()) else {
+                    continue;
+                };
+                // Only slots this manager could have created.
+                if !path.is_dir() || Self::sanitize_branch_component(slot) != slot {
+                    continue;
+                }
+                let tracked = repo
+                    .find_worktree(slot)
+                    .is_ok_and(|wt| wt.validate().is_ok());
+                if !tracked {
+                    leftovers.push(slot.to_string());
+                }
+            }
+        }
+
+        let names = repo
+            .worktrees()
+            .map_err(|e| format!("Failed to list worktrees: {e}"))?;
+        for name in names.iter().flatten() {
+            if leftovers.iter().any(|slot| slot == name) {
+                continue;
+            }
+            if let Ok(wt) = repo.find_worktree(name)
+                && wt.path().ends_with(Path::new(".hive-worktrees").join(name))
+                && !wt.path().exists()
+            {
+                leftovers.push(name.to_string());
+            }
+        }
+
+        leftovers.sort();
+        Ok(leftovers)
+    }
+
+    /// Whether removing leftover `slot` loses nothing: its directory is
+    /// gone or empty, or it is a checkout on a branch with no uncommitted
+    /// or untracked changes. Anything else may hold an agent's unsaved work.
+    pub fn leftover_is_disposable(&self, slot: &str) -> bool {
+        let path = self.worktrees_dir().join(slot);
+        if !path.exists() {
+            return true;
+        }
+        let Ok(mut entries) = std::fs::read_dir(&path) else {
+            return false;
+        };
+        if entries.next().is_none() {
+            return true;
+        }
+        let Ok(repo) = Repository::open(&path) else {
+            return false;
+        };
+        if !repo.head().is_ok_and(|head| head.is_branch()) {
+            return false;
+        }
+        let mut options = git2::StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false);
+        repo.statuses(Some(&mut options))
+            .is_ok_and(|statuses| statuses.is_empty())
+    }
+
     /// Find a branch matching `swarm/*/{team_id}` pattern.
     fn find_branch_for_team(&self, repo: &Repository, team_id: &str) -> Option<String> {
         let branches = repo.branches(Some(BranchType::Local)).ok()?;
@@ -955,6 +1135,42 @@ mod tests {
         assert!(team_ids.contains(&"team-two"));
     }
 
+    #[test]
+    fn leftover_worktrees_finds_untracked_and_missing_slots() {
+        let (dir, _repo) = setup_test_repo();
+        let manager = WorktreeManager::new(dir.path());
+        assert!(manager.leftover_worktrees().unwrap().is_empty());
+
+        manager.create_worktree("run-6", "live").unwrap();
+        let gone = manager.create_worktree("run-6", "gone").unwrap();
+        fs::remove_dir_all(&gone.worktree_path).unwrap();
+        fs::create_dir_all(manager.worktrees_dir().join("stray")).unwrap();
+
+        assert_eq!(manager.leftover_worktrees().unwrap(), ["gone", "stray"]);
+
+        manager.cleanup_worktree("gone", true).unwrap();
+        manager.cleanup_worktree("stray", false).unwrap();
+        assert!(manager.leftover_worktrees().unwrap().is_empty());
+    }
+
+    #[test]
+    fn leftover_with_changes_is_not_disposable() {
+        let (dir, _repo) = setup_test_repo();
+        let manager = WorktreeManager::new(dir.path());
+        assert!(manager.leftover_is_disposable("never-created"));
+
+        let stray = manager.worktrees_dir().join("stray");
+        fs::create_dir_all(&stray).unwrap();
+        assert!(manager.leftover_is_disposable("stray"));
+        fs::write(stray.join("notes.md"), "unsaved").unwrap();
+        assert!(!manager.leftover_is_disposable("stray"));
+
+        let wt = manager.create_worktree("run-9", "team").unwrap();
+        assert!(manager.leftover_is_disposable("team"));
+        fs::write(wt.worktree_path.join("new.rs"), "fn main() {}").unwrap();
+        assert!(!manager.leftover_is_disposable("team"));
+    }
+
     #[test]
     fn path_validation_prevents_escape() {
         let (dir, _repo) = setup_test_repo();
@@ -1038,6 +1254,24 @@ mod tests {
         assert_eq!(list[0].team_id, "team-c");
     }
 
+    #[test]
+    fn team_sandbox_uses_team_compose_project() {
+        let (dir, _repo) = setup_test_repo();
+        let manager = WorktreeManager::new(dir.path());
+        let wt = manager.create_worktree("run-8", "Team_A").unwrap();
+        assert_eq!(wt.run_id(), Some("run-8"));
+
+        let sandbox = manager.team_sandbox(&wt, SandboxConfig::default());
+        assert_eq!(sandbox.workspace(), wt.worktree_path.as_path());
+        let config = sandbox.config();
+        assert_eq!(
+            config.compose_project.as_deref(),
+            Some("hive-swarm-run-8-team_a")
+        );
+        assert_eq!(config.labels[SWARM_RUN_LABEL], "run-8");
+        assert_eq!(config.labels[SWARM_TEAM_LABEL], "Team_A");
+    }
+
     #[test]
     fn team_worktree_serialization() {
         let wt = TeamWorktree {
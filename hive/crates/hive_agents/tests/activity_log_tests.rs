@@ -171,3 +171,40 @@ async fn activity_service_with_log_persists_events() {
     assert_eq!(entries.len(), 1);
     assert_eq!(entries[0].event_type, "agent_started");
 }
+
+#[tokio::test]
+async fn activity_service_audits_approvals_and_tools() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit = std::sync::Arc::new(hive_core::AuditLog::open(dir.path(), None).unwrap());
+    let service = ActivityService::new_bus_only();
+    service.attach_audit_log(audit.clone());
+
+    service.emit(ActivityEvent::ShellExecuted {
+        agent_id: "coder".into(),
+        command: "cargo test".into(),
+        exit_code: 0,
+    });
+    service.emit(ActivityEvent::CostIncurred {
+        agent_id: "coder".into(),
+        model: "m".into(),
+        input_tokens: 1,
+        output_tokens: 1,
+        cost_usd: 0.01,
+    });
+    service.emit(ActivityEvent::ApprovalDenied {
+        request_id: "req-1".into(),
+        reason: Some("too risky".into()),
+    });
+
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    let records = audit.records().unwrap();
+    assert_eq!(records.len(), 2, "cost events are not audited");
+    assert_eq!(records[0].category, hive_core::AuditCategory::ToolExecution);
+    assert_eq!(records[0].actor, "coder");
+    assert_eq!(records[0].resource, "cargo test");
+    assert_eq!(records[1].category, hive_core::AuditCategory::Approval);
+    assert_eq!(records[1].action, "approval_denied");
+    assert_eq!(records[1].resource, "req-1");
+    assert!(audit.verify().unwrap().is_intact());
+}
@@ -140,7 +140,10 @@ async fn approval_gate_rule_match_creates_request() {
     let request = pending.unwrap();
     assert_eq!(request.matched_rule, "always");
 
-    gate.respond(&request.id, ApprovalDecision::Approved);
+    assert_eq!(
+        gate.respond(&request.id, "local", ApprovalDecision::Approved),
+        ApprovalProgress::Granted
+    );
     assert_eq!(gate.pending_count(), 0);
 }
 
@@ -159,6 +162,7 @@ async fn approval_gate_respond_deny() {
 
     gate.respond(
         &pending.unwrap().id,
+        "local",
         ApprovalDecision::Denied {
             reason: Some("nope".into()),
         },
@@ -201,3 +205,275 @@ fn notification_dismiss() {
     svc.dismiss(&id);
     assert_eq!(svc.all().len(), 1);
 }
+
+use chrono::NaiveDate;
+use hive_agents::activity::{ApprovalPolicy, ApprovalProgress, PolicyContext, PolicyOutcome};
+
+const POLICY_TOML: &str = r#"
+default = "allow"
+
+[[rule]]
+name = "no-prod-push-after-hours"
+priority = 100
+outcome = "deny"
+[rule.when]
+operation = "git_push"
+workspace = "*prod*"
+not = { time = { days = ["mon", "tue", "wed", "thu", "fri"], from = "09:00", to = "18:00" } }
+
+[[rule]]
+name = "expensive-models"
+priority = 50
+outcome = { require_approvers = 2 }
+[rule.when]
+any = [{ model = "*opus*" }, { cost_above = 5.0 }]
+
+[[rule]]
+name = "reviewers-may-push"
+priority = 10
+outcome = "allow"
+[rule.when]
+role = "reviewer"
+command = "git push*"
+
+[[test]]
+name = "weekday push is fine"
+operation = "git_push"
+workspace = "acme-prod"
+at = "2026-03-02T10:00:00"
+expect = "allow"
+
+[[test]]
+name = "saturday push is blocked"
+operation = "git_push"
+workspace = "acme-prod"
+at = "2026-03-07T10:00:00"
+expect = "deny"
+expect_rule = "no-prod-push-after-hours"
+"#;
+
+fn at(day: u32, hour: u32) -> chrono::NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 3, day)
+        .unwrap()
+        .and_hms_opt(hour, 0, 0)
+        .unwrap()
+}
+
+fn push() -> OperationType {
+    OperationType::GitPush {
+        remote: "origin".into(),
+        branch: "main".into(),
+    }
+}
+
+#[test]
+fn policy_composes_conditions() {
+    let policy = ApprovalPolicy::from_toml(POLICY_TOML).unwrap();
+
+    // Monday 10:00 is inside the window, Monday 22:00 and Saturday are not.
+    let ctx = |when| {
+        PolicyContext::new("a", push())
+            .with_workspace("acme-prod")
+            .at(when)
+    };
+    assert_eq!(
+        policy.evaluate(&ctx(at(2, 10))).outcome,
+        PolicyOutcome::Allow
+    );
+    assert_eq!(
+        policy.evaluate(&ctx(at(2, 22))).outcome,
+        PolicyOutcome::Deny
+    );
+    assert_eq!(
+        policy.evaluate(&ctx(at(7, 10))).outcome,
+        PolicyOutcome::Deny
+    );
+
+    // Other workspaces are unaffected.
+    let staging = PolicyContext::new("a", push())
+        .with_workspace("acme-staging")
+        .at(at(7, 10));
+    assert_eq!(policy.evaluate(&staging).rule, None);
+
+    let opus = PolicyContext::new(
+        "a",
+        OperationType::AiCall {
+            model: "claude-opus-4".into(),
+            estimated_cost: 0.2,
+        },
+    );
+    let decision = policy.evaluate(&opus);
+    assert_eq!(decision.outcome, PolicyOutcome::RequireApprovers(2));
+    assert_eq!(decision.rule.as_deref(), Some("expensive-models"));
+}
+
+#[test]
+fn policy_embedded_tests_run() {
+    let policy = ApprovalPolicy::from_toml(POLICY_TOML).unwrap();
+    let results = policy.run_tests();
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|r| r.passed), "{results:?}");
+
+    let mut broken = policy.clone();
+    broken.tests[0].expect = PolicyOutcome::Deny;
+    assert!(!broken.run_tests()[0].passed);
+}
+
+#[test]
+fn policy_parses_yaml() {
+    let yaml = r#"
+default: require_approval
+approval_ttl_secs: 60
+rules:
+  - name: trusted-personas
+    outcome: allow
+    when:
+      persona: [investigate, "code review"]
+  - name: no-deletes
+    outcome: deny
+    when:
+      operation: file_delete
+"#;
+    let policy = ApprovalPolicy::from_yaml(yaml).unwrap();
+    let review =
+        PolicyContext::new("a", OperationType::Custom("x".into())).with_persona("Code Review");
+    assert_eq!(policy.evaluate(&review).outcome, PolicyOutcome::Allow);
+
+    let delete = PolicyContext::new("a", OperationType::FileDelete("a.txt".into()));
+    assert_eq!(policy.evaluate(&delete).outcome, PolicyOutcome::Deny);
+
+    let other = PolicyContext::new("a", OperationType::ShellCommand("ls".into()));
+    let decision = policy.evaluate(&other);
+    assert_eq!(decision.outcome, PolicyOutcome::RequireApproval);
+    assert_eq!(decision.expires_after_secs, Some(60));
+}
+
+#[test]
+fn policy_rejects_invalid_files() {
+    let unknown_field = "[[rule]]\nname = \"x\"\noutcome = \"deny\"\nwhen = { colour = \"red\" }\n";
+    assert!(ApprovalPolicy::from_toml(unknown_field).is_err());
+
+    let duplicate =
+        "[[rule]]\nname = \"x\"\noutcome = \"deny\"\n[[rule]]\nname = \"x\"\noutcome = \"allow\"\n";
+    assert!(ApprovalPolicy::from_toml(duplicate).is_err());
+
+    let bad_time =
+        "[[rule]]\nname = \"x\"\noutcome = \"deny\"\nwhen = { time = { from = \"9am\" } }\n";
+    assert!(ApprovalPolicy::from_toml(bad_time).is_err());
+}
+
+#[tokio::test]
+async fn policy_allow_overrides_builtin_rules() {
+    let policy = ApprovalPolicy::from_toml(POLICY_TOML).unwrap();
+    let gate = ApprovalGate::new(ApprovalRule::defaults()).with_policy(policy);
+
+    let cmd = OperationType::ShellCommand("git push origin main".into());
+    assert!(gate.check_with_channel("a", &cmd).is_some());
+
+    let reviewer = PolicyContext::new("a", cmd).with_role("reviewer");
+    assert!(gate.check_with_context(&reviewer).is_none());
+}
+
+#[tokio::test]
+async fn policy_deny_resolves_immediately() {
+    let policy = ApprovalPolicy::from_toml(POLICY_TOML).unwrap();
+    let gate = ApprovalGate::new(vec![]).with_policy(policy);
+
+    let ctx = PolicyContext::new("a", push())
+        .with_workspace("acme-prod")
+        .at(at(7, 10));
+    let (request, rx) = gate.check_with_context(&ctx).unwrap();
+    assert!(request.denial.is_some());
+    assert_eq!(gate.pending_count(), 0);
+    assert!(matches!(rx.await.unwrap(), ApprovalDecision::Denied { .. }));
+}
+
+#[tokio::test]
+async fn require_n_approvers_needs_distinct_approvals() {
+    let policy = ApprovalPolicy::from_toml(POLICY_TOML).unwrap();
+    let gate = ApprovalGate::new(vec![]).with_policy(policy);
+
+    let op = OperationType::AiCall {
+        model: "claude-opus-4".into(),
+        estimated_cost: 1.0,
+    };
+    let (request, rx) = gate.check_with_channel("a", &op).unwrap();
+    assert_eq!(request.required_approvals, 2);
+
+    assert_eq!(
+        gate.approve(&request.id, "alice"),
+        ApprovalProgress::Waiting { remaining: 1 }
+    );
+    assert_eq!(
+        gate.approve(&request.id, "alice"),
+        ApprovalProgress::Waiting { remaining: 1 }
+    );
+    assert_eq!(gate.approve(&request.id, "bob"), ApprovalProgress::Granted);
+    assert_eq!(rx.await.unwrap(), ApprovalDecision::Approved);
+    assert_eq!(
+        gate.approve(&request.id, "carol"),
+        ApprovalProgress::Unknown
+    );
+}
+
+#[tokio::test]
+async fn respond_reaches_quorum_with_distinct_approvers() {
+    let policy = ApprovalPolicy::from_toml(POLICY_TOML).unwrap();
+    let gate = ApprovalGate::new(vec![]).with_policy(policy);
+
+    let op = OperationType::AiCall {
+        model: "claude-opus-4".into(),
+        estimated_cost: 1.0,
+    };
+    let (request, rx) = gate.check_with_channel("a", &op).unwrap();
+    assert_eq!(request.required_approvals, 2);
+
+    // The desktop user and a paired phone are two approvers.
+    assert_eq!(
+        gate.respond(&request.id, "local", ApprovalDecision::Approved),
+        ApprovalProgress::Waiting { remaining: 1 }
+    );
+    assert_eq!(gate.pending_count(), 1);
+    assert_eq!(
+        gate.respond(&request.id, "device-7f3a", ApprovalDecision::Approved),
+        ApprovalProgress::Granted
+    );
+    assert_eq!(rx.await.unwrap(), ApprovalDecision::Approved);
+    assert_eq!(gate.pending_count(), 0);
+}
+
+#[tokio::test]
+async fn approvals_expire() {
+    let policy =
+        ApprovalPolicy::from_toml("default = \"require_approval\"\napproval_ttl_secs = 0\n")
+            .unwrap();
+    let gate = ApprovalGate::new(vec![]).with_policy(policy);
+
+    let (request, rx) = gate
+        .check_with_channel("a", &OperationType::Custom("deploy".into()))
+        .unwrap();
+    assert!(request.expires_at.is_some());
+    assert_eq!(gate.expire_stale(), vec![request.id.clone()]);
+    assert_eq!(gate.pending_count(), 0);
+    assert_eq!(rx.await.unwrap(), ApprovalDecision::Timeout);
+}
+
+#[test]
+fn security_gateway_block_rule_matches() {
+    let rule = ApprovalRule {
+        name: "security-gateway".into(),
+        enabled: true,
+        trigger: RuleTrigger::SecurityGatewayBlock,
+        priority: 100,
+    };
+    assert!(rule.matches(&OperationType::ShellCommand("rm -rf /".into())));
+    assert!(rule.matches(&OperationType::ShellCommand("echo $(whoami)".into())));
+    assert!(rule.matches(&OperationType::FileDelete(
+        "/home/me/.ssh/id_ed25519".into()
+    )));
+    assert!(!rule.matches(&OperationType::ShellCommand("cargo test".into())));
+    assert!(!rule.matches(&OperationType::FileModify {
+        path: "src/main.rs".into(),
+        scope: "1 file".into(),
+    }));
+}
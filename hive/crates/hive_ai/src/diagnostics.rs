@@ -0,0 +1,430 @@
+//! Doctor checks for AI backends: whether configured provider API keys are
+//! accepted, and whether the LanceDB memory store opens.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use hive_core::HiveConfig;
+use hive_core::diagnostics::{
+    CheckStatus, Diagnostic, DiagnosticContext, DiagnosticRegistry, DoctorCheck,
+};
+use reqwest::StatusCode;
+
+use crate::memory::MemoryStore;
+
+const KEY_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Register this crate's diagnostics.
+pub fn register(registry: &mut DiagnosticRegistry) -> Result<()> {
+    registry.register(ProviderKeysCheck::default())?;
+    registry.register(MemoryStoreCheck)?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Provider keys
+// ---------------------------------------------------------------------------
+
+/// How a provider expects its API key.
+#[derive(Clone, Copy)]
+enum KeyAuth {
+    Bearer,
+    Header(&'static str),
+    Query(&'static str),
+}
+
+/// A cheap authenticated request that tells a good key from a bad one.
+#[derive(Clone)]
+struct KeyProbe {
+    provider: &'static str,
+    key: fn(&HiveConfig) -> Option<&String>,
+    url: String,
+    auth: KeyAuth,
+    headers: &'static [(&'static str, &'static str)],
+}
+
+impl KeyProbe {
+    fn new(
+        provider: &'static str,
+        key: fn(&HiveConfig) -> Option<&String>,
+        url: &str,
+        auth: KeyAuth,
+    ) -> Self {
+        Self {
+            provider,
+            key,
+            url: url.to_string(),
+            auth,
+            headers: &[],
+        }
+    }
+}
+
+enum KeyState {
+    Valid,
+    Rejected(StatusCode),
+    Unverified(String),
+}
+
+/// Every configured cloud provider key is accepted by its API.
+struct ProviderKeysCheck {
+    probes: Vec<KeyProbe>,
+}
+
+impl Default for ProviderKeysCheck {
+    fn default() -> Self {
+        use KeyAuth::*;
+        let anthropic = KeyProbe {
+            headers: &[("anthropic-version", "2023-06-01")],
+            ..KeyProbe::new(
+                "Anthropic",
+                |c| c.anthropic_api_key.as_ref(),
+                "https://api.anthropic.com/v1/models",
+                Header("x-api-key"),
+            )
+        };
+        Self {
+            probes: vec![
+                anthropic,
+                KeyProbe::new(
+                    "OpenAI",
+                    |c| c.openai_api_key.as_ref(),
+                    "https://api.openai.com/v1/models",
+                    Bearer,
+                ),
+                KeyProbe::new(
+                    "OpenRouter",
+                    |c| c.openrouter_api_key.as_ref(),
+                    "https://openrouter.ai/api/v1/key",
+                    Bearer,
+                ),
+                KeyProbe::new(
+                    "Google",
+                    |c| c.google_api_key.as_ref(),
+                    "https://generativelanguage.googleapis.com/v1beta/models",
+                    Query("key"),
+                ),
+                KeyProbe::new(
+                    "Groq",
+                    |c| c.groq_api_key.as_ref(),
+                    "https://api.groq.com/openai/v1/models",
+                    Bearer,
+                ),
+                KeyProbe::new(
+                    "Mistral",
+                    |c| c.mistral_api_key.as_ref(),
+                    "https://api.mistral.ai/v1/models",
+                    Bearer,
+                ),
+                KeyProbe::new(
+                    "xAI",
+                    |c| c.xai_api_key.as_ref(),
+                    "https://api.x.ai/v1/models",
+                    Bearer,
+                ),
+                KeyProbe::new(
+                    "Hugging Face",
+                    |c| c.huggingface_api_key.as_ref(),
+                    "https://huggingface.co/api/whoami-v2",
+                    Bearer,
+                ),
+                KeyProbe::new(
+                    "Venice",
+                    |c| c.venice_api_key.as_ref(),
+                    "https://api.venice.ai/api/v1/models",
+                    Bearer,
+                ),
+            ],
+        }
+    }
+}
+
+impl ProviderKeysCheck {
+    const NAME: &str = "Provider API Keys";
+    const DESCRIPTION: &str = "Check that configured cloud provider keys are accepted";
+
+    async fn probe(client: &reqwest::Client, probe: &KeyProbe, key: &str) -> KeyState {
+        let mut request = client.get(&probe.url);
+        request = match probe.auth {
+            KeyAuth::Bearer => request.bearer_auth(key),
+            KeyAuth::Header(name) => request.header(name, key),
+            KeyAuth::Query(name) => request.query(&[(name, key)]),
+        };
+        for (name, value) in probe.headers {
+            request = request.header(*name, *value);
+        }
+        match request.send().await {
+            Ok(resp) if resp.status().is_success() => KeyState::Valid,
+            Ok(resp)
+                if matches!(
+                    resp.status(),
+                    StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+                ) =>
+            {
+                KeyState::Rejected(resp.status())
+            }
+            Ok(resp) => KeyState::Unverified(format!("HTTP {}", resp.status().as_u16())),
+            // Only the error kind: the URL may carry the key as a query param.
+            Err(e) if e.is_timeout() => KeyState::Unverified("timed out".into()),
+            Err(e) if e.is_connect() => KeyState::Unverified("connection failed".into()),
+            Err(_) => KeyState::Unverified("request failed".into()),
+        }
+    }
+}
+
+#[async_trait]
+impl Diagnostic for ProviderKeysCheck {
+    fn id(&self) -> &'static str {
+        "ai.provider_keys"
+    }
+
+    fn category(&self) -> &'static str {
+        "providers"
+    }
+
+    async fn run(&self, ctx: &DiagnosticContext) -> DoctorCheck {
+        let Some(ref config) = ctx.config else {
+            return DoctorCheck::warn(
+                Self::NAME,
+                Self::DESCRIPTION,
+                "Configuration could not be loaded; keys not checked",
+            );
+        };
+
+        let configured: Vec<(&KeyProbe, &str)> = self
+            .probes
+            .iter()
+            .filter_map(|probe| {
+                let key = (probe.key)(config)?;
+                (!key.is_empty()).then_some((probe, key.as_str()))
+            })
+            .collect();
+        if configured.is_empty() {
+            return DoctorCheck::warn(
+                Self::NAME,
+                Self::DESCRIPTION,
+                "No cloud provider API keys configured",
+            )
+            .with_fix("Add a key in Settings, or use a local model server");
+        }
+
+        let client = match reqwest::Client::builder()
+            .timeout(KEY_PROBE_TIMEOUT)
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => return DoctorCheck::fail(Self::NAME, Self::DESCRIPTION, e.to_string()),
+        };
+        let states = futures::future::join_all(
+            configured
+                .iter()
+                .map(|(probe, key)| Self::probe(&client, probe, key)),
+        )
+        .await;
+
+        let mut valid = Vec::new();
+        let mut rejected = Vec::new();
+        let mut unverified = Vec::new();
+        for ((probe, _), state) in configured.iter().zip(states) {
+            match state {
+                KeyState::Valid => valid.push(probe.provider.to_string()),
+                KeyState::Rejected(status) => {
+                    rejected.push(format!("{} (HTTP {})", probe.provider, status.as_u16()))
+                }
+                KeyState::Unverified(reason) => {
+                    unverified.push(format!("{} ({reason})", probe.provider))
+                }
+            }
+        }
+
+        let mut parts = Vec::new();
+        if !valid.is_empty() {
+            parts.push(format!("valid: {}", valid.join(", ")));
+        }
+        if !rejected.is_empty() {
+            parts.push(format!("rejected: {}", rejected.join(", ")));
+        }
+        if !unverified.is_empty() {
+            parts.push(format!("could not verify: {}", unverified.join(", ")));
+        }
+        let message = parts.join("; ");
+
+        if !rejected.is_empty() {
+            DoctorCheck::fail(Self::NAME, Self::DESCRIPTION, message)
+                .with_fix("Replace the rejected keys in Settings; they may be revoked or expired")
+        } else if !unverified.is_empty() {
+            DoctorCheck::warn(Self::NAME, Self::DESCRIPTION, message)
+                .with_fix("Check network access to the provider APIs")
+        } else {
+            DoctorCheck::pass(Self::NAME, Self::DESCRIPTION, message)
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// LanceDB memory store
+// ---------------------------------------------------------------------------
+
+/// `~/.hive/hive_memory.lance` opens and its tables can be counted.
+struct MemoryStoreCheck;
+
+impl MemoryStoreCheck {
+    const NAME: &str = "Memory Store";
+    const DESCRIPTION: &str = "Check that the LanceDB memory store is readable";
+
+    fn store_path(ctx: &DiagnosticContext) -> PathBuf {
+        ctx.hive_dir.join("hive_memory.lance")
+    }
+}
+
+#[async_trait]
+impl Diagnostic for MemoryStoreCheck {
+    fn id(&self) -> &'static str {
+        "ai.memory_store"
+    }
+
+    fn category(&self) -> &'static str {
+        "storage"
+    }
+
+    async fn run(&self, ctx: &DiagnosticContext) -> DoctorCheck {
+        let path = Self::store_path(ctx);
+        if !path.exists() {
+            return DoctorCheck::pass(
+                Self::NAME,
+                Self::DESCRIPTION,
+                "Memory store not created yet",
+            );
+        }
+
+        let stats = match MemoryStore::open(&path.to_string_lossy()).await {
+            Ok(store) => store.stats().await,
+            Err(e) => Err(e),
+        };
+        match stats {
+            Ok(stats) => DoctorCheck::pass(
+                Self::NAME,
+                Self::DESCRIPTION,
+                format!(
+                    "{} memories, {} indexed chunks",
+                    stats.total_memories, stats.total_chunks
+                ),
+            ),
+            Err(e) => DoctorCheck::fail(
+                Self::NAME,
+                Self::DESCRIPTION,
+                format!("Could not read {}: {e}", path.display()),
+            )
+            .with_fix(
+                "Run 'hive doctor --fix' to move the store aside; Hive recreates it and reindexes on next start",
+            ),
+        }
+    }
+
+    fn can_fix(&self, check: &DoctorCheck) -> bool {
+        check.status == CheckStatus::Fail
+    }
+
+    async fn fix(&self, ctx: &DiagnosticContext) -> Result<String> {
+        let path = Self::store_path(ctx);
+        if !path.exists() {
+            bail!("{} does not exist", path.display());
+        }
+        let backup = path.with_extension(format!(
+            "lance.broken-{}",
+            chrono::Utc::now().format("%Y%m%d%H%M%S")
+        ));
+        std::fs::rename(&path, &backup)?;
+        Ok(format!("Moved the memory store to {}", backup.display()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serve `status` to every request on a local port.
+    async fn serve_status(status: &'static str) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                let response =
+                    format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{addr}/v1/models")
+    }
+
+    fn context(config: HiveConfig) -> DiagnosticContext {
+        DiagnosticContext::new("/tmp/hive-doctor-test", "/tmp").with_config(config)
+    }
+
+    fn openai_probe(url: String) -> ProviderKeysCheck {
+        ProviderKeysCheck {
+            probes: vec![KeyProbe {
+                url,
+                ..KeyProbe::new("OpenAI", |c| c.openai_api_key.as_ref(), "", KeyAuth::Bearer)
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn no_keys_warns() {
+        let check = ProviderKeysCheck::default()
+            .run(&context(HiveConfig::default()))
+            .await;
+        assert_eq!(check.status, CheckStatus::Warn);
+    }
+
+    #[tokio::test]
+    async fn rejected_key_fails_without_leaking_it() {
+        let url = serve_status("401 Unauthorized").await;
+        let config = HiveConfig {
+            openai_api_key: Some("sk-secret".into()),
+            ..Default::default()
+        };
+        let check = openai_probe(url).run(&context(config)).await;
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.message.contains("OpenAI (HTTP 401)"));
+        assert!(!check.message.contains("sk-secret"));
+    }
+
+    #[tokio::test]
+    async fn accepted_key_passes() {
+        let url = serve_status("200 OK").await;
+        let config = HiveConfig {
+            openai_api_key: Some("sk-good".into()),
+            ..Default::default()
+        };
+        let check = openai_probe(url).run(&context(config)).await;
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert_eq!(check.message, "valid: OpenAI");
+    }
+
+    #[tokio::test]
+    async fn memory_store_missing_passes_and_broken_is_moved_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = DiagnosticContext::new(dir.path(), dir.path());
+        assert_eq!(MemoryStoreCheck.run(&ctx).await.status, CheckStatus::Pass);
+
+        std::fs::write(dir.path().join("hive_memory.lance"), "not a directory").unwrap();
+        let check = MemoryStoreCheck.run(&ctx).await;
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(MemoryStoreCheck.can_fix(&check));
+
+        MemoryStoreCheck.fix(&ctx).await.unwrap();
+        assert!(!dir.path().join("hive_memory.lance").exists());
+        assert_eq!(MemoryStoreCheck.run(&ctx).await.status, CheckStatus::Pass);
+    }
+}
@@ -1,5 +1,6 @@
 pub mod context_engine;
 pub mod cost;
+pub mod diagnostics;
 pub mod discovery;
 pub mod embeddings;
 pub mod fleet_learning;
@@ -25,36 +25,9 @@ const MAX_SYMBOLS: usize = 500;
 /// Maximum file size (bytes) for symbol extraction.
 const MAX_FILE_SIZE_FOR_SYMBOLS: u64 = 100_000;
 
-/// Directories to always skip during file-tree walks.
-/// Shared between QuickIndex and BackgroundIndexer.
-pub const SKIP_DIRS: &[&str] = &[
-    ".git",
-    "node_modules",
-    "target",
-    ".hive-worktrees",
-    "__pycache__",
-    ".venv",
-    "venv",
-    ".tox",
-    "dist",
-    "build",
-    ".next",
-    ".nuxt",
-    ".cache",
-    ".gradle",
-    ".idea",
-    ".vscode",
-];
-
-/// Returns `true` if a directory entry should be skipped during file-tree walks.
-/// Shared between QuickIndex and BackgroundIndexer.
-pub fn should_skip_dir(name: &str) -> bool {
-    name.starts_with('.')
-        || SKIP_DIRS.contains(&name)
-        || name.starts_with("target-")
-        || name.starts_with("dist-")
-        || name.starts_with("build-")
-}
+/// Directory skip rules, shared between QuickIndex, BackgroundIndexer and
+/// the file watcher.
+pub use hive_fs::watcher::{SKIP_DIRS, should_skip_dir};
 
 /// Extensions worth scanning for symbols.
 const SYMBOL_EXTENSIONS: &[&str] = &[
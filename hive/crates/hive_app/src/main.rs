@@ -25,14 +25,14 @@ use hive_ui::globals::{
     AppApprovalGate, AppAssistant, AppAutomation, AppAws, AppAzure, AppBitbucket, AppBrowser,
     AppChannels, AppCli, AppCollectiveMemory, AppCompetenceDetector, AppConfig, AppContextEngine,
     AppCortexAutoApply, AppCortexEventTx, AppCortexInteractionTracker, AppCortexStatus,
-    AppCortexStatusRx, AppCrossChannel, AppDatabase, AppDocker, AppDocsIndexer, AppFleetLearning,
-    AppGcp, AppGitLab, AppHeartbeatScheduler, AppHiveMemory, AppHueClient, AppIde,
-    AppIntegrationDb, AppKnowledge, AppKubernetes, AppLearning, AppLocalAiDetection,
+    AppCortexStatusRx, AppCrossChannel, AppDatabase, AppDocker, AppDocsIndexer, AppEnterprise,
+    AppFleetLearning, AppGcp, AppGitLab, AppHeartbeatScheduler, AppHiveMemory, AppHueClient,
+    AppIde, AppIntegrationDb, AppKnowledge, AppKubernetes, AppLearning, AppLocalAiDetection,
     AppMarketplace, AppMcpServer, AppMessaging, AppNetwork, AppNotifications, AppOllamaManager,
     AppPersonas, AppPluginManager, AppProjectManagement, AppRagService, AppReminderRx,
     AppRpcConfig, AppScheduler, AppSecurity, AppSemanticSearch, AppShield, AppSkillManager,
-    AppSkills, AppSpecs, AppStandupService, AppTts, AppUiActionTx, AppUpdater, AppVoiceAssistant,
-    AppWallets,
+    AppSkills, AppSpecs, AppStandupService, AppTeamDispatcher, AppTts, AppUiActionTx, AppUpdater,
+    AppVoiceAssistant, AppWallets,
 };
 use hive_ui::workspace::{
     ClearChat, HiveWorkspace, NewConversation, SwitchPanel, SwitchToAgents, SwitchToChannels,
@@ -114,6 +114,27 @@ fn jira_domain_from_base_url(base_url: &str) -> String {
         .unwrap_or_else(|| host.to_string())
 }
 
+/// Routes the requests of teams assigned by peers through the local providers,
+/// like the desktop swarm executor.
+struct RemoteTeamExecutor {
+    handle: hive_ai::AiRoutingHandle,
+}
+
+impl hive_agents::AiExecutor for RemoteTeamExecutor {
+    async fn execute(
+        &self,
+        request: &hive_ai::types::ChatRequest,
+    ) -> Result<hive_ai::types::ChatResponse, String> {
+        let (provider, resolved) = self
+            .handle
+            .route(&request.messages, &request.model)
+            .ok_or("no provider available")?;
+        let mut req = request.clone();
+        req.model = resolved;
+        provider.chat(&req).await.map_err(|e| e.to_string())
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Actions
 // ---------------------------------------------------------------------------
@@ -469,6 +490,43 @@ fn init_services(cx: &mut App) -> anyhow::Result<()> {
     cx.set_global(AppActivityService(activity_service.clone()));
     info!("ActivityService initialized");
 
+    // Tamper-evident audit log: approvals, tool executions and key changes.
+    // Keyed from SecureStorage so records can't be silently re-chained.
+    let audit_dir = HiveConfig::base_dir()
+        .map(|d| d.join("audit"))
+        .unwrap_or_else(|_| std::path::PathBuf::from("audit"));
+    let config_manager = &cx.global::<AppConfig>().0;
+    let audit_log = match hive_core::AuditLog::open(&audit_dir, config_manager.audit_keys()) {
+        Ok(audit_log) => {
+            let audit_log = std::sync::Arc::new(audit_log);
+            config_manager.attach_audit_log(audit_log.clone());
+            activity_service.attach_audit_log(audit_log.clone());
+            info!("AuditLog initialized at {}", audit_dir.display());
+            Some(audit_log)
+        }
+        Err(e) => {
+            warn!("AuditLog open failed, auditing disabled: {e}");
+            None
+        }
+    };
+
+    // Enterprise teams and usage; team and role changes land in the audit log.
+    let enterprise_path = HiveConfig::base_dir()
+        .map(|d| d.join("enterprise.json"))
+        .unwrap_or_else(|_| std::path::PathBuf::from("enterprise.json"));
+    let mut enterprise = hive_core::EnterpriseService::load_from_file(&enterprise_path)
+        .unwrap_or_else(|e| {
+            warn!("Enterprise data load failed, starting empty: {e}");
+            hive_core::EnterpriseService::new()
+        });
+    if let Some(audit_log) = &audit_log {
+        enterprise.attach_audit_log(audit_log.clone());
+    }
+    cx.set_global(AppEnterprise(std::sync::Arc::new(std::sync::Mutex::new(
+        enterprise,
+    ))));
+    info!("EnterpriseService initialized");
+
     // Agent notification service — approval requests, budget warnings, completions.
     let agent_notifications = std::sync::Arc::new(hive_agents::NotificationService::new());
     cx.set_global(AppAgentNotifications(agent_notifications));
@@ -591,11 +649,43 @@ fn init_services(cx: &mut App) -> anyhow::Result<()> {
     cx.set_global(AppHeartbeatScheduler(heartbeat));
     info!("HeartbeatScheduler initialized");
 
-    // Approval gate — rule-based operation approval for agent orchestration.
+    // Approval gate — rule-based operation approval for agent orchestration,
+    // optionally extended by a declarative policy file.
     let approval_rules = hive_agents::ApprovalRule::defaults();
-    let approval_gate = std::sync::Arc::new(hive_agents::ApprovalGate::new(approval_rules));
+    let mut approval_gate = hive_agents::ApprovalGate::new(approval_rules);
+    if let Some(policy_path) = hive_agents::ApprovalPolicy::configured_path(&config) {
+        match hive_agents::ApprovalPolicy::load(&policy_path) {
+            Ok(policy) => {
+                info!(
+                    "Approval policy loaded from {} ({} rules)",
+                    policy_path.display(),
+                    policy.rules.len()
+                );
+                if let Some(audit_log) = &audit_log {
+                    let digest =
+                        hive_agents::ApprovalPolicy::file_digest(&policy_path).unwrap_or_default();
+                    let event = hive_core::AuditEvent::new(
+                        hive_core::AuditCategory::PolicyChange,
+                        "policy_loaded",
+                    )
+                    .resource(policy_path.display().to_string())
+                    .details(serde_json::json!({
+                        "rules": policy.rules.len(),
+                        "default": policy.default.to_string(),
+                        "sha256": digest,
+                    }));
+                    if let Err(e) = audit_log.append(event) {
+                        warn!("Failed to audit approval policy load: {e}");
+                    }
+                }
+                approval_gate = approval_gate.with_policy(policy);
+            }
+            Err(e) => warn!("Approval policy ignored: {e:#}"),
+        }
+    }
+    let approval_gate = std::sync::Arc::new(approval_gate);
     cx.set_global(AppApprovalGate(approval_gate));
-    info!("ApprovalGate initialized with default rules");
+    info!("ApprovalGate initialized");
 
     // Skills registry — file-backed, loads from ~/.hive/skills/*.toml.
     // Ensures all 16 built-in skills exist on disk on first run.
@@ -1400,11 +1490,22 @@ fn init_services(cx: &mut App) -> anyhow::Result<()> {
 
         let identity_path = network_base_dir.join("network_identity.json");
         let identity = hive_network::NodeIdentity::load_or_generate(&identity_path, &node_name);
-        let node = hive_network::HiveNode::new(identity, net_config);
+        let trust_store = hive_network::TrustStore::load_or_default(
+            &network_base_dir.join("network_trust.json"),
+            net_config.trust_policy,
+        );
+        let delegate_teams = net_config.delegate_teams;
+        let accept_teams = net_config.accept_teams;
+        let node = hive_network::HiveNode::new(identity, net_config).with_trust_store(trust_store);
         let listen_addr = node.config().listen_addr;
 
         cx.set_global(AppNetwork(std::sync::Arc::new(node.handle())));
 
+        // Remote teams — both directions are opt-in via network.json.
+        let team_dispatcher = Arc::new(std::sync::OnceLock::new());
+        cx.set_global(AppTeamDispatcher(team_dispatcher.clone()));
+        let team_routing = accept_teams.then(|| cx.global::<AppAiService>().0.routing_handle());
+
         // Start the real node on a background thread with its own tokio runtime.
         std::thread::Builder::new()
             .name("hive-p2p".into())
@@ -1422,6 +1523,65 @@ fn init_services(cx: &mut App) -> anyhow::Result<()> {
                             return;
                         }
                     }
+                    // Advertise local hardware and models in the capability
+                    // document published with each heartbeat.
+                    let handle = node.handle();
+                    if let Ok(hw) = tokio::task::spawn_blocking(
+                        hive_terminal::local_ai::detection::detect_hardware,
+                    )
+                    .await
+                    {
+                        handle
+                            .set_hardware(hive_network::HardwareProfile {
+                                cpu_cores: hw.cpu_cores,
+                                memory_bytes: hw.memory_bytes,
+                                gpus: hw
+                                    .gpus
+                                    .into_iter()
+                                    .map(|g| hive_network::GpuProfile {
+                                        name: g.name,
+                                        memory_bytes: g.memory_bytes,
+                                    })
+                                    .collect(),
+                            })
+                            .await;
+                    }
+                    let mut detector = hive_terminal::local_ai::detection::LocalAiDetector::new();
+                    let offers = detector
+                        .detect_all()
+                        .await
+                        .into_iter()
+                        .filter(|p| p.status.is_online())
+                        .map(|p| hive_network::ProviderOffer {
+                            name: p.kind.display_name().to_string(),
+                            local: true,
+                            models: p
+                                .models
+                                .iter()
+                                .map(|m| hive_network::ModelOffer::local(m.name.clone()))
+                                .collect(),
+                        })
+                        .collect();
+                    handle.set_providers(offers).await;
+
+                    if delegate_teams {
+                        let dispatcher =
+                            hive_agents::NetworkTeamDispatcher::attach(handle.clone()).await;
+                        let _ = team_dispatcher.set(dispatcher);
+                        info!("Swarm team delegation to federated peers enabled");
+                    }
+                    if let Some(routing) = team_routing {
+                        let worker = hive_agents::RemoteTeamWorker::new(
+                            handle.clone(),
+                            Arc::new(RemoteTeamExecutor { handle: routing }),
+                            hive_agents::swarm::SwarmConfig::default(),
+                        );
+                        worker.attach().await;
+                        info!("Accepting swarm teams from trusted peers");
+                        // Drives assignments for the lifetime of the app.
+                        worker.run().await;
+                    }
+
                     // Park the runtime so spawned tasks (server, discovery,
                     // heartbeat) keep running for the lifetime of the app.
                     loop {
@@ -1433,10 +1593,18 @@ fn init_services(cx: &mut App) -> anyhow::Result<()> {
         info!("P2P network node initialized (background start in progress)");
     }
 
-    // Auto-update service — checks GitHub releases for newer versions.
-    let updater = UpdateService::new(VERSION);
+    // Auto-update service — checks GitHub releases (or the configured
+    // mirror) for newer signed versions on the configured channel.
+    let updater = UpdateService::with_options(
+        VERSION,
+        config.update_channel,
+        config.update_mirror_url.clone(),
+    );
     cx.set_global(AppUpdater(updater));
-    info!("UpdateService initialized (current: v{VERSION})");
+    info!(
+        "UpdateService initialized (current: v{VERSION}, channel: {})",
+        config.update_channel
+    );
 
     // Remote control daemon — web UI for phone/tablet access.
     //
@@ -1465,6 +1633,27 @@ fn init_services(cx: &mut App) -> anyhow::Result<()> {
                     .build()
                     .expect("Remote daemon tokio runtime");
                 rt.block_on(async {
+                    let auth = match hive_remote::auth::WebAuth::load(
+                        &data_dir.join("remote_devices.json"),
+                    ) {
+                        Ok(auth) => std::sync::Arc::new(auth),
+                        Err(e) => {
+                            error!("Failed to load paired remote devices: {}", e);
+                            return;
+                        }
+                    };
+                    // Without any paired device nobody can reach the web UI,
+                    // so offer a full-control invite for the first one.
+                    if !auth.has_active_devices() {
+                        match auth.create_invite(hive_remote::pairing::DeviceScope::Full) {
+                            Ok(invite) => info!(
+                                "Pair a remote device within 10 minutes using: {}",
+                                invite.url
+                            ),
+                            Err(e) => warn!("Failed to create remote pairing invite: {}", e),
+                        }
+                    }
+
                     let daemon_config = hive_remote::daemon::DaemonConfig {
                         config_root: None,
                         data_dir,
@@ -1486,7 +1675,7 @@ fn init_services(cx: &mut App) -> anyhow::Result<()> {
                             }
 
                             let daemon = std::sync::Arc::new(tokio::sync::RwLock::new(daemon));
-                            let router = hive_remote::web_server::build_router(daemon);
+                            let router = hive_remote::web_server::build_router(daemon, auth);
                             let addr = format!("0.0.0.0:{}", remote_web_port);
                             match tokio::net::TcpListener::bind(&addr).await {
                                 Ok(listener) => {
@@ -1536,6 +1725,19 @@ fn init_services(cx: &mut App) -> anyhow::Result<()> {
                                     handler =
                                         handler.with_rag(cx.global::<AppRagService>().0.clone());
                                 }
+                                // Tasks live next to a2a.toml so they survive restarts.
+                                let store_dir = a2a_config_path
+                                    .parent()
+                                    .unwrap_or_else(|| std::path::Path::new("."));
+                                match hive_a2a::open_task_store(
+                                    &a2a_config.server.task_store,
+                                    store_dir,
+                                ) {
+                                    Ok(store) => handler = handler.with_store(store),
+                                    Err(e) => {
+                                        warn!("A2A task store unavailable, tasks will not persist: {e}")
+                                    }
+                                }
                                 std::sync::Arc::new(handler)
                                     as std::sync::Arc<dyn hive_a2a::TaskHandlerAdapter>
                             })
@@ -1931,6 +2133,16 @@ fn start_background_update_check(cx: &mut App) {
 // ---------------------------------------------------------------------------
 
 fn main() {
+    // Used by the auto-updater's post-install health check and by packagers;
+    // must not start the UI.
+    if std::env::args()
+        .skip(1)
+        .any(|a| a == "--version" || a == "-V")
+    {
+        println!("hive {VERSION}");
+        return;
+    }
+
     let _log_guard = logging::init_logging().expect("Failed to initialize logging");
     info!("Starting Hive v{VERSION}");
 
@@ -13,6 +13,7 @@ pub enum Role {
     User,
     Assistant,
     System,
+    Tool,
 }
 
 impl Role {
@@ -21,6 +22,7 @@ impl Role {
             Role::User => "You",
             Role::Assistant => "Hive",
             Role::System => "System",
+            Role::Tool => "Tool",
         }
     }
 }
@@ -36,6 +38,8 @@ pub struct ChatApp {
     pub scroll_offset: u16,
     pub should_quit: bool,
     pub stream_buffer: String,
+    /// Tool call awaiting a y/n from the user (local mode).
+    pub pending_approval: Option<String>,
 }
 
 impl ChatApp {
@@ -51,6 +55,7 @@ impl ChatApp {
             scroll_offset: 0,
             should_quit: false,
             stream_buffer: String::new(),
+            pending_approval: None,
         };
         app.messages.push(Message {
             role: Role::System,
@@ -133,12 +138,13 @@ impl ChatApp {
     pub fn api_messages(&self) -> Vec<ChatMessage> {
         self.messages
             .iter()
-            .filter(|m| m.role != Role::System)
+            .filter(|m| !matches!(m.role, Role::System | Role::Tool))
             .map(|m| ChatMessage {
                 role: match m.role {
                     Role::User => "user".into(),
                     Role::Assistant => "assistant".into(),
                     Role::System => "system".into(),
+                    Role::Tool => "tool".into(),
                 },
                 content: m.content.clone(),
             })
@@ -168,6 +174,65 @@ impl ChatApp {
         self.scroll_offset = 0;
     }
 
+    /// Finish a locally streamed reply. A reply that only requested tools
+    /// has no text and adds no message.
+    pub fn finalize_local_stream(&mut self, tokens: u32) {
+        let content = std::mem::take(&mut self.stream_buffer);
+        if !content.is_empty() {
+            self.messages.push(Message {
+                role: Role::Assistant,
+                content,
+            });
+        }
+        self.session_tokens += tokens as i32;
+        self.waiting = false;
+        self.scroll_offset = 0;
+    }
+
+    /// Show a finished tool call, with long output cut down to a preview.
+    pub fn add_tool_output(&mut self, summary: &str, output: &str) {
+        const PREVIEW_LINES: usize = 8;
+        let mut content = summary.to_string();
+        for line in output.lines().take(PREVIEW_LINES) {
+            content.push_str("\n  ");
+            content.push_str(line);
+        }
+        let hidden = output.lines().count().saturating_sub(PREVIEW_LINES);
+        if hidden > 0 {
+            content.push_str(&format!("\n  ... {hidden} more line(s)"));
+        }
+        self.messages.push(Message {
+            role: Role::Tool,
+            content,
+        });
+        self.scroll_offset = 0;
+    }
+
+    /// Ask the user to approve a tool call; input is disabled until
+    /// [`resolve_approval`](Self::resolve_approval).
+    pub fn request_approval(&mut self, summary: &str, reason: Option<&str>) {
+        let mut content = format!("Allow {summary}? [y/n]");
+        if let Some(reason) = reason {
+            content.push_str(&format!("\n  ({reason})"));
+        }
+        self.messages.push(Message {
+            role: Role::System,
+            content,
+        });
+        self.pending_approval = Some(summary.to_string());
+        self.scroll_offset = 0;
+    }
+
+    pub fn resolve_approval(&mut self, approved: bool) {
+        if let Some(summary) = self.pending_approval.take() {
+            let verdict = if approved { "Approved" } else { "Rejected" };
+            self.messages.push(Message {
+                role: Role::System,
+                content: format!("{verdict}: {summary}"),
+            });
+        }
+    }
+
     pub fn add_error(&mut self, err: &str) {
         self.messages.push(Message {
             role: Role::System,
@@ -282,6 +347,39 @@ mod tests {
         assert!(ChatApp::parse_sse_line("").is_none());
     }
 
+    #[test]
+    fn tool_only_local_reply_adds_no_message() {
+        let mut app = ChatApp::new("llama3.2".into(), "local".into());
+        app.waiting = true;
+        app.finalize_local_stream(20);
+        assert_eq!(app.messages.len(), 1);
+        assert_eq!(app.session_tokens, 20);
+        assert!(!app.waiting);
+
+        app.add_tool_output("read_file a.rs", &"line\n".repeat(10));
+        let preview = &app.messages.last().unwrap().content;
+        assert!(preview.ends_with("... 2 more line(s)"));
+        assert!(app.api_messages().is_empty());
+    }
+
+    #[test]
+    fn approval_prompt_is_resolved_once() {
+        let mut app = ChatApp::new("llama3.2".into(), "local".into());
+        app.request_approval("execute_command cargo test", None);
+        assert_eq!(
+            app.pending_approval.as_deref(),
+            Some("execute_command cargo test")
+        );
+
+        app.resolve_approval(false);
+        app.resolve_approval(true);
+        assert!(app.pending_approval.is_none());
+        assert_eq!(
+            app.messages.last().unwrap().content,
+            "Rejected: execute_command cargo test"
+        );
+    }
+
     fn sample_response(content: &str) -> ChatResponse {
         ChatResponse {
             id: "resp-1".into(),
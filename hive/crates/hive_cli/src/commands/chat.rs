@@ -1,4 +1,8 @@
 //! hive chat command - interactive TUI chat.
+//!
+//! By default the chat goes through Hive Cloud. With `--local` it drives the
+//! configured providers directly (see [`crate::local`]) with tool use and
+//! approval prompts, and saves the conversation for the desktop app.
 
 use std::io::Stdout;
 use std::time::Duration;
@@ -9,18 +13,66 @@ use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
 use crossterm::ExecutableCommand;
+use hive_core::config::ConfigManager;
 use ratatui::prelude::*;
 
 use crate::api::{ChatResponse, CloudClient};
-use crate::app::{ChatApp, SseEvent};
+use crate::app::{ChatApp, Message, Role, SseEvent};
+use crate::local::{describe_call, LocalSession, MAX_TOOL_ITERATIONS};
 use crate::ui;
 
-pub async fn run(model_override: Option<String>) -> Result<()> {
+type ChatTerminal = Terminal<CrosstermBackend<Stdout>>;
+
+pub async fn run(
+    model_override: Option<String>,
+    local: bool,
+    resume: Option<String>,
+) -> Result<()> {
+    if local {
+        return run_local(model_override, resume).await;
+    }
     let config = hive_core::HiveConfig::load()?;
     let client = CloudClient::new(config.cloud_api_url.as_deref(), config.cloud_jwt.as_deref());
     let model = model_override.unwrap_or_else(|| config.default_model.clone());
     let tier = config.cloud_tier.clone().unwrap_or_else(|| "free".into());
     let mut app = ChatApp::new(model, tier);
+    let mut terminal = enter_terminal()?;
+    let result = run_chat_loop(&mut terminal, &mut app, &client).await;
+    leave_terminal(&mut terminal)?;
+    result
+}
+
+async fn run_local(model_override: Option<String>, resume: Option<String>) -> Result<()> {
+    // ConfigManager (unlike HiveConfig::load) fills in the stored API keys.
+    let config = ConfigManager::new()?.get();
+    let mut session = LocalSession::start(&config, model_override, resume.as_deref()).await?;
+    let mut app = ChatApp::new(session.model().to_string(), "local".into());
+    for stored in &session.transcript.conversation.messages {
+        let role = match stored.role.as_str() {
+            "user" => Role::User,
+            "assistant" => Role::Assistant,
+            "tool" => Role::Tool,
+            _ => continue,
+        };
+        app.messages.push(Message {
+            role,
+            content: stored.content.clone(),
+        });
+    }
+
+    let mut terminal = enter_terminal()?;
+    let result = run_local_chat_loop(&mut terminal, &mut app, &mut session).await;
+    leave_terminal(&mut terminal)?;
+
+    session.save()?;
+    if !session.transcript.conversation.messages.is_empty() {
+        let id = session.transcript.id();
+        println!("Conversation saved as {id}. Resume with: hive chat --local --resume {id}");
+    }
+    result
+}
+
+fn enter_terminal() -> Result<ChatTerminal> {
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
     stdout.execute(EnterAlternateScreen)?;
@@ -34,19 +86,20 @@ pub async fn run(model_override: Option<String>) -> Result<()> {
     }));
 
     let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-    let result = run_chat_loop(&mut terminal, &mut app, &client).await;
+    Ok(Terminal::new(backend)?)
+}
 
+fn leave_terminal(terminal: &mut ChatTerminal) -> Result<()> {
     // Restore terminal and panic hook
     disable_raw_mode()?;
     terminal.backend_mut().execute(LeaveAlternateScreen)?;
     terminal.show_cursor()?;
     let _ = std::panic::take_hook(); // drop the custom hook
-    result
+    Ok(())
 }
 
 async fn run_chat_loop(
-    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    terminal: &mut ChatTerminal,
     app: &mut ChatApp,
     client: &CloudClient,
 ) -> Result<()> {
@@ -78,16 +131,188 @@ async fn run_chat_loop(
     Ok(())
 }
 
+async fn run_local_chat_loop(
+    terminal: &mut ChatTerminal,
+    app: &mut ChatApp,
+    session: &mut LocalSession,
+) -> Result<()> {
+    loop {
+        terminal.draw(|f| ui::draw_chat(f, app))?;
+        if app.should_quit {
+            break;
+        }
+        if event::poll(Duration::from_millis(50))? {
+            if let Event::Key(key) = event::read()? {
+                match handle_key(key, app) {
+                    KeyAction::Quit => break,
+                    KeyAction::Submit => {
+                        if let Some(text) = app.submit_input() {
+                            session.transcript.push_user(&text);
+                            run_local_turn(terminal, app, session).await?;
+                            if let Err(e) = session.save() {
+                                app.add_error(&format!("Failed to save conversation: {e}"));
+                            }
+                        }
+                    }
+                    KeyAction::Continue => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Stream one reply and run the tool rounds it asks for. Provider errors are
+/// shown in the chat; only terminal I/O errors are returned.
+async fn run_local_turn(
+    terminal: &mut ChatTerminal,
+    app: &mut ChatApp,
+    session: &mut LocalSession,
+) -> Result<()> {
+    for iteration in 0..=MAX_TOOL_ITERATIONS {
+        app.waiting = true;
+        terminal.draw(|f| ui::draw_chat(f, app))?;
+
+        let (provider, request) = match session.prepare() {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                app.add_error(&e.to_string());
+                return Ok(());
+            }
+        };
+        let mut rx = match provider.stream_chat(&request).await {
+            Ok(rx) => rx,
+            Err(e) => {
+                app.add_error(&e.to_string());
+                return Ok(());
+            }
+        };
+
+        let mut content = String::new();
+        let mut tool_calls = Vec::new();
+        let mut usage = None;
+        while let Some(chunk) = rx.recv().await {
+            content.push_str(&chunk.content);
+            app.append_stream_chunk(&chunk.content);
+            if chunk.usage.is_some() {
+                usage = chunk.usage;
+            }
+            if let Some(calls) = chunk.tool_calls {
+                tool_calls = calls;
+            }
+            terminal.draw(|f| ui::draw_chat(f, app))?;
+            if quit_requested()? {
+                app.should_quit = true;
+                break;
+            }
+            if chunk.done {
+                break;
+            }
+        }
+
+        let tokens = usage
+            .as_ref()
+            .map_or(0, |u| u.prompt_tokens + u.completion_tokens);
+        app.finalize_local_stream(tokens);
+
+        let run_tools =
+            !tool_calls.is_empty() && !app.should_quit && iteration < MAX_TOOL_ITERATIONS;
+        session.transcript.push_assistant(
+            &content,
+            run_tools.then(|| tool_calls.clone()),
+            &request.model,
+            usage.as_ref(),
+        );
+        if !run_tools {
+            if !tool_calls.is_empty() && !app.should_quit {
+                app.add_error(&format!("Stopped after {MAX_TOOL_ITERATIONS} tool rounds"));
+            }
+            return Ok(());
+        }
+
+        let mut rejected = Vec::new();
+        for approval in session.approvals_needed(&tool_calls) {
+            // Once the user quits, reject whatever is left so the history
+            // still answers every call.
+            if app.should_quit {
+                rejected.push(approval.call.id);
+                continue;
+            }
+            if let Some(denial) = &approval.denial {
+                app.add_error(&format!("{}: {denial}", approval.summary));
+                rejected.push(approval.call.id);
+                continue;
+            }
+            app.request_approval(&approval.summary, approval.reason.as_deref());
+            let approved = match wait_for_approval(terminal, app)? {
+                Some(approved) => approved,
+                None => {
+                    app.should_quit = true;
+                    false
+                }
+            };
+            app.resolve_approval(approved);
+            if !approved {
+                rejected.push(approval.call.id);
+            }
+        }
+
+        app.waiting = true;
+        terminal.draw(|f| ui::draw_chat(f, app))?;
+        let results = tokio::task::block_in_place(|| session.execute(&tool_calls, &rejected));
+        for (call, result) in tool_calls.iter().zip(&results) {
+            app.add_tool_output(&describe_call(call), &result.content);
+        }
+        if app.should_quit {
+            app.waiting = false;
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+/// Drain pending key events, reporting whether the user asked to quit.
+fn quit_requested() -> Result<bool> {
+    while event::poll(Duration::ZERO)? {
+        if let Event::Key(key) = event::read()? {
+            if is_quit(&key) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Block until the user answers the pending approval. `None` means quit.
+fn wait_for_approval(terminal: &mut ChatTerminal, app: &ChatApp) -> Result<Option<bool>> {
+    loop {
+        terminal.draw(|f| ui::draw_chat(f, app))?;
+        if let Event::Key(key) = event::read()? {
+            if is_quit(&key) {
+                return Ok(None);
+            }
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => return Ok(Some(true)),
+                KeyCode::Char('n') | KeyCode::Char('N') => return Ok(Some(false)),
+                _ => {}
+            }
+        }
+    }
+}
+
 enum KeyAction {
     Quit,
     Submit,
     Continue,
 }
 
-fn handle_key(key: KeyEvent, app: &mut ChatApp) -> KeyAction {
-    if key.code == KeyCode::Esc
+fn is_quit(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Esc
         || (key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c'))
-    {
+}
+
+fn handle_key(key: KeyEvent, app: &mut ChatApp) -> KeyAction {
+    if is_quit(&key) {
         return KeyAction::Quit;
     }
     if app.waiting {
@@ -136,7 +361,7 @@ fn handle_key(key: KeyEvent, app: &mut ChatApp) -> KeyAction {
 }
 
 async fn process_sse_response(
-    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    terminal: &mut ChatTerminal,
     app: &mut ChatApp,
     response: reqwest::Response,
 ) {
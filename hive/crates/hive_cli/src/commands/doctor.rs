@@ -0,0 +1,111 @@
+//! hive doctor command.
+
+use crate::ui;
+use anyhow::{bail, Context, Result};
+use hive_core::config::ConfigManager;
+use hive_core::diagnostics::{CheckStatus, DiagnosticContext, DiagnosticRegistry, DoctorReport};
+use hive_core::HiveConfig;
+use std::path::PathBuf;
+
+/// Run every registered diagnostic (or those matching `only`), optionally
+/// apply safe fixes, and print the report.
+pub async fn run(
+    as_json: bool,
+    fix: bool,
+    only: Vec<String>,
+    workspace: Option<PathBuf>,
+) -> Result<()> {
+    let mut registry = build_registry()?;
+    registry.retain_matching(&only);
+    if registry.is_empty() {
+        bail!("No diagnostics match {}", only.join(", "));
+    }
+
+    let workspace = match workspace {
+        Some(path) => path,
+        None => std::env::current_dir().context("Failed to resolve current directory")?,
+    };
+    let mut ctx = DiagnosticContext::new(HiveConfig::base_dir()?, workspace);
+    if let Ok(manager) = ConfigManager::new() {
+        ctx = ctx.with_config(manager.get());
+    }
+
+    let mut report = registry.run(&ctx).await;
+    if fix {
+        registry.apply_fixes(&ctx, &mut report).await;
+    }
+
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report, fix);
+    }
+
+    if report.has_failures() {
+        bail!("{} check(s) failed", report.summary.fail);
+    }
+    Ok(())
+}
+
+/// Every crate's diagnostics, in registration order.
+fn build_registry() -> Result<DiagnosticRegistry> {
+    let mut registry = DiagnosticRegistry::new();
+    hive_core::diagnostics::register(&mut registry)?;
+    hive_ai::diagnostics::register(&mut registry)?;
+    hive_terminal::diagnostics::register(&mut registry)?;
+    hive_agents::diagnostics::register(&mut registry)?;
+    Ok(registry)
+}
+
+fn print_report(report: &DoctorReport, fixed: bool) {
+    ui::print_header("Hive Doctor");
+    println!(
+        "  Hive {} on {}/{}",
+        report.hive_version, report.os, report.arch
+    );
+    println!();
+    for result in &report.results {
+        println!(
+            "  [{}] {:<24} {}",
+            result.check.status, result.check.name, result.check.message
+        );
+        if result.check.status != CheckStatus::Pass {
+            if let Some(ref suggestion) = result.check.fix_suggestion {
+                println!("  {:<32}-> {}", "", suggestion);
+            }
+        }
+    }
+
+    if !report.fixes.is_empty() {
+        println!();
+        println!("  Fixes:");
+        for outcome in &report.fixes {
+            println!(
+                "    [{}] {}: {}",
+                if outcome.success { "ok" } else { "FAILED" },
+                outcome.id,
+                outcome.message
+            );
+        }
+    } else if fixed {
+        println!();
+        println!("  Nothing to fix automatically.");
+    }
+
+    let summary = &report.summary;
+    println!();
+    println!(
+        "  {} passed, {} warnings, {} failed",
+        summary.pass, summary.warn, summary.fail
+    );
+    let fixable = report
+        .results
+        .iter()
+        .filter(|r| r.fixable && r.check.status != CheckStatus::Pass)
+        .count();
+    if fixable > 0 && !fixed {
+        println!("  {fixable} issue(s) can be fixed with 'hive doctor --fix'.");
+    }
+    println!("  Attach 'hive doctor --json' output to support tickets.");
+    println!();
+}
@@ -1,9 +1,12 @@
 pub mod build_ticket;
 pub mod chat;
 pub mod config;
+pub mod doctor;
 pub mod login;
 pub mod models;
+pub mod policy;
 pub mod remote;
+pub mod run;
 pub mod status;
 pub mod sync;
 pub mod tools;
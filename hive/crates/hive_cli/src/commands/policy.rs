@@ -0,0 +1,64 @@
+//! hive policy command handler.
+
+use crate::ui;
+use anyhow::{bail, Result};
+use hive_agents::activity::ApprovalPolicy;
+use std::path::Path;
+
+/// Evaluate the sample operations of a policy file (and optionally a separate
+/// cases file) and report which ones produce an unexpected outcome.
+pub async fn test(file: &Path, cases: Option<&Path>, as_json: bool) -> Result<()> {
+    let policy = ApprovalPolicy::load(file)?;
+    let mut results = policy.run_tests();
+    if let Some(cases) = cases {
+        let extra = ApprovalPolicy::load_test_cases(cases)?;
+        results.extend(policy.run_cases(&extra));
+    }
+
+    let failed = results.iter().filter(|r| !r.passed).count();
+
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        ui::print_header("Approval Policy Tests");
+        println!("  Policy: {}", file.display());
+        println!(
+            "  Rules: {}  Default: {}",
+            policy.rules.len(),
+            policy.default
+        );
+        println!();
+        if results.is_empty() {
+            println!("  No test cases found. Add [[test]] entries or pass --cases.");
+            println!();
+            return Ok(());
+        }
+        println!(
+            "  {:<6} {:<32} {:<24} {:<24} {}",
+            "", "CASE", "EXPECTED", "ACTUAL", "RULE"
+        );
+        println!("  {}", "-".repeat(100));
+        for r in &results {
+            let expected = match &r.expected_rule {
+                Some(rule) => format!("{} ({rule})", r.expected),
+                None => r.expected.to_string(),
+            };
+            println!(
+                "  {:<6} {:<32} {:<24} {:<24} {}",
+                if r.passed { "ok" } else { "FAIL" },
+                r.name,
+                expected,
+                r.decision.outcome.to_string(),
+                r.decision.rule.as_deref().unwrap_or("(default)")
+            );
+        }
+        println!();
+        println!("  {} passed, {} failed", results.len() - failed, failed);
+        println!();
+    }
+
+    if failed > 0 {
+        bail!("{failed} policy test case(s) failed");
+    }
+    Ok(())
+}
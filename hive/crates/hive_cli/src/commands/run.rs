@@ -0,0 +1,643 @@
+//! `hive run "<prompt>"` — a headless agent turn for scripts and CI.
+//!
+//! Runs the same tool-using loop as `hive chat --local`, but without a TUI:
+//! there is nobody to approve writes or commands, so the `--allow-tool` list
+//! is the approval. Without one, only read-only tools are offered. Calls that
+//! would need approval because untrusted content entered the context are
+//! always rejected.
+//!
+//! Progress goes to stderr and the final answer to stdout, or with `--json`
+//! every step is printed to stdout as one JSON object per line (`start`,
+//! `tool_call`, `tool_result`, `usage`, `final`, `error`).
+//!
+//! Exit codes:
+//!
+//! * `0` — the agent produced a final answer.
+//! * `1` — the run failed (no provider, provider or I/O error).
+//! * `2` — invalid input (empty prompt, unknown persona, skill or tool).
+//! * `3` — stopped after `--max-iterations` tool rounds.
+//! * `4` — stopped because `--max-cost` was reached.
+//!
+//! The cost cap also holds for providers that stream no token usage: their
+//! usage is estimated from the request and reply text, and `usage` events
+//! carry `"estimated": true`.
+
+use std::io::{IsTerminal, Read};
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+use hive_agents::skills::SkillsRegistry;
+use hive_agents::{Persona, PersonaRegistry, SkillLoader};
+use hive_ai::cost::{calculate_cost, estimate_message_tokens, estimate_tokens};
+use hive_ai::{ChatRequest, TokenUsage, ToolCall as AiToolCall};
+use hive_core::config::ConfigManager;
+use hive_core::HiveConfig;
+use serde::Serialize;
+
+use crate::local::{self, describe_call, LocalSession};
+
+pub const EXIT_ERROR: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_MAX_ITERATIONS: i32 = 3;
+pub const EXIT_BUDGET: i32 = 4;
+
+/// Options for a headless run, straight from the command line.
+pub struct RunOptions {
+    /// Prompt text; read from stdin when absent or `-`.
+    pub prompt: Option<String>,
+    pub workspace: Option<PathBuf>,
+    pub persona: Option<String>,
+    pub skill: Option<String>,
+    pub model: Option<String>,
+    pub max_iterations: usize,
+    /// Spending cap in USD, checked after every model response. Responses
+    /// without reported usage are costed from estimated token counts.
+    pub max_cost: Option<f64>,
+    pub allow_tools: Vec<String>,
+    pub json: bool,
+}
+
+/// Invalid input from the caller, reported with [`EXIT_USAGE`].
+#[derive(Debug)]
+struct UsageError(String);
+
+impl std::fmt::Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+fn usage_error(message: impl Into<String>) -> anyhow::Error {
+    anyhow!(UsageError(message.into()))
+}
+
+/// How a run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Completed,
+    MaxIterations,
+    BudgetExceeded,
+}
+
+impl Status {
+    fn exit_code(self) -> i32 {
+        match self {
+            Self::Completed => 0,
+            Self::MaxIterations => EXIT_MAX_ITERATIONS,
+            Self::BudgetExceeded => EXIT_BUDGET,
+        }
+    }
+}
+
+/// One line of `--json` output.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Event<'a> {
+    Start {
+        conversation_id: &'a str,
+        model: &'a str,
+        workspace: String,
+        tools: Vec<&'a str>,
+    },
+    ToolCall {
+        id: &'a str,
+        name: &'a str,
+        input: &'a serde_json::Value,
+    },
+    ToolResult {
+        id: &'a str,
+        name: &'a str,
+        content: &'a str,
+        is_error: bool,
+    },
+    Usage {
+        iteration: usize,
+        model: &'a str,
+        prompt_tokens: u32,
+        completion_tokens: u32,
+        cost_usd: f64,
+        total_cost_usd: f64,
+        /// The provider reported no usage; the token counts are estimates.
+        estimated: bool,
+    },
+    Final {
+        status: Status,
+        answer: &'a str,
+        iterations: usize,
+        total_cost_usd: f64,
+        conversation_id: &'a str,
+    },
+    Error {
+        message: String,
+        exit_code: i32,
+    },
+}
+
+/// Run the `run` subcommand and exit with a status scripts can check.
+pub async fn run(opts: RunOptions) -> Result<()> {
+    let json = opts.json;
+    match run_agent(opts).await {
+        Ok(Status::Completed) => Ok(()),
+        Ok(status) => std::process::exit(status.exit_code()),
+        Err(e) => {
+            let exit_code = if e.is::<UsageError>() {
+                EXIT_USAGE
+            } else {
+                EXIT_ERROR
+            };
+            if json {
+                emit(
+                    true,
+                    &Event::Error {
+                        message: format!("{e:#}"),
+                        exit_code,
+                    },
+                );
+            } else {
+                eprintln!("Error: {e:#}");
+            }
+            std::process::exit(exit_code)
+        }
+    }
+}
+
+async fn run_agent(opts: RunOptions) -> Result<Status> {
+    let prompt = read_prompt(opts.prompt)?;
+
+    // Tools resolve relative paths and run commands in the process cwd.
+    if let Some(workspace) = &opts.workspace {
+        if !workspace.is_dir() {
+            return Err(usage_error(format!(
+                "workspace '{}' is not a directory",
+                workspace.display()
+            )));
+        }
+        std::env::set_current_dir(workspace)
+            .with_context(|| format!("Failed to enter {}", workspace.display()))?;
+    }
+    let workspace = std::env::current_dir().context("Failed to resolve current directory")?;
+
+    let persona = match &opts.persona {
+        Some(name) => {
+            let registry = PersonaRegistry::new();
+            let persona = find_persona(&registry, name).ok_or_else(|| {
+                usage_error(format!(
+                    "unknown persona '{name}'. Built-in personas: {}",
+                    persona_names(&registry).join(", ")
+                ))
+            })?;
+            Some(persona.clone())
+        }
+        None => None,
+    };
+    let skill = match &opts.skill {
+        Some(name) => Some(load_skill(name)?),
+        None => None,
+    };
+
+    // ConfigManager (unlike HiveConfig::load) fills in the stored API keys.
+    let config = ConfigManager::new()?.get();
+    let mut session = LocalSession::start(&config, opts.model, None).await?;
+    let allowed = if opts.allow_tools.is_empty() {
+        session
+            .tool_names()
+            .into_iter()
+            .filter(|name| !local::requires_approval(name))
+            .map(String::from)
+            .collect()
+    } else {
+        opts.allow_tools
+    };
+    session
+        .restrict_tools(&allowed)
+        .map_err(|e| usage_error(e.to_string()))?;
+    session.set_system_prompt(system_prompt(&workspace, persona.as_ref(), skill.as_ref()));
+
+    emit(
+        opts.json,
+        &Event::Start {
+            conversation_id: session.transcript.id(),
+            model: session.model(),
+            workspace: workspace.display().to_string(),
+            tools: session.tool_names(),
+        },
+    );
+    session.transcript.push_user(&prompt);
+
+    let result = run_loop(&mut session, opts.max_iterations, opts.max_cost, opts.json).await;
+    if let Err(e) = session.save() {
+        tracing::warn!("Failed to save conversation: {e}");
+    }
+    result
+}
+
+async fn run_loop(
+    session: &mut LocalSession,
+    max_iterations: usize,
+    max_cost: Option<f64>,
+    json: bool,
+) -> Result<Status> {
+    let mut total_cost = 0.0;
+    for iteration in 0..=max_iterations {
+        let (provider, request) = session.prepare()?;
+        let mut rx = provider.stream_chat(&request).await?;
+
+        let mut content = String::new();
+        let mut tool_calls = Vec::new();
+        let mut usage = None;
+        while let Some(chunk) = rx.recv().await {
+            content.push_str(&chunk.content);
+            if chunk.usage.is_some() {
+                usage = chunk.usage;
+            }
+            if let Some(calls) = chunk.tool_calls {
+                tool_calls = calls;
+            }
+            if chunk.done {
+                break;
+            }
+        }
+
+        // Without reported usage, estimate it so --max-cost still holds.
+        let billed = usage
+            .clone()
+            .unwrap_or_else(|| estimate_usage(&request, &content, &tool_calls));
+        let cost = calculate_cost(
+            &request.model,
+            billed.prompt_tokens as usize,
+            billed.completion_tokens as usize,
+        )
+        .total_cost;
+        total_cost += cost;
+        emit(
+            json,
+            &Event::Usage {
+                iteration,
+                model: &request.model,
+                prompt_tokens: billed.prompt_tokens,
+                completion_tokens: billed.completion_tokens,
+                cost_usd: cost,
+                total_cost_usd: total_cost,
+                estimated: usage.is_none(),
+            },
+        );
+
+        let status = if tool_calls.is_empty() {
+            Some(Status::Completed)
+        } else if iteration == max_iterations {
+            Some(Status::MaxIterations)
+        } else if max_cost.is_some_and(|cap| total_cost >= cap) {
+            Some(Status::BudgetExceeded)
+        } else {
+            None
+        };
+        session.transcript.push_assistant(
+            &content,
+            status.is_none().then(|| tool_calls.clone()),
+            &request.model,
+            usage.as_ref(),
+        );
+        if let Some(status) = status {
+            emit(
+                json,
+                &Event::Final {
+                    status,
+                    answer: &content,
+                    iterations: iteration,
+                    total_cost_usd: total_cost,
+                    conversation_id: session.transcript.id(),
+                },
+            );
+            return Ok(status);
+        }
+
+        if !json && !content.trim().is_empty() {
+            eprintln!("{}", content.trim());
+        }
+        for call in &tool_calls {
+            emit(
+                json,
+                &Event::ToolCall {
+                    id: &call.id,
+                    name: &call.name,
+                    input: &call.input,
+                },
+            );
+        }
+
+        // Nobody can answer an approval prompt here: gated tools only run
+        // when allow-listed, and never once untrusted content is in play or
+        // an approval rule asks for a decision.
+        let rejected: Vec<String> = session
+            .approvals_needed(&tool_calls)
+            .into_iter()
+            .filter(|approval| approval.reason.is_some() || approval.denial.is_some())
+            .map(|approval| approval.call.id)
+            .collect();
+        let results = tokio::task::block_in_place(|| session.execute(&tool_calls, &rejected));
+        for (call, result) in tool_calls.iter().zip(&results) {
+            emit(
+                json,
+                &Event::ToolResult {
+                    id: &call.id,
+                    name: &call.name,
+                    content: &result.content,
+                    is_error: result.is_error,
+                },
+            );
+        }
+    }
+    unreachable!("the last iteration always returns")
+}
+
+/// Token usage for a response whose provider reported none, estimated from
+/// everything sent and received. Errs high rather than low: tool schemas and
+/// call arguments are counted as serialized JSON.
+fn estimate_usage(request: &ChatRequest, content: &str, tool_calls: &[AiToolCall]) -> TokenUsage {
+    fn json_tokens<T: Serialize + ?Sized>(value: &T) -> usize {
+        serde_json::to_string(value)
+            .map(|json| estimate_tokens(&json))
+            .unwrap_or_default()
+    }
+
+    let prompt = request
+        .system_prompt
+        .as_deref()
+        .map_or(0, |system| estimate_message_tokens("system", system))
+        + request
+            .messages
+            .iter()
+            .map(|m| {
+                estimate_message_tokens("", &m.content)
+                    + m.tool_calls.as_deref().map_or(0, json_tokens)
+            })
+            .sum::<usize>()
+        + request.tools.as_deref().map_or(0, json_tokens);
+    let completion = estimate_tokens(content)
+        + if tool_calls.is_empty() {
+            0
+        } else {
+            json_tokens(tool_calls)
+        };
+    let prompt_tokens = u32::try_from(prompt).unwrap_or(u32::MAX);
+    let completion_tokens = u32::try_from(completion).unwrap_or(u32::MAX);
+    TokenUsage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens.saturating_add(completion_tokens),
+        ..TokenUsage::default()
+    }
+}
+
+/// The prompt argument, or stdin when it is absent or `-`.
+fn read_prompt(prompt: Option<String>) -> Result<String> {
+    let prompt = match prompt.filter(|p| p != "-") {
+        Some(prompt) => prompt,
+        None => {
+            let mut stdin = std::io::stdin();
+            if stdin.is_terminal() {
+                return Err(usage_error(
+                    "no prompt given. Pass it as an argument or pipe it on stdin.",
+                ));
+            }
+            let mut prompt = String::new();
+            stdin
+                .read_to_string(&mut prompt)
+                .context("Failed to read prompt from stdin")?;
+            prompt
+        }
+    };
+    let prompt = prompt.trim();
+    if prompt.is_empty() {
+        return Err(usage_error("prompt is empty"));
+    }
+    Ok(prompt.to_string())
+}
+
+/// Look a persona up by kind (`code_review`) or display name (`Code Reviewer`).
+fn find_persona<'a>(registry: &'a PersonaRegistry, name: &str) -> Option<&'a Persona> {
+    let wanted = normalize(name);
+    registry.all().into_iter().find(|persona| {
+        normalize(&persona.name) == wanted || normalize(&persona.kind.to_string()) == wanted
+    })
+}
+
+fn persona_names(registry: &PersonaRegistry) -> Vec<String> {
+    let mut names: Vec<String> = registry.all().iter().map(|p| normalize(&p.name)).collect();
+    names.sort();
+    names
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+/// Instructions for an enabled skill from `~/.hive/skills`.
+fn load_skill(name: &str) -> Result<(String, String)> {
+    let registry =
+        SkillsRegistry::with_loader(SkillLoader::new(HiveConfig::base_dir()?.join("skills")));
+    match registry.get(name) {
+        Some(skill) if skill.enabled => Ok((skill.name.clone(), skill.instructions.clone())),
+        Some(_) => Err(usage_error(format!("skill '{name}' is disabled"))),
+        None => Err(usage_error(format!(
+            "unknown skill '{name}'. Installed skills: {}",
+            registry
+                .list_enabled()
+                .iter()
+                .map(|s| s.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+fn system_prompt(
+    workspace: &std::path::Path,
+    persona: Option<&Persona>,
+    skill: Option<&(String, String)>,
+) -> String {
+    let mut prompt = match persona {
+        Some(persona) => persona.system_prompt.clone(),
+        None => "You are Hive, a coding agent.".to_string(),
+    };
+    prompt.push_str(&format!(
+        "\n\nYou are running non-interactively in {}. Nobody can answer \
+         questions, so finish the task with the available tools and end with \
+         a concise final answer. Tools that are not offered are not allowed.",
+        workspace.display()
+    ));
+    if let Some((name, instructions)) = skill {
+        prompt.push_str(&format!("\n\n## Skill: {name}\n\n{instructions}"));
+    }
+    prompt
+}
+
+/// Print `event` as a JSON line, or as human-readable output.
+fn emit(json: bool, event: &Event) {
+    if json {
+        match serde_json::to_string(event) {
+            Ok(line) => println!("{line}"),
+            Err(e) => tracing::warn!("Failed to encode event: {e}"),
+        }
+        return;
+    }
+    match event {
+        Event::Start { model, tools, .. } => {
+            eprintln!("hive run: {model} with tools {}", tools.join(", "));
+        }
+        Event::ToolCall { id, name, input } => {
+            let call = hive_ai::ToolCall {
+                id: id.to_string(),
+                name: name.to_string(),
+                input: (*input).clone(),
+            };
+            eprintln!("-> {}", describe_call(&call));
+        }
+        Event::ToolResult {
+            name,
+            content,
+            is_error,
+            ..
+        } => {
+            let first = content.lines().next().unwrap_or_default();
+            let status = if *is_error { "error" } else { "ok" };
+            eprintln!("   {name} {status}: {first}");
+        }
+        Event::Usage { .. } => {}
+        Event::Final {
+            status,
+            answer,
+            iterations,
+            total_cost_usd,
+            conversation_id,
+        } => {
+            if !answer.trim().is_empty() {
+                println!("{}", answer.trim());
+            }
+            match status {
+                Status::Completed => {}
+                Status::MaxIterations => {
+                    eprintln!("Stopped after {iterations} tool rounds (--max-iterations).")
+                }
+                Status::BudgetExceeded => {
+                    eprintln!("Stopped at ${total_cost_usd:.4} (--max-cost).")
+                }
+            }
+            eprintln!(
+                "{iterations} tool round(s), ${total_cost_usd:.4}. \
+                 Resume with 'hive chat --local --resume {conversation_id}'."
+            );
+        }
+        Event::Error { message, .. } => eprintln!("Error: {message}"),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn personas_resolve_by_kind_or_name() {
+        let registry = PersonaRegistry::new();
+        assert_eq!(
+            find_persona(&registry, "code_review").unwrap().name,
+            "Code Reviewer"
+        );
+        assert_eq!(
+            find_persona(&registry, "investigator").unwrap().name,
+            "Investigator"
+        );
+        assert_eq!(
+            find_persona(&registry, "Code Reviewer").unwrap().name,
+            "Code Reviewer"
+        );
+        assert!(find_persona(&registry, "wizard").is_none());
+    }
+
+    #[test]
+    fn usage_errors_are_distinguished() {
+        let err = read_prompt(Some("   ".into())).unwrap_err();
+        assert!(err.is::<UsageError>());
+        assert_eq!(read_prompt(Some(" fix it \n".into())).unwrap(), "fix it");
+    }
+
+    #[test]
+    fn events_are_tagged_json_lines() {
+        let input = serde_json::json!({"path": "src/main.rs"});
+        let line = serde_json::to_string(&Event::ToolCall {
+            id: "call-1",
+            name: "read_file",
+            input: &input,
+        })
+        .unwrap();
+        assert_eq!(
+            line,
+            r#"{"type":"tool_call","id":"call-1","name":"read_file","input":{"path":"src/main.rs"}}"#
+        );
+
+        let line = serde_json::to_value(Event::Final {
+            status: Status::BudgetExceeded,
+            answer: "",
+            iterations: 2,
+            total_cost_usd: 0.5,
+            conversation_id: "abc",
+        })
+        .unwrap();
+        assert_eq!(line["type"], "final");
+        assert_eq!(line["status"], "budget_exceeded");
+        assert_eq!(Status::BudgetExceeded.exit_code(), EXIT_BUDGET);
+    }
+
+    #[test]
+    fn missing_usage_is_estimated_from_request_and_reply() {
+        let request = ChatRequest {
+            messages: vec![hive_ai::ChatMessage::text(
+                hive_ai::MessageRole::User,
+                "x".repeat(4_000),
+            )],
+            model: "claude-opus-4-6".into(),
+            max_tokens: 4096,
+            temperature: None,
+            system_prompt: Some("You are Hive.".into()),
+            tools: None,
+            cache_system_prompt: false,
+        };
+        let calls = [AiToolCall {
+            id: "call-1".into(),
+            name: "read_file".into(),
+            input: serde_json::json!({ "path": "src/main.rs" }),
+        }];
+
+        let usage = estimate_usage(&request, &"y".repeat(400), &calls);
+        assert!(usage.prompt_tokens > 1_000);
+        assert!(usage.completion_tokens > 100);
+        assert_eq!(
+            usage.total_tokens,
+            usage.prompt_tokens + usage.completion_tokens
+        );
+        let cost = calculate_cost(
+            &request.model,
+            usage.prompt_tokens as usize,
+            usage.completion_tokens as usize,
+        );
+        assert!(cost.total_cost > 0.0);
+    }
+
+    #[test]
+    fn system_prompt_layers_persona_and_skill() {
+        let registry = PersonaRegistry::new();
+        let persona = find_persona(&registry, "debug").unwrap();
+        let skill = ("changelog".to_string(), "Write a changelog.".to_string());
+        let prompt = system_prompt(std::path::Path::new("/repo"), Some(persona), Some(&skill));
+        assert!(prompt.starts_with(&persona.system_prompt));
+        assert!(prompt.contains("non-interactively in /repo"));
+        assert!(prompt.ends_with("## Skill: changelog\n\nWrite a changelog."));
+    }
+}
@@ -0,0 +1,675 @@
+//! Local-provider agent session for `hive chat --local` and `hive run`.
+//!
+//! Drives [`hive_ai::AiService`] directly with the user's configured providers
+//! and routing policy instead of going through Hive Cloud. The model may call
+//! the built-in tool registry from `hive_agents`. Tool calls go through the
+//! same approval rules and policy file as the desktop app's [`ApprovalGate`];
+//! where no rule speaks, writes, commands and input automation are held for
+//! approval, as are high-risk tools once untrusted content has entered the
+//! context. Conversations are persisted to the same
+//! [`ConversationStore`] the desktop app uses, so they can be resumed from
+//! either side.
+
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use hive_agents::tool_use::{self, ReplaceInFilesTool, ToolRegistry, ToolResult};
+use hive_agents::{
+    ApprovalGate, ApprovalPolicy, ApprovalRule, ContentGuard, OperationType, PolicyContext,
+    PolicyOutcome,
+};
+use hive_ai::cost::calculate_cost;
+use hive_ai::{
+    AiProvider, AiService, AiServiceConfig, ChatMessage as AiChatMessage, ChatRequest,
+    MessageRole as AiMessageRole, TokenUsage, ToolCall as AiToolCall,
+    ToolDefinition as AiToolDefinition,
+};
+use hive_core::{Conversation, ConversationStore, HiveConfig, StoredMessage};
+
+/// Maximum number of tool rounds per user message.
+pub const MAX_TOOL_ITERATIONS: usize = 10;
+
+/// Agent id local sessions present to approval policies.
+pub const APPROVAL_AGENT_ID: &str = "hive-cli";
+
+/// Tools that change the machine and need a y/n from the user unless an
+/// approval rule explicitly allows the call.
+const APPROVAL_REQUIRED_TOOLS: &[&str] = &[
+    "write_file",
+    "replace_in_files",
+    "execute_command",
+    "click",
+    "type_text",
+    "press_enter",
+];
+
+// ---------------------------------------------------------------------------
+// Transcript
+// ---------------------------------------------------------------------------
+
+/// The provider-facing history of a local chat plus its persisted form.
+///
+/// The two diverge on purpose: the stored conversation uses the desktop
+/// format, which keeps tool output but not the tool-call linkage, so a resumed
+/// conversation is replayed to the provider without its tool messages.
+pub struct Transcript {
+    pub history: Vec<AiChatMessage>,
+    pub conversation: Conversation,
+}
+
+impl Transcript {
+    pub fn new(model: &str) -> Self {
+        Self {
+            history: Vec::new(),
+            conversation: Conversation::new(model),
+        }
+    }
+
+    /// Rebuild a transcript from a stored desktop or CLI conversation.
+    pub fn resume(conversation: Conversation) -> Self {
+        let history = conversation
+            .messages
+            .iter()
+            .filter_map(|m| {
+                let role = match m.role.as_str() {
+                    "user" => AiMessageRole::User,
+                    "assistant" => AiMessageRole::Assistant,
+                    "system" => AiMessageRole::System,
+                    _ => return None,
+                };
+                (!m.content.is_empty()).then(|| AiChatMessage::text(role, &m.content))
+            })
+            .collect();
+        Self {
+            history,
+            conversation,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.conversation.id
+    }
+
+    pub fn push_user(&mut self, text: &str) {
+        self.history
+            .push(AiChatMessage::text(AiMessageRole::User, text));
+        self.conversation.add_message(stored("user", text));
+    }
+
+    /// Record an assistant reply, including any tool calls it requested.
+    pub fn push_assistant(
+        &mut self,
+        content: &str,
+        tool_calls: Option<Vec<AiToolCall>>,
+        model: &str,
+        usage: Option<&TokenUsage>,
+    ) {
+        let mut message = AiChatMessage::text(AiMessageRole::Assistant, content);
+        message.tool_calls = tool_calls;
+        self.history.push(message);
+
+        // Tool-call-only replies have no text worth persisting.
+        if content.is_empty() {
+            return;
+        }
+        let mut record = stored("assistant", content);
+        record.model = Some(model.to_string());
+        if let Some(usage) = usage {
+            let cost = calculate_cost(
+                model,
+                usage.prompt_tokens as usize,
+                usage.completion_tokens as usize,
+            );
+            record.cost = Some(cost.total_cost);
+            record.tokens = Some(usage.prompt_tokens + usage.completion_tokens);
+        }
+        self.conversation.add_message(record);
+    }
+
+    pub fn push_tool_results(&mut self, results: &[ToolResult]) {
+        for result in results {
+            let mut message = AiChatMessage::text(AiMessageRole::Tool, &result.content);
+            message.tool_call_id = Some(result.tool_use_id.clone());
+            self.history.push(message);
+            self.conversation
+                .add_message(stored("tool", &result.content));
+        }
+    }
+}
+
+fn stored(role: &str, content: &str) -> StoredMessage {
+    StoredMessage {
+        role: role.to_string(),
+        content: content.to_string(),
+        timestamp: chrono::Utc::now(),
+        model: None,
+        cost: None,
+        tokens: None,
+        thinking: None,
+        is_compacted: false,
+        compacted_from: None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Session
+// ---------------------------------------------------------------------------
+
+/// A tool call the model made that needs the user's approval first.
+pub struct ApprovalRequest {
+    pub call: AiToolCall,
+    /// One-line description of what the call will do.
+    pub summary: String,
+    /// Why approval is needed beyond the tool itself: untrusted content in
+    /// the context, or an approval rule that asks for it.
+    pub reason: Option<String>,
+    /// Set when an approval rule denies the call outright. Such calls are
+    /// rejected without asking.
+    pub denial: Option<String>,
+}
+
+/// Everything `hive chat --local` and `hive run` need to run a turn.
+pub struct LocalSession {
+    ai: AiService,
+    model: String,
+    registry: ToolRegistry,
+    tools: Vec<AiToolDefinition>,
+    guard: ContentGuard,
+    gate: ApprovalGate,
+    workspace: String,
+    store: ConversationStore,
+    system_prompt: String,
+    pub transcript: Transcript,
+}
+
+impl LocalSession {
+    /// Build a session from config, optionally resuming conversation `resume`.
+    ///
+    /// Without an explicit `model`, a resumed conversation keeps its own
+    /// model and a new one uses the configured default. The configured
+    /// approval policy must load; a broken one is an error rather than
+    /// silently unenforced.
+    pub async fn start(
+        config: &HiveConfig,
+        model: Option<String>,
+        resume: Option<&str>,
+    ) -> Result<Self> {
+        let mut ai = AiService::new(AiServiceConfig::from(config));
+        if ai.first_provider().is_none() {
+            return Err(anyhow!(
+                "no AI provider is configured. Set an API key or a local provider \
+                 URL (ollama/lmstudio) before using --local or `hive run`."
+            ));
+        }
+        // Learn which local models are actually installed so routing can
+        // pick them.
+        ai.start_discovery().scan_all().await;
+
+        let store = ConversationStore::new()?;
+        let transcript = match resume {
+            Some(id) => Transcript::resume(
+                store
+                    .load(id)
+                    .map_err(|e| anyhow!("could not load conversation '{id}': {e}"))?,
+            ),
+            None => Transcript::new(model.as_deref().unwrap_or(&config.default_model)),
+        };
+        let model = model.unwrap_or_else(|| transcript.conversation.model.clone());
+
+        let registry = tool_use::builtin_registry();
+        let tools = tool_definitions(&registry);
+        let cwd = std::env::current_dir()?;
+
+        let mut gate = ApprovalGate::new(ApprovalRule::defaults());
+        if let Some(path) = ApprovalPolicy::configured_path(config) {
+            gate = gate.with_policy(ApprovalPolicy::load(&path)?);
+        }
+
+        Ok(Self {
+            ai,
+            model,
+            registry,
+            tools,
+            guard: ContentGuard::new(),
+            gate,
+            workspace: cwd.display().to_string(),
+            store,
+            system_prompt: format!(
+                "You are Hive, a coding assistant running in the user's terminal. \
+                 The working directory is {}. Use the available tools to inspect \
+                 and change files; the user approves every write and command.",
+                cwd.display()
+            ),
+            transcript,
+        })
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Replace the default interactive system prompt.
+    pub fn set_system_prompt(&mut self, prompt: String) {
+        self.system_prompt = prompt;
+    }
+
+    /// Names of the tools advertised to the model, in advertised order.
+    pub fn tool_names(&self) -> Vec<&str> {
+        self.tools.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    /// Advertise only the tools in `allowed`. Calls to any other tool are
+    /// answered with an error instead of running.
+    pub fn restrict_tools(&mut self, allowed: &[String]) -> Result<()> {
+        if let Some(unknown) = allowed
+            .iter()
+            .find(|name| !self.tools.iter().any(|t| &t.name == *name))
+        {
+            return Err(anyhow!(
+                "unknown tool '{unknown}'. Available tools: {}",
+                self.tool_names().join(", ")
+            ));
+        }
+        self.tools.retain(|t| allowed.contains(&t.name));
+        Ok(())
+    }
+
+    /// Route the current history and build the next streaming request.
+    pub fn prepare(&self) -> Result<(Arc<dyn AiProvider>, ChatRequest)> {
+        self.ai
+            .prepare_stream(
+                self.transcript.history.clone(),
+                &self.model,
+                Some(self.system_prompt.clone()),
+                Some(self.tools.clone()),
+            )
+            .ok_or_else(|| anyhow!("no provider available for model '{}'", self.model))
+    }
+
+    /// The calls in `calls` that must be approved before they run, and
+    /// those an approval rule denies.
+    pub fn approvals_needed(&self, calls: &[AiToolCall]) -> Vec<ApprovalRequest> {
+        calls
+            .iter()
+            .filter_map(|call| {
+                let guard_reason = self.guard.approval_reason(&call.name);
+                let (rule_reason, denial) = match self.gate_call(call) {
+                    GateVerdict::Run if guard_reason.is_none() => return None,
+                    GateVerdict::Run | GateVerdict::Ask(None) => (None, None),
+                    GateVerdict::Ask(Some(rule)) => {
+                        (Some(format!("Required by approval rule '{rule}'")), None)
+                    }
+                    GateVerdict::Deny(denial) => (None, Some(denial)),
+                };
+                let reason = match (guard_reason, rule_reason) {
+                    (Some(guard), Some(rule)) => Some(format!("{guard}; {rule}")),
+                    (guard, rule) => guard.or(rule),
+                };
+                Some(ApprovalRequest {
+                    call: call.clone(),
+                    summary: describe_call(call),
+                    reason,
+                    denial,
+                })
+            })
+            .collect()
+    }
+
+    /// Evaluate `call` against the approval rules and policy.
+    fn gate_call(&self, call: &AiToolCall) -> GateVerdict {
+        let Some(operation) = tool_operation(call) else {
+            return GateVerdict::Run;
+        };
+        let ctx = PolicyContext::new(APPROVAL_AGENT_ID, operation)
+            .with_model(self.model.clone())
+            .with_workspace(self.workspace.clone());
+        gate_verdict(&self.gate, &ctx, &call.name)
+    }
+
+    /// Run `calls`, skipping those the user rejected, and record everything
+    /// in the transcript. Returns the results in call order.
+    pub fn execute(&mut self, calls: &[AiToolCall], rejected: &[String]) -> Vec<ToolResult> {
+        let results: Vec<ToolResult> = calls
+            .iter()
+            .map(|call| {
+                let agent_call = tool_use::ToolCall {
+                    id: call.id.clone(),
+                    name: call.name.clone(),
+                    input: call.input.clone(),
+                };
+                if !self.tools.iter().any(|t| t.name == call.name) {
+                    return ToolResult {
+                        tool_use_id: call.id.clone(),
+                        content: format!("Tool '{}' is not available in this session.", call.name),
+                        is_error: true,
+                    };
+                }
+                if let GateVerdict::Deny(denial) = self.gate_call(call) {
+                    return ToolResult {
+                        tool_use_id: call.id.clone(),
+                        content: format!("{denial}. Do not retry."),
+                        is_error: true,
+                    };
+                }
+                if rejected.contains(&call.id) {
+                    return ToolResult {
+                        tool_use_id: call.id.clone(),
+                        content: format!(
+                            "User rejected {}. Do not retry without asking.",
+                            describe_call(call)
+                        ),
+                        is_error: true,
+                    };
+                }
+                let mut result = self.registry.execute(&agent_call);
+                self.guard.guard_tool_result(&agent_call, &mut result);
+                result
+            })
+            .collect();
+        self.transcript.push_tool_results(&results);
+        results
+    }
+
+    /// Persist the conversation to the shared conversation store.
+    pub fn save(&self) -> Result<()> {
+        if self.transcript.conversation.messages.is_empty() {
+            return Ok(());
+        }
+        self.store.save(&self.transcript.conversation)
+    }
+}
+
+/// Whether `tool` changes the machine and needs approval before it runs
+/// when no approval rule decides otherwise.
+pub fn requires_approval(tool: &str) -> bool {
+    APPROVAL_REQUIRED_TOOLS.contains(&tool)
+}
+
+/// How the approval rules treat one tool call.
+#[derive(Debug, Clone, PartialEq)]
+enum GateVerdict {
+    Run,
+    /// Ask the user, naming the rule that asked (`None` for the built-in
+    /// approval-required tools).
+    Ask(Option<String>),
+    Deny(String),
+}
+
+/// Apply `gate` to a tool call. A matching gate or policy rule decides;
+/// otherwise the policy default does, except that approval-required tools
+/// still ask. The terminal has a single approver, so rules requiring several
+/// approvers are answered by one y/n.
+fn gate_verdict(gate: &ApprovalGate, ctx: &PolicyContext, tool: &str) -> GateVerdict {
+    let decision = gate.evaluate(ctx);
+    let rule = || {
+        decision
+            .rule
+            .clone()
+            .unwrap_or_else(|| "policy-default".into())
+    };
+    match decision.outcome {
+        PolicyOutcome::Deny => {
+            GateVerdict::Deny(format!("Denied by approval policy rule '{}'", rule()))
+        }
+        PolicyOutcome::Allow if decision.rule.is_some() || !requires_approval(tool) => {
+            GateVerdict::Run
+        }
+        PolicyOutcome::Allow => GateVerdict::Ask(None),
+        PolicyOutcome::RequireApproval | PolicyOutcome::RequireApprovers(_) => {
+            GateVerdict::Ask(Some(rule()))
+        }
+    }
+}
+
+/// The operation a tool call performs, as approval rules and policies see
+/// it, or `None` for calls that only read.
+fn tool_operation(call: &AiToolCall) -> Option<OperationType> {
+    let input = |key: &str| {
+        call.input
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    match call.name.as_str() {
+        "execute_command" => Some(OperationType::ShellCommand(input("command"))),
+        "write_file" => Some(OperationType::FileModify {
+            path: input("path"),
+            scope: "1 files".into(),
+        }),
+        "replace_in_files" if call.input.get("apply").and_then(|v| v.as_bool()) == Some(true) => {
+            let files = ReplaceInFilesTool::preview(&call.input)
+                .map(|preview| preview.files.len())
+                .unwrap_or_default();
+            let path = input("path");
+            Some(OperationType::FileModify {
+                path: if path.is_empty() { ".".into() } else { path },
+                scope: format!("{files} files"),
+            })
+        }
+        "click" | "type_text" | "press_enter" => Some(OperationType::Custom(describe_call(call))),
+        _ => None,
+    }
+}
+
+/// Convert the registry's tool definitions into the provider wire format.
+fn tool_definitions(registry: &ToolRegistry) -> Vec<AiToolDefinition> {
+    let mut tools: Vec<AiToolDefinition> = registry
+        .definitions()
+        .into_iter()
+        .map(|def| AiToolDefinition {
+            name: def.name.clone(),
+            description: def.description.clone(),
+            input_schema: def.input_schema.clone(),
+        })
+        .collect();
+    // The registry is a map; keep the advertised order stable.
+    tools.sort_by(|a, b| a.name.cmp(&b.name));
+    tools
+}
+
+/// A short, human-readable description of a tool call for approval prompts.
+pub fn describe_call(call: &AiToolCall) -> String {
+    let target = ["command", "path", "text", "url"]
+        .iter()
+        .find_map(|key| call.input.get(*key).and_then(|v| v.as_str()));
+    match target {
+        Some(target) => {
+            let target = target.lines().next().unwrap_or_default();
+            if target.chars().count() > 80 {
+                let short: String = target.chars().take(77).collect();
+                format!("{} {short}...", call.name)
+            } else {
+                format!("{} {target}", call.name)
+            }
+        }
+        None => call.name.clone(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(name: &str, input: serde_json::Value) -> AiToolCall {
+        AiToolCall {
+            id: format!("call-{name}"),
+            name: name.into(),
+            input,
+        }
+    }
+
+    #[test]
+    fn resume_replays_text_messages_only() {
+        let mut conversation = Conversation::new("llama3.2");
+        conversation.add_message(stored("user", "list files"));
+        conversation.add_message(stored("tool", "a.rs\nb.rs"));
+        conversation.add_message(stored("assistant", "There are two files."));
+        conversation.add_message(stored("error", "boom"));
+
+        let transcript = Transcript::resume(conversation);
+
+        let roles: Vec<_> = transcript.history.iter().map(|m| m.role).collect();
+        assert_eq!(roles, [AiMessageRole::User, AiMessageRole::Assistant]);
+        assert_eq!(transcript.conversation.messages.len(), 4);
+    }
+
+    #[test]
+    fn transcript_keeps_tool_linkage_in_history_but_not_on_disk() {
+        let mut transcript = Transcript::new("llama3.2");
+        transcript.push_user("read main.rs");
+        transcript.push_assistant(
+            "",
+            Some(vec![call(
+                "read_file",
+                serde_json::json!({"path": "main.rs"}),
+            )]),
+            "llama3.2",
+            None,
+        );
+        transcript.push_tool_results(&[ToolResult {
+            tool_use_id: "call-read_file".into(),
+            content: "fn main() {}".into(),
+            is_error: false,
+        }]);
+        transcript.push_assistant(
+            "It is empty.",
+            None,
+            "llama3.2",
+            Some(&TokenUsage {
+                prompt_tokens: 10,
+                completion_tokens: 5,
+                total_tokens: 15,
+                ..Default::default()
+            }),
+        );
+
+        assert_eq!(transcript.history.len(), 4);
+        assert!(transcript.history[1].tool_calls.is_some());
+        assert_eq!(
+            transcript.history[2].tool_call_id.as_deref(),
+            Some("call-read_file")
+        );
+
+        let stored_roles: Vec<_> = transcript
+            .conversation
+            .messages
+            .iter()
+            .map(|m| m.role.as_str())
+            .collect();
+        assert_eq!(stored_roles, ["user", "tool", "assistant"]);
+        assert_eq!(transcript.conversation.total_tokens, 15);
+        assert_eq!(transcript.conversation.title, "read main.rs");
+    }
+
+    #[test]
+    fn describe_call_names_the_target() {
+        assert_eq!(
+            describe_call(&call(
+                "execute_command",
+                serde_json::json!({"command": "cargo test\nrm -rf /"})
+            )),
+            "execute_command cargo test"
+        );
+        assert_eq!(
+            describe_call(&call(
+                "write_file",
+                serde_json::json!({"path": "src/lib.rs"})
+            )),
+            "write_file src/lib.rs"
+        );
+        assert_eq!(
+            describe_call(&call("git_status", serde_json::json!({}))),
+            "git_status"
+        );
+    }
+
+    fn verdict(gate: &ApprovalGate, call: &AiToolCall) -> GateVerdict {
+        match tool_operation(call) {
+            Some(op) => gate_verdict(gate, &PolicyContext::new(APPROVAL_AGENT_ID, op), &call.name),
+            None => GateVerdict::Run,
+        }
+    }
+
+    #[test]
+    fn default_rules_gate_machine_changing_tools() {
+        let gate = ApprovalGate::new(ApprovalRule::defaults());
+        let read = call("read_file", serde_json::json!({ "path": "a.rs" }));
+        let write = call("write_file", serde_json::json!({ "path": "a.rs" }));
+        let push = call(
+            "execute_command",
+            serde_json::json!({ "command": "git push origin main" }),
+        );
+        let preview = call("replace_in_files", serde_json::json!({ "pattern": "a" }));
+
+        assert_eq!(verdict(&gate, &read), GateVerdict::Run);
+        assert_eq!(verdict(&gate, &preview), GateVerdict::Run);
+        assert_eq!(verdict(&gate, &write), GateVerdict::Ask(None));
+        assert_eq!(
+            verdict(&gate, &push),
+            GateVerdict::Ask(Some("git-push".into()))
+        );
+    }
+
+    #[test]
+    fn policy_rules_allow_deny_and_require_approval() {
+        let policy = ApprovalPolicy::from_toml(
+            r#"
+            [[rule]]
+            name = "tests-are-fine"
+            outcome = "allow"
+            [rule.when]
+            command = "cargo test*"
+
+            [[rule]]
+            name = "no-env-writes"
+            outcome = "deny"
+            [rule.when]
+            path = "*.env"
+
+            [[rule]]
+            name = "review-clicks"
+            outcome = { require_approvers = 2 }
+            [rule.when]
+            operation = "custom"
+            "#,
+        )
+        .unwrap();
+        let gate = ApprovalGate::new(ApprovalRule::defaults()).with_policy(policy);
+
+        let test = call(
+            "execute_command",
+            serde_json::json!({ "command": "cargo test -p hive_cli" }),
+        );
+        let env = call("write_file", serde_json::json!({ "path": ".env" }));
+        let click = call("click", serde_json::json!({ "x": 1, "y": 2 }));
+        let build = call("execute_command", serde_json::json!({ "command": "make" }));
+
+        assert_eq!(verdict(&gate, &test), GateVerdict::Run);
+        assert_eq!(
+            verdict(&gate, &env),
+            GateVerdict::Deny("Denied by approval policy rule 'no-env-writes'".into())
+        );
+        assert_eq!(
+            verdict(&gate, &click),
+            GateVerdict::Ask(Some("review-clicks".into()))
+        );
+        assert_eq!(verdict(&gate, &build), GateVerdict::Ask(None));
+    }
+
+    #[test]
+    fn builtin_tools_are_advertised_in_stable_order() {
+        let tools = tool_definitions(&tool_use::builtin_registry());
+        let names: Vec<_> = tools.iter().map(|t| t.name.as_str()).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+        for gated in APPROVAL_REQUIRED_TOOLS {
+            assert!(names.contains(gated), "{gated} is not a builtin tool");
+        }
+    }
+}
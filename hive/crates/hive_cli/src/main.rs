@@ -1,11 +1,13 @@
 //! Hive AI terminal client.
 //!
 //! A Ratatui-based CLI for interacting with Hive Cloud services: login,
-//! chat with AI models, sync data, and manage configuration.
+//! chat with AI models (through the cloud or directly with local providers),
+//! sync data, and manage configuration.
 
 mod api;
 mod app;
 mod commands;
+mod local;
 mod ui;
 
 use clap::{Parser, Subcommand};
@@ -29,6 +31,12 @@ enum Commands {
         /// Model to use (e.g. gpt-4o, claude-3-sonnet)
         #[arg(short, long)]
         model: Option<String>,
+        /// Use your configured providers and local models instead of Hive Cloud
+        #[arg(long)]
+        local: bool,
+        /// Resume a saved conversation (desktop or CLI) by ID
+        #[arg(long, requires = "local")]
+        resume: Option<String>,
     },
     /// Cloud sync operations
     Sync {
@@ -51,6 +59,11 @@ enum Commands {
         #[command(subcommand)]
         action: ToolAction,
     },
+    /// Approval policy tools
+    Policy {
+        #[command(subcommand)]
+        action: PolicyAction,
+    },
     /// Turn a ticket into a built branch (and optionally a draft PR)
     BuildTicket {
         /// Ticket source: jira, linear, or github
@@ -67,6 +80,53 @@ enum Commands {
         #[arg(long, default_value = "main")]
         base: String,
     },
+    /// Run an agent non-interactively, for scripts and CI.
+    ///
+    /// Exit codes: 0 answered, 1 failed, 2 invalid input, 3 hit
+    /// --max-iterations, 4 hit --max-cost.
+    Run {
+        /// Task for the agent (read from stdin when omitted or "-")
+        prompt: Option<String>,
+        /// Directory the agent works in (default: current directory)
+        #[arg(long)]
+        workspace: Option<PathBuf>,
+        /// Persona to act as, e.g. investigate, implement, code_review
+        #[arg(long)]
+        persona: Option<String>,
+        /// Installed skill whose instructions the agent follows
+        #[arg(long)]
+        skill: Option<String>,
+        /// Model to use (default: configured default model)
+        #[arg(short, long)]
+        model: Option<String>,
+        /// Maximum number of tool rounds
+        #[arg(long, default_value_t = local::MAX_TOOL_ITERATIONS)]
+        max_iterations: usize,
+        /// Stop once the run has cost this many USD
+        #[arg(long)]
+        max_cost: Option<f64>,
+        /// Tools the agent may use, pre-approved (default: read-only tools)
+        #[arg(long = "allow-tool", value_delimiter = ',')]
+        allow_tools: Vec<String>,
+        /// Print a JSON Lines event stream instead of human-readable output
+        #[arg(long)]
+        json: bool,
+    },
+    /// Diagnose the local Hive installation and optionally fix what is safe to fix
+    Doctor {
+        /// Print a machine-readable JSON report for support tickets
+        #[arg(long)]
+        json: bool,
+        /// Apply safe automatic fixes, then re-check
+        #[arg(long)]
+        fix: bool,
+        /// Run only these checks: an id (core.sqlite), id prefix (core) or category (providers)
+        #[arg(long)]
+        only: Vec<String>,
+        /// Workspace to check for swarm leftovers (default: current directory)
+        #[arg(long)]
+        workspace: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -93,6 +153,21 @@ enum ToolAction {
     },
 }
 
+#[derive(Subcommand)]
+enum PolicyAction {
+    /// Run sample operations against an approval policy file (TOML or YAML)
+    Test {
+        /// Policy file to evaluate
+        file: PathBuf,
+        /// Extra test cases file with [[test]] entries
+        #[arg(long)]
+        cases: Option<PathBuf>,
+        /// Print machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum SyncAction {
     /// Push a blob to cloud storage
@@ -128,7 +203,11 @@ async fn main() -> anyhow::Result<()> {
     match cli.command {
         Commands::Login => commands::login::run().await,
         Commands::Status => commands::status::run().await,
-        Commands::Chat { model } => commands::chat::run(model).await,
+        Commands::Chat {
+            model,
+            local,
+            resume,
+        } => commands::chat::run(model, local, resume).await,
         Commands::Sync { action } => match action {
             SyncAction::Push { key, file } => commands::sync::push(&key, &file).await,
             SyncAction::Pull { key, file } => commands::sync::pull(&key, &file).await,
@@ -145,6 +224,11 @@ async fn main() -> anyhow::Result<()> {
                 workspace,
             } => commands::tools::call(workspace, &name, &args).await,
         },
+        Commands::Policy { action } => match action {
+            PolicyAction::Test { file, cases, json } => {
+                commands::policy::test(&file, cases.as_deref(), json).await
+            }
+        },
         Commands::BuildTicket {
             source,
             id,
@@ -152,5 +236,35 @@ async fn main() -> anyhow::Result<()> {
             repo,
             base,
         } => commands::build_ticket::run(&source, &id, open_pr, repo, base).await,
+        Commands::Run {
+            prompt,
+            workspace,
+            persona,
+            skill,
+            model,
+            max_iterations,
+            max_cost,
+            allow_tools,
+            json,
+        } => {
+            commands::run::run(commands::run::RunOptions {
+                prompt,
+                workspace,
+                persona,
+                skill,
+                model,
+                max_iterations,
+                max_cost,
+                allow_tools,
+                json,
+            })
+            .await
+        }
+        Commands::Doctor {
+            json,
+            fix,
+            only,
+            workspace,
+        } => commands::doctor::run(json, fix, only, workspace).await,
     }
 }
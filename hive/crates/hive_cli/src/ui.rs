@@ -38,6 +38,10 @@ fn draw_messages(frame: &mut Frame, app: &ChatApp, area: Rect) {
                 Style::default().fg(Color::Yellow).bold(),
                 Style::default().fg(Color::Yellow),
             ),
+            Role::Tool => (
+                Style::default().fg(Color::Magenta).bold(),
+                Style::default().fg(DIM),
+            ),
         };
         lines.push(Line::from(vec![Span::styled(
             format!("[{}] ", msg.role.label()),
@@ -89,6 +93,19 @@ fn draw_messages(frame: &mut Frame, app: &ChatApp, area: Rect) {
 }
 
 fn draw_input(frame: &mut Frame, app: &ChatApp, area: Rect) {
+    if app.pending_approval.is_some() {
+        let prompt = Paragraph::new("Press y to approve, n to reject").block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow))
+                .title(Span::styled(
+                    " Approve? ",
+                    Style::default().fg(Color::Yellow).bold(),
+                )),
+        );
+        frame.render_widget(prompt, area);
+        return;
+    }
     let input_text = if app.waiting {
         "Waiting for response...".to_string()
     } else {
@@ -2,22 +2,31 @@ use anyhow::Context;
 use axum::{Router, routing::get};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tracing::{Level, info};
+use tracing::{Level, error, info};
 
 mod admin;
 mod auth;
 #[cfg(test)]
 mod billing;
 mod relay;
+mod sync;
 
-fn build_app() -> Router {
-    let relay_service = Arc::new(relay::RelayService::default());
+/// The public API and the internal `/metrics` listener's router. Metrics
+/// carry no authentication, so they are never served on the public address.
+fn build_app() -> anyhow::Result<(Router, Router)> {
+    let relay_service =
+        Arc::new(relay::RelayService::from_env().context("Failed to set up the relay")?);
+    relay_service.start();
     let admin_state = Arc::new(admin::AdminState::new(relay_service.clone()));
+    let sync_state = Arc::new(sync::SyncState::from_env().context("Failed to set up cloud sync")?);
 
-    Router::new()
+    let metrics = relay::metrics_router(relay_service.clone());
+    let app = Router::new()
         .route("/", get(|| async { "Hive Cloud API v1" }))
         .nest("/relay", relay::router(relay_service))
         .nest("/admin", admin::router(admin_state))
+        .nest("/v1/sync", sync::router(sync_state));
+    Ok((app, metrics))
 }
 
 #[tokio::main]
@@ -26,7 +35,22 @@ async fn main() -> anyhow::Result<()> {
 
     info!("Starting hive-cloud backend...");
 
-    let app = build_app();
+    let (app, metrics) = build_app()?;
+
+    // Metrics go on a separate listener meant for the scrape network only —
+    // loopback unless HIVE_CLOUD_METRICS_BIND says otherwise.
+    let metrics_bind =
+        std::env::var("HIVE_CLOUD_METRICS_BIND").unwrap_or_else(|_| "127.0.0.1:9464".into());
+    let metrics_addr: SocketAddr = metrics_bind
+        .parse()
+        .context("Invalid HIVE_CLOUD_METRICS_BIND address")?;
+    let metrics_listener = tokio::net::TcpListener::bind(metrics_addr).await?;
+    info!("serving metrics on {}", metrics_addr);
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(metrics_listener, metrics).await {
+            error!("Metrics listener failed: {}", e);
+        }
+    });
 
     // Run it with hyper — bind address is configurable via HIVE_CLOUD_BIND
     let bind = std::env::var("HIVE_CLOUD_BIND").unwrap_or_else(|_| "127.0.0.1:3000".into());
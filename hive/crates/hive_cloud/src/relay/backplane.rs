@@ -0,0 +1,199 @@
+//! Fan-out between relay instances.
+//!
+//! Each instance only holds the sockets connected to it. When a frame is
+//! forwarded, the instance delivers it to its own sockets and publishes it on
+//! the backplane so every other instance can deliver it to theirs.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::Utc;
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// How often a [`SqliteBackplane`] looks for messages from other instances.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long published messages stay in the backplane table. Instances that
+/// fall further behind than this miss them.
+const RETENTION: chrono::Duration = chrono::Duration::seconds(60);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackplaneMessage {
+    /// Instance that published the message; it ignores its own messages.
+    pub origin: String,
+    pub room_id: String,
+    pub event: BackplaneEvent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BackplaneEvent {
+    /// A serialized `RelayFrame::Forward` from `from`, addressed to `to` or
+    /// broadcast to the rest of the room.
+    Forward {
+        from: String,
+        to: Option<String>,
+        frame: String,
+    },
+    /// The room was expired; instances drop its local sockets.
+    Expire,
+}
+
+#[async_trait]
+pub trait Backplane: Send + Sync {
+    async fn publish(&self, message: BackplaneMessage) -> anyhow::Result<()>;
+
+    /// Receive every message published after this call, including this
+    /// instance's own.
+    fn subscribe(&self) -> broadcast::Receiver<BackplaneMessage>;
+}
+
+/// Backplane connecting relay services in the same process. Used for single
+/// instance deployments and for exercising multi-instance behaviour in tests.
+pub struct MemoryBackplane {
+    sender: broadcast::Sender<BackplaneMessage>,
+}
+
+impl Default for MemoryBackplane {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(1024);
+        Self { sender }
+    }
+}
+
+#[async_trait]
+impl Backplane for MemoryBackplane {
+    async fn publish(&self, message: BackplaneMessage) -> anyhow::Result<()> {
+        // No subscribers just means no other instance is listening.
+        let _ = self.sender.send(message);
+        Ok(())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<BackplaneMessage> {
+        self.sender.subscribe()
+    }
+}
+
+/// Backplane for relay instances that share a SQLite database, normally the
+/// file behind their [`SqliteRoomStore`](super::SqliteRoomStore).
+///
+/// Published messages are appended to a table that every instance polls, and
+/// pruned after [`RETENTION`].
+pub struct SqliteBackplane {
+    inner: Arc<SqliteInner>,
+}
+
+struct SqliteInner {
+    conn: Mutex<Connection>,
+    sender: broadcast::Sender<BackplaneMessage>,
+}
+
+impl SqliteBackplane {
+    /// Open the backplane table in `path` and start polling it. Must be
+    /// called from within a Tokio runtime.
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("failed to open relay backplane {}", path.display()))?;
+        Self::with_connection(conn)
+    }
+
+    fn with_connection(conn: Connection) -> anyhow::Result<Self> {
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+            CREATE TABLE IF NOT EXISTS relay_backplane (
+                id         INTEGER PRIMARY KEY AUTOINCREMENT,
+                message    TEXT    NOT NULL,
+                created_at INTEGER NOT NULL
+            );",
+        )?;
+        // Only messages published from now on are delivered, as with
+        // `MemoryBackplane`.
+        let last_id: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(id), 0) FROM relay_backplane",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let (sender, _) = broadcast::channel(1024);
+        let inner = Arc::new(SqliteInner {
+            conn: Mutex::new(conn),
+            sender,
+        });
+        tokio::spawn(poll(Arc::downgrade(&inner), last_id));
+        Ok(Self { inner })
+    }
+}
+
+/// Rebroadcast rows published after `last_id` until the backplane is dropped.
+async fn poll(inner: Weak<SqliteInner>, mut last_id: i64) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let Some(inner) = inner.upgrade() else {
+            break;
+        };
+        match inner.read_after(last_id) {
+            Ok(rows) => {
+                for (id, message) in rows {
+                    last_id = id;
+                    match serde_json::from_str(&message) {
+                        Ok(message) => {
+                            let _ = inner.sender.send(message);
+                        }
+                        Err(e) => warn!("Skipping malformed relay backplane message {}: {}", id, e),
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to read the relay backplane: {}", e),
+        }
+    }
+}
+
+impl SqliteInner {
+    fn read_after(&self, last_id: i64) -> anyhow::Result<Vec<(i64, String)>> {
+        let conn = self.conn.lock().expect("relay backplane lock poisoned");
+        let mut stmt =
+            conn.prepare("SELECT id, message FROM relay_backplane WHERE id > ?1 ORDER BY id")?;
+        let rows = stmt
+            .query_map(params![last_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        Ok(rows)
+    }
+}
+
+#[async_trait]
+impl Backplane for SqliteBackplane {
+    async fn publish(&self, message: BackplaneMessage) -> anyhow::Result<()> {
+        let message = serde_json::to_string(&message)?;
+        let now = Utc::now();
+        let conn = self
+            .inner
+            .conn
+            .lock()
+            .expect("relay backplane lock poisoned");
+        conn.execute(
+            "INSERT INTO relay_backplane (message, created_at) VALUES (?1, ?2)",
+            params![message, now.timestamp_millis()],
+        )?;
+        conn.execute(
+            "DELETE FROM relay_backplane WHERE created_at < ?1",
+            params![(now - RETENTION).timestamp_millis()],
+        )?;
+        Ok(())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<BackplaneMessage> {
+        self.inner.sender.subscribe()
+    }
+}
@@ -0,0 +1,156 @@
+//! Relay counters, rendered in the Prometheus text exposition format.
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Default)]
+pub struct RelayMetrics {
+    pub connections: AtomicU64,
+    pub frames_register: AtomicU64,
+    pub frames_join: AtomicU64,
+    pub frames_forward: AtomicU64,
+    pub frames_leave: AtomicU64,
+    pub frames_ping: AtomicU64,
+    pub frames_invalid: AtomicU64,
+    pub bytes_forwarded: AtomicU64,
+    pub quota_rejections: AtomicU64,
+    pub rooms_expired: AtomicU64,
+    pub backplane_published: AtomicU64,
+    pub backplane_received: AtomicU64,
+}
+
+impl RelayMetrics {
+    pub fn incr(counter: &AtomicU64) {
+        Self::add(counter, 1);
+    }
+
+    pub fn add(counter: &AtomicU64, value: u64) {
+        counter.fetch_add(value, Ordering::Relaxed);
+    }
+
+    /// Render every metric. Room and member gauges come from the room store,
+    /// so they are passed in rather than tracked here.
+    pub fn render(&self, rooms: u64, members: u64) -> String {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        let mut out = String::new();
+
+        gauge(
+            &mut out,
+            "hive_relay_rooms",
+            "Rooms known to the relay.",
+            rooms,
+        );
+        gauge(
+            &mut out,
+            "hive_relay_members",
+            "Nodes registered in a room, connected or not.",
+            members,
+        );
+        gauge(
+            &mut out,
+            "hive_relay_connections",
+            "WebSocket connections open on this instance.",
+            load(&self.connections),
+        );
+
+        header(
+            &mut out,
+            "hive_relay_frames_total",
+            "counter",
+            "Frames received from clients, by type.",
+        );
+        for (kind, counter) in [
+            ("register", &self.frames_register),
+            ("join", &self.frames_join),
+            ("forward", &self.frames_forward),
+            ("leave", &self.frames_leave),
+            ("ping", &self.frames_ping),
+            ("invalid", &self.frames_invalid),
+        ] {
+            let _ = writeln!(
+                out,
+                "hive_relay_frames_total{{type=\"{kind}\"}} {}",
+                load(counter)
+            );
+        }
+
+        counter(
+            &mut out,
+            "hive_relay_bytes_forwarded_total",
+            "Bytes delivered to sockets on this instance.",
+            load(&self.bytes_forwarded),
+        );
+        counter(
+            &mut out,
+            "hive_relay_quota_rejections_total",
+            "Forwards refused because the room's bandwidth quota was used up.",
+            load(&self.quota_rejections),
+        );
+        counter(
+            &mut out,
+            "hive_relay_rooms_expired_total",
+            "Rooms removed after being idle.",
+            load(&self.rooms_expired),
+        );
+
+        header(
+            &mut out,
+            "hive_relay_backplane_messages_total",
+            "counter",
+            "Messages exchanged with other instances, by direction.",
+        );
+        for (direction, counter) in [
+            ("published", &self.backplane_published),
+            ("received", &self.backplane_received),
+        ] {
+            let _ = writeln!(
+                out,
+                "hive_relay_backplane_messages_total{{direction=\"{direction}\"}} {}",
+                load(counter)
+            );
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: u64) {
+    header(out, name, "gauge", help);
+    let _ = writeln!(out, "{name} {value}");
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: u64) {
+    header(out, name, "counter", help);
+    let _ = writeln!(out, "{name} {value}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_prometheus_text() {
+        let metrics = RelayMetrics::default();
+        RelayMetrics::incr(&metrics.frames_forward);
+        RelayMetrics::add(&metrics.bytes_forwarded, 512);
+        RelayMetrics::incr(&metrics.backplane_received);
+
+        let text = metrics.render(2, 3);
+        assert!(text.contains("# TYPE hive_relay_rooms gauge\nhive_relay_rooms 2\n"));
+        assert!(text.contains("hive_relay_members 3\n"));
+        assert!(text.contains("hive_relay_frames_total{type=\"forward\"} 1\n"));
+        assert!(text.contains("hive_relay_frames_total{type=\"ping\"} 0\n"));
+        assert!(text.contains("hive_relay_bytes_forwarded_total 512\n"));
+        assert!(text.contains("hive_relay_backplane_messages_total{direction=\"received\"} 1\n"));
+        // Every sample line belongs to a declared metric.
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            let name = line.split(['{', ' ']).next().unwrap();
+            assert!(text.contains(&format!("# TYPE {name} ")), "{line}");
+        }
+    }
+}
@@ -0,0 +1,869 @@
+//! WebSocket relay between paired devices.
+//!
+//! Room and membership state lives in a [`RoomStore`] so it survives restarts
+//! and can be shared by several relay instances; each instance only keeps
+//! the sockets connected to it and exchanges forwarded frames with the others
+//! over a [`Backplane`]. Rooms are metered against a per-tier bandwidth quota
+//! and expired once idle.
+
+mod backplane;
+mod metrics;
+mod store;
+
+pub use backplane::{
+    Backplane, BackplaneEvent, BackplaneMessage, MemoryBackplane, SqliteBackplane,
+};
+pub use metrics::RelayMetrics;
+pub use store::{MemoryRoomStore, RoomStore, SqliteRoomStore};
+
+use crate::auth::validate_jwt;
+use anyhow::Context;
+use axum::{
+    Router,
+    extract::{
+        State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    http::header,
+    response::IntoResponse,
+    routing::get,
+};
+use chrono::{DateTime, Duration, Utc};
+use futures::{sink::SinkExt, stream::StreamExt};
+use hive_remote::relay::{EncryptedEnvelope, RelayFrame};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use tokio::sync::{RwLock, broadcast, mpsc};
+use tracing::{debug, error, info, warn};
+
+type Tx = mpsc::UnboundedSender<Message>;
+
+/// Bandwidth quotas and expiry settings.
+#[derive(Debug, Clone, Copy)]
+pub struct RelayLimits {
+    /// Length of a quota window; usage resets when a new one starts.
+    pub quota_window: Duration,
+    pub free_bytes: u64,
+    pub pro_bytes: u64,
+    pub team_bytes: u64,
+    /// Rooms with no joins or traffic for this long are removed.
+    pub idle_timeout: Duration,
+    /// How often to look for idle rooms.
+    pub sweep_interval: std::time::Duration,
+}
+
+impl Default for RelayLimits {
+    fn default() -> Self {
+        const GIB: u64 = 1024 * 1024 * 1024;
+        Self {
+            quota_window: Duration::hours(24),
+            free_bytes: GIB,
+            pro_bytes: 50 * GIB,
+            team_bytes: 200 * GIB,
+            idle_timeout: Duration::hours(1),
+            sweep_interval: std::time::Duration::from_secs(60),
+        }
+    }
+}
+
+impl RelayLimits {
+    /// Bytes a room may relay per window. Unknown tiers get the free quota.
+    pub fn quota_for(&self, tier: &str) -> u64 {
+        match tier {
+            "pro" => self.pro_bytes,
+            "team" => self.team_bytes,
+            _ => self.free_bytes,
+        }
+    }
+}
+
+pub struct RelayService {
+    instance_id: String,
+    store: Arc<dyn RoomStore>,
+    backplane: Arc<dyn Backplane>,
+    limits: RelayLimits,
+    jwt_secret: Option<String>,
+    /// Sockets connected to this instance: room_id -> node_id -> sender.
+    connections: RwLock<HashMap<String, HashMap<String, Tx>>>,
+    metrics: RelayMetrics,
+}
+
+impl Default for RelayService {
+    fn default() -> Self {
+        Self::new(
+            Arc::new(MemoryRoomStore::default()),
+            Arc::new(MemoryBackplane::default()),
+            RelayLimits::default(),
+            None,
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RelayRoomSnapshot {
+    pub room_id: String,
+    pub participants: u32,
+    pub created_at: DateTime<Utc>,
+    pub bytes_transferred: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RelaySnapshot {
+    pub active_rooms: u64,
+    pub connected_devices: u64,
+    pub rooms: Vec<RelayRoomSnapshot>,
+}
+
+/// Per-socket state.
+struct Session {
+    tx: Tx,
+    node_id: Option<String>,
+    token_hash: String,
+    tier: String,
+    room_id: Option<String>,
+}
+
+pub fn router(state: Arc<RelayService>) -> Router {
+    Router::new()
+        .route("/ws", get(ws_handler))
+        .with_state(state)
+}
+
+/// `GET /metrics` in the Prometheus text format. Unauthenticated, so it is
+/// served on the internal metrics listener rather than the public API.
+pub fn metrics_router(state: Arc<RelayService>) -> Router {
+    Router::new()
+        .route("/metrics", get(get_metrics))
+        .with_state(state)
+}
+
+impl RelayService {
+    pub fn new(
+        store: Arc<dyn RoomStore>,
+        backplane: Arc<dyn Backplane>,
+        limits: RelayLimits,
+        jwt_secret: Option<String>,
+    ) -> Self {
+        let instance_id = hex::encode(
+            &Sha256::digest(format!(
+                "{}-{}",
+                std::process::id(),
+                Utc::now().timestamp_nanos_opt().unwrap_or_default()
+            ))[..8],
+        );
+        Self {
+            instance_id,
+            store,
+            backplane,
+            limits,
+            jwt_secret,
+            connections: RwLock::new(HashMap::new()),
+            metrics: RelayMetrics::default(),
+        }
+    }
+
+    /// Build the relay from the environment.
+    ///
+    /// `HIVE_CLOUD_RELAY_STORE=sqlite` keeps rooms in `HIVE_CLOUD_RELAY_DB`
+    /// (default `./data/relay.db`) and exchanges forwarded frames through the
+    /// same file, so every instance pointed at it serves the same rooms. The
+    /// default `memory` store forgets rooms on restart and only reaches
+    /// sockets on this instance. `HIVE_CLOUD_JWT_SECRET` verifies session tokens to pick the
+    /// room's quota tier; unverified devices get the free tier.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let (store, backplane): (Arc<dyn RoomStore>, Arc<dyn Backplane>) =
+            match std::env::var("HIVE_CLOUD_RELAY_STORE")
+                .unwrap_or_else(|_| "memory".into())
+                .as_str()
+            {
+                "memory" => (
+                    Arc::new(MemoryRoomStore::default()),
+                    Arc::new(MemoryBackplane::default()),
+                ),
+                // Instances sharing the database share rooms and forwarded
+                // frames alike.
+                "sqlite" => {
+                    let path = PathBuf::from(
+                        std::env::var("HIVE_CLOUD_RELAY_DB")
+                            .unwrap_or_else(|_| "./data/relay.db".into()),
+                    );
+                    (
+                        Arc::new(SqliteRoomStore::open(&path)?),
+                        Arc::new(SqliteBackplane::open(&path)?),
+                    )
+                }
+                other => anyhow::bail!("unsupported HIVE_CLOUD_RELAY_STORE `{other}`"),
+            };
+
+        let jwt_secret = std::env::var("HIVE_CLOUD_JWT_SECRET")
+            .ok()
+            .filter(|value| !value.trim().is_empty());
+
+        Ok(Self::new(
+            store,
+            backplane,
+            RelayLimits::default(),
+            jwt_secret,
+        ))
+    }
+
+    /// Spawn the backplane listener and the idle-room sweep.
+    pub fn start(self: &Arc<Self>) {
+        let mut messages = self.backplane.subscribe();
+        let service = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match messages.recv().await {
+                    Ok(message) => service.handle_backplane(message).await,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Relay backplane lagged, dropped {} messages", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(service.limits.sweep_interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = service.expire_idle(Utc::now()).await {
+                    error!("Failed to expire idle relay rooms: {:#}", e);
+                }
+            }
+        });
+    }
+
+    pub async fn snapshot(&self) -> RelaySnapshot {
+        let rooms = self.store.rooms().unwrap_or_else(|e| {
+            error!("Failed to list relay rooms: {:#}", e);
+            Vec::new()
+        });
+        let room_snapshots = rooms
+            .into_iter()
+            .map(|room| RelayRoomSnapshot {
+                room_id: room.room_id,
+                participants: room.members.len() as u32,
+                created_at: room.created_at,
+                bytes_transferred: room.bytes_transferred,
+            })
+            .collect::<Vec<_>>();
+
+        RelaySnapshot {
+            active_rooms: room_snapshots.len() as u64,
+            connected_devices: room_snapshots
+                .iter()
+                .map(|room| u64::from(room.participants))
+                .sum(),
+            rooms: room_snapshots,
+        }
+    }
+
+    pub async fn render_metrics(&self) -> String {
+        let snapshot = self.snapshot().await;
+        self.metrics
+            .render(snapshot.active_rooms, snapshot.connected_devices)
+    }
+
+    #[cfg(test)]
+    pub async fn seed_room_for_test(
+        &self,
+        room_id: &str,
+        participants: &[&str],
+        bytes_transferred: u64,
+    ) {
+        let now = Utc::now();
+        self.store.open_room(room_id, "free", now).unwrap();
+        for participant in participants {
+            self.store
+                .add_member(room_id, participant, "", now)
+                .unwrap();
+        }
+        self.store
+            .record_traffic(room_id, bytes_transferred, now, self.limits.quota_window)
+            .unwrap();
+    }
+
+    fn tier_for(&self, session_token: &str) -> String {
+        self.jwt_secret
+            .as_deref()
+            .and_then(|secret| validate_jwt(session_token, secret).ok())
+            .map(|claims| claims.tier)
+            .unwrap_or_else(|| "free".into())
+    }
+
+    async fn register(&self, session: &mut Session, session_token: &str, node_id: String) {
+        RelayMetrics::incr(&self.metrics.frames_register);
+        if let (Some(room_id), Some(previous)) = (session.room_id.take(), &session.node_id) {
+            self.detach(&room_id, previous).await;
+        }
+        session.token_hash = hex::encode(Sha256::digest(session_token.as_bytes()));
+        session.tier = self.tier_for(session_token);
+        debug!("Node registered: {}", node_id);
+
+        // A node reconnecting with the same session goes straight back into
+        // the room it was in.
+        match self.store.membership(&node_id) {
+            Ok(Some(membership)) if membership.token_hash == session.token_hash => {
+                self.attach(&membership.room_id, &node_id, &session.tx)
+                    .await;
+                info!("Node {} rejoined room {}", node_id, membership.room_id);
+                session.room_id = Some(membership.room_id);
+            }
+            Ok(_) => {}
+            Err(e) => error!(
+                "Failed to look up relay membership for {}: {:#}",
+                node_id, e
+            ),
+        }
+        session.node_id = Some(node_id);
+    }
+
+    async fn join(&self, session: &mut Session, room_id: String) {
+        RelayMetrics::incr(&self.metrics.frames_join);
+        let Some(node_id) = session.node_id.clone() else {
+            warn!("Received Join/Create room before register");
+            return;
+        };
+        if let Some(previous) = session.room_id.take() {
+            self.detach(&previous, &node_id).await;
+        }
+
+        let now = Utc::now();
+        let joined = self
+            .store
+            .open_room(&room_id, &session.tier, now)
+            .and_then(|_| {
+                self.store
+                    .add_member(&room_id, &node_id, &session.token_hash, now)
+            });
+        if let Err(e) = joined {
+            error!("Failed to add {} to room {}: {:#}", node_id, room_id, e);
+            send_error(&session.tx, 500, "failed to join room");
+            return;
+        }
+
+        self.attach(&room_id, &node_id, &session.tx).await;
+        info!("Node {} joined room {}", node_id, room_id);
+        session.room_id = Some(room_id);
+    }
+
+    async fn forward(&self, session: &mut Session, to: Option<String>, payload: EncryptedEnvelope) {
+        RelayMetrics::incr(&self.metrics.frames_forward);
+        let (Some(room_id), Some(sender_node)) = (session.room_id.clone(), &session.node_id) else {
+            return;
+        };
+        let room = match self.store.room(&room_id) {
+            Ok(Some(room)) => room,
+            Ok(None) => {
+                // Expired while the node was connected elsewhere or idle.
+                session.room_id = None;
+                send_error(&session.tx, 410, "room has expired");
+                return;
+            }
+            Err(e) => {
+                error!("Failed to load relay room {}: {:#}", room_id, e);
+                return;
+            }
+        };
+
+        let outbound = RelayFrame::Forward {
+            to: to.clone(),
+            payload,
+        };
+        let out_text = serde_json::to_string(&outbound).unwrap_or_default();
+        let message_bytes = out_text.len() as u64;
+
+        let now = Utc::now();
+        let used = self
+            .store
+            .record_traffic(&room_id, 0, now, self.limits.quota_window)
+            .unwrap_or_default();
+        if used.saturating_add(message_bytes) > self.limits.quota_for(&room.tier) {
+            RelayMetrics::incr(&self.metrics.quota_rejections);
+            send_error(&session.tx, 429, "room bandwidth quota exceeded");
+            return;
+        }
+
+        let delivered = self
+            .deliver_local(&room_id, sender_node, to.as_deref(), &out_text)
+            .await;
+        self.record_delivery(&room_id, message_bytes.saturating_mul(delivered), now);
+
+        // A direct message that reached its target here needs no fan-out.
+        if to.is_some() && delivered > 0 {
+            return;
+        }
+        self.publish(BackplaneMessage {
+            origin: self.instance_id.clone(),
+            room_id,
+            event: BackplaneEvent::Forward {
+                from: sender_node.clone(),
+                to,
+                frame: out_text,
+            },
+        })
+        .await;
+    }
+
+    async fn leave(&self, session: &mut Session) {
+        RelayMetrics::incr(&self.metrics.frames_leave);
+        let (Some(room_id), Some(node_id)) = (session.room_id.take(), &session.node_id) else {
+            return;
+        };
+        self.detach(&room_id, node_id).await;
+        let result = self.store.remove_member(&room_id, node_id).and_then(|_| {
+            match self.store.room(&room_id)? {
+                Some(room) if room.members.is_empty() => self.store.remove_room(&room_id),
+                _ => Ok(()),
+            }
+        });
+        if let Err(e) = result {
+            error!(
+                "Failed to remove {} from room {}: {:#}",
+                node_id, room_id, e
+            );
+        }
+    }
+
+    async fn handle_backplane(&self, message: BackplaneMessage) {
+        if message.origin == self.instance_id {
+            return;
+        }
+        RelayMetrics::incr(&self.metrics.backplane_received);
+        match message.event {
+            BackplaneEvent::Forward { from, to, frame } => {
+                let delivered = self
+                    .deliver_local(&message.room_id, &from, to.as_deref(), &frame)
+                    .await;
+                self.record_delivery(
+                    &message.room_id,
+                    (frame.len() as u64).saturating_mul(delivered),
+                    Utc::now(),
+                );
+            }
+            BackplaneEvent::Expire => self.close_local_room(&message.room_id).await,
+        }
+    }
+
+    /// Remove rooms idle since before `now - idle_timeout`, returning their IDs.
+    pub async fn expire_idle(&self, now: DateTime<Utc>) -> anyhow::Result<Vec<String>> {
+        let expired = self
+            .store
+            .idle_rooms(now - self.limits.idle_timeout)
+            .context("failed to list idle rooms")?;
+        for room_id in &expired {
+            self.store.remove_room(room_id)?;
+            self.close_local_room(room_id).await;
+            RelayMetrics::incr(&self.metrics.rooms_expired);
+            info!("Expired idle relay room {}", room_id);
+            self.publish(BackplaneMessage {
+                origin: self.instance_id.clone(),
+                room_id: room_id.clone(),
+                event: BackplaneEvent::Expire,
+            })
+            .await;
+        }
+        Ok(expired)
+    }
+
+    async fn publish(&self, message: BackplaneMessage) {
+        match self.backplane.publish(message).await {
+            Ok(()) => RelayMetrics::incr(&self.metrics.backplane_published),
+            Err(e) => error!("Failed to publish to relay backplane: {:#}", e),
+        }
+    }
+
+    /// Send `frame` to local sockets in the room: the `to` node, or everyone
+    /// but `from`. Returns the number of sockets reached.
+    async fn deliver_local(&self, room_id: &str, from: &str, to: Option<&str>, frame: &str) -> u64 {
+        let connections = self.connections.read().await;
+        let Some(clients) = connections.get(room_id) else {
+            return 0;
+        };
+        let mut delivered = 0_u64;
+        if let Some(target) = to {
+            if let Some(client_tx) = clients.get(target) {
+                let _ = client_tx.send(Message::Text(frame.to_owned().into()));
+                delivered = 1;
+            }
+        } else {
+            // Broadcast to all except sender
+            for (node, client_tx) in clients.iter() {
+                if node != from {
+                    let _ = client_tx.send(Message::Text(frame.to_owned().into()));
+                    delivered += 1;
+                }
+            }
+        }
+        delivered
+    }
+
+    fn record_delivery(&self, room_id: &str, bytes: u64, now: DateTime<Utc>) {
+        if bytes == 0 {
+            return;
+        }
+        RelayMetrics::add(&self.metrics.bytes_forwarded, bytes);
+        if let Err(e) = self
+            .store
+            .record_traffic(room_id, bytes, now, self.limits.quota_window)
+        {
+            error!("Failed to record relay traffic for {}: {:#}", room_id, e);
+        }
+    }
+
+    async fn attach(&self, room_id: &str, node_id: &str, tx: &Tx) {
+        self.connections
+            .write()
+            .await
+            .entry(room_id.to_string())
+            .or_default()
+            .insert(node_id.to_string(), tx.clone());
+    }
+
+    async fn detach(&self, room_id: &str, node_id: &str) {
+        let mut connections = self.connections.write().await;
+        if let Some(clients) = connections.get_mut(room_id) {
+            clients.remove(node_id);
+            if clients.is_empty() {
+                connections.remove(room_id);
+            }
+        }
+    }
+
+    async fn close_local_room(&self, room_id: &str) {
+        if let Some(clients) = self.connections.write().await.remove(room_id) {
+            for client_tx in clients.values() {
+                send_error(client_tx, 410, "room expired after inactivity");
+            }
+        }
+    }
+}
+
+fn send_error(tx: &Tx, code: u16, message: &str) {
+    let frame = RelayFrame::Error {
+        code,
+        message: message.to_string(),
+    };
+    let out = serde_json::to_string(&frame).unwrap_or_default();
+    let _ = tx.send(Message::Text(out.into()));
+}
+
+async fn get_metrics(State(state): State<Arc<RelayService>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.render_metrics().await,
+    )
+}
+
+async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<RelayService>>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_socket(socket, state))
+}
+
+async fn handle_socket(socket: WebSocket, state: Arc<RelayService>) {
+    let (mut sender, mut receiver) = socket.split();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    // Spawn a task to forward messages from our internal channel to the websocket
+    let mut send_task = tokio::spawn(async move {
+        while let Some(msg) = rx.recv().await {
+            if sender.send(msg).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut session = Session {
+        tx,
+        node_id: None,
+        token_hash: String::new(),
+        tier: "free".into(),
+        room_id: None,
+    };
+    RelayMetrics::incr(&state.metrics.connections);
+
+    let mut recv_task = {
+        let state = state.clone();
+        tokio::spawn(async move {
+            while let Some(Ok(msg)) = receiver.next().await {
+                if let Message::Text(text) = msg {
+                    match serde_json::from_str::<RelayFrame>(&text) {
+                        Ok(frame) => match frame {
+                            RelayFrame::Register {
+                                session_token,
+                                node_id,
+                            } => state.register(&mut session, &session_token, node_id).await,
+                            RelayFrame::JoinRoom { room_id, .. }
+                            | RelayFrame::CreateRoom { room_id, .. } => {
+                                state.join(&mut session, room_id).await
+                            }
+                            RelayFrame::Forward { to, payload } => {
+                                state.forward(&mut session, to, payload).await
+                            }
+                            RelayFrame::LeaveRoom => state.leave(&mut session).await,
+                            RelayFrame::Ping => {
+                                RelayMetrics::incr(&state.metrics.frames_ping);
+                                let pong =
+                                    serde_json::to_string(&RelayFrame::Pong).unwrap_or_default();
+                                let _ = session.tx.send(Message::Text(pong.into()));
+                            }
+                            _ => debug!("Unhandled frame: {:?}", frame),
+                        },
+                        Err(e) => {
+                            error!("Failed to parse RelayFrame: {}", e);
+                            RelayMetrics::incr(&state.metrics.frames_invalid);
+                            send_error(&session.tx, 400, &format!("Parse error: {}", e));
+                        }
+                    }
+                }
+            }
+
+            // The membership outlives the socket so the node can reconnect;
+            // only this instance's handle to it goes away.
+            if let (Some(room_id), Some(node_id)) = (session.room_id, session.node_id.as_ref()) {
+                state.detach(&room_id, node_id).await;
+            }
+        })
+    };
+
+    tokio::select! {
+        _ = (&mut send_task) => recv_task.abort(),
+        _ = (&mut recv_task) => send_task.abort(),
+    };
+    state
+        .metrics
+        .connections
+        .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::create_jwt;
+
+    fn session(tier: &str) -> (Session, mpsc::UnboundedReceiver<Message>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let session = Session {
+            tx,
+            node_id: None,
+            token_hash: String::new(),
+            tier: tier.into(),
+            room_id: None,
+        };
+        (session, rx)
+    }
+
+    fn envelope(size: usize) -> EncryptedEnvelope {
+        EncryptedEnvelope {
+            nonce: [0; 12],
+            ciphertext: vec![7; size],
+            sender_fingerprint: "fp".into(),
+        }
+    }
+
+    async fn next_frame(rx: &mut mpsc::UnboundedReceiver<Message>) -> RelayFrame {
+        let message = tokio::time::timeout(std::time::Duration::from_secs(2), rx.recv())
+            .await
+            .expect("timed out waiting for a frame")
+            .expect("channel closed");
+        match message {
+            Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("unexpected message {other:?}"),
+        }
+    }
+
+    async fn joined(
+        service: &RelayService,
+        token: &str,
+        node_id: &str,
+        room_id: &str,
+    ) -> (Session, mpsc::UnboundedReceiver<Message>) {
+        let (mut session, rx) = session("free");
+        service.register(&mut session, token, node_id.into()).await;
+        service.join(&mut session, room_id.into()).await;
+        (session, rx)
+    }
+
+    #[tokio::test]
+    async fn snapshot_reports_rooms_participants_and_bytes() {
+        let service = RelayService::default();
+        service
+            .seed_room_for_test("room-1", &["node-a", "node-b"], 512)
+            .await;
+
+        let snapshot = service.snapshot().await;
+        assert_eq!(snapshot.active_rooms, 1);
+        assert_eq!(snapshot.connected_devices, 2);
+        assert_eq!(snapshot.rooms.len(), 1);
+        assert_eq!(snapshot.rooms[0].room_id, "room-1");
+        assert_eq!(snapshot.rooms[0].participants, 2);
+        assert_eq!(snapshot.rooms[0].bytes_transferred, 512);
+    }
+
+    #[tokio::test]
+    async fn forwards_between_instances_over_the_backplane() {
+        let store: Arc<dyn RoomStore> = Arc::new(MemoryRoomStore::default());
+        let backplane: Arc<dyn Backplane> = Arc::new(MemoryBackplane::default());
+        let instance = || {
+            let service = Arc::new(RelayService::new(
+                store.clone(),
+                backplane.clone(),
+                RelayLimits::default(),
+                None,
+            ));
+            service.start();
+            service
+        };
+        let (east, west) = (instance(), instance());
+
+        let (mut alice, _alice_rx) = joined(&east, "token-a", "node-a", "room-1").await;
+        let (_bob, mut bob_rx) = joined(&west, "token-b", "node-b", "room-1").await;
+
+        east.forward(&mut alice, None, envelope(16)).await;
+        let RelayFrame::Forward { to, payload } = next_frame(&mut bob_rx).await else {
+            panic!("expected a forwarded frame");
+        };
+        assert_eq!(to, None);
+        assert_eq!(payload.ciphertext, vec![7; 16]);
+
+        // Only the instance that delivered the frame counts the bytes.
+        let room = store.room("room-1").unwrap().unwrap();
+        assert!(room.bytes_transferred > 0);
+        assert_eq!(
+            west.metrics
+                .bytes_forwarded
+                .load(std::sync::atomic::Ordering::Relaxed),
+            room.bytes_transferred
+        );
+        assert!(
+            east.render_metrics()
+                .await
+                .contains("hive_relay_backplane_messages_total{direction=\"published\"} 1\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn forwards_between_instances_sharing_a_sqlite_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("relay.db");
+        let instance = || {
+            let service = Arc::new(RelayService::new(
+                Arc::new(SqliteRoomStore::open(&path).unwrap()),
+                Arc::new(SqliteBackplane::open(&path).unwrap()),
+                RelayLimits::default(),
+                None,
+            ));
+            service.start();
+            service
+        };
+        let (east, west) = (instance(), instance());
+
+        let (mut alice, _alice_rx) = joined(&east, "token-a", "node-a", "room-1").await;
+        let (_bob, mut bob_rx) = joined(&west, "token-b", "node-b", "room-1").await;
+
+        east.forward(&mut alice, Some("node-b".into()), envelope(16))
+            .await;
+        let RelayFrame::Forward { to, payload } = next_frame(&mut bob_rx).await else {
+            panic!("expected a forwarded frame");
+        };
+        assert_eq!(to.as_deref(), Some("node-b"));
+        assert_eq!(payload.ciphertext, vec![7; 16]);
+    }
+
+    #[tokio::test]
+    async fn reconnecting_node_rejoins_its_room() {
+        let service = RelayService::default();
+        let (first, _) = joined(&service, "token-a", "node-a", "room-1").await;
+        // Disconnect: the socket goes away, the membership stays.
+        service.detach("room-1", "node-a").await;
+        drop(first);
+
+        let (mut again, _rx) = session("free");
+        service
+            .register(&mut again, "token-a", "node-a".into())
+            .await;
+        assert_eq!(again.room_id.as_deref(), Some("room-1"));
+
+        // A different session token does not inherit the membership.
+        let (mut impostor, _rx) = session("free");
+        service
+            .register(&mut impostor, "token-x", "node-a".into())
+            .await;
+        assert_eq!(impostor.room_id, None);
+
+        service.leave(&mut again).await;
+        assert_eq!(service.snapshot().await.active_rooms, 0);
+    }
+
+    #[tokio::test]
+    async fn rejects_forwards_over_the_tier_quota() {
+        let limits = RelayLimits {
+            free_bytes: 400,
+            pro_bytes: 4_000,
+            ..RelayLimits::default()
+        };
+        let service = RelayService::new(
+            Arc::new(MemoryRoomStore::default()),
+            Arc::new(MemoryBackplane::default()),
+            limits,
+            Some("secret".into()),
+        );
+
+        let (mut alice, mut alice_rx) = joined(&service, "not-a-jwt", "node-a", "free-room").await;
+        let (_bob, mut bob_rx) = joined(&service, "not-a-jwt", "node-b", "free-room").await;
+        service.forward(&mut alice, None, envelope(16)).await;
+        assert!(matches!(
+            next_frame(&mut bob_rx).await,
+            RelayFrame::Forward { .. }
+        ));
+        service.forward(&mut alice, None, envelope(512)).await;
+        assert!(matches!(
+            next_frame(&mut alice_rx).await,
+            RelayFrame::Error { code: 429, .. }
+        ));
+        assert!(bob_rx.try_recv().is_err());
+
+        // The creator's verified tier sets the room's quota.
+        let pro = create_jwt("user-1", "pro", "secret").unwrap();
+        let (mut carol, _carol_rx) = joined(&service, &pro, "node-c", "pro-room").await;
+        let (_dave, mut dave_rx) = joined(&service, "not-a-jwt", "node-d", "pro-room").await;
+        service.forward(&mut carol, None, envelope(512)).await;
+        assert!(matches!(
+            next_frame(&mut dave_rx).await,
+            RelayFrame::Forward { .. }
+        ));
+        assert!(
+            service
+                .render_metrics()
+                .await
+                .contains("hive_relay_quota_rejections_total 1\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn expires_idle_rooms_and_notifies_members() {
+        let service = RelayService::default();
+        let (mut alice, mut alice_rx) = joined(&service, "token-a", "node-a", "room-1").await;
+
+        assert!(service.expire_idle(Utc::now()).await.unwrap().is_empty());
+        let later = Utc::now() + service.limits.idle_timeout + Duration::seconds(1);
+        assert_eq!(service.expire_idle(later).await.unwrap(), vec!["room-1"]);
+
+        assert!(matches!(
+            next_frame(&mut alice_rx).await,
+            RelayFrame::Error { code: 410, .. }
+        ));
+        assert_eq!(service.snapshot().await.active_rooms, 0);
+
+        service.forward(&mut alice, None, envelope(8)).await;
+        assert!(matches!(
+            next_frame(&mut alice_rx).await,
+            RelayFrame::Error { code: 410, .. }
+        ));
+        assert_eq!(alice.room_id, None);
+    }
+}
@@ -0,0 +1,611 @@
+//! Persistent relay state: rooms, their members and bandwidth accounting.
+//!
+//! Live sockets stay in each relay instance; everything a device needs to
+//! get back into its room after a restart, or when it reconnects to another
+//! instance, lives in a [`RoomStore`] shared by all instances.
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// A relay room as persisted between restarts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomRecord {
+    pub room_id: String,
+    /// Subscription tier of the device that created the room.
+    pub tier: String,
+    pub created_at: DateTime<Utc>,
+    /// Last join or forwarded message.
+    pub last_active: DateTime<Utc>,
+    /// Bytes delivered since the room was created.
+    pub bytes_transferred: u64,
+    pub window_started_at: DateTime<Utc>,
+    /// Bytes delivered in the current quota window.
+    pub window_bytes: u64,
+    /// Registered node IDs, sorted.
+    pub members: Vec<String>,
+}
+
+/// A node's registration in a room.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Membership {
+    pub room_id: String,
+    /// SHA-256 of the session token the node registered with; a reconnect
+    /// must present the same token to be put back into the room.
+    pub token_hash: String,
+}
+
+pub trait RoomStore: Send + Sync {
+    /// Create `room_id` unless it exists, returning the stored room.
+    fn open_room(
+        &self,
+        room_id: &str,
+        tier: &str,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<RoomRecord>;
+
+    fn room(&self, room_id: &str) -> anyhow::Result<Option<RoomRecord>>;
+
+    fn rooms(&self) -> anyhow::Result<Vec<RoomRecord>>;
+
+    /// Register `node_id` in `room_id`, moving it out of any other room.
+    fn add_member(
+        &self,
+        room_id: &str,
+        node_id: &str,
+        token_hash: &str,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()>;
+
+    fn remove_member(&self, room_id: &str, node_id: &str) -> anyhow::Result<()>;
+
+    fn membership(&self, node_id: &str) -> anyhow::Result<Option<Membership>>;
+
+    /// Add delivered bytes to a room's totals, starting a new quota window
+    /// if the current one is older than `window`. Returns the bytes used in
+    /// the current window.
+    fn record_traffic(
+        &self,
+        room_id: &str,
+        bytes: u64,
+        now: DateTime<Utc>,
+        window: Duration,
+    ) -> anyhow::Result<u64>;
+
+    /// Delete a room and its memberships.
+    fn remove_room(&self, room_id: &str) -> anyhow::Result<()>;
+
+    /// Rooms with no activity since `cutoff`.
+    fn idle_rooms(&self, cutoff: DateTime<Utc>) -> anyhow::Result<Vec<String>>;
+}
+
+/// Bytes used in the window containing `now`, given where it started.
+fn current_window(
+    started_at: DateTime<Utc>,
+    bytes: u64,
+    now: DateTime<Utc>,
+    window: Duration,
+) -> (DateTime<Utc>, u64) {
+    if now - started_at >= window {
+        (now, 0)
+    } else {
+        (started_at, bytes)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// In-memory store
+// ---------------------------------------------------------------------------
+
+/// Store for single-instance deployments and tests; forgets everything on
+/// restart.
+#[derive(Default)]
+pub struct MemoryRoomStore {
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Default)]
+struct MemoryState {
+    rooms: HashMap<String, RoomRecord>,
+    members: HashMap<String, Membership>,
+}
+
+impl MemoryState {
+    fn detach(&mut self, node_id: &str) {
+        if let Some(previous) = self.members.remove(node_id)
+            && let Some(room) = self.rooms.get_mut(&previous.room_id)
+        {
+            room.members.retain(|member| member != node_id);
+        }
+    }
+}
+
+impl RoomStore for MemoryRoomStore {
+    fn open_room(
+        &self,
+        room_id: &str,
+        tier: &str,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<RoomRecord> {
+        let mut state = self.state.lock().unwrap();
+        let room = state
+            .rooms
+            .entry(room_id.to_string())
+            .or_insert_with(|| RoomRecord {
+                room_id: room_id.to_string(),
+                tier: tier.to_string(),
+                created_at: now,
+                last_active: now,
+                bytes_transferred: 0,
+                window_started_at: now,
+                window_bytes: 0,
+                members: Vec::new(),
+            });
+        Ok(room.clone())
+    }
+
+    fn room(&self, room_id: &str) -> anyhow::Result<Option<RoomRecord>> {
+        Ok(self.state.lock().unwrap().rooms.get(room_id).cloned())
+    }
+
+    fn rooms(&self) -> anyhow::Result<Vec<RoomRecord>> {
+        let mut rooms: Vec<_> = self.state.lock().unwrap().rooms.values().cloned().collect();
+        rooms.sort_by(|left, right| left.room_id.cmp(&right.room_id));
+        Ok(rooms)
+    }
+
+    fn add_member(
+        &self,
+        room_id: &str,
+        node_id: &str,
+        token_hash: &str,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.detach(node_id);
+        let room = state
+            .rooms
+            .get_mut(room_id)
+            .with_context(|| format!("room {room_id} does not exist"))?;
+        room.members.push(node_id.to_string());
+        room.members.sort();
+        room.last_active = now;
+        state.members.insert(
+            node_id.to_string(),
+            Membership {
+                room_id: room_id.to_string(),
+                token_hash: token_hash.to_string(),
+            },
+        );
+        Ok(())
+    }
+
+    fn remove_member(&self, room_id: &str, node_id: &str) -> anyhow::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state
+            .members
+            .get(node_id)
+            .is_some_and(|membership| membership.room_id == room_id)
+        {
+            state.detach(node_id);
+        }
+        Ok(())
+    }
+
+    fn membership(&self, node_id: &str) -> anyhow::Result<Option<Membership>> {
+        Ok(self.state.lock().unwrap().members.get(node_id).cloned())
+    }
+
+    fn record_traffic(
+        &self,
+        room_id: &str,
+        bytes: u64,
+        now: DateTime<Utc>,
+        window: Duration,
+    ) -> anyhow::Result<u64> {
+        let mut state = self.state.lock().unwrap();
+        let Some(room) = state.rooms.get_mut(room_id) else {
+            return Ok(0);
+        };
+        let (started_at, used) =
+            current_window(room.window_started_at, room.window_bytes, now, window);
+        room.window_started_at = started_at;
+        room.window_bytes = used.saturating_add(bytes);
+        room.bytes_transferred = room.bytes_transferred.saturating_add(bytes);
+        if bytes > 0 {
+            room.last_active = now;
+        }
+        Ok(room.window_bytes)
+    }
+
+    fn remove_room(&self, room_id: &str) -> anyhow::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.rooms.remove(room_id);
+        state
+            .members
+            .retain(|_, membership| membership.room_id != room_id);
+        Ok(())
+    }
+
+    fn idle_rooms(&self, cutoff: DateTime<Utc>) -> anyhow::Result<Vec<String>> {
+        let state = self.state.lock().unwrap();
+        let mut idle: Vec<_> = state
+            .rooms
+            .values()
+            .filter(|room| room.last_active < cutoff)
+            .map(|room| room.room_id.clone())
+            .collect();
+        idle.sort();
+        Ok(idle)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SQLite store
+// ---------------------------------------------------------------------------
+
+/// Store backed by a SQLite database, so rooms survive restarts. Instances
+/// sharing the database file see the same rooms.
+pub struct SqliteRoomStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteRoomStore {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("failed to open relay store {}", path.display()))?;
+        Self::with_connection(conn)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> anyhow::Result<Self> {
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+            PRAGMA foreign_keys = ON;
+            CREATE TABLE IF NOT EXISTS relay_rooms (
+                room_id           TEXT    PRIMARY KEY,
+                tier              TEXT    NOT NULL,
+                created_at        INTEGER NOT NULL,
+                last_active       INTEGER NOT NULL,
+                bytes_transferred INTEGER NOT NULL DEFAULT 0,
+                window_started_at INTEGER NOT NULL,
+                window_bytes      INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE TABLE IF NOT EXISTS relay_members (
+                node_id    TEXT    PRIMARY KEY,
+                room_id    TEXT    NOT NULL REFERENCES relay_rooms(room_id) ON DELETE CASCADE,
+                token_hash TEXT    NOT NULL,
+                joined_at  INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS relay_members_room ON relay_members(room_id);",
+        )
+        .context("failed to initialise relay store schema")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn load_room(conn: &Connection, room_id: &str) -> anyhow::Result<Option<RoomRecord>> {
+        let room = conn
+            .query_row(
+                "SELECT room_id, tier, created_at, last_active, bytes_transferred,
+                        window_started_at, window_bytes
+                 FROM relay_rooms WHERE room_id = ?1",
+                params![room_id],
+                |row| {
+                    Ok(RoomRecord {
+                        room_id: row.get(0)?,
+                        tier: row.get(1)?,
+                        created_at: from_millis(row.get(2)?),
+                        last_active: from_millis(row.get(3)?),
+                        bytes_transferred: row.get::<_, i64>(4)? as u64,
+                        window_started_at: from_millis(row.get(5)?),
+                        window_bytes: row.get::<_, i64>(6)? as u64,
+                        members: Vec::new(),
+                    })
+                },
+            )
+            .optional()?;
+        let Some(mut room) = room else {
+            return Ok(None);
+        };
+
+        let mut stmt =
+            conn.prepare("SELECT node_id FROM relay_members WHERE room_id = ?1 ORDER BY node_id")?;
+        room.members = stmt
+            .query_map(params![room_id], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        Ok(Some(room))
+    }
+}
+
+fn to_millis(time: DateTime<Utc>) -> i64 {
+    time.timestamp_millis()
+}
+
+fn from_millis(millis: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(millis).unwrap_or_default()
+}
+
+impl RoomStore for SqliteRoomStore {
+    fn open_room(
+        &self,
+        room_id: &str,
+        tier: &str,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<RoomRecord> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR IGNORE INTO relay_rooms
+                (room_id, tier, created_at, last_active, window_started_at)
+             VALUES (?1, ?2, ?3, ?3, ?3)",
+            params![room_id, tier, to_millis(now)],
+        )?;
+        Self::load_room(&conn, room_id)?.context("room vanished after insert")
+    }
+
+    fn room(&self, room_id: &str) -> anyhow::Result<Option<RoomRecord>> {
+        Self::load_room(&self.conn.lock().unwrap(), room_id)
+    }
+
+    fn rooms(&self) -> anyhow::Result<Vec<RoomRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let ids: Vec<String> = conn
+            .prepare("SELECT room_id FROM relay_rooms ORDER BY room_id")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        ids.iter()
+            .filter_map(|id| Self::load_room(&conn, id).transpose())
+            .collect()
+    }
+
+    fn add_member(
+        &self,
+        room_id: &str,
+        node_id: &str,
+        token_hash: &str,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO relay_members (node_id, room_id, token_hash, joined_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(node_id) DO UPDATE SET
+                room_id = excluded.room_id,
+                token_hash = excluded.token_hash,
+                joined_at = excluded.joined_at",
+            params![node_id, room_id, token_hash, to_millis(now)],
+        )
+        .with_context(|| format!("failed to add {node_id} to room {room_id}"))?;
+        tx.execute(
+            "UPDATE relay_rooms SET last_active = ?2 WHERE room_id = ?1",
+            params![room_id, to_millis(now)],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn remove_member(&self, room_id: &str, node_id: &str) -> anyhow::Result<()> {
+        self.conn.lock().unwrap().execute(
+            "DELETE FROM relay_members WHERE room_id = ?1 AND node_id = ?2",
+            params![room_id, node_id],
+        )?;
+        Ok(())
+    }
+
+    fn membership(&self, node_id: &str) -> anyhow::Result<Option<Membership>> {
+        Ok(self
+            .conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT room_id, token_hash FROM relay_members WHERE node_id = ?1",
+                params![node_id],
+                |row| {
+                    Ok(Membership {
+                        room_id: row.get(0)?,
+                        token_hash: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    fn record_traffic(
+        &self,
+        room_id: &str,
+        bytes: u64,
+        now: DateTime<Utc>,
+        window: Duration,
+    ) -> anyhow::Result<u64> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let current = tx
+            .query_row(
+                "SELECT window_started_at, window_bytes FROM relay_rooms WHERE room_id = ?1",
+                params![room_id],
+                |row| Ok((from_millis(row.get(0)?), row.get::<_, i64>(1)? as u64)),
+            )
+            .optional()?;
+        let Some((started_at, used)) = current else {
+            return Ok(0);
+        };
+
+        let (started_at, used) = current_window(started_at, used, now, window);
+        let used = used.saturating_add(bytes);
+        tx.execute(
+            "UPDATE relay_rooms SET
+                window_started_at = ?2,
+                window_bytes = ?3,
+                bytes_transferred = bytes_transferred + ?4,
+                last_active = CASE WHEN ?4 > 0 THEN ?5 ELSE last_active END
+             WHERE room_id = ?1",
+            params![
+                room_id,
+                to_millis(started_at),
+                used as i64,
+                bytes as i64,
+                to_millis(now)
+            ],
+        )?;
+        tx.commit()?;
+        Ok(used)
+    }
+
+    fn remove_room(&self, room_id: &str) -> anyhow::Result<()> {
+        self.conn.lock().unwrap().execute(
+            "DELETE FROM relay_rooms WHERE room_id = ?1",
+            params![room_id],
+        )?;
+        Ok(())
+    }
+
+    fn idle_rooms(&self, cutoff: DateTime<Utc>) -> anyhow::Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let ids = conn
+            .prepare("SELECT room_id FROM relay_rooms WHERE last_active < ?1 ORDER BY room_id")?
+            .query_map(params![to_millis(cutoff)], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stores() -> Vec<(&'static str, Box<dyn RoomStore>)> {
+        vec![
+            ("memory", Box::new(MemoryRoomStore::default())),
+            (
+                "sqlite",
+                Box::new(SqliteRoomStore::open_in_memory().unwrap()),
+            ),
+        ]
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn rooms_and_members_round_trip() {
+        for (name, store) in stores() {
+            let created = store.open_room("room-1", "pro", at(0)).unwrap();
+            assert_eq!(created.tier, "pro", "{name}");
+            // Re-opening keeps the original creator's tier.
+            assert_eq!(
+                store.open_room("room-1", "free", at(5)).unwrap().tier,
+                "pro"
+            );
+
+            store
+                .add_member("room-1", "node-b", "hash-b", at(10))
+                .unwrap();
+            store
+                .add_member("room-1", "node-a", "hash-a", at(10))
+                .unwrap();
+            let room = store.room("room-1").unwrap().unwrap();
+            assert_eq!(room.members, vec!["node-a", "node-b"], "{name}");
+            assert_eq!(room.last_active, at(10), "{name}");
+
+            // Joining another room moves the node.
+            store.open_room("room-2", "free", at(20)).unwrap();
+            store
+                .add_member("room-2", "node-a", "hash-a", at(20))
+                .unwrap();
+            assert_eq!(
+                store.membership("node-a").unwrap().unwrap().room_id,
+                "room-2",
+                "{name}"
+            );
+            assert_eq!(
+                store.room("room-1").unwrap().unwrap().members,
+                vec!["node-b"],
+                "{name}"
+            );
+
+            store.remove_member("room-1", "node-b").unwrap();
+            assert!(store.membership("node-b").unwrap().is_none(), "{name}");
+
+            store.remove_room("room-2").unwrap();
+            assert!(store.membership("node-a").unwrap().is_none(), "{name}");
+            let ids: Vec<_> = store
+                .rooms()
+                .unwrap()
+                .into_iter()
+                .map(|room| room.room_id)
+                .collect();
+            assert_eq!(ids, vec!["room-1"], "{name}");
+        }
+    }
+
+    #[test]
+    fn traffic_accumulates_per_window() {
+        for (name, store) in stores() {
+            let window = Duration::seconds(60);
+            store.open_room("room-1", "free", at(0)).unwrap();
+
+            assert_eq!(
+                store.record_traffic("room-1", 100, at(1), window).unwrap(),
+                100
+            );
+            assert_eq!(
+                store.record_traffic("room-1", 50, at(30), window).unwrap(),
+                150
+            );
+            // A new window starts once the old one has elapsed.
+            assert_eq!(
+                store.record_traffic("room-1", 10, at(61), window).unwrap(),
+                10
+            );
+            assert_eq!(
+                store.record_traffic("missing", 10, at(61), window).unwrap(),
+                0
+            );
+
+            let room = store.room("room-1").unwrap().unwrap();
+            assert_eq!(room.bytes_transferred, 160, "{name}");
+            assert_eq!(room.last_active, at(61), "{name}");
+
+            assert!(store.idle_rooms(at(61)).unwrap().is_empty(), "{name}");
+            assert_eq!(store.idle_rooms(at(62)).unwrap(), vec!["room-1"], "{name}");
+        }
+    }
+
+    #[test]
+    fn sqlite_store_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("relay.db");
+        {
+            let store = SqliteRoomStore::open(&path).unwrap();
+            store.open_room("room-1", "team", at(0)).unwrap();
+            store
+                .add_member("room-1", "node-a", "hash-a", at(1))
+                .unwrap();
+        }
+
+        let store = SqliteRoomStore::open(&path).unwrap();
+        assert_eq!(
+            store.membership("node-a").unwrap(),
+            Some(Membership {
+                room_id: "room-1".into(),
+                token_hash: "hash-a".into(),
+            })
+        );
+        assert_eq!(store.room("room-1").unwrap().unwrap().tier, "team");
+    }
+}
@@ -0,0 +1,96 @@
+//! Storage backends for sync blob bodies.
+//!
+//! Backends only see opaque object names chosen by the sync service; keys,
+//! versions and ownership are tracked in the [`SyncIndex`](super::index::SyncIndex).
+
+use anyhow::Context;
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+#[async_trait]
+pub trait BlobBackend: Send + Sync {
+    /// Store `data` under `object`, replacing anything already there.
+    async fn put(&self, object: &str, data: &[u8]) -> anyhow::Result<()>;
+
+    /// Fetch an object's body, or `None` if it does not exist.
+    async fn get(&self, object: &str) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Delete an object. Deleting a missing object is not an error.
+    async fn delete(&self, object: &str) -> anyhow::Result<()>;
+}
+
+/// Stores objects as files below a root directory.
+pub struct LocalBlobBackend {
+    root: PathBuf,
+}
+
+impl LocalBlobBackend {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, object: &str) -> PathBuf {
+        self.root.join(object)
+    }
+}
+
+#[async_trait]
+impl BlobBackend for LocalBlobBackend {
+    async fn put(&self, object: &str, data: &[u8]) -> anyhow::Result<()> {
+        let path = self.path(object);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        // Write to a sibling and rename so readers never see a partial body.
+        let tmp = self.path(&format!("{object}.tmp"));
+        tokio::fs::write(&tmp, data)
+            .await
+            .with_context(|| format!("failed to write {}", tmp.display()))?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .with_context(|| format!("failed to move blob into {}", path.display()))?;
+        Ok(())
+    }
+
+    async fn get(&self, object: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path(object)).await {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err).with_context(|| format!("failed to read blob {object}")),
+        }
+    }
+
+    async fn delete(&self, object: &str) -> anyhow::Result<()> {
+        match tokio::fs::remove_file(self.path(object)).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err).with_context(|| format!("failed to delete blob {object}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn local_backend_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = LocalBlobBackend::new(dir.path());
+
+        assert!(backend.get("u/abc/def.1").await.unwrap().is_none());
+        backend.put("u/abc/def.1", b"hello").await.unwrap();
+        backend.put("u/abc/def.1", b"hello again").await.unwrap();
+        assert_eq!(
+            backend.get("u/abc/def.1").await.unwrap().as_deref(),
+            Some(&b"hello again"[..])
+        );
+
+        backend.delete("u/abc/def.1").await.unwrap();
+        backend.delete("u/abc/def.1").await.unwrap();
+        assert!(backend.get("u/abc/def.1").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,219 @@
+//! SQLite index of the blobs each user has stored.
+//!
+//! The index is the source of truth for what exists: blob bodies live in a
+//! [`BlobBackend`](super::backend::BlobBackend) under the object name
+//! recorded here, and a body without an index row is garbage.
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Metadata for one stored blob.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobRecord {
+    pub key: String,
+    pub size_bytes: u64,
+    /// Hex SHA-256 of the blob body.
+    pub checksum: String,
+    /// Starts at 1 and increases with every write of the key.
+    pub version: u64,
+    pub updated_at: DateTime<Utc>,
+    /// Client-supplied version vector (device id to counter). Opaque to the
+    /// server; stored so devices can compare versions from the manifest.
+    pub version_vector: BTreeMap<String, u64>,
+    /// Backend object holding the body.
+    pub object: String,
+}
+
+impl BlobRecord {
+    /// Strong entity tag for the current version, quoted as sent in headers.
+    pub fn etag(&self) -> String {
+        format!("\"{}-{}\"", self.version, &self.checksum[..16])
+    }
+}
+
+pub struct SyncIndex {
+    conn: Connection,
+}
+
+impl SyncIndex {
+    /// Open (or create) the index database at `path`.
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("failed to open sync index {}", path.display()))?;
+        Self::with_connection(conn)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> anyhow::Result<Self> {
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+            CREATE TABLE IF NOT EXISTS sync_blobs (
+                user_id     TEXT    NOT NULL,
+                key         TEXT    NOT NULL,
+                size_bytes  INTEGER NOT NULL,
+                checksum    TEXT    NOT NULL,
+                version     INTEGER NOT NULL,
+                updated_at  TEXT    NOT NULL,
+                version_vector TEXT NOT NULL DEFAULT '{}',
+                object      TEXT    NOT NULL,
+                PRIMARY KEY (user_id, key)
+            );",
+        )
+        .context("failed to initialise sync index schema")?;
+        Ok(Self { conn })
+    }
+
+    pub fn get(&self, user_id: &str, key: &str) -> anyhow::Result<Option<BlobRecord>> {
+        self.conn
+            .query_row(
+                "SELECT key, size_bytes, checksum, version, updated_at, object, version_vector
+                 FROM sync_blobs WHERE user_id = ?1 AND key = ?2",
+                params![user_id, key],
+                read_record,
+            )
+            .optional()?
+            .transpose()
+    }
+
+    /// All of a user's blobs, ordered by key.
+    pub fn list(&self, user_id: &str) -> anyhow::Result<Vec<BlobRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT key, size_bytes, checksum, version, updated_at, object, version_vector
+             FROM sync_blobs WHERE user_id = ?1 ORDER BY key",
+        )?;
+        let rows = stmt.query_map(params![user_id], read_record)?;
+        rows.map(|row| row?).collect()
+    }
+
+    /// Total bytes stored by a user.
+    pub fn usage(&self, user_id: &str) -> anyhow::Result<u64> {
+        let total: i64 = self.conn.query_row(
+            "SELECT COALESCE(SUM(size_bytes), 0) FROM sync_blobs WHERE user_id = ?1",
+            params![user_id],
+            |row| row.get(0),
+        )?;
+        Ok(total as u64)
+    }
+
+    pub fn upsert(&self, user_id: &str, record: &BlobRecord) -> anyhow::Result<()> {
+        self.conn.execute(
+            "INSERT INTO sync_blobs
+                (user_id, key, size_bytes, checksum, version, updated_at, object, version_vector)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(user_id, key) DO UPDATE SET
+                size_bytes = excluded.size_bytes,
+                checksum = excluded.checksum,
+                version = excluded.version,
+                updated_at = excluded.updated_at,
+                object = excluded.object,
+                version_vector = excluded.version_vector",
+            params![
+                user_id,
+                record.key,
+                record.size_bytes as i64,
+                record.checksum,
+                record.version as i64,
+                record.updated_at.to_rfc3339(),
+                record.object,
+                serde_json::to_string(&record.version_vector)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a blob's row, returning whether it existed.
+    pub fn remove(&self, user_id: &str, key: &str) -> anyhow::Result<bool> {
+        let removed = self.conn.execute(
+            "DELETE FROM sync_blobs WHERE user_id = ?1 AND key = ?2",
+            params![user_id, key],
+        )?;
+        Ok(removed > 0)
+    }
+}
+
+fn read_record(row: &rusqlite::Row<'_>) -> rusqlite::Result<anyhow::Result<BlobRecord>> {
+    let key: String = row.get(0)?;
+    let size_bytes: i64 = row.get(1)?;
+    let checksum: String = row.get(2)?;
+    let version: i64 = row.get(3)?;
+    let updated_at: String = row.get(4)?;
+    let object: String = row.get(5)?;
+    let version_vector: String = row.get(6)?;
+
+    let updated_at = match DateTime::parse_from_rfc3339(&updated_at) {
+        Ok(updated_at) => updated_at.with_timezone(&Utc),
+        Err(err) => return Ok(Err(anyhow::anyhow!("invalid updated_at for {key}: {err}"))),
+    };
+    let version_vector = match serde_json::from_str(&version_vector) {
+        Ok(version_vector) => version_vector,
+        Err(err) => {
+            return Ok(Err(anyhow::anyhow!(
+                "invalid version vector for {key}: {err}"
+            )));
+        }
+    };
+
+    Ok(Ok(BlobRecord {
+        key,
+        size_bytes: size_bytes as u64,
+        checksum,
+        version: version as u64,
+        updated_at,
+        version_vector,
+        object,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(key: &str, size_bytes: u64, version: u64) -> BlobRecord {
+        BlobRecord {
+            key: key.into(),
+            size_bytes,
+            checksum: "ab".repeat(32),
+            version,
+            updated_at: Utc::now(),
+            version_vector: BTreeMap::from([("device-a".to_string(), version)]),
+            object: format!("objects/{key}.{version}"),
+        }
+    }
+
+    #[test]
+    fn upsert_get_list_and_remove() {
+        let index = SyncIndex::open_in_memory().unwrap();
+        index.upsert("user-a", &record("b.json", 10, 1)).unwrap();
+        index.upsert("user-a", &record("a.json", 5, 1)).unwrap();
+        index.upsert("user-a", &record("b.json", 20, 2)).unwrap();
+        index.upsert("user-b", &record("a.json", 7, 1)).unwrap();
+
+        let stored = index.get("user-a", "b.json").unwrap().unwrap();
+        assert_eq!(stored.version, 2);
+        assert_eq!(stored.size_bytes, 20);
+        assert_eq!(stored.version_vector["device-a"], 2);
+        assert_eq!(stored.etag(), format!("\"2-{}\"", "ab".repeat(8)));
+
+        let keys: Vec<String> = index
+            .list("user-a")
+            .unwrap()
+            .into_iter()
+            .map(|record| record.key)
+            .collect();
+        assert_eq!(keys, vec!["a.json", "b.json"]);
+        assert_eq!(index.usage("user-a").unwrap(), 25);
+        assert_eq!(index.usage("user-c").unwrap(), 0);
+
+        assert!(index.remove("user-a", "a.json").unwrap());
+        assert!(!index.remove("user-a", "a.json").unwrap());
+        assert!(index.get("user-a", "a.json").unwrap().is_none());
+        assert!(index.get("user-b", "a.json").unwrap().is_some());
+    }
+}
@@ -0,0 +1,1112 @@
+//! Per-user blob storage behind the `/v1/sync` API used by
+//! `hive_core::cloud_sync::SyncClient`.
+//!
+//! Every request carries a cloud JWT; blobs are namespaced by the token's
+//! subject and capped by the quota of its tier. Writes are versioned, and
+//! clients can make them conditional with `If-Match` / `If-None-Match` on
+//! the returned `ETag` to avoid clobbering a newer copy from another device.
+//! Bodies are opaque to the server (clients encrypt them); a client-supplied
+//! version vector is stored alongside each blob and echoed in the manifest
+//! and on reads.
+
+mod backend;
+mod index;
+#[cfg(feature = "s3")]
+mod s3;
+
+pub use backend::{BlobBackend, LocalBlobBackend};
+pub use index::{BlobRecord, SyncIndex};
+
+use crate::auth::{Claims, validate_jwt};
+use anyhow::Context;
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+/// Longest accepted blob key, in bytes.
+const MAX_KEY_LEN: usize = 512;
+
+/// Request header carrying the client's version vector for a write.
+const VERSION_VECTOR_HEADER: &str = "x-hive-version-vector";
+
+/// Most devices a version vector may name.
+const MAX_VERSION_VECTOR_LEN: usize = 256;
+
+type ApiError = (StatusCode, String);
+
+/// Storage limits for one subscription tier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TierQuota {
+    pub storage_bytes: u64,
+    pub max_blob_bytes: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct QuotaPolicy {
+    pub free: TierQuota,
+    pub pro: TierQuota,
+    pub team: TierQuota,
+}
+
+impl Default for QuotaPolicy {
+    fn default() -> Self {
+        const MIB: u64 = 1024 * 1024;
+        const GIB: u64 = 1024 * MIB;
+        Self {
+            free: TierQuota {
+                storage_bytes: 100 * MIB,
+                max_blob_bytes: 10 * MIB,
+            },
+            pro: TierQuota {
+                storage_bytes: 10 * GIB,
+                max_blob_bytes: 100 * MIB,
+            },
+            team: TierQuota {
+                storage_bytes: 50 * GIB,
+                max_blob_bytes: 100 * MIB,
+            },
+        }
+    }
+}
+
+impl QuotaPolicy {
+    /// Limits for `tier`. Unknown tiers get the free allowance.
+    pub fn for_tier(&self, tier: &str) -> TierQuota {
+        match tier {
+            "pro" => self.pro,
+            "team" => self.team,
+            _ => self.free,
+        }
+    }
+
+    fn largest_blob(&self) -> u64 {
+        [self.free, self.pro, self.team]
+            .iter()
+            .map(|quota| quota.max_blob_bytes)
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+pub struct SyncState {
+    index: Mutex<SyncIndex>,
+    backend: Arc<dyn BlobBackend>,
+    quotas: QuotaPolicy,
+    jwt_secret: Option<String>,
+}
+
+impl SyncState {
+    pub fn new(
+        index: SyncIndex,
+        backend: Arc<dyn BlobBackend>,
+        quotas: QuotaPolicy,
+        jwt_secret: Option<String>,
+    ) -> Self {
+        Self {
+            index: Mutex::new(index),
+            backend,
+            quotas,
+            jwt_secret,
+        }
+    }
+
+    /// Build the sync service from the environment.
+    ///
+    /// `HIVE_CLOUD_JWT_SECRET` verifies client tokens (without it every
+    /// request is rejected with 503). The index lives in `HIVE_CLOUD_SYNC_DIR`
+    /// (default `./data/sync`), as do blob bodies unless
+    /// `HIVE_CLOUD_SYNC_BACKEND=s3` selects the S3-compatible backend.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let dir = PathBuf::from(
+            std::env::var("HIVE_CLOUD_SYNC_DIR").unwrap_or_else(|_| "./data/sync".into()),
+        );
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create sync directory {}", dir.display()))?;
+        let index = SyncIndex::open(&dir.join("sync_index.db"))?;
+
+        let backend: Arc<dyn BlobBackend> = match std::env::var("HIVE_CLOUD_SYNC_BACKEND")
+            .unwrap_or_else(|_| "local".into())
+            .as_str()
+        {
+            "local" => Arc::new(LocalBlobBackend::new(dir.join("blobs"))),
+            #[cfg(feature = "s3")]
+            "s3" => Arc::new(s3::S3BlobBackend::new(s3::S3Config::from_env()?)),
+            other => anyhow::bail!("unsupported HIVE_CLOUD_SYNC_BACKEND `{other}`"),
+        };
+
+        let jwt_secret = std::env::var("HIVE_CLOUD_JWT_SECRET")
+            .ok()
+            .filter(|value| !value.trim().is_empty());
+
+        Ok(Self::new(
+            index,
+            backend,
+            QuotaPolicy::default(),
+            jwt_secret,
+        ))
+    }
+}
+
+/// Manifest entry as served to clients.
+#[derive(Debug, Clone, Serialize)]
+struct ManifestEntry {
+    key: String,
+    size_bytes: u64,
+    checksum: String,
+    updated_at: DateTime<Utc>,
+    version: u64,
+    etag: String,
+    version_vector: BTreeMap<String, u64>,
+}
+
+impl From<&BlobRecord> for ManifestEntry {
+    fn from(record: &BlobRecord) -> Self {
+        Self {
+            key: record.key.clone(),
+            size_bytes: record.size_bytes,
+            checksum: record.checksum.clone(),
+            updated_at: record.updated_at,
+            version: record.version,
+            etag: record.etag(),
+            version_vector: record.version_vector.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestResponse {
+    blobs: Vec<ManifestEntry>,
+    total_size_bytes: u64,
+    storage_limit_bytes: u64,
+}
+
+pub fn router(state: Arc<SyncState>) -> Router {
+    let body_limit = state.quotas.largest_blob() as usize;
+    Router::new()
+        .route("/manifest", get(get_manifest))
+        .route(
+            "/blobs/{*key}",
+            get(get_blob).put(put_blob).delete(delete_blob),
+        )
+        .layer(DefaultBodyLimit::max(body_limit))
+        .with_state(state)
+}
+
+async fn get_manifest(
+    State(state): State<Arc<SyncState>>,
+    headers: HeaderMap,
+) -> Result<Json<ManifestResponse>, ApiError> {
+    let claims = authorize(&headers, &state)?;
+    let records = state
+        .index
+        .lock()
+        .await
+        .list(&claims.sub)
+        .map_err(internal)?;
+
+    Ok(Json(ManifestResponse {
+        total_size_bytes: records.iter().map(|record| record.size_bytes).sum(),
+        storage_limit_bytes: state.quotas.for_tier(&claims.tier).storage_bytes,
+        blobs: records.iter().map(ManifestEntry::from).collect(),
+    }))
+}
+
+async fn get_blob(
+    State(state): State<Arc<SyncState>>,
+    Path(key): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let claims = authorize(&headers, &state)?;
+    validate_key(&key)?;
+
+    // A concurrent overwrite can delete the object between the index lookup
+    // and the read, so look the record up again once before giving up.
+    for _ in 0..2 {
+        let Some(record) = state
+            .index
+            .lock()
+            .await
+            .get(&claims.sub, &key)
+            .map_err(internal)?
+        else {
+            return Err(not_found(&key));
+        };
+
+        if header_str(&headers, header::IF_NONE_MATCH)
+            .is_some_and(|value| etag_matches(value, &record.etag()))
+        {
+            return Ok((StatusCode::NOT_MODIFIED, version_headers(&record)).into_response());
+        }
+
+        if let Some(data) = state.backend.get(&record.object).await.map_err(internal)? {
+            let mut headers = version_headers(&record);
+            headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/octet-stream"),
+            );
+            return Ok((headers, data).into_response());
+        }
+    }
+
+    error!(key, "sync index references a missing blob object");
+    Err((
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "blob data is unavailable".into(),
+    ))
+}
+
+async fn put_blob(
+    State(state): State<Arc<SyncState>>,
+    Path(key): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ApiError> {
+    let claims = authorize(&headers, &state)?;
+    validate_key(&key)?;
+
+    let quota = state.quotas.for_tier(&claims.tier);
+    let size_bytes = body.len() as u64;
+    if size_bytes > quota.max_blob_bytes {
+        return Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "blob is {size_bytes} bytes; the {} tier allows at most {} bytes per blob",
+                claims.tier, quota.max_blob_bytes
+            ),
+        ));
+    }
+    let checksum = hex::encode(Sha256::digest(&body));
+    let version_vector = parse_version_vector(&headers)?;
+
+    // Fail early, before uploading anything, if the write can't succeed.
+    {
+        let index = state.index.lock().await;
+        check_write(&index, &claims.sub, &key, &headers, size_bytes, quota)?;
+    }
+
+    // Every write gets its own object, so the upload needs no lock: a write
+    // that loses a race below only ever discards the object it uploaded.
+    let object = object_name(&claims.sub, &key);
+    state.backend.put(&object, &body).await.map_err(internal)?;
+
+    // Re-check under the lock so the precondition check, quota check and
+    // version bump are atomic with respect to other writers.
+    let index = state.index.lock().await;
+    let committed =
+        check_write(&index, &claims.sub, &key, &headers, size_bytes, quota).and_then(|existing| {
+            let version = existing.as_ref().map_or(1, |record| record.version + 1);
+            let record = BlobRecord {
+                object: object.clone(),
+                key,
+                size_bytes,
+                checksum,
+                version,
+                updated_at: Utc::now(),
+                version_vector,
+            };
+            index.upsert(&claims.sub, &record).map_err(internal)?;
+            Ok((record, existing))
+        });
+    drop(index);
+    let (record, existing) = match committed {
+        Ok(committed) => committed,
+        Err(err) => {
+            discard_object(&state, &object).await;
+            return Err(err);
+        }
+    };
+
+    if let Some(previous) = &existing {
+        discard_object(&state, &previous.object).await;
+    }
+
+    let status = if existing.is_some() {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+    Ok((
+        status,
+        version_headers(&record),
+        Json(ManifestEntry::from(&record)),
+    )
+        .into_response())
+}
+
+async fn delete_blob(
+    State(state): State<Arc<SyncState>>,
+    Path(key): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode, ApiError> {
+    let claims = authorize(&headers, &state)?;
+    validate_key(&key)?;
+
+    let index = state.index.lock().await;
+    let Some(record) = index.get(&claims.sub, &key).map_err(internal)? else {
+        return Err(not_found(&key));
+    };
+    check_preconditions(&headers, Some(&record))?;
+    index.remove(&claims.sub, &key).map_err(internal)?;
+    drop(index);
+
+    discard_object(&state, &record.object).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn authorize(headers: &HeaderMap, state: &SyncState) -> Result<Claims, ApiError> {
+    let secret = state.jwt_secret.as_deref().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "cloud sync is not configured".to_string(),
+    ))?;
+
+    let token = header_str(headers, header::AUTHORIZATION)
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or((StatusCode::UNAUTHORIZED, "missing bearer token".to_string()))?;
+
+    validate_jwt(token, secret).map_err(|_| {
+        (
+            StatusCode::UNAUTHORIZED,
+            "invalid or expired token".to_string(),
+        )
+    })
+}
+
+/// Keys are relative, slash-separated paths of `[A-Za-z0-9._-]` segments.
+fn validate_key(key: &str) -> Result<(), ApiError> {
+    let valid = !key.is_empty()
+        && key.len() <= MAX_KEY_LEN
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-'))
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err((StatusCode::BAD_REQUEST, format!("invalid blob key `{key}`")))
+    }
+}
+
+/// Read the optional version vector a client attached to a write.
+fn parse_version_vector(headers: &HeaderMap) -> Result<BTreeMap<String, u64>, ApiError> {
+    let Some(value) = headers.get(VERSION_VECTOR_HEADER) else {
+        return Ok(BTreeMap::new());
+    };
+    let invalid = |reason: &str| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("invalid {VERSION_VECTOR_HEADER} header: {reason}"),
+        )
+    };
+    let text = value.to_str().map_err(|_| invalid("not ASCII"))?;
+    let vector: BTreeMap<String, u64> =
+        serde_json::from_str(text).map_err(|_| invalid("expected a JSON object of counters"))?;
+    if vector.len() > MAX_VERSION_VECTOR_LEN {
+        return Err(invalid("too many devices"));
+    }
+    Ok(vector)
+}
+
+/// Check a write of `size_bytes` to `key` against the request's
+/// preconditions and the user's storage quota. Returns the record the write
+/// replaces.
+fn check_write(
+    index: &SyncIndex,
+    user_id: &str,
+    key: &str,
+    headers: &HeaderMap,
+    size_bytes: u64,
+    quota: TierQuota,
+) -> Result<Option<BlobRecord>, ApiError> {
+    let existing = index.get(user_id, key).map_err(internal)?;
+    check_preconditions(headers, existing.as_ref())?;
+
+    let usage = index.usage(user_id).map_err(internal)?;
+    let replaced = existing.as_ref().map_or(0, |record| record.size_bytes);
+    let new_usage = usage - replaced + size_bytes;
+    if new_usage > quota.storage_bytes {
+        return Err((
+            StatusCode::INSUFFICIENT_STORAGE,
+            format!(
+                "storage quota exceeded: {new_usage} of {} bytes",
+                quota.storage_bytes
+            ),
+        ));
+    }
+    Ok(existing)
+}
+
+/// Enforce `If-Match` / `If-None-Match` against the current version.
+fn check_preconditions(headers: &HeaderMap, current: Option<&BlobRecord>) -> Result<(), ApiError> {
+    let etag = current.map(BlobRecord::etag);
+
+    if let Some(expected) = header_str(headers, header::IF_MATCH) {
+        let matched = etag
+            .as_deref()
+            .is_some_and(|etag| etag_matches(expected, etag));
+        if !matched {
+            return Err(precondition_failed(etag.as_deref()));
+        }
+    }
+    let unexpected = header_str(headers, header::IF_NONE_MATCH);
+    if let (Some(unexpected), Some(etag)) = (unexpected, etag.as_deref())
+        && etag_matches(unexpected, etag)
+    {
+        return Err(precondition_failed(Some(etag)));
+    }
+    Ok(())
+}
+
+/// Whether a conditional header value (`*` or a list of tags) names `etag`.
+fn etag_matches(header_value: &str, etag: &str) -> bool {
+    header_value.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
+
+fn precondition_failed(current: Option<&str>) -> ApiError {
+    let message = match current {
+        Some(etag) => format!("blob has changed; current version is {etag}"),
+        None => "blob does not exist".to_string(),
+    };
+    (StatusCode::PRECONDITION_FAILED, message)
+}
+
+fn version_headers(record: &BlobRecord) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Ok(etag) = HeaderValue::from_str(&record.etag()) {
+        headers.insert(header::ETAG, etag);
+    }
+    headers.insert("x-hive-version", HeaderValue::from(record.version));
+    // Clients check this against the vector inside the encrypted blob.
+    if let Ok(vector) = serde_json::to_string(&record.version_vector)
+        && let Ok(value) = HeaderValue::from_str(&vector)
+    {
+        headers.insert(VERSION_VECTOR_HEADER, value);
+    }
+    headers
+}
+
+fn header_str(headers: &HeaderMap, name: header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// Backend object for one version of a user's blob. Hashing keeps user ids
+/// and keys out of storage paths.
+/// A fresh object name for one write of `key`.
+fn object_name(user_id: &str, key: &str) -> String {
+    format!(
+        "u/{}/{}.{}",
+        hex::encode(Sha256::digest(user_id.as_bytes())),
+        hex::encode(Sha256::digest(key.as_bytes())),
+        Uuid::new_v4().simple()
+    )
+}
+
+async fn discard_object(state: &SyncState, object: &str) {
+    if let Err(err) = state.backend.delete(object).await {
+        warn!(object, "failed to delete sync blob object: {err:#}");
+    }
+}
+
+fn not_found(key: &str) -> ApiError {
+    (StatusCode::NOT_FOUND, format!("blob `{key}` not found"))
+}
+
+fn internal(err: anyhow::Error) -> ApiError {
+    error!("sync request failed: {err:#}");
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "internal storage error".into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::create_jwt;
+    use hive_core::cloud_sync::{SyncAction, SyncClient, SyncError, SyncKind, SyncLedger};
+    use hive_core::kanban::{KanbanBoard, KanbanColumn, Priority};
+    use tokio::net::TcpListener;
+
+    const SECRET: &str = "sync-secret";
+    const PASSPHRASE: &str = "correct horse battery staple";
+
+    /// Bytes the client's encryption envelope adds to every blob.
+    const ENVELOPE_OVERHEAD: i64 = 5 + 12 + 16;
+
+    struct TestServer {
+        base: String,
+        _dir: tempfile::TempDir,
+    }
+
+    impl TestServer {
+        async fn start(quotas: QuotaPolicy, jwt_secret: Option<&str>) -> Self {
+            let dir = tempfile::tempdir().unwrap();
+            let state = SyncState::new(
+                SyncIndex::open(&dir.path().join("index.db")).unwrap(),
+                Arc::new(LocalBlobBackend::new(dir.path().join("blobs"))),
+                quotas,
+                jwt_secret.map(ToOwned::to_owned),
+            );
+            let app = Router::new().nest("/v1/sync", router(Arc::new(state)));
+            Self {
+                base: spawn(app).await,
+                _dir: dir,
+            }
+        }
+
+        fn client(&self, user_id: &str, tier: &str) -> SyncClient {
+            SyncClient::new(
+                self.base.clone(),
+                create_jwt(user_id, tier, SECRET).unwrap(),
+            )
+        }
+
+        async fn unlocked_client(&self, user_id: &str, tier: &str) -> SyncClient {
+            let mut client = self.client(user_id, tier);
+            client.unlock(PASSPHRASE).await.unwrap();
+            client
+        }
+
+        fn blob_url(&self, key: &str) -> String {
+            format!("{}/v1/sync/blobs/{key}", self.base)
+        }
+
+        /// Upload without the client's encryption, returning the status.
+        async fn put_raw(&self, user_id: &str, tier: &str, key: &str, body: Vec<u8>) -> u16 {
+            reqwest::Client::new()
+                .put(self.blob_url(key))
+                .bearer_auth(create_jwt(user_id, tier, SECRET).unwrap())
+                .body(body)
+                .send()
+                .await
+                .unwrap()
+                .status()
+                .as_u16()
+        }
+
+        async fn get_raw(&self, user_id: &str, key: &str) -> Vec<u8> {
+            reqwest::Client::new()
+                .get(self.blob_url(key))
+                .bearer_auth(create_jwt(user_id, "free", SECRET).unwrap())
+                .send()
+                .await
+                .unwrap()
+                .bytes()
+                .await
+                .unwrap()
+                .to_vec()
+        }
+    }
+
+    fn server_status(err: SyncError) -> u16 {
+        match err {
+            SyncError::Server { status, .. } => status,
+            other => panic!("expected a server error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn sync_client_round_trip() {
+        let server = TestServer::start(QuotaPolicy::default(), Some(SECRET)).await;
+        let client = server.unlocked_client("user-1", "pro").await;
+
+        client
+            .push("settings.json", b"{\"theme\":\"dark\"}")
+            .await
+            .unwrap();
+        client.push("conversations/a.json", b"hello").await.unwrap();
+        client
+            .push("settings.json", b"{\"theme\":\"light\"}")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            client.pull("settings.json").await.unwrap(),
+            b"{\"theme\":\"light\"}"
+        );
+
+        let manifest = client.manifest().await.unwrap();
+        let keys: Vec<&str> = manifest
+            .blobs
+            .iter()
+            .map(|entry| entry.key.as_str())
+            .collect();
+        assert_eq!(
+            keys,
+            vec!["_hive/keyinfo", "conversations/a.json", "settings.json"]
+        );
+        assert_eq!(
+            manifest.total_size_bytes,
+            manifest
+                .blobs
+                .iter()
+                .map(|entry| entry.size_bytes)
+                .sum::<i64>()
+        );
+        assert_eq!(manifest.blobs[2].size_bytes, 17 + ENVELOPE_OVERHEAD);
+        assert_eq!(
+            manifest.storage_limit_bytes as u64,
+            QuotaPolicy::default().pro.storage_bytes
+        );
+
+        // The server only ever sees ciphertext.
+        let stored = server.get_raw("user-1", "settings.json").await;
+        assert!(!stored.windows(5).any(|window| window == b"theme"));
+        assert_ne!(
+            manifest.blobs[2].checksum,
+            hex::encode(Sha256::digest(b"{\"theme\":\"light\"}"))
+        );
+
+        client.delete("settings.json").await.unwrap();
+        assert_eq!(
+            server_status(client.pull("settings.json").await.unwrap_err()),
+            404
+        );
+        assert_eq!(
+            server_status(client.delete("settings.json").await.unwrap_err()),
+            404
+        );
+    }
+
+    #[tokio::test]
+    async fn conditional_writes_detect_conflicts() {
+        let server = TestServer::start(QuotaPolicy::default(), Some(SECRET)).await;
+        let token = create_jwt("user-1", "free", SECRET).unwrap();
+        let http = reqwest::Client::new();
+        let url = server.blob_url("notes.md");
+
+        let created = http
+            .put(&url)
+            .bearer_auth(&token)
+            .header("If-None-Match", "*")
+            .body("v1")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(created.status(), 201);
+        let v1 = created.headers()["etag"].to_str().unwrap().to_string();
+
+        // A second create-only write loses to the first.
+        let duplicate = http
+            .put(&url)
+            .bearer_auth(&token)
+            .header("If-None-Match", "*")
+            .body("other")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(duplicate.status(), 412);
+
+        let updated = http
+            .put(&url)
+            .bearer_auth(&token)
+            .header("If-Match", &v1)
+            .header("X-Hive-Version-Vector", r#"{"laptop":2}"#)
+            .body("v2")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(updated.status(), 200);
+        assert_eq!(updated.headers()["x-hive-version"], "2");
+        let v2 = updated.headers()["etag"].to_str().unwrap().to_string();
+        assert_ne!(v1, v2);
+
+        // Another device still holding v1 must not overwrite v2.
+        let stale = http
+            .put(&url)
+            .bearer_auth(&token)
+            .header("If-Match", &v1)
+            .body("stale")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(stale.status(), 412);
+
+        let not_modified = http
+            .get(&url)
+            .bearer_auth(&token)
+            .header("If-None-Match", &v2)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(not_modified.status(), 304);
+
+        let stale_delete = http
+            .delete(&url)
+            .bearer_auth(&token)
+            .header("If-Match", &v1)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(stale_delete.status(), 412);
+
+        assert_eq!(server.get_raw("user-1", "notes.md").await, b"v2");
+        let manifest = server.client("user-1", "free").manifest().await.unwrap();
+        assert_eq!(manifest.blobs[0].version, 2);
+        assert_eq!(manifest.blobs[0].version_vector.get("laptop"), 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_version_vectors() {
+        let server = TestServer::start(QuotaPolicy::default(), Some(SECRET)).await;
+        let response = reqwest::Client::new()
+            .put(server.blob_url("notes.md"))
+            .bearer_auth(create_jwt("user-1", "free", SECRET).unwrap())
+            .header("X-Hive-Version-Vector", r#"{"laptop":-1}"#)
+            .body("v1")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn quotas_follow_the_token_tier() {
+        let quota = TierQuota {
+            storage_bytes: 10,
+            max_blob_bytes: 8,
+        };
+        let quotas = QuotaPolicy {
+            free: quota,
+            pro: TierQuota {
+                storage_bytes: 100,
+                max_blob_bytes: 50,
+            },
+            team: quota,
+        };
+        let server = TestServer::start(quotas, Some(SECRET)).await;
+
+        assert_eq!(
+            server.put_raw("user-1", "free", "big", vec![0; 9]).await,
+            413
+        );
+
+        assert_eq!(server.put_raw("user-1", "free", "a", vec![0; 6]).await, 201);
+        assert_eq!(server.put_raw("user-1", "free", "b", vec![0; 6]).await, 507);
+        // Replacing a blob only counts the difference in size.
+        assert_eq!(server.put_raw("user-1", "free", "a", vec![0; 8]).await, 200);
+        let free = server.client("user-1", "free");
+        assert_eq!(free.manifest().await.unwrap().total_size_bytes, 8);
+
+        assert_eq!(
+            server.put_raw("user-2", "pro", "big", vec![0; 40]).await,
+            201
+        );
+    }
+
+    #[tokio::test]
+    async fn users_cannot_see_each_others_blobs() {
+        let server = TestServer::start(QuotaPolicy::default(), Some(SECRET)).await;
+        let alice = server.unlocked_client("alice", "free").await;
+        let bob = server.unlocked_client("bob", "free").await;
+
+        alice.push("settings.json", b"alice").await.unwrap();
+        bob.push("settings.json", b"bob").await.unwrap();
+
+        assert_eq!(alice.pull("settings.json").await.unwrap(), b"alice");
+        assert_eq!(bob.pull("settings.json").await.unwrap(), b"bob");
+
+        bob.delete("settings.json").await.unwrap();
+        assert_eq!(alice.pull("settings.json").await.unwrap(), b"alice");
+        let keys: Vec<String> = bob
+            .manifest()
+            .await
+            .unwrap()
+            .blobs
+            .into_iter()
+            .map(|entry| entry.key)
+            .collect();
+        assert_eq!(keys, vec!["_hive/keyinfo"]);
+    }
+
+    #[tokio::test]
+    async fn encrypted_sync_merges_edits_from_two_devices() {
+        let server = TestServer::start(QuotaPolicy::default(), Some(SECRET)).await;
+        let laptop = server.unlocked_client("user-1", "pro").await;
+        let desktop = server.unlocked_client("user-1", "pro").await;
+        let mut laptop_ledger = SyncLedger::new("laptop");
+        let mut desktop_ledger = SyncLedger::new("desktop");
+
+        let mut intruder = server.client("user-1", "pro");
+        assert!(matches!(
+            intruder.unlock("not the passphrase").await,
+            Err(SyncError::WrongPassphrase)
+        ));
+
+        let mut board = KanbanBoard::new();
+        let docs = board.add_task("Write sync docs", None, Priority::High);
+        let outcome = laptop
+            .sync(
+                "kanban.json",
+                SyncKind::Kanban,
+                &serde_json::to_vec(&board).unwrap(),
+                &mut laptop_ledger,
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.action, SyncAction::Uploaded);
+        let stored = server.get_raw("user-1", "kanban.json").await;
+        assert!(!stored.windows(10).any(|window| window == b"Write sync"));
+
+        // A fresh device adopts the cloud board instead of its empty one.
+        let outcome = desktop
+            .sync(
+                "kanban.json",
+                SyncKind::Kanban,
+                &serde_json::to_vec(&KanbanBoard::new()).unwrap(),
+                &mut desktop_ledger,
+            )
+            .await
+            .unwrap();
+        let mut desktop_board: KanbanBoard = serde_json::from_slice(&outcome.data).unwrap();
+        assert!(desktop_board.get_task(&docs.id).is_some());
+
+        // Both devices edit concurrently.
+        let review = board.add_task("Review PR", None, Priority::Low);
+        desktop_board
+            .move_task(&docs.id, KanbanColumn::InProgress)
+            .unwrap();
+
+        let outcome = laptop
+            .sync(
+                "kanban.json",
+                SyncKind::Kanban,
+                &serde_json::to_vec(&board).unwrap(),
+                &mut laptop_ledger,
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.action, SyncAction::Uploaded);
+
+        let outcome = desktop
+            .sync(
+                "kanban.json",
+                SyncKind::Kanban,
+                &serde_json::to_vec(&desktop_board).unwrap(),
+                &mut desktop_ledger,
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.action, SyncAction::Merged);
+        assert!(outcome.conflicts.is_empty());
+        let merged: KanbanBoard = serde_json::from_slice(&outcome.data).unwrap();
+        assert!(merged.get_task(&review.id).is_some());
+        assert_eq!(
+            merged.get_task(&docs.id).unwrap().column,
+            KanbanColumn::InProgress
+        );
+
+        let outcome = laptop
+            .sync(
+                "kanban.json",
+                SyncKind::Kanban,
+                &serde_json::to_vec(&board).unwrap(),
+                &mut laptop_ledger,
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.action, SyncAction::Downloaded);
+        let board: KanbanBoard = serde_json::from_slice(&outcome.data).unwrap();
+        assert_eq!(
+            board.get_task(&docs.id).unwrap().column,
+            KanbanColumn::InProgress
+        );
+
+        let manifest = laptop.manifest().await.unwrap();
+        assert!(laptop_ledger.stale_keys(&manifest).is_empty());
+        let entry = manifest
+            .blobs
+            .iter()
+            .find(|entry| entry.key == "kanban.json")
+            .unwrap();
+        assert_eq!(entry.version_vector.get("laptop"), 2);
+        assert_eq!(entry.version_vector.get("desktop"), 1);
+    }
+
+    #[tokio::test]
+    async fn sync_rejects_a_version_vector_the_server_rewrote() {
+        let server = TestServer::start(QuotaPolicy::default(), Some(SECRET)).await;
+        let laptop = server.unlocked_client("user-1", "pro").await;
+        let mut ledger = SyncLedger::new("laptop");
+        laptop
+            .sync("notes.json", SyncKind::Config, b"{\"a\":1}", &mut ledger)
+            .await
+            .unwrap();
+
+        // Re-store the same ciphertext under a forged vector, as a
+        // compromised server could.
+        let ciphertext = server.get_raw("user-1", "notes.json").await;
+        let status = reqwest::Client::new()
+            .put(server.blob_url("notes.json"))
+            .bearer_auth(create_jwt("user-1", "pro", SECRET).unwrap())
+            .header(VERSION_VECTOR_HEADER, r#"{"laptop":1,"desktop":9}"#)
+            .body(ciphertext)
+            .send()
+            .await
+            .unwrap()
+            .status();
+        assert_eq!(status, 200);
+
+        let desktop = server.unlocked_client("user-1", "pro").await;
+        let err = desktop
+            .sync(
+                "notes.json",
+                SyncKind::Config,
+                b"{}",
+                &mut SyncLedger::new("desktop"),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SyncError::InvalidData(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_tokens_and_unconfigured_secret() {
+        let server = TestServer::start(QuotaPolicy::default(), Some(SECRET)).await;
+        let forged = SyncClient::new(
+            server.base.clone(),
+            create_jwt("user-1", "pro", "wrong-secret").unwrap(),
+        );
+        assert_eq!(server_status(forged.manifest().await.unwrap_err()), 401);
+
+        let missing = reqwest::get(server.blob_url("settings.json"))
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), 401);
+
+        let unconfigured = TestServer::start(QuotaPolicy::default(), None).await;
+        let client = unconfigured.client("user-1", "pro");
+        assert_eq!(server_status(client.manifest().await.unwrap_err()), 503);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_keys() {
+        let server = TestServer::start(QuotaPolicy::default(), Some(SECRET)).await;
+        let client = server.unlocked_client("user-1", "free").await;
+
+        for key in ["a//b", "a/..%2Fb", "spaces%20here"] {
+            let err = client.push(key, b"x").await.unwrap_err();
+            assert_eq!(server_status(err), 400, "key {key}");
+        }
+    }
+
+    /// Local storage whose uploads wait until the test lets them through.
+    struct GatedBackend {
+        inner: LocalBlobBackend,
+        started: tokio::sync::mpsc::UnboundedSender<()>,
+        release: tokio::sync::Semaphore,
+    }
+
+    #[async_trait::async_trait]
+    impl BlobBackend for GatedBackend {
+        async fn put(&self, object: &str, data: &[u8]) -> anyhow::Result<()> {
+            let _ = self.started.send(());
+            self.release.acquire().await?.forget();
+            self.inner.put(object, data).await
+        }
+
+        async fn get(&self, object: &str) -> anyhow::Result<Option<Vec<u8>>> {
+            self.inner.get(object).await
+        }
+
+        async fn delete(&self, object: &str) -> anyhow::Result<()> {
+            self.inner.delete(object).await
+        }
+    }
+
+    fn count_files(dir: &std::path::Path) -> usize {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .map(|path| if path.is_dir() { count_files(&path) } else { 1 })
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn uploads_run_outside_the_index_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let (started_tx, mut started) = tokio::sync::mpsc::unbounded_channel();
+        let backend = Arc::new(GatedBackend {
+            inner: LocalBlobBackend::new(dir.path().join("blobs")),
+            started: started_tx,
+            release: tokio::sync::Semaphore::new(0),
+        });
+        let state = SyncState::new(
+            SyncIndex::open(&dir.path().join("index.db")).unwrap(),
+            backend.clone(),
+            QuotaPolicy::default(),
+            Some(SECRET.into()),
+        );
+        let base = spawn(Router::new().nest("/v1/sync", router(Arc::new(state)))).await;
+        let token = create_jwt("user-1", "pro", SECRET).unwrap();
+        let create = |body: &'static [u8]| {
+            reqwest::Client::new()
+                .put(format!("{base}/v1/sync/blobs/notes.md"))
+                .bearer_auth(&token)
+                .header("if-none-match", "*")
+                .body(body)
+                .send()
+        };
+
+        let first = tokio::spawn(create(b"first"));
+        let second = tokio::spawn(create(b"second"));
+        started.recv().await.unwrap();
+        started.recv().await.unwrap();
+
+        // Both uploads are in flight, yet the index is free.
+        let client = SyncClient::new(base.clone(), token.clone());
+        let manifest = tokio::time::timeout(std::time::Duration::from_secs(5), client.manifest())
+            .await
+            .expect("manifest blocked behind an upload")
+            .unwrap();
+        assert!(manifest.blobs.is_empty());
+
+        backend.release.add_permits(2);
+        let mut statuses = vec![
+            first.await.unwrap().unwrap().status().as_u16(),
+            second.await.unwrap().unwrap().status().as_u16(),
+        ];
+        statuses.sort_unstable();
+        assert_eq!(statuses, vec![201, 412]);
+
+        // The losing write removed its own object and left the winner's.
+        assert_eq!(count_files(&dir.path().join("blobs")), 1);
+        assert_eq!(client.manifest().await.unwrap().blobs.len(), 1);
+    }
+
+    #[test]
+    fn etag_matching_handles_lists_wildcards_and_weak_tags() {
+        assert!(etag_matches("*", "\"1-abc\""));
+        assert!(etag_matches("\"2-def\", \"1-abc\"", "\"1-abc\""));
+        assert!(etag_matches("W/\"1-abc\"", "\"1-abc\""));
+        assert!(!etag_matches("\"2-abc\"", "\"1-abc\""));
+    }
+
+    async fn spawn(router: Router) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+        format!("http://{addr}")
+    }
+}
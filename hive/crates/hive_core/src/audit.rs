@@ -0,0 +1,858 @@
+//! Tamper-evident, append-only audit log.
+//!
+//! Every record carries the SHA-256 hash of the record before it, so editing,
+//! deleting or reordering any line breaks the chain from that point on. When
+//! [`AuditKeys`] are supplied (derived from [`SecureStorage`]) each record is
+//! additionally HMAC'd, which stops an attacker with disk access from simply
+//! recomputing the chain, and the log periodically writes ed25519-signed
+//! checkpoints so truncating the tail is detectable too.
+//!
+//! On disk the log is two JSON Lines files inside one directory:
+//!
+//! - `audit.jsonl` — one [`AuditRecord`] per line
+//! - `checkpoints.jsonl` — one [`AuditCheckpoint`] per line
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use hmac::{Hmac, Mac};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use crate::secure_storage::SecureStorage;
+
+const LOG_FILENAME: &str = "audit.jsonl";
+const CHECKPOINT_FILENAME: &str = "checkpoints.jsonl";
+
+/// `prev_hash` of the first record in a log.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Default number of records between automatic checkpoints.
+const DEFAULT_CHECKPOINT_INTERVAL: u64 = 100;
+
+/// Subkey purposes passed to [`SecureStorage::derive_subkey`].
+const MAC_KEY_PURPOSE: &str = "hive-audit-mac-v1";
+const SIGNING_KEY_PURPOSE: &str = "hive-audit-checkpoint-v1";
+
+type HmacSha256 = Hmac<Sha256>;
+
+// ---------------------------------------------------------------------------
+// Data types
+// ---------------------------------------------------------------------------
+
+/// Broad class of an audited event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditCategory {
+    /// An approval was requested, granted, denied or timed out.
+    Approval,
+    /// An agent executed a tool, shell command or file operation.
+    ToolExecution,
+    /// An API key or token was set, rotated or removed.
+    KeyChange,
+    /// An approval or security policy was created or edited.
+    PolicyChange,
+    /// Enterprise team / membership / configuration events.
+    Enterprise,
+    /// Any other security-relevant event.
+    Security,
+}
+
+/// An event to append to the audit log. Hash-chain fields are filled in by
+/// [`AuditLog::append`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEvent {
+    pub category: AuditCategory,
+    pub action: String,
+    pub actor: String,
+    pub resource: String,
+    pub details: Option<serde_json::Value>,
+}
+
+impl AuditEvent {
+    /// Create an event with the given category and action name
+    /// (e.g. `"approval_granted"`).
+    pub fn new(category: AuditCategory, action: impl Into<String>) -> Self {
+        Self {
+            category,
+            action: action.into(),
+            actor: String::new(),
+            resource: String::new(),
+            details: None,
+        }
+    }
+
+    /// Who performed the action (user, agent id, device id).
+    pub fn actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = actor.into();
+        self
+    }
+
+    /// What the action was performed on (path, provider, request id).
+    pub fn resource(mut self, resource: impl Into<String>) -> Self {
+        self.resource = resource.into();
+        self
+    }
+
+    /// Structured details. Never put secrets here.
+    pub fn details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+/// A single hash-chained record as stored on disk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Zero-based position in the log; gaps mean deleted records.
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
+    pub category: AuditCategory,
+    pub action: String,
+    pub actor: String,
+    pub resource: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+    /// Hash of the previous record ([`GENESIS_HASH`] for the first).
+    pub prev_hash: String,
+    /// SHA-256 over this record's content and `prev_hash`, hex-encoded.
+    pub hash: String,
+    /// HMAC-SHA256 of `hash` under the audit MAC key, when keys are configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mac: Option<String>,
+}
+
+/// The fields covered by [`AuditRecord::hash`], in a fixed order.
+#[derive(Serialize)]
+struct HashInput<'a> {
+    seq: u64,
+    timestamp: &'a DateTime<Utc>,
+    category: AuditCategory,
+    action: &'a str,
+    actor: &'a str,
+    resource: &'a str,
+    details: &'a Option<serde_json::Value>,
+    prev_hash: &'a str,
+}
+
+impl AuditRecord {
+    /// Recompute the content hash of this record.
+    pub fn compute_hash(&self) -> String {
+        let input = HashInput {
+            seq: self.seq,
+            timestamp: &self.timestamp,
+            category: self.category,
+            action: &self.action,
+            actor: &self.actor,
+            resource: &self.resource,
+            details: &self.details,
+            prev_hash: &self.prev_hash,
+        };
+        let bytes = serde_json::to_vec(&input).expect("audit record serializes");
+        hex::encode(Sha256::digest(&bytes))
+    }
+}
+
+/// A signed statement that the log contained `seq` records ending in `hash`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditCheckpoint {
+    /// Sequence number of the last record covered.
+    pub seq: u64,
+    /// Hash of the record at `seq`.
+    pub hash: String,
+    pub timestamp: DateTime<Utc>,
+    /// Hex-encoded ed25519 public key that produced `signature`.
+    pub public_key: String,
+    /// Hex-encoded ed25519 signature over [`AuditCheckpoint::signed_message`].
+    pub signature: String,
+}
+
+impl AuditCheckpoint {
+    fn signed_message(seq: u64, hash: &str, timestamp: &DateTime<Utc>) -> Vec<u8> {
+        format!("hive-audit-checkpoint:{seq}:{hash}:{}", timestamp.to_rfc3339()).into_bytes()
+    }
+}
+
+/// Secret material used to MAC records and sign checkpoints.
+#[derive(Clone)]
+pub struct AuditKeys {
+    mac_key: [u8; 32],
+    signing_key: SigningKey,
+}
+
+impl std::fmt::Debug for AuditKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditKeys")
+            .field("public_key", &self.public_key_hex())
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuditKeys {
+    /// Derive audit keys from the user's secure storage vault.
+    pub fn from_secure_storage(storage: &SecureStorage) -> Self {
+        Self::from_secrets(
+            storage.derive_subkey(MAC_KEY_PURPOSE),
+            storage.derive_subkey(SIGNING_KEY_PURPOSE),
+        )
+    }
+
+    /// Build keys from explicit 32-byte secrets (tests, server deployments).
+    pub fn from_secrets(mac_key: [u8; 32], signing_seed: [u8; 32]) -> Self {
+        Self {
+            mac_key,
+            signing_key: SigningKey::from_bytes(&signing_seed),
+        }
+    }
+
+    /// Hex-encoded checkpoint verification key, for publishing to reviewers.
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.signing_key.verifying_key().as_bytes())
+    }
+
+    fn mac(&self, hash: &str) -> String {
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(&self.mac_key)
+            .expect("HMAC accepts any key length");
+        mac.update(hash.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn verify_mac(&self, hash: &str, mac_hex: &str) -> bool {
+        let Ok(expected) = hex::decode(mac_hex) else {
+            return false;
+        };
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(&self.mac_key)
+            .expect("HMAC accepts any key length");
+        mac.update(hash.as_bytes());
+        mac.verify_slice(&expected).is_ok()
+    }
+}
+
+/// Where and why verification failed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TamperReport {
+    /// Sequence number of the first record that fails verification, if the
+    /// failure can be attributed to one.
+    pub seq: Option<u64>,
+    /// 1-based line number in `audit.jsonl` (0 when not line-specific).
+    pub line: usize,
+    pub reason: String,
+}
+
+/// Result of [`AuditLog::verify`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditVerification {
+    pub records_checked: usize,
+    pub checkpoints_checked: usize,
+    /// Whether record MACs and checkpoint keys were checked.
+    pub authenticated: bool,
+    /// The first problem found, or `None` if the log is intact.
+    pub first_tampered: Option<TamperReport>,
+}
+
+impl AuditVerification {
+    /// `true` when no tampering was detected.
+    pub fn is_intact(&self) -> bool {
+        self.first_tampered.is_none()
+    }
+}
+
+/// Self-contained export for compliance review.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditExport {
+    pub exported_at: DateTime<Utc>,
+    /// Checkpoint verification key, when the log is keyed.
+    pub public_key: Option<String>,
+    pub verification: AuditVerification,
+    pub records: Vec<AuditRecord>,
+    pub checkpoints: Vec<AuditCheckpoint>,
+}
+
+// ---------------------------------------------------------------------------
+// AuditLog
+// ---------------------------------------------------------------------------
+
+struct ChainState {
+    file: File,
+    next_seq: u64,
+    last_hash: String,
+    since_checkpoint: u64,
+}
+
+/// Append-only, hash-chained audit log. Safe to share behind an `Arc`.
+pub struct AuditLog {
+    dir: PathBuf,
+    keys: Option<AuditKeys>,
+    checkpoint_interval: u64,
+    state: Mutex<ChainState>,
+}
+
+impl std::fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("dir", &self.dir)
+            .field("keyed", &self.keys.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuditLog {
+    /// Open (or create) the audit log in `dir`.
+    ///
+    /// Opening never fails because of tampering — it resumes the chain from
+    /// the last readable record. Call [`AuditLog::verify`] to check integrity.
+    pub fn open(dir: &Path, keys: Option<AuditKeys>) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create audit dir {}", dir.display()))?;
+
+        let log_path = dir.join(LOG_FILENAME);
+        let (next_seq, last_hash) = read_records(&log_path)?
+            .into_iter()
+            .rev()
+            .find_map(|(_, r)| r.ok())
+            .map(|r| (r.seq + 1, r.hash))
+            .unwrap_or_else(|| (0, GENESIS_HASH.to_string()));
+
+        let checkpointed = read_checkpoints(&dir.join(CHECKPOINT_FILENAME))?
+            .last()
+            .map(|c| c.seq + 1)
+            .unwrap_or(0);
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .with_context(|| format!("Failed to open {}", log_path.display()))?;
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            keys,
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+            state: Mutex::new(ChainState {
+                file,
+                next_seq,
+                last_hash,
+                since_checkpoint: next_seq.saturating_sub(checkpointed),
+            }),
+        })
+    }
+
+    /// Set how many records are appended between automatic checkpoints.
+    /// `0` disables automatic checkpoints. Only effective with keys.
+    pub fn with_checkpoint_interval(mut self, interval: u64) -> Self {
+        self.checkpoint_interval = interval;
+        self
+    }
+
+    /// Directory holding the log files.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Number of records appended so far.
+    pub fn len(&self) -> u64 {
+        self.state.lock().next_seq
+    }
+
+    /// Whether the log has no records.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append an event, returning the stored record.
+    pub fn append(&self, event: AuditEvent) -> Result<AuditRecord> {
+        let mut state = self.state.lock();
+
+        let mut record = AuditRecord {
+            seq: state.next_seq,
+            timestamp: Utc::now(),
+            category: event.category,
+            action: event.action,
+            actor: event.actor,
+            resource: event.resource,
+            details: event.details,
+            prev_hash: state.last_hash.clone(),
+            hash: String::new(),
+            mac: None,
+        };
+        record.hash = record.compute_hash();
+        record.mac = self.keys.as_ref().map(|k| k.mac(&record.hash));
+
+        let mut line = serde_json::to_string(&record).context("Failed to encode audit record")?;
+        line.push('\n');
+        state
+            .file
+            .write_all(line.as_bytes())
+            .context("Failed to append audit record")?;
+        state.file.flush()?;
+
+        state.next_seq += 1;
+        state.last_hash = record.hash.clone();
+        state.since_checkpoint += 1;
+
+        debug!(
+            "Audit #{}: {:?} {} by {}",
+            record.seq, record.category, record.action, record.actor
+        );
+
+        if self.keys.is_some()
+            && self.checkpoint_interval > 0
+            && state.since_checkpoint >= self.checkpoint_interval
+        {
+            self.write_checkpoint(&mut state)?;
+        }
+
+        Ok(record)
+    }
+
+    /// Write a signed checkpoint for the current head. Returns `None` when
+    /// the log is unkeyed or empty.
+    pub fn checkpoint(&self) -> Result<Option<AuditCheckpoint>> {
+        let mut state = self.state.lock();
+        self.write_checkpoint(&mut state)
+    }
+
+    fn write_checkpoint(&self, state: &mut ChainState) -> Result<Option<AuditCheckpoint>> {
+        let Some(keys) = &self.keys else {
+            return Ok(None);
+        };
+        if state.next_seq == 0 {
+            return Ok(None);
+        }
+
+        // Make sure the records the checkpoint vouches for are on disk first.
+        state.file.sync_data().context("Failed to sync audit log")?;
+
+        let seq = state.next_seq - 1;
+        let timestamp = Utc::now();
+        let message = AuditCheckpoint::signed_message(seq, &state.last_hash, &timestamp);
+        let checkpoint = AuditCheckpoint {
+            seq,
+            hash: state.last_hash.clone(),
+            timestamp,
+            public_key: keys.public_key_hex(),
+            signature: hex::encode(keys.signing_key.sign(&message).to_bytes()),
+        };
+
+        let path = self.dir.join(CHECKPOINT_FILENAME);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut line = serde_json::to_string(&checkpoint)?;
+        line.push('\n');
+        file.write_all(line.as_bytes())?;
+        file.sync_data()?;
+
+        state.since_checkpoint = 0;
+        debug!("Audit checkpoint at #{seq}");
+        Ok(Some(checkpoint))
+    }
+
+    /// Read every parseable record, in order.
+    pub fn records(&self) -> Result<Vec<AuditRecord>> {
+        let _guard = self.state.lock();
+        Ok(read_records(&self.dir.join(LOG_FILENAME))?
+            .into_iter()
+            .filter_map(|(_, r)| r.ok())
+            .collect())
+    }
+
+    /// Read every checkpoint, in order.
+    pub fn checkpoints(&self) -> Result<Vec<AuditCheckpoint>> {
+        read_checkpoints(&self.dir.join(CHECKPOINT_FILENAME))
+    }
+
+    /// Walk the whole chain and report the first record that was modified,
+    /// deleted, reordered or forged, plus any checkpoint that no longer
+    /// matches (which catches truncation of the tail).
+    pub fn verify(&self) -> Result<AuditVerification> {
+        let _guard = self.state.lock();
+        let lines = read_records(&self.dir.join(LOG_FILENAME))?;
+        let checkpoints = read_checkpoints(&self.dir.join(CHECKPOINT_FILENAME))?;
+        Ok(verify_chain(&lines, &checkpoints, self.keys.as_ref()))
+    }
+
+    /// Verify the log and write a self-contained JSON export to `path`.
+    pub fn export(&self, path: &Path) -> Result<AuditVerification> {
+        let verification = self.verify()?;
+        let export = AuditExport {
+            exported_at: Utc::now(),
+            public_key: self.keys.as_ref().map(AuditKeys::public_key_hex),
+            verification: verification.clone(),
+            records: self.records()?,
+            checkpoints: self.checkpoints()?,
+        };
+        let json = serde_json::to_string_pretty(&export)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write audit export {}", path.display()))?;
+        Ok(verification)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Reading and verification
+// ---------------------------------------------------------------------------
+
+type RecordLine = (usize, std::result::Result<AuditRecord, String>);
+
+fn read_records(path: &Path) -> Result<Vec<RecordLine>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut out = Vec::new();
+    for (idx, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        out.push((
+            idx + 1,
+            serde_json::from_str::<AuditRecord>(&line).map_err(|e| e.to_string()),
+        ));
+    }
+    Ok(out)
+}
+
+fn read_checkpoints(path: &Path) -> Result<Vec<AuditCheckpoint>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut out = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(c) => out.push(c),
+            Err(e) => warn!("Skipping unreadable audit checkpoint: {e}"),
+        }
+    }
+    Ok(out)
+}
+
+fn verify_chain(
+    lines: &[RecordLine],
+    checkpoints: &[AuditCheckpoint],
+    keys: Option<&AuditKeys>,
+) -> AuditVerification {
+    let mut result = AuditVerification {
+        records_checked: 0,
+        checkpoints_checked: 0,
+        authenticated: keys.is_some(),
+        first_tampered: None,
+    };
+    let tampered = |seq: Option<u64>, line: usize, reason: String| {
+        Some(TamperReport { seq, line, reason })
+    };
+
+    let mut hashes: Vec<String> = Vec::with_capacity(lines.len());
+    let mut prev_hash = GENESIS_HASH.to_string();
+
+    for (expected_seq, (line, parsed)) in lines.iter().enumerate() {
+        let expected_seq = expected_seq as u64;
+        let record = match parsed {
+            Ok(r) => r,
+            Err(e) => {
+                result.first_tampered =
+                    tampered(Some(expected_seq), *line, format!("unreadable record: {e}"));
+                return result;
+            }
+        };
+
+        let problem = if record.seq != expected_seq {
+            Some(format!(
+                "expected record #{expected_seq}, found #{} (records deleted or reordered)",
+                record.seq
+            ))
+        } else if record.prev_hash != prev_hash {
+            Some("previous-hash link does not match the preceding record".to_string())
+        } else if record.compute_hash() != record.hash {
+            Some("record contents do not match its hash".to_string())
+        } else if let Some(keys) = keys {
+            match &record.mac {
+                Some(mac) if keys.verify_mac(&record.hash, mac) => None,
+                Some(_) => Some("record MAC does not verify".to_string()),
+                None => Some("record is missing its MAC".to_string()),
+            }
+        } else {
+            None
+        };
+
+        if let Some(reason) = problem {
+            result.first_tampered = tampered(Some(expected_seq), *line, reason);
+            return result;
+        }
+
+        prev_hash = record.hash.clone();
+        hashes.push(record.hash.clone());
+        result.records_checked += 1;
+    }
+
+    let trusted_key = keys.map(AuditKeys::public_key_hex);
+    for checkpoint in checkpoints {
+        let signature_ok = (|| {
+            if let Some(trusted) = &trusted_key
+                && &checkpoint.public_key != trusted
+            {
+                return false;
+            }
+            let key_bytes: [u8; 32] = match hex::decode(&checkpoint.public_key)
+                .ok()
+                .and_then(|b| b.try_into().ok())
+            {
+                Some(b) => b,
+                None => return false,
+            };
+            let sig_bytes: [u8; 64] = match hex::decode(&checkpoint.signature)
+                .ok()
+                .and_then(|b| b.try_into().ok())
+            {
+                Some(b) => b,
+                None => return false,
+            };
+            let Ok(key) = VerifyingKey::from_bytes(&key_bytes) else {
+                return false;
+            };
+            let message = AuditCheckpoint::signed_message(
+                checkpoint.seq,
+                &checkpoint.hash,
+                &checkpoint.timestamp,
+            );
+            key.verify(&message, &Signature::from_bytes(&sig_bytes))
+                .is_ok()
+        })();
+
+        if !signature_ok {
+            result.first_tampered = tampered(
+                None,
+                0,
+                format!("checkpoint at #{} has an invalid signature", checkpoint.seq),
+            );
+            return result;
+        }
+
+        match hashes.get(checkpoint.seq as usize) {
+            Some(hash) if *hash == checkpoint.hash => {}
+            Some(_) => {
+                result.first_tampered = tampered(
+                    Some(checkpoint.seq),
+                    lines[checkpoint.seq as usize].0,
+                    "record does not match its signed checkpoint".to_string(),
+                );
+                return result;
+            }
+            None => {
+                result.first_tampered = tampered(
+                    Some(hashes.len() as u64),
+                    0,
+                    format!(
+                        "log ends at {} records but a signed checkpoint covers #{} (tail truncated)",
+                        hashes.len(),
+                        checkpoint.seq
+                    ),
+                );
+                return result;
+            }
+        }
+        result.checkpoints_checked += 1;
+    }
+
+    result
+}
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn keys() -> AuditKeys {
+        AuditKeys::from_secrets([1u8; 32], [2u8; 32])
+    }
+
+    fn event(n: usize) -> AuditEvent {
+        AuditEvent::new(AuditCategory::ToolExecution, "tool_called")
+            .actor("agent-1")
+            .resource(format!("file-{n}.rs"))
+            .details(serde_json::json!({ "n": n }))
+    }
+
+    fn log_with(dir: &Path, n: usize, keys: Option<AuditKeys>) -> AuditLog {
+        let log = AuditLog::open(dir, keys).unwrap();
+        for i in 0..n {
+            log.append(event(i)).unwrap();
+        }
+        log
+    }
+
+    fn rewrite_lines(dir: &Path, f: impl FnOnce(&mut Vec<String>)) {
+        let path = dir.join(LOG_FILENAME);
+        let mut lines: Vec<String> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+        f(&mut lines);
+        std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+    }
+
+    #[test]
+    fn test_append_chains_records() {
+        let tmp = TempDir::new().unwrap();
+        let log = log_with(tmp.path(), 3, None);
+
+        let records = log.records().unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].prev_hash, GENESIS_HASH);
+        assert_eq!(records[1].prev_hash, records[0].hash);
+        assert_eq!(records[2].prev_hash, records[1].hash);
+        assert!(records.iter().all(|r| r.mac.is_none()));
+        assert!(log.verify().unwrap().is_intact());
+    }
+
+    #[test]
+    fn test_reopen_continues_chain() {
+        let tmp = TempDir::new().unwrap();
+        let last = log_with(tmp.path(), 2, None).records().unwrap()[1].clone();
+
+        let log = AuditLog::open(tmp.path(), None).unwrap();
+        assert_eq!(log.len(), 2);
+        let next = log.append(event(2)).unwrap();
+        assert_eq!(next.seq, 2);
+        assert_eq!(next.prev_hash, last.hash);
+        assert!(log.verify().unwrap().is_intact());
+    }
+
+    #[test]
+    fn test_verify_pinpoints_modified_record() {
+        let tmp = TempDir::new().unwrap();
+        let log = log_with(tmp.path(), 5, None);
+
+        rewrite_lines(tmp.path(), |lines| {
+            lines[2] = lines[2].replace("file-2.rs", "innocent.rs");
+        });
+
+        let report = log.verify().unwrap().first_tampered.unwrap();
+        assert_eq!(report.seq, Some(2));
+        assert_eq!(report.line, 3);
+        assert!(report.reason.contains("hash"));
+    }
+
+    #[test]
+    fn test_verify_detects_deleted_record() {
+        let tmp = TempDir::new().unwrap();
+        let log = log_with(tmp.path(), 5, None);
+
+        rewrite_lines(tmp.path(), |lines| {
+            lines.remove(1);
+        });
+
+        let verification = log.verify().unwrap();
+        assert_eq!(verification.records_checked, 1);
+        let report = verification.first_tampered.unwrap();
+        assert_eq!(report.seq, Some(1));
+        assert!(report.reason.contains("deleted"));
+    }
+
+    #[test]
+    fn test_mac_catches_recomputed_chain() {
+        let tmp = TempDir::new().unwrap();
+        let log = log_with(tmp.path(), 3, Some(keys()));
+
+        // An attacker without the MAC key edits a record and recomputes the
+        // whole chain after it.
+        let mut records = log.records().unwrap();
+        records[1].actor = "someone-else".into();
+        let mut prev = records[0].hash.clone();
+        for r in records.iter_mut().skip(1) {
+            r.prev_hash = prev.clone();
+            r.hash = r.compute_hash();
+            prev = r.hash.clone();
+        }
+        rewrite_lines(tmp.path(), |lines| {
+            *lines = records
+                .iter()
+                .map(|r| serde_json::to_string(r).unwrap())
+                .collect();
+        });
+
+        // Chain-only verification is fooled...
+        let unkeyed = AuditLog::open(tmp.path(), None).unwrap();
+        assert!(unkeyed.verify().unwrap().is_intact());
+
+        // ...but the MAC is not.
+        let report = log.verify().unwrap().first_tampered.unwrap();
+        assert_eq!(report.seq, Some(1));
+        assert!(report.reason.contains("MAC"));
+    }
+
+    #[test]
+    fn test_checkpoints_detect_truncation() {
+        let tmp = TempDir::new().unwrap();
+        let log = AuditLog::open(tmp.path(), Some(keys()))
+            .unwrap()
+            .with_checkpoint_interval(2);
+        for i in 0..4 {
+            log.append(event(i)).unwrap();
+        }
+        assert_eq!(log.checkpoints().unwrap().len(), 2);
+        assert_eq!(log.verify().unwrap().checkpoints_checked, 2);
+
+        rewrite_lines(tmp.path(), |lines| lines.truncate(2));
+
+        let report = log.verify().unwrap().first_tampered.unwrap();
+        assert_eq!(report.seq, Some(2));
+        assert!(report.reason.contains("truncated"));
+    }
+
+    #[test]
+    fn test_checkpoint_from_other_key_is_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let forger = AuditLog::open(tmp.path(), Some(AuditKeys::from_secrets([1u8; 32], [9u8; 32])))
+            .unwrap();
+        forger.append(event(0)).unwrap();
+        forger.checkpoint().unwrap().unwrap();
+
+        let log = AuditLog::open(tmp.path(), Some(keys())).unwrap();
+        let report = log.verify().unwrap().first_tampered.unwrap();
+        assert!(report.reason.contains("signature"));
+    }
+
+    #[test]
+    fn test_checkpoint_requires_keys() {
+        let tmp = TempDir::new().unwrap();
+        let log = log_with(tmp.path(), 1, None);
+        assert!(log.checkpoint().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_export_includes_verification() {
+        let tmp = TempDir::new().unwrap();
+        let log = log_with(tmp.path(), 2, Some(keys()));
+        log.checkpoint().unwrap();
+
+        let out = tmp.path().join("export.json");
+        assert!(log.export(&out).unwrap().is_intact());
+
+        let export: AuditExport =
+            serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(export.records.len(), 2);
+        assert_eq!(export.checkpoints.len(), 1);
+        assert_eq!(export.public_key, Some(keys().public_key_hex()));
+        assert!(export.verification.authenticated);
+    }
+}
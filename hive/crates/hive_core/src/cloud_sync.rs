@@ -1,11 +1,65 @@
 //! Client-side cloud sync module.
 //!
-//! Provides a [`SyncClient`] that pushes/pulls opaque blobs to the Hive Cloud
-//! sync API, enabling settings, conversations, and other data to follow the
-//! user across devices.
+//! Provides a [`SyncClient`] that pushes/pulls blobs to the Hive Cloud sync
+//! API, enabling settings, conversations, and other data to follow the user
+//! across devices.
+//!
+//! Everything leaves the device encrypted: [`SyncClient::unlock`] derives an
+//! AES-256-GCM key from the user's sync passphrase (Argon2id, salt shared via
+//! the `_hive/keyinfo` blob) and the server only ever stores ciphertext.
+//!
+//! [`SyncClient::sync`] reconciles one key with the cloud copy. Each copy
+//! carries a [`VersionVector`]; when both this device and another one changed
+//! a key since it was last synced, the two copies are merged against the
+//! common base kept in the local [`SyncLedger`]:
+//!
+//! * conversations are merged by message ID,
+//! * config is merged per top-level field, the most recent write winning,
+//! * kanban boards are merged per task.
+//!
+//! Edits that cannot be merged automatically (the same task changed on two
+//! devices, say) keep the local version and are reported as
+//! [`SyncConflict`]s so the user can pick.
+//!
+//! The authoritative version vector travels inside the encrypted document.
+//! The server keeps a plaintext copy for the manifest and echoes it on
+//! every read; a pull whose echoed vector differs from the encrypted one is
+//! rejected as tampered.
 
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, KeyInit, Payload},
+};
+use anyhow::Context;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::Engine;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Blob holding the key-derivation salt and a passphrase check value.
+const KEY_INFO_BLOB: &str = "_hive/keyinfo";
+/// Keys under this prefix belong to the sync machinery, not to callers.
+const RESERVED_PREFIX: &str = "_hive/";
+/// Plaintext of the key-info check value.
+const KEY_CHECK: &[u8] = b"hive-cloud-sync";
+
+const ENVELOPE_MAGIC: &[u8; 4] = b"HSYN";
+const ENVELOPE_VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+
+/// Header carrying a write's version vector to the server manifest, and
+/// the stored vector back on reads.
+const VERSION_VECTOR_HEADER: &str = "X-Hive-Version-Vector";
+
+/// How often [`SyncClient::sync`] re-reads the cloud copy after losing a
+/// race with another device before giving up.
+const MAX_SYNC_ATTEMPTS: usize = 3;
 
 // ---------------------------------------------------------------------------
 // Error type
@@ -22,6 +76,21 @@ pub enum SyncError {
 
     #[error("Not authenticated — cloud JWT is missing or expired")]
     NotAuthenticated,
+
+    #[error("Cloud sync is locked — unlock it with the sync passphrase first")]
+    Locked,
+
+    #[error("Wrong sync passphrase")]
+    WrongPassphrase,
+
+    #[error("Encryption error: {0}")]
+    Crypto(String),
+
+    #[error("Invalid sync data: {0}")]
+    InvalidData(String),
+
+    #[error("Cloud copy of `{0}` kept changing; try again")]
+    Contended(String),
 }
 
 // ---------------------------------------------------------------------------
@@ -35,6 +104,14 @@ pub struct ManifestEntry {
     pub size_bytes: i64,
     pub checksum: String,
     pub updated_at: DateTime<Utc>,
+    /// Server-side write counter for the key.
+    #[serde(default)]
+    pub version: u64,
+    #[serde(default)]
+    pub etag: String,
+    /// Version vector recorded by the device that wrote this copy.
+    #[serde(default)]
+    pub version_vector: VersionVector,
 }
 
 /// The full manifest returned by the sync API.
@@ -45,6 +122,301 @@ pub struct ManifestResponse {
     pub storage_limit_bytes: i64,
 }
 
+// ---------------------------------------------------------------------------
+// Version vectors
+// ---------------------------------------------------------------------------
+
+/// Per-device write counters for one synced key.
+///
+/// Ordered by causality: `a < b` when `b` has seen every write `a` has, and
+/// `partial_cmp` returns `None` when each has writes the other has not seen.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct VersionVector(BTreeMap<String, u64>);
+
+impl VersionVector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counter for `device`, zero if it never wrote.
+    pub fn get(&self, device: &str) -> u64 {
+        self.0.get(device).copied().unwrap_or(0)
+    }
+
+    /// Record a write by `device`.
+    pub fn increment(&mut self, device: &str) {
+        *self.0.entry(device.to_string()).or_insert(0) += 1;
+    }
+
+    /// Absorb every write `other` has seen.
+    pub fn merge(&mut self, other: &VersionVector) {
+        for (device, &count) in &other.0 {
+            let entry = self.0.entry(device.clone()).or_insert(0);
+            *entry = (*entry).max(count);
+        }
+    }
+}
+
+impl PartialOrd for VersionVector {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let devices: HashSet<&String> = self.0.keys().chain(other.0.keys()).collect();
+        let mut ordering = Ordering::Equal;
+        for device in devices {
+            let step = self.get(device).cmp(&other.get(device));
+            ordering = match (ordering, step) {
+                (current, Ordering::Equal) => current,
+                (Ordering::Equal, step) => step,
+                (current, step) if current == step => current,
+                _ => return None,
+            };
+        }
+        Some(ordering)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Encryption
+// ---------------------------------------------------------------------------
+
+/// Symmetric key for sync blobs, derived from the user's sync passphrase.
+///
+/// The passphrase and the derived key never leave the device.
+pub struct SyncKey {
+    cipher: Aes256Gcm,
+}
+
+impl SyncKey {
+    /// Derive the key with Argon2id (same parameters as config export).
+    pub fn derive(passphrase: &str, salt: &[u8]) -> Result<Self, SyncError> {
+        let params = Params::new(19_456, 2, 1, Some(32))
+            .map_err(|e| SyncError::Crypto(format!("Invalid Argon2 params: {e}")))?;
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+        let mut key_bytes = [0u8; 32];
+        argon2
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key_bytes)
+            .map_err(|e| SyncError::Crypto(format!("Argon2 key derivation failed: {e}")))?;
+
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes)),
+        })
+    }
+
+    /// Encrypt `plaintext` for storage under `blob_key`.
+    ///
+    /// Output format: `[4B magic "HSYN"] [1B version] [12B nonce] [ciphertext]`.
+    /// The blob key is authenticated as associated data, so the server cannot
+    /// swap ciphertexts between keys.
+    pub fn encrypt(&self, blob_key: &str, plaintext: &[u8]) -> Result<Vec<u8>, SyncError> {
+        let nonce_bytes: [u8; NONCE_LEN] = rand::random();
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                Nonce::from_slice(&nonce_bytes),
+                Payload {
+                    msg: plaintext,
+                    aad: blob_key.as_bytes(),
+                },
+            )
+            .map_err(|e| SyncError::Crypto(format!("AES-256-GCM encryption failed: {e}")))?;
+
+        let mut output = Vec::with_capacity(5 + NONCE_LEN + ciphertext.len());
+        output.extend_from_slice(ENVELOPE_MAGIC);
+        output.push(ENVELOPE_VERSION);
+        output.extend_from_slice(&nonce_bytes);
+        output.extend_from_slice(&ciphertext);
+        Ok(output)
+    }
+
+    /// Decrypt a blob produced by [`Self::encrypt`] for the same `blob_key`.
+    pub fn decrypt(&self, blob_key: &str, data: &[u8]) -> Result<Vec<u8>, SyncError> {
+        const HEADER_LEN: usize = 5 + NONCE_LEN;
+        if data.len() < HEADER_LEN || &data[..4] != ENVELOPE_MAGIC {
+            return Err(SyncError::Crypto(format!(
+                "`{blob_key}` is not an encrypted sync blob"
+            )));
+        }
+        if data[4] != ENVELOPE_VERSION {
+            return Err(SyncError::Crypto(format!(
+                "Unsupported sync blob version {} for `{blob_key}`",
+                data[4]
+            )));
+        }
+
+        self.cipher
+            .decrypt(
+                Nonce::from_slice(&data[5..HEADER_LEN]),
+                Payload {
+                    msg: &data[HEADER_LEN..],
+                    aad: blob_key.as_bytes(),
+                },
+            )
+            .map_err(|_| {
+                SyncError::Crypto(format!(
+                    "Failed to decrypt `{blob_key}` — wrong passphrase or tampered data"
+                ))
+            })
+    }
+}
+
+/// Public parameters stored in [`KEY_INFO_BLOB`].
+#[derive(Serialize, Deserialize)]
+struct KeyInfo {
+    version: u8,
+    salt: String,
+    /// [`KEY_CHECK`] encrypted with the derived key.
+    check: String,
+}
+
+// ---------------------------------------------------------------------------
+// Sync documents and the local ledger
+// ---------------------------------------------------------------------------
+
+/// How a synced key's content is merged when two devices edit it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncKind {
+    /// A serialized [`Conversation`](crate::conversations::Conversation).
+    Conversation,
+    /// A serialized [`HiveConfig`](crate::config::HiveConfig).
+    Config,
+    /// A serialized [`KanbanBoard`](crate::kanban::KanbanBoard).
+    Kanban,
+    /// Arbitrary bytes, merged as a single unit.
+    Opaque,
+}
+
+/// When and where a config field was last written.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FieldStamp {
+    pub at: DateTime<Utc>,
+    pub device: String,
+}
+
+/// The encrypted payload stored for every key synced via [`SyncClient::sync`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SyncDocument {
+    kind: SyncKind,
+    clock: VersionVector,
+    /// Per-field write stamps, only used for [`SyncKind::Config`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    stamps: BTreeMap<String, FieldStamp>,
+    data: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LedgerEntry {
+    clock: VersionVector,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    stamps: BTreeMap<String, FieldStamp>,
+    /// Content as of the last sync: the common base for three-way merges.
+    base: Value,
+}
+
+/// What this device last synced for each key.
+///
+/// Holds plaintext copies of synced data, so it belongs next to the local
+/// data it mirrors (e.g. `~/.hive/sync_ledger.json`), never in the cloud.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncLedger {
+    device_id: String,
+    #[serde(default)]
+    entries: BTreeMap<String, LedgerEntry>,
+}
+
+impl SyncLedger {
+    /// An empty ledger for `device_id`, which must be unique per device.
+    pub fn new(device_id: impl Into<String>) -> Self {
+        Self {
+            device_id: device_id.into(),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Load the ledger at `path`, starting a fresh one with a new device ID
+    /// if the file does not exist yet.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new(uuid::Uuid::new_v4().to_string()));
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read sync ledger {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse sync ledger {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string(self).context("Failed to serialize sync ledger")?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write sync ledger {}", path.display()))
+    }
+
+    pub fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// Keys whose cloud copy holds writes this device has not synced yet.
+    pub fn stale_keys<'a>(&self, manifest: &'a ManifestResponse) -> Vec<&'a str> {
+        manifest
+            .blobs
+            .iter()
+            .filter(|entry| !entry.key.starts_with(RESERVED_PREFIX))
+            .filter(|entry| {
+                self.entries.get(&entry.key).is_none_or(|synced| {
+                    !matches!(
+                        entry.version_vector.partial_cmp(&synced.clock),
+                        Some(Ordering::Less | Ordering::Equal)
+                    )
+                })
+            })
+            .map(|entry| entry.key.as_str())
+            .collect()
+    }
+
+    /// Drop the sync history for `key`, e.g. after deleting it locally.
+    pub fn forget(&mut self, key: &str) {
+        self.entries.remove(key);
+    }
+}
+
+/// What [`SyncClient::sync`] did with a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncAction {
+    /// Local and cloud copies already matched.
+    UpToDate,
+    /// Local changes were uploaded.
+    Uploaded,
+    /// The cloud copy was newer and replaces the local one.
+    Downloaded,
+    /// Both sides had changed; the merged result was uploaded.
+    Merged,
+}
+
+/// An edit made on two devices that could not be merged automatically.
+///
+/// The local value was kept; `remote` holds the other device's version so
+/// the user can choose it instead. `None` means the item was deleted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncConflict {
+    pub key: String,
+    /// What conflicted, e.g. `task:<id>` or `field:title`.
+    pub item: String,
+    pub local: Option<Value>,
+    pub remote: Option<Value>,
+}
+
+/// Result of syncing one key.
+#[derive(Debug, Clone)]
+pub struct SyncOutcome {
+    pub action: SyncAction,
+    /// The reconciled content, to be written back over the local copy.
+    pub data: Vec<u8>,
+    pub conflicts: Vec<SyncConflict>,
+}
+
 // ---------------------------------------------------------------------------
 // Client
 // ---------------------------------------------------------------------------
@@ -54,6 +426,23 @@ pub struct SyncClient {
     api_url: String,
     jwt: String,
     client: reqwest::Client,
+    key: Option<SyncKey>,
+}
+
+/// A blob as served by the sync API.
+struct FetchedBlob {
+    data: Vec<u8>,
+    etag: String,
+    /// The server's plaintext copy of the blob's version vector.
+    version_vector: Option<VersionVector>,
+}
+
+/// Condition attached to an upload.
+enum WriteCondition<'a> {
+    /// Only create the blob if it does not exist yet.
+    Absent,
+    /// Only replace the copy with this ETag.
+    Matches(&'a str),
 }
 
 impl SyncClient {
@@ -66,83 +455,92 @@ impl SyncClient {
             api_url: api_url.trim_end_matches('/').to_string(),
             jwt,
             client: reqwest::Client::new(),
+            key: None,
         }
     }
 
-    /// Upload (or overwrite) a blob by key.
+    /// Derive the sync key from `passphrase`.
     ///
-    /// `PUT /v1/sync/blobs/{key}`
-    pub async fn push(&self, key: &str, data: &[u8]) -> Result<(), SyncError> {
-        if self.jwt.is_empty() {
-            return Err(SyncError::NotAuthenticated);
-        }
+    /// The first device to unlock picks the salt and publishes it with a
+    /// check value; later devices must supply the same passphrase or get
+    /// [`SyncError::WrongPassphrase`].
+    pub async fn unlock(&mut self, passphrase: &str) -> Result<(), SyncError> {
+        for _ in 0..MAX_SYNC_ATTEMPTS {
+            if let Some(blob) = self.get_blob(KEY_INFO_BLOB).await? {
+                let info: KeyInfo = serde_json::from_slice(&blob.data)
+                    .map_err(|e| SyncError::InvalidData(format!("Bad key info: {e}")))?;
+                let salt = hex::decode(&info.salt)
+                    .map_err(|e| SyncError::InvalidData(format!("Bad key salt: {e}")))?;
+                let check = hex::decode(&info.check)
+                    .map_err(|e| SyncError::InvalidData(format!("Bad key check: {e}")))?;
 
-        let url = format!("{}/v1/sync/blobs/{}", self.api_url, key);
-        let resp = self
-            .client
-            .put(&url)
-            .header("Authorization", format!("Bearer {}", self.jwt))
-            .header("Content-Type", "application/octet-stream")
-            .body(data.to_vec())
-            .send()
-            .await?;
+                let key = SyncKey::derive(passphrase, &salt)?;
+                match key.decrypt(KEY_INFO_BLOB, &check) {
+                    Ok(plaintext) if plaintext == KEY_CHECK => {}
+                    _ => return Err(SyncError::WrongPassphrase),
+                }
+                self.key = Some(key);
+                return Ok(());
+            }
 
-        if !resp.status().is_success() {
-            let status = resp.status().as_u16();
-            let message = resp.text().await.unwrap_or_default();
-            return Err(SyncError::Server { status, message });
+            let salt: [u8; SALT_LEN] = rand::random();
+            let key = SyncKey::derive(passphrase, &salt)?;
+            let info = KeyInfo {
+                version: ENVELOPE_VERSION,
+                salt: hex::encode(salt),
+                check: hex::encode(key.encrypt(KEY_INFO_BLOB, KEY_CHECK)?),
+            };
+            let body = serde_json::to_vec(&info)
+                .map_err(|e| SyncError::InvalidData(format!("Bad key info: {e}")))?;
+            // Another device may publish its salt first; adopt it if so.
+            if self
+                .put_blob(KEY_INFO_BLOB, body, Some(WriteCondition::Absent), None)
+                .await?
+                .is_some()
+            {
+                self.key = Some(key);
+                return Ok(());
+            }
         }
+        Err(SyncError::Contended(KEY_INFO_BLOB.to_string()))
+    }
+
+    /// Whether [`Self::unlock`] has succeeded.
+    pub fn is_unlocked(&self) -> bool {
+        self.key.is_some()
+    }
 
+    /// Encrypt and upload (or overwrite) a blob by key.
+    ///
+    /// `PUT /v1/sync/blobs/{key}`
+    pub async fn push(&self, key: &str, data: &[u8]) -> Result<(), SyncError> {
+        check_key(key)?;
+        let body = self.sync_key()?.encrypt(key, data)?;
+        self.put_blob(key, body, None, None).await?;
         Ok(())
     }
 
-    /// Download a blob by key.
+    /// Download and decrypt a blob by key.
     ///
     /// `GET /v1/sync/blobs/{key}`
     pub async fn pull(&self, key: &str) -> Result<Vec<u8>, SyncError> {
-        if self.jwt.is_empty() {
-            return Err(SyncError::NotAuthenticated);
-        }
-
-        let url = format!("{}/v1/sync/blobs/{}", self.api_url, key);
-        let resp = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.jwt))
-            .send()
-            .await?;
-
-        if !resp.status().is_success() {
-            let status = resp.status().as_u16();
-            let message = resp.text().await.unwrap_or_default();
-            return Err(SyncError::Server { status, message });
+        check_key(key)?;
+        let sync_key = self.sync_key()?;
+        match self.get_blob(key).await? {
+            Some(blob) => sync_key.decrypt(key, &blob.data),
+            None => Err(SyncError::Server {
+                status: 404,
+                message: format!("blob `{key}` not found"),
+            }),
         }
-
-        let bytes = resp.bytes().await?;
-        Ok(bytes.to_vec())
     }
 
     /// Retrieve the full manifest of stored blobs.
     ///
     /// `GET /v1/sync/manifest`
     pub async fn manifest(&self) -> Result<ManifestResponse, SyncError> {
-        if self.jwt.is_empty() {
-            return Err(SyncError::NotAuthenticated);
-        }
-
         let url = format!("{}/v1/sync/manifest", self.api_url);
-        let resp = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.jwt))
-            .send()
-            .await?;
-
-        if !resp.status().is_success() {
-            let status = resp.status().as_u16();
-            let message = resp.text().await.unwrap_or_default();
-            return Err(SyncError::Server { status, message });
-        }
+        let resp = self.send(self.client.get(&url)).await?;
 
         let manifest: ManifestResponse = resp.json().await.map_err(|e| SyncError::Server {
             status: 0,
@@ -156,14 +554,226 @@ impl SyncClient {
     ///
     /// `DELETE /v1/sync/blobs/{key}`
     pub async fn delete(&self, key: &str) -> Result<(), SyncError> {
-        if self.jwt.is_empty() {
-            return Err(SyncError::NotAuthenticated);
+        let url = format!("{}/v1/sync/blobs/{}", self.api_url, key);
+        self.send(self.client.delete(&url)).await?;
+        Ok(())
+    }
+
+    /// Reconcile the local copy of `key` with the cloud copy.
+    ///
+    /// `local` is the current local content (JSON for every kind except
+    /// [`SyncKind::Opaque`]). Returns the content the local copy should be
+    /// replaced with, plus any conflicts that were resolved in favour of the
+    /// local side. `ledger` is updated and should be saved afterwards.
+    pub async fn sync(
+        &self,
+        key: &str,
+        kind: SyncKind,
+        local: &[u8],
+        ledger: &mut SyncLedger,
+    ) -> Result<SyncOutcome, SyncError> {
+        check_key(key)?;
+        let sync_key = self.sync_key()?;
+        let local_data = decode_local(kind, local)?;
+
+        for _ in 0..MAX_SYNC_ATTEMPTS {
+            let device = ledger.device_id.clone();
+            let entry = ledger.entries.get(key);
+            let base = entry.map(|entry| &entry.base);
+            let base_clock = entry.map(|entry| entry.clock.clone()).unwrap_or_default();
+            let local_stamps = match (kind, entry) {
+                (SyncKind::Config, Some(entry)) => {
+                    stamp_changes(&entry.base, &entry.stamps, &local_data, &device)
+                }
+                _ => BTreeMap::new(),
+            };
+            let local_changed = base != Some(&local_data);
+
+            let remote = match self.get_blob(key).await? {
+                Some(blob) => {
+                    let plaintext = sync_key.decrypt(key, &blob.data)?;
+                    let document: SyncDocument = serde_json::from_slice(&plaintext)
+                        .map_err(|e| SyncError::InvalidData(format!("`{key}`: {e}")))?;
+                    if document.kind != kind {
+                        return Err(SyncError::InvalidData(format!(
+                            "`{key}` is synced as {:?}, not {kind:?}",
+                            document.kind
+                        )));
+                    }
+                    if blob.version_vector.as_ref() != Some(&document.clock) {
+                        return Err(SyncError::InvalidData(format!(
+                            "`{key}`: the server's version vector does not match the encrypted copy"
+                        )));
+                    }
+                    Some((document, blob.etag))
+                }
+                None => None,
+            };
+
+            let Some((remote, etag)) = remote else {
+                let mut document = SyncDocument {
+                    kind,
+                    clock: base_clock,
+                    stamps: local_stamps,
+                    data: local_data.clone(),
+                };
+                document.clock.increment(&device);
+                if kind == SyncKind::Config {
+                    stamp_unstamped(&mut document.stamps, &document.data, &device);
+                }
+                if self
+                    .upload(key, &document, WriteCondition::Absent)
+                    .await?
+                    .is_none()
+                {
+                    continue;
+                }
+                return finish(ledger, key, document, SyncAction::Uploaded, Vec::new());
+            };
+
+            let remote_changed = entry.is_none_or(|entry| entry.clock != remote.clock);
+            if !local_changed && !remote_changed {
+                return finish(ledger, key, remote, SyncAction::UpToDate, Vec::new());
+            }
+            if !local_changed || remote.data == local_data {
+                return finish(ledger, key, remote, SyncAction::Downloaded, Vec::new());
+            }
+
+            let mut conflicts = Vec::new();
+            let (data, stamps, action) = if !remote_changed {
+                (local_data.clone(), local_stamps, SyncAction::Uploaded)
+            } else {
+                let (data, stamps) = merge(
+                    key,
+                    kind,
+                    base,
+                    (&local_data, &local_stamps),
+                    (&remote.data, &remote.stamps),
+                    &mut conflicts,
+                );
+                (data, stamps, SyncAction::Merged)
+            };
+
+            let mut clock = remote.clock.clone();
+            clock.merge(&base_clock);
+            if action == SyncAction::Merged && data == remote.data && stamps == remote.stamps {
+                // Nothing local survived the merge; adopt the cloud copy as is.
+                return finish(ledger, key, remote, SyncAction::Downloaded, conflicts);
+            }
+            clock.increment(&device);
+
+            let document = SyncDocument {
+                kind,
+                clock,
+                stamps,
+                data,
+            };
+            if self
+                .upload(key, &document, WriteCondition::Matches(&etag))
+                .await?
+                .is_none()
+            {
+                continue;
+            }
+            return finish(ledger, key, document, action, conflicts);
         }
 
+        Err(SyncError::Contended(key.to_string()))
+    }
+
+    fn sync_key(&self) -> Result<&SyncKey, SyncError> {
+        self.key.as_ref().ok_or(SyncError::Locked)
+    }
+
+    async fn upload(
+        &self,
+        key: &str,
+        document: &SyncDocument,
+        condition: WriteCondition<'_>,
+    ) -> Result<Option<String>, SyncError> {
+        let plaintext = serde_json::to_vec(document)
+            .map_err(|e| SyncError::InvalidData(format!("`{key}`: {e}")))?;
+        let body = self.sync_key()?.encrypt(key, &plaintext)?;
+        self.put_blob(key, body, Some(condition), Some(&document.clock))
+            .await
+    }
+
+    /// Fetch a blob, or `None` if it does not exist.
+    async fn get_blob(&self, key: &str) -> Result<Option<FetchedBlob>, SyncError> {
+        let url = format!("{}/v1/sync/blobs/{}", self.api_url, key);
+        let resp = match self.send(self.client.get(&url)).await {
+            Err(SyncError::Server { status: 404, .. }) => return Ok(None),
+            other => other?,
+        };
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(reqwest::header::ETAG.as_str()).unwrap_or_default();
+        let version_vector = header(VERSION_VECTOR_HEADER)
+            .map(|value| {
+                serde_json::from_str(&value).map_err(|e| {
+                    SyncError::InvalidData(format!("`{key}`: bad version vector: {e}"))
+                })
+            })
+            .transpose()?;
+        let data = resp.bytes().await?.to_vec();
+        Ok(Some(FetchedBlob {
+            data,
+            etag,
+            version_vector,
+        }))
+    }
+
+    /// Upload a blob, returning its new ETag, or `None` if `condition` no
+    /// longer holds because another device wrote first.
+    async fn put_blob(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        condition: Option<WriteCondition<'_>>,
+        clock: Option<&VersionVector>,
+    ) -> Result<Option<String>, SyncError> {
         let url = format!("{}/v1/sync/blobs/{}", self.api_url, key);
-        let resp = self
+        let mut request = self
             .client
-            .delete(&url)
+            .put(&url)
+            .header("Content-Type", "application/octet-stream")
+            .body(body);
+        request = match condition {
+            Some(WriteCondition::Absent) => request.header("If-None-Match", "*"),
+            Some(WriteCondition::Matches(etag)) => request.header("If-Match", etag),
+            None => request,
+        };
+        if let Some(clock) = clock {
+            let clock = serde_json::to_string(clock)
+                .map_err(|e| SyncError::InvalidData(format!("`{key}`: {e}")))?;
+            request = request.header(VERSION_VECTOR_HEADER, clock);
+        }
+
+        match self.send(request).await {
+            Ok(resp) => Ok(Some(
+                resp.headers()
+                    .get(reqwest::header::ETAG)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string(),
+            )),
+            Err(SyncError::Server { status: 412, .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Authenticate and send a request, turning error statuses into
+    /// [`SyncError::Server`].
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, SyncError> {
+        if self.jwt.is_empty() {
+            return Err(SyncError::NotAuthenticated);
+        }
+
+        let resp = request
             .header("Authorization", format!("Bearer {}", self.jwt))
             .send()
             .await?;
@@ -174,6 +784,676 @@ impl SyncClient {
             return Err(SyncError::Server { status, message });
         }
 
-        Ok(())
+        Ok(resp)
+    }
+}
+
+fn check_key(key: &str) -> Result<(), SyncError> {
+    if key.starts_with(RESERVED_PREFIX) {
+        return Err(SyncError::InvalidData(format!(
+            "`{key}` is reserved for sync metadata"
+        )));
+    }
+    Ok(())
+}
+
+fn decode_local(kind: SyncKind, local: &[u8]) -> Result<Value, SyncError> {
+    match kind {
+        SyncKind::Opaque => Ok(Value::String(
+            base64::engine::general_purpose::STANDARD.encode(local),
+        )),
+        _ => serde_json::from_slice(local)
+            .map_err(|e| SyncError::InvalidData(format!("Local {kind:?} is not JSON: {e}"))),
+    }
+}
+
+fn encode_local(kind: SyncKind, data: &Value) -> Result<Vec<u8>, SyncError> {
+    match (kind, data) {
+        (SyncKind::Opaque, Value::String(encoded)) => base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| SyncError::InvalidData(format!("Bad opaque payload: {e}"))),
+        (SyncKind::Opaque, _) => Err(SyncError::InvalidData("Bad opaque payload".into())),
+        _ => serde_json::to_vec_pretty(data)
+            .map_err(|e| SyncError::InvalidData(format!("Failed to serialize {kind:?}: {e}"))),
+    }
+}
+
+/// Record `document` as the new sync base for `key` and build the outcome.
+fn finish(
+    ledger: &mut SyncLedger,
+    key: &str,
+    document: SyncDocument,
+    action: SyncAction,
+    conflicts: Vec<SyncConflict>,
+) -> Result<SyncOutcome, SyncError> {
+    let data = encode_local(document.kind, &document.data)?;
+    ledger.entries.insert(
+        key.to_string(),
+        LedgerEntry {
+            clock: document.clock,
+            stamps: document.stamps,
+            base: document.data,
+        },
+    );
+    Ok(SyncOutcome {
+        action,
+        data,
+        conflicts,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Merging
+// ---------------------------------------------------------------------------
+
+type Stamps = BTreeMap<String, FieldStamp>;
+
+/// Merge concurrently edited copies of one key.
+fn merge(
+    key: &str,
+    kind: SyncKind,
+    base: Option<&Value>,
+    (local, local_stamps): (&Value, &Stamps),
+    (remote, remote_stamps): (&Value, &Stamps),
+    conflicts: &mut Vec<SyncConflict>,
+) -> (Value, Stamps) {
+    let mut merger = Merger { key, conflicts };
+    match kind {
+        SyncKind::Conversation => (merger.conversation(base, local, remote), Stamps::new()),
+        SyncKind::Config => merge_config(local, local_stamps, remote, remote_stamps),
+        SyncKind::Kanban => (merger.kanban(base, local, remote), Stamps::new()),
+        SyncKind::Opaque => (
+            merger
+                .value("content", base, Some(local), Some(remote))
+                .unwrap_or_else(|| local.clone()),
+            Stamps::new(),
+        ),
+    }
+}
+
+struct Merger<'a> {
+    key: &'a str,
+    conflicts: &'a mut Vec<SyncConflict>,
+}
+
+impl Merger<'_> {
+    /// Three-way merge of a single item. `None` means absent/deleted.
+    ///
+    /// When both sides changed it differently the local side wins (a
+    /// deletion loses to an edit) and the conflict is recorded.
+    fn value(
+        &mut self,
+        item: &str,
+        base: Option<&Value>,
+        local: Option<&Value>,
+        remote: Option<&Value>,
+    ) -> Option<Value> {
+        if local == remote || remote == base {
+            return local.cloned();
+        }
+        if local == base {
+            return remote.cloned();
+        }
+        self.conflicts.push(SyncConflict {
+            key: self.key.to_string(),
+            item: item.to_string(),
+            local: local.cloned(),
+            remote: remote.cloned(),
+        });
+        local.or(remote).cloned()
+    }
+
+    /// Three-way merge of each top-level field of two JSON objects, except
+    /// those in `skip`.
+    fn fields(
+        &mut self,
+        base: Option<&Value>,
+        local: &Value,
+        remote: &Value,
+        skip: &[&str],
+    ) -> Map<String, Value> {
+        let empty = Map::new();
+        let base = base.and_then(Value::as_object);
+        let local = local.as_object().unwrap_or(&empty);
+        let remote = remote.as_object().unwrap_or(&empty);
+
+        let mut merged = Map::new();
+        for name in ordered_union(local.keys(), remote.keys()) {
+            if skip.contains(&name.as_str()) {
+                continue;
+            }
+            let base_value = base.map_or(local.get(name), |base| base.get(name));
+            if let Some(value) = self.value(
+                &format!("field:{name}"),
+                base_value,
+                local.get(name),
+                remote.get(name),
+            ) {
+                merged.insert(name.clone(), value);
+            }
+        }
+        merged
+    }
+
+    /// Merge a list of objects identified by `id_of`, item by item.
+    fn items(
+        &mut self,
+        label: &str,
+        base: &[Value],
+        local: &[Value],
+        remote: &[Value],
+        id_of: impl Fn(&Value) -> String,
+    ) -> Vec<Value> {
+        let base: HashMap<String, &Value> = base.iter().map(|v| (id_of(v), v)).collect();
+        let local: Vec<(String, &Value)> = local.iter().map(|v| (id_of(v), v)).collect();
+        let remote: Vec<(String, &Value)> = remote.iter().map(|v| (id_of(v), v)).collect();
+        let local_by_id: HashMap<&String, &Value> = local.iter().map(|(id, v)| (id, *v)).collect();
+        let remote_by_id: HashMap<&String, &Value> =
+            remote.iter().map(|(id, v)| (id, *v)).collect();
+
+        ordered_union(
+            local.iter().map(|(id, _)| id),
+            remote.iter().map(|(id, _)| id),
+        )
+        .into_iter()
+        .filter_map(|id| {
+            self.value(
+                &format!("{label}:{id}"),
+                base.get(id).copied(),
+                local_by_id.get(id).copied(),
+                remote_by_id.get(id).copied(),
+            )
+        })
+        .collect()
+    }
+
+    /// Messages are merged by [`message_id`]; other fields three-way, with
+    /// the cost/token totals recomputed from the merged messages.
+    fn conversation(&mut self, base: Option<&Value>, local: &Value, remote: &Value) -> Value {
+        let mut messages = self.items(
+            "message",
+            array(base, "messages"),
+            array(Some(local), "messages"),
+            array(Some(remote), "messages"),
+            message_id,
+        );
+        messages.sort_by_key(|message| {
+            message
+                .get("timestamp")
+                .and_then(Value::as_str)
+                .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+        });
+
+        let mut merged = self.fields(
+            base,
+            local,
+            remote,
+            &["messages", "totalCost", "totalTokens", "updatedAt"],
+        );
+        let total_cost: f64 = messages
+            .iter()
+            .filter_map(|m| m.get("cost").and_then(Value::as_f64))
+            .sum();
+        let total_tokens: u64 = messages
+            .iter()
+            .filter_map(|m| m.get("tokenCount").and_then(Value::as_u64))
+            .sum();
+        let updated_at = [local, remote]
+            .into_iter()
+            .filter_map(|side| side.get("updatedAt"))
+            .max_by_key(|ts| {
+                ts.as_str()
+                    .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            })
+            .cloned();
+
+        merged.insert("messages".into(), Value::Array(messages));
+        merged.insert("totalCost".into(), total_cost.into());
+        merged.insert("totalTokens".into(), total_tokens.into());
+        if let Some(updated_at) = updated_at {
+            merged.insert("updatedAt".into(), updated_at);
+        }
+        Value::Object(merged)
+    }
+
+    /// Tasks are merged by ID; WIP limits per column.
+    fn kanban(&mut self, base: Option<&Value>, local: &Value, remote: &Value) -> Value {
+        let tasks = self.items(
+            "task",
+            array(base, "tasks"),
+            array(Some(local), "tasks"),
+            array(Some(remote), "tasks"),
+            |task| {
+                task.get("id")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string()
+            },
+        );
+
+        let no_limits = Value::Object(Map::new());
+        let wip_limits = self.fields(
+            base.map(|base| base.get("wip_limits").unwrap_or(&no_limits)),
+            local.get("wip_limits").unwrap_or(&no_limits),
+            remote.get("wip_limits").unwrap_or(&no_limits),
+            &[],
+        );
+
+        let mut merged = self.fields(base, local, remote, &["tasks", "wip_limits"]);
+        merged.insert("tasks".into(), Value::Array(tasks));
+        merged.insert("wip_limits".into(), Value::Object(wip_limits));
+        Value::Object(merged)
+    }
+}
+
+/// Config fields resolve independently: the side that wrote a field most
+/// recently wins. A side without a stamp for a field (never changed it since
+/// the first sync) defers to one that has.
+fn merge_config(
+    local: &Value,
+    local_stamps: &Stamps,
+    remote: &Value,
+    remote_stamps: &Stamps,
+) -> (Value, Stamps) {
+    let empty = Map::new();
+    let local_fields = local.as_object().unwrap_or(&empty);
+    let remote_fields = remote.as_object().unwrap_or(&empty);
+
+    let names = ordered_union(
+        local_fields.keys().chain(local_stamps.keys()),
+        remote_fields.keys().chain(remote_stamps.keys()),
+    );
+    let mut merged = Map::new();
+    let mut stamps = Stamps::new();
+    for name in names {
+        let take_remote = match (local_stamps.get(name), remote_stamps.get(name)) {
+            (_, None) => !local_fields.contains_key(name),
+            (None, Some(_)) => true,
+            (Some(local), Some(remote)) => remote > local,
+        };
+        let (fields, winner) = if take_remote {
+            (remote_fields, remote_stamps.get(name))
+        } else {
+            (local_fields, local_stamps.get(name))
+        };
+        if let Some(value) = fields.get(name) {
+            merged.insert(name.clone(), value.clone());
+        }
+        if let Some(stamp) = winner {
+            stamps.insert(name.clone(), stamp.clone());
+        }
+    }
+    (Value::Object(merged), stamps)
+}
+
+/// Stamp the config fields that differ from the last synced `base` as
+/// written now by `device`; unchanged fields keep their previous stamps.
+fn stamp_changes(base: &Value, base_stamps: &Stamps, local: &Value, device: &str) -> Stamps {
+    let empty = Map::new();
+    let base = base.as_object().unwrap_or(&empty);
+    let local = local.as_object().unwrap_or(&empty);
+    let now = FieldStamp {
+        at: Utc::now(),
+        device: device.to_string(),
+    };
+
+    ordered_union(base.keys(), local.keys())
+        .into_iter()
+        .filter_map(|name| {
+            if base.get(name) != local.get(name) {
+                Some((name.clone(), now.clone()))
+            } else {
+                base_stamps
+                    .get(name)
+                    .map(|stamp| (name.clone(), stamp.clone()))
+            }
+        })
+        .collect()
+}
+
+/// Stamp every field of a first upload that has no stamp yet, so devices
+/// joining later adopt it instead of overwriting it with their defaults.
+fn stamp_unstamped(stamps: &mut Stamps, data: &Value, device: &str) {
+    let now = FieldStamp {
+        at: Utc::now(),
+        device: device.to_string(),
+    };
+    for name in data.as_object().into_iter().flat_map(Map::keys) {
+        stamps.entry(name.clone()).or_insert_with(|| now.clone());
+    }
+}
+
+/// Stable identity of a stored message.
+///
+/// Messages carry no ID of their own; role, timestamp and content together
+/// identify one, and none of them change once the message is written.
+fn message_id(message: &Value) -> String {
+    let field = |name: &str| message.get(name).map(Value::to_string).unwrap_or_default();
+    let mut hasher = Sha256::new();
+    for part in [field("role"), field("timestamp"), field("content")] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(&hasher.finalize()[..16])
+}
+
+fn array<'a>(value: Option<&'a Value>, field: &str) -> &'a [Value] {
+    value
+        .and_then(|value| value.get(field))
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Items of `first` in order, followed by the items only in `second`.
+fn ordered_union<'a>(
+    first: impl IntoIterator<Item = &'a String>,
+    second: impl IntoIterator<Item = &'a String>,
+) -> Vec<&'a String> {
+    let mut seen = HashSet::new();
+    first
+        .into_iter()
+        .chain(second)
+        .filter(|item| seen.insert(*item))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversations::{Conversation, StoredMessage};
+    use crate::kanban::{KanbanBoard, KanbanColumn, Priority};
+    use serde_json::json;
+
+    fn vector(entries: &[(&str, u64)]) -> VersionVector {
+        VersionVector(
+            entries
+                .iter()
+                .map(|(device, count)| (device.to_string(), *count))
+                .collect(),
+        )
+    }
+
+    fn message(role: &str, content: &str, secs: i64) -> StoredMessage {
+        StoredMessage {
+            role: role.into(),
+            content: content.into(),
+            timestamp: DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap(),
+            model: None,
+            cost: Some(0.5),
+            tokens: Some(10),
+            thinking: None,
+            is_compacted: false,
+            compacted_from: None,
+        }
+    }
+
+    fn merge_json(
+        kind: SyncKind,
+        base: &Value,
+        local: &Value,
+        remote: &Value,
+    ) -> (Value, Vec<SyncConflict>) {
+        let mut conflicts = Vec::new();
+        let (merged, _) = merge(
+            "key",
+            kind,
+            Some(base),
+            (local, &Stamps::new()),
+            (remote, &Stamps::new()),
+            &mut conflicts,
+        );
+        (merged, conflicts)
+    }
+
+    #[test]
+    fn version_vectors_order_by_causality() {
+        let a = vector(&[("laptop", 2), ("desktop", 1)]);
+        assert_eq!(a.partial_cmp(&a.clone()), Some(Ordering::Equal));
+        assert!(vector(&[("laptop", 1)]) < a);
+        assert!(a > vector(&[("desktop", 1)]));
+
+        let concurrent = vector(&[("laptop", 1), ("desktop", 2)]);
+        assert_eq!(a.partial_cmp(&concurrent), None);
+
+        let mut merged = a.clone();
+        merged.merge(&concurrent);
+        merged.increment("phone");
+        assert_eq!(
+            merged,
+            vector(&[("laptop", 2), ("desktop", 2), ("phone", 1)])
+        );
+        assert!(merged > a && merged > concurrent);
+    }
+
+    #[test]
+    fn sync_key_round_trips_and_binds_the_blob_key() {
+        let key = SyncKey::derive("correct horse", &[7; SALT_LEN]).unwrap();
+        let sealed = key
+            .encrypt("settings.json", b"{\"theme\":\"dark\"}")
+            .unwrap();
+
+        assert!(!sealed.windows(5).any(|window| window == b"theme"));
+        assert_eq!(
+            key.decrypt("settings.json", &sealed).unwrap(),
+            b"{\"theme\":\"dark\"}"
+        );
+        assert!(key.decrypt("other.json", &sealed).is_err());
+
+        let wrong = SyncKey::derive("battery staple", &[7; SALT_LEN]).unwrap();
+        assert!(matches!(
+            wrong.decrypt("settings.json", &sealed),
+            Err(SyncError::Crypto(_))
+        ));
+    }
+
+    #[test]
+    fn conversations_merge_by_message_id() {
+        let mut base = Conversation::new("model");
+        base.add_message(message("user", "hello", 0));
+        base.add_message(message("assistant", "hi", 1));
+
+        let mut local = base.clone();
+        local.add_message(message("user", "from laptop", 2));
+        let mut remote = base.clone();
+        remote.add_message(message("user", "from desktop", 3));
+        remote.messages.remove(1);
+
+        let (merged, conflicts) = merge_json(
+            SyncKind::Conversation,
+            &serde_json::to_value(&base).unwrap(),
+            &serde_json::to_value(&local).unwrap(),
+            &serde_json::to_value(&remote).unwrap(),
+        );
+        let merged: Conversation = serde_json::from_value(merged).unwrap();
+
+        assert!(conflicts.is_empty());
+        let contents: Vec<&str> = merged.messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["hello", "from laptop", "from desktop"]);
+        assert_eq!(merged.total_tokens, 30);
+        assert!((merged.total_cost - 1.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn kanban_merges_per_task_and_reports_conflicts() {
+        let mut base = KanbanBoard::new();
+        let shared = base.add_task("Shared", None, Priority::Medium);
+        let contested = base.add_task("Contested", None, Priority::Medium);
+
+        let mut local = serde_json::to_value(&base).unwrap();
+        let mut remote = local.clone();
+        let base = local.clone();
+
+        // Laptop moves one task and edits another; desktop adds a task and
+        // edits the same contested task differently.
+        let mut laptop: KanbanBoard = serde_json::from_value(local.clone()).unwrap();
+        laptop
+            .move_task(&shared.id, KanbanColumn::InProgress)
+            .unwrap();
+        laptop
+            .update_task(
+                &contested.id,
+                Some("Laptop title".into()),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        local = serde_json::to_value(&laptop).unwrap();
+
+        let mut desktop: KanbanBoard = serde_json::from_value(remote.clone()).unwrap();
+        let added = desktop.add_task("Desktop task", None, Priority::Low);
+        desktop
+            .update_task(
+                &contested.id,
+                Some("Desktop title".into()),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        remote = serde_json::to_value(&desktop).unwrap();
+
+        let (merged, conflicts) = merge_json(SyncKind::Kanban, &base, &local, &remote);
+        let merged: KanbanBoard = serde_json::from_value(merged).unwrap();
+
+        assert_eq!(merged.all_tasks().len(), 3);
+        assert_eq!(
+            merged.get_task(&shared.id).unwrap().column,
+            KanbanColumn::InProgress
+        );
+        assert!(merged.get_task(&added.id).is_some());
+        assert_eq!(
+            merged.get_task(&contested.id).unwrap().title,
+            "Laptop title"
+        );
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].item, format!("task:{}", contested.id));
+        assert_eq!(
+            conflicts[0].remote.as_ref().unwrap()["title"],
+            "Desktop title"
+        );
+    }
+
+    #[test]
+    fn config_fields_resolve_by_latest_write() {
+        let earlier = FieldStamp {
+            at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            device: "laptop".into(),
+        };
+        let later = FieldStamp {
+            at: DateTime::from_timestamp(1_700_000_100, 0).unwrap(),
+            device: "desktop".into(),
+        };
+
+        let local = json!({"theme": "dark", "font_size": 14, "local_only": true});
+        let local_stamps = Stamps::from([
+            ("theme".into(), later.clone()),
+            ("font_size".into(), earlier.clone()),
+        ]);
+        let remote = json!({"theme": "light", "font_size": 16, "remote_only": 1});
+        let remote_stamps = Stamps::from([
+            ("theme".into(), earlier.clone()),
+            ("font_size".into(), later.clone()),
+            ("remote_only".into(), earlier.clone()),
+        ]);
+
+        let (merged, stamps) = merge_config(&local, &local_stamps, &remote, &remote_stamps);
+        assert_eq!(
+            merged,
+            json!({"theme": "dark", "font_size": 16, "local_only": true, "remote_only": 1})
+        );
+        assert_eq!(stamps["theme"], later);
+        assert_eq!(stamps["font_size"], later);
+
+        // A field deleted after its last remote write stays deleted.
+        let (merged, _) = merge_config(
+            &json!({}),
+            &Stamps::from([("remote_only".into(), later.clone())]),
+            &remote,
+            &remote_stamps,
+        );
+        assert!(merged.get("remote_only").is_none());
+    }
+
+    #[test]
+    fn stamps_only_cover_changed_fields() {
+        let old = FieldStamp {
+            at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            device: "desktop".into(),
+        };
+        let base = json!({"theme": "dark", "font_size": 14});
+        let base_stamps = Stamps::from([("theme".into(), old.clone())]);
+        let local = json!({"theme": "dark", "font_size": 15});
+
+        let stamps = stamp_changes(&base, &base_stamps, &local, "laptop");
+        assert_eq!(stamps["theme"], old);
+        assert_eq!(stamps["font_size"].device, "laptop");
+    }
+
+    #[test]
+    fn ledger_reports_keys_with_unseen_writes() {
+        let mut ledger = SyncLedger::new("laptop");
+        ledger.entries.insert(
+            "synced".into(),
+            LedgerEntry {
+                clock: vector(&[("laptop", 2)]),
+                stamps: Stamps::new(),
+                base: json!({}),
+            },
+        );
+        ledger.entries.insert(
+            "behind".into(),
+            LedgerEntry {
+                clock: vector(&[("laptop", 1)]),
+                stamps: Stamps::new(),
+                base: json!({}),
+            },
+        );
+
+        let entry = |key: &str, clock: VersionVector| ManifestEntry {
+            key: key.into(),
+            size_bytes: 1,
+            checksum: String::new(),
+            updated_at: Utc::now(),
+            version: 1,
+            etag: String::new(),
+            version_vector: clock,
+        };
+        let manifest = ManifestResponse {
+            blobs: vec![
+                entry(KEY_INFO_BLOB, VersionVector::new()),
+                entry("synced", vector(&[("laptop", 2)])),
+                entry("behind", vector(&[("laptop", 1), ("desktop", 1)])),
+                entry("new", vector(&[("desktop", 1)])),
+            ],
+            total_size_bytes: 4,
+            storage_limit_bytes: 100,
+        };
+
+        assert_eq!(ledger.stale_keys(&manifest), vec!["behind", "new"]);
+    }
+
+    #[test]
+    fn ledger_round_trips_through_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ledger.json");
+
+        let fresh = SyncLedger::load(&path).unwrap();
+        assert!(!fresh.device_id().is_empty());
+        fresh.save(&path).unwrap();
+        assert_eq!(
+            SyncLedger::load(&path).unwrap().device_id(),
+            fresh.device_id()
+        );
     }
 }
@@ -7,6 +7,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tracing::{info, warn};
 
+use crate::audit::{AuditCategory, AuditEvent, AuditKeys, AuditLog};
 use crate::secure_storage::SecureStorage;
 
 // ---------------------------------------------------------------------------
@@ -400,12 +401,25 @@ pub struct HiveConfig {
     pub daily_budget_usd: f64,
     pub monthly_budget_usd: f64,
 
+    // Approvals
+    /// Declarative approval policy (TOML or YAML). When unset,
+    /// `~/.hive/approval_policy.toml` is used if it exists.
+    #[serde(default)]
+    pub approval_policy_path: Option<String>,
+
     // UI
     pub theme: String,
     pub font_size: u32,
 
     // General
     pub auto_update: bool,
+    /// Release channel followed by the auto-updater.
+    #[serde(default)]
+    pub update_channel: crate::updater::UpdateChannel,
+    /// Optional release mirror used instead of GitHub (e.g. a local
+    /// `file://` directory when testing release signing).
+    #[serde(default)]
+    pub update_mirror_url: Option<String>,
     pub notifications_enabled: bool,
     pub log_level: String,
     pub close_to_tray_notice_seen: bool,
@@ -509,6 +523,26 @@ pub struct HiveConfig {
     #[serde(default)]
     pub remote_auto_start: bool,
 
+    // Terminal
+    /// Record terminal sessions (asciicast files and the shared command
+    /// history) under `~/.hive/terminal/`. Turn off to keep no record of
+    /// what was typed or printed.
+    #[serde(default = "default_terminal_recording")]
+    pub terminal_recording: bool,
+    /// Size in megabytes at which the command history is rotated. The
+    /// previous file is kept until the next rotation.
+    #[serde(default = "default_terminal_history_max_mb")]
+    pub terminal_history_max_mb: u64,
+    /// Days after which recorded history and casts are deleted; `0` keeps
+    /// them until the size limit rotates them out.
+    #[serde(default = "default_terminal_history_max_days")]
+    pub terminal_history_max_days: u32,
+    /// Workspaces whose `devcontainer.json` agent sandboxes may start. A
+    /// repository's dev container config picks the images and commands the
+    /// sandbox runs, so it is ignored unless the workspace is listed here.
+    #[serde(default)]
+    pub sandbox_project_environments: Vec<PathBuf>,
+
     // Learning Cortex
     /// Whether the cortex auto-applies self-improvement changes without user
     /// confirmation.  Defaults to `true` for a zero-config experience; users
@@ -546,6 +580,18 @@ fn default_auto_apply() -> bool {
     true
 }
 
+fn default_terminal_recording() -> bool {
+    true
+}
+
+fn default_terminal_history_max_mb() -> u64 {
+    32
+}
+
+fn default_terminal_history_max_days() -> u32 {
+    90
+}
+
 impl Default for HiveConfig {
     fn default() -> Self {
         Self {
@@ -584,9 +630,12 @@ impl Default for HiveConfig {
             speculative_show_metrics: true,
             daily_budget_usd: 10.0,
             monthly_budget_usd: 100.0,
+            approval_policy_path: None,
             theme: "HiveCode Dark".into(),
             font_size: 14,
             auto_update: true,
+            update_channel: crate::updater::UpdateChannel::default(),
+            update_mirror_url: None,
             notifications_enabled: true,
             log_level: "info".into(),
             close_to_tray_notice_seen: false,
@@ -630,6 +679,10 @@ impl Default for HiveConfig {
             remote_local_port: default_remote_local_port(),
             remote_web_port: default_remote_web_port(),
             remote_auto_start: false,
+            terminal_recording: default_terminal_recording(),
+            terminal_history_max_mb: default_terminal_history_max_mb(),
+            terminal_history_max_days: default_terminal_history_max_days(),
+            sandbox_project_environments: Vec::new(),
             obsidian_vault_path: None,
             notion_api_key: None,
             auto_apply_enabled: default_auto_apply(),
@@ -724,6 +777,19 @@ impl HiveConfig {
         Ok(())
     }
 
+    /// Whether agent sandboxes for `workspace` may use its dev container
+    /// config: true when it is, or is inside, a workspace the user opted in.
+    pub fn allows_project_environment(&self, workspace: &std::path::Path) -> bool {
+        let Ok(workspace) = workspace.canonicalize() else {
+            return false;
+        };
+        self.sandbox_project_environments.iter().any(|allowed| {
+            allowed
+                .canonicalize()
+                .is_ok_and(|allowed| workspace.starts_with(allowed))
+        })
+    }
+
     /// Migrates from old `~/.hivecode/` directory if it exists.
     pub fn migrate_from_hivecode() -> Result<bool> {
         let home = dirs::home_dir().context("Could not determine home directory")?;
@@ -807,6 +873,8 @@ pub struct ConfigManager {
     config_path: PathBuf,
     secure_storage: Option<SecureStorage>,
     keys_path: PathBuf,
+    /// Receives a record for every key/token change (never the secret itself).
+    audit_log: RwLock<Option<Arc<AuditLog>>>,
     _watcher: Option<RecommendedWatcher>,
 }
 
@@ -860,6 +928,7 @@ impl ConfigManager {
             config_path,
             secure_storage,
             keys_path,
+            audit_log: RwLock::new(None),
             _watcher: Some(watcher),
         })
     }
@@ -978,6 +1047,31 @@ impl ConfigManager {
         self.config.read().clone()
     }
 
+    /// Audit MAC/signing keys derived from this manager's secure storage, so
+    /// callers can open a keyed [`AuditLog`] without a second Argon2 run.
+    pub fn audit_keys(&self) -> Option<AuditKeys> {
+        self.secure_storage
+            .as_ref()
+            .map(AuditKeys::from_secure_storage)
+    }
+
+    /// Record every subsequent API key and OAuth token change in `log`.
+    pub fn attach_audit_log(&self, log: Arc<AuditLog>) {
+        *self.audit_log.write() = Some(log);
+    }
+
+    fn audit_key_change(&self, action: &str, key_name: &str) {
+        if let Some(log) = self.audit_log.read().as_ref()
+            && let Err(e) = log.append(
+                AuditEvent::new(AuditCategory::KeyChange, action)
+                    .actor(whoami::username())
+                    .resource(key_name),
+            )
+        {
+            warn!("Failed to audit {action} for {key_name}: {e}");
+        }
+    }
+
     /// Update the config. The closure receives a mutable reference to the
     /// config. After mutation, non-secret fields are saved to `config.json`
     /// and API keys are saved to SecureStorage.
@@ -1068,7 +1162,11 @@ impl ConfigManager {
         }
         // Persist only keys to SecureStorage (config.json is not touched)
         let config = self.config.read();
-        self.save_api_keys(&config)
+        self.save_api_keys(&config)?;
+        drop(config);
+        let action = if key.is_some() { "key_set" } else { "key_removed" };
+        self.audit_key_change(action, provider);
+        Ok(())
     }
 
     /// Persist all API keys to the encrypted key store.
@@ -1208,7 +1306,9 @@ impl ConfigManager {
         let mut key_map = load_key_map(&self.keys_path);
         let encrypted = ss.encrypt(&json)?;
         key_map.insert(key.to_string(), encrypted);
-        save_key_map(&self.keys_path, &key_map)
+        save_key_map(&self.keys_path, &key_map)?;
+        self.audit_key_change("oauth_token_set", key);
+        Ok(())
     }
 
     /// Store an OAuth token for a specific connected account.
@@ -1226,8 +1326,10 @@ impl ConfigManager {
         let json = serde_json::to_string(token)?;
         let mut key_map = load_key_map(&self.keys_path);
         let encrypted = ss.encrypt(&json)?;
-        key_map.insert(key, encrypted);
-        save_key_map(&self.keys_path, &key_map)
+        key_map.insert(key.clone(), encrypted);
+        save_key_map(&self.keys_path, &key_map)?;
+        self.audit_key_change("oauth_token_set", &key);
+        Ok(())
     }
 
     /// Remove an OAuth token for a platform.
@@ -1235,7 +1337,9 @@ impl ConfigManager {
         let key = Self::oauth_key(platform);
         let mut key_map = load_key_map(&self.keys_path);
         key_map.remove(key);
-        save_key_map(&self.keys_path, &key_map)
+        save_key_map(&self.keys_path, &key_map)?;
+        self.audit_key_change("oauth_token_removed", key);
+        Ok(())
     }
 
     /// Remove an OAuth token for a specific connected account.
@@ -1249,7 +1353,9 @@ impl ConfigManager {
         };
         let mut key_map = load_key_map(&self.keys_path);
         key_map.remove(&key);
-        save_key_map(&self.keys_path, &key_map)
+        save_key_map(&self.keys_path, &key_map)?;
+        self.audit_key_change("oauth_token_removed", &key);
+        Ok(())
     }
 
     /// Remove all OAuth tokens stored for a platform, including account-scoped tokens.
@@ -1258,7 +1364,9 @@ impl ConfigManager {
         let prefix = format!("{key}:");
         let mut key_map = load_key_map(&self.keys_path);
         key_map.retain(|candidate, _| candidate != key && !candidate.starts_with(&prefix));
-        save_key_map(&self.keys_path, &key_map)
+        save_key_map(&self.keys_path, &key_map)?;
+        self.audit_key_change("oauth_token_removed", &prefix);
+        Ok(())
     }
 
     /// Add a connected account to the config.
@@ -1957,6 +2065,7 @@ mod tests {
             config_path: config_path.clone(),
             secure_storage: Some(ss),
             keys_path: keys_path.clone(),
+            audit_log: RwLock::new(None),
             _watcher: None,
         }
     }
@@ -2143,6 +2252,28 @@ mod tests {
         assert!(mgr2.get_api_key("huggingface").is_none());
     }
 
+    #[test]
+    fn test_key_changes_are_audited_without_secrets() {
+        let (tmp, config_path, keys_path) = make_temp_config_dir();
+        let mgr = test_config_manager(&config_path, &keys_path);
+        let log = Arc::new(AuditLog::open(&tmp.path().join("audit"), mgr.audit_keys()).unwrap());
+        mgr.attach_audit_log(Arc::clone(&log));
+
+        mgr.set_api_key("anthropic", Some("sk-ant-secret".into()))
+            .unwrap();
+        mgr.set_api_key("anthropic", None).unwrap();
+
+        let records = log.records().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].category, AuditCategory::KeyChange);
+        assert_eq!(records[0].action, "key_set");
+        assert_eq!(records[0].resource, "anthropic");
+        assert_eq!(records[1].action, "key_removed");
+        let raw = std::fs::read_to_string(tmp.path().join("audit/audit.jsonl")).unwrap();
+        assert!(!raw.contains("sk-ant-secret"));
+        assert!(log.verify().unwrap().is_intact());
+    }
+
     #[test]
     fn test_import_wrong_password() {
         let (_tmp, config_path, keys_path) = make_temp_config_dir();
@@ -0,0 +1,916 @@
+//! Pluggable `hive doctor` diagnostics.
+//!
+//! Every crate that owns something worth checking implements [`Diagnostic`]
+//! for it and exposes a `register` function that adds its checks to a
+//! [`DiagnosticRegistry`]. Running the registry produces a [`DoctorReport`]
+//! that serializes to JSON for support tickets. Checks may offer a safe
+//! auto-fix, applied with [`DiagnosticRegistry::apply_fixes`].
+//!
+//! This module also carries the checks for state owned by `hive_core`: the
+//! data directory, `config.json`, the SQLite database and `~/.hive` disk
+//! usage.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::config::HiveConfig;
+
+/// How long a single check may take before it is reported as failed.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(20);
+
+// ---------------------------------------------------------------------------
+// Results
+// ---------------------------------------------------------------------------
+
+/// Result status for a single doctor health check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl std::fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckStatus::Pass => write!(f, "PASS"),
+            CheckStatus::Warn => write!(f, "WARN"),
+            CheckStatus::Fail => write!(f, "FAIL"),
+        }
+    }
+}
+
+/// A single health-check result produced by the doctor command.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorCheck {
+    pub name: String,
+    pub description: String,
+    pub status: CheckStatus,
+    pub message: String,
+    pub fix_suggestion: Option<String>,
+}
+
+impl DoctorCheck {
+    pub fn pass(name: &str, description: &str, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            status: CheckStatus::Pass,
+            message: message.into(),
+            fix_suggestion: None,
+        }
+    }
+
+    pub fn warn(name: &str, description: &str, message: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Warn,
+            ..Self::pass(name, description, message)
+        }
+    }
+
+    pub fn fail(name: &str, description: &str, message: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Fail,
+            ..Self::pass(name, description, message)
+        }
+    }
+
+    pub fn with_fix(mut self, suggestion: impl Into<String>) -> Self {
+        self.fix_suggestion = Some(suggestion.into());
+        self
+    }
+}
+
+/// Summarised pass/warn/fail counts from a doctor run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorSummary {
+    pub pass: usize,
+    pub warn: usize,
+    pub fail: usize,
+    pub total: usize,
+}
+
+impl DoctorSummary {
+    pub fn from_checks<'a>(checks: impl IntoIterator<Item = &'a DoctorCheck>) -> Self {
+        let mut summary = Self {
+            pass: 0,
+            warn: 0,
+            fail: 0,
+            total: 0,
+        };
+        for check in checks {
+            match check.status {
+                CheckStatus::Pass => summary.pass += 1,
+                CheckStatus::Warn => summary.warn += 1,
+                CheckStatus::Fail => summary.fail += 1,
+            }
+            summary.total += 1;
+        }
+        summary
+    }
+}
+
+/// One diagnostic's outcome within a [`DoctorReport`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticResult {
+    /// Stable identifier, e.g. `core.sqlite`.
+    pub id: String,
+    pub category: String,
+    #[serde(flatten)]
+    pub check: DoctorCheck,
+    /// Whether `hive doctor --fix` can repair this result.
+    pub fixable: bool,
+    pub duration_ms: u64,
+}
+
+/// What happened when an auto-fix was applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixOutcome {
+    pub id: String,
+    pub success: bool,
+    pub message: String,
+}
+
+/// Everything a doctor run found, in a form suitable for attaching to a
+/// support ticket. Secrets never appear in check messages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorReport {
+    pub hive_version: String,
+    pub os: String,
+    pub arch: String,
+    pub generated_at: DateTime<Utc>,
+    pub summary: DoctorSummary,
+    pub results: Vec<DiagnosticResult>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fixes: Vec<FixOutcome>,
+}
+
+impl DoctorReport {
+    fn new(results: Vec<DiagnosticResult>) -> Self {
+        Self {
+            hive_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            generated_at: Utc::now(),
+            summary: DoctorSummary::from_checks(results.iter().map(|r| &r.check)),
+            results,
+            fixes: Vec::new(),
+        }
+    }
+
+    /// Whether any check failed outright.
+    pub fn has_failures(&self) -> bool {
+        self.summary.fail > 0
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Diagnostic trait & context
+// ---------------------------------------------------------------------------
+
+/// Environment a doctor run inspects.
+#[derive(Debug, Clone)]
+pub struct DiagnosticContext {
+    /// The Hive data directory (normally `~/.hive`).
+    pub hive_dir: PathBuf,
+    /// Project the doctor was run from.
+    pub workspace: PathBuf,
+    /// Loaded configuration including API keys, when it could be loaded.
+    pub config: Option<HiveConfig>,
+}
+
+impl DiagnosticContext {
+    pub fn new(hive_dir: impl Into<PathBuf>, workspace: impl Into<PathBuf>) -> Self {
+        Self {
+            hive_dir: hive_dir.into(),
+            workspace: workspace.into(),
+            config: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: HiveConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+}
+
+/// A health check that `hive doctor` can run.
+#[async_trait]
+pub trait Diagnostic: Send + Sync {
+    /// Stable identifier, `<crate>.<check>`; used by `--only` and in JSON.
+    fn id(&self) -> &'static str;
+
+    /// Grouping shown in the report, e.g. `storage` or `providers`.
+    fn category(&self) -> &'static str;
+
+    async fn run(&self, ctx: &DiagnosticContext) -> DoctorCheck;
+
+    /// Whether [`fix`](Self::fix) can repair the given result. Fixes must
+    /// be safe to apply unattended: they never delete user data.
+    fn can_fix(&self, _check: &DoctorCheck) -> bool {
+        false
+    }
+
+    /// Repair the problem, returning a description of what was done.
+    async fn fix(&self, _ctx: &DiagnosticContext) -> Result<String> {
+        bail!("{} has no automatic fix", self.id())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Registry
+// ---------------------------------------------------------------------------
+
+/// The set of diagnostics a doctor run executes.
+#[derive(Default, Clone)]
+pub struct DiagnosticRegistry {
+    diagnostics: Vec<Arc<dyn Diagnostic>>,
+}
+
+impl DiagnosticRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a diagnostic. Returns an error if its id is already registered.
+    pub fn register(&mut self, diagnostic: impl Diagnostic + 'static) -> Result<()> {
+        if self.get(diagnostic.id()).is_some() {
+            bail!("Diagnostic already registered: {}", diagnostic.id());
+        }
+        debug!(id = diagnostic.id(), "registered diagnostic");
+        self.diagnostics.push(Arc::new(diagnostic));
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Arc<dyn Diagnostic>> {
+        self.diagnostics.iter().find(|d| d.id() == id)
+    }
+
+    /// Registered ids in registration order.
+    pub fn ids(&self) -> Vec<&'static str> {
+        self.diagnostics.iter().map(|d| d.id()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Keep only diagnostics whose id or category matches one of `filters`.
+    /// An id filter also matches by prefix, so `core` selects `core.*`.
+    pub fn retain_matching(&mut self, filters: &[String]) {
+        if filters.is_empty() {
+            return;
+        }
+        self.diagnostics.retain(|d| {
+            filters.iter().any(|f| {
+                d.id() == f
+                    || d.category() == f
+                    || d.id()
+                        .strip_prefix(f.as_str())
+                        .is_some_and(|rest| rest.starts_with('.'))
+            })
+        });
+    }
+
+    /// Run every diagnostic concurrently and collect the report, in
+    /// registration order.
+    pub async fn run(&self, ctx: &DiagnosticContext) -> DoctorReport {
+        let ctx = Arc::new(ctx.clone());
+        let handles: Vec<_> = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let diagnostic = Arc::clone(diagnostic);
+                let ctx = Arc::clone(&ctx);
+                tokio::spawn(async move { run_one(diagnostic.as_ref(), &ctx).await })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(handles.len());
+        for (diagnostic, handle) in self.diagnostics.iter().zip(handles) {
+            let result = match handle.await {
+                Ok(result) => result,
+                Err(e) => DiagnosticResult {
+                    id: diagnostic.id().to_string(),
+                    category: diagnostic.category().to_string(),
+                    check: DoctorCheck::fail(
+                        diagnostic.id(),
+                        "Diagnostic crashed",
+                        format!("Check panicked: {e}"),
+                    ),
+                    fixable: false,
+                    duration_ms: 0,
+                },
+            };
+            results.push(result);
+        }
+        DoctorReport::new(results)
+    }
+
+    /// Apply the auto-fix of every fixable, non-passing result in `report`,
+    /// then re-run those checks so the report reflects the new state.
+    pub async fn apply_fixes(&self, ctx: &DiagnosticContext, report: &mut DoctorReport) {
+        for result in &mut report.results {
+            if !result.fixable || result.check.status == CheckStatus::Pass {
+                continue;
+            }
+            let Some(diagnostic) = self.get(&result.id) else {
+                continue;
+            };
+            let outcome = match diagnostic.fix(ctx).await {
+                Ok(message) => FixOutcome {
+                    id: result.id.clone(),
+                    success: true,
+                    message,
+                },
+                Err(e) => {
+                    warn!(id = %result.id, error = %e, "auto-fix failed");
+                    FixOutcome {
+                        id: result.id.clone(),
+                        success: false,
+                        message: format!("{e:#}"),
+                    }
+                }
+            };
+            report.fixes.push(outcome);
+            *result = run_one(diagnostic.as_ref(), ctx).await;
+        }
+        report.summary = DoctorSummary::from_checks(report.results.iter().map(|r| &r.check));
+    }
+}
+
+async fn run_one(diagnostic: &dyn Diagnostic, ctx: &DiagnosticContext) -> DiagnosticResult {
+    let started = Instant::now();
+    let check = match tokio::time::timeout(CHECK_TIMEOUT, diagnostic.run(ctx)).await {
+        Ok(check) => check,
+        Err(_) => DoctorCheck::fail(
+            diagnostic.id(),
+            "Diagnostic timed out",
+            format!("No result after {}s", CHECK_TIMEOUT.as_secs()),
+        ),
+    };
+    DiagnosticResult {
+        id: diagnostic.id().to_string(),
+        category: diagnostic.category().to_string(),
+        fixable: check.status != CheckStatus::Pass && diagnostic.can_fix(&check),
+        check,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Core checks
+// ---------------------------------------------------------------------------
+
+/// Register the diagnostics for state owned by `hive_core`.
+pub fn register(registry: &mut DiagnosticRegistry) -> Result<()> {
+    registry.register(DataDirCheck)?;
+    registry.register(ConfigCheck)?;
+    registry.register(DatabaseCheck)?;
+    registry.register(DiskUsageCheck::default())?;
+    Ok(())
+}
+
+/// `~/.hive` and its standard subdirectories exist.
+struct DataDirCheck;
+
+impl DataDirCheck {
+    const NAME: &str = "Data Directory";
+    const DESCRIPTION: &str = "Check that ~/.hive and its subdirectories exist";
+
+    fn required_dirs(hive_dir: &Path) -> [PathBuf; 3] {
+        [
+            hive_dir.to_path_buf(),
+            hive_dir.join("conversations"),
+            hive_dir.join("logs"),
+        ]
+    }
+}
+
+#[async_trait]
+impl Diagnostic for DataDirCheck {
+    fn id(&self) -> &'static str {
+        "core.data_dir"
+    }
+
+    fn category(&self) -> &'static str {
+        "storage"
+    }
+
+    async fn run(&self, ctx: &DiagnosticContext) -> DoctorCheck {
+        let missing: Vec<_> = Self::required_dirs(&ctx.hive_dir)
+            .into_iter()
+            .filter(|dir| !dir.is_dir())
+            .map(|dir| dir.display().to_string())
+            .collect();
+        if missing.is_empty() {
+            DoctorCheck::pass(
+                Self::NAME,
+                Self::DESCRIPTION,
+                format!("Data directory found at {}", ctx.hive_dir.display()),
+            )
+        } else {
+            DoctorCheck::warn(
+                Self::NAME,
+                Self::DESCRIPTION,
+                format!("Missing: {}", missing.join(", ")),
+            )
+            .with_fix("Run 'hive doctor --fix' to create them")
+        }
+    }
+
+    fn can_fix(&self, _check: &DoctorCheck) -> bool {
+        true
+    }
+
+    async fn fix(&self, ctx: &DiagnosticContext) -> Result<String> {
+        for dir in Self::required_dirs(&ctx.hive_dir) {
+            std::fs::create_dir_all(&dir)?;
+        }
+        Ok(format!("Created {}", ctx.hive_dir.display()))
+    }
+}
+
+/// `config.json` exists, parses, and holds sensible values.
+struct ConfigCheck;
+
+impl ConfigCheck {
+    const NAME: &str = "Config File";
+    const DESCRIPTION: &str = "Check that ~/.hive/config.json is valid";
+
+    /// Problems with individual values in an otherwise well-formed config.
+    fn value_problems(config: &HiveConfig) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut urls = vec![
+            ("ollama_url", Some(&config.ollama_url)),
+            ("lmstudio_url", Some(&config.lmstudio_url)),
+        ];
+        urls.extend([
+            ("litellm_url", config.litellm_url.as_ref()),
+            ("local_provider_url", config.local_provider_url.as_ref()),
+            ("cloud_api_url", config.cloud_api_url.as_ref()),
+            ("cloud_relay_url", config.cloud_relay_url.as_ref()),
+        ]);
+        for (key, value) in urls {
+            if let Some(value) = value.filter(|v| !v.is_empty())
+                && url::Url::parse(value).is_err()
+            {
+                problems.push(format!("{key} is not a valid URL"));
+            }
+        }
+        if config.daily_budget_usd < 0.0 {
+            problems.push("daily_budget_usd is negative".into());
+        }
+        if config.monthly_budget_usd < 0.0 {
+            problems.push("monthly_budget_usd is negative".into());
+        }
+        if let Some(path) = config.approval_policy_path.as_deref()
+            && !Path::new(path).exists()
+        {
+            problems.push(format!("approval_policy_path {path} does not exist"));
+        }
+        problems
+    }
+}
+
+#[async_trait]
+impl Diagnostic for ConfigCheck {
+    fn id(&self) -> &'static str {
+        "core.config"
+    }
+
+    fn category(&self) -> &'static str {
+        "config"
+    }
+
+    async fn run(&self, ctx: &DiagnosticContext) -> DoctorCheck {
+        let path = ctx.hive_dir.join("config.json");
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return DoctorCheck::warn(
+                    Self::NAME,
+                    Self::DESCRIPTION,
+                    format!("Config file not found at {}", path.display()),
+                )
+                .with_fix("Run 'hive doctor --fix' to write a default configuration");
+            }
+            Err(e) => {
+                return DoctorCheck::fail(
+                    Self::NAME,
+                    Self::DESCRIPTION,
+                    format!("Could not read {}: {e}", path.display()),
+                );
+            }
+        };
+
+        let config: HiveConfig = match serde_json::from_str(&content) {
+            Ok(config) => config,
+            Err(e) => {
+                return DoctorCheck::fail(
+                    Self::NAME,
+                    Self::DESCRIPTION,
+                    format!("{} does not match the config schema: {e}", path.display()),
+                )
+                .with_fix("Correct the reported field, or move the file aside to regenerate it");
+            }
+        };
+
+        let problems = Self::value_problems(&config);
+        if problems.is_empty() {
+            DoctorCheck::pass(
+                Self::NAME,
+                Self::DESCRIPTION,
+                format!("Config file valid at {}", path.display()),
+            )
+        } else {
+            DoctorCheck::warn(Self::NAME, Self::DESCRIPTION, problems.join("; "))
+                .with_fix("Edit the values with 'hive config <key> <value>'")
+        }
+    }
+
+    fn can_fix(&self, check: &DoctorCheck) -> bool {
+        // Only a missing file is fixed; an invalid one may hold settings the
+        // user wants to keep.
+        check.message.starts_with("Config file not found")
+    }
+
+    async fn fix(&self, ctx: &DiagnosticContext) -> Result<String> {
+        let path = ctx.hive_dir.join("config.json");
+        if path.exists() {
+            bail!("{} already exists", path.display());
+        }
+        std::fs::create_dir_all(&ctx.hive_dir)?;
+        HiveConfig::default().save_to_path(&path)?;
+        Ok(format!("Wrote default config to {}", path.display()))
+    }
+}
+
+/// The SQLite database passes `PRAGMA integrity_check`.
+struct DatabaseCheck;
+
+impl DatabaseCheck {
+    const NAME: &str = "SQLite Database";
+    const DESCRIPTION: &str = "Check integrity of ~/.hive/memory.db";
+}
+
+#[async_trait]
+impl Diagnostic for DatabaseCheck {
+    fn id(&self) -> &'static str {
+        "core.sqlite"
+    }
+
+    fn category(&self) -> &'static str {
+        "storage"
+    }
+
+    async fn run(&self, ctx: &DiagnosticContext) -> DoctorCheck {
+        let path = ctx.hive_dir.join("memory.db");
+        if !path.exists() {
+            return DoctorCheck::pass(Self::NAME, Self::DESCRIPTION, "Database not created yet");
+        }
+
+        let result = tokio::task::spawn_blocking(move || -> rusqlite::Result<Vec<String>> {
+            let conn = rusqlite::Connection::open_with_flags(
+                &path,
+                rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+            )?;
+            let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            rows.collect()
+        })
+        .await;
+
+        match result {
+            Ok(Ok(rows)) if rows.len() == 1 && rows[0] == "ok" => {
+                DoctorCheck::pass(Self::NAME, Self::DESCRIPTION, "Integrity check passed")
+            }
+            Ok(Ok(rows)) => DoctorCheck::fail(
+                Self::NAME,
+                Self::DESCRIPTION,
+                format!(
+                    "Integrity check reported {} problem(s): {}",
+                    rows.len(),
+                    rows.iter().take(3).cloned().collect::<Vec<_>>().join("; ")
+                ),
+            )
+            .with_fix(
+                "Quit Hive, back up memory.db, and recover it with 'sqlite3 memory.db .recover'",
+            ),
+            Ok(Err(e)) => DoctorCheck::fail(
+                Self::NAME,
+                Self::DESCRIPTION,
+                format!("Could not open database: {e}"),
+            ),
+            Err(e) => DoctorCheck::fail(Self::NAME, Self::DESCRIPTION, e.to_string()),
+        }
+    }
+}
+
+/// Total size of `~/.hive`, with old logs as the safe thing to reclaim.
+struct DiskUsageCheck {
+    warn_bytes: u64,
+    log_retention: Duration,
+}
+
+impl Default for DiskUsageCheck {
+    fn default() -> Self {
+        Self {
+            warn_bytes: 5 * 1024 * 1024 * 1024,
+            log_retention: Duration::from_secs(14 * 24 * 60 * 60),
+        }
+    }
+}
+
+impl DiskUsageCheck {
+    const NAME: &str = "Data Disk Usage";
+    const DESCRIPTION: &str = "Check how much space ~/.hive uses";
+
+    /// Log files last modified before the retention window.
+    fn old_logs(&self, hive_dir: &Path) -> Vec<PathBuf> {
+        let cutoff = SystemTime::now() - self.log_retention;
+        let Ok(entries) = std::fs::read_dir(hive_dir.join("logs")) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter(|entry| {
+                entry
+                    .metadata()
+                    .ok()
+                    .filter(|meta| meta.is_file())
+                    .and_then(|meta| meta.modified().ok())
+                    .is_some_and(|modified| modified < cutoff)
+            })
+            .map(|entry| entry.path())
+            .collect()
+    }
+}
+
+/// Recursive size of `path` in bytes. Symlinks are not followed.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| dir_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[async_trait]
+impl Diagnostic for DiskUsageCheck {
+    fn id(&self) -> &'static str {
+        "core.disk_usage"
+    }
+
+    fn category(&self) -> &'static str {
+        "storage"
+    }
+
+    async fn run(&self, ctx: &DiagnosticContext) -> DoctorCheck {
+        let hive_dir = ctx.hive_dir.clone();
+        let entries = tokio::task::spawn_blocking(move || {
+            let mut entries: Vec<(String, u64)> = std::fs::read_dir(&hive_dir)
+                .map(|dir| {
+                    dir.flatten()
+                        .map(|e| {
+                            (
+                                e.file_name().to_string_lossy().to_string(),
+                                dir_size(&e.path()),
+                            )
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            entries.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+            entries
+        })
+        .await
+        .unwrap_or_default();
+
+        let total: u64 = entries.iter().map(|(_, size)| size).sum();
+        let largest = entries
+            .iter()
+            .take(3)
+            .map(|(name, size)| format!("{name} {}", format_bytes(*size)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let message = if largest.is_empty() {
+            format!("{} used", format_bytes(total))
+        } else {
+            format!("{} used (largest: {largest})", format_bytes(total))
+        };
+
+        if total < self.warn_bytes {
+            DoctorCheck::pass(Self::NAME, Self::DESCRIPTION, message)
+        } else {
+            DoctorCheck::warn(Self::NAME, Self::DESCRIPTION, message).with_fix(
+                "Run 'hive doctor --fix' to remove old logs, or prune checkpoints and indexes you no longer need",
+            )
+        }
+    }
+
+    fn can_fix(&self, _check: &DoctorCheck) -> bool {
+        true
+    }
+
+    async fn fix(&self, ctx: &DiagnosticContext) -> Result<String> {
+        let old = self.old_logs(&ctx.hive_dir);
+        let mut freed = 0;
+        for path in &old {
+            freed += dir_size(path);
+            std::fs::remove_file(path)?;
+        }
+        Ok(format!(
+            "Removed {} log file(s) older than {} days ({})",
+            old.len(),
+            self.log_retention.as_secs() / 86_400,
+            format_bytes(freed)
+        ))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(dir: &Path) -> DiagnosticContext {
+        DiagnosticContext::new(dir.join(".hive"), dir)
+    }
+
+    fn core_registry() -> DiagnosticRegistry {
+        let mut registry = DiagnosticRegistry::new();
+        register(&mut registry).unwrap();
+        registry
+    }
+
+    #[test]
+    fn duplicate_ids_are_rejected() {
+        let mut registry = core_registry();
+        assert!(registry.register(DataDirCheck).is_err());
+        assert_eq!(registry.len(), 4);
+    }
+
+    #[test]
+    fn retain_matching_by_id_prefix_and_category() {
+        let mut registry = core_registry();
+        registry.retain_matching(&["storage".into()]);
+        assert_eq!(
+            registry.ids(),
+            ["core.data_dir", "core.sqlite", "core.disk_usage"]
+        );
+
+        let mut registry = core_registry();
+        registry.retain_matching(&["core".into()]);
+        assert_eq!(registry.len(), 4);
+
+        let mut registry = core_registry();
+        registry.retain_matching(&["core.config".into(), "cor".into()]);
+        assert_eq!(registry.ids(), ["core.config"]);
+    }
+
+    #[tokio::test]
+    async fn fresh_install_is_fixed() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = context(dir.path());
+        let registry = core_registry();
+
+        let mut report = registry.run(&ctx).await;
+        let by_id = |report: &DoctorReport, id: &str| {
+            report.results.iter().find(|r| r.id == id).unwrap().clone()
+        };
+        assert_eq!(
+            by_id(&report, "core.data_dir").check.status,
+            CheckStatus::Warn
+        );
+        assert!(by_id(&report, "core.config").fixable);
+        assert_eq!(
+            by_id(&report, "core.sqlite").check.status,
+            CheckStatus::Pass
+        );
+
+        registry.apply_fixes(&ctx, &mut report).await;
+        assert_eq!(report.fixes.len(), 2);
+        assert!(report.fixes.iter().all(|f| f.success));
+        assert_eq!(report.summary.pass, 4);
+        assert!(ctx.hive_dir.join("config.json").is_file());
+        assert!(ctx.hive_dir.join("logs").is_dir());
+    }
+
+    #[tokio::test]
+    async fn invalid_config_fails_without_fix() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = context(dir.path());
+        std::fs::create_dir_all(&ctx.hive_dir).unwrap();
+        std::fs::write(ctx.hive_dir.join("config.json"), r#"{"font_size": "big"}"#).unwrap();
+
+        let check = ConfigCheck.run(&ctx).await;
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.message.contains("schema"));
+        assert!(!ConfigCheck.can_fix(&check));
+
+        let config = HiveConfig {
+            ollama_url: "not a url".into(),
+            ..Default::default()
+        };
+        config
+            .save_to_path(&ctx.hive_dir.join("config.json"))
+            .unwrap();
+        let check = ConfigCheck.run(&ctx).await;
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.message.contains("ollama_url"));
+    }
+
+    #[tokio::test]
+    async fn sqlite_integrity() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = context(dir.path());
+        std::fs::create_dir_all(&ctx.hive_dir).unwrap();
+
+        let db = ctx.hive_dir.join("memory.db");
+        let conn = rusqlite::Connection::open(&db).unwrap();
+        conn.execute_batch("CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (1);")
+            .unwrap();
+        drop(conn);
+        assert_eq!(DatabaseCheck.run(&ctx).await.status, CheckStatus::Pass);
+
+        std::fs::write(&db, b"definitely not sqlite").unwrap();
+        assert_eq!(DatabaseCheck.run(&ctx).await.status, CheckStatus::Fail);
+    }
+
+    #[tokio::test]
+    async fn disk_usage_fix_removes_only_old_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = context(dir.path());
+        let logs = ctx.hive_dir.join("logs");
+        std::fs::create_dir_all(&logs).unwrap();
+        std::fs::write(logs.join("hive.log"), "recent").unwrap();
+
+        let check = DiskUsageCheck {
+            warn_bytes: 1,
+            log_retention: Duration::ZERO,
+        };
+        let result = check.run(&ctx).await;
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert!(result.message.contains("logs"));
+
+        let retaining = DiskUsageCheck::default();
+        assert!(retaining.fix(&ctx).await.unwrap().starts_with("Removed 0"));
+        assert!(logs.join("hive.log").exists());
+
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(check.fix(&ctx).await.unwrap().starts_with("Removed 1"));
+        assert!(!logs.join("hive.log").exists());
+    }
+
+    #[tokio::test]
+    async fn report_serializes_flat_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = core_registry().run(&context(dir.path())).await;
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["summary"]["total"], 4);
+        assert_eq!(json["results"][0]["id"], "core.data_dir");
+        assert_eq!(json["results"][0]["status"], "Warn");
+        assert!(json.get("fixes").is_none());
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.0 GB");
+    }
+}
@@ -2,9 +2,12 @@ use anyhow::{Context, Result, bail};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::sync::Arc;
 use tracing::debug;
 use uuid::Uuid;
 
+use crate::audit::{AuditCategory, AuditEvent, AuditLog};
+
 // ---------------------------------------------------------------------------
 // Data types
 // ---------------------------------------------------------------------------
@@ -108,6 +111,9 @@ pub struct EnterpriseService {
     teams: Vec<Team>,
     audit_log: Vec<AuditEntry>,
     usage_metrics: Vec<UsageMetric>,
+    /// Tamper-evident sink that mirrors every audit entry.
+    #[serde(skip)]
+    chained_log: Option<Arc<AuditLog>>,
 }
 
 impl EnterpriseService {
@@ -117,9 +123,16 @@ impl EnterpriseService {
             teams: Vec::new(),
             audit_log: Vec::new(),
             usage_metrics: Vec::new(),
+            chained_log: None,
         }
     }
 
+    /// Mirror every subsequent [`EnterpriseService::log_audit`] call into a
+    /// hash-chained [`AuditLog`].
+    pub fn attach_audit_log(&mut self, log: Arc<AuditLog>) {
+        self.chained_log = Some(log);
+    }
+
     // -----------------------------------------------------------------------
     // Team management
     // -----------------------------------------------------------------------
@@ -307,6 +320,26 @@ impl EnterpriseService {
             entry.action, entry.user_name, entry.resource_id
         );
 
+        if let Some(log) = &self.chained_log {
+            let category = match entry.action {
+                AuditAction::ApiKeyAccess => AuditCategory::KeyChange,
+                AuditAction::SecurityEvent => AuditCategory::Security,
+                _ => AuditCategory::Enterprise,
+            };
+            let event = AuditEvent::new(category, format!("{:?}", entry.action))
+                .actor(entry.user_id.clone())
+                .resource(format!("{}:{}", entry.resource_type, entry.resource_id))
+                .details(serde_json::json!({
+                    "entry_id": entry.id,
+                    "user_name": entry.user_name,
+                    "details": entry.details,
+                    "ip_address": entry.ip_address,
+                }));
+            if let Err(e) = log.append(event) {
+                tracing::warn!("Failed to append enterprise audit entry: {e}");
+            }
+        }
+
         let clone = entry.clone();
         self.audit_log.push(entry);
         clone
@@ -653,6 +686,40 @@ mod tests {
     // 13. get_audit_log with limit
     // -----------------------------------------------------------------------
 
+    #[test]
+    fn test_log_audit_mirrors_into_chained_log() {
+        let tmp = tempfile::tempdir().unwrap();
+        let chained = Arc::new(AuditLog::open(tmp.path(), None).unwrap());
+        let mut svc = EnterpriseService::new();
+        svc.attach_audit_log(Arc::clone(&chained));
+
+        svc.log_audit(
+            "user-1",
+            "Alice",
+            AuditAction::ChangeRole,
+            "member",
+            "m-1",
+            Some("Member -> Admin".into()),
+            None,
+        );
+        svc.log_audit(
+            "user-1",
+            "Alice",
+            AuditAction::ApiKeyAccess,
+            "provider",
+            "openai",
+            None,
+            None,
+        );
+
+        let records = chained.records().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].category, AuditCategory::Enterprise);
+        assert_eq!(records[0].resource, "member:m-1");
+        assert_eq!(records[1].category, AuditCategory::KeyChange);
+        assert!(chained.verify().unwrap().is_intact());
+    }
+
     #[test]
     fn test_get_audit_log_limit() {
         let mut svc = EnterpriseService::new();
@@ -3,6 +3,8 @@
 //! This crate provides the foundational types, configuration management,
 //! persistence layer, and shared services used across all other Hive crates.
 
+/// Tamper-evident, hash-chained audit log with signed checkpoints.
+pub mod audit;
 /// Background task scheduling and lifecycle management.
 pub mod background;
 /// Interactive whiteboard canvas with element and connection management.
@@ -19,6 +21,8 @@ pub mod config;
 pub mod context;
 /// Conversation persistence and search using JSON files.
 pub mod conversations;
+/// Pluggable `hive doctor` health checks with JSON reports and auto-fixes.
+pub mod diagnostics;
 /// Enterprise team management, audit logging, and usage tracking.
 pub mod enterprise;
 /// Error classification, severity levels, and user-friendly error messages.
@@ -44,6 +48,10 @@ pub mod theme_manager;
 /// Auto-update service — checks GitHub for newer releases and installs updates.
 pub mod updater;
 
+pub use audit::{
+    AuditCategory, AuditCheckpoint, AuditEvent, AuditKeys, AuditLog, AuditRecord,
+    AuditVerification, TamperReport,
+};
 pub use background::{BackgroundService, BackgroundTask, TaskStatus};
 pub use canvas::{CanvasElement, CanvasState, Connection, ElementType, LiveCanvas, Point, Size};
 pub use channels::{AgentChannel, ChannelMessage, ChannelStore, ChannelThread, MessageAuthor};
@@ -57,6 +65,10 @@ pub use context::{
     model_context_size,
 };
 pub use conversations::{Conversation, ConversationStore, ConversationSummary, StoredMessage};
+pub use diagnostics::{
+    CheckStatus, Diagnostic, DiagnosticContext, DiagnosticRegistry, DiagnosticResult, DoctorCheck,
+    DoctorReport, DoctorSummary, FixOutcome,
+};
 pub use enterprise::{
     AuditAction, AuditEntry, EnterpriseService, Team, TeamMember, TeamRole, UsageMetric,
 };
@@ -73,4 +85,4 @@ pub use secure_storage::SecureStorage;
 pub use security::{SandboxPolicy, SecurityGateway};
 pub use session::SessionState;
 pub use theme_manager::{ThemeColors, ThemeDefinition, ThemeFonts, ThemeManager};
-pub use updater::{UpdateInfo, UpdateService};
+pub use updater::{UpdateChannel, UpdateInfo, UpdateService};
@@ -175,6 +175,18 @@ impl SecureStorage {
         Err(anyhow::anyhow!("Decryption failed: {primary_err}"))
     }
 
+    /// Derive a stable 256-bit subkey bound to `purpose` (HMAC-SHA256 over
+    /// the storage key). Lets other subsystems — e.g. the audit log — key
+    /// their MACs and signatures from the vault without ever holding the
+    /// encryption key itself.
+    pub fn derive_subkey(&self, purpose: &str) -> [u8; 32] {
+        use hmac::{Hmac, Mac};
+        let mut mac = <Hmac<sha2::Sha256> as Mac>::new_from_slice(&self.key_material)
+            .expect("HMAC accepts any key length");
+        mac.update(purpose.as_bytes());
+        mac.finalize().into_bytes().into()
+    }
+
     /// Returns the default salt file path: `~/.hive/storage.salt`.
     fn default_salt_path() -> Result<PathBuf> {
         let home = dirs::home_dir().context("Could not determine home directory")?;
@@ -279,6 +291,18 @@ mod tests {
         SecureStorage::from_salt_and_passphrase(&salt, passphrase).unwrap()
     }
 
+    // ---- subkey derivation ----
+
+    #[test]
+    fn derive_subkey_is_stable_and_purpose_bound() {
+        let tmp = TempDir::new().unwrap();
+        let storage = storage_in_with_passphrase(tmp.path(), None);
+        let a = storage.derive_subkey("hive-audit-mac-v1");
+        assert_eq!(a, storage.duplicate().derive_subkey("hive-audit-mac-v1"));
+        assert_ne!(a, storage.derive_subkey("hive-audit-checkpoint-v1"));
+        assert_ne!(&a[..], &storage.key_material[..]);
+    }
+
     // ---- basic encrypt / decrypt (same as before) ----
 
     #[test]
@@ -179,6 +179,29 @@ impl SecurityGateway {
 
     /// Validate a file path for access.
     pub fn check_path(&self, path: &Path) -> Result<(), String> {
+        self.check_path_lexical(path)?;
+        let path_str = path.to_string_lossy();
+
+        // Resolve to catch traversal — reject if path can't be resolved
+        let resolved = path
+            .canonicalize()
+            .map_err(|_| format!("Cannot resolve path: {path_str}"))?;
+        let resolved_str = resolved.to_string_lossy();
+        for prefix in &self.blocked_path_prefixes {
+            if resolved_str.contains(prefix) {
+                return Err(format!(
+                    "Path traversal to sensitive directory blocked: {prefix}"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lexical half of [`check_path`](Self::check_path): rejects system roots
+    /// and sensitive prefixes without touching the filesystem, so it also
+    /// works for paths that do not exist yet.
+    pub fn check_path_lexical(&self, path: &Path) -> Result<(), String> {
         let path_str = path.to_string_lossy();
 
         // Block system roots (Unix "/" and any Windows drive root like "C:\", "D:/", "E:")
@@ -199,20 +222,6 @@ impl SecurityGateway {
                 return Err(format!("Access to sensitive path blocked: {prefix}"));
             }
         }
-
-        // Resolve to catch traversal — reject if path can't be resolved
-        let resolved = path
-            .canonicalize()
-            .map_err(|_| format!("Cannot resolve path: {path_str}"))?;
-        let resolved_str = resolved.to_string_lossy();
-        for prefix in &self.blocked_path_prefixes {
-            if resolved_str.contains(prefix) {
-                return Err(format!(
-                    "Path traversal to sensitive directory blocked: {prefix}"
-                ));
-            }
-        }
-
         Ok(())
     }
 
@@ -1,33 +1,106 @@
 //! Auto-update service — checks GitHub releases for newer versions and
 //! performs in-place binary replacement on all platforms.
 //!
+//! Every release ships a `SHA256SUMS` manifest signed with the Hive release
+//! key (minisign format, ed25519). Before anything is extracted the updater:
+//!
+//! 1. verifies the manifest signature against [`RELEASE_PUBLIC_KEY`],
+//! 2. checks the trusted comment names the version being installed (so an
+//!    old, validly signed manifest cannot be replayed as a newer release),
+//! 3. checks the downloaded archive's SHA-256 against the manifest entry.
+//!
+//! The new binary is then swapped in atomically and must pass a
+//! `--version` health check; otherwise the previous binary is restored.
+//!
 //! The update strategy varies by platform:
-//! - **macOS (Homebrew)**: `brew upgrade hive` (preferred) or binary swap
+//! - **Homebrew installs** (macOS or Linux): not self-updated. Homebrew
+//!   does not check the release signature, so the updater defers to
+//!   `brew upgrade` instead of swapping a binary Homebrew owns.
+//! - **macOS**: Downloads the tarball and replaces the binary in-place
 //! - **Linux**: Downloads the tarball and replaces the binary in-place
 //! - **Windows**: Downloads the zip, extracts, and replaces the exe
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{
     Arc,
     atomic::{AtomicBool, Ordering},
 };
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result, bail};
+use base64::Engine;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tracing::{info, warn};
 
 /// Repository owner/name on GitHub.
 const GITHUB_REPO: &str = "PatSul/Hive";
 
+/// Minisign public key used to sign release manifests.
+///
+/// Pinned at compile time — rotating the release key requires shipping a
+/// release signed with the old key that carries the new one.
+///
+/// Release CI signs with the matching secret key (`MINISIGN_SECRET_KEY`) and
+/// checks the result against this constant before publishing.
+pub const RELEASE_PUBLIC_KEY: &str = "RWSW5xd4ppnosCU9d+gPx5XOYRGSZjDpc+4QXNetSpFx58nXFCcQHto5";
+
+/// Homebrew tap formula that manages Homebrew installs.
+const HOMEBREW_FORMULA: &str = "PatSul/tap/hive";
+
+/// Name of the signed checksum manifest attached to every release.
+const MANIFEST_ASSET: &str = "SHA256SUMS";
+
+/// Name of the minisign signature for [`MANIFEST_ASSET`].
+const SIGNATURE_ASSET: &str = "SHA256SUMS.minisig";
+
+/// How long the post-install `--version` health check may run.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Release channel the updater follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    /// Published, non-prerelease GitHub releases.
+    #[default]
+    Stable,
+    /// Newest release including prereleases.
+    Beta,
+}
+
+impl UpdateChannel {
+    /// Lowercase channel name as used in config and mirror paths.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+        }
+    }
+}
+
+impl std::fmt::Display for UpdateChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Information about an available update.
 #[derive(Debug, Clone)]
 pub struct UpdateInfo {
     /// The new version string (e.g. "0.3.0").
     pub version: String,
+    /// Channel the release was found on.
+    pub channel: UpdateChannel,
     /// URL to the release page on GitHub.
     pub release_url: String,
     /// URL to the platform-appropriate asset for direct download.
     pub asset_url: String,
+    /// URL to the `SHA256SUMS` manifest for the release.
+    pub manifest_url: String,
+    /// URL to the minisign signature over the manifest.
+    pub signature_url: String,
     /// Release notes / body text (markdown).
     pub release_notes: String,
 }
@@ -40,6 +113,9 @@ pub struct UpdateService {
 
 struct UpdateServiceInner {
     current_version: String,
+    channel: UpdateChannel,
+    mirror_url: Option<String>,
+    public_key: String,
     update_info: RwLock<Option<UpdateInfo>>,
     checking: AtomicBool,
     updating: AtomicBool,
@@ -48,9 +124,51 @@ struct UpdateServiceInner {
 impl UpdateService {
     /// Create a new update service with the current running version.
     pub fn new(current_version: impl Into<String>) -> Self {
+        Self::with_options(current_version, UpdateChannel::Stable, None)
+    }
+
+    /// Create an update service following `channel`, optionally reading
+    /// releases from a mirror instead of GitHub.
+    ///
+    /// A mirror serves `{mirror}/{channel}.json` in the GitHub release
+    /// format; asset URLs may be relative to the mirror. `file://` mirrors
+    /// are supported for local testing.
+    pub fn with_options(
+        current_version: impl Into<String>,
+        channel: UpdateChannel,
+        mirror_url: Option<String>,
+    ) -> Self {
+        Self::build(
+            current_version.into(),
+            channel,
+            mirror_url.filter(|u| !u.trim().is_empty()),
+            RELEASE_PUBLIC_KEY.to_string(),
+        )
+    }
+
+    /// Replace the pinned release key. Intended for self-built release
+    /// pipelines and tests that sign with their own key.
+    pub fn with_public_key(self, public_key: impl Into<String>) -> Self {
+        Self::build(
+            self.inner.current_version.clone(),
+            self.inner.channel,
+            self.inner.mirror_url.clone(),
+            public_key.into(),
+        )
+    }
+
+    fn build(
+        current_version: String,
+        channel: UpdateChannel,
+        mirror_url: Option<String>,
+        public_key: String,
+    ) -> Self {
         Self {
             inner: Arc::new(UpdateServiceInner {
-                current_version: current_version.into(),
+                current_version,
+                channel,
+                mirror_url,
+                public_key,
                 update_info: RwLock::new(None),
                 checking: AtomicBool::new(false),
                 updating: AtomicBool::new(false),
@@ -63,6 +181,16 @@ impl UpdateService {
         &self.inner.current_version
     }
 
+    /// Returns the release channel this service follows.
+    pub fn channel(&self) -> UpdateChannel {
+        self.inner.channel
+    }
+
+    /// Returns the configured mirror URL, if any.
+    pub fn mirror_url(&self) -> Option<&str> {
+        self.inner.mirror_url.as_deref()
+    }
+
     /// Returns the cached update info if a newer version is available.
     pub fn available_update(&self) -> Option<UpdateInfo> {
         self.inner.update_info.read().clone()
@@ -92,15 +220,15 @@ impl UpdateService {
         match &result {
             Ok(Some(info)) => {
                 info!(
-                    "Update available: {} -> {}",
-                    self.inner.current_version, info.version
+                    "Update available ({}): {} -> {}",
+                    info.channel, self.inner.current_version, info.version
                 );
                 *self.inner.update_info.write() = Some(info.clone());
             }
             Ok(None) => {
                 info!(
-                    "No update available (current: {})",
-                    self.inner.current_version
+                    "No update available (current: {}, channel: {})",
+                    self.inner.current_version, self.inner.channel
                 );
                 *self.inner.update_info.write() = None;
             }
@@ -113,54 +241,46 @@ impl UpdateService {
     }
 
     fn do_check(&self) -> Result<Option<UpdateInfo>> {
-        let url = format!("https://api.github.com/repos/{GITHUB_REPO}/releases/latest");
-
         // Use a blocking reqwest client (we're on a background thread).
-        let client = reqwest::blocking::Client::builder()
-            .user_agent("hive-updater/1.0")
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .context("Failed to build HTTP client")?;
-
-        let resp = client.get(&url).send().context("Failed to reach GitHub")?;
-
-        if !resp.status().is_success() {
-            bail!("GitHub API returned status {}", resp.status());
-        }
-
-        let body: serde_json::Value = resp.json().context("Failed to parse response")?;
-
-        let tag = body["tag_name"]
-            .as_str()
-            .context("No tag_name in response")?;
-
-        let remote_version = tag.strip_prefix('v').unwrap_or(tag);
+        let client = http_client(Duration::from_secs(10))?;
+
+        let (release, base_url) = match &self.inner.mirror_url {
+            Some(mirror) => {
+                let base = mirror.trim_end_matches('/');
+                let url = format!("{base}/{}.json", self.inner.channel);
+                let body = fetch_bytes(&client, &url)?;
+                let release: serde_json::Value =
+                    serde_json::from_slice(&body).context("Failed to parse mirror release")?;
+                (release, Some(format!("{base}/")))
+            }
+            None => (self.fetch_github_release(&client)?, None),
+        };
+
+        parse_release(
+            &release,
+            self.inner.channel,
+            &self.inner.current_version,
+            base_url.as_deref(),
+        )
+    }
 
-        if !is_newer(remote_version, &self.inner.current_version) {
-            return Ok(None);
+    /// Fetch the newest release for the configured channel from GitHub.
+    fn fetch_github_release(&self, client: &reqwest::blocking::Client) -> Result<serde_json::Value> {
+        match self.inner.channel {
+            UpdateChannel::Stable => {
+                let url = format!("https://api.github.com/repos/{GITHUB_REPO}/releases/latest");
+                let body = fetch_bytes(client, &url)?;
+                serde_json::from_slice(&body).context("Failed to parse response")
+            }
+            UpdateChannel::Beta => {
+                let url =
+                    format!("https://api.github.com/repos/{GITHUB_REPO}/releases?per_page=20");
+                let body = fetch_bytes(client, &url)?;
+                let releases: Vec<serde_json::Value> =
+                    serde_json::from_slice(&body).context("Failed to parse response")?;
+                newest_release(releases).context("No published releases found")
+            }
         }
-
-        // Find the right asset for this platform.
-        let asset_name = platform_asset_name();
-        let assets = body["assets"].as_array().context("No assets array")?;
-
-        let asset_url = assets
-            .iter()
-            .find(|a| a["name"].as_str() == Some(asset_name))
-            .and_then(|a| a["browser_download_url"].as_str())
-            .map(String::from)
-            .context(format!("Asset {asset_name} not found in release"))?;
-
-        let release_url = body["html_url"].as_str().unwrap_or("").to_string();
-
-        let release_notes = body["body"].as_str().unwrap_or("").to_string();
-
-        Ok(Some(UpdateInfo {
-            version: remote_version.to_string(),
-            release_url,
-            asset_url,
-            release_notes,
-        }))
     }
 
     /// Download and install the update. Blocking — call from a background thread.
@@ -182,44 +302,64 @@ impl UpdateService {
     }
 
     fn do_install(&self, info: &UpdateInfo) -> Result<PathBuf> {
-        info!("Downloading update v{}...", info.version);
-
-        let client = reqwest::blocking::Client::builder()
-            .user_agent("hive-updater/1.0")
-            .timeout(std::time::Duration::from_secs(300))
-            .build()
-            .context("Failed to build HTTP client")?;
+        let current_exe =
+            std::env::current_exe().context("Cannot determine current executable path")?;
+        if is_homebrew_install(&current_exe) {
+            bail!(
+                "Hive v{} is available. This copy is managed by Homebrew; \
+                 update it with `brew upgrade {HOMEBREW_FORMULA}`",
+                info.version
+            );
+        }
 
-        let resp = client
-            .get(&info.asset_url)
-            .send()
-            .context("Failed to download update")?;
+        info!("Downloading update v{}...", info.version);
 
-        if !resp.status().is_success() {
-            bail!("Download failed with status {}", resp.status());
+        let client = http_client(Duration::from_secs(300))?;
+
+        // Verify the signed manifest before touching the archive.
+        let manifest = fetch_bytes(&client, &info.manifest_url)
+            .context("Failed to download release manifest")?;
+        let signature = fetch_bytes(&client, &info.signature_url)
+            .context("Failed to download manifest signature")?;
+        let signature =
+            String::from_utf8(signature).context("Manifest signature is not valid UTF-8")?;
+
+        let trusted_comment = verify_minisign(&self.inner.public_key, &manifest, &signature)
+            .context("Release manifest signature verification failed")?;
+        if !comment_names_version(&trusted_comment, &info.version) {
+            bail!(
+                "Signed manifest is for a different release (trusted comment: {trusted_comment:?})"
+            );
         }
 
-        let bytes = resp.bytes().context("Failed to read response body")?;
+        let manifest = String::from_utf8(manifest).context("Manifest is not valid UTF-8")?;
+        let asset_name = platform_asset_name();
+        let expected = manifest_digest(&manifest, asset_name)
+            .with_context(|| format!("{asset_name} is not listed in the signed manifest"))?;
 
-        let current_exe =
-            std::env::current_exe().context("Cannot determine current executable path")?;
+        let bytes = fetch_bytes(&client, &info.asset_url).context("Failed to download update")?;
+        let actual = hex::encode(Sha256::digest(&bytes));
+        if !actual.eq_ignore_ascii_case(&expected) {
+            bail!("Checksum mismatch for {asset_name}: expected {expected}, got {actual}");
+        }
+        info!("Verified signature and checksum for v{}", info.version);
 
         let tmp_dir = tempfile::tempdir().context("Failed to create temp directory")?;
 
         // Platform-specific extraction and installation.
         #[cfg(target_os = "macos")]
         {
-            self.install_macos(&bytes, &current_exe, tmp_dir.path())?;
+            self.install_macos(&bytes, &current_exe, tmp_dir.path(), &info.version)?;
         }
 
         #[cfg(target_os = "linux")]
         {
-            self.install_linux(&bytes, &current_exe, tmp_dir.path())?;
+            self.install_linux(&bytes, &current_exe, tmp_dir.path(), &info.version)?;
         }
 
         #[cfg(target_os = "windows")]
         {
-            self.install_windows(&bytes, &current_exe, tmp_dir.path())?;
+            self.install_windows(&bytes, &current_exe, tmp_dir.path(), &info.version)?;
         }
 
         info!("Update v{} installed successfully", info.version);
@@ -230,49 +370,12 @@ impl UpdateService {
     fn install_macos(
         &self,
         data: &[u8],
-        current_exe: &std::path::Path,
-        tmp: &std::path::Path,
+        current_exe: &Path,
+        tmp: &Path,
+        version: &str,
     ) -> Result<()> {
-        // First try Homebrew upgrade if available.
-        if let Ok(output) = std::process::Command::new("brew")
-            .args(["upgrade", "PatSul/tap/hive"])
-            .output()
-        {
-            if output.status.success() {
-                info!("Updated via Homebrew");
-                return Ok(());
-            }
-            warn!("Homebrew upgrade failed, falling back to binary swap");
-        }
-
-        // Fallback: extract tar.gz and replace binary.
-        let archive_path = tmp.join("update.tar.gz");
-        std::fs::write(&archive_path, data).context("Failed to write archive")?;
-
-        let status = std::process::Command::new("tar")
-            .args(["xzf", &archive_path.to_string_lossy()])
-            .current_dir(tmp)
-            .status()
-            .context("Failed to run tar")?;
-
-        if !status.success() {
-            bail!("tar extraction failed");
-        }
-
-        let new_binary = tmp.join("hive");
-        if !new_binary.exists() {
-            bail!("Extracted binary not found");
-        }
-
-        // Swap the binary: rename old, copy new, delete old.
-        let backup = current_exe.with_extension("old");
-        std::fs::rename(current_exe, &backup).context("Failed to back up current binary")?;
-
-        if let Err(e) = std::fs::copy(&new_binary, current_exe) {
-            // Restore backup on failure.
-            let _ = std::fs::rename(&backup, current_exe);
-            bail!("Failed to install new binary: {e}");
-        }
+        let new_binary = extract_tarball(data, tmp)?;
+        swap_binary(&new_binary, current_exe, version)?;
 
         // Remove quarantine attribute.
         let _ = std::process::Command::new("xattr")
@@ -283,7 +386,6 @@ impl UpdateService {
             ])
             .output();
 
-        let _ = std::fs::remove_file(&backup);
         Ok(())
     }
 
@@ -291,51 +393,21 @@ impl UpdateService {
     fn install_linux(
         &self,
         data: &[u8],
-        current_exe: &std::path::Path,
-        tmp: &std::path::Path,
+        current_exe: &Path,
+        tmp: &Path,
+        version: &str,
     ) -> Result<()> {
-        let archive_path = tmp.join("update.tar.gz");
-        std::fs::write(&archive_path, data).context("Failed to write archive")?;
-
-        let status = std::process::Command::new("tar")
-            .args(["xzf", &archive_path.to_string_lossy()])
-            .current_dir(tmp)
-            .status()
-            .context("Failed to run tar")?;
-
-        if !status.success() {
-            bail!("tar extraction failed");
-        }
-
-        let new_binary = tmp.join("hive");
-        if !new_binary.exists() {
-            bail!("Extracted binary not found");
-        }
-
-        let backup = current_exe.with_extension("old");
-        std::fs::rename(current_exe, &backup).context("Failed to back up current binary")?;
-
-        if let Err(e) = std::fs::copy(&new_binary, current_exe) {
-            let _ = std::fs::rename(&backup, current_exe);
-            bail!("Failed to install new binary: {e}");
-        }
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let _ = std::fs::set_permissions(current_exe, std::fs::Permissions::from_mode(0o755));
-        }
-
-        let _ = std::fs::remove_file(&backup);
-        Ok(())
+        let new_binary = extract_tarball(data, tmp)?;
+        swap_binary(&new_binary, current_exe, version)
     }
 
     #[cfg(target_os = "windows")]
     fn install_windows(
         &self,
         data: &[u8],
-        current_exe: &std::path::Path,
-        tmp: &std::path::Path,
+        current_exe: &Path,
+        tmp: &Path,
+        version: &str,
     ) -> Result<()> {
         let archive_path = tmp.join("update.zip");
         std::fs::write(&archive_path, data).context("Failed to write archive")?;
@@ -363,36 +435,356 @@ impl UpdateService {
             bail!("Extracted binary not found");
         }
 
-        // On Windows, we can't replace a running exe directly.
-        // Rename current to .old, copy new, schedule .old for deletion on reboot.
-        let backup = current_exe.with_extension("exe.old");
-        std::fs::rename(current_exe, &backup).context("Failed to back up current binary")?;
+        // On Windows a running exe can be renamed but not overwritten, so the
+        // swap renames the current exe aside first. The backup may still be
+        // locked afterwards; it is removed on the next successful update.
+        swap_binary(&new_binary, current_exe, version)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Release discovery
+// ---------------------------------------------------------------------------
 
-        if let Err(e) = std::fs::copy(&new_binary, current_exe) {
-            let _ = std::fs::rename(&backup, current_exe);
-            bail!("Failed to install new binary: {e}");
+fn http_client(timeout: Duration) -> Result<reqwest::blocking::Client> {
+    reqwest::blocking::Client::builder()
+        .user_agent("hive-updater/1.0")
+        .timeout(timeout)
+        .build()
+        .context("Failed to build HTTP client")
+}
+
+/// Fetch a URL, reading `file://` URLs directly from disk.
+fn fetch_bytes(client: &reqwest::blocking::Client, url: &str) -> Result<Vec<u8>> {
+    if url.starts_with("file://") {
+        let path = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.to_file_path().ok())
+            .with_context(|| format!("Invalid file URL: {url}"))?;
+        return std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()));
+    }
+
+    let resp = client
+        .get(url)
+        .send()
+        .with_context(|| format!("Failed to reach {url}"))?;
+
+    if !resp.status().is_success() {
+        bail!("{url} returned status {}", resp.status());
+    }
+
+    Ok(resp.bytes().context("Failed to read response body")?.to_vec())
+}
+
+/// Pick the newest non-draft release from a GitHub release listing.
+fn newest_release(releases: Vec<serde_json::Value>) -> Option<serde_json::Value> {
+    releases
+        .into_iter()
+        .filter(|r| !r["draft"].as_bool().unwrap_or(false))
+        .filter(|r| r["tag_name"].is_string())
+        .max_by_key(|r| {
+            let tag = r["tag_name"].as_str().unwrap_or_default();
+            parse_version(tag.strip_prefix('v').unwrap_or(tag))
+        })
+}
+
+/// Turn a GitHub-shaped release object into [`UpdateInfo`] when it is newer
+/// than `current_version`. Relative asset URLs are resolved against
+/// `base_url` (used by mirrors).
+fn parse_release(
+    body: &serde_json::Value,
+    channel: UpdateChannel,
+    current_version: &str,
+    base_url: Option<&str>,
+) -> Result<Option<UpdateInfo>> {
+    let tag = body["tag_name"]
+        .as_str()
+        .context("No tag_name in response")?;
+
+    let remote_version = tag.strip_prefix('v').unwrap_or(tag);
+
+    if channel == UpdateChannel::Stable && body["prerelease"].as_bool().unwrap_or(false) {
+        return Ok(None);
+    }
+
+    if !is_newer(remote_version, current_version) {
+        return Ok(None);
+    }
+
+    let assets = body["assets"].as_array().context("No assets array")?;
+    let find_asset = |name: &str| -> Result<String> {
+        let url = assets
+            .iter()
+            .find(|a| a["name"].as_str() == Some(name))
+            .and_then(|a| a["browser_download_url"].as_str())
+            .with_context(|| format!("Asset {name} not found in release"))?;
+        resolve_url(base_url, url)
+    };
+
+    // Find the right asset for this platform.
+    let asset_url = find_asset(platform_asset_name())?;
+    let manifest_url = find_asset(MANIFEST_ASSET)?;
+    let signature_url = find_asset(SIGNATURE_ASSET)?;
+
+    let release_url = body["html_url"].as_str().unwrap_or("").to_string();
+
+    let release_notes = body["body"].as_str().unwrap_or("").to_string();
+
+    Ok(Some(UpdateInfo {
+        version: remote_version.to_string(),
+        channel,
+        release_url,
+        asset_url,
+        manifest_url,
+        signature_url,
+        release_notes,
+    }))
+}
+
+fn resolve_url(base_url: Option<&str>, url: &str) -> Result<String> {
+    match base_url {
+        Some(base) if url::Url::parse(url).is_err() => Ok(url::Url::parse(base)
+            .and_then(|b| b.join(url))
+            .with_context(|| format!("Cannot resolve asset URL {url} against {base}"))?
+            .to_string()),
+        _ => Ok(url.to_string()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Signature and manifest verification
+// ---------------------------------------------------------------------------
+
+/// Verify a minisign signature over `data` with a base64 minisign public key.
+///
+/// Supports both legacy (`Ed`) and prehashed (`ED`, BLAKE2b-512) signatures.
+/// Returns the trusted comment, whose integrity is covered by the global
+/// signature.
+pub fn verify_minisign(public_key: &str, data: &[u8], signature: &str) -> Result<String> {
+    let b64 = base64::engine::general_purpose::STANDARD;
+
+    let pk = b64
+        .decode(public_key.trim())
+        .context("Public key is not valid base64")?;
+    if pk.len() != 42 || &pk[..2] != b"Ed" {
+        bail!("Unsupported public key format");
+    }
+    let key_id = &pk[2..10];
+    let key_bytes: [u8; 32] = pk[10..42].try_into().expect("length checked");
+    let key = VerifyingKey::from_bytes(&key_bytes).context("Invalid ed25519 public key")?;
+
+    let mut lines = signature.lines().map(str::trim_end);
+    let _untrusted = lines
+        .next()
+        .filter(|l| l.starts_with("untrusted comment:"))
+        .context("Signature is missing its untrusted comment")?;
+    let sig_line = lines.next().context("Signature is missing its payload")?;
+    let trusted_comment = lines
+        .next()
+        .and_then(|l| l.strip_prefix("trusted comment: "))
+        .context("Signature is missing its trusted comment")?;
+    let global_line = lines
+        .next()
+        .context("Signature is missing its global signature")?;
+
+    let sig = b64
+        .decode(sig_line.trim())
+        .context("Signature is not valid base64")?;
+    if sig.len() != 74 {
+        bail!("Unsupported signature format");
+    }
+    if &sig[2..10] != key_id {
+        bail!("Signature was made with a different key");
+    }
+    let sig_bytes: [u8; 64] = sig[10..74].try_into().expect("length checked");
+    let signature_value = Signature::from_bytes(&sig_bytes);
+
+    match &sig[..2] {
+        b"Ed" => key.verify(data, &signature_value),
+        b"ED" => {
+            let digest = blake2::Blake2b512::digest(data);
+            key.verify(&digest, &signature_value)
         }
+        _ => bail!("Unsupported signature algorithm"),
+    }
+    .context("Signature does not match data")?;
+
+    let global = b64
+        .decode(global_line.trim())
+        .context("Global signature is not valid base64")?;
+    let global: [u8; 64] = global
+        .as_slice()
+        .try_into()
+        .context("Global signature has the wrong length")?;
+    let mut signed_comment = sig_bytes.to_vec();
+    signed_comment.extend_from_slice(trusted_comment.as_bytes());
+    key.verify(&signed_comment, &Signature::from_bytes(&global))
+        .context("Trusted comment signature does not match")?;
+
+    Ok(trusted_comment.to_string())
+}
 
-        // Try to clean up the old binary (may fail if still in use).
-        let _ = std::fs::remove_file(&backup);
-        Ok(())
+/// Whether a trusted comment names `version` (as `v1.2.3` or `1.2.3`) as a
+/// whitespace-separated token.
+fn comment_names_version(comment: &str, version: &str) -> bool {
+    comment
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .map(|t| t.strip_prefix("version:").unwrap_or(t))
+        .any(|t| t.strip_prefix('v').unwrap_or(t) == version)
+}
+
+/// Look up the hex SHA-256 for `file_name` in a `sha256sum`-style manifest.
+fn manifest_digest(manifest: &str, file_name: &str) -> Option<String> {
+    manifest.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let digest = parts.next()?;
+        let name = parts.next()?.trim_start_matches('*');
+        (name == file_name && digest.len() == 64 && digest.bytes().all(|b| b.is_ascii_hexdigit()))
+            .then(|| digest.to_ascii_lowercase())
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Installation
+// ---------------------------------------------------------------------------
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn extract_tarball(data: &[u8], tmp: &Path) -> Result<PathBuf> {
+    let archive_path = tmp.join("update.tar.gz");
+    std::fs::write(&archive_path, data).context("Failed to write archive")?;
+
+    let status = std::process::Command::new("tar")
+        .args(["xzf", &archive_path.to_string_lossy()])
+        .current_dir(tmp)
+        .status()
+        .context("Failed to run tar")?;
+
+    if !status.success() {
+        bail!("tar extraction failed");
     }
+
+    let new_binary = tmp.join("hive");
+    if !new_binary.exists() {
+        bail!("Extracted binary not found");
+    }
+    Ok(new_binary)
 }
 
-/// Compare two semver-like version strings, return true if `remote > local`.
-fn is_newer(remote: &str, local: &str) -> bool {
-    let parse = |v: &str| -> (u32, u32, u32) {
-        let parts: Vec<&str> = v.split('.').collect();
-        let major = parts.first().and_then(|s| s.parse().ok()).unwrap_or(0);
-        let minor = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
-        let patch = parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0);
-        (major, minor, patch)
+/// Atomically replace `current_exe` with `new_binary`, rolling back to the
+/// previous binary if the new one fails its `--version` health check.
+///
+/// The new binary is first staged next to the current one so the final
+/// step is a same-filesystem rename.
+fn swap_binary(new_binary: &Path, current_exe: &Path, version: &str) -> Result<()> {
+    let staged = sibling_path(current_exe, "new");
+    let backup = sibling_path(current_exe, "old");
+
+    // Leftover from a previous update whose backup could not be removed.
+    let _ = std::fs::remove_file(&backup);
+
+    std::fs::copy(new_binary, &staged).context("Failed to stage new binary")?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))
+            .context("Failed to mark new binary executable")?;
+    }
+
+    if let Err(e) = std::fs::rename(current_exe, &backup) {
+        let _ = std::fs::remove_file(&staged);
+        return Err(e).context("Failed to back up current binary");
+    }
+
+    if let Err(e) = std::fs::rename(&staged, current_exe) {
+        let _ = std::fs::rename(&backup, current_exe);
+        let _ = std::fs::remove_file(&staged);
+        bail!("Failed to install new binary: {e}");
+    }
+
+    if let Err(e) = health_check(current_exe, version) {
+        warn!("New binary failed health check, rolling back: {e:#}");
+        let _ = std::fs::remove_file(current_exe);
+        std::fs::rename(&backup, current_exe)
+            .context("Health check failed and rollback to previous binary failed")?;
+        return Err(e.context("New binary failed its health check; previous version restored"));
+    }
+
+    // May fail on Windows while the old exe is still running.
+    let _ = std::fs::remove_file(&backup);
+    Ok(())
+}
+
+/// Whether `exe` lives in a Homebrew keg (`<prefix>/Cellar/<formula>/...`).
+/// Homebrew links kegs into `<prefix>/bin`, so symlinks are resolved first.
+fn is_homebrew_install(exe: &Path) -> bool {
+    let exe = exe.canonicalize().unwrap_or_else(|_| exe.to_path_buf());
+    exe.components().any(|c| c.as_os_str() == "Cellar")
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Run `binary --version` and require it to succeed and report `version`.
+fn health_check(binary: &Path, version: &str) -> Result<()> {
+    let mut child = std::process::Command::new(binary)
+        .arg("--version")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .context("Failed to launch new binary")?;
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().context("Failed to wait for new binary")? {
+            break status;
+        }
+        if started.elapsed() > HEALTH_CHECK_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("`--version` did not exit within {HEALTH_CHECK_TIMEOUT:?}");
+        }
+        std::thread::sleep(Duration::from_millis(50));
     };
 
-    let r = parse(remote);
-    let l = parse(local);
+    let mut stdout = String::new();
+    if let Some(mut out) = child.stdout.take() {
+        use std::io::Read;
+        let _ = out.read_to_string(&mut stdout);
+    }
 
-    r > l
+    if !status.success() {
+        bail!("`--version` exited with {status}");
+    }
+    if !stdout.contains(version) {
+        bail!("`--version` reported {:?}, expected {version}", stdout.trim());
+    }
+    Ok(())
+}
+
+/// Compare two semver-like version strings, return true if `remote > local`.
+fn is_newer(remote: &str, local: &str) -> bool {
+    parse_version(remote) > parse_version(local)
+}
+
+/// Parse `major.minor.patch[-pre]` into a comparable tuple.
+///
+/// A prerelease sorts below its final release (`0.4.0-beta.1 < 0.4.0`).
+fn parse_version(v: &str) -> (u32, u32, u32, bool) {
+    let (core, pre) = match v.split_once('-') {
+        Some((core, _)) => (core, true),
+        None => (v, false),
+    };
+    let parts: Vec<&str> = core.split('.').collect();
+    let major = parts.first().and_then(|s| s.parse().ok()).unwrap_or(0);
+    let minor = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+    let patch = parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0);
+    (major, minor, patch, !pre)
 }
 
 /// The expected asset filename for the current platform.
@@ -436,10 +828,277 @@ mod tests {
         assert!(!is_newer("0.1.0", "0.2.1"));
     }
 
+    #[test]
+    fn test_is_newer_prerelease() {
+        assert!(is_newer("0.4.0-beta.1", "0.3.9"));
+        assert!(is_newer("0.4.0", "0.4.0-beta.1"));
+        assert!(!is_newer("0.4.0-beta.1", "0.4.0"));
+    }
+
     #[test]
     fn test_platform_asset_name() {
         let name = platform_asset_name();
         assert!(!name.is_empty());
         assert!(name.contains("hive"));
     }
+
+    // -- minisign helpers ---------------------------------------------------
+
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const KEY_ID: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    fn test_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn public_key_b64(key: &SigningKey) -> String {
+        let mut pk = b"Ed".to_vec();
+        pk.extend_from_slice(&KEY_ID);
+        pk.extend_from_slice(key.verifying_key().as_bytes());
+        base64::engine::general_purpose::STANDARD.encode(pk)
+    }
+
+    fn sign(key: &SigningKey, data: &[u8], trusted_comment: &str, prehashed: bool) -> String {
+        let b64 = base64::engine::general_purpose::STANDARD;
+        let sig = if prehashed {
+            key.sign(&blake2::Blake2b512::digest(data))
+        } else {
+            key.sign(data)
+        };
+        let mut payload = if prehashed { b"ED".to_vec() } else { b"Ed".to_vec() };
+        payload.extend_from_slice(&KEY_ID);
+        payload.extend_from_slice(&sig.to_bytes());
+
+        let mut global = sig.to_bytes().to_vec();
+        global.extend_from_slice(trusted_comment.as_bytes());
+        let global = key.sign(&global);
+
+        format!(
+            "untrusted comment: signature from minisign secret key\n{}\ntrusted comment: {}\n{}\n",
+            b64.encode(payload),
+            trusted_comment,
+            b64.encode(global.to_bytes())
+        )
+    }
+
+    #[test]
+    fn test_release_public_key_parses() {
+        let pk = base64::engine::general_purpose::STANDARD
+            .decode(RELEASE_PUBLIC_KEY)
+            .unwrap();
+        assert_eq!(pk.len(), 42);
+        assert_eq!(&pk[..2], b"Ed");
+        assert!(VerifyingKey::from_bytes(&pk[10..].try_into().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_verify_minisign_roundtrip() {
+        let key = test_key();
+        let data = b"abc  hive-linux-x64.tar.gz\n";
+        for prehashed in [false, true] {
+            let sig = sign(&key, data, "hive v0.4.0", prehashed);
+            let comment = verify_minisign(&public_key_b64(&key), data, &sig).unwrap();
+            assert_eq!(comment, "hive v0.4.0");
+        }
+    }
+
+    #[test]
+    fn test_verify_minisign_rejects_tampered_data() {
+        let key = test_key();
+        let sig = sign(&key, b"original", "hive v0.4.0", true);
+        assert!(verify_minisign(&public_key_b64(&key), b"tampered", &sig).is_err());
+    }
+
+    #[test]
+    fn test_verify_minisign_rejects_tampered_comment() {
+        let key = test_key();
+        let sig = sign(&key, b"data", "hive v0.4.0", true);
+        let forged = sig.replace("hive v0.4.0", "hive v9.9.9");
+        assert!(verify_minisign(&public_key_b64(&key), b"data", &forged).is_err());
+    }
+
+    #[test]
+    fn test_verify_minisign_rejects_other_key() {
+        let key = test_key();
+        let other = SigningKey::from_bytes(&[9u8; 32]);
+        let sig = sign(&other, b"data", "hive v0.4.0", false);
+        assert!(verify_minisign(&public_key_b64(&key), b"data", &sig).is_err());
+        assert!(verify_minisign(RELEASE_PUBLIC_KEY, b"data", &sig).is_err());
+    }
+
+    #[test]
+    fn test_release_key_verifies_signed_fixture() {
+        // Signed by release CI's key, the same way CI signs SHA256SUMS.
+        let manifest = include_str!("testdata/release/SHA256SUMS");
+        let signature = include_str!("testdata/release/SHA256SUMS.minisig");
+
+        let comment = verify_minisign(RELEASE_PUBLIC_KEY, manifest.as_bytes(), signature).unwrap();
+        assert!(comment_names_version(&comment, "0.0.1-fixture"));
+        assert!(manifest_digest(manifest, "hive-linux-x64.tar.gz").is_some());
+
+        let tampered = manifest.replace("e3b0", "e3b1");
+        assert!(verify_minisign(RELEASE_PUBLIC_KEY, tampered.as_bytes(), signature).is_err());
+    }
+
+    #[test]
+    fn test_homebrew_installs_are_detected() {
+        assert!(is_homebrew_install(Path::new(
+            "/opt/homebrew/Cellar/hive/0.3.0/bin/hive"
+        )));
+        assert!(is_homebrew_install(Path::new(
+            "/home/linuxbrew/.linuxbrew/Cellar/hive/0.3.0/bin/hive"
+        )));
+        assert!(!is_homebrew_install(Path::new("/usr/local/bin/hive")));
+        assert!(!is_homebrew_install(Path::new(
+            "/Applications/Hive.app/Contents/MacOS/hive"
+        )));
+    }
+
+    #[test]
+    fn test_comment_names_version() {
+        assert!(comment_names_version("hive v0.4.0", "0.4.0"));
+        assert!(comment_names_version("timestamp:1700000000\tversion:0.4.0", "0.4.0"));
+        assert!(!comment_names_version("hive v0.4.0", "0.4.1"));
+        assert!(!comment_names_version("hive v0.4.01", "0.4.0"));
+    }
+
+    #[test]
+    fn test_manifest_digest() {
+        let digest = "a".repeat(64);
+        let manifest = format!(
+            "{digest}  hive-linux-x64.tar.gz\n{}  *hive-windows-x64.zip\nnot-a-digest  other\n",
+            "B".repeat(64)
+        );
+        assert_eq!(
+            manifest_digest(&manifest, "hive-linux-x64.tar.gz").as_deref(),
+            Some(digest.as_str())
+        );
+        assert_eq!(
+            manifest_digest(&manifest, "hive-windows-x64.zip"),
+            Some("b".repeat(64))
+        );
+        assert!(manifest_digest(&manifest, "other").is_none());
+        assert!(manifest_digest(&manifest, "missing").is_none());
+    }
+
+    fn release_json(tag: &str, prerelease: bool, base: &str) -> serde_json::Value {
+        serde_json::json!({
+            "tag_name": tag,
+            "prerelease": prerelease,
+            "html_url": "https://example.com/release",
+            "body": "notes",
+            "assets": [
+                { "name": platform_asset_name(), "browser_download_url": format!("{base}{}", platform_asset_name()) },
+                { "name": MANIFEST_ASSET, "browser_download_url": format!("{base}SHA256SUMS") },
+                { "name": SIGNATURE_ASSET, "browser_download_url": format!("{base}SHA256SUMS.minisig") },
+            ]
+        })
+    }
+
+    #[test]
+    fn test_parse_release_stable_skips_prerelease() {
+        let release = release_json("v0.4.0-beta.1", true, "https://x/");
+        assert!(
+            parse_release(&release, UpdateChannel::Stable, "0.3.0", None)
+                .unwrap()
+                .is_none()
+        );
+        let info = parse_release(&release, UpdateChannel::Beta, "0.3.0", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.version, "0.4.0-beta.1");
+        assert_eq!(info.channel, UpdateChannel::Beta);
+    }
+
+    #[test]
+    fn test_parse_release_resolves_mirror_relative_urls() {
+        let release = release_json("v0.4.0", false, "v0.4.0/");
+        let info = parse_release(
+            &release,
+            UpdateChannel::Stable,
+            "0.3.0",
+            Some("file:///srv/mirror/"),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(info.manifest_url, "file:///srv/mirror/v0.4.0/SHA256SUMS");
+        assert_eq!(
+            info.signature_url,
+            "file:///srv/mirror/v0.4.0/SHA256SUMS.minisig"
+        );
+    }
+
+    #[test]
+    fn test_parse_release_requires_signed_manifest() {
+        let mut release = release_json("v0.4.0", false, "https://x/");
+        release["assets"].as_array_mut().unwrap().truncate(1);
+        assert!(parse_release(&release, UpdateChannel::Stable, "0.3.0", None).is_err());
+    }
+
+    #[test]
+    fn test_newest_release_ignores_drafts() {
+        let releases = vec![
+            serde_json::json!({ "tag_name": "v0.3.0" }),
+            serde_json::json!({ "tag_name": "v0.5.0", "draft": true }),
+            serde_json::json!({ "tag_name": "v0.4.0-beta.2", "prerelease": true }),
+        ];
+        let newest = newest_release(releases).unwrap();
+        assert_eq!(newest["tag_name"], "v0.4.0-beta.2");
+    }
+
+    #[test]
+    fn test_update_channel_serde() {
+        assert_eq!(
+            serde_json::to_string(&UpdateChannel::Beta).unwrap(),
+            "\"beta\""
+        );
+        let parsed: UpdateChannel = serde_json::from_str("\"stable\"").unwrap();
+        assert_eq!(parsed, UpdateChannel::Stable);
+    }
+
+    #[test]
+    fn test_with_options_ignores_blank_mirror() {
+        let svc = UpdateService::with_options("0.3.0", UpdateChannel::Beta, Some("  ".into()));
+        assert!(svc.mirror_url().is_none());
+        assert_eq!(svc.channel(), UpdateChannel::Beta);
+    }
+
+    #[cfg(unix)]
+    fn write_script(path: &Path, body: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::write(path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_swap_binary_installs_healthy_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let current = dir.path().join("hive");
+        let new = dir.path().join("download");
+        write_script(&current, "echo hive 0.3.0");
+        write_script(&new, "echo hive 0.4.0");
+
+        swap_binary(&new, &current, "0.4.0").unwrap();
+
+        assert!(std::fs::read_to_string(&current).unwrap().contains("0.4.0"));
+        assert!(!sibling_path(&current, "old").exists());
+        assert!(!sibling_path(&current, "new").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_swap_binary_rolls_back_on_failed_health_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let current = dir.path().join("hive");
+        let new = dir.path().join("download");
+        write_script(&current, "echo hive 0.3.0");
+        write_script(&new, "exit 3");
+
+        assert!(swap_binary(&new, &current, "0.4.0").is_err());
+
+        assert!(std::fs::read_to_string(&current).unwrap().contains("0.3.0"));
+        assert!(!sibling_path(&current, "old").exists());
+    }
 }
@@ -0,0 +1,687 @@
+//! Workspace checkpoints for undoing agent edits.
+//!
+//! Snapshots are commits in a shadow repository kept under
+//! `~/.hive/checkpoints/`, whose working tree is the workspace itself. The
+//! user's own repository, branches and index are never touched, and
+//! workspaces that are not git repositories work the same way. Each
+//! conversation has its own timeline ref.
+//!
+//! Checkpoints are taken automatically: while a [`CheckpointTurn`] is active
+//! on a store, the first write announced through
+//! [`CheckpointStore::before_write`] snapshots the workspace before the write
+//! happens. Turns are armed per store, so writes made by anything not holding
+//! the store are never attributed to an agent turn.
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, TimeZone, Utc};
+use git2::{
+    DiffOptions, IndexAddOption, ObjectType, Oid, Repository, Signature, Sort, Tree, TreeEntry,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tracing::debug;
+
+use crate::git::{FileDiff, file_diff};
+
+/// Patterns never snapshotted, on top of the workspace's own `.gitignore`
+/// files. Written to the shadow repository's `info/exclude` when created.
+const DEFAULT_EXCLUDES: &str = "\
+node_modules/
+target/
+.venv/
+__pycache__/
+.DS_Store
+";
+
+/// Ref namespace holding one timeline per conversation.
+const REF_PREFIX: &str = "refs/hive/checkpoints/";
+
+const CONVERSATION_TRAILER: &str = "Conversation: ";
+
+/// A snapshot of the workspace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: String,
+    pub conversation_id: String,
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+    /// The previous checkpoint in the same conversation.
+    pub parent: Option<String>,
+}
+
+/// What [`CheckpointStore::restore`] changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoreSummary {
+    /// Checkpoint of the workspace as it was just before the restore, so
+    /// the restore itself can be undone.
+    pub backup: Checkpoint,
+    /// Files written back from the checkpoint.
+    pub restored: Vec<PathBuf>,
+    /// Files created after the checkpoint, now removed.
+    pub removed: Vec<PathBuf>,
+}
+
+/// Checkpoints of one workspace.
+pub struct CheckpointStore {
+    repo: Mutex<Repository>,
+    workspace: PathBuf,
+    /// Turns currently armed on this store.
+    armed: Mutex<Vec<Arc<ArmedTurn>>>,
+}
+
+impl CheckpointStore {
+    /// Open the store for `workspace` under `~/.hive/checkpoints/`, creating
+    /// it on first use.
+    pub fn open(workspace: &Path) -> Result<Self> {
+        let workspace = workspace
+            .canonicalize()
+            .with_context(|| format!("Cannot resolve workspace: {}", workspace.display()))?;
+        let key = Oid::hash_object(ObjectType::Blob, workspace.to_string_lossy().as_bytes())?;
+        let storage = hive_core::config::HiveConfig::base_dir()?
+            .join("checkpoints")
+            .join(&key.to_string()[..16]);
+        Self::open_at(&workspace, &storage)
+    }
+
+    /// Open a store for `workspace` kept in `storage`.
+    pub fn open_at(workspace: &Path, storage: &Path) -> Result<Self> {
+        let workspace = workspace
+            .canonicalize()
+            .with_context(|| format!("Cannot resolve workspace: {}", workspace.display()))?;
+        let repo = match Repository::open_bare(storage) {
+            Ok(repo) => repo,
+            Err(_) => {
+                let repo = Repository::init_bare(storage).with_context(|| {
+                    format!("Failed to create checkpoint store at {}", storage.display())
+                })?;
+                let info = storage.join("info");
+                std::fs::create_dir_all(&info)?;
+                std::fs::write(info.join("exclude"), DEFAULT_EXCLUDES)?;
+                debug!("Created checkpoint store at {}", storage.display());
+                repo
+            }
+        };
+        repo.set_workdir(&workspace, false)
+            .context("Failed to attach checkpoint store to workspace")?;
+
+        Ok(Self {
+            repo: Mutex::new(repo),
+            workspace,
+            armed: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// The canonical workspace root.
+    pub fn workspace(&self) -> &Path {
+        &self.workspace
+    }
+
+    fn repo(&self) -> MutexGuard<'_, Repository> {
+        self.repo.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Snapshot the workspace onto the conversation's timeline. If nothing
+    /// changed since the conversation's last checkpoint, that one is
+    /// returned instead of recording a duplicate.
+    pub fn create(&self, conversation_id: &str, label: &str) -> Result<Checkpoint> {
+        let repo = self.repo();
+        let refname = timeline_ref(conversation_id);
+
+        let mut index = repo.index().context("Failed to open checkpoint index")?;
+        index
+            .add_all(["*"], IndexAddOption::DEFAULT, None)
+            .context("Failed to snapshot workspace")?;
+        // Drop files deleted since the last snapshot.
+        index
+            .update_all(["*"], None)
+            .context("Failed to snapshot workspace")?;
+        index.write().context("Failed to write checkpoint index")?;
+        let tree_id = index.write_tree().context("Failed to write snapshot")?;
+
+        let parent = repo
+            .find_reference(&refname)
+            .ok()
+            .and_then(|reference| reference.peel_to_commit().ok());
+        if let Some(parent) = &parent
+            && parent.tree_id() == tree_id
+        {
+            return checkpoint_from_commit(parent);
+        }
+
+        let tree = repo.find_tree(tree_id)?;
+        let signature = Signature::now("Hive", "checkpoints@hive.local")?;
+        let message = format!("{label}\n\n{CONVERSATION_TRAILER}{conversation_id}\n");
+        let parents: Vec<_> = parent.iter().collect();
+        let oid = repo
+            .commit(
+                Some(&refname),
+                &signature,
+                &signature,
+                &message,
+                &tree,
+                &parents,
+            )
+            .context("Failed to record checkpoint")?;
+
+        debug!(
+            "Checkpoint {} for {conversation_id}: {label}",
+            &oid.to_string()[..8]
+        );
+        checkpoint_from_commit(&repo.find_commit(oid)?)
+    }
+
+    /// A conversation's checkpoints, most recent first.
+    pub fn timeline(&self, conversation_id: &str) -> Result<Vec<Checkpoint>> {
+        let repo = self.repo();
+        let Ok(reference) = repo.find_reference(&timeline_ref(conversation_id)) else {
+            return Ok(Vec::new());
+        };
+        let tip = reference.peel_to_commit()?.id();
+
+        let mut walk = repo.revwalk()?;
+        walk.push(tip)?;
+        walk.simplify_first_parent()?;
+        walk.set_sorting(Sort::TOPOLOGICAL)?;
+        walk.map(|oid| checkpoint_from_commit(&repo.find_commit(oid?)?))
+            .collect()
+    }
+
+    /// Conversations that have checkpoints.
+    pub fn conversations(&self) -> Result<Vec<String>> {
+        let repo = self.repo();
+        let mut ids = Vec::new();
+        for reference in repo.references_glob(&format!("{REF_PREFIX}*"))? {
+            let commit = reference?.peel_to_commit()?;
+            ids.push(checkpoint_from_commit(&commit)?.conversation_id);
+        }
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Look up a checkpoint by id (a full or abbreviated hash).
+    pub fn get(&self, id: &str) -> Result<Checkpoint> {
+        let repo = self.repo();
+        checkpoint_from_commit(&find_checkpoint(&repo, id)?)
+    }
+
+    /// Changes from the checkpoint to the current workspace. Files created
+    /// since the checkpoint are reported as untracked.
+    pub fn diff(&self, id: &str) -> Result<Vec<FileDiff>> {
+        let repo = self.repo();
+        let tree = find_checkpoint(&repo, id)?.tree()?;
+        let diff = diff_to_workspace(&repo, &tree)?;
+        let mut files = Vec::new();
+        for index in 0..diff.deltas().len() {
+            if let Some(file) = file_diff(&diff, index)? {
+                files.push(file);
+            }
+        }
+        Ok(files)
+    }
+
+    /// Put the whole workspace back the way it was at the checkpoint:
+    /// changed and deleted files are rewritten and files created since are
+    /// removed. Ignored files are left alone. The current state is
+    /// checkpointed first.
+    pub fn restore(&self, id: &str) -> Result<RestoreSummary> {
+        let target = self.get(id)?;
+        let backup = self.create(
+            &target.conversation_id,
+            &format!("Before restoring {}", short(&target.id)),
+        )?;
+
+        let repo = self.repo();
+        let tree = find_checkpoint(&repo, &target.id)?.tree()?;
+        let diff = diff_to_workspace(&repo, &tree)?;
+
+        let mut restored = Vec::new();
+        let mut removed = Vec::new();
+        for delta in diff.deltas() {
+            let path = delta
+                .old_file()
+                .path()
+                .or_else(|| delta.new_file().path())
+                .context("Diff entry has no path")?
+                .to_path_buf();
+            match tree.get_path(&path) {
+                Ok(entry) => {
+                    self.write_entry(&repo, &path, &entry)?;
+                    restored.push(path);
+                }
+                Err(_) => {
+                    self.remove(&path)?;
+                    removed.push(path);
+                }
+            }
+        }
+
+        debug!(
+            "Restored checkpoint {}: {} restored, {} removed",
+            short(&target.id),
+            restored.len(),
+            removed.len()
+        );
+        Ok(RestoreSummary {
+            backup,
+            restored,
+            removed,
+        })
+    }
+
+    /// Put one file (relative to the workspace root) back the way it was at
+    /// the checkpoint, removing it if it did not exist then.
+    pub fn restore_file(&self, id: &str, path: &Path) -> Result<()> {
+        let repo = self.repo();
+        let tree = find_checkpoint(&repo, id)?.tree()?;
+        match tree.get_path(path) {
+            Ok(entry) => self.write_entry(&repo, path, &entry),
+            Err(_) => self.remove(path),
+        }
+    }
+
+    fn write_entry(&self, repo: &Repository, path: &Path, entry: &TreeEntry<'_>) -> Result<()> {
+        let blob = entry
+            .to_object(repo)?
+            .peel_to_blob()
+            .with_context(|| format!("{} is not a file in the checkpoint", path.display()))?;
+        let full = self.workspace.join(path);
+        if let Some(parent) = full.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        #[cfg(unix)]
+        if entry.filemode() == 0o120000 {
+            use std::os::unix::ffi::OsStrExt;
+            let _ = std::fs::remove_file(&full);
+            let target = std::ffi::OsStr::from_bytes(blob.content());
+            return std::os::unix::fs::symlink(target, &full)
+                .with_context(|| format!("Failed to restore {}", full.display()));
+        }
+
+        std::fs::write(&full, blob.content())
+            .with_context(|| format!("Failed to restore {}", full.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = if entry.filemode() == 0o100755 {
+                0o755
+            } else {
+                0o644
+            };
+            std::fs::set_permissions(&full, std::fs::Permissions::from_mode(mode))?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, path: &Path) -> Result<()> {
+        let full = self.workspace.join(path);
+        match std::fs::remove_file(&full) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to remove {}", full.display())),
+        }
+    }
+
+    /// Arm automatic checkpointing for an agent turn. Until the returned
+    /// guard is dropped, the first write into the workspace announced
+    /// through [`before_write`](Self::before_write) checkpoints it first.
+    pub fn begin_turn(self: &Arc<Self>, conversation_id: &str, label: &str) -> CheckpointTurn {
+        let turn = Arc::new(ArmedTurn {
+            conversation_id: conversation_id.to_string(),
+            label: label.to_string(),
+            checkpoint: Mutex::new(None),
+        });
+        self.armed().push(Arc::clone(&turn));
+        CheckpointTurn {
+            store: Arc::clone(self),
+            turn,
+        }
+    }
+
+    fn armed(&self) -> MutexGuard<'_, Vec<Arc<ArmedTurn>>> {
+        self.armed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Call before modifying `path`: checkpoints the workspace if `path` is
+    /// inside it and a turn armed on this store has not been checkpointed
+    /// yet.
+    pub fn before_write(&self, path: &Path) -> Result<()> {
+        let turns: Vec<Arc<ArmedTurn>> = {
+            let armed = self.armed();
+            if armed.is_empty() {
+                return Ok(());
+            }
+            armed.clone()
+        };
+        let Some(resolved) = resolve(path) else {
+            return Ok(());
+        };
+        if !resolved.starts_with(&self.workspace) {
+            return Ok(());
+        }
+
+        for turn in &turns {
+            let mut checkpoint = turn.checkpoint.lock().unwrap_or_else(|e| e.into_inner());
+            if checkpoint.is_none() {
+                let taken = self
+                    .create(&turn.conversation_id, &turn.label)
+                    .context("Could not checkpoint the workspace before writing")?;
+                *checkpoint = Some(taken);
+            }
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Automatic checkpoints
+// ---------------------------------------------------------------------------
+
+struct ArmedTurn {
+    conversation_id: String,
+    label: String,
+    checkpoint: Mutex<Option<Checkpoint>>,
+}
+
+/// An agent turn during which workspace writes are checkpointed. Disarms
+/// when dropped.
+pub struct CheckpointTurn {
+    store: Arc<CheckpointStore>,
+    turn: Arc<ArmedTurn>,
+}
+
+impl CheckpointTurn {
+    /// The checkpoint taken for this turn, if anything was written.
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        self.turn
+            .checkpoint
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl Drop for CheckpointTurn {
+    fn drop(&mut self) {
+        self.store
+            .armed()
+            .retain(|turn| !Arc::ptr_eq(turn, &self.turn));
+    }
+}
+
+/// Canonical form of a path that may not exist yet: its nearest existing
+/// ancestor canonicalized, with the rest appended.
+fn resolve(path: &Path) -> Option<PathBuf> {
+    let absolute = std::path::absolute(path).ok()?;
+    let mut existing = absolute.as_path();
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return Some(
+                rest.iter()
+                    .rev()
+                    .fold(canonical, |acc, part| acc.join(part)),
+            );
+        }
+        rest.push(existing.file_name()?);
+        existing = existing.parent()?;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn timeline_ref(conversation_id: &str) -> String {
+    let name: String = conversation_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{REF_PREFIX}{name}")
+}
+
+fn find_checkpoint<'r>(repo: &'r Repository, id: &str) -> Result<git2::Commit<'r>> {
+    repo.revparse_single(id)
+        .and_then(|object| object.peel_to_commit())
+        .ok()
+        .filter(|commit| {
+            commit
+                .message()
+                .is_some_and(|m| m.contains(CONVERSATION_TRAILER))
+        })
+        .with_context(|| format!("No checkpoint {id}"))
+}
+
+fn checkpoint_from_commit(commit: &git2::Commit<'_>) -> Result<Checkpoint> {
+    let message = commit.message().unwrap_or("");
+    let Some(conversation_id) = message
+        .lines()
+        .find_map(|line| line.strip_prefix(CONVERSATION_TRAILER))
+    else {
+        bail!("Commit {} is not a checkpoint", commit.id());
+    };
+    Ok(Checkpoint {
+        id: commit.id().to_string(),
+        conversation_id: conversation_id.trim().to_string(),
+        label: commit.summary().unwrap_or("").to_string(),
+        created_at: Utc
+            .timestamp_opt(commit.time().seconds(), 0)
+            .single()
+            .unwrap_or_default(),
+        parent: commit.parent_ids().next().map(|oid| oid.to_string()),
+    })
+}
+
+fn diff_to_workspace<'r>(repo: &'r Repository, tree: &Tree<'_>) -> Result<git2::Diff<'r>> {
+    let mut opts = DiffOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    repo.diff_tree_to_workdir(Some(tree), Some(&mut opts))
+        .context("Failed to diff checkpoint")
+}
+
+fn short(id: &str) -> &str {
+    &id[..id.len().min(8)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileService, FileStatusType};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, TempDir, Arc<CheckpointStore>) {
+        let workspace = tempfile::tempdir().unwrap();
+        let storage = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::open_at(workspace.path(), storage.path()).unwrap();
+        (workspace, storage, Arc::new(store))
+    }
+
+    #[test]
+    fn test_timeline_and_dedup() {
+        let (workspace, _storage, store) = setup();
+        fs::write(workspace.path().join("a.txt"), "one").unwrap();
+        let first = store.create("conv-1", "first").unwrap();
+        let again = store.create("conv-1", "unchanged").unwrap();
+        assert_eq!(first, again);
+
+        fs::write(workspace.path().join("a.txt"), "two").unwrap();
+        let second = store.create("conv-1", "second").unwrap();
+        assert_eq!(second.parent.as_deref(), Some(first.id.as_str()));
+        store.create("conv/2", "other conversation").unwrap();
+
+        let timeline = store.timeline("conv-1").unwrap();
+        let labels: Vec<_> = timeline.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, ["second", "first"]);
+        assert_eq!(timeline[0].conversation_id, "conv-1");
+        assert_eq!(store.conversations().unwrap(), ["conv-1", "conv/2"]);
+        assert!(store.timeline("unknown").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_diff_against_current_tree() {
+        let (workspace, _storage, store) = setup();
+        fs::write(workspace.path().join("keep.txt"), "same\n").unwrap();
+        fs::write(workspace.path().join("edit.txt"), "before\n").unwrap();
+        fs::write(workspace.path().join("gone.txt"), "bye\n").unwrap();
+        let checkpoint = store.create("c", "start").unwrap();
+
+        fs::write(workspace.path().join("edit.txt"), "after\n").unwrap();
+        fs::remove_file(workspace.path().join("gone.txt")).unwrap();
+        fs::write(workspace.path().join("new.txt"), "hello\n").unwrap();
+
+        let mut diff = store.diff(&checkpoint.id).unwrap();
+        diff.sort_by(|a, b| a.path.cmp(&b.path));
+        let summary: Vec<_> = diff
+            .iter()
+            .map(|f| (f.path.to_str().unwrap(), f.status))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("edit.txt", FileStatusType::Modified),
+                ("gone.txt", FileStatusType::Deleted),
+                ("new.txt", FileStatusType::Untracked),
+            ]
+        );
+        assert_eq!(diff[0].hunks[0].lines[1].content, "after");
+    }
+
+    #[test]
+    fn test_restore_whole_checkpoint() {
+        let (workspace, _storage, store) = setup();
+        let root = workspace.path();
+        fs::create_dir(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.join("notes.md"), "mine\n").unwrap();
+        fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        let checkpoint = store.create("c", "before agent").unwrap();
+
+        fs::write(root.join("src/lib.rs"), "fn b() {}\n").unwrap();
+        fs::remove_file(root.join("notes.md")).unwrap();
+        fs::write(root.join("src/extra.rs"), "// new\n").unwrap();
+        fs::write(root.join("build.log"), "ignored\n").unwrap();
+
+        let summary = store.restore(&checkpoint.id[..10]).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+            "fn a() {}\n"
+        );
+        assert_eq!(fs::read_to_string(root.join("notes.md")).unwrap(), "mine\n");
+        assert!(!root.join("src/extra.rs").exists());
+        assert!(root.join("build.log").exists());
+        assert_eq!(summary.removed, [PathBuf::from("src/extra.rs")]);
+        assert_eq!(summary.restored.len(), 2);
+        assert!(store.diff(&checkpoint.id).unwrap().is_empty());
+
+        // The backup taken before restoring undoes the restore.
+        store.restore(&summary.backup.id).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+            "fn b() {}\n"
+        );
+        assert!(root.join("src/extra.rs").exists());
+    }
+
+    #[test]
+    fn test_restore_single_file() {
+        let (workspace, _storage, store) = setup();
+        let root = workspace.path();
+        fs::write(root.join("a.txt"), "a1").unwrap();
+        fs::write(root.join("b.txt"), "b1").unwrap();
+        let checkpoint = store.create("c", "start").unwrap();
+        fs::write(root.join("a.txt"), "a2").unwrap();
+        fs::write(root.join("b.txt"), "b2").unwrap();
+        fs::write(root.join("c.txt"), "c2").unwrap();
+
+        store
+            .restore_file(&checkpoint.id, Path::new("a.txt"))
+            .unwrap();
+        store
+            .restore_file(&checkpoint.id, Path::new("c.txt"))
+            .unwrap();
+        assert_eq!(fs::read_to_string(root.join("a.txt")).unwrap(), "a1");
+        assert_eq!(fs::read_to_string(root.join("b.txt")).unwrap(), "b2");
+        assert!(!root.join("c.txt").exists());
+        assert!(store.get("not-a-checkpoint").is_err());
+    }
+
+    #[test]
+    fn test_user_repository_is_untouched() {
+        let (workspace, _storage, store) = setup();
+        let root = workspace.path();
+        let user_repo = Repository::init(root).unwrap();
+        fs::write(root.join("tracked.txt"), "v1").unwrap();
+        let checkpoint = store.create("c", "start").unwrap();
+        fs::write(root.join("tracked.txt"), "v2").unwrap();
+        store.restore(&checkpoint.id).unwrap();
+
+        assert!(user_repo.head().is_err(), "no commits in the user's repo");
+        assert!(user_repo.index().unwrap().is_empty());
+        assert!(user_repo.references().unwrap().next().is_none());
+        let diff = store.diff(&checkpoint.id).unwrap();
+        assert!(diff.iter().all(|f| !f.path.starts_with(".git")));
+    }
+
+    #[test]
+    fn test_turn_checkpoints_before_first_write() {
+        let (workspace, _storage, store) = setup();
+        let file = workspace.path().join("code.rs");
+        fs::write(&file, "original").unwrap();
+
+        let write = |path: &Path, content: &str| {
+            store.before_write(path).unwrap();
+            FileService::write_file(path, content).unwrap();
+        };
+
+        // Writes outside a turn are not checkpointed.
+        write(&file, "user edit");
+        assert!(store.timeline("conv").unwrap().is_empty());
+
+        let turn = store.begin_turn("conv", "Agent turn 1");
+        write(&file, "agent edit 1");
+        write(&workspace.path().join("new/mod.rs"), "agent");
+        let checkpoint = turn.checkpoint().unwrap();
+        assert_eq!(checkpoint.label, "Agent turn 1");
+        drop(turn);
+
+        let timeline = store.timeline("conv").unwrap();
+        assert_eq!(timeline.len(), 1);
+        store.restore(&checkpoint.id).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "user edit");
+        assert!(!workspace.path().join("new/mod.rs").exists());
+
+        // A write elsewhere does not trigger this workspace's turn.
+        let elsewhere = tempfile::tempdir().unwrap();
+        let turn = store.begin_turn("conv", "Agent turn 2");
+        write(&elsewhere.path().join("x.txt"), "x");
+        assert!(turn.checkpoint().is_none());
+    }
+
+    #[test]
+    fn test_turn_is_armed_on_its_own_store_only() {
+        let (workspace, _storage, store) = setup();
+        let other_storage = tempfile::tempdir().unwrap();
+        let other = CheckpointStore::open_at(workspace.path(), other_storage.path()).unwrap();
+        let file = workspace.path().join("code.rs");
+        fs::write(&file, "original").unwrap();
+
+        let turn = store.begin_turn("conv", "Agent turn");
+        other.before_write(&file).unwrap();
+        assert!(turn.checkpoint().is_none());
+
+        store.before_write(&file).unwrap();
+        assert!(turn.checkpoint().is_some());
+        drop(turn);
+        assert!(store.armed().is_empty());
+    }
+}
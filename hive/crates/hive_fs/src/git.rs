@@ -1,5 +1,10 @@
-use anyhow::{Context, Result};
-use git2::{DiffOptions, Repository, StatusOptions};
+use anyhow::{Context, Result, bail};
+use git2::{
+    BranchType, Delta, DiffOptions, ErrorCode, IndexEntry, IndexTime, ObjectType, Oid, Patch,
+    Repository, StashFlags, StatusOptions, build::CheckoutBuilder,
+};
+use std::collections::HashMap;
+use std::ops::{Range, RangeInclusive};
 use std::path::{Path, PathBuf};
 use tracing::debug;
 
@@ -29,6 +34,107 @@ pub struct GitLogEntry {
     pub timestamp: i64,
 }
 
+/// Which two states of the repository a structured diff compares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffTarget {
+    /// Index against the working tree: changes not yet staged, including
+    /// untracked files.
+    Unstaged,
+    /// HEAD against the index: changes staged for the next commit.
+    Staged,
+}
+
+/// Whether a diff line is shared by both sides, or only on one of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+/// A single line of a diff hunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// 1-based line number on the old side; `None` for added lines.
+    pub old_lineno: Option<u32>,
+    /// 1-based line number on the new side; `None` for removed lines.
+    pub new_lineno: Option<u32>,
+    /// Line text without its line ending.
+    pub content: String,
+    /// The line ends its file without a trailing newline.
+    pub no_newline_at_eof: bool,
+}
+
+/// A contiguous block of changes with surrounding context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// The `@@ -a,b +c,d @@` header line.
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<DiffLine>,
+}
+
+/// The changes to one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    /// Path relative to the repo root (the old path for deletions).
+    pub path: PathBuf,
+    pub status: FileStatusType,
+    /// Binary files have no hunks.
+    pub binary: bool,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// The commit a blamed line was last changed in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameCommit {
+    pub hash: String,
+    pub author: String,
+    pub email: String,
+    pub timestamp: i64,
+    pub summary: String,
+}
+
+/// One line of a blamed file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+    /// 1-based line number in the working tree copy of the file.
+    pub line: usize,
+    pub content: String,
+    /// `None` when the line has not been committed yet.
+    pub commit: Option<BlameCommit>,
+}
+
+/// An entry in the stash list; `index` 0 is the most recent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StashEntry {
+    pub index: usize,
+    pub message: String,
+    pub commit: String,
+}
+
+/// A local or remote-tracking branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchInfo {
+    /// Short name: `main` for a local branch, `origin/main` for a remote one.
+    pub name: String,
+    pub is_remote: bool,
+    /// The branch is checked out.
+    pub is_head: bool,
+    pub commit: String,
+    pub summary: String,
+    /// Upstream of a local branch, e.g. `origin/main`.
+    pub upstream: Option<String>,
+    /// Commits on the branch that its upstream does not have.
+    pub ahead: usize,
+    /// Commits on the upstream that the branch does not have.
+    pub behind: usize,
+}
+
 /// Git operations service wrapping a `git2::Repository`.
 pub struct GitService {
     repo: Repository,
@@ -246,6 +352,736 @@ impl GitService {
 
         Ok(entries)
     }
+
+    // -- Structured diffs ----------------------------------------------------
+
+    /// Diff every changed file, split into hunks and lines.
+    pub fn diff_files(&self, target: DiffTarget) -> Result<Vec<FileDiff>> {
+        let diff = self.diff_for(target, None)?;
+        let mut files = Vec::new();
+        for index in 0..diff.deltas().len() {
+            if let Some(file) = file_diff(&diff, index)? {
+                files.push(file);
+            }
+        }
+        Ok(files)
+    }
+
+    /// Diff a single file. Returns `None` when it has no changes.
+    pub fn diff_file(&self, path: &Path, target: DiffTarget) -> Result<Option<FileDiff>> {
+        let diff = self.diff_for(target, Some(path))?;
+        if diff.deltas().len() == 0 {
+            return Ok(None);
+        }
+        file_diff(&diff, 0)
+    }
+
+    fn diff_for(&self, target: DiffTarget, path: Option<&Path>) -> Result<git2::Diff<'_>> {
+        let mut opts = DiffOptions::new();
+        if let Some(path) = path {
+            opts.pathspec(path).disable_pathspec_match(true);
+        }
+        match target {
+            DiffTarget::Unstaged => {
+                opts.include_untracked(true)
+                    .recurse_untracked_dirs(true)
+                    .show_untracked_content(true);
+                self.repo.diff_index_to_workdir(None, Some(&mut opts))
+            }
+            DiffTarget::Staged => {
+                let head_tree = self.head_tree();
+                self.repo
+                    .diff_tree_to_index(head_tree.as_ref(), None, Some(&mut opts))
+            }
+        }
+        .context("Failed to generate diff")
+    }
+
+    // -- Hunk staging --------------------------------------------------------
+    //
+    // Hunks are addressed by their index in `diff_file(path, target).hunks`,
+    // and line ranges by indices into that hunk's `lines`; only the added and
+    // removed lines inside the range are acted on.
+
+    /// Stage one hunk of the file's unstaged changes.
+    pub fn stage_hunk(&self, path: &Path, hunk: usize) -> Result<()> {
+        self.stage_selection(path, hunk, None)
+    }
+
+    /// Stage some lines of one hunk of the file's unstaged changes.
+    pub fn stage_lines(&self, path: &Path, hunk: usize, lines: Range<usize>) -> Result<()> {
+        self.stage_selection(path, hunk, Some(lines))
+    }
+
+    /// Unstage one hunk of the file's staged changes.
+    pub fn unstage_hunk(&self, path: &Path, hunk: usize) -> Result<()> {
+        self.unstage_selection(path, hunk, None)
+    }
+
+    /// Unstage some lines of one hunk of the file's staged changes.
+    pub fn unstage_lines(&self, path: &Path, hunk: usize, lines: Range<usize>) -> Result<()> {
+        self.unstage_selection(path, hunk, Some(lines))
+    }
+
+    /// Throw away one hunk of the file's unstaged changes, restoring the
+    /// working tree from the index.
+    pub fn discard_hunk(&self, path: &Path, hunk: usize) -> Result<()> {
+        self.discard_selection(path, hunk, None)
+    }
+
+    /// Throw away some lines of one hunk of the file's unstaged changes.
+    pub fn discard_lines(&self, path: &Path, hunk: usize, lines: Range<usize>) -> Result<()> {
+        self.discard_selection(path, hunk, Some(lines))
+    }
+
+    fn stage_selection(&self, path: &Path, hunk: usize, lines: Option<Range<usize>>) -> Result<()> {
+        let hunk_lines = self.hunk_lines(path, DiffTarget::Unstaged, hunk)?;
+        let base = self.index_content(path)?.unwrap_or_default();
+        let staged = apply_selection(&base, &hunk_lines, lines, Side::Old);
+        self.write_index_content(path, &staged)?;
+        debug!("Staged hunk {hunk} of {}", path.display());
+        Ok(())
+    }
+
+    fn unstage_selection(
+        &self,
+        path: &Path,
+        hunk: usize,
+        lines: Option<Range<usize>>,
+    ) -> Result<()> {
+        let hunk_lines = self.hunk_lines(path, DiffTarget::Staged, hunk)?;
+        let base = self.index_content(path)?.unwrap_or_default();
+        let unstaged = apply_selection(&base, &hunk_lines, lines, Side::New);
+
+        if unstaged.is_empty() && self.head_content(path)?.is_none() {
+            // Unstaging all of a newly added file takes it out of the index.
+            let mut index = self.repo.index().context("Failed to get index")?;
+            index
+                .remove_path(path)
+                .with_context(|| format!("Failed to unstage: {}", path.display()))?;
+            index.write().context("Failed to write index")?;
+        } else {
+            self.write_index_content(path, &unstaged)?;
+        }
+        debug!("Unstaged hunk {hunk} of {}", path.display());
+        Ok(())
+    }
+
+    fn discard_selection(
+        &self,
+        path: &Path,
+        hunk: usize,
+        lines: Option<Range<usize>>,
+    ) -> Result<()> {
+        let hunk_lines = self.hunk_lines(path, DiffTarget::Unstaged, hunk)?;
+        let full_path = self.workdir()?.join(path);
+        let base = read_if_exists(&full_path)?.unwrap_or_default();
+        let restored = apply_selection(&base, &hunk_lines, lines, Side::New);
+        std::fs::write(&full_path, restored)
+            .with_context(|| format!("Failed to write {}", full_path.display()))?;
+        debug!("Discarded hunk {hunk} of {}", path.display());
+        Ok(())
+    }
+
+    /// The raw lines of one hunk, as numbered by [`diff_file`](Self::diff_file).
+    fn hunk_lines(&self, path: &Path, target: DiffTarget, hunk: usize) -> Result<Vec<RawLine>> {
+        let diff = self.diff_for(target, Some(path))?;
+        let patch = match diff.deltas().len() {
+            0 => None,
+            _ => Patch::from_diff(&diff, 0).context("Failed to build patch")?,
+        };
+        let Some(patch) = patch else {
+            bail!("No changes to {}", path.display());
+        };
+        if patch.delta().flags().is_binary() {
+            bail!("Cannot select hunks of binary file {}", path.display());
+        }
+        if hunk >= patch.num_hunks() {
+            bail!(
+                "{} has {} hunk(s); there is no hunk {hunk}",
+                path.display(),
+                patch.num_hunks()
+            );
+        }
+
+        let mut lines = Vec::new();
+        for line_index in 0..patch.num_lines_in_hunk(hunk)? {
+            let line = patch.line_in_hunk(hunk, line_index)?;
+            let Some(kind) = line_kind(line.origin()) else {
+                continue;
+            };
+            lines.push(RawLine {
+                kind,
+                old_lineno: line.old_lineno(),
+                new_lineno: line.new_lineno(),
+                content: line.content().to_vec(),
+            });
+        }
+        Ok(lines)
+    }
+
+    fn head_tree(&self) -> Option<git2::Tree<'_>> {
+        self.repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_tree().ok())
+    }
+
+    fn workdir(&self) -> Result<&Path> {
+        self.repo
+            .workdir()
+            .context("Repository has no working directory")
+    }
+
+    fn head_content(&self, path: &Path) -> Result<Option<Vec<u8>>> {
+        let Some(tree) = self.head_tree() else {
+            return Ok(None);
+        };
+        let Ok(entry) = tree.get_path(path) else {
+            return Ok(None);
+        };
+        let blob = self
+            .repo
+            .find_blob(entry.id())
+            .with_context(|| format!("Failed to read {} at HEAD", path.display()))?;
+        Ok(Some(blob.content().to_vec()))
+    }
+
+    fn index_content(&self, path: &Path) -> Result<Option<Vec<u8>>> {
+        let index = self.repo.index().context("Failed to get index")?;
+        let Some(entry) = index.get_path(path, 0) else {
+            return Ok(None);
+        };
+        let blob = self
+            .repo
+            .find_blob(entry.id)
+            .with_context(|| format!("Failed to read staged {}", path.display()))?;
+        Ok(Some(blob.content().to_vec()))
+    }
+
+    /// Replace the staged content of `path`, adding it to the index if needed.
+    fn write_index_content(&self, path: &Path, content: &[u8]) -> Result<()> {
+        let mut index = self.repo.index().context("Failed to get index")?;
+        let entry = match index.get_path(path, 0) {
+            Some(entry) => entry,
+            None => new_index_entry(path, self.file_mode(path)),
+        };
+        index
+            .add_frombuffer(&entry, content)
+            .with_context(|| format!("Failed to stage: {}", path.display()))?;
+        index.write().context("Failed to write index")
+    }
+
+    /// Git file mode for a working tree file that is not in the index yet.
+    fn file_mode(&self, path: &Path) -> u32 {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let executable = self
+                .repo
+                .workdir()
+                .and_then(|dir| std::fs::metadata(dir.join(path)).ok())
+                .is_some_and(|meta| meta.permissions().mode() & 0o111 != 0);
+            if executable {
+                return 0o100755;
+            }
+        }
+        let _ = path;
+        0o100644
+    }
+
+    // -- Blame ---------------------------------------------------------------
+
+    /// Blame the working tree copy of a file, optionally limited to a range
+    /// of 1-based lines. Lines changed since the last commit have no commit.
+    pub fn blame(
+        &self,
+        path: &Path,
+        lines: Option<RangeInclusive<usize>>,
+    ) -> Result<Vec<BlameLine>> {
+        let committed = self
+            .repo
+            .blame_file(path, None)
+            .with_context(|| format!("Failed to blame {}", path.display()))?;
+        let content = match read_if_exists(&self.workdir()?.join(path))? {
+            Some(content) => content,
+            None => self.head_content(path)?.unwrap_or_default(),
+        };
+        let blame = committed
+            .blame_buffer(&content)
+            .with_context(|| format!("Failed to blame {}", path.display()))?;
+
+        let mut commits: HashMap<Oid, BlameCommit> = HashMap::new();
+        let mut result = Vec::new();
+        for (index, text) in String::from_utf8_lossy(&content).lines().enumerate() {
+            let line = index + 1;
+            if lines.as_ref().is_some_and(|range| !range.contains(&line)) {
+                continue;
+            }
+            let oid = blame
+                .get_line(line)
+                .map(|hunk| hunk.final_commit_id())
+                .filter(|oid| !oid.is_zero());
+            let commit = match oid {
+                Some(oid) => Some(match commits.get(&oid) {
+                    Some(commit) => commit.clone(),
+                    None => {
+                        let commit = self.blame_commit(oid)?;
+                        commits.insert(oid, commit.clone());
+                        commit
+                    }
+                }),
+                None => None,
+            };
+            result.push(BlameLine {
+                line,
+                content: text.to_string(),
+                commit,
+            });
+        }
+        Ok(result)
+    }
+
+    fn blame_commit(&self, oid: Oid) -> Result<BlameCommit> {
+        let commit = self
+            .repo
+            .find_commit(oid)
+            .with_context(|| format!("Failed to find commit {oid}"))?;
+        let author = commit.author();
+        Ok(BlameCommit {
+            hash: oid.to_string(),
+            author: author.name().unwrap_or("unknown").to_string(),
+            email: author.email().unwrap_or("").to_string(),
+            timestamp: commit.time().seconds(),
+            summary: commit.summary().unwrap_or("").to_string(),
+        })
+    }
+
+    // -- Stash ---------------------------------------------------------------
+
+    /// Stash local changes. Returns the stash commit hash, or `None` when
+    /// there was nothing to stash.
+    pub fn stash_push(
+        &mut self,
+        message: Option<&str>,
+        include_untracked: bool,
+    ) -> Result<Option<String>> {
+        let signature = self
+            .repo
+            .signature()
+            .context("Failed to get git signature. Configure user.name and user.email.")?;
+        let flags = if include_untracked {
+            StashFlags::INCLUDE_UNTRACKED
+        } else {
+            StashFlags::DEFAULT
+        };
+        match self.repo.stash_save2(&signature, message, Some(flags)) {
+            Ok(oid) => {
+                debug!("Stashed changes as {}", &oid.to_string()[..8]);
+                Ok(Some(oid.to_string()))
+            }
+            Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e).context("Failed to stash changes"),
+        }
+    }
+
+    /// List stashes, most recent first.
+    pub fn stash_list(&mut self) -> Result<Vec<StashEntry>> {
+        let mut entries = Vec::new();
+        self.repo
+            .stash_foreach(|index, message, oid| {
+                entries.push(StashEntry {
+                    index,
+                    message: message.to_string(),
+                    commit: oid.to_string(),
+                });
+                true
+            })
+            .context("Failed to list stashes")?;
+        Ok(entries)
+    }
+
+    /// Apply a stash and drop it. Fails without dropping it if applying
+    /// would overwrite local changes.
+    pub fn stash_pop(&mut self, index: usize) -> Result<()> {
+        self.repo
+            .stash_pop(index, None)
+            .with_context(|| format!("Failed to pop stash@{{{index}}}"))?;
+        debug!("Popped stash@{{{index}}}");
+        Ok(())
+    }
+
+    /// Delete a stash without applying it.
+    pub fn stash_drop(&mut self, index: usize) -> Result<()> {
+        self.repo
+            .stash_drop(index)
+            .with_context(|| format!("Failed to drop stash@{{{index}}}"))
+    }
+
+    // -- Branches ------------------------------------------------------------
+
+    /// Local branches followed by remote-tracking branches, each sorted by
+    /// name, with upstream and ahead/behind counts for local branches.
+    pub fn branches(&self) -> Result<Vec<BranchInfo>> {
+        let mut result = Vec::new();
+        for kind in [BranchType::Local, BranchType::Remote] {
+            let mut infos = Vec::new();
+            for branch in self
+                .repo
+                .branches(Some(kind))
+                .context("Failed to list branches")?
+            {
+                let (branch, _) = branch.context("Failed to read branch")?;
+                let Some(name) = branch.name()?.map(str::to_string) else {
+                    continue;
+                };
+                let Ok(commit) = branch.get().peel_to_commit() else {
+                    // Symbolic refs such as `origin/HEAD`.
+                    continue;
+                };
+                if kind == BranchType::Remote && name.ends_with("/HEAD") {
+                    continue;
+                }
+
+                let mut info = BranchInfo {
+                    name,
+                    is_remote: kind == BranchType::Remote,
+                    is_head: branch.is_head(),
+                    commit: commit.id().to_string(),
+                    summary: commit.summary().unwrap_or("").to_string(),
+                    upstream: None,
+                    ahead: 0,
+                    behind: 0,
+                };
+                if let Ok(upstream) = branch.upstream() {
+                    info.upstream = upstream.name()?.map(str::to_string);
+                    if let Some(upstream_oid) = upstream.get().target() {
+                        let (ahead, behind) = self
+                            .repo
+                            .graph_ahead_behind(commit.id(), upstream_oid)
+                            .context("Failed to compare with upstream")?;
+                        info.ahead = ahead;
+                        info.behind = behind;
+                    }
+                }
+                infos.push(info);
+            }
+            infos.sort_by(|a, b| a.name.cmp(&b.name));
+            result.extend(infos);
+        }
+        Ok(result)
+    }
+
+    /// Create a local branch at `start_point` (any revision, default HEAD)
+    /// without switching to it.
+    pub fn create_branch(&self, name: &str, start_point: Option<&str>) -> Result<()> {
+        let commit = self
+            .repo
+            .revparse_single(start_point.unwrap_or("HEAD"))
+            .and_then(|object| object.peel_to_commit())
+            .with_context(|| {
+                format!(
+                    "Failed to resolve start point {}",
+                    start_point.unwrap_or("HEAD")
+                )
+            })?;
+        self.repo
+            .branch(name, &commit, false)
+            .with_context(|| format!("Failed to create branch {name}"))?;
+        debug!("Created branch {name}");
+        Ok(())
+    }
+
+    /// Check out a local branch. When only a remote-tracking branch of that
+    /// name exists (e.g. `origin/name`), a local branch tracking it is
+    /// created first. Fails if local changes would be overwritten.
+    pub fn switch_branch(&self, name: &str) -> Result<()> {
+        let branch = match self.repo.find_branch(name, BranchType::Local) {
+            Ok(branch) => branch,
+            Err(e) if e.code() == ErrorCode::NotFound => self.track_remote_branch(name)?,
+            Err(e) => return Err(e).with_context(|| format!("Failed to find branch {name}")),
+        };
+        let refname = branch
+            .get()
+            .name()
+            .context("Branch name is not valid UTF-8")?
+            .to_string();
+        let target = branch
+            .get()
+            .peel(ObjectType::Commit)
+            .with_context(|| format!("Branch {name} has no commit"))?;
+
+        self.repo
+            .checkout_tree(&target, Some(CheckoutBuilder::new().safe()))
+            .with_context(|| format!("Cannot switch to {name}"))?;
+        self.repo
+            .set_head(&refname)
+            .with_context(|| format!("Failed to switch to {name}"))?;
+        debug!("Switched to branch {name}");
+        Ok(())
+    }
+
+    fn track_remote_branch(&self, name: &str) -> Result<git2::Branch<'_>> {
+        let mut candidates = Vec::new();
+        for branch in self.repo.branches(Some(BranchType::Remote))? {
+            let (branch, _) = branch?;
+            if let Some(remote_name) = branch.name()?
+                && remote_name.split_once('/').map(|(_, rest)| rest) == Some(name)
+            {
+                candidates.push(remote_name.to_string());
+            }
+        }
+        let remote_name = match candidates.as_slice() {
+            [only] => only.clone(),
+            [] => bail!("No branch named {name}"),
+            _ => bail!(
+                "{name} exists on several remotes ({}); create the branch explicitly",
+                candidates.join(", ")
+            ),
+        };
+
+        let commit = self
+            .repo
+            .find_branch(&remote_name, BranchType::Remote)?
+            .get()
+            .peel_to_commit()?;
+        let mut branch = self
+            .repo
+            .branch(name, &commit, false)
+            .with_context(|| format!("Failed to create branch {name}"))?;
+        branch
+            .set_upstream(Some(&remote_name))
+            .with_context(|| format!("Failed to track {remote_name}"))?;
+        Ok(branch)
+    }
+
+    /// Delete a local branch. Refuses the checked-out branch, and unless
+    /// `force` is set, a branch whose commits are not all in HEAD.
+    pub fn delete_branch(&self, name: &str, force: bool) -> Result<()> {
+        let mut branch = self
+            .repo
+            .find_branch(name, BranchType::Local)
+            .with_context(|| format!("No branch named {name}"))?;
+        if branch.is_head() {
+            bail!("Cannot delete the checked-out branch {name}");
+        }
+        if !force {
+            let tip = branch.get().peel_to_commit()?.id();
+            let head = self
+                .repo
+                .head()
+                .and_then(|head| head.peel_to_commit())
+                .context("Failed to resolve HEAD")?
+                .id();
+            if tip != head && !self.repo.graph_descendant_of(head, tip)? {
+                bail!("Branch {name} is not fully merged; force the delete to discard it");
+            }
+        }
+        branch
+            .delete()
+            .with_context(|| format!("Failed to delete branch {name}"))?;
+        debug!("Deleted branch {name}");
+        Ok(())
+    }
+
+    /// Set (or with `None`, clear) the upstream of a local branch, e.g.
+    /// `origin/main`.
+    pub fn set_upstream(&self, name: &str, upstream: Option<&str>) -> Result<()> {
+        let mut branch = self
+            .repo
+            .find_branch(name, BranchType::Local)
+            .with_context(|| format!("No branch named {name}"))?;
+        branch
+            .set_upstream(upstream)
+            .with_context(|| format!("Failed to set upstream of {name}"))
+    }
+
+    /// Commits reachable from `local` but not `upstream`, and the reverse.
+    /// Both sides accept any revision.
+    pub fn ahead_behind(&self, local: &str, upstream: &str) -> Result<(usize, usize)> {
+        let resolve = |rev: &str| {
+            self.repo
+                .revparse_single(rev)
+                .and_then(|object| object.peel_to_commit())
+                .map(|commit| commit.id())
+                .with_context(|| format!("Failed to resolve {rev}"))
+        };
+        self.repo
+            .graph_ahead_behind(resolve(local)?, resolve(upstream)?)
+            .context("Failed to count commits")
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Diff helpers
+// ---------------------------------------------------------------------------
+
+/// A hunk line with its content exactly as stored, line ending included.
+struct RawLine {
+    kind: DiffLineKind,
+    old_lineno: Option<u32>,
+    new_lineno: Option<u32>,
+    content: Vec<u8>,
+}
+
+/// Which side of a hunk the base content of [`apply_selection`] is.
+#[derive(Clone, Copy)]
+enum Side {
+    /// Base is the old side; selected changes are applied to it.
+    Old,
+    /// Base is the new side; selected changes are reverted from it.
+    New,
+}
+
+/// Apply the selected lines of a hunk to `base`, leaving everything else
+/// as it is in `base`. `lines` indexes into the hunk; `None` selects all.
+fn apply_selection(
+    base: &[u8],
+    hunk: &[RawLine],
+    lines: Option<Range<usize>>,
+    side: Side,
+) -> Vec<u8> {
+    let base_lines: Vec<&[u8]> = base.split_inclusive(|&b| b == b'\n').collect();
+    let (on_base, lineno): (DiffLineKind, fn(&RawLine) -> Option<u32>) = match side {
+        Side::Old => (DiffLineKind::Removed, |line| line.old_lineno),
+        Side::New => (DiffLineKind::Added, |line| line.new_lineno),
+    };
+    let selected = |index: usize| lines.as_ref().is_none_or(|range| range.contains(&index));
+
+    let mut out = Vec::with_capacity(base.len());
+    let mut pos = 0;
+    for (index, line) in hunk.iter().enumerate() {
+        if line.kind == DiffLineKind::Context || line.kind == on_base {
+            // A line present in the base: copy up to it, then keep it unless
+            // the change removing it is selected.
+            let Some(n) = lineno(line).map(|n| n as usize).filter(|&n| n > pos) else {
+                continue;
+            };
+            for base_line in base_lines.iter().take(n - 1).skip(pos) {
+                push_line(&mut out, base_line);
+            }
+            if (line.kind == DiffLineKind::Context || !selected(index))
+                && let Some(base_line) = base_lines.get(n - 1)
+            {
+                push_line(&mut out, base_line);
+            }
+            pos = n;
+        } else if selected(index) {
+            push_line(&mut out, &line.content);
+        }
+    }
+    for base_line in base_lines.iter().skip(pos) {
+        push_line(&mut out, base_line);
+    }
+    out
+}
+
+/// Append a line, first ending the previous one if it lacked a newline: a
+/// line without one can only be the last.
+fn push_line(out: &mut Vec<u8>, line: &[u8]) {
+    if out.last().is_some_and(|&b| b != b'\n') {
+        out.push(b'\n');
+    }
+    out.extend_from_slice(line);
+}
+
+fn line_kind(origin: char) -> Option<DiffLineKind> {
+    match origin {
+        ' ' => Some(DiffLineKind::Context),
+        '+' => Some(DiffLineKind::Added),
+        '-' => Some(DiffLineKind::Removed),
+        // File headers and "no newline at end of file" markers.
+        _ => None,
+    }
+}
+
+pub(crate) fn file_diff(diff: &git2::Diff<'_>, index: usize) -> Result<Option<FileDiff>> {
+    let Some(delta) = diff.get_delta(index) else {
+        return Ok(None);
+    };
+    let status = match delta.status() {
+        Delta::Added => FileStatusType::Added,
+        Delta::Untracked => FileStatusType::Untracked,
+        Delta::Deleted => FileStatusType::Deleted,
+        Delta::Renamed => FileStatusType::Renamed,
+        Delta::Unmodified | Delta::Ignored => return Ok(None),
+        _ => FileStatusType::Modified,
+    };
+    let path = delta
+        .new_file()
+        .path()
+        .or_else(|| delta.old_file().path())
+        .context("Diff entry has no path")?
+        .to_path_buf();
+
+    let patch = Patch::from_diff(diff, index).context("Failed to build patch")?;
+    let binary = delta.flags().is_binary() || patch.is_none();
+    let mut hunks = Vec::new();
+    if let Some(patch) = patch.filter(|_| !binary) {
+        for hunk_index in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(hunk_index)?;
+            let mut lines = Vec::with_capacity(line_count);
+            for line_index in 0..line_count {
+                let line = patch.line_in_hunk(hunk_index, line_index)?;
+                let Some(kind) = line_kind(line.origin()) else {
+                    continue;
+                };
+                let raw = line.content();
+                let text = String::from_utf8_lossy(raw);
+                let text = text.strip_suffix('\n').unwrap_or(&text);
+                lines.push(DiffLine {
+                    kind,
+                    old_lineno: line.old_lineno(),
+                    new_lineno: line.new_lineno(),
+                    content: text.strip_suffix('\r').unwrap_or(text).to_string(),
+                    no_newline_at_eof: !raw.ends_with(b"\n"),
+                });
+            }
+            hunks.push(DiffHunk {
+                header: String::from_utf8_lossy(hunk.header())
+                    .trim_end()
+                    .to_string(),
+                old_start: hunk.old_start(),
+                old_lines: hunk.old_lines(),
+                new_start: hunk.new_start(),
+                new_lines: hunk.new_lines(),
+                lines,
+            });
+        }
+    }
+
+    Ok(Some(FileDiff {
+        path,
+        status,
+        binary,
+        hunks,
+    }))
+}
+
+fn new_index_entry(path: &Path, mode: u32) -> IndexEntry {
+    let path = path.to_string_lossy().replace('\\', "/").into_bytes();
+    IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode,
+        uid: 0,
+        gid: 0,
+        file_size: 0,
+        id: Oid::zero(),
+        flags: path.len().min(0xfff) as u16,
+        flags_extended: 0,
+        path,
+    }
+}
+
+fn read_if_exists(path: &Path) -> Result<Option<Vec<u8>>> {
+    match std::fs::read(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
 }
 
 #[cfg(test)]
@@ -348,4 +1184,373 @@ mod tests {
                 .any(|s| s.status == FileStatusType::Modified)
         );
     }
+
+    // -- Structured diffs and hunk staging -----------------------------------
+
+    fn commit_file(dir: &TempDir, git: &GitService, name: &str, content: &str) {
+        fs::write(dir.path().join(name), content).unwrap();
+        git.stage(&[Path::new(name)]).unwrap();
+        git.commit(&format!("update {name}")).unwrap();
+    }
+
+    fn staged_content(git: &GitService, name: &str) -> String {
+        String::from_utf8(git.index_content(Path::new(name)).unwrap().unwrap()).unwrap()
+    }
+
+    /// Twenty numbered lines, with lines 2 and 17 changed so they land in
+    /// separate hunks.
+    fn two_hunk_change(dir: &TempDir, git: &GitService) {
+        let original: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+        commit_file(dir, git, "f.txt", &original);
+        let changed = original
+            .replace("line 2\n", "line 2 changed\n")
+            .replace("line 17\n", "line 17 changed\nline 17b\n");
+        fs::write(dir.path().join("f.txt"), changed).unwrap();
+    }
+
+    #[test]
+    fn test_structured_diff() {
+        let (dir, git) = setup_repo();
+        two_hunk_change(&dir, &git);
+        fs::write(dir.path().join("new.txt"), "fresh").unwrap();
+
+        let files = git.diff_files(DiffTarget::Unstaged).unwrap();
+        assert_eq!(files.len(), 2);
+        let file = files.iter().find(|f| f.path == Path::new("f.txt")).unwrap();
+        assert_eq!(file.status, FileStatusType::Modified);
+        assert_eq!(file.hunks.len(), 2);
+
+        let hunk = &file.hunks[0];
+        assert!(hunk.header.starts_with("@@ -1,5 +1,5 @@"));
+        let removed = hunk
+            .lines
+            .iter()
+            .find(|l| l.kind == DiffLineKind::Removed)
+            .unwrap();
+        assert_eq!(removed.content, "line 2");
+        assert_eq!((removed.old_lineno, removed.new_lineno), (Some(2), None));
+        let added = hunk
+            .lines
+            .iter()
+            .find(|l| l.kind == DiffLineKind::Added)
+            .unwrap();
+        assert_eq!(added.content, "line 2 changed");
+        assert_eq!((added.old_lineno, added.new_lineno), (None, Some(2)));
+
+        let untracked = files
+            .iter()
+            .find(|f| f.path == Path::new("new.txt"))
+            .unwrap();
+        assert_eq!(untracked.status, FileStatusType::Untracked);
+        let line = &untracked.hunks[0].lines[0];
+        assert_eq!(line.content, "fresh");
+        assert!(line.no_newline_at_eof);
+
+        assert!(git.diff_files(DiffTarget::Staged).unwrap().is_empty());
+        assert!(
+            git.diff_file(Path::new("missing.txt"), DiffTarget::Unstaged)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_stage_and_unstage_hunk() {
+        let (dir, git) = setup_repo();
+        two_hunk_change(&dir, &git);
+        let path = Path::new("f.txt");
+
+        git.stage_hunk(path, 1).unwrap();
+        let staged = staged_content(&git, "f.txt");
+        assert!(staged.contains("line 2\n"));
+        assert!(staged.contains("line 17 changed\nline 17b\n"));
+
+        let staged_diff = git.diff_file(path, DiffTarget::Staged).unwrap().unwrap();
+        assert_eq!(staged_diff.hunks.len(), 1);
+        let unstaged_diff = git.diff_file(path, DiffTarget::Unstaged).unwrap().unwrap();
+        assert_eq!(unstaged_diff.hunks.len(), 1);
+        assert!(unstaged_diff.hunks[0].header.starts_with("@@ -1,5 +1,5 @@"));
+
+        git.unstage_hunk(path, 0).unwrap();
+        assert!(git.diff_file(path, DiffTarget::Staged).unwrap().is_none());
+        // The working tree is untouched throughout.
+        let worktree = fs::read_to_string(dir.path().join("f.txt")).unwrap();
+        assert!(worktree.contains("line 2 changed\n"));
+        assert!(worktree.contains("line 17b\n"));
+    }
+
+    #[test]
+    fn test_stage_lines() {
+        let (dir, git) = setup_repo();
+        two_hunk_change(&dir, &git);
+        let path = Path::new("f.txt");
+
+        // Stage only the added "line 17b", not the change to line 17.
+        let diff = git.diff_file(path, DiffTarget::Unstaged).unwrap().unwrap();
+        let hunk = &diff.hunks[1];
+        let index = hunk
+            .lines
+            .iter()
+            .position(|l| l.content == "line 17b")
+            .unwrap();
+        git.stage_lines(path, 1, index..index + 1).unwrap();
+
+        let expected: String = (1..=20)
+            .flat_map(|i| {
+                let mut lines = vec![format!("line {i}\n")];
+                if i == 17 {
+                    lines.push("line 17b\n".into());
+                }
+                lines
+            })
+            .collect();
+        assert_eq!(staged_content(&git, "f.txt"), expected);
+    }
+
+    #[test]
+    fn test_discard_hunk() {
+        let (dir, git) = setup_repo();
+        two_hunk_change(&dir, &git);
+        let path = Path::new("f.txt");
+
+        git.discard_hunk(path, 0).unwrap();
+        let worktree = fs::read_to_string(dir.path().join("f.txt")).unwrap();
+        assert!(worktree.contains("line 2\n"));
+        assert!(worktree.contains("line 17 changed\nline 17b\n"));
+
+        let diff = git.diff_file(path, DiffTarget::Unstaged).unwrap().unwrap();
+        assert_eq!(diff.hunks.len(), 1);
+        git.discard_hunk(path, 0).unwrap();
+        assert!(git.diff_file(path, DiffTarget::Unstaged).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_stage_hunk_of_untracked_file() {
+        let (dir, git) = setup_repo();
+        commit_file(&dir, &git, "a.txt", "a\n");
+        fs::write(dir.path().join("new.txt"), "one\ntwo").unwrap();
+        let path = Path::new("new.txt");
+
+        git.stage_hunk(path, 0).unwrap();
+        assert_eq!(staged_content(&git, "new.txt"), "one\ntwo");
+        assert!(git.diff_file(path, DiffTarget::Unstaged).unwrap().is_none());
+
+        // Unstaging all of it takes the file out of the index again.
+        git.unstage_hunk(path, 0).unwrap();
+        assert!(git.index_content(path).unwrap().is_none());
+        assert!(
+            git.status()
+                .unwrap()
+                .iter()
+                .any(|s| s.path == path && s.status == FileStatusType::Untracked)
+        );
+    }
+
+    #[test]
+    fn test_hunk_errors() {
+        let (dir, git) = setup_repo();
+        two_hunk_change(&dir, &git);
+        let err = git.stage_hunk(Path::new("f.txt"), 5).unwrap_err();
+        assert!(err.to_string().contains("2 hunk(s)"), "{err}");
+        assert!(git.stage_hunk(Path::new("clean.txt"), 0).is_err());
+    }
+
+    // -- Blame ----------------------------------------------------------------
+
+    #[test]
+    fn test_blame() {
+        let (dir, git) = setup_repo();
+        commit_file(&dir, &git, "b.txt", "first\nsecond\n");
+        commit_file(&dir, &git, "b.txt", "first\nsecond edited\n");
+        fs::write(
+            dir.path().join("b.txt"),
+            "first\nsecond edited\nuncommitted\n",
+        )
+        .unwrap();
+
+        let lines = git.blame(Path::new("b.txt"), None).unwrap();
+        assert_eq!(lines.len(), 3);
+        let log = git.log(10).unwrap();
+        let first = lines[0].commit.as_ref().unwrap();
+        assert_eq!(first.hash, log[1].hash);
+        assert_eq!(first.author, "Test User");
+        assert_eq!(first.email, "test@example.com");
+        assert_eq!(first.summary, "update b.txt");
+        assert_eq!(lines[1].commit.as_ref().unwrap().hash, log[0].hash);
+        assert_eq!(lines[1].content, "second edited");
+        assert!(lines[2].commit.is_none());
+
+        let only_second = git.blame(Path::new("b.txt"), Some(2..=2)).unwrap();
+        assert_eq!(only_second.len(), 1);
+        assert_eq!(only_second[0].line, 2);
+    }
+
+    // -- Stash ----------------------------------------------------------------
+
+    #[test]
+    fn test_stash_push_list_pop() {
+        let (dir, mut git) = setup_repo();
+        commit_file(&dir, &git, "s.txt", "clean\n");
+        assert!(git.stash_push(None, false).unwrap().is_none());
+
+        fs::write(dir.path().join("s.txt"), "dirty\n").unwrap();
+        fs::write(dir.path().join("extra.txt"), "untracked\n").unwrap();
+        let stash = git.stash_push(Some("wip"), true).unwrap().unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("s.txt")).unwrap(),
+            "clean\n"
+        );
+        assert!(!dir.path().join("extra.txt").exists());
+
+        let stashes = git.stash_list().unwrap();
+        assert_eq!(stashes.len(), 1);
+        assert_eq!(stashes[0].index, 0);
+        assert_eq!(stashes[0].commit, stash);
+        assert!(stashes[0].message.contains("wip"));
+
+        git.stash_pop(0).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("s.txt")).unwrap(),
+            "dirty\n"
+        );
+        assert!(dir.path().join("extra.txt").exists());
+        assert!(git.stash_list().unwrap().is_empty());
+        assert!(git.stash_drop(0).is_err());
+    }
+
+    // -- Branches -------------------------------------------------------------
+
+    #[test]
+    fn test_branch_create_switch_delete() {
+        let (dir, git) = setup_repo();
+        commit_file(&dir, &git, "a.txt", "a\n");
+        let main = git.current_branch().unwrap();
+
+        git.create_branch("feature", None).unwrap();
+        git.switch_branch("feature").unwrap();
+        assert_eq!(git.current_branch().unwrap(), "feature");
+        commit_file(&dir, &git, "feature.txt", "feature\n");
+
+        git.switch_branch(&main).unwrap();
+        assert!(!dir.path().join("feature.txt").exists());
+
+        let branches = git.branches().unwrap();
+        let names: Vec<_> = branches.iter().map(|b| b.name.as_str()).collect();
+        assert!(names.contains(&"feature"));
+        assert!(branches.iter().any(|b| b.name == main && b.is_head));
+
+        let err = git.delete_branch("feature", false).unwrap_err();
+        assert!(err.to_string().contains("not fully merged"), "{err}");
+        let err = git.delete_branch(&main, true).unwrap_err();
+        assert!(err.to_string().contains("checked-out"), "{err}");
+        git.delete_branch("feature", true).unwrap();
+        assert!(git.branches().unwrap().iter().all(|b| b.name != "feature"));
+    }
+
+    #[test]
+    fn test_switch_refuses_to_overwrite_local_changes() {
+        let (dir, git) = setup_repo();
+        commit_file(&dir, &git, "a.txt", "a\n");
+        let main = git.current_branch().unwrap();
+        git.create_branch("other", None).unwrap();
+        git.switch_branch("other").unwrap();
+        commit_file(&dir, &git, "a.txt", "other\n");
+        git.switch_branch(&main).unwrap();
+
+        fs::write(dir.path().join("a.txt"), "local edit\n").unwrap();
+        assert!(git.switch_branch("other").is_err());
+        assert_eq!(git.current_branch().unwrap(), main);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "local edit\n"
+        );
+    }
+
+    #[test]
+    fn test_upstream_tracking_and_ahead_behind() {
+        let (dir, git) = setup_repo();
+        commit_file(&dir, &git, "a.txt", "a\n");
+        let main = git.current_branch().unwrap();
+        git.repo
+            .remote("origin", "https://example.com/repo.git")
+            .unwrap();
+
+        // The remote has one commit we lack; we have two it lacks.
+        let base = git.repo.head().unwrap().peel_to_commit().unwrap();
+        git.create_branch("remote-work", None).unwrap();
+        git.switch_branch("remote-work").unwrap();
+        commit_file(&dir, &git, "r.txt", "r\n");
+        let remote_tip = git.repo.head().unwrap().target().unwrap();
+        git.repo
+            .reference("refs/remotes/origin/main", remote_tip, true, "test")
+            .unwrap();
+        git.repo
+            .reference("refs/remotes/origin/topic", base.id(), true, "test")
+            .unwrap();
+        git.switch_branch(&main).unwrap();
+        git.delete_branch("remote-work", true).unwrap();
+        commit_file(&dir, &git, "l1.txt", "1\n");
+        commit_file(&dir, &git, "l2.txt", "2\n");
+
+        git.set_upstream(&main, Some("origin/main")).unwrap();
+        let branches = git.branches().unwrap();
+        let local = branches.iter().find(|b| b.name == main).unwrap();
+        assert_eq!(local.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((local.ahead, local.behind), (2, 1));
+        assert!(
+            branches
+                .iter()
+                .any(|b| b.name == "origin/main" && b.is_remote)
+        );
+        assert_eq!(git.ahead_behind("HEAD", "origin/main").unwrap(), (2, 1));
+
+        // Switching to a name that only exists on the remote tracks it.
+        git.switch_branch("topic").unwrap();
+        let branches = git.branches().unwrap();
+        let topic = branches.iter().find(|b| b.name == "topic").unwrap();
+        assert!(topic.is_head);
+        assert_eq!(topic.upstream.as_deref(), Some("origin/topic"));
+        assert_eq!((topic.ahead, topic.behind), (0, 0));
+
+        git.set_upstream("topic", None).unwrap();
+        let branches = git.branches().unwrap();
+        assert!(
+            branches
+                .iter()
+                .find(|b| b.name == "topic")
+                .unwrap()
+                .upstream
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_apply_selection_handles_missing_final_newline() {
+        let hunk = [
+            RawLine {
+                kind: DiffLineKind::Removed,
+                old_lineno: Some(1),
+                new_lineno: None,
+                content: b"a".to_vec(),
+            },
+            RawLine {
+                kind: DiffLineKind::Added,
+                old_lineno: None,
+                new_lineno: Some(1),
+                content: b"a\n".to_vec(),
+            },
+            RawLine {
+                kind: DiffLineKind::Added,
+                old_lineno: None,
+                new_lineno: Some(2),
+                content: b"b\n".to_vec(),
+            },
+        ];
+        assert_eq!(
+            apply_selection(b"a", &hunk, Some(2..3), Side::Old),
+            b"a\nb\n"
+        );
+        assert_eq!(apply_selection(b"a", &hunk, None, Side::Old), b"a\nb\n");
+        assert_eq!(apply_selection(b"a\nb\n", &hunk, None, Side::New), b"a");
+    }
 }
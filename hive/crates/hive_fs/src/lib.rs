@@ -1,11 +1,21 @@
 // Phase 3: File operations, search, git
 
+pub mod checkpoint;
 pub mod files;
 pub mod git;
 pub mod search;
 pub mod watcher;
 
+pub use checkpoint::{Checkpoint, CheckpointStore, CheckpointTurn, RestoreSummary};
 pub use files::{DirEntry, FileService, FileStats};
-pub use git::{FileStatusType, GitFileStatus, GitLogEntry, GitService};
-pub use search::{SearchOptions, SearchResult, SearchService, is_likely_binary};
-pub use watcher::{FileWatcher, WatchEvent};
+pub use git::{
+    BlameCommit, BlameLine, BranchInfo, DiffHunk, DiffLine, DiffLineKind, DiffTarget, FileDiff,
+    FileStatusType, GitFileStatus, GitLogEntry, GitService, StashEntry,
+};
+pub use search::{
+    FileReplacement, REPLACE_TIMELINE, ReplaceOutcome, ReplacePreview, ReplacementEdit, SearchMode,
+    SearchOptions, SearchResult, SearchService, is_likely_binary,
+};
+pub use watcher::{
+    BulkReason, ChangeSet, DebouncedWatcher, FileWatcher, WatchEvent, WatcherConfig,
+};
@@ -1,10 +1,33 @@
-use anyhow::{Context, Result};
-use ignore::WalkBuilder;
-use regex::RegexBuilder;
+use anyhow::{Context, Result, bail};
+use ignore::{WalkBuilder, WalkState};
+use regex::{Regex, RegexBuilder};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use tracing::debug;
 
+use crate::checkpoint::{Checkpoint, CheckpointStore};
+
+/// Checkpoint timeline that search-and-replace snapshots are recorded on.
+pub const REPLACE_TIMELINE: &str = "search-replace";
+
+/// How the search pattern is interpreted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchMode {
+    /// A regular expression.
+    #[default]
+    Regex,
+    /// Plain text.
+    Literal,
+    /// Plain text that must start and end at word boundaries.
+    WholeWord,
+    /// Characters matched in order against file paths, best match first.
+    /// File contents are not read.
+    FuzzyFilename,
+}
+
 /// Options controlling how a search is performed.
 #[derive(Debug, Clone)]
 pub struct SearchOptions {
@@ -12,6 +35,13 @@ pub struct SearchOptions {
     pub max_results: usize,
     pub include_hidden: bool,
     pub file_pattern: Option<String>,
+    pub mode: SearchMode,
+    /// Match against whole files instead of line by line, so a match can
+    /// span lines. `.` also matches newlines and `^`/`$` match at line
+    /// boundaries.
+    pub multiline: bool,
+    /// Lines of context to include before and after each match.
+    pub context_lines: usize,
 }
 
 impl Default for SearchOptions {
@@ -21,108 +51,267 @@ impl Default for SearchOptions {
             max_results: 100,
             include_hidden: false,
             file_pattern: None,
+            mode: SearchMode::default(),
+            multiline: false,
+            context_lines: 0,
         }
     }
 }
 
 /// A single search match with location and context.
+///
+/// For [`SearchMode::FuzzyFilename`] `line_number` is 0 and `line_content`
+/// is the path relative to the search root, with the match span covering the
+/// first to last matched character.
 #[derive(Debug, Clone)]
 pub struct SearchResult {
     pub path: PathBuf,
     pub line_number: usize,
+    /// The matched line, or every line the match spans in multiline mode.
     pub line_content: String,
+    /// Byte offsets of the match within `line_content`.
     pub match_start: usize,
     pub match_end: usize,
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
+}
+
+/// One changed region in a [`FileReplacement`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplacementEdit {
+    /// First line of the region, 1-based.
+    pub line_number: usize,
+    /// The lines as they are now.
+    pub before: String,
+    /// The same lines after replacement.
+    pub after: String,
+}
+
+/// The replacements that would be made in one file.
+#[derive(Debug, Clone)]
+pub struct FileReplacement {
+    pub path: PathBuf,
+    pub replacements: usize,
+    pub edits: Vec<ReplacementEdit>,
+    pub new_content: String,
+    /// Content the preview was computed from, checked again before writing.
+    original: String,
+}
+
+/// Result of [`SearchService::preview_replace`]. Nothing is written until it
+/// is passed to [`SearchService::apply_replace`].
+#[derive(Debug, Clone)]
+pub struct ReplacePreview {
+    pub pattern: String,
+    pub replacement: String,
+    /// Files with at least one match, sorted by path.
+    pub files: Vec<FileReplacement>,
+}
+
+impl ReplacePreview {
+    pub fn total_replacements(&self) -> usize {
+        self.files.iter().map(|f| f.replacements).sum()
+    }
+}
+
+/// What [`SearchService::apply_replace`] did.
+#[derive(Debug, Clone)]
+pub struct ReplaceOutcome {
+    pub files_changed: usize,
+    pub replacements: usize,
+    /// Snapshot taken before writing, on the [`REPLACE_TIMELINE`] timeline.
+    pub checkpoint: Option<Checkpoint>,
 }
 
 /// File content search service using regex and gitignore-aware traversal.
 pub struct SearchService;
 
 impl SearchService {
-    /// Search for a regex pattern across files under `root`.
+    /// Search for `pattern` across files under `root`.
     ///
     /// Respects `.gitignore` rules and supports glob-based file filtering.
+    /// Files are searched in parallel; results are sorted by path and line.
     pub fn search(root: &Path, pattern: &str, options: SearchOptions) -> Result<Vec<SearchResult>> {
-        let regex = RegexBuilder::new(pattern)
-            .case_insensitive(!options.case_sensitive)
-            .build()
-            .with_context(|| format!("Invalid search pattern: {pattern}"))?;
-
-        let glob_matcher = match &options.file_pattern {
-            Some(glob) => Some(
-                glob::Pattern::new(glob)
-                    .with_context(|| format!("Invalid file glob pattern: {glob}"))?,
-            ),
-            None => None,
-        };
-
-        let walker = WalkBuilder::new(root)
-            .hidden(!options.include_hidden)
-            .git_ignore(true)
-            .git_global(true)
-            .git_exclude(true)
-            .build();
+        let fuzzy = options.mode == SearchMode::FuzzyFilename;
+        let results = Mutex::new(Vec::new());
+        Self::search_streaming(root, pattern, options, |result| {
+            results
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(result);
+            true
+        })?;
 
-        let mut results = Vec::new();
+        let mut results = results.into_inner().unwrap_or_else(|e| e.into_inner());
+        // Fuzzy results arrive best first; keep that order.
+        if !fuzzy {
+            results.sort_by(|a, b| {
+                (&a.path, a.line_number, a.match_start).cmp(&(
+                    &b.path,
+                    b.line_number,
+                    b.match_start,
+                ))
+            });
+        }
+        debug!("Found {} results", results.len());
+        Ok(results)
+    }
 
+    /// Search like [`search`](Self::search), handing each result to
+    /// `on_result` as soon as it is found. Results arrive in no particular
+    /// order, except in fuzzy filename mode where they are ranked first.
+    /// Return `false` from `on_result` to stop the search.
+    ///
+    /// Returns the number of results delivered.
+    pub fn search_streaming(
+        root: &Path,
+        pattern: &str,
+        options: SearchOptions,
+        on_result: impl Fn(SearchResult) -> bool + Sync,
+    ) -> Result<usize> {
         debug!("Searching for '{}' in {}", pattern, root.display());
 
-        for entry in walker {
-            if results.len() >= options.max_results {
-                break;
+        if options.mode == SearchMode::FuzzyFilename {
+            let mut delivered = 0;
+            for result in fuzzy_search(root, pattern, &options)? {
+                delivered += 1;
+                if !on_result(result) {
+                    break;
+                }
             }
+            return Ok(delivered);
+        }
 
-            let entry = match entry {
-                Ok(e) => e,
-                Err(_) => continue,
-            };
-
-            // Skip directories
-            let entry_path = entry.path();
-            if entry_path.is_dir() {
-                continue;
+        let regex = build_regex(pattern, &options)?;
+        let reserved = AtomicUsize::new(0);
+        let stopped = AtomicBool::new(false);
+        walk_files(root, &options, |path| {
+            for result in search_file(path, &regex, &options) {
+                if stopped.load(Ordering::Relaxed)
+                    || reserved.fetch_add(1, Ordering::Relaxed) >= options.max_results
+                {
+                    stopped.store(true, Ordering::Relaxed);
+                    return false;
+                }
+                if !on_result(result) {
+                    stopped.store(true, Ordering::Relaxed);
+                    return false;
+                }
             }
+            !stopped.load(Ordering::Relaxed)
+        })?;
 
-            // Apply file glob filter
-            if let Some(ref glob) = glob_matcher {
-                let file_name = match entry_path.file_name() {
-                    Some(name) => name.to_string_lossy(),
-                    None => continue,
-                };
-                if !glob.matches(&file_name) {
-                    continue;
-                }
+        Ok(reserved.into_inner().min(options.max_results))
+    }
+
+    /// Compute every replacement of `pattern` with `replacement` under
+    /// `root` without writing anything. `max_results` does not apply.
+    ///
+    /// In [`SearchMode::Regex`] the replacement may refer to capture groups
+    /// as `$1` or `${name}`; in the other text modes it is used verbatim.
+    pub fn preview_replace(
+        root: &Path,
+        pattern: &str,
+        replacement: &str,
+        options: SearchOptions,
+    ) -> Result<ReplacePreview> {
+        if options.mode == SearchMode::FuzzyFilename {
+            bail!("Fuzzy filename search cannot be used to replace");
+        }
+        let regex = build_regex(pattern, &options)?;
+        let expand = options.mode == SearchMode::Regex;
+
+        let files = Mutex::new(Vec::new());
+        walk_files(root, &options, |path| {
+            if let Some(file) = replace_in_file(path, &regex, replacement, expand, &options) {
+                files.lock().unwrap_or_else(|e| e.into_inner()).push(file);
             }
+            true
+        })?;
 
-            // Skip binary files by checking the first 512 bytes
-            if is_likely_binary(entry_path) {
-                continue;
+        let mut files = files.into_inner().unwrap_or_else(|e| e.into_inner());
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(ReplacePreview {
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            files,
+        })
+    }
+
+    /// Write a previewed replacement.
+    ///
+    /// All files are checked against the preview first and nothing is
+    /// written if any changed since. New contents go to temporary files that
+    /// are then renamed into place; if a rename fails, files already replaced
+    /// are put back. When `checkpoints` is given the workspace is
+    /// checkpointed first so the whole replacement can be undone.
+    pub fn apply_replace(
+        preview: &ReplacePreview,
+        checkpoints: Option<&CheckpointStore>,
+    ) -> Result<ReplaceOutcome> {
+        for file in &preview.files {
+            let current = fs::read_to_string(&file.path)
+                .with_context(|| format!("Failed to read file: {}", file.path.display()))?;
+            if current != file.original {
+                bail!(
+                    "{} changed since the preview; search again before replacing",
+                    file.path.display()
+                );
             }
+        }
 
-            let content = match fs::read_to_string(entry_path) {
-                Ok(c) => c,
-                Err(_) => continue, // Skip files that can't be read as UTF-8
-            };
+        let checkpoint = match checkpoints {
+            Some(store) => Some(store.create(
+                REPLACE_TIMELINE,
+                &format!(
+                    "Before replacing '{}' with '{}'",
+                    preview.pattern, preview.replacement
+                ),
+            )?),
+            None => None,
+        };
+        if let Some(store) = checkpoints {
+            for file in &preview.files {
+                store.before_write(&file.path)?;
+            }
+        }
 
-            for (line_idx, line) in content.lines().enumerate() {
-                if results.len() >= options.max_results {
-                    break;
+        let mut staged = Vec::with_capacity(preview.files.len());
+        for file in &preview.files {
+            match stage(file) {
+                Ok(temp) => staged.push(temp),
+                Err(e) => {
+                    for temp in &staged {
+                        let _ = fs::remove_file(temp);
+                    }
+                    return Err(e);
                 }
+            }
+        }
 
-                if let Some(m) = regex.find(line) {
-                    results.push(SearchResult {
-                        path: entry_path.to_path_buf(),
-                        line_number: line_idx + 1,
-                        line_content: line.to_string(),
-                        match_start: m.start(),
-                        match_end: m.end(),
-                    });
+        for (done, (file, temp)) in preview.files.iter().zip(&staged).enumerate() {
+            if let Err(e) = fs::rename(temp, &file.path) {
+                for file in &preview.files[..done] {
+                    let _ = fs::write(&file.path, &file.original);
                 }
+                for temp in &staged[done..] {
+                    let _ = fs::remove_file(temp);
+                }
+                return Err(e)
+                    .with_context(|| format!("Failed to write file: {}", file.path.display()));
             }
         }
 
-        debug!("Found {} results", results.len());
-        Ok(results)
+        debug!(
+            "Replaced '{}' in {} files",
+            preview.pattern,
+            preview.files.len()
+        );
+        Ok(ReplaceOutcome {
+            files_changed: preview.files.len(),
+            replacements: preview.total_replacements(),
+            checkpoint,
+        })
     }
 }
 
@@ -140,6 +329,399 @@ pub fn is_likely_binary(path: &Path) -> bool {
     buf[..n].contains(&0)
 }
 
+// ---------------------------------------------------------------------------
+// Traversal
+// ---------------------------------------------------------------------------
+
+/// Visit every non-ignored file under `root` matching the file glob, on
+/// several threads. Stops once `visit` returns `false`.
+fn walk_files(
+    root: &Path,
+    options: &SearchOptions,
+    visit: impl Fn(&Path) -> bool + Sync,
+) -> Result<()> {
+    let glob_matcher = match &options.file_pattern {
+        Some(glob) => Some(
+            glob::Pattern::new(glob)
+                .with_context(|| format!("Invalid file glob pattern: {glob}"))?,
+        ),
+        None => None,
+    };
+
+    let walker = WalkBuilder::new(root)
+        .hidden(!options.include_hidden)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .build_parallel();
+
+    walker.run(|| {
+        let visit = &visit;
+        let glob_matcher = &glob_matcher;
+        Box::new(move |entry| {
+            let Ok(entry) = entry else {
+                return WalkState::Continue;
+            };
+
+            // Skip directories
+            let entry_path = entry.path();
+            if entry_path.is_dir() {
+                return WalkState::Continue;
+            }
+
+            // Apply file glob filter
+            if let Some(glob) = glob_matcher {
+                let Some(file_name) = entry_path.file_name() else {
+                    return WalkState::Continue;
+                };
+                if !glob.matches(&file_name.to_string_lossy()) {
+                    return WalkState::Continue;
+                }
+            }
+
+            if visit(entry_path) {
+                WalkState::Continue
+            } else {
+                WalkState::Quit
+            }
+        })
+    });
+    Ok(())
+}
+
+/// Text of a readable, non-binary file.
+fn read_text(path: &Path) -> Option<String> {
+    // Skip binary files by checking the first 512 bytes
+    if is_likely_binary(path) {
+        return None;
+    }
+    // Skip files that can't be read as UTF-8
+    fs::read_to_string(path).ok()
+}
+
+// ---------------------------------------------------------------------------
+// Matching
+// ---------------------------------------------------------------------------
+
+fn build_regex(pattern: &str, options: &SearchOptions) -> Result<Regex> {
+    let source = match options.mode {
+        SearchMode::Regex => pattern.to_string(),
+        SearchMode::Literal => regex::escape(pattern),
+        SearchMode::WholeWord => format!(r"\b{}\b", regex::escape(pattern)),
+        SearchMode::FuzzyFilename => bail!("Fuzzy filename search has no content pattern"),
+    };
+    RegexBuilder::new(&source)
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(options.multiline)
+        .dot_matches_new_line(options.multiline)
+        .build()
+        .with_context(|| format!("Invalid search pattern: {pattern}"))
+}
+
+fn search_file(path: &Path, regex: &Regex, options: &SearchOptions) -> Vec<SearchResult> {
+    let Some(content) = read_text(path) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let context = |first: usize, last: usize| {
+        let before = lines[first.saturating_sub(options.context_lines)..first].to_vec();
+        let after_end = (last + 1 + options.context_lines).min(lines.len());
+        let after = lines[(last + 1).min(after_end)..after_end].to_vec();
+        (to_strings(&before), to_strings(&after))
+    };
+
+    let mut results = Vec::new();
+    if options.multiline {
+        let starts = line_starts(&content);
+        for m in regex.find_iter(&content) {
+            if m.is_empty() {
+                continue;
+            }
+            let first = line_of(&starts, m.start());
+            let last = line_of(&starts, m.end() - 1);
+            let start = starts[first];
+            let end = starts.get(last + 1).map_or(content.len(), |&next| next);
+            let line_content = content[start..end].trim_end_matches(['\n', '\r']);
+            let (context_before, context_after) = context(first, last);
+            results.push(SearchResult {
+                path: path.to_path_buf(),
+                line_number: first + 1,
+                line_content: line_content.to_string(),
+                match_start: m.start() - start,
+                match_end: (m.end() - start).min(line_content.len()),
+                context_before,
+                context_after,
+            });
+        }
+    } else {
+        for (line_idx, line) in lines.iter().enumerate() {
+            if let Some(m) = regex.find(line) {
+                let (context_before, context_after) = context(line_idx, line_idx);
+                results.push(SearchResult {
+                    path: path.to_path_buf(),
+                    line_number: line_idx + 1,
+                    line_content: line.to_string(),
+                    match_start: m.start(),
+                    match_end: m.end(),
+                    context_before,
+                    context_after,
+                });
+            }
+        }
+    }
+    results
+}
+
+fn to_strings(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|line| line.to_string()).collect()
+}
+
+/// Byte offset at which each line starts.
+fn line_starts(content: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .filter(|&start| start < content.len() || start == 0)
+        .collect()
+}
+
+/// Zero-based line containing byte `offset`.
+fn line_of(starts: &[usize], offset: usize) -> usize {
+    starts.partition_point(|&start| start <= offset) - 1
+}
+
+// ---------------------------------------------------------------------------
+// Fuzzy filename matching
+// ---------------------------------------------------------------------------
+
+fn fuzzy_search(root: &Path, query: &str, options: &SearchOptions) -> Result<Vec<SearchResult>> {
+    let query: Vec<char> = if options.case_sensitive {
+        query.chars().filter(|c| !c.is_whitespace()).collect()
+    } else {
+        query
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    if query.is_empty() {
+        bail!("Fuzzy filename search needs a query");
+    }
+
+    let scored = Mutex::new(Vec::new());
+    walk_files(root, options, |path| {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let display = relative.to_string_lossy().replace('\\', "/");
+        if let Some((score, start, end)) = fuzzy_score(&query, &display, options.case_sensitive) {
+            scored.lock().unwrap_or_else(|e| e.into_inner()).push((
+                score,
+                SearchResult {
+                    path: path.to_path_buf(),
+                    line_number: 0,
+                    line_content: display,
+                    match_start: start,
+                    match_end: end,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                },
+            ));
+        }
+        true
+    })?;
+
+    let mut scored = scored.into_inner().unwrap_or_else(|e| e.into_inner());
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then(a.line_content.len().cmp(&b.line_content.len()))
+            .then(a.line_content.cmp(&b.line_content))
+    });
+    scored.truncate(options.max_results);
+    Ok(scored.into_iter().map(|(_, result)| result).collect())
+}
+
+/// Score `candidate` against `query` (already lowercased when matching is
+/// case-insensitive). Every query character must appear in order; runs of
+/// consecutive characters, characters starting a path segment or word, and
+/// matches within the file name score higher.
+///
+/// Returns the score and the byte span from the first to the last matched
+/// character.
+fn fuzzy_score(
+    query: &[char],
+    candidate: &str,
+    case_sensitive: bool,
+) -> Option<(i64, usize, usize)> {
+    let file_name_start = candidate.rfind('/').map_or(0, |i| i + 1);
+    let chars: Vec<(usize, char)> = candidate.char_indices().collect();
+
+    // Match from the end so the file name is preferred over directories.
+    let mut positions = Vec::with_capacity(query.len());
+    let mut next = chars.len();
+    for &wanted in query.iter().rev() {
+        let found = chars[..next].iter().rposition(|&(_, c)| {
+            if case_sensitive {
+                c == wanted
+            } else {
+                c.to_lowercase().eq(std::iter::once(wanted))
+            }
+        })?;
+        positions.push(found);
+        next = found;
+    }
+    positions.reverse();
+
+    let mut score = 0i64;
+    for (i, &pos) in positions.iter().enumerate() {
+        let (offset, c) = chars[pos];
+        score += 1;
+        if i > 0 && positions[i - 1] + 1 == pos {
+            score += 5;
+        }
+        let prev = pos.checked_sub(1).map(|p| chars[p].1);
+        let word_start = match prev {
+            None => true,
+            Some(p) => {
+                matches!(p, '/' | '_' | '-' | '.' | ' ') || (p.is_lowercase() && c.is_uppercase())
+            }
+        };
+        if word_start {
+            score += 8;
+        }
+        if offset >= file_name_start {
+            score += 3;
+        }
+    }
+    // Prefer tighter matches.
+    let first = positions[0];
+    let last = positions[positions.len() - 1];
+    score -= (last - first + 1 - positions.len()) as i64;
+
+    let (end_offset, end_char) = chars[last];
+    Some((score, chars[first].0, end_offset + end_char.len_utf8()))
+}
+
+// ---------------------------------------------------------------------------
+// Replacement
+// ---------------------------------------------------------------------------
+
+fn replace_in_file(
+    path: &Path,
+    regex: &Regex,
+    replacement: &str,
+    expand: bool,
+    options: &SearchOptions,
+) -> Option<FileReplacement> {
+    let original = read_text(path)?;
+    let mut new_content = String::with_capacity(original.len());
+    let mut edits = Vec::new();
+    let mut replacements = 0;
+
+    if options.multiline {
+        let starts = line_starts(&original);
+        let mut last = 0;
+        for caps in regex.captures_iter(&original) {
+            let m = caps.get(0).expect("group 0 is the whole match");
+            let mut replaced = String::new();
+            expand_into(&caps, replacement, expand, &mut replaced);
+
+            let first_line = line_of(&starts, m.start());
+            let start = starts[first_line];
+            let end = original[m.end()..]
+                .find('\n')
+                .map_or(original.len(), |i| m.end() + i);
+            edits.push(ReplacementEdit {
+                line_number: first_line + 1,
+                before: original[start..end].to_string(),
+                after: format!(
+                    "{}{replaced}{}",
+                    &original[start..m.start()],
+                    &original[m.end()..end]
+                ),
+            });
+
+            new_content.push_str(&original[last..m.start()]);
+            new_content.push_str(&replaced);
+            last = m.end();
+            replacements += 1;
+        }
+        new_content.push_str(&original[last..]);
+    } else {
+        for (line_idx, line) in original.split_inclusive('\n').enumerate() {
+            let body = line.trim_end_matches(['\n', '\r']);
+            let (after, count) = replace_all(regex, body, replacement, expand);
+            if count > 0 {
+                edits.push(ReplacementEdit {
+                    line_number: line_idx + 1,
+                    before: body.to_string(),
+                    after: after.clone(),
+                });
+                replacements += count;
+            }
+            new_content.push_str(&after);
+            new_content.push_str(&line[body.len()..]);
+        }
+    }
+
+    (replacements > 0 && new_content != original).then(|| FileReplacement {
+        path: path.to_path_buf(),
+        replacements,
+        edits,
+        new_content,
+        original,
+    })
+}
+
+fn replace_all(regex: &Regex, text: &str, replacement: &str, expand: bool) -> (String, usize) {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut count = 0;
+    for caps in regex.captures_iter(text) {
+        let m = caps.get(0).expect("group 0 is the whole match");
+        out.push_str(&text[last..m.start()]);
+        expand_into(&caps, replacement, expand, &mut out);
+        last = m.end();
+        count += 1;
+    }
+    out.push_str(&text[last..]);
+    (out, count)
+}
+
+fn expand_into(caps: &regex::Captures<'_>, replacement: &str, expand: bool, out: &mut String) {
+    if expand {
+        caps.expand(replacement, out);
+    } else {
+        out.push_str(replacement);
+    }
+}
+
+/// Write a file's new content next to it, keeping its permissions, and
+/// return the temporary path. Fails rather than reuse a temporary path that
+/// already exists, which may be a link planted to redirect the write.
+fn stage(file: &FileReplacement) -> Result<PathBuf> {
+    let name = file
+        .path
+        .file_name()
+        .with_context(|| format!("Not a file: {}", file.path.display()))?;
+    let temp = file
+        .path
+        .with_file_name(format!(".{}.hive-replace", name.to_string_lossy()));
+    let mut out = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temp)
+        .with_context(|| format!("Failed to create file: {}", temp.display()))?;
+    if let Err(e) = out.write_all(file.new_content.as_bytes()) {
+        drop(out);
+        let _ = fs::remove_file(&temp);
+        return Err(e).with_context(|| format!("Failed to write file: {}", temp.display()));
+    }
+    drop(out);
+    if let Ok(metadata) = fs::metadata(&file.path) {
+        let _ = fs::set_permissions(&temp, metadata.permissions());
+    }
+    Ok(temp)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,4 +823,227 @@ mod tests {
                 .any(|r| r.path.to_string_lossy().contains("deep.rs"))
         );
     }
+
+    #[test]
+    fn test_literal_and_whole_word_modes() {
+        let dir = setup_search_dir();
+        fs::write(dir.path().join("words.txt"), "a.b\naxb\ncat\ncatalog\n").unwrap();
+
+        let literal = SearchOptions {
+            mode: SearchMode::Literal,
+            ..Default::default()
+        };
+        let results = SearchService::search(dir.path(), "a.b", literal).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].line_content, "a.b");
+
+        let whole_word = SearchOptions {
+            mode: SearchMode::WholeWord,
+            ..Default::default()
+        };
+        let results = SearchService::search(dir.path(), "cat", whole_word).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].line_number, 3);
+    }
+
+    #[test]
+    fn test_multiline_search_with_context() {
+        let dir = setup_search_dir();
+        let opts = SearchOptions {
+            multiline: true,
+            context_lines: 1,
+            file_pattern: Some("hello.rs".into()),
+            ..Default::default()
+        };
+        let results = SearchService::search(dir.path(), r"main\(\) \{\n\s+println", opts).unwrap();
+        assert_eq!(results.len(), 1);
+        let result = &results[0];
+        assert_eq!(result.line_number, 1);
+        assert_eq!(result.line_content, "fn main() {\n    println!(\"Hello\");");
+        assert_eq!(
+            &result.line_content[result.match_start..result.match_end],
+            "main() {\n    println"
+        );
+        assert!(result.context_before.is_empty());
+        assert_eq!(result.context_after, ["}"]);
+
+        let opts = SearchOptions {
+            context_lines: 1,
+            ..Default::default()
+        };
+        let results = SearchService::search(dir.path(), "println", opts).unwrap();
+        assert_eq!(results[0].context_before, ["fn main() {"]);
+        assert_eq!(results[0].context_after, ["}"]);
+    }
+
+    #[test]
+    fn test_fuzzy_filename_ranking() {
+        let dir = setup_search_dir();
+        fs::create_dir_all(dir.path().join("src/search")).unwrap();
+        fs::write(dir.path().join("src/search/mod.rs"), "").unwrap();
+        fs::write(dir.path().join("src/search_service.rs"), "").unwrap();
+        let opts = SearchOptions {
+            mode: SearchMode::FuzzyFilename,
+            case_sensitive: false,
+            ..Default::default()
+        };
+        let results = SearchService::search(dir.path(), "srchsvc", opts.clone()).unwrap();
+        assert_eq!(results[0].line_content, "src/search_service.rs");
+        assert_eq!(results[0].line_number, 0);
+
+        let results = SearchService::search(dir.path(), "deep", opts.clone()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            &results[0].line_content[results[0].match_start..results[0].match_end],
+            "deep"
+        );
+        assert!(
+            SearchService::search(dir.path(), "zzz", opts)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_streaming_can_stop_early() {
+        let dir = setup_search_dir();
+        let seen = AtomicUsize::new(0);
+        let delivered =
+            SearchService::search_streaming(dir.path(), "fn", SearchOptions::default(), |_| {
+                seen.fetch_add(1, Ordering::SeqCst);
+                false
+            })
+            .unwrap();
+        assert_eq!(delivered, 1);
+        assert_eq!(seen.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_preview_and_apply_replace_with_captures() {
+        let dir = setup_search_dir();
+        let preview = SearchService::preview_replace(
+            dir.path(),
+            r#"println!\("(\w+)"\)"#,
+            r#"tracing::info!("${1}!")"#,
+            SearchOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(preview.files.len(), 2);
+        assert_eq!(preview.total_replacements(), 2);
+        assert_eq!(
+            preview.files[0].edits,
+            [ReplacementEdit {
+                line_number: 2,
+                before: "    println!(\"Hello\");".into(),
+                after: "    tracing::info!(\"Hello!\");".into(),
+            }]
+        );
+        // Nothing is written by the preview.
+        let hello = dir.path().join("hello.rs");
+        assert!(fs::read_to_string(&hello).unwrap().contains("println"));
+
+        let outcome = SearchService::apply_replace(&preview, None).unwrap();
+        assert_eq!(outcome.files_changed, 2);
+        assert_eq!(
+            fs::read_to_string(&hello).unwrap(),
+            "fn main() {\n    tracing::info!(\"Hello!\");\n}\n"
+        );
+        assert!(fs::read_dir(dir.path()).unwrap().all(|e| {
+            !e.unwrap()
+                .file_name()
+                .to_string_lossy()
+                .ends_with(".hive-replace")
+        }));
+    }
+
+    #[test]
+    fn test_literal_replace_does_not_expand_and_multiline_replace() {
+        let dir = setup_search_dir();
+        let literal = SearchOptions {
+            mode: SearchMode::Literal,
+            ..Default::default()
+        };
+        let preview = SearchService::preview_replace(dir.path(), "note", "$1", literal).unwrap();
+        SearchService::apply_replace(&preview, None).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("notes.txt")).unwrap(),
+            "This is a $1\nWith multiple lines\n"
+        );
+
+        let multiline = SearchOptions {
+            multiline: true,
+            ..Default::default()
+        };
+        let preview =
+            SearchService::preview_replace(dir.path(), r"\$1\nWith", "note; with", multiline)
+                .unwrap();
+        assert_eq!(preview.files[0].edits[0].line_number, 1);
+        SearchService::apply_replace(&preview, None).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("notes.txt")).unwrap(),
+            "This is a note; with multiple lines\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_rejects_stale_preview() {
+        let dir = setup_search_dir();
+        let preview =
+            SearchService::preview_replace(dir.path(), "fn", "pub fn", SearchOptions::default())
+                .unwrap();
+        fs::write(dir.path().join("world.rs"), "fn changed() {}\n").unwrap();
+
+        let err = SearchService::apply_replace(&preview, None).unwrap_err();
+        assert!(err.to_string().contains("changed since the preview"));
+        // No file was touched.
+        assert!(
+            fs::read_to_string(dir.path().join("hello.rs"))
+                .unwrap()
+                .starts_with("fn main")
+        );
+    }
+
+    #[test]
+    fn test_replace_checkpoint_undoes_replacement() {
+        let dir = setup_search_dir();
+        let storage = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::open_at(dir.path(), storage.path()).unwrap();
+
+        let preview =
+            SearchService::preview_replace(dir.path(), "deep", "shallow", SearchOptions::default())
+                .unwrap();
+        let outcome = SearchService::apply_replace(&preview, Some(&store)).unwrap();
+        assert_eq!(outcome.replacements, 2);
+        let deep = dir.path().join("sub/deep.rs");
+        assert_eq!(
+            fs::read_to_string(&deep).unwrap(),
+            "fn shallow() { /* shallow */ }\n"
+        );
+
+        let checkpoint = outcome.checkpoint.unwrap();
+        assert_eq!(checkpoint.conversation_id, REPLACE_TIMELINE);
+        store.restore(&checkpoint.id).unwrap();
+        assert_eq!(
+            fs::read_to_string(&deep).unwrap(),
+            "fn deep() { /* deep */ }\n"
+        );
+    }
+
+    #[test]
+    fn test_replace_refuses_existing_temp_file() {
+        let dir = setup_search_dir();
+        let planted = dir.path().join(".hello.rs.hive-replace");
+        fs::write(&planted, "planted").unwrap();
+
+        let preview =
+            SearchService::preview_replace(dir.path(), "Hello", "Hi", SearchOptions::default())
+                .unwrap();
+        assert!(SearchService::apply_replace(&preview, None).is_err());
+        assert_eq!(fs::read_to_string(&planted).unwrap(), "planted");
+        assert!(
+            fs::read_to_string(dir.path().join("hello.rs"))
+                .unwrap()
+                .contains("Hello")
+        );
+    }
 }
@@ -1,10 +1,16 @@
 use anyhow::{Context, Result};
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder, gitconfig_excludes_path};
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 use tracing::{debug, warn};
 
 /// Simplified file system event.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum WatchEvent {
     Created(PathBuf),
     Modified(PathBuf),
@@ -12,6 +18,17 @@ pub enum WatchEvent {
     Renamed { from: PathBuf, to: PathBuf },
 }
 
+impl WatchEvent {
+    /// Every path the event refers to.
+    pub fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        let (first, second) = match self {
+            Self::Created(path) | Self::Modified(path) | Self::Deleted(path) => (path, None),
+            Self::Renamed { from, to } => (from, Some(to)),
+        };
+        std::iter::once(first).chain(second)
+    }
+}
+
 /// Watches a directory for file system changes and invokes a callback.
 pub struct FileWatcher {
     _watcher: RecommendedWatcher,
@@ -46,6 +63,486 @@ impl FileWatcher {
     }
 }
 
+/// Directories to always skip during file-tree walks.
+/// Shared between QuickIndex, BackgroundIndexer and [`DebouncedWatcher`].
+pub const SKIP_DIRS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "target",
+    ".hive-worktrees",
+    "__pycache__",
+    ".venv",
+    "venv",
+    ".tox",
+    "dist",
+    "build",
+    ".next",
+    ".nuxt",
+    ".cache",
+    ".gradle",
+    ".idea",
+    ".vscode",
+];
+
+/// Returns `true` if a directory entry should be skipped during file-tree walks.
+/// Shared between QuickIndex, BackgroundIndexer and [`DebouncedWatcher`].
+pub fn should_skip_dir(name: &str) -> bool {
+    name.starts_with('.')
+        || SKIP_DIRS.contains(&name)
+        || name.starts_with("target-")
+        || name.starts_with("dist-")
+        || name.starts_with("build-")
+}
+
+// ---------------------------------------------------------------------------
+// Debounced watcher
+// ---------------------------------------------------------------------------
+
+/// Tuning for [`DebouncedWatcher`].
+#[derive(Debug, Clone)]
+pub struct WatcherConfig {
+    /// Quiet period after the last event before a batch is sent.
+    pub debounce: Duration,
+    /// Longest a batch is held back while events keep arriving.
+    pub max_delay: Duration,
+    /// Batches with more changes than this are sent as a
+    /// [`ChangeSet::Bulk`] so subscribers rescan instead.
+    pub bulk_threshold: usize,
+    /// Drop changes matched by the project's `.gitignore` files (at any
+    /// depth), `.git/info/exclude` and the user's global excludes file.
+    /// [`should_skip_dir`] always applies.
+    pub respect_gitignore: bool,
+    /// Capacity of the broadcast channel. Subscribers that fall further
+    /// behind miss batches and see `RecvError::Lagged`.
+    pub channel_capacity: usize,
+}
+
+impl Default for WatcherConfig {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(150),
+            max_delay: Duration::from_secs(1),
+            bulk_threshold: 500,
+            respect_gitignore: true,
+            channel_capacity: 64,
+        }
+    }
+}
+
+/// Why a batch was reported as a bulk change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkReason {
+    /// `HEAD` moved, e.g. `git checkout` or `git switch`. Branch names, or
+    /// the commit id when detached.
+    BranchSwitch {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// More files changed at once than [`WatcherConfig::bulk_threshold`].
+    TooManyChanges,
+}
+
+/// A debounced batch of changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeSet {
+    /// Coalesced per-file changes, sorted by path. A file created and then
+    /// modified is reported once as created; a file created and deleted
+    /// again is not reported at all.
+    Files(Vec<WatchEvent>),
+    /// Too much changed to list usefully; subscribers should rescan.
+    Bulk { reason: BulkReason, changed: usize },
+}
+
+/// Watches a project directory and broadcasts debounced, filtered batches
+/// of changes to any number of subscribers.
+///
+/// Changes under [`should_skip_dir`] directories and, by default, ignored
+/// files are dropped. The watcher stays active as long as this struct is
+/// alive.
+pub struct DebouncedWatcher {
+    _watcher: RecommendedWatcher,
+    sender: broadcast::Sender<ChangeSet>,
+    root: PathBuf,
+}
+
+impl DebouncedWatcher {
+    pub fn new(path: &Path, config: WatcherConfig) -> Result<Self> {
+        let root = path
+            .canonicalize()
+            .with_context(|| format!("Cannot resolve path: {}", path.display()))?;
+        let (sender, _) = broadcast::channel(config.channel_capacity.max(1));
+        let (raw_tx, raw_rx) = mpsc::channel();
+
+        let mut watcher = notify::recommended_watcher(move |res| {
+            let _ = raw_tx.send(res);
+        })
+        .context("Failed to create file watcher")?;
+        watcher
+            .watch(&root, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch path: {}", root.display()))?;
+
+        let batcher = Batcher::new(&root, config);
+        let batch_sender = sender.clone();
+        std::thread::Builder::new()
+            .name("hive-fs-watcher".into())
+            .spawn(move || batcher.run(raw_rx, batch_sender))
+            .context("Failed to start file watcher thread")?;
+
+        debug!("Watching for changes (debounced): {}", root.display());
+        Ok(Self {
+            _watcher: watcher,
+            sender,
+            root,
+        })
+    }
+
+    /// Receive every batch sent from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<ChangeSet> {
+        self.sender.subscribe()
+    }
+
+    /// The canonical directory being watched.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+/// Collects raw events on the watcher thread and turns them into batches.
+struct Batcher {
+    config: WatcherConfig,
+    rules: IgnoreRules,
+    head_path: PathBuf,
+    head: Option<String>,
+    pending: Coalescer,
+    branch_switch: Option<BulkReason>,
+    batch_started: Option<Instant>,
+}
+
+impl Batcher {
+    fn new(root: &Path, config: WatcherConfig) -> Self {
+        let head_path = root.join(".git").join("HEAD");
+        Self {
+            rules: IgnoreRules::load(root, config.respect_gitignore),
+            head: read_head(&head_path),
+            head_path,
+            config,
+            pending: Coalescer::default(),
+            branch_switch: None,
+            batch_started: None,
+        }
+    }
+
+    fn run(
+        mut self,
+        raw: mpsc::Receiver<notify::Result<Event>>,
+        sender: broadcast::Sender<ChangeSet>,
+    ) {
+        loop {
+            let received = match self.batch_started {
+                None => raw.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                Some(started) => {
+                    let remaining = self.config.max_delay.saturating_sub(started.elapsed());
+                    raw.recv_timeout(self.config.debounce.min(remaining))
+                }
+            };
+            match received {
+                Ok(Ok(event)) => {
+                    for change in translate_event(&event) {
+                        self.record(change);
+                    }
+                    if self.batch_started.is_none()
+                        && (!self.pending.is_empty() || self.branch_switch.is_some())
+                    {
+                        self.batch_started = Some(Instant::now());
+                    }
+                    if self
+                        .batch_started
+                        .is_some_and(|started| started.elapsed() >= self.config.max_delay)
+                    {
+                        self.flush(&sender);
+                    }
+                }
+                Ok(Err(e)) => warn!("File watcher error: {e}"),
+                Err(RecvTimeoutError::Timeout) => self.flush(&sender),
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.flush(&sender);
+                    break;
+                }
+            }
+        }
+        debug!("File watcher stopped: {}", self.rules.root.display());
+    }
+
+    fn record(&mut self, change: WatchEvent) {
+        if change.paths().any(|path| *path == self.head_path) {
+            let head = read_head(&self.head_path);
+            if head != self.head {
+                let from = std::mem::replace(&mut self.head, head.clone());
+                self.branch_switch = Some(BulkReason::BranchSwitch { from, to: head });
+            }
+            return;
+        }
+        for path in change.paths() {
+            self.rules.note_change(path);
+        }
+
+        let change = match change {
+            WatchEvent::Renamed { from, to } => {
+                match (self.rules.is_ignored(&from), self.rules.is_ignored(&to)) {
+                    (false, false) => WatchEvent::Renamed { from, to },
+                    (true, false) => WatchEvent::Created(to),
+                    (false, true) => WatchEvent::Deleted(from),
+                    (true, true) => return,
+                }
+            }
+            other if other.paths().any(|path| self.rules.is_ignored(path)) => return,
+            other => other,
+        };
+        self.pending.push(change);
+    }
+
+    fn flush(&mut self, sender: &broadcast::Sender<ChangeSet>) {
+        self.batch_started = None;
+        let changes = self.pending.drain();
+        let batch = if let Some(reason) = self.branch_switch.take() {
+            ChangeSet::Bulk {
+                reason,
+                changed: changes.len(),
+            }
+        } else if changes.len() > self.config.bulk_threshold {
+            ChangeSet::Bulk {
+                reason: BulkReason::TooManyChanges,
+                changed: changes.len(),
+            }
+        } else if changes.is_empty() {
+            return;
+        } else {
+            ChangeSet::Files(changes)
+        };
+        debug!("File watcher batch: {batch:?}");
+        // No subscribers is not an error.
+        let _ = sender.send(batch);
+    }
+}
+
+/// The branch `HEAD` points at, or the commit id when detached.
+fn read_head(head_path: &Path) -> Option<String> {
+    let head = std::fs::read_to_string(head_path).ok()?;
+    let head = head.trim();
+    Some(
+        head.strip_prefix("ref: refs/heads/")
+            .or_else(|| head.strip_prefix("ref: "))
+            .unwrap_or(head)
+            .to_string(),
+    )
+}
+
+/// Decides which paths under the root are worth reporting.
+struct IgnoreRules {
+    root: PathBuf,
+    respect_gitignore: bool,
+    global_excludes: Option<PathBuf>,
+    /// `.git/info/exclude` followed by the global excludes file, consulted
+    /// after every `.gitignore` has had its say.
+    repo_wide: Vec<Gitignore>,
+    /// Each directory's `.gitignore`, parsed on first use. `None` when the
+    /// directory has none.
+    per_dir: HashMap<PathBuf, Option<Gitignore>>,
+}
+
+impl IgnoreRules {
+    fn load(root: &Path, respect_gitignore: bool) -> Self {
+        Self::with_global_excludes(root, respect_gitignore, gitconfig_excludes_path())
+    }
+
+    fn with_global_excludes(
+        root: &Path,
+        respect_gitignore: bool,
+        global_excludes: Option<PathBuf>,
+    ) -> Self {
+        let mut rules = Self {
+            root: root.to_path_buf(),
+            respect_gitignore,
+            global_excludes,
+            repo_wide: Vec::new(),
+            per_dir: HashMap::new(),
+        };
+        rules.load_repo_wide();
+        rules
+    }
+
+    fn load_repo_wide(&mut self) {
+        self.repo_wide.clear();
+        if !self.respect_gitignore {
+            return;
+        }
+        let exclude = self.root.join(".git").join("info").join("exclude");
+        for file in std::iter::once(exclude).chain(self.global_excludes.clone()) {
+            if let Some(gitignore) = build_gitignore(&self.root, &file) {
+                self.repo_wide.push(gitignore);
+            }
+        }
+    }
+
+    /// Pick up edits to ignore files among the changed paths.
+    fn note_change(&mut self, path: &Path) {
+        if path.ends_with(".gitignore") {
+            if let Some(dir) = path.parent() {
+                self.per_dir.remove(dir);
+            }
+        } else if path == self.root.join(".git").join("info").join("exclude")
+            || self.global_excludes.as_deref() == Some(path)
+        {
+            self.load_repo_wide();
+        }
+    }
+
+    fn dir_rules(&mut self, dir: &Path) -> Option<&Gitignore> {
+        self.per_dir
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| build_gitignore(dir, &dir.join(".gitignore")))
+            .as_ref()
+    }
+
+    fn is_ignored(&mut self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return true;
+        };
+        let is_dir = path.is_dir();
+        let mut components: Vec<_> = relative.components().collect();
+        // The last component is only a directory name if it is a directory.
+        if !is_dir {
+            components.pop();
+        }
+        if components
+            .iter()
+            .any(|c| should_skip_dir(&c.as_os_str().to_string_lossy()))
+        {
+            return true;
+        }
+        if !self.respect_gitignore {
+            return false;
+        }
+
+        // As in git, the `.gitignore` nearest the path wins, then the
+        // repository's exclude file, then the global one.
+        let mut dir = path.parent();
+        while let Some(current) = dir.filter(|d| d.starts_with(&self.root)) {
+            if let Some(gitignore) = self.dir_rules(current) {
+                match gitignore.matched_path_or_any_parents(path, is_dir) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
+                }
+            }
+            dir = current.parent();
+        }
+        self.repo_wide
+            .iter()
+            .map(|gitignore| gitignore.matched_path_or_any_parents(path, is_dir))
+            .find(|m| !m.is_none())
+            .is_some_and(|m| m.is_ignore())
+    }
+}
+
+/// Parse one ignore file with patterns relative to `root`. `None` when the
+/// file does not exist or holds no patterns.
+fn build_gitignore(root: &Path, file: &Path) -> Option<Gitignore> {
+    if !file.is_file() {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(root);
+    if let Some(e) = builder.add(file) {
+        warn!("Ignoring bad pattern in {}: {e}", file.display());
+    }
+    match builder.build() {
+        Ok(gitignore) if !gitignore.is_empty() => Some(gitignore),
+        Ok(_) => None,
+        Err(e) => {
+            warn!("Failed to load ignore rules from {}: {e}", file.display());
+            None
+        }
+    }
+}
+
+/// What has happened to a path so far in the current batch. Keyed by the
+/// path's latest name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pending {
+    Created,
+    Modified,
+    Deleted,
+    RenamedFrom(PathBuf),
+}
+
+/// Folds a stream of events into the net change per path.
+#[derive(Debug, Default)]
+struct Coalescer {
+    pending: BTreeMap<PathBuf, Pending>,
+}
+
+impl Coalescer {
+    fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    fn push(&mut self, event: WatchEvent) {
+        match event {
+            WatchEvent::Created(path) => {
+                let next = match self.pending.get(&path) {
+                    // Deleted and created again: the content changed.
+                    Some(Pending::Deleted) => Pending::Modified,
+                    Some(other) => other.clone(),
+                    None => Pending::Created,
+                };
+                self.pending.insert(path, next);
+            }
+            WatchEvent::Modified(path) => {
+                let next = match self.pending.get(&path) {
+                    Some(Pending::Deleted) | None => Pending::Modified,
+                    Some(other) => other.clone(),
+                };
+                self.pending.insert(path, next);
+            }
+            WatchEvent::Deleted(path) => match self.pending.remove(&path) {
+                // Never existed as far as subscribers know.
+                Some(Pending::Created) => {}
+                Some(Pending::RenamedFrom(original)) => {
+                    self.pending.insert(original, Pending::Deleted);
+                }
+                Some(_) | None => {
+                    self.pending.insert(path, Pending::Deleted);
+                }
+            },
+            WatchEvent::Renamed { from, to } => {
+                let next = match self.pending.remove(&from) {
+                    Some(Pending::Created) => Pending::Created,
+                    Some(Pending::RenamedFrom(original)) if original == to => Pending::Modified,
+                    Some(Pending::RenamedFrom(original)) => Pending::RenamedFrom(original),
+                    Some(Pending::Deleted) => {
+                        self.pending.insert(from, Pending::Deleted);
+                        Pending::Created
+                    }
+                    Some(Pending::Modified) | None => Pending::RenamedFrom(from),
+                };
+                self.pending.insert(to, next);
+            }
+        }
+    }
+
+    fn drain(&mut self) -> Vec<WatchEvent> {
+        std::mem::take(&mut self.pending)
+            .into_iter()
+            .map(|(path, pending)| match pending {
+                Pending::Created => WatchEvent::Created(path),
+                Pending::Modified => WatchEvent::Modified(path),
+                Pending::Deleted => WatchEvent::Deleted(path),
+                Pending::RenamedFrom(from) => WatchEvent::Renamed { from, to: path },
+            })
+            .collect()
+    }
+}
+
 /// Translate a raw `notify::Event` into zero or more `WatchEvent`s.
 fn translate_event(event: &Event) -> Vec<WatchEvent> {
     let paths = &event.paths;
@@ -157,4 +654,242 @@ mod tests {
             if from == &old_path && to == &new_path
         ));
     }
+
+    fn coalesce(events: Vec<WatchEvent>) -> Vec<WatchEvent> {
+        let mut coalescer = Coalescer::default();
+        for event in events {
+            coalescer.push(event);
+        }
+        coalescer.drain()
+    }
+
+    #[test]
+    fn test_coalesce_create_then_modify_is_create() {
+        let p = PathBuf::from("/p/a.rs");
+        let events = vec![
+            WatchEvent::Created(p.clone()),
+            WatchEvent::Modified(p.clone()),
+            WatchEvent::Modified(p.clone()),
+        ];
+        assert_eq!(coalesce(events), [WatchEvent::Created(p)]);
+    }
+
+    #[test]
+    fn test_coalesce_create_then_delete_is_nothing() {
+        let p = PathBuf::from("/p/tmp.rs");
+        let events = vec![
+            WatchEvent::Created(p.clone()),
+            WatchEvent::Modified(p.clone()),
+            WatchEvent::Deleted(p),
+        ];
+        assert!(coalesce(events).is_empty());
+    }
+
+    #[test]
+    fn test_coalesce_delete_then_create_is_modify() {
+        let p = PathBuf::from("/p/a.rs");
+        let events = vec![
+            WatchEvent::Deleted(p.clone()),
+            WatchEvent::Created(p.clone()),
+        ];
+        assert_eq!(coalesce(events), [WatchEvent::Modified(p)]);
+    }
+
+    #[test]
+    fn test_coalesce_renames() {
+        let a = PathBuf::from("/p/a.rs");
+        let b = PathBuf::from("/p/b.rs");
+        let c = PathBuf::from("/p/c.rs");
+
+        // Rename chains collapse to one rename.
+        let events = vec![
+            WatchEvent::Renamed {
+                from: a.clone(),
+                to: b.clone(),
+            },
+            WatchEvent::Modified(b.clone()),
+            WatchEvent::Renamed {
+                from: b.clone(),
+                to: c.clone(),
+            },
+        ];
+        assert_eq!(
+            coalesce(events),
+            [WatchEvent::Renamed {
+                from: a.clone(),
+                to: c.clone()
+            }]
+        );
+
+        // A new file saved through a temporary name is just created.
+        let events = vec![
+            WatchEvent::Created(a.clone()),
+            WatchEvent::Renamed {
+                from: a.clone(),
+                to: b.clone(),
+            },
+        ];
+        assert_eq!(coalesce(events), [WatchEvent::Created(b.clone())]);
+
+        // Renamed and then deleted: the original is gone.
+        let events = vec![
+            WatchEvent::Renamed {
+                from: a.clone(),
+                to: b.clone(),
+            },
+            WatchEvent::Deleted(b),
+        ];
+        assert_eq!(coalesce(events), [WatchEvent::Deleted(a)]);
+    }
+
+    #[test]
+    fn test_ignore_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::write(root.join(".gitignore"), "*.log\ngenerated/\n").unwrap();
+        fs::create_dir_all(root.join("src")).unwrap();
+        let mut rules = IgnoreRules::with_global_excludes(&root, true, None);
+
+        assert!(!rules.is_ignored(&root.join("src/main.rs")));
+        assert!(!rules.is_ignored(&root.join(".gitignore")));
+        assert!(rules.is_ignored(&root.join("target/debug/build.rs")));
+        assert!(rules.is_ignored(&root.join("node_modules/x/index.js")));
+        assert!(rules.is_ignored(&root.join(".git/index")));
+        assert!(rules.is_ignored(&root.join("server.log")));
+        assert!(rules.is_ignored(&root.join("generated/api.rs")));
+        assert!(rules.is_ignored(Path::new("/elsewhere/file.rs")));
+
+        let mut rules = IgnoreRules::with_global_excludes(&root, false, None);
+        assert!(!rules.is_ignored(&root.join("server.log")));
+        assert!(rules.is_ignored(&root.join("target/debug/build.rs")));
+    }
+
+    #[test]
+    fn test_ignore_rules_honor_nested_and_global_excludes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("repo");
+        fs::create_dir_all(root.join("web/assets")).unwrap();
+        fs::create_dir_all(root.join(".git/info")).unwrap();
+        let root = root.canonicalize().unwrap();
+        fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        fs::write(root.join("web/.gitignore"), "*.map\n!keep.log\n").unwrap();
+        fs::write(root.join(".git/info/exclude"), "scratch/\n").unwrap();
+        let global = dir.path().join("global-excludes");
+        fs::write(&global, ".DS_Store\n").unwrap();
+        let mut rules = IgnoreRules::with_global_excludes(&root, true, Some(global));
+
+        assert!(rules.is_ignored(&root.join("web/assets/app.js.map")));
+        assert!(!rules.is_ignored(&root.join("app.js.map")));
+        assert!(rules.is_ignored(&root.join("web/debug.log")));
+        assert!(!rules.is_ignored(&root.join("web/keep.log")));
+        assert!(rules.is_ignored(&root.join("scratch/notes.md")));
+        assert!(rules.is_ignored(&root.join("web/.DS_Store")));
+
+        // Editing a nested .gitignore takes effect on the next change.
+        fs::write(root.join("web/.gitignore"), "").unwrap();
+        rules.note_change(&root.join("web/.gitignore"));
+        assert!(!rules.is_ignored(&root.join("web/assets/app.js.map")));
+    }
+
+    #[test]
+    fn test_batcher_reports_branch_switch_as_bulk() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir(root.join(".git")).unwrap();
+        let head = root.join(".git/HEAD");
+        fs::write(&head, "ref: refs/heads/main\n").unwrap();
+
+        let (sender, mut receiver) = broadcast::channel(8);
+        let mut batcher = Batcher::new(&root, WatcherConfig::default());
+        fs::write(&head, "ref: refs/heads/feature\n").unwrap();
+        batcher.record(WatchEvent::Modified(root.join("src/lib.rs")));
+        batcher.record(WatchEvent::Modified(root.join("src/main.rs")));
+        batcher.record(WatchEvent::Renamed {
+            from: root.join(".git/HEAD.lock"),
+            to: head.clone(),
+        });
+        batcher.flush(&sender);
+
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ChangeSet::Bulk {
+                reason: BulkReason::BranchSwitch {
+                    from: Some("main".into()),
+                    to: Some("feature".into()),
+                },
+                changed: 2,
+            }
+        );
+
+        // Rewriting HEAD with the same branch is not a switch.
+        batcher.record(WatchEvent::Modified(head));
+        batcher.flush(&sender);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_batcher_bulk_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let config = WatcherConfig {
+            bulk_threshold: 2,
+            ..Default::default()
+        };
+        let (sender, mut receiver) = broadcast::channel(8);
+        let mut batcher = Batcher::new(&root, config);
+
+        for name in ["a.rs", "b.rs", "c.rs"] {
+            batcher.record(WatchEvent::Created(root.join(name)));
+        }
+        batcher.record(WatchEvent::Created(root.join("target/out.o")));
+        batcher.flush(&sender);
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ChangeSet::Bulk {
+                reason: BulkReason::TooManyChanges,
+                changed: 3,
+            }
+        );
+
+        batcher.record(WatchEvent::Renamed {
+            from: root.join("target/tmp"),
+            to: root.join("a.rs"),
+        });
+        batcher.flush(&sender);
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ChangeSet::Files(vec![WatchEvent::Created(root.join("a.rs"))])
+        );
+    }
+
+    #[test]
+    fn test_debounced_watcher_batches_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let watcher = DebouncedWatcher::new(dir.path(), WatcherConfig::default()).unwrap();
+        let mut first = watcher.subscribe();
+        let mut second = watcher.subscribe();
+        std::thread::sleep(Duration::from_millis(100));
+
+        let root = watcher.root().to_path_buf();
+        fs::create_dir(root.join("target")).unwrap();
+        fs::write(root.join("target/junk.o"), "x").unwrap();
+        fs::write(root.join("new.rs"), "fn a() {}").unwrap();
+        fs::write(root.join("new.rs"), "fn b() {}").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let batch = loop {
+            match first.try_recv() {
+                Ok(batch) => break batch,
+                Err(_) if Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(20));
+                }
+                Err(e) => panic!("no batch received: {e}"),
+            }
+        };
+        assert_eq!(
+            batch,
+            ChangeSet::Files(vec![WatchEvent::Created(root.join("new.rs"))])
+        );
+        assert_eq!(second.try_recv().unwrap(), batch);
+    }
 }
@@ -0,0 +1,475 @@
+//! Capability documents and capacity-aware peer scheduling.
+//!
+//! Each node publishes a signed [`CapabilityDocument`] describing the
+//! providers and models it can run, its hardware, its current load and its
+//! cost rates. Documents ride along with heartbeats, are verified against the
+//! sender's pinned key, and feed [`rank_peers`], which picks the best peer for
+//! a task given its [`TaskRequirements`], measured latency and load.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::identity::{NodeKeypair, PeerId, verify_signature};
+use crate::peer::{PeerInfo, PeerState};
+
+/// Domain-separation label for capability document signatures.
+const CAPABILITY_SIGNING_LABEL: &str = "hive-capabilities-v1\n";
+
+/// Latency assumed for peers that have not answered a heartbeat yet.
+const UNKNOWN_LATENCY_MS: f64 = 250.0;
+
+/// Score penalty for a fully loaded peer, in milliseconds of latency.
+const LOAD_PENALTY_MS: f64 = 500.0;
+
+// ---------------------------------------------------------------------------
+// Document
+// ---------------------------------------------------------------------------
+
+/// A model a peer can run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelOffer {
+    /// Model ID as understood by the peer's provider.
+    pub id: String,
+    /// `hive_ai::types::ModelCapability` names in snake_case
+    /// (e.g. `"tool_use"`, `"vision"`).
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    /// Cost per million input tokens in USD. Zero for local models.
+    #[serde(default)]
+    pub input_cost_per_mtok: f64,
+    /// Cost per million output tokens in USD. Zero for local models.
+    #[serde(default)]
+    pub output_cost_per_mtok: f64,
+}
+
+impl ModelOffer {
+    /// A local model with no per-token cost.
+    pub fn local(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            capabilities: Vec::new(),
+            input_cost_per_mtok: 0.0,
+            output_cost_per_mtok: 0.0,
+        }
+    }
+
+    /// Whether the model has every capability in `required`.
+    pub fn supports(&self, required: &[String]) -> bool {
+        required.iter().all(|cap| self.capabilities.contains(cap))
+    }
+
+    /// Blended cost per million tokens, weighting input and output equally.
+    pub fn blended_cost_per_mtok(&self) -> f64 {
+        (self.input_cost_per_mtok + self.output_cost_per_mtok) / 2.0
+    }
+}
+
+/// A provider available on a peer, local or cloud.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderOffer {
+    /// Provider name (e.g. "Ollama", "Anthropic").
+    pub name: String,
+    /// Whether the provider runs on the peer's own hardware.
+    #[serde(default)]
+    pub local: bool,
+    /// Models the provider serves.
+    #[serde(default)]
+    pub models: Vec<ModelOffer>,
+}
+
+/// A GPU on the peer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpuProfile {
+    pub name: String,
+    #[serde(default)]
+    pub memory_bytes: Option<u64>,
+}
+
+/// Hardware available to a peer's local models.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HardwareProfile {
+    #[serde(default)]
+    pub cpu_cores: u32,
+    #[serde(default)]
+    pub memory_bytes: Option<u64>,
+    #[serde(default)]
+    pub gpus: Vec<GpuProfile>,
+}
+
+/// How busy a peer currently is.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoadReport {
+    /// Tasks currently running.
+    pub active_tasks: u32,
+    /// Tasks accepted but not yet started.
+    pub queue_depth: u32,
+    /// Tasks the peer runs at most concurrently.
+    pub max_concurrent: u32,
+}
+
+impl LoadReport {
+    /// Fraction of capacity in use, counting queued tasks. Can exceed 1.0.
+    pub fn utilization(&self) -> f64 {
+        (self.active_tasks + self.queue_depth) as f64 / self.max_concurrent.max(1) as f64
+    }
+
+    /// Whether the peer can start another task without queueing it.
+    pub fn has_spare_capacity(&self) -> bool {
+        self.queue_depth == 0 && self.active_tasks < self.max_concurrent
+    }
+}
+
+/// A node that never reported its load takes no tasks.
+impl Default for LoadReport {
+    fn default() -> Self {
+        Self {
+            active_tasks: 0,
+            queue_depth: 0,
+            max_concurrent: 0,
+        }
+    }
+}
+
+/// A peer's signed statement of what it can run and how busy it is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapabilityDocument {
+    /// The peer the document describes.
+    pub peer_id: PeerId,
+    #[serde(default)]
+    pub providers: Vec<ProviderOffer>,
+    #[serde(default)]
+    pub hardware: HardwareProfile,
+    #[serde(default)]
+    pub load: LoadReport,
+    /// Unix timestamp (seconds) when the document was signed.
+    #[serde(default)]
+    pub issued_at: i64,
+    /// Increases with every signature so stale copies can be discarded.
+    #[serde(default)]
+    pub sequence: u64,
+    /// Hex-encoded ed25519 signature by the peer's identity key.
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl CapabilityDocument {
+    /// An empty, unsigned document for `peer_id`.
+    pub fn new(peer_id: PeerId) -> Self {
+        Self {
+            peer_id,
+            providers: Vec::new(),
+            hardware: HardwareProfile::default(),
+            load: LoadReport::default(),
+            issued_at: 0,
+            sequence: 0,
+            signature: String::new(),
+        }
+    }
+
+    fn signing_bytes(&self) -> Vec<u8> {
+        let unsigned = Self {
+            signature: String::new(),
+            ..self.clone()
+        };
+        let mut bytes = CAPABILITY_SIGNING_LABEL.as_bytes().to_vec();
+        bytes.extend(serde_json::to_vec(&unsigned).unwrap_or_default());
+        bytes
+    }
+
+    /// Stamp the document with the current time and the next sequence
+    /// number, then sign it.
+    pub fn sign(&mut self, keypair: &NodeKeypair) {
+        self.issued_at = Utc::now().timestamp();
+        self.sequence += 1;
+        self.signature = keypair.sign_hex(&self.signing_bytes());
+    }
+
+    /// Whether the document was signed by `public_key` (hex) and untampered.
+    pub fn verify(&self, public_key: &str) -> bool {
+        verify_signature(public_key, &self.signing_bytes(), &self.signature)
+    }
+
+    /// Whether the document was issued within the last `max_age_secs`.
+    pub fn is_fresh(&self, max_age_secs: i64) -> bool {
+        (Utc::now().timestamp() - self.issued_at).abs() <= max_age_secs
+    }
+
+    /// Whether this document supersedes `other`.
+    pub fn is_newer_than(&self, other: &CapabilityDocument) -> bool {
+        (self.issued_at, self.sequence) > (other.issued_at, other.sequence)
+    }
+
+    /// The cheapest model matching `requirements`, if any.
+    pub fn best_model(&self, requirements: &TaskRequirements) -> Option<&ModelOffer> {
+        self.providers
+            .iter()
+            .flat_map(|p| p.models.iter())
+            .filter(|m| requirements.models.is_empty() || requirements.models.contains(&m.id))
+            .filter(|m| m.supports(&requirements.capabilities))
+            .filter(|m| {
+                requirements
+                    .max_cost_per_mtok
+                    .is_none_or(|max| m.blended_cost_per_mtok() <= max)
+            })
+            .min_by(|a, b| {
+                a.blended_cost_per_mtok()
+                    .total_cmp(&b.blended_cost_per_mtok())
+            })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Scheduling
+// ---------------------------------------------------------------------------
+
+/// What a task needs from the peer that runs it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskRequirements {
+    /// Acceptable model IDs. Empty accepts any model.
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// `hive_ai::types::ModelCapability` names the model must support.
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    /// Upper bound on the blended cost per million tokens.
+    #[serde(default)]
+    pub max_cost_per_mtok: Option<f64>,
+    /// Skip peers that would have to queue the task.
+    #[serde(default)]
+    pub require_spare_capacity: bool,
+}
+
+/// A peer chosen for a task, with the model it should use.
+#[derive(Debug, Clone)]
+pub struct ScheduledPeer {
+    pub peer: PeerInfo,
+    /// The model on that peer that satisfies the requirements.
+    pub model: String,
+    /// Lower is better: latency in ms plus a load penalty.
+    pub score: f64,
+}
+
+/// Rank connected peers with a fresh capability document that can run a task
+/// meeting `requirements`, best first.
+///
+/// Each peer is scored as its heartbeat latency plus [`LOAD_PENALTY_MS`]
+/// scaled by its utilization, so an idle distant peer can beat a busy nearby
+/// one.
+pub fn rank_peers(
+    peers: &[PeerInfo],
+    requirements: &TaskRequirements,
+    max_age_secs: i64,
+) -> Vec<ScheduledPeer> {
+    let mut ranked: Vec<ScheduledPeer> = peers
+        .iter()
+        .filter(|peer| peer.state == PeerState::Connected)
+        .filter_map(|peer| {
+            let doc = peer.capabilities.as_ref()?;
+            if !doc.is_fresh(max_age_secs) {
+                return None;
+            }
+            if requirements.require_spare_capacity && !doc.load.has_spare_capacity() {
+                return None;
+            }
+            let model = doc.best_model(requirements)?;
+            let latency = peer
+                .latency_ms
+                .map(|ms| ms as f64)
+                .unwrap_or(UNKNOWN_LATENCY_MS);
+            Some(ScheduledPeer {
+                peer: peer.clone(),
+                model: model.id.clone(),
+                score: latency + LOAD_PENALTY_MS * doc.load.utilization(),
+            })
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| a.score.total_cmp(&b.score));
+    ranked
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::NodeIdentity;
+
+    fn document(identity: &NodeIdentity, models: Vec<ModelOffer>, load: LoadReport) -> PeerInfo {
+        let keypair = identity.keypair().unwrap();
+        let mut doc = CapabilityDocument::new(identity.peer_id.clone());
+        doc.providers.push(ProviderOffer {
+            name: "Ollama".into(),
+            local: true,
+            models,
+        });
+        doc.load = load;
+        doc.sign(keypair);
+        PeerInfo {
+            id: identity.peer_id.clone(),
+            identity: identity.clone(),
+            addr: "127.0.0.1:9470".parse().unwrap(),
+            state: PeerState::Connected,
+            connected_at: None,
+            last_seen: Utc::now(),
+            latency_ms: None,
+            capabilities: Some(doc),
+        }
+    }
+
+    fn vision_model(id: &str) -> ModelOffer {
+        ModelOffer {
+            capabilities: vec!["vision".into(), "tool_use".into()],
+            ..ModelOffer::local(id)
+        }
+    }
+
+    fn load(active_tasks: u32, max_concurrent: u32) -> LoadReport {
+        LoadReport {
+            active_tasks,
+            queue_depth: 0,
+            max_concurrent,
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let identity = NodeIdentity::generate("signer");
+        let mut doc = CapabilityDocument::new(identity.peer_id.clone());
+        doc.providers.push(ProviderOffer {
+            name: "Ollama".into(),
+            local: true,
+            models: vec![ModelOffer::local("llama3")],
+        });
+        doc.sign(identity.keypair().unwrap());
+        assert_eq!(doc.sequence, 1);
+        assert!(doc.verify(&identity.public_key));
+
+        // Tampering with the load invalidates the signature.
+        let mut tampered = doc.clone();
+        tampered.load.active_tasks = 0;
+        tampered.load.max_concurrent = 64;
+        assert!(!tampered.verify(&identity.public_key));
+
+        // A different key does not verify.
+        let other = NodeIdentity::generate("other");
+        assert!(!doc.verify(&other.public_key));
+    }
+
+    #[test]
+    fn test_document_json_roundtrip_still_verifies() {
+        let identity = NodeIdentity::generate("roundtrip");
+        let mut doc = CapabilityDocument::new(identity.peer_id.clone());
+        doc.hardware = HardwareProfile {
+            cpu_cores: 16,
+            memory_bytes: Some(64 << 30),
+            gpus: vec![GpuProfile {
+                name: "RTX 4090".into(),
+                memory_bytes: Some(24 << 30),
+            }],
+        };
+        doc.sign(identity.keypair().unwrap());
+
+        let json = serde_json::to_string(&doc).unwrap();
+        let parsed: CapabilityDocument = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, doc);
+        assert!(parsed.verify(&identity.public_key));
+    }
+
+    #[test]
+    fn test_newer_than_uses_sequence() {
+        let identity = NodeIdentity::generate("seq");
+        let mut doc = CapabilityDocument::new(identity.peer_id.clone());
+        doc.sign(identity.keypair().unwrap());
+        let older = doc.clone();
+        doc.sign(identity.keypair().unwrap());
+        assert!(doc.is_newer_than(&older));
+        assert!(!older.is_newer_than(&doc));
+    }
+
+    #[test]
+    fn test_best_model_filters_and_prefers_cheapest() {
+        let identity = NodeIdentity::generate("models");
+        let mut paid = vision_model("gpt-4o");
+        paid.input_cost_per_mtok = 2.5;
+        paid.output_cost_per_mtok = 10.0;
+        let peer = document(
+            &identity,
+            vec![ModelOffer::local("phi3"), paid, vision_model("llava")],
+            LoadReport::default(),
+        );
+        let doc = peer.capabilities.unwrap();
+
+        let any = TaskRequirements::default();
+        assert_eq!(doc.best_model(&any).unwrap().id, "phi3");
+
+        let vision = TaskRequirements {
+            capabilities: vec!["vision".into()],
+            ..Default::default()
+        };
+        assert_eq!(doc.best_model(&vision).unwrap().id, "llava");
+
+        let named = TaskRequirements {
+            models: vec!["gpt-4o".into()],
+            max_cost_per_mtok: Some(1.0),
+            ..Default::default()
+        };
+        assert!(doc.best_model(&named).is_none());
+    }
+
+    #[test]
+    fn test_rank_prefers_low_latency_and_low_load() {
+        let near_busy = NodeIdentity::generate("near-busy");
+        let far_idle = NodeIdentity::generate("far-idle");
+        let no_model = NodeIdentity::generate("no-model");
+
+        let mut a = document(&near_busy, vec![vision_model("llava")], load(2, 2));
+        a.latency_ms = Some(5);
+        let mut b = document(&far_idle, vec![vision_model("llava")], load(0, 2));
+        b.latency_ms = Some(60);
+        let c = document(&no_model, vec![ModelOffer::local("phi3")], load(0, 2));
+
+        let requirements = TaskRequirements {
+            capabilities: vec!["vision".into()],
+            ..Default::default()
+        };
+        let ranked = rank_peers(&[a, b, c], &requirements, 60);
+        let names: Vec<&str> = ranked
+            .iter()
+            .map(|s| s.peer.identity.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["far-idle", "near-busy"]);
+        assert_eq!(ranked[0].model, "llava");
+    }
+
+    #[test]
+    fn test_rank_skips_stale_disconnected_and_saturated() {
+        let stale = NodeIdentity::generate("stale");
+        let gone = NodeIdentity::generate("gone");
+        let full = NodeIdentity::generate("full");
+
+        let mut a = document(&stale, vec![ModelOffer::local("phi3")], load(0, 1));
+        a.capabilities.as_mut().unwrap().issued_at -= 600;
+        let mut b = document(&gone, vec![ModelOffer::local("phi3")], load(0, 1));
+        b.state = PeerState::Disconnected;
+        let c = document(&full, vec![ModelOffer::local("phi3")], load(1, 1));
+        // Never reported a load, so it runs no tasks for others.
+        let d = document(
+            &NodeIdentity::generate("silent"),
+            vec![ModelOffer::local("phi3")],
+            LoadReport::default(),
+        );
+
+        let requirements = TaskRequirements {
+            require_spare_capacity: true,
+            ..Default::default()
+        };
+        assert!(rank_peers(&[a, b, c.clone(), d], &requirements, 60).is_empty());
+
+        // Without the spare-capacity requirement the saturated peer qualifies.
+        let ranked = rank_peers(&[c], &TaskRequirements::default(), 60);
+        assert_eq!(ranked.len(), 1);
+    }
+}
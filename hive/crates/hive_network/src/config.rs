@@ -6,6 +6,8 @@ use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+use crate::trust::TrustPolicy;
+
 /// Configuration for the Hive networking layer.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkConfig {
@@ -30,8 +32,53 @@ pub struct NetworkConfig {
     #[serde(with = "duration_serde")]
     pub connection_timeout: Duration,
 
-    /// List of bootstrap peer addresses to connect to on startup.
+    /// Bootstrap peer addresses, dialled on startup and redialled whenever
+    /// they disconnect. Use these to federate across subnets where LAN
+    /// discovery cannot reach.
     pub known_peers: Vec<String>,
+
+    /// How often disconnected bootstrap peers are redialled.
+    #[serde(with = "duration_serde", default = "default_bootstrap_retry_interval")]
+    pub bootstrap_retry_interval: Duration,
+
+    /// Address peers should dial to reach this node, advertised through
+    /// gossip (e.g. a port-forwarded public address). Defaults to
+    /// `listen_addr` unless that is a wildcard address.
+    #[serde(default)]
+    pub advertise_addr: Option<String>,
+
+    /// Whether this node forwards relayed envelopes between other peers.
+    #[serde(default = "default_relay_enabled")]
+    pub relay_enabled: bool,
+
+    /// Hops a relayed envelope may take before it is dropped.
+    #[serde(default = "default_relay_max_hops")]
+    pub relay_max_hops: u8,
+
+    /// How peers that are not yet in the trust store are treated.
+    #[serde(default)]
+    pub trust_policy: TrustPolicy,
+
+    /// Whether swarm teams may be delegated to peers with spare capacity.
+    #[serde(default)]
+    pub delegate_teams: bool,
+
+    /// Whether this node runs swarm teams assigned by trusted peers, spending
+    /// its own providers on them.
+    #[serde(default)]
+    pub accept_teams: bool,
+}
+
+fn default_bootstrap_retry_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_relay_enabled() -> bool {
+    true
+}
+
+fn default_relay_max_hops() -> u8 {
+    4
 }
 
 impl Default for NetworkConfig {
@@ -46,11 +93,25 @@ impl Default for NetworkConfig {
             heartbeat_interval: Duration::from_secs(30),
             connection_timeout: Duration::from_secs(10),
             known_peers: Vec::new(),
+            bootstrap_retry_interval: default_bootstrap_retry_interval(),
+            advertise_addr: None,
+            relay_enabled: default_relay_enabled(),
+            relay_max_hops: default_relay_max_hops(),
+            trust_policy: TrustPolicy::TrustOnFirstUse,
+            delegate_teams: false,
+            accept_teams: false,
         }
     }
 }
 
 impl NetworkConfig {
+    /// The address advertised to the federation, if peers can dial us.
+    pub fn advertised_addr(&self) -> Option<String> {
+        self.advertise_addr.clone().or_else(|| {
+            (!self.listen_addr.ip().is_unspecified()).then(|| self.listen_addr.to_string())
+        })
+    }
+
     /// Save the config to a JSON file.
     pub fn save_to_file(&self, path: &Path) -> Result<(), String> {
         if let Some(parent) = path.parent() {
@@ -130,6 +191,35 @@ mod tests {
         assert_eq!(config.max_peers, 32);
         assert_eq!(config.heartbeat_interval, Duration::from_secs(30));
         assert!(config.known_peers.is_empty());
+        assert_eq!(config.trust_policy, TrustPolicy::TrustOnFirstUse);
+    }
+
+    #[test]
+    fn test_config_without_trust_policy_defaults_to_tofu() {
+        let json = r#"{"listen_addr":"0.0.0.0:9470","discovery_enabled":true,"discovery_port":9471,"max_peers":32,"heartbeat_interval":30,"connection_timeout":10,"known_peers":[]}"#;
+        let config: NetworkConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.trust_policy, TrustPolicy::TrustOnFirstUse);
+        assert!(config.relay_enabled);
+        assert_eq!(config.relay_max_hops, 4);
+        assert_eq!(config.bootstrap_retry_interval, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_advertised_addr_skips_wildcard_listen_addr() {
+        let mut config = NetworkConfig::default();
+        assert_eq!(config.advertised_addr(), None);
+
+        config.listen_addr = "192.168.1.20:9470".parse().unwrap();
+        assert_eq!(
+            config.advertised_addr().as_deref(),
+            Some("192.168.1.20:9470")
+        );
+
+        config.advertise_addr = Some("203.0.113.5:19470".into());
+        assert_eq!(
+            config.advertised_addr().as_deref(),
+            Some("203.0.113.5:19470")
+        );
     }
 
     #[test]
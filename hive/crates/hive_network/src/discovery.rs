@@ -3,16 +3,25 @@
 //! The [`DiscoveryService`] periodically broadcasts an announcement packet
 //! on the local network and listens for announcements from other peers.
 //! Discovered peers are reported through an mpsc channel.
+//!
+//! Announcements are signed with the node key and timestamped; unsigned,
+//! forged or stale announcements are dropped. A valid announcement only
+//! proves who is reachable where — trust is still decided during the
+//! transport handshake.
 
 use std::net::SocketAddr;
 use std::time::Duration;
 
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tokio::net::UdpSocket;
 use tokio::sync::mpsc;
 use tracing::{debug, info, trace, warn};
 
-use crate::identity::PeerId;
+use crate::identity::{NodeKeypair, PeerId, decode_public_key, verify_signature};
+
+/// Maximum clock difference, in seconds, for an announcement to be accepted.
+const MAX_ANNOUNCEMENT_AGE_SECS: i64 = 120;
 
 /// An announcement broadcast by a peer on the LAN.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +37,50 @@ pub struct Announcement {
     /// Whether this peer can act as a relay for other peers.
     #[serde(default)]
     pub relay_capable: bool,
+    /// Hex-encoded ed25519 public key the peer ID is derived from.
+    #[serde(default)]
+    pub public_key: String,
+    /// Unix timestamp (seconds) at which the announcement was signed.
+    #[serde(default)]
+    pub timestamp: i64,
+    /// Hex-encoded signature over all other fields.
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl Announcement {
+    /// The bytes covered by the signature.
+    fn signing_bytes(&self) -> Vec<u8> {
+        format!(
+            "hive-announce-v1\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            self.peer_id,
+            self.listen_addr,
+            self.name,
+            self.version,
+            self.relay_capable,
+            self.public_key,
+            self.timestamp
+        )
+        .into_bytes()
+    }
+
+    /// Timestamp and sign the announcement with the node key.
+    pub fn sign(&mut self, keypair: &NodeKeypair) {
+        self.public_key = keypair.public_key_hex();
+        self.timestamp = Utc::now().timestamp();
+        self.signature = keypair.sign_hex(&self.signing_bytes());
+    }
+
+    /// Whether the announcement is fresh, signed by the key its peer ID is
+    /// derived from, and untampered.
+    pub fn verify(&self) -> bool {
+        let Some(key) = decode_public_key(&self.public_key) else {
+            return false;
+        };
+        self.peer_id.matches_key(&key)
+            && (Utc::now().timestamp() - self.timestamp).abs() <= MAX_ANNOUNCEMENT_AGE_SECS
+            && verify_signature(&self.public_key, &self.signing_bytes(), &self.signature)
+    }
 }
 
 /// Event emitted when a peer is discovered on the LAN.
@@ -46,8 +99,10 @@ pub struct DiscoveryConfig {
     pub port: u16,
     /// How often to broadcast an announcement.
     pub interval: Duration,
-    /// Our own announcement to broadcast.
+    /// Our own announcement to broadcast; re-signed before every broadcast.
     pub announcement: Announcement,
+    /// Node key used to sign the announcement.
+    pub keypair: NodeKeypair,
 }
 
 /// LAN discovery service using UDP broadcast.
@@ -83,7 +138,8 @@ impl DiscoveryService {
         info!("Discovery service listening on {bind_addr}");
 
         let our_peer_id = config.announcement.peer_id.clone();
-        let announcement_json = serde_json::to_string(&config.announcement).unwrap_or_default();
+        let mut announcement = config.announcement;
+        let keypair = config.keypair;
         let broadcast_addr: SocketAddr = format!("255.255.255.255:{}", config.port)
             .parse()
             .expect("valid broadcast address from port number");
@@ -99,12 +155,13 @@ impl DiscoveryService {
             .map_err(|e| crate::error::NetworkError::Discovery(format!("Set broadcast: {e}")))?;
 
         // Spawn broadcaster.
-        let announcement_bytes = announcement_json.into_bytes();
         let mut shutdown_bcast = shutdown.resubscribe();
         tokio::spawn(async move {
             loop {
                 tokio::select! {
                     _ = tokio::time::sleep(interval) => {
+                        announcement.sign(&keypair);
+                        let announcement_bytes = serde_json::to_vec(&announcement).unwrap_or_default();
                         match sender_socket.send_to(&announcement_bytes, broadcast_addr).await {
                             Ok(_) => trace!("Broadcast announcement sent"),
                             Err(e) => debug!("Broadcast send failed: {e}"),
@@ -131,6 +188,10 @@ impl DiscoveryService {
                                     if announcement.peer_id == our_peer_id {
                                         continue;
                                     }
+                                    if !announcement.verify() {
+                                        debug!("Ignoring unsigned or invalid announcement from {src_addr}");
+                                        continue;
+                                    }
 
                                     debug!("Discovered peer '{}' at {src_addr}", announcement.name);
                                     let _ = discovered_tx
@@ -166,6 +227,19 @@ impl DiscoveryService {
 mod tests {
     use super::*;
 
+    fn unsigned(peer_id: &str) -> Announcement {
+        Announcement {
+            peer_id: PeerId::from_string(peer_id),
+            listen_addr: "127.0.0.1:9470".to_string(),
+            name: "node".to_string(),
+            version: "0.1.0".to_string(),
+            relay_capable: false,
+            public_key: String::new(),
+            timestamp: 0,
+            signature: String::new(),
+        }
+    }
+
     #[test]
     fn test_announcement_serialize_roundtrip() {
         let announcement = Announcement {
@@ -174,6 +248,7 @@ mod tests {
             name: "test-node".to_string(),
             version: "0.1.0".to_string(),
             relay_capable: false,
+            ..unsigned("test-peer")
         };
 
         let json = serde_json::to_string(&announcement).unwrap();
@@ -183,6 +258,46 @@ mod tests {
         assert_eq!(deserialized.listen_addr, "127.0.0.1:9470");
     }
 
+    #[test]
+    fn test_signed_announcement_verifies() {
+        let keypair = NodeKeypair::generate();
+        let mut announcement = Announcement {
+            peer_id: PeerId::from_public_key(&keypair.verifying_key()),
+            ..unsigned("ignored")
+        };
+        assert!(!announcement.verify());
+
+        announcement.sign(&keypair);
+        assert!(announcement.verify());
+
+        let json = serde_json::to_string(&announcement).unwrap();
+        let received: Announcement = serde_json::from_str(&json).unwrap();
+        assert!(received.verify());
+    }
+
+    #[test]
+    fn test_tampered_announcement_fails() {
+        let keypair = NodeKeypair::generate();
+        let mut announcement = Announcement {
+            peer_id: PeerId::from_public_key(&keypair.verifying_key()),
+            ..unsigned("ignored")
+        };
+        announcement.sign(&keypair);
+
+        let mut redirected = announcement.clone();
+        redirected.listen_addr = "10.0.0.66:9470".to_string();
+        assert!(!redirected.verify());
+
+        // Signed by a key the peer ID is not derived from.
+        let mut impersonated = announcement.clone();
+        impersonated.sign(&NodeKeypair::generate());
+        assert!(!impersonated.verify());
+
+        let mut stale = announcement;
+        stale.timestamp -= MAX_ANNOUNCEMENT_AGE_SECS + 60;
+        assert!(!stale.verify());
+    }
+
     #[test]
     fn test_discovery_config_creation() {
         let config = DiscoveryConfig {
@@ -194,7 +309,9 @@ mod tests {
                 name: "my-node".to_string(),
                 version: "0.1.0".to_string(),
                 relay_capable: false,
+                ..unsigned("my-peer")
             },
+            keypair: NodeKeypair::generate(),
         };
 
         assert_eq!(config.port, 9471);
@@ -209,6 +326,7 @@ mod tests {
             name: "relay-node".to_string(),
             version: "0.1.0".to_string(),
             relay_capable: true,
+            ..unsigned("relay-peer")
         };
 
         let json = serde_json::to_string(&announcement).unwrap();
@@ -223,6 +341,8 @@ mod tests {
         let deserialized: Announcement = serde_json::from_str(json).unwrap();
         assert!(!deserialized.relay_capable);
         assert_eq!(deserialized.name, "old-node");
+        // Old, unsigned announcements are parsed but never trusted.
+        assert!(!deserialized.verify());
     }
 
     #[tokio::test]
@@ -237,6 +357,7 @@ mod tests {
             name: "loopback-node".to_string(),
             version: "0.1.0".to_string(),
             relay_capable: false,
+            ..unsigned("loopback-peer")
         };
         let data = serde_json::to_vec(&announcement).unwrap();
 
@@ -25,6 +25,14 @@ pub enum NetworkError {
     #[error("Connection refused by {0}")]
     ConnectionRefused(String),
 
+    /// The cryptographic handshake with a peer failed.
+    #[error("Handshake failed: {0}")]
+    Handshake(String),
+
+    /// The peer authenticated but is not trusted by the local trust store.
+    #[error("Untrusted peer: {0}")]
+    Untrusted(String),
+
     /// An operation timed out.
     #[error("Timeout after {0:?}")]
     Timeout(Duration),
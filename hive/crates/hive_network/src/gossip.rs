@@ -0,0 +1,544 @@
+//! Gossip membership — SWIM-style failure detection across the federation.
+//!
+//! Every node keeps a [`Membership`] table of the peers it knows about,
+//! whether or not it has a direct connection to them. Each heartbeat carries
+//! a digest of that table, so membership spreads hop by hop, and every entry
+//! remembers the neighbour it was learned from as the next hop used by
+//! [`crate::relay`] to reach it.
+//!
+//! Liveness follows SWIM: a member that has not been heard from for
+//! [`SUSPECT_AFTER_HEARTBEATS`] heartbeats becomes [`MemberStatus::Suspect`],
+//! and a suspect that does not refute the suspicion within
+//! [`DEAD_AFTER_HEARTBEATS`] more becomes [`MemberStatus::Dead`]. Records are
+//! signed by the member they describe, so only the member itself can refute a
+//! suspicion (by re-signing with a higher incarnation number); neighbours can
+//! spread suspicion but cannot forge liveness.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::identity::{NodeIdentity, NodeKeypair, PeerId, decode_public_key, verify_signature};
+
+/// Domain-separation label prepended to member records before signing.
+pub const MEMBER_SIGNING_LABEL: &[u8] = b"hive-member-v1\n";
+
+/// Heartbeats a member may miss before it is suspected.
+pub const SUSPECT_AFTER_HEARTBEATS: u32 = 3;
+
+/// Further heartbeats a suspect has to refute the suspicion before it is
+/// declared dead.
+pub const DEAD_AFTER_HEARTBEATS: u32 = 3;
+
+/// Heartbeats a dead member is still gossiped before it is forgotten.
+pub const REAP_AFTER_HEARTBEATS: u32 = 10;
+
+/// Maximum number of entries in one gossip digest.
+pub const MAX_DIGEST_ENTRIES: usize = 64;
+
+/// Liveness of a member as seen by this node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemberStatus {
+    Alive,
+    /// Missed heartbeats; the member has a chance to refute.
+    Suspect,
+    Dead,
+}
+
+impl MemberStatus {
+    /// Which status wins when two updates carry the same incarnation.
+    fn precedence(self) -> u8 {
+        match self {
+            Self::Alive => 0,
+            Self::Suspect => 1,
+            Self::Dead => 2,
+        }
+    }
+}
+
+/// A member's self-signed description of itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberRecord {
+    pub peer_id: PeerId,
+    /// Hex-encoded ed25519 public key the peer ID is derived from.
+    pub public_key: String,
+    pub name: String,
+    /// Address other nodes can dial, if the member accepts connections.
+    #[serde(default)]
+    pub listen_addr: Option<String>,
+    /// Whether the member forwards relay frames for other peers.
+    #[serde(default)]
+    pub relay_capable: bool,
+    /// Bumped by the member to refute suspicion; newer records win.
+    pub incarnation: u64,
+    /// Hex-encoded signature by the member over the record.
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl MemberRecord {
+    /// An unsigned record for the local node.
+    pub fn new(identity: &NodeIdentity, listen_addr: Option<String>, relay_capable: bool) -> Self {
+        Self {
+            peer_id: identity.peer_id.clone(),
+            public_key: identity.public_key.clone(),
+            name: identity.name.clone(),
+            listen_addr,
+            relay_capable,
+            incarnation: 0,
+            signature: String::new(),
+        }
+    }
+
+    /// The bytes covered by the signature: the record with the signature
+    /// cleared, prefixed with [`MEMBER_SIGNING_LABEL`].
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let unsigned = Self {
+            signature: String::new(),
+            ..self.clone()
+        };
+        let mut bytes = MEMBER_SIGNING_LABEL.to_vec();
+        bytes.extend(serde_json::to_vec(&unsigned).unwrap_or_default());
+        bytes
+    }
+
+    /// Sign the record with the member's key.
+    pub fn sign(&mut self, keypair: &NodeKeypair) {
+        self.signature = keypair.sign_hex(&self.signing_bytes());
+    }
+
+    /// Whether the peer ID is derived from the record's key and the record
+    /// is signed by it.
+    pub fn verify(&self) -> bool {
+        decode_public_key(&self.public_key).is_some_and(|key| self.peer_id.matches_key(&key))
+            && verify_signature(&self.public_key, &self.signing_bytes(), &self.signature)
+    }
+}
+
+/// One entry of a gossip digest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemberUpdate {
+    pub record: MemberRecord,
+    pub status: MemberStatus,
+    /// Links between the sender and the member; 0 for the sender itself.
+    pub distance: u8,
+}
+
+/// A known member and how to reach it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Member {
+    pub record: MemberRecord,
+    pub status: MemberStatus,
+    /// Neighbour frames for this member are forwarded through. `None` when
+    /// the route was lost and no other neighbour has offered one yet.
+    pub via: Option<PeerId>,
+    /// Links to the member along `via`.
+    pub distance: u8,
+    /// Last time the member, or its next hop on its behalf, was heard from.
+    pub last_heard: DateTime<Utc>,
+    /// When `status` last changed.
+    pub status_since: DateTime<Utc>,
+}
+
+impl Member {
+    fn set_status(&mut self, status: MemberStatus, now: DateTime<Utc>) {
+        if self.status != status {
+            self.status = status;
+            self.status_since = now;
+        }
+    }
+}
+
+/// The local node's view of federation membership.
+#[derive(Debug)]
+pub struct Membership {
+    local: MemberRecord,
+    members: HashMap<String, Member>,
+}
+
+impl Membership {
+    /// Create a table containing only the local node.
+    pub fn new(local: MemberRecord) -> Self {
+        Self {
+            local,
+            members: HashMap::new(),
+        }
+    }
+
+    /// The local node's current record.
+    pub fn local(&self) -> &MemberRecord {
+        &self.local
+    }
+
+    /// Re-sign the local record with a fresh incarnation.
+    ///
+    /// Incarnations start from the wall clock so a restarted node supersedes
+    /// whatever the federation remembers about its previous run.
+    pub fn renew(&mut self, keypair: &NodeKeypair) {
+        let now = Utc::now().timestamp_millis().max(0) as u64;
+        self.local.incarnation = (self.local.incarnation + 1).max(now);
+        self.local.sign(keypair);
+    }
+
+    /// Get a member by ID.
+    pub fn get(&self, peer_id: &PeerId) -> Option<&Member> {
+        self.members.get(peer_id.as_str())
+    }
+
+    /// List all known members, excluding the local node.
+    pub fn list(&self) -> Vec<&Member> {
+        self.members.values().collect()
+    }
+
+    /// The neighbour to forward frames for `peer_id` through, unless the
+    /// member is dead or unroutable.
+    pub fn next_hop(&self, peer_id: &PeerId) -> Option<&PeerId> {
+        self.get(peer_id)
+            .filter(|m| m.status != MemberStatus::Dead)
+            .and_then(|m| m.via.as_ref())
+    }
+
+    /// Record first-hand evidence that `peer_id` is alive: direct traffic or
+    /// an end-to-end relayed message.
+    pub fn heard_from(&mut self, peer_id: &PeerId, now: DateTime<Utc>) {
+        if let Some(member) = self.members.get_mut(peer_id.as_str()) {
+            member.last_heard = now;
+            if member.status == MemberStatus::Suspect {
+                member.set_status(MemberStatus::Alive, now);
+            }
+        }
+    }
+
+    /// The digest sent with each heartbeat: the local record plus the most
+    /// recently changed routable members.
+    pub fn digest(&self) -> Vec<MemberUpdate> {
+        let mut members: Vec<&Member> = self.members.values().filter(|m| m.via.is_some()).collect();
+        members.sort_by_key(|m| std::cmp::Reverse(m.status_since));
+
+        let mut digest = vec![MemberUpdate {
+            record: self.local.clone(),
+            status: MemberStatus::Alive,
+            distance: 0,
+        }];
+        digest.extend(
+            members
+                .into_iter()
+                .take(MAX_DIGEST_ENTRIES - 1)
+                .map(|m| MemberUpdate {
+                    record: m.record.clone(),
+                    status: m.status,
+                    distance: m.distance,
+                }),
+        );
+        digest
+    }
+
+    /// Merge a digest received from neighbour `from`.
+    ///
+    /// Returns `true` if the digest suspects or declares the local node dead
+    /// at its current incarnation; the caller should [`renew`](Self::renew)
+    /// to refute it.
+    pub fn merge(&mut self, from: &PeerId, updates: Vec<MemberUpdate>, now: DateTime<Utc>) -> bool {
+        let mut refute = false;
+        for update in updates {
+            if !update.record.verify() {
+                continue;
+            }
+            if update.record.peer_id == self.local.peer_id {
+                refute |= update.status != MemberStatus::Alive
+                    && update.record.incarnation >= self.local.incarnation;
+                continue;
+            }
+
+            let distance = update.distance.saturating_add(1);
+            let key = update.record.peer_id.as_str().to_string();
+            let Some(member) = self.members.get_mut(&key) else {
+                if update.status != MemberStatus::Dead {
+                    self.members.insert(
+                        key,
+                        Member {
+                            record: update.record,
+                            status: update.status,
+                            via: Some(from.clone()),
+                            distance,
+                            last_heard: now,
+                            status_since: now,
+                        },
+                    );
+                }
+                continue;
+            };
+
+            let newer = update.record.incarnation > member.record.incarnation
+                || (update.record.incarnation == member.record.incarnation
+                    && update.status.precedence() > member.status.precedence());
+            if newer {
+                member.record = update.record;
+                member.set_status(update.status, now);
+                if update.status == MemberStatus::Alive {
+                    member.last_heard = now;
+                }
+            }
+
+            if update.status == MemberStatus::Dead {
+                continue;
+            }
+            if member.via.as_ref() == Some(from) {
+                // Our next hop still reaches the member.
+                member.distance = distance;
+                if update.status == MemberStatus::Alive && member.status == MemberStatus::Alive {
+                    member.last_heard = now;
+                }
+            } else if member.via.is_none() || distance < member.distance {
+                member.via = Some(from.clone());
+                member.distance = distance;
+            }
+        }
+        refute
+    }
+
+    /// Advance failure detection. Returns members that just became suspect.
+    pub fn sweep(&mut self, now: DateTime<Utc>, heartbeat: Duration) -> Vec<PeerId> {
+        let after = |beats: u32| {
+            chrono::Duration::from_std(heartbeat * beats).unwrap_or(chrono::Duration::MAX)
+        };
+        let (suspect_after, dead_after, reap_after) = (
+            after(SUSPECT_AFTER_HEARTBEATS),
+            after(DEAD_AFTER_HEARTBEATS),
+            after(REAP_AFTER_HEARTBEATS),
+        );
+
+        let mut suspected = Vec::new();
+        self.members.retain(|_, member| {
+            match member.status {
+                MemberStatus::Alive if now - member.last_heard > suspect_after => {
+                    member.set_status(MemberStatus::Suspect, now);
+                    suspected.push(member.record.peer_id.clone());
+                }
+                MemberStatus::Suspect if now - member.status_since > dead_after => {
+                    member.set_status(MemberStatus::Dead, now);
+                }
+                MemberStatus::Dead if now - member.status_since > reap_after => return false,
+                _ => {}
+            }
+            true
+        });
+        suspected
+    }
+
+    /// Forget every route through `neighbour` after its connection closed.
+    pub fn drop_routes_via(&mut self, neighbour: &PeerId) {
+        for member in self.members.values_mut() {
+            if member.via.as_ref() == Some(neighbour) {
+                member.via = None;
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEAT: Duration = Duration::from_secs(1);
+
+    fn signed_record(identity: &NodeIdentity, incarnation: u64) -> MemberRecord {
+        let mut record = MemberRecord::new(identity, Some("10.0.0.2:9470".into()), true);
+        record.incarnation = incarnation;
+        record.sign(identity.keypair().unwrap());
+        record
+    }
+
+    fn update(record: MemberRecord, status: MemberStatus, distance: u8) -> MemberUpdate {
+        MemberUpdate {
+            record,
+            status,
+            distance,
+        }
+    }
+
+    fn local_membership() -> (NodeIdentity, Membership) {
+        let identity = NodeIdentity::generate("local");
+        let mut membership = Membership::new(MemberRecord::new(&identity, None, true));
+        membership.renew(identity.keypair().unwrap());
+        (identity, membership)
+    }
+
+    #[test]
+    fn records_must_be_signed_by_their_member() {
+        let identity = NodeIdentity::generate("member");
+        let mut record = signed_record(&identity, 1);
+        assert!(record.verify());
+
+        record.incarnation = 2;
+        assert!(!record.verify());
+
+        let forger = NodeIdentity::generate("forger");
+        record.sign(forger.keypair().unwrap());
+        assert!(!record.verify());
+    }
+
+    #[test]
+    fn merge_learns_members_and_routes_through_sender() {
+        let (_, mut membership) = local_membership();
+        let neighbour = NodeIdentity::generate("neighbour");
+        let far = NodeIdentity::generate("far");
+        let now = Utc::now();
+
+        membership.merge(
+            &neighbour.peer_id,
+            vec![
+                update(signed_record(&neighbour, 1), MemberStatus::Alive, 0),
+                update(signed_record(&far, 1), MemberStatus::Alive, 1),
+            ],
+            now,
+        );
+
+        assert_eq!(
+            membership.next_hop(&neighbour.peer_id),
+            Some(&neighbour.peer_id)
+        );
+        assert_eq!(membership.next_hop(&far.peer_id), Some(&neighbour.peer_id));
+        assert_eq!(membership.get(&far.peer_id).unwrap().distance, 2);
+        assert_eq!(membership.digest().len(), 3);
+    }
+
+    #[test]
+    fn merge_prefers_shorter_routes() {
+        let (_, mut membership) = local_membership();
+        let slow = PeerId::from_string("slow");
+        let fast = PeerId::from_string("fast");
+        let far = NodeIdentity::generate("far");
+        let now = Utc::now();
+
+        membership.merge(
+            &slow,
+            vec![update(signed_record(&far, 1), MemberStatus::Alive, 3)],
+            now,
+        );
+        membership.merge(
+            &fast,
+            vec![update(signed_record(&far, 1), MemberStatus::Alive, 1)],
+            now,
+        );
+        assert_eq!(membership.next_hop(&far.peer_id), Some(&fast));
+
+        membership.drop_routes_via(&fast);
+        assert_eq!(membership.next_hop(&far.peer_id), None);
+        membership.merge(
+            &slow,
+            vec![update(signed_record(&far, 1), MemberStatus::Alive, 3)],
+            now,
+        );
+        assert_eq!(membership.next_hop(&far.peer_id), Some(&slow));
+    }
+
+    #[test]
+    fn suspicion_only_yields_to_a_newer_incarnation() {
+        let (_, mut membership) = local_membership();
+        let from = PeerId::from_string("neighbour");
+        let member = NodeIdentity::generate("member");
+        let now = Utc::now();
+
+        membership.merge(
+            &from,
+            vec![update(signed_record(&member, 1), MemberStatus::Alive, 0)],
+            now,
+        );
+        membership.merge(
+            &from,
+            vec![update(signed_record(&member, 1), MemberStatus::Suspect, 0)],
+            now,
+        );
+        membership.merge(
+            &from,
+            vec![update(signed_record(&member, 1), MemberStatus::Alive, 0)],
+            now,
+        );
+        assert_eq!(
+            membership.get(&member.peer_id).unwrap().status,
+            MemberStatus::Suspect
+        );
+
+        membership.merge(
+            &from,
+            vec![update(signed_record(&member, 2), MemberStatus::Alive, 0)],
+            now,
+        );
+        assert_eq!(
+            membership.get(&member.peer_id).unwrap().status,
+            MemberStatus::Alive
+        );
+    }
+
+    #[test]
+    fn local_node_refutes_suspicion() {
+        let (identity, mut membership) = local_membership();
+        let from = PeerId::from_string("neighbour");
+        let current = membership.local().clone();
+
+        let mut stale = MemberRecord {
+            incarnation: current.incarnation - 1,
+            ..current.clone()
+        };
+        stale.sign(identity.keypair().unwrap());
+        assert!(!membership.merge(
+            &from,
+            vec![update(stale, MemberStatus::Suspect, 1)],
+            Utc::now()
+        ));
+
+        assert!(membership.merge(
+            &from,
+            vec![update(current.clone(), MemberStatus::Suspect, 1)],
+            Utc::now()
+        ));
+        membership.renew(identity.keypair().unwrap());
+        assert!(membership.local().incarnation > current.incarnation);
+        assert!(membership.local().verify());
+    }
+
+    #[test]
+    fn sweep_suspects_then_kills_then_forgets_silent_members() {
+        let (_, mut membership) = local_membership();
+        let from = PeerId::from_string("neighbour");
+        let member = NodeIdentity::generate("member");
+        let start = Utc::now();
+        membership.merge(
+            &from,
+            vec![update(signed_record(&member, 1), MemberStatus::Alive, 0)],
+            start,
+        );
+
+        let later = |secs| start + chrono::Duration::seconds(secs);
+        assert!(membership.sweep(later(2), BEAT).is_empty());
+        assert_eq!(
+            membership.sweep(later(4), BEAT),
+            vec![member.peer_id.clone()]
+        );
+
+        membership.heard_from(&member.peer_id, later(5));
+        assert_eq!(
+            membership.get(&member.peer_id).unwrap().status,
+            MemberStatus::Alive
+        );
+
+        membership.sweep(later(9), BEAT);
+        membership.sweep(later(13), BEAT);
+        assert_eq!(
+            membership.get(&member.peer_id).unwrap().status,
+            MemberStatus::Dead
+        );
+        assert_eq!(membership.next_hop(&member.peer_id), None);
+
+        membership.sweep(later(24), BEAT);
+        assert!(membership.get(&member.peer_id).is_none());
+    }
+}
@@ -0,0 +1,518 @@
+//! Mutually authenticated key exchange run before any envelope is accepted.
+//!
+//! The exchange follows the Noise `XX` message pattern:
+//!
+//! ```text
+//! -> e
+//! <- e, ee, s
+//! -> s
+//! ```
+//!
+//! Both sides send a fresh X25519 ephemeral key, derive a handshake key from
+//! the ephemeral Diffie-Hellman result and then exchange their identities
+//! encrypted under it. Node keys are ed25519 signing keys, so instead of the
+//! `es`/`se` static DH each side proves ownership of its [`PeerId`] by signing
+//! the running transcript hash. After the third message both sides derive one
+//! AES-256-GCM key per direction and use counter nonces, so frames cannot be
+//! replayed, reordered or moved between connections.
+//!
+//! The types here are transport-agnostic; [`crate::transport`] drives them
+//! over a WebSocket.
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::error::NetworkError;
+use crate::identity::{NodeIdentity, NodeKeypair, verify_signature};
+
+/// Protocol name mixed into the transcript hash.
+pub const PROTOCOL_NAME: &str = "hive-xx-x25519-ed25519-aesgcm-sha256-v1";
+
+/// Domain-separation labels for the two identity signatures.
+const INITIATOR_LABEL: &[u8] = b"hive-xx-initiator";
+const RESPONDER_LABEL: &[u8] = b"hive-xx-responder";
+
+/// A handshake message, sent as a JSON text frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HandshakeMessage {
+    /// `-> e`
+    Initiate { protocol: String, ephemeral: String },
+    /// `<- e, ee, s`
+    Respond { ephemeral: String, sealed: String },
+    /// `-> s`
+    Finish { sealed: String },
+}
+
+impl HandshakeMessage {
+    /// Serialize the message to a JSON string for transmission.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserialize a message from a JSON string.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// The identity proof carried (encrypted) in the second and third messages.
+#[derive(Serialize, Deserialize)]
+struct IdentityProof {
+    identity: NodeIdentity,
+    /// Signature over the role label and transcript hash.
+    signature: String,
+}
+
+// ---------------------------------------------------------------------------
+// Cipher state
+// ---------------------------------------------------------------------------
+
+/// One direction of an encrypted channel: AES-256-GCM with a 64-bit counter
+/// nonce.
+pub struct CipherState {
+    cipher: Aes256Gcm,
+    counter: u64,
+}
+
+impl CipherState {
+    fn new(key: &[u8; 32]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new_from_slice(key).expect("32-byte key is valid for AES-256-GCM"),
+            counter: 0,
+        }
+    }
+
+    fn next_nonce(&mut self) -> Result<[u8; 12], NetworkError> {
+        if self.counter == u64::MAX {
+            return Err(NetworkError::Transport("Nonce space exhausted".into()));
+        }
+        let mut nonce = [0u8; 12];
+        nonce[4..].copy_from_slice(&self.counter.to_be_bytes());
+        self.counter += 1;
+        Ok(nonce)
+    }
+
+    /// Encrypt the next frame.
+    pub fn encrypt(&mut self, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, NetworkError> {
+        let nonce = self.next_nonce()?;
+        self.cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .map_err(|_| NetworkError::Transport("Encryption failed".into()))
+    }
+
+    /// Decrypt the next frame. Fails if the frame was tampered with, replayed
+    /// or arrived out of order.
+    pub fn decrypt(&mut self, ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>, NetworkError> {
+        let nonce = self.next_nonce()?;
+        self.cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|_| NetworkError::Transport("Decryption failed".into()))
+    }
+}
+
+/// The result of a completed handshake.
+pub struct SecureSession {
+    /// The authenticated remote identity.
+    pub remote: NodeIdentity,
+    /// Cipher for frames we send.
+    pub send: CipherState,
+    /// Cipher for frames we receive.
+    pub recv: CipherState,
+}
+
+// ---------------------------------------------------------------------------
+// Symmetric state
+// ---------------------------------------------------------------------------
+
+/// Transcript hash plus the keys derived from the ephemeral DH.
+struct SymmetricState {
+    hash: [u8; 32],
+    shared: [u8; 32],
+    handshake: Option<CipherState>,
+}
+
+impl SymmetricState {
+    fn new() -> Self {
+        Self {
+            hash: Sha256::digest(PROTOCOL_NAME.as_bytes()).into(),
+            shared: [0u8; 32],
+            handshake: None,
+        }
+    }
+
+    fn mix_hash(&mut self, data: &[u8]) {
+        let mut hasher = Sha256::new();
+        hasher.update(self.hash);
+        hasher.update(data);
+        self.hash = hasher.finalize().into();
+    }
+
+    /// Mix in the `ee` DH result and derive the handshake key.
+    fn mix_key(&mut self, shared: [u8; 32]) {
+        self.shared = shared;
+        let mut key = [0u8; 32];
+        Hkdf::<Sha256>::new(Some(&self.hash), &shared)
+            .expand(b"hive-xx-handshake", &mut key)
+            .expect("32 bytes is within HKDF-SHA256 limit");
+        self.handshake = Some(CipherState::new(&key));
+    }
+
+    fn seal(&mut self, proof: &IdentityProof) -> Result<String, NetworkError> {
+        let plaintext = serde_json::to_vec(proof)?;
+        let aad = self.hash;
+        let cipher = self.handshake.as_mut().expect("handshake key derived");
+        let sealed = cipher.encrypt(&plaintext, &aad)?;
+        self.mix_hash(&sealed);
+        Ok(hex::encode(sealed))
+    }
+
+    fn open(&mut self, sealed: &str) -> Result<IdentityProof, NetworkError> {
+        let sealed = hex::decode(sealed).map_err(|_| handshake_error("malformed payload"))?;
+        let aad = self.hash;
+        let cipher = self.handshake.as_mut().expect("handshake key derived");
+        let plaintext = cipher
+            .decrypt(&sealed, &aad)
+            .map_err(|_| handshake_error("payload failed to decrypt"))?;
+        self.mix_hash(&sealed);
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// Derive the per-direction transport keys from the final transcript.
+    fn split(&self) -> (CipherState, CipherState) {
+        let mut okm = [0u8; 64];
+        Hkdf::<Sha256>::new(Some(&self.hash), &self.shared)
+            .expand(b"hive-xx-transport", &mut okm)
+            .expect("64 bytes is within HKDF-SHA256 limit");
+        let mut initiator_key = [0u8; 32];
+        let mut responder_key = [0u8; 32];
+        initiator_key.copy_from_slice(&okm[..32]);
+        responder_key.copy_from_slice(&okm[32..]);
+        (
+            CipherState::new(&initiator_key),
+            CipherState::new(&responder_key),
+        )
+    }
+}
+
+fn handshake_error(msg: &str) -> NetworkError {
+    NetworkError::Handshake(msg.to_string())
+}
+
+fn local_keypair(local: &NodeIdentity) -> Result<&NodeKeypair, NetworkError> {
+    local
+        .keypair()
+        .ok_or_else(|| handshake_error("local identity has no signing key"))
+}
+
+fn decode_ephemeral(hex_key: &str) -> Result<[u8; 32], NetworkError> {
+    hex::decode(hex_key)
+        .ok()
+        .and_then(|b| <[u8; 32]>::try_from(b).ok())
+        .ok_or_else(|| handshake_error("malformed ephemeral key"))
+}
+
+fn signed_message(label: &[u8], hash: &[u8; 32]) -> Vec<u8> {
+    [label, hash.as_slice()].concat()
+}
+
+fn prove(
+    local: &NodeIdentity,
+    keypair: &NodeKeypair,
+    label: &[u8],
+    hash: &[u8; 32],
+) -> IdentityProof {
+    IdentityProof {
+        identity: NodeIdentity::remote(
+            local.peer_id.clone(),
+            local.name.clone(),
+            local.version.clone(),
+            local.public_key.clone(),
+        )
+        .with_capabilities(local.capabilities.clone()),
+        signature: keypair.sign_hex(&signed_message(label, hash)),
+    }
+}
+
+fn verify_proof(proof: &IdentityProof, label: &[u8], hash: &[u8; 32]) -> Result<(), NetworkError> {
+    if !proof.identity.is_self_certifying() {
+        return Err(handshake_error("peer ID is not derived from its key"));
+    }
+    if !verify_signature(
+        &proof.identity.public_key,
+        &signed_message(label, hash),
+        &proof.signature,
+    ) {
+        return Err(handshake_error("invalid identity signature"));
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Initiator
+// ---------------------------------------------------------------------------
+
+/// The connecting side of the handshake.
+pub struct Initiator {
+    local: NodeIdentity,
+    ephemeral: StaticSecret,
+    state: SymmetricState,
+}
+
+impl Initiator {
+    /// Start a handshake, returning the first message to send.
+    pub fn start(local: &NodeIdentity) -> Result<(Self, HandshakeMessage), NetworkError> {
+        local_keypair(local)?;
+        let ephemeral = StaticSecret::from(rand::random::<[u8; 32]>());
+        let public = PublicKey::from(&ephemeral);
+
+        let mut state = SymmetricState::new();
+        state.mix_hash(public.as_bytes());
+
+        let msg = HandshakeMessage::Initiate {
+            protocol: PROTOCOL_NAME.to_string(),
+            ephemeral: hex::encode(public.as_bytes()),
+        };
+        Ok((
+            Self {
+                local: local.clone(),
+                ephemeral,
+                state,
+            },
+            msg,
+        ))
+    }
+
+    /// Process the responder's reply, returning the final message to send
+    /// and the established session.
+    pub fn finish(
+        mut self,
+        reply: HandshakeMessage,
+    ) -> Result<(HandshakeMessage, SecureSession), NetworkError> {
+        let HandshakeMessage::Respond { ephemeral, sealed } = reply else {
+            return Err(handshake_error("expected respond message"));
+        };
+        let remote_e = decode_ephemeral(&ephemeral)?;
+        self.state.mix_hash(&remote_e);
+        let shared = self.ephemeral.diffie_hellman(&PublicKey::from(remote_e));
+        self.state.mix_key(*shared.as_bytes());
+
+        let signed_hash = self.state.hash;
+        let remote = self.state.open(&sealed)?;
+        verify_proof(&remote, RESPONDER_LABEL, &signed_hash)?;
+
+        let keypair = local_keypair(&self.local)?;
+        let proof = prove(&self.local, keypair, INITIATOR_LABEL, &self.state.hash);
+        let sealed = self.state.seal(&proof)?;
+
+        let (send, recv) = self.state.split();
+        Ok((
+            HandshakeMessage::Finish { sealed },
+            SecureSession {
+                remote: remote.identity,
+                send,
+                recv,
+            },
+        ))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Responder
+// ---------------------------------------------------------------------------
+
+/// The accepting side of the handshake.
+pub struct Responder {
+    state: SymmetricState,
+}
+
+impl Responder {
+    /// Process the initiator's first message, returning the reply to send.
+    pub fn respond(
+        local: &NodeIdentity,
+        initiate: HandshakeMessage,
+    ) -> Result<(Self, HandshakeMessage), NetworkError> {
+        let HandshakeMessage::Initiate {
+            protocol,
+            ephemeral,
+        } = initiate
+        else {
+            return Err(handshake_error("expected initiate message"));
+        };
+        if protocol != PROTOCOL_NAME {
+            return Err(handshake_error(&format!(
+                "unsupported protocol '{protocol}'"
+            )));
+        }
+        let keypair = local_keypair(local)?;
+
+        let remote_e = decode_ephemeral(&ephemeral)?;
+        let secret = StaticSecret::from(rand::random::<[u8; 32]>());
+        let public = PublicKey::from(&secret);
+
+        let mut state = SymmetricState::new();
+        state.mix_hash(&remote_e);
+        state.mix_hash(public.as_bytes());
+        let shared = secret.diffie_hellman(&PublicKey::from(remote_e));
+        state.mix_key(*shared.as_bytes());
+
+        let proof = prove(local, keypair, RESPONDER_LABEL, &state.hash);
+        let sealed = state.seal(&proof)?;
+
+        Ok((
+            Self { state },
+            HandshakeMessage::Respond {
+                ephemeral: hex::encode(public.as_bytes()),
+                sealed,
+            },
+        ))
+    }
+
+    /// Process the initiator's final message and establish the session.
+    pub fn finish(mut self, finish: HandshakeMessage) -> Result<SecureSession, NetworkError> {
+        let HandshakeMessage::Finish { sealed } = finish else {
+            return Err(handshake_error("expected finish message"));
+        };
+        let signed_hash = self.state.hash;
+        let remote = self.state.open(&sealed)?;
+        verify_proof(&remote, INITIATOR_LABEL, &signed_hash)?;
+
+        let (recv, send) = self.state.split();
+        Ok(SecureSession {
+            remote: remote.identity,
+            send,
+            recv,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_handshake(
+        initiator: &NodeIdentity,
+        responder: &NodeIdentity,
+    ) -> (SecureSession, SecureSession) {
+        let (init, m1) = Initiator::start(initiator).unwrap();
+        let (resp, m2) = Responder::respond(responder, m1).unwrap();
+        let (m3, init_session) = init.finish(m2).unwrap();
+        let resp_session = resp.finish(m3).unwrap();
+        (init_session, resp_session)
+    }
+
+    #[test]
+    fn test_handshake_authenticates_both_sides() {
+        let alice = NodeIdentity::generate("alice");
+        let bob = NodeIdentity::generate("bob");
+        let (a, b) = run_handshake(&alice, &bob);
+
+        assert_eq!(a.remote.peer_id, bob.peer_id);
+        assert_eq!(a.remote.name, "bob");
+        assert_eq!(b.remote.peer_id, alice.peer_id);
+        assert!(a.remote.keypair().is_none());
+    }
+
+    #[test]
+    fn test_session_keys_match_per_direction() {
+        let alice = NodeIdentity::generate("alice");
+        let bob = NodeIdentity::generate("bob");
+        let (mut a, mut b) = run_handshake(&alice, &bob);
+
+        let frame = a.send.encrypt(b"to bob", &[]).unwrap();
+        assert_eq!(b.recv.decrypt(&frame, &[]).unwrap(), b"to bob");
+
+        let frame = b.send.encrypt(b"to alice", &[]).unwrap();
+        assert_eq!(a.recv.decrypt(&frame, &[]).unwrap(), b"to alice");
+    }
+
+    #[test]
+    fn test_replayed_frame_is_rejected() {
+        let alice = NodeIdentity::generate("alice");
+        let bob = NodeIdentity::generate("bob");
+        let (mut a, mut b) = run_handshake(&alice, &bob);
+
+        let frame = a.send.encrypt(b"once", &[]).unwrap();
+        assert!(b.recv.decrypt(&frame, &[]).is_ok());
+        assert!(b.recv.decrypt(&frame, &[]).is_err());
+    }
+
+    #[test]
+    fn test_tampered_response_is_rejected() {
+        let alice = NodeIdentity::generate("alice");
+        let bob = NodeIdentity::generate("bob");
+
+        let (init, m1) = Initiator::start(&alice).unwrap();
+        let (_resp, m2) = Responder::respond(&bob, m1).unwrap();
+        let HandshakeMessage::Respond { ephemeral, sealed } = m2 else {
+            panic!("expected respond");
+        };
+        let mut bytes = hex::decode(&sealed).unwrap();
+        bytes[0] ^= 0xff;
+        let tampered = HandshakeMessage::Respond {
+            ephemeral,
+            sealed: hex::encode(bytes),
+        };
+        assert!(matches!(
+            init.finish(tampered),
+            Err(NetworkError::Handshake(_))
+        ));
+    }
+
+    #[test]
+    fn test_responder_cannot_claim_foreign_peer_id() {
+        let alice = NodeIdentity::generate("alice");
+        let bob = NodeIdentity::generate("bob");
+        let mallory = NodeIdentity::generate("mallory");
+
+        // Mallory answers with Bob's peer ID but her own key.
+        let mut spoof = mallory.clone();
+        spoof.peer_id = bob.peer_id.clone();
+
+        let (init, m1) = Initiator::start(&alice).unwrap();
+        let (_resp, m2) = Responder::respond(&spoof, m1).unwrap();
+        assert!(init.finish(m2).is_err());
+    }
+
+    #[test]
+    fn test_identity_without_key_cannot_handshake() {
+        let local = NodeIdentity::generate("local");
+        let keyless = NodeIdentity::remote(
+            local.peer_id.clone(),
+            "keyless",
+            "0.1.0",
+            local.public_key.clone(),
+        );
+        assert!(Initiator::start(&keyless).is_err());
+    }
+
+    #[test]
+    fn test_wrong_protocol_is_rejected() {
+        let bob = NodeIdentity::generate("bob");
+        let msg = HandshakeMessage::Initiate {
+            protocol: "plaintext".into(),
+            ephemeral: hex::encode([9u8; 32]),
+        };
+        assert!(Responder::respond(&bob, msg).is_err());
+    }
+}
@@ -1,9 +1,15 @@
 //! Peer identity — unique node identification and persistence.
+//!
+//! Every node owns an ed25519 key pair. Its [`PeerId`] is derived from the
+//! public key, so a peer can prove it owns an ID by signing with the matching
+//! secret key (see [`crate::handshake`]).
 
 use std::fmt;
 use std::path::Path;
 
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 /// A unique identifier for a peer node.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -20,6 +26,18 @@ impl PeerId {
         Self(s.into())
     }
 
+    /// Derive the peer ID for an ed25519 public key: the hex-encoded first
+    /// 16 bytes of its SHA-256 digest.
+    pub fn from_public_key(key: &VerifyingKey) -> Self {
+        let digest = Sha256::digest(key.as_bytes());
+        Self(hex::encode(&digest[..16]))
+    }
+
+    /// Whether this ID was derived from `key`.
+    pub fn matches_key(&self, key: &VerifyingKey) -> bool {
+        *self == Self::from_public_key(key)
+    }
+
     /// Return the inner string representation.
     pub fn as_str(&self) -> &str {
         &self.0
@@ -32,10 +50,95 @@ impl fmt::Display for PeerId {
     }
 }
 
+/// An ed25519 node key pair.
+#[derive(Clone)]
+pub struct NodeKeypair {
+    signing: SigningKey,
+}
+
+impl NodeKeypair {
+    /// Generate a fresh key pair using the OS CSPRNG.
+    pub fn generate() -> Self {
+        let seed: [u8; 32] = rand::random();
+        Self::from_secret_bytes(&seed)
+    }
+
+    /// Rebuild a key pair from its 32-byte secret seed.
+    pub fn from_secret_bytes(bytes: &[u8; 32]) -> Self {
+        Self {
+            signing: SigningKey::from_bytes(bytes),
+        }
+    }
+
+    /// The 32-byte secret seed.
+    pub fn secret_bytes(&self) -> [u8; 32] {
+        self.signing.to_bytes()
+    }
+
+    /// The public half of the key pair.
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.signing.verifying_key()
+    }
+
+    /// Hex-encoded public key.
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.verifying_key().as_bytes())
+    }
+
+    /// Sign `message`, returning the hex-encoded signature.
+    pub fn sign_hex(&self, message: &[u8]) -> String {
+        hex::encode(self.signing.sign(message).to_bytes())
+    }
+
+    /// The X25519 secret corresponding to this key pair, for opening data
+    /// sealed to [`x25519_public_key`] of our public key.
+    pub fn x25519_secret(&self) -> x25519_dalek::StaticSecret {
+        x25519_dalek::StaticSecret::from(self.signing.to_scalar_bytes())
+    }
+}
+
+impl fmt::Debug for NodeKeypair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NodeKeypair")
+            .field("public_key", &self.public_key_hex())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Decode a hex-encoded ed25519 public key.
+pub fn decode_public_key(public_key: &str) -> Option<VerifyingKey> {
+    let bytes: [u8; 32] = hex::decode(public_key).ok()?.try_into().ok()?;
+    VerifyingKey::from_bytes(&bytes).ok()
+}
+
+/// The X25519 public key birationally equivalent to a hex-encoded ed25519
+/// public key, so data can be sealed to a peer knowing only its identity.
+pub fn x25519_public_key(public_key: &str) -> Option<x25519_dalek::PublicKey> {
+    let key = decode_public_key(public_key)?;
+    Some(x25519_dalek::PublicKey::from(
+        key.to_montgomery().to_bytes(),
+    ))
+}
+
+/// Verify a hex-encoded ed25519 `signature` over `message` against a
+/// hex-encoded public key.
+pub fn verify_signature(public_key: &str, message: &[u8], signature: &str) -> bool {
+    let Some(key) = decode_public_key(public_key) else {
+        return false;
+    };
+    let Some(bytes) = hex::decode(signature)
+        .ok()
+        .and_then(|b| <[u8; 64]>::try_from(b).ok())
+    else {
+        return false;
+    };
+    key.verify(message, &Signature::from_bytes(&bytes)).is_ok()
+}
+
 /// The full identity of a Hive node on the network.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeIdentity {
-    /// Unique peer identifier.
+    /// Unique peer identifier, derived from `public_key`.
     pub peer_id: PeerId,
     /// Human-readable name for the node (e.g. hostname).
     pub name: String,
@@ -43,13 +146,33 @@ pub struct NodeIdentity {
     pub version: String,
     /// Capabilities advertised by this node.
     pub capabilities: Vec<String>,
+    /// Hex-encoded ed25519 public key.
+    #[serde(default)]
+    pub public_key: String,
+    /// Signing key. Only present on the local node's own identity.
+    #[serde(skip)]
+    keypair: Option<NodeKeypair>,
+}
+
+/// On-disk form of the local identity, including the secret key.
+#[derive(Serialize, Deserialize)]
+struct StoredIdentity {
+    #[serde(flatten)]
+    identity: NodeIdentity,
+    #[serde(default)]
+    secret_key: Option<String>,
 }
 
 impl NodeIdentity {
-    /// Create a new identity with a fresh PeerId.
+    /// Create a new identity with a fresh key pair and derived PeerId.
     pub fn generate(name: impl Into<String>) -> Self {
+        Self::from_keypair(NodeKeypair::generate(), name)
+    }
+
+    /// Create an identity for an existing key pair.
+    pub fn from_keypair(keypair: NodeKeypair, name: impl Into<String>) -> Self {
         Self {
-            peer_id: PeerId::generate(),
+            peer_id: PeerId::from_public_key(&keypair.verifying_key()),
             name: name.into(),
             version: env!("CARGO_PKG_VERSION").to_string(),
             capabilities: vec![
@@ -57,27 +180,86 @@ impl NodeIdentity {
                 "channel_sync".to_string(),
                 "fleet_learn".to_string(),
             ],
+            public_key: keypair.public_key_hex(),
+            keypair: Some(keypair),
         }
     }
 
-    /// Save the identity to a JSON file.
+    /// Describe a remote peer. Remote identities carry no signing key.
+    pub fn remote(
+        peer_id: PeerId,
+        name: impl Into<String>,
+        version: impl Into<String>,
+        public_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            peer_id,
+            name: name.into(),
+            version: version.into(),
+            capabilities: Vec::new(),
+            public_key: public_key.into(),
+            keypair: None,
+        }
+    }
+
+    /// Replace the advertised capabilities.
+    pub fn with_capabilities(mut self, capabilities: Vec<String>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// The signing key, if this is the local node's identity.
+    pub fn keypair(&self) -> Option<&NodeKeypair> {
+        self.keypair.as_ref()
+    }
+
+    /// Whether `peer_id` is derived from a valid `public_key`.
+    pub fn is_self_certifying(&self) -> bool {
+        decode_public_key(&self.public_key).is_some_and(|key| self.peer_id.matches_key(&key))
+    }
+
+    /// Save the identity, including its secret key, to a JSON file readable
+    /// only by the current user.
     pub fn save_to_file(&self, path: &Path) -> Result<(), String> {
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)
                 .map_err(|e| format!("Failed to create directory: {e}"))?;
         }
-        let json = serde_json::to_string_pretty(self)
+        let stored = StoredIdentity {
+            identity: self.clone(),
+            secret_key: self.keypair.as_ref().map(|k| hex::encode(k.secret_bytes())),
+        };
+        let json = serde_json::to_string_pretty(&stored)
             .map_err(|e| format!("Failed to serialize identity: {e}"))?;
-        std::fs::write(path, json).map_err(|e| format!("Failed to write identity file: {e}"))
+        std::fs::write(path, json).map_err(|e| format!("Failed to write identity file: {e}"))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| format!("Failed to restrict identity file: {e}"))?;
+        }
+        Ok(())
     }
 
     /// Load an identity from a JSON file, or generate a new one if the file
     /// does not exist.
+    ///
+    /// Files written before node keys existed (random UUID peer IDs) are
+    /// replaced by a key-derived identity that keeps the stored name.
     pub fn load_or_generate(path: &Path, name: impl Into<String>) -> Self {
+        let mut name = name.into();
         if path.exists() {
             match std::fs::read_to_string(path) {
-                Ok(data) => match serde_json::from_str::<NodeIdentity>(&data) {
-                    Ok(identity) => return identity,
+                Ok(data) => match serde_json::from_str::<StoredIdentity>(&data) {
+                    Ok(stored) => match Self::from_stored(stored) {
+                        Ok(identity) => return identity,
+                        Err(stored_name) => {
+                            tracing::warn!(
+                                "Identity file has no valid node key, generating a new one"
+                            );
+                            name = stored_name;
+                        }
+                    },
                     Err(e) => {
                         tracing::warn!("Corrupt identity file, generating new: {e}");
                     }
@@ -94,6 +276,32 @@ impl NodeIdentity {
         }
         identity
     }
+
+    /// Restore the key pair from a stored identity. Returns the stored name
+    /// when the key is missing or does not match the stored peer ID.
+    fn from_stored(stored: StoredIdentity) -> Result<Self, String> {
+        let keypair = stored
+            .secret_key
+            .as_deref()
+            .and_then(|s| hex::decode(s).ok())
+            .and_then(|b| <[u8; 32]>::try_from(b).ok())
+            .map(|b| NodeKeypair::from_secret_bytes(&b));
+        match keypair {
+            Some(keypair)
+                if stored
+                    .identity
+                    .peer_id
+                    .matches_key(&keypair.verifying_key()) =>
+            {
+                Ok(Self {
+                    public_key: keypair.public_key_hex(),
+                    keypair: Some(keypair),
+                    ..stored.identity
+                })
+            }
+            _ => Err(stored.identity.name),
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -112,6 +320,18 @@ mod tests {
         assert!(!a.as_str().is_empty());
     }
 
+    #[test]
+    fn x25519_keys_agree_with_identity_keys() {
+        let a = NodeKeypair::generate();
+        let b = NodeKeypair::generate();
+        let a_public = x25519_public_key(&a.public_key_hex()).unwrap();
+        let b_public = x25519_public_key(&b.public_key_hex()).unwrap();
+        assert_eq!(
+            a.x25519_secret().diffie_hellman(&b_public).as_bytes(),
+            b.x25519_secret().diffie_hellman(&a_public).as_bytes()
+        );
+    }
+
     #[test]
     fn test_peer_id_from_string() {
         let id = PeerId::from_string("test-peer-123");
@@ -125,6 +345,41 @@ mod tests {
         assert_eq!(identity.name, "test-node");
         assert!(!identity.peer_id.as_str().is_empty());
         assert!(!identity.capabilities.is_empty());
+        assert!(identity.is_self_certifying());
+        assert!(identity.keypair().is_some());
+    }
+
+    #[test]
+    fn test_signature_roundtrip() {
+        let keypair = NodeKeypair::generate();
+        let sig = keypair.sign_hex(b"hello");
+        assert!(verify_signature(&keypair.public_key_hex(), b"hello", &sig));
+        assert!(!verify_signature(&keypair.public_key_hex(), b"hellO", &sig));
+
+        let other = NodeKeypair::generate();
+        assert!(!verify_signature(&other.public_key_hex(), b"hello", &sig));
+        assert!(!verify_signature("not-hex", b"hello", &sig));
+    }
+
+    #[test]
+    fn test_remote_identity_has_no_keypair() {
+        let local = NodeIdentity::generate("local");
+        let remote = NodeIdentity::remote(
+            local.peer_id.clone(),
+            "remote",
+            "0.1.0",
+            local.public_key.clone(),
+        );
+        assert!(remote.keypair().is_none());
+        assert!(remote.is_self_certifying());
+
+        let forged = NodeIdentity::remote(
+            PeerId::from_string("someone-else"),
+            "forged",
+            "0.1.0",
+            local.public_key.clone(),
+        );
+        assert!(!forged.is_self_certifying());
     }
 
     #[test]
@@ -135,6 +390,10 @@ mod tests {
         assert_eq!(deserialized.peer_id, identity.peer_id);
         assert_eq!(deserialized.name, identity.name);
         assert_eq!(deserialized.capabilities, identity.capabilities);
+        assert_eq!(deserialized.public_key, identity.public_key);
+        // The secret key is never part of the public serialization.
+        assert!(deserialized.keypair().is_none());
+        assert!(!json.contains("secret"));
     }
 
     #[test]
@@ -150,6 +409,31 @@ mod tests {
         let loaded = NodeIdentity::load_or_generate(&path, "fallback-name");
         assert_eq!(loaded.peer_id, original.peer_id);
         assert_eq!(loaded.name, "persist-test");
+        assert_eq!(
+            loaded.keypair().unwrap().secret_bytes(),
+            original.keypair().unwrap().secret_bytes()
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_legacy_identity_file_is_rekeyed() {
+        let dir = std::env::temp_dir().join("hive_network_test_legacy_identity");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("identity.json");
+        let legacy = r#"{"peer_id":"0b5c0e4e-legacy","name":"old-node","version":"0.1.0","capabilities":[]}"#;
+        std::fs::write(&path, legacy).unwrap();
+
+        let loaded = NodeIdentity::load_or_generate(&path, "fallback-name");
+        assert_eq!(loaded.name, "old-node");
+        assert!(loaded.is_self_certifying());
+        assert_ne!(loaded.peer_id.as_str(), "0b5c0e4e-legacy");
+
+        let reloaded = NodeIdentity::load_or_generate(&path, "fallback-name");
+        assert_eq!(reloaded.peer_id, loaded.peer_id);
 
         let _ = std::fs::remove_dir_all(&dir);
     }
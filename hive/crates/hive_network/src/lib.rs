@@ -6,11 +6,22 @@
 //!
 //! # Architecture
 //!
+//! - **Identity**: ed25519 node keys; peer IDs are derived from public keys.
 //! - **Transport**: WebSocket-based (via `tokio-tungstenite`) bidirectional
-//!   connections between peers.
-//! - **Discovery**: UDP broadcast on the LAN for automatic peer discovery.
+//!   connections between peers, secured by a Noise-style `XX` handshake and
+//!   encrypted with per-direction AES-256-GCM keys.
+//! - **Trust**: trust-on-first-use or allow-list key pinning, with per-peer
+//!   permissions for which message kinds a peer may send.
+//! - **Discovery**: signed UDP broadcast announcements on the LAN, plus
+//!   configured bootstrap peers for federating across subnets.
+//! - **Gossip**: SWIM-style membership and failure detection piggybacked on
+//!   heartbeats, covering peers beyond direct connections.
+//! - **Relay**: sealed envelopes forwarded hop by hop (TTL, de-duplication)
+//!   so peers behind NAT can talk through a mutually reachable node.
 //! - **Protocol**: Envelope-based typed messaging with JSON payloads.
 //! - **Routing**: Handler-based dispatch for incoming messages.
+//! - **Scheduling**: signed capability documents (models, hardware, load,
+//!   cost rates) ride on heartbeats and drive capacity-aware peer selection.
 //!
 //! # Quick start
 //!
@@ -29,21 +40,33 @@
 //! # }
 //! ```
 
+pub mod capability;
 pub mod config;
 pub mod discovery;
 pub mod error;
+pub mod gossip;
+pub mod handshake;
 pub mod identity;
 pub mod message;
 pub mod node;
 pub mod peer;
+pub mod relay;
 pub mod router;
 pub mod transport;
+pub mod trust;
 
 // ── Re-exports for convenience ──────────────────────────────────────────
 
+pub use capability::{
+    CapabilityDocument, GpuProfile, HardwareProfile, LoadReport, ModelOffer, ProviderOffer,
+    ScheduledPeer, TaskRequirements,
+};
 pub use config::NetworkConfig;
 pub use error::NetworkError;
-pub use identity::{NodeIdentity, PeerId};
+pub use gossip::{Member, MemberRecord, MemberStatus, Membership};
+pub use identity::{NodeIdentity, NodeKeypair, PeerId};
 pub use message::{Envelope, MessageKind};
 pub use node::{HiveNode, HiveNodeHandle};
 pub use peer::{PeerInfo, PeerRegistry, PeerState};
+pub use relay::RelayFrame;
+pub use trust::{PeerPermissions, SharedTrustStore, TrustPolicy, TrustStore, TrustedPeer};
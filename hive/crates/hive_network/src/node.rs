@@ -3,32 +3,52 @@
 //! [`HiveNode`] is the primary public API for hive_network. It manages:
 //! - WebSocket server (accept incoming connections)
 //! - Outbound connections (connect to known peers)
+//! - Peer authentication (handshake + trust store, per-peer permissions)
 //! - LAN discovery (find peers on the local network)
-//! - Heartbeat loop (keep connections alive)
+//! - Bootstrap peers (dial configured addresses, redial when they drop)
+//! - Gossip membership (SWIM-style failure detection beyond direct peers)
+//! - Multi-hop relay (reach peers without a direct connection)
+//! - Heartbeat loop (keep connections alive, measure latency, and publish
+//!   this node's signed capability document)
 //! - Message routing (dispatch envelopes to handlers)
+//! - Peer scheduling (pick the best peer for a task from capability documents)
 
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use chrono::Utc;
 use tokio::sync::{RwLock, broadcast, mpsc};
 use tracing::{debug, error, info, warn};
 
+use crate::capability::{
+    CapabilityDocument, HardwareProfile, LoadReport, ProviderOffer, ScheduledPeer,
+    TaskRequirements, rank_peers,
+};
 use crate::config::NetworkConfig;
 use crate::discovery::{Announcement, DiscoveredPeer, DiscoveryConfig, DiscoveryService};
 use crate::error::NetworkError;
-use crate::identity::{NodeIdentity, PeerId};
+use crate::gossip::{Member, MemberRecord, Membership};
+use crate::identity::{NodeIdentity, NodeKeypair, PeerId};
 use crate::message::{Envelope, MessageKind};
 use crate::peer::{PeerInfo, PeerRegistry, PeerState};
+use crate::relay::{RelayFrame, SeenFrames};
 use crate::router::{MessageRouter, goodbye_handler, heartbeat_handler, hello_handler};
-use crate::transport::{self, PeerConnection, TransportEvent};
+use crate::transport::{self, PeerConnection, SecurityContext, TransportEvent};
+use crate::trust::{SharedTrustStore, TrustStore};
 
-/// Read-only handle for querying live network state from outside the runtime
-/// that owns the running [`HiveNode`].
+/// Handle for querying live network state, registering handlers and sending
+/// to peers from outside the runtime that owns the running [`HiveNode`].
 #[derive(Clone)]
 pub struct HiveNodeHandle {
     identity: NodeIdentity,
     peers: Arc<RwLock<PeerRegistry>>,
+    trust: SharedTrustStore,
+    router: Arc<RwLock<MessageRouter>>,
+    connections: Arc<RwLock<HashMap<String, PeerConnection>>>,
+    capabilities: Arc<RwLock<CapabilityDocument>>,
+    capability_max_age_secs: i64,
+    federation: Federation,
 }
 
 impl HiveNodeHandle {
@@ -65,6 +85,90 @@ impl HiveNodeHandle {
         let registry = self.peers.blocking_read();
         registry.list_connected().into_iter().cloned().collect()
     }
+
+    /// The trust store deciding which peers may connect and what they may send.
+    pub fn trust_store(&self) -> SharedTrustStore {
+        Arc::clone(&self.trust)
+    }
+
+    /// Register a handler for incoming messages of a specific kind.
+    pub async fn on_message(&self, kind: MessageKind, handler: crate::router::MessageHandler) {
+        self.router.write().await.register(kind, handler);
+    }
+
+    /// Whether an authenticated connection to `peer_id` is currently open.
+    pub async fn is_connected(&self, peer_id: &PeerId) -> bool {
+        self.connections
+            .read()
+            .await
+            .values()
+            .any(|conn| conn.peer_id() == peer_id)
+    }
+
+    /// Send an envelope to a connected peer, looked up by its authenticated ID.
+    pub async fn send_to_peer(
+        &self,
+        peer_id: &PeerId,
+        envelope: &Envelope,
+    ) -> Result<(), NetworkError> {
+        send_to_peer(&self.connections, peer_id, envelope).await
+    }
+
+    /// Send an envelope to any live federation member: directly if connected,
+    /// otherwise sealed and forwarded through relaying peers.
+    pub async fn send_routed(
+        &self,
+        peer_id: &PeerId,
+        envelope: &Envelope,
+    ) -> Result<(), NetworkError> {
+        self.federation
+            .send_routed(
+                &self.connections,
+                peer_id,
+                None,
+                envelope,
+                MessageKind::RelayRequest,
+            )
+            .await
+    }
+
+    /// Get a snapshot of gossip membership, including peers reachable only
+    /// through relays.
+    pub async fn members(&self) -> Vec<Member> {
+        self.federation.members().await
+    }
+
+    /// Replace the providers and models this node advertises.
+    pub async fn set_providers(&self, providers: Vec<ProviderOffer>) {
+        self.capabilities.write().await.providers = providers;
+    }
+
+    /// Replace the hardware profile this node advertises.
+    pub async fn set_hardware(&self, hardware: HardwareProfile) {
+        self.capabilities.write().await.hardware = hardware;
+    }
+
+    /// Report this node's current load; published with the next heartbeat.
+    pub async fn set_load(&self, load: LoadReport) {
+        self.capabilities.write().await.load = load;
+    }
+
+    /// The capability document this node currently advertises.
+    pub async fn local_capabilities(&self) -> CapabilityDocument {
+        self.capabilities.read().await.clone()
+    }
+
+    /// Rank connected peers able to run a task meeting `requirements`, best
+    /// first, by latency and load.
+    pub async fn rank_peers(&self, requirements: &TaskRequirements) -> Vec<ScheduledPeer> {
+        let peers = self.connected_peers().await;
+        rank_peers(&peers, requirements, self.capability_max_age_secs)
+    }
+
+    /// Pick the best connected peer for a task meeting `requirements`.
+    pub async fn schedule(&self, requirements: &TaskRequirements) -> Option<ScheduledPeer> {
+        self.rank_peers(requirements).await.into_iter().next()
+    }
 }
 
 /// The top-level Hive network node.
@@ -78,10 +182,18 @@ pub struct HiveNode {
     config: NetworkConfig,
     /// Registry of known peers.
     peers: Arc<RwLock<PeerRegistry>>,
+    /// Pinned peer keys and per-peer permissions.
+    trust: SharedTrustStore,
     /// Message router for dispatching incoming envelopes.
     router: Arc<RwLock<MessageRouter>>,
     /// Active WebSocket connections keyed by peer address.
     connections: Arc<RwLock<HashMap<String, PeerConnection>>>,
+    /// The capability document published with each heartbeat.
+    capabilities: Arc<RwLock<CapabilityDocument>>,
+    /// Gossip membership and relay state.
+    federation: Federation,
+    /// Transport event sender of the running node, for manual connections.
+    event_tx: Option<mpsc::Sender<TransportEvent>>,
     /// Shutdown signal broadcaster.
     shutdown_tx: Option<broadcast::Sender<()>>,
     /// Whether the node is currently running.
@@ -101,12 +213,20 @@ impl HiveNode {
         );
         router.register(MessageKind::Goodbye, goodbye_handler());
 
+        let trust = TrustStore::new(config.trust_policy).shared();
+        let capabilities = CapabilityDocument::new(identity.peer_id.clone());
+        let federation = Federation::new(&identity, &config);
+
         Self {
             identity,
             config,
             peers: Arc::new(RwLock::new(PeerRegistry::new())),
+            trust,
             router: Arc::new(RwLock::new(router)),
             connections: Arc::new(RwLock::new(HashMap::new())),
+            capabilities: Arc::new(RwLock::new(capabilities)),
+            federation,
+            event_tx: None,
             shutdown_tx: None,
             running: false,
         }
@@ -118,11 +238,35 @@ impl HiveNode {
         Self::new(identity, NetworkConfig::default())
     }
 
+    /// Use a (typically persisted) trust store instead of an empty in-memory one.
+    pub fn with_trust_store(mut self, store: TrustStore) -> Self {
+        self.trust = store.shared();
+        self
+    }
+
     /// Create a read-only handle that shares this node's live peer registry.
     pub fn handle(&self) -> HiveNodeHandle {
         HiveNodeHandle {
             identity: self.identity.clone(),
             peers: Arc::clone(&self.peers),
+            trust: Arc::clone(&self.trust),
+            router: Arc::clone(&self.router),
+            connections: Arc::clone(&self.connections),
+            capabilities: Arc::clone(&self.capabilities),
+            capability_max_age_secs: self.capability_max_age_secs(),
+            federation: self.federation.clone(),
+        }
+    }
+
+    /// The trust store deciding which peers may connect and what they may send.
+    pub fn trust_store(&self) -> SharedTrustStore {
+        Arc::clone(&self.trust)
+    }
+
+    fn security(&self) -> SecurityContext {
+        SecurityContext {
+            identity: self.identity.clone(),
+            trust: Arc::clone(&self.trust),
         }
     }
 
@@ -158,17 +302,47 @@ impl HiveNode {
         registry.list_connected().into_iter().cloned().collect()
     }
 
+    /// Get a snapshot of gossip membership, including peers reachable only
+    /// through relays.
+    pub async fn members(&self) -> Vec<Member> {
+        self.federation.members().await
+    }
+
     /// Register a custom message handler for a specific message kind.
     pub async fn on_message(&self, kind: MessageKind, handler: crate::router::MessageHandler) {
         let mut router = self.router.write().await;
         router.register(kind, handler);
     }
 
+    /// How old a peer's capability document may be before the scheduler
+    /// ignores it: three missed heartbeats.
+    fn capability_max_age_secs(&self) -> i64 {
+        (self.config.heartbeat_interval.as_secs() as i64 * 3).max(1)
+    }
+
+    /// Rank connected peers able to run a task meeting `requirements`, best
+    /// first, by latency and load.
+    pub async fn rank_peers(&self, requirements: &TaskRequirements) -> Vec<ScheduledPeer> {
+        self.handle().rank_peers(requirements).await
+    }
+
+    /// Pick the best connected peer for a task meeting `requirements`.
+    pub async fn schedule(&self, requirements: &TaskRequirements) -> Option<ScheduledPeer> {
+        self.handle().schedule(requirements).await
+    }
+
     /// Start the node — begins listening, discovery, and heartbeat loops.
     pub async fn start(&mut self) -> Result<(), NetworkError> {
         if self.running {
             return Ok(());
         }
+        let Some(keypair) = self.identity.keypair().cloned() else {
+            return Err(NetworkError::Handshake(
+                "node identity has no signing key".into(),
+            ));
+        };
+        let security = self.security();
+        self.federation.membership.write().await.renew(&keypair);
 
         let (shutdown_tx, _) = broadcast::channel(8);
         self.shutdown_tx = Some(shutdown_tx.clone());
@@ -176,15 +350,22 @@ impl HiveNode {
         // Channels for transport events and new connections.
         let (event_tx, event_rx) = mpsc::channel(256);
         let (conn_tx, conn_rx) = mpsc::channel(64);
+        self.event_tx = Some(event_tx.clone());
 
         // Start WebSocket server.
         let server_addr = self.config.listen_addr;
         let server_shutdown = shutdown_tx.subscribe();
         let server_event_tx = event_tx.clone();
+        let server_security = security.clone();
         tokio::spawn(async move {
-            if let Err(e) =
-                transport::start_server(server_addr, server_event_tx, conn_tx, server_shutdown)
-                    .await
+            if let Err(e) = transport::start_server(
+                server_addr,
+                server_security,
+                server_event_tx,
+                conn_tx,
+                server_shutdown,
+            )
+            .await
             {
                 error!("WebSocket server error: {e}");
             }
@@ -201,8 +382,12 @@ impl HiveNode {
                     listen_addr: self.config.listen_addr.to_string(),
                     name: self.identity.name.clone(),
                     version: self.identity.version.clone(),
-                    relay_capable: false,
+                    relay_capable: self.config.relay_enabled,
+                    public_key: String::new(),
+                    timestamp: 0,
+                    signature: String::new(),
                 },
+                keypair: keypair.clone(),
             };
             let discovery_shutdown = shutdown_tx.subscribe();
             if let Err(e) =
@@ -214,36 +399,40 @@ impl HiveNode {
             // Spawn task to handle discovered peers.
             let peers = Arc::clone(&self.peers);
             let event_tx_disc = event_tx.clone();
-            let our_peer_id = self.identity.peer_id.clone();
             let connections = Arc::clone(&self.connections);
+            let disc_security = security.clone();
             tokio::spawn(async move {
                 Self::handle_discoveries(
                     discovered_rx,
                     peers,
                     connections,
                     event_tx_disc,
-                    our_peer_id,
+                    disc_security,
                 )
                 .await;
             });
         }
 
-        // Connect to known (bootstrap) peers.
-        for addr in &self.config.known_peers {
-            let addr = addr.clone();
+        // Dial known (bootstrap) peers, and redial any that drop.
+        if !self.config.known_peers.is_empty() {
+            let known_peers = self.config.known_peers.clone();
+            let retry_interval = self.config.bootstrap_retry_interval;
             let event_tx = event_tx.clone();
             let connections = Arc::clone(&self.connections);
+            let peers = Arc::clone(&self.peers);
+            let security = security.clone();
+            let bootstrap_shutdown = shutdown_tx.subscribe();
             tokio::spawn(async move {
-                match transport::connect_to_peer(&addr, event_tx).await {
-                    Ok(conn) => {
-                        let key = addr.clone();
-                        connections.write().await.insert(key, conn);
-                        info!("Connected to bootstrap peer {addr}");
-                    }
-                    Err(e) => {
-                        warn!("Failed to connect to bootstrap peer {addr}: {e}");
-                    }
-                }
+                Self::bootstrap_loop(
+                    known_peers,
+                    retry_interval,
+                    connections,
+                    peers,
+                    event_tx,
+                    security,
+                    bootstrap_shutdown,
+                )
+                .await;
             });
         }
 
@@ -251,6 +440,8 @@ impl HiveNode {
         let router = Arc::clone(&self.router);
         let connections = Arc::clone(&self.connections);
         let peers = Arc::clone(&self.peers);
+        let trust = Arc::clone(&self.trust);
+        let federation = self.federation.clone();
         let event_shutdown = shutdown_tx.subscribe();
         tokio::spawn(async move {
             Self::event_loop(
@@ -259,6 +450,8 @@ impl HiveNode {
                 router,
                 connections,
                 peers,
+                trust,
+                federation,
                 event_shutdown,
             )
             .await;
@@ -267,12 +460,18 @@ impl HiveNode {
         // Spawn heartbeat loop.
         let connections_hb = Arc::clone(&self.connections);
         let our_peer_id_hb = self.identity.peer_id.clone();
+        let capabilities_hb = Arc::clone(&self.capabilities);
+        let keypair_hb = keypair.clone();
+        let federation_hb = self.federation.clone();
         let heartbeat_interval = self.config.heartbeat_interval;
         let hb_shutdown = shutdown_tx.subscribe();
         tokio::spawn(async move {
             Self::heartbeat_loop(
                 connections_hb,
                 our_peer_id_hb,
+                capabilities_hb,
+                keypair_hb,
+                federation_hb,
                 heartbeat_interval,
                 hb_shutdown,
             )
@@ -300,6 +499,8 @@ impl HiveNode {
             let _ = conn.close().await;
         }
         conns.clear();
+        drop(conns);
+        self.event_tx = None;
 
         // Mark all peers as disconnected.
         let mut registry = self.peers.write().await;
@@ -322,9 +523,15 @@ impl HiveNode {
             return Err(NetworkError::NotRunning);
         }
 
-        let (event_tx, _) = mpsc::channel(64);
-        let conn = transport::connect_to_peer(addr, event_tx).await?;
+        let Some(event_tx) = self.event_tx.clone() else {
+            return Err(NetworkError::NotRunning);
+        };
+        let conn = transport::connect_to_peer(addr, &self.security(), None, event_tx).await?;
         let peer_id = conn.peer_id().clone();
+        if let Ok(sock) = addr.trim_start_matches("ws://").parse() {
+            let info = connected_peer_info(conn.remote_identity().clone(), sock);
+            self.peers.write().await.add_peer(info);
+        }
 
         self.connections
             .write()
@@ -349,6 +556,31 @@ impl HiveNode {
         }
     }
 
+    /// Send an envelope to a connected peer, looked up by its authenticated ID.
+    pub async fn send_to_peer(
+        &self,
+        peer_id: &PeerId,
+        envelope: &Envelope,
+    ) -> Result<(), NetworkError> {
+        if !self.running {
+            return Err(NetworkError::NotRunning);
+        }
+        send_to_peer(&self.connections, peer_id, envelope).await
+    }
+
+    /// Send an envelope to any live federation member: directly if connected,
+    /// otherwise sealed and forwarded through relaying peers.
+    pub async fn send_routed(
+        &self,
+        peer_id: &PeerId,
+        envelope: &Envelope,
+    ) -> Result<(), NetworkError> {
+        if !self.running {
+            return Err(NetworkError::NotRunning);
+        }
+        self.handle().send_routed(peer_id, envelope).await
+    }
+
     /// Broadcast an envelope to all connected peers. Returns the number of
     /// peers the message was sent to.
     pub async fn broadcast(&self, envelope: &Envelope) -> Result<usize, NetworkError> {
@@ -383,12 +615,15 @@ impl HiveNode {
     // -----------------------------------------------------------------------
 
     /// Main event loop — processes transport events and incoming connection handles.
+    #[allow(clippy::too_many_arguments)]
     async fn event_loop(
         mut event_rx: mpsc::Receiver<TransportEvent>,
         mut conn_rx: mpsc::Receiver<(SocketAddr, PeerConnection)>,
         router: Arc<RwLock<MessageRouter>>,
         connections: Arc<RwLock<HashMap<String, PeerConnection>>>,
         peers: Arc<RwLock<PeerRegistry>>,
+        trust: SharedTrustStore,
+        federation: Federation,
         mut shutdown: broadcast::Receiver<()>,
     ) {
         loop {
@@ -403,14 +638,67 @@ impl HiveNode {
                 // Transport event (message, connect, disconnect).
                 Some(event) = event_rx.recv() => {
                     match event {
-                        TransportEvent::InboundConnection { addr, peer_id: _ } => {
-                            debug!("Inbound connection from {addr}");
+                        TransportEvent::InboundConnection { addr, identity } => {
+                            debug!("Authenticated inbound connection from {} at {addr}", identity.peer_id);
+                            peers.write().await.add_peer(connected_peer_info(identity, addr));
                         }
-                        TransportEvent::Message { from_addr, envelope } => {
-                            // Update peer last-seen.
+                        TransportEvent::Message { from_addr, peer_id, envelope } => {
+                            // Enforce per-peer permissions before anything else.
+                            if !trust.read().await.permits(&peer_id, &envelope.kind) {
+                                warn!(
+                                    "Dropping {} from {peer_id}: not permitted",
+                                    envelope.kind.dispatch_key()
+                                );
+                                continue;
+                            }
+
+                            // Update peer last-seen, latency and capabilities.
                             {
                                 let mut reg = peers.write().await;
-                                reg.update_last_seen(&envelope.from);
+                                reg.update_last_seen(&peer_id);
+                                match envelope.kind {
+                                    MessageKind::Heartbeat => {
+                                        if let Some(doc) = envelope
+                                            .payload
+                                            .get("capabilities")
+                                            .and_then(|v| serde_json::from_value(v.clone()).ok())
+                                            && !reg.update_capabilities(&peer_id, doc)
+                                        {
+                                            debug!("Ignoring stale or unverified capabilities from {peer_id}");
+                                        }
+                                    }
+                                    MessageKind::HeartbeatAck => {
+                                        if let Some(rtt) = heartbeat_rtt_ms(&envelope.payload) {
+                                            reg.update_latency(&peer_id, rtt);
+                                        }
+                                    }
+                                    _ => {}
+                                }
+                            }
+
+                            // Gossip: the sender is alive, and its heartbeats
+                            // carry its view of the membership.
+                            {
+                                let now = Utc::now();
+                                let mut membership = federation.membership.write().await;
+                                membership.heard_from(&peer_id, now);
+                                if envelope.kind == MessageKind::Heartbeat
+                                    && let Some(updates) = envelope
+                                        .payload
+                                        .get("members")
+                                        .and_then(|v| serde_json::from_value(v.clone()).ok())
+                                    && membership.merge(&peer_id, updates, now)
+                                    && let Some(keypair) = federation.identity.keypair()
+                                {
+                                    debug!("Refuting suspicion gossiped by {peer_id}");
+                                    membership.renew(keypair);
+                                }
+                            }
+
+                            // Relay frames are forwarded or unsealed, not routed.
+                            if matches!(envelope.kind, MessageKind::RelayRequest | MessageKind::RelayResponse) {
+                                federation.handle_frame(&peer_id, envelope, &router, &connections, &trust).await;
+                                continue;
                             }
 
                             // Route the message.
@@ -425,9 +713,17 @@ impl HiveNode {
                                     }
                             }
                         }
-                        TransportEvent::Disconnected { addr } => {
-                            debug!("Peer at {addr} disconnected");
-                            connections.write().await.remove(&addr.to_string());
+                        TransportEvent::Disconnected { addr, peer_id } => {
+                            debug!("Peer {peer_id} at {addr} disconnected");
+                            let still_connected = {
+                                let mut conns = connections.write().await;
+                                conns.remove(&addr.to_string());
+                                conns.values().any(|conn| conn.peer_id() == &peer_id)
+                            };
+                            if !still_connected {
+                                peers.write().await.update_state(&peer_id, PeerState::Disconnected);
+                                federation.membership.write().await.drop_routes_via(&peer_id);
+                            }
                         }
                     }
                 }
@@ -446,13 +742,13 @@ impl HiveNode {
         peers: Arc<RwLock<PeerRegistry>>,
         connections: Arc<RwLock<HashMap<String, PeerConnection>>>,
         event_tx: mpsc::Sender<TransportEvent>,
-        our_peer_id: PeerId,
+        security: SecurityContext,
     ) {
         while let Some(discovered) = discovered_rx.recv().await {
             let ann = &discovered.announcement;
 
             // Skip ourselves.
-            if ann.peer_id == our_peer_id {
+            if ann.peer_id == security.identity.peer_id {
                 continue;
             }
 
@@ -482,17 +778,18 @@ impl HiveNode {
 
             let peer_info = PeerInfo {
                 id: ann.peer_id.clone(),
-                identity: NodeIdentity {
-                    peer_id: ann.peer_id.clone(),
-                    name: ann.name.clone(),
-                    version: ann.version.clone(),
-                    capabilities: Vec::new(),
-                },
+                identity: NodeIdentity::remote(
+                    ann.peer_id.clone(),
+                    ann.name.clone(),
+                    ann.version.clone(),
+                    ann.public_key.clone(),
+                ),
                 addr,
                 state: PeerState::Discovered,
                 connected_at: None,
                 last_seen: chrono::Utc::now(),
                 latency_ms: None,
+                capabilities: None,
             };
 
             {
@@ -501,9 +798,16 @@ impl HiveNode {
                 registry.update_state(&ann.peer_id, PeerState::Connecting);
             }
 
-            // Attempt to connect.
+            // Attempt to connect; the handshake must prove the announced identity.
             let addr_str = ann.listen_addr.clone();
-            match transport::connect_to_peer(&addr_str, event_tx.clone()).await {
+            match transport::connect_to_peer(
+                &addr_str,
+                &security,
+                Some(&ann.peer_id),
+                event_tx.clone(),
+            )
+            .await
+            {
                 Ok(conn) => {
                     connections.write().await.insert(addr_str.clone(), conn);
                     let mut registry = peers.write().await;
@@ -522,34 +826,107 @@ impl HiveNode {
         }
     }
 
-    /// Heartbeat loop — pings all connected peers at the configured interval.
+    /// Bootstrap loop — dials every configured peer that has no open
+    /// connection, then again every `retry_interval`.
+    async fn bootstrap_loop(
+        known_peers: Vec<String>,
+        retry_interval: std::time::Duration,
+        connections: Arc<RwLock<HashMap<String, PeerConnection>>>,
+        peers: Arc<RwLock<PeerRegistry>>,
+        event_tx: mpsc::Sender<TransportEvent>,
+        security: SecurityContext,
+        mut shutdown: broadcast::Receiver<()>,
+    ) {
+        let (connections, peers, security) = (&connections, &peers, &security);
+        loop {
+            let pending: Vec<&String> = {
+                let conns = connections.read().await;
+                known_peers
+                    .iter()
+                    .filter(|addr| !conns.contains_key(*addr))
+                    .collect()
+            };
+            futures::future::join_all(pending.into_iter().map(|addr| {
+                let event_tx = event_tx.clone();
+                async move {
+                    match transport::connect_to_peer(addr, security, None, event_tx).await {
+                        Ok(conn) => {
+                            if let Ok(sock) = addr.trim_start_matches("ws://").parse() {
+                                let info =
+                                    connected_peer_info(conn.remote_identity().clone(), sock);
+                                peers.write().await.add_peer(info);
+                            }
+                            connections.write().await.insert(addr.clone(), conn);
+                            info!("Connected to bootstrap peer {addr}");
+                        }
+                        Err(e) => {
+                            warn!("Failed to connect to bootstrap peer {addr}: {e}");
+                        }
+                    }
+                }
+            }))
+            .await;
+
+            tokio::select! {
+                _ = tokio::time::sleep(retry_interval) => {}
+                _ = shutdown.recv() => {
+                    debug!("Bootstrap loop shutting down");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Heartbeat loop — pings all connected peers at the configured interval,
+    /// re-signing and attaching this node's capability document and gossip
+    /// digest each time, then runs failure detection.
     async fn heartbeat_loop(
         connections: Arc<RwLock<HashMap<String, PeerConnection>>>,
         our_peer_id: PeerId,
+        capabilities: Arc<RwLock<CapabilityDocument>>,
+        keypair: NodeKeypair,
+        federation: Federation,
         interval: std::time::Duration,
         mut shutdown: broadcast::Receiver<()>,
     ) {
         loop {
             tokio::select! {
                 _ = tokio::time::sleep(interval) => {
+                    let doc = {
+                        let mut doc = capabilities.write().await;
+                        doc.sign(&keypair);
+                        doc.clone()
+                    };
+                    let members = federation.membership.read().await.digest();
                     let heartbeat = Envelope::broadcast(
                         our_peer_id.clone(),
                         MessageKind::Heartbeat,
-                        serde_json::json!({"ts": chrono::Utc::now().to_rfc3339()}),
+                        serde_json::json!({
+                            "ts": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                            "capabilities": doc,
+                            "members": members,
+                        }),
                     );
 
-                    let mut conns = connections.write().await;
-                    let mut failed = Vec::new();
+                    {
+                        let mut conns = connections.write().await;
+                        let mut failed = Vec::new();
 
-                    for (addr, conn) in conns.iter_mut() {
-                        if let Err(e) = conn.send(&heartbeat).await {
-                            debug!("Heartbeat to {addr} failed: {e}");
-                            failed.push(addr.clone());
+                        for (addr, conn) in conns.iter_mut() {
+                            if let Err(e) = conn.send(&heartbeat).await {
+                                debug!("Heartbeat to {addr} failed: {e}");
+                                failed.push(addr.clone());
+                            }
+                        }
+
+                        for key in failed {
+                            conns.remove(&key);
                         }
                     }
 
-                    for key in failed {
-                        conns.remove(&key);
+                    let suspects = federation.membership.write().await.sweep(Utc::now(), interval);
+                    for suspect in suspects {
+                        federation.probe_indirect(&connections, &suspect).await;
                     }
                 }
                 _ = shutdown.recv() => {
@@ -561,6 +938,319 @@ impl HiveNode {
     }
 }
 
+/// Send `envelope` over the open connection authenticated as `peer_id`.
+async fn send_to_peer(
+    connections: &RwLock<HashMap<String, PeerConnection>>,
+    peer_id: &PeerId,
+    envelope: &Envelope,
+) -> Result<(), NetworkError> {
+    let mut conns = connections.write().await;
+    let Some((key, conn)) = conns.iter_mut().find(|(_, conn)| conn.peer_id() == peer_id) else {
+        return Err(NetworkError::PeerNotFound(peer_id.to_string()));
+    };
+    if let Err(e) = conn.send(envelope).await {
+        warn!("Send to {peer_id} at {key} failed: {e}");
+        let key = key.clone();
+        conns.remove(&key);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Neighbours asked to probe a suspect on our behalf (SWIM's `ping-req`).
+const INDIRECT_PROBES: usize = 3;
+
+/// Gossip membership and relay state, shared by the node's tasks and handles.
+#[derive(Clone)]
+struct Federation {
+    identity: NodeIdentity,
+    membership: Arc<RwLock<Membership>>,
+    seen: Arc<RwLock<SeenFrames>>,
+    relay_enabled: bool,
+    max_hops: u8,
+}
+
+impl Federation {
+    fn new(identity: &NodeIdentity, config: &NetworkConfig) -> Self {
+        let local = MemberRecord::new(identity, config.advertised_addr(), config.relay_enabled);
+        Self {
+            identity: identity.clone(),
+            membership: Arc::new(RwLock::new(Membership::new(local))),
+            seen: Arc::new(RwLock::new(SeenFrames::new())),
+            relay_enabled: config.relay_enabled,
+            max_hops: config.relay_max_hops,
+        }
+    }
+
+    async fn members(&self) -> Vec<Member> {
+        let membership = self.membership.read().await;
+        membership.list().into_iter().cloned().collect()
+    }
+
+    /// The connected neighbour to hand a frame for `target` to: the target
+    /// itself, or the next hop gossip recorded for it unless that is `exclude`.
+    async fn next_hop(
+        &self,
+        connections: &RwLock<HashMap<String, PeerConnection>>,
+        target: &PeerId,
+        exclude: Option<&PeerId>,
+    ) -> Option<PeerId> {
+        let conns = connections.read().await;
+        let connected = |id: &PeerId| conns.values().any(|conn| conn.peer_id() == id);
+        if connected(target) {
+            return Some(target.clone());
+        }
+        let hop = self.membership.read().await.next_hop(target).cloned()?;
+        (Some(&hop) != exclude && connected(&hop)).then_some(hop)
+    }
+
+    /// Send `envelope` to `target` directly if connected, otherwise sealed in
+    /// a relay frame of `kind`. `target_key` overrides the key gossip knows.
+    async fn send_routed(
+        &self,
+        connections: &RwLock<HashMap<String, PeerConnection>>,
+        target: &PeerId,
+        target_key: Option<&str>,
+        envelope: &Envelope,
+        kind: MessageKind,
+    ) -> Result<(), NetworkError> {
+        let Some(hop) = self.next_hop(connections, target, None).await else {
+            return Err(NetworkError::PeerNotFound(target.to_string()));
+        };
+        if &hop == target {
+            return send_to_peer(connections, target, envelope).await;
+        }
+
+        let target_key = match target_key {
+            Some(key) => key.to_string(),
+            None => self
+                .membership
+                .read()
+                .await
+                .get(target)
+                .map(|m| m.record.public_key.clone())
+                .ok_or_else(|| NetworkError::PeerNotFound(target.to_string()))?,
+        };
+        let frame = RelayFrame::seal(&self.identity, target, &target_key, envelope, self.max_hops)?;
+        self.seen.write().await.insert(&frame.id);
+        let outer = Envelope::new(
+            self.identity.peer_id.clone(),
+            Some(hop.clone()),
+            kind,
+            serde_json::to_value(&frame)?,
+        );
+        send_to_peer(connections, &hop, &outer).await
+    }
+
+    /// Handle a relay frame received from neighbour `from`: deliver it if it
+    /// is for us, otherwise forward it one hop closer to its target.
+    async fn handle_frame(
+        &self,
+        from: &PeerId,
+        envelope: Envelope,
+        router: &RwLock<MessageRouter>,
+        connections: &RwLock<HashMap<String, PeerConnection>>,
+        trust: &SharedTrustStore,
+    ) {
+        let kind = envelope.kind;
+        let mut frame: RelayFrame = match serde_json::from_value(envelope.payload) {
+            Ok(frame) => frame,
+            Err(e) => {
+                debug!("Malformed relay frame from {from}: {e}");
+                return;
+            }
+        };
+        if !frame.verify() {
+            warn!("Dropping relay frame from {from}: expired or badly signed");
+            return;
+        }
+        if !self.seen.write().await.insert(&frame.id) {
+            return;
+        }
+
+        if frame.target == self.identity.peer_id {
+            self.deliver(frame, router, connections, trust).await;
+            return;
+        }
+        if !self.relay_enabled {
+            debug!(
+                "Not relaying frame {} for {}: relaying disabled",
+                frame.id, frame.origin
+            );
+            return;
+        }
+        if frame.ttl == 0 {
+            debug!(
+                "Relay frame {} from {} ran out of hops",
+                frame.id, frame.origin
+            );
+            return;
+        }
+        frame.ttl -= 1;
+
+        let Some(hop) = self.next_hop(connections, &frame.target, Some(from)).await else {
+            debug!("No route to {} for relay frame {}", frame.target, frame.id);
+            return;
+        };
+        let outer = match serde_json::to_value(&frame) {
+            Ok(payload) => Envelope::new(
+                self.identity.peer_id.clone(),
+                Some(hop.clone()),
+                kind,
+                payload,
+            ),
+            Err(e) => {
+                warn!("Failed to encode relay frame {}: {e}", frame.id);
+                return;
+            }
+        };
+        if let Err(e) = send_to_peer(connections, &hop, &outer).await {
+            debug!("Forwarding relay frame {} to {hop} failed: {e}", frame.id);
+        }
+    }
+
+    /// Unseal a frame addressed to us, check the origin against the trust
+    /// store as if it had connected directly, and route the inner envelope.
+    /// Any response travels back the same way.
+    async fn deliver(
+        &self,
+        frame: RelayFrame,
+        router: &RwLock<MessageRouter>,
+        connections: &RwLock<HashMap<String, PeerConnection>>,
+        trust: &SharedTrustStore,
+    ) {
+        let Some(keypair) = self.identity.keypair() else {
+            return;
+        };
+        let inner = match frame.open(keypair) {
+            Ok(inner) => inner,
+            Err(e) => {
+                warn!("Dropping relay frame from {}: {e}", frame.origin);
+                return;
+            }
+        };
+        {
+            let mut store = trust.write().await;
+            if let Err(e) = store.authorize(&frame.origin_identity()) {
+                warn!("Dropping relayed {}: {e}", inner.kind.dispatch_key());
+                return;
+            }
+            if !store.permits(&frame.origin, &inner.kind) {
+                warn!(
+                    "Dropping relayed {} from {}: not permitted",
+                    inner.kind.dispatch_key(),
+                    frame.origin
+                );
+                return;
+            }
+        }
+        self.membership
+            .write()
+            .await
+            .heard_from(&frame.origin, Utc::now());
+
+        let response = router.read().await.dispatch(inner).await;
+        if let Some(response) = response
+            && let Err(e) = self
+                .send_routed(
+                    connections,
+                    &frame.origin,
+                    Some(&frame.origin_key),
+                    &response,
+                    MessageKind::RelayResponse,
+                )
+                .await
+        {
+            debug!("Failed to return relayed response to {}: {e}", frame.origin);
+        }
+    }
+
+    /// Ask up to [`INDIRECT_PROBES`] other neighbours to relay a heartbeat to
+    /// `suspect`. Its relayed ack clears the suspicion.
+    async fn probe_indirect(
+        &self,
+        connections: &RwLock<HashMap<String, PeerConnection>>,
+        suspect: &PeerId,
+    ) {
+        let Some(target_key) = self
+            .membership
+            .read()
+            .await
+            .get(suspect)
+            .map(|m| m.record.public_key.clone())
+        else {
+            return;
+        };
+        let probe = Envelope::new(
+            self.identity.peer_id.clone(),
+            Some(suspect.clone()),
+            MessageKind::Heartbeat,
+            serde_json::json!({
+                "ts": Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            }),
+        );
+        let frame =
+            match RelayFrame::seal(&self.identity, suspect, &target_key, &probe, self.max_hops) {
+                Ok(frame) => frame,
+                Err(e) => {
+                    debug!("Failed to seal probe for {suspect}: {e}");
+                    return;
+                }
+            };
+        self.seen.write().await.insert(&frame.id);
+        let Ok(payload) = serde_json::to_value(&frame) else {
+            return;
+        };
+
+        let mut helpers: Vec<PeerId> = connections
+            .read()
+            .await
+            .values()
+            .map(|conn| conn.peer_id().clone())
+            .filter(|id| id != suspect)
+            .collect();
+        helpers.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        helpers.dedup();
+        debug!(
+            "Probing suspect {suspect} through {} neighbour(s)",
+            helpers.len().min(INDIRECT_PROBES)
+        );
+        for helper in helpers.into_iter().take(INDIRECT_PROBES) {
+            let request = Envelope::new(
+                self.identity.peer_id.clone(),
+                Some(helper.clone()),
+                MessageKind::RelayRequest,
+                payload.clone(),
+            );
+            if let Err(e) = send_to_peer(connections, &helper, &request).await {
+                debug!("Indirect probe via {helper} failed: {e}");
+            }
+        }
+    }
+}
+
+/// Round-trip time of a heartbeat, from the timestamp echoed in its ack.
+fn heartbeat_rtt_ms(payload: &serde_json::Value) -> Option<u64> {
+    let sent = chrono::DateTime::parse_from_rfc3339(payload.get("ts")?.as_str()?).ok()?;
+    let elapsed = chrono::Utc::now().signed_duration_since(sent);
+    u64::try_from(elapsed.num_milliseconds()).ok()
+}
+
+/// Registry entry for a peer that just completed the handshake.
+fn connected_peer_info(identity: NodeIdentity, addr: SocketAddr) -> PeerInfo {
+    let now = chrono::Utc::now();
+    PeerInfo {
+        id: identity.peer_id.clone(),
+        identity,
+        addr,
+        state: PeerState::Connected,
+        connected_at: Some(now),
+        last_seen: now,
+        latency_ms: None,
+        capabilities: None,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -631,6 +1321,27 @@ mod tests {
         node.stop().await;
     }
 
+    #[tokio::test]
+    async fn test_start_requires_signing_key() {
+        let mut config = NetworkConfig::default();
+        config.listen_addr = "127.0.0.1:0".parse().unwrap();
+        config.discovery_enabled = false;
+
+        let local = NodeIdentity::generate("keyless-node");
+        let keyless = NodeIdentity::remote(
+            local.peer_id.clone(),
+            "keyless-node",
+            "0.1.0",
+            local.public_key.clone(),
+        );
+        let mut node = HiveNode::new(keyless, config);
+        assert!(matches!(
+            node.start().await,
+            Err(NetworkError::Handshake(_))
+        ));
+        assert!(!node.is_running());
+    }
+
     #[tokio::test]
     async fn test_send_when_not_running() {
         let node = HiveNode::with_defaults("stopped-node");
@@ -722,6 +1433,7 @@ mod tests {
             connected_at: Some(chrono::Utc::now()),
             last_seen: chrono::Utc::now(),
             latency_ms: Some(12),
+            capabilities: None,
         };
 
         {
@@ -734,4 +1446,214 @@ mod tests {
         assert_eq!(peers[0].identity.name, "peer-a");
         assert_eq!(handle.connected_peers().await.len(), 1);
     }
+
+    #[tokio::test]
+    async fn handle_schedules_peers_by_advertised_capabilities() {
+        let mut config = NetworkConfig::default();
+        config.discovery_enabled = false;
+        let node = HiveNode::new(NodeIdentity::generate("scheduler-node"), config);
+        let handle = node.handle();
+
+        let peer_identity = NodeIdentity::generate("gpu-peer");
+        let mut doc = CapabilityDocument::new(peer_identity.peer_id.clone());
+        doc.providers = vec![ProviderOffer {
+            name: "Ollama".into(),
+            local: true,
+            models: vec![crate::capability::ModelOffer::local("llama3.1:8b")],
+        }];
+        doc.sign(peer_identity.keypair().unwrap());
+
+        {
+            let mut registry = node.peers.write().await;
+            registry.add_peer(PeerInfo {
+                id: peer_identity.peer_id.clone(),
+                identity: peer_identity.clone(),
+                addr: "127.0.0.1:9471".parse().unwrap(),
+                state: PeerState::Connected,
+                connected_at: Some(chrono::Utc::now()),
+                last_seen: chrono::Utc::now(),
+                latency_ms: Some(5),
+                capabilities: None,
+            });
+            assert!(registry.update_capabilities(&peer_identity.peer_id, doc));
+        }
+
+        let picked = handle
+            .schedule(&TaskRequirements {
+                models: vec!["llama3.1:8b".into()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(picked.peer.id, peer_identity.peer_id);
+        assert_eq!(picked.model, "llama3.1:8b");
+
+        let none = handle
+            .schedule(&TaskRequirements {
+                models: vec!["claude-opus".into()],
+                ..Default::default()
+            })
+            .await;
+        assert!(none.is_none());
+
+        handle
+            .set_load(LoadReport {
+                active_tasks: 2,
+                queue_depth: 0,
+                max_concurrent: 4,
+            })
+            .await;
+        assert_eq!(handle.local_capabilities().await.load.active_tasks, 2);
+    }
+
+    #[test]
+    fn heartbeat_rtt_reads_echoed_timestamp() {
+        let sent = chrono::Utc::now() - chrono::Duration::milliseconds(40);
+        let payload = serde_json::json!({
+            "ts": sent.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        });
+        assert!(heartbeat_rtt_ms(&payload).unwrap() >= 40);
+        assert!(heartbeat_rtt_ms(&serde_json::json!({})).is_none());
+    }
+
+    fn free_addr() -> SocketAddr {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    }
+
+    fn federated_config(listen_addr: SocketAddr, known_peers: Vec<String>) -> NetworkConfig {
+        NetworkConfig {
+            listen_addr,
+            discovery_enabled: false,
+            heartbeat_interval: std::time::Duration::from_millis(100),
+            known_peers,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn peers_without_a_direct_link_talk_through_a_relay() {
+        // A and C both dial B but not each other.
+        let addr_b = free_addr();
+        let relay_identity = NodeIdentity::generate("relay-b");
+        let mut relay_trust = TrustStore::new(crate::TrustPolicy::TrustOnFirstUse);
+        relay_trust.default_permissions = crate::PeerPermissions::all();
+        let mut node_b = HiveNode::new(relay_identity, federated_config(addr_b, Vec::new()))
+            .with_trust_store(relay_trust);
+        node_b.start().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        let mut node_a = HiveNode::new(
+            NodeIdentity::generate("node-a"),
+            federated_config(free_addr(), vec![addr_b.to_string()]),
+        );
+        let mut target_trust = TrustStore::new(crate::TrustPolicy::TrustOnFirstUse);
+        target_trust.default_permissions = crate::PeerPermissions::all();
+        let mut node_c = HiveNode::new(
+            NodeIdentity::generate("node-c"),
+            federated_config(free_addr(), vec![addr_b.to_string()]),
+        )
+        .with_trust_store(target_trust);
+
+        let echo: crate::router::MessageHandler = Arc::new(|env: Envelope| {
+            Box::pin(async move {
+                Some(Envelope::new(
+                    env.to.clone().unwrap(),
+                    Some(env.from),
+                    MessageKind::TaskResult,
+                    serde_json::json!({"echo": env.payload}),
+                ))
+            })
+        });
+        node_c.on_message(MessageKind::TaskRequest, echo).await;
+
+        let (result_tx, mut result_rx) = mpsc::channel(1);
+        let on_result: crate::router::MessageHandler = Arc::new(move |env: Envelope| {
+            let result_tx = result_tx.clone();
+            Box::pin(async move {
+                let _ = result_tx.send(env).await;
+                None
+            })
+        });
+        node_a.on_message(MessageKind::TaskResult, on_result).await;
+
+        node_a.start().await.unwrap();
+        node_c.start().await.unwrap();
+
+        // Wait for C's membership record to reach A through B's gossip.
+        let c_id = node_c.peer_id().clone();
+        let mut learned = false;
+        for _ in 0..50 {
+            if node_a
+                .members()
+                .await
+                .iter()
+                .any(|m| m.record.peer_id == c_id)
+            {
+                learned = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert!(learned, "A never learned about C through gossip");
+        assert!(!node_a.handle().is_connected(&c_id).await);
+
+        let request = Envelope::new(
+            node_a.peer_id().clone(),
+            Some(c_id.clone()),
+            MessageKind::TaskRequest,
+            serde_json::json!({"objective": "ping"}),
+        );
+        node_a.send_routed(&c_id, &request).await.unwrap();
+
+        let reply = tokio::time::timeout(std::time::Duration::from_secs(5), result_rx.recv())
+            .await
+            .expect("no relayed reply")
+            .unwrap();
+        assert_eq!(reply.from, c_id);
+        assert_eq!(reply.payload["echo"]["objective"], "ping");
+
+        node_a.stop().await;
+        node_b.stop().await;
+        node_c.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_connected_peer_is_registered_and_pinned() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr_a = listener.local_addr().unwrap();
+        drop(listener);
+
+        let mut config_a = NetworkConfig::default();
+        config_a.listen_addr = addr_a;
+        config_a.discovery_enabled = false;
+        let mut node_a = HiveNode::new(NodeIdentity::generate("node-a"), config_a);
+        node_a.start().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        let mut config_b = NetworkConfig::default();
+        config_b.listen_addr = "127.0.0.1:0".parse().unwrap();
+        config_b.discovery_enabled = false;
+        let mut node_b = HiveNode::new(NodeIdentity::generate("node-b"), config_b);
+        node_b.start().await.unwrap();
+
+        let remote_id = node_b.connect_to(&addr_a.to_string()).await.unwrap();
+        assert_eq!(&remote_id, node_a.peer_id());
+        assert!(
+            node_b
+                .trust_store()
+                .read()
+                .await
+                .get(node_a.peer_id())
+                .is_some()
+        );
+
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let peers_a = node_a.connected_peers().await;
+        assert_eq!(peers_a.len(), 1);
+        assert_eq!(&peers_a[0].id, node_b.peer_id());
+
+        node_a.stop().await;
+        node_b.stop().await;
+    }
 }
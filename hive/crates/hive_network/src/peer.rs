@@ -7,6 +7,7 @@ use std::path::Path;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::capability::CapabilityDocument;
 use crate::identity::{NodeIdentity, PeerId};
 
 /// Connection state of a peer.
@@ -43,6 +44,9 @@ pub struct PeerInfo {
     pub last_seen: DateTime<Utc>,
     /// Round-trip latency in milliseconds (from heartbeat).
     pub latency_ms: Option<u64>,
+    /// Latest verified capability document received from the peer.
+    #[serde(default)]
+    pub capabilities: Option<CapabilityDocument>,
 }
 
 /// Registry of all known peers.
@@ -129,6 +133,29 @@ impl PeerRegistry {
         }
     }
 
+    /// Store a capability document from a peer.
+    ///
+    /// The document must describe the peer, be signed by its pinned identity
+    /// key, and supersede any document already held. Returns whether it was
+    /// accepted.
+    pub fn update_capabilities(&mut self, peer_id: &PeerId, doc: CapabilityDocument) -> bool {
+        let Some(peer) = self.peers.get_mut(peer_id.as_str()) else {
+            return false;
+        };
+        if doc.peer_id != *peer_id || !doc.verify(&peer.identity.public_key) {
+            return false;
+        }
+        if peer
+            .capabilities
+            .as_ref()
+            .is_some_and(|current| !doc.is_newer_than(current))
+        {
+            return false;
+        }
+        peer.capabilities = Some(doc);
+        true
+    }
+
     /// Save the registry to a JSON file.
     pub fn save_to_file(&self, path: &Path) -> Result<(), String> {
         if let Some(parent) = path.parent() {
@@ -212,6 +239,7 @@ mod tests {
             connected_at: None,
             last_seen: Utc::now(),
             latency_ms: None,
+            capabilities: None,
         }
     }
 
@@ -297,6 +325,34 @@ mod tests {
         assert_eq!(registry.get_peer(&peer_id).unwrap().latency_ms, Some(42));
     }
 
+    #[test]
+    fn test_registry_capabilities_must_verify_and_advance() {
+        let mut registry = PeerRegistry::new();
+        let peer = make_peer("iota", 9478);
+        let peer_id = peer.id.clone();
+        let keypair = peer.identity.keypair().unwrap().clone();
+        registry.add_peer(peer);
+
+        let mut doc = CapabilityDocument::new(peer_id.clone());
+        doc.sign(&keypair);
+        let first = doc.clone();
+        assert!(registry.update_capabilities(&peer_id, doc.clone()));
+
+        // Replaying the same (or an older) document is ignored.
+        assert!(!registry.update_capabilities(&peer_id, first));
+
+        // A document signed by another key is rejected.
+        let mut forged = CapabilityDocument::new(peer_id.clone());
+        forged.sequence = 10;
+        forged.sign(NodeIdentity::generate("mallory").keypair().unwrap());
+        assert!(!registry.update_capabilities(&peer_id, forged));
+
+        doc.sign(&keypair);
+        assert!(registry.update_capabilities(&peer_id, doc));
+        let stored = registry.get_peer(&peer_id).unwrap();
+        assert_eq!(stored.capabilities.as_ref().unwrap().sequence, 2);
+    }
+
     #[test]
     fn test_registry_save_load() {
         let dir = std::env::temp_dir().join("hive_network_test_registry");
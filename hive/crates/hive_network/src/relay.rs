@@ -0,0 +1,325 @@
+//! Multi-hop relay — envelopes for peers without a direct connection.
+//!
+//! An envelope for a peer we are not connected to travels inside a
+//! [`RelayFrame`], carried hop by hop in `RelayRequest` envelopes (and
+//! `RelayResponse` envelopes for replies). Each relay forwards the frame to the
+//! next hop its [`Membership`](crate::gossip::Membership) table records for the
+//! target, decrementing the TTL and dropping frames it has already seen, so two
+//! nodes behind NAT can talk through any peer both can reach.
+//!
+//! The origin signs every frame and seals the inner envelope to the target's
+//! identity key (X25519 derived from its ed25519 key, AES-256-GCM), so relays
+//! learn who is talking to whom but cannot read or alter what is said.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use chrono::{DateTime, Utc};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::error::NetworkError;
+use crate::identity::{
+    NodeIdentity, NodeKeypair, PeerId, decode_public_key, verify_signature, x25519_public_key,
+};
+use crate::message::Envelope;
+
+/// Domain-separation label prepended to frames before signing.
+pub const RELAY_SIGNING_LABEL: &[u8] = b"hive-relay-v1\n";
+
+/// HKDF info string for the per-frame sealing key.
+const SEAL_INFO: &[u8] = b"hive-relay-seal";
+
+/// Frames older than this are dropped, bounding how long IDs must be
+/// remembered for de-duplication.
+pub const RELAY_MAX_AGE_SECS: i64 = 120;
+
+/// Maximum number of frame IDs remembered for de-duplication.
+const SEEN_CAPACITY: usize = 4096;
+
+/// A sealed envelope on its way to a peer through one or more relays.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayFrame {
+    /// Unique frame ID, used for de-duplication.
+    pub id: String,
+    pub origin: PeerId,
+    /// Hex-encoded ed25519 key of the origin; its peer ID is derived from it.
+    pub origin_key: String,
+    pub origin_name: String,
+    pub target: PeerId,
+    pub issued_at: DateTime<Utc>,
+    /// Hops the frame may still take. Decremented by each relay, so it is
+    /// not covered by the signature.
+    pub ttl: u8,
+    /// Hex-encoded ephemeral X25519 key the inner envelope is sealed with.
+    pub ephemeral: String,
+    /// Hex-encoded AES-256-GCM ciphertext of the inner envelope.
+    pub sealed: String,
+    /// Hex-encoded signature by the origin.
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl RelayFrame {
+    /// Seal `envelope` for `target`, whose hex-encoded public key is
+    /// `target_key`, and sign the frame as `origin`.
+    pub fn seal(
+        origin: &NodeIdentity,
+        target: &PeerId,
+        target_key: &str,
+        envelope: &Envelope,
+        ttl: u8,
+    ) -> Result<Self, NetworkError> {
+        let keypair = origin
+            .keypair()
+            .ok_or_else(|| NetworkError::Handshake("node identity has no signing key".into()))?;
+        let recipient = x25519_public_key(target_key)
+            .ok_or_else(|| NetworkError::PeerNotFound(format!("{target}: invalid public key")))?;
+
+        let ephemeral = StaticSecret::from(rand::random::<[u8; 32]>());
+        let ephemeral_public = PublicKey::from(&ephemeral);
+        let mut frame = Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            origin: origin.peer_id.clone(),
+            origin_key: origin.public_key.clone(),
+            origin_name: origin.name.clone(),
+            target: target.clone(),
+            issued_at: Utc::now(),
+            ttl,
+            ephemeral: hex::encode(ephemeral_public.as_bytes()),
+            sealed: String::new(),
+            signature: String::new(),
+        };
+
+        let shared = ephemeral.diffie_hellman(&recipient);
+        let cipher = frame.cipher(shared.as_bytes());
+        let plaintext = serde_json::to_vec(envelope)?;
+        let sealed = cipher
+            .encrypt(
+                Nonce::from_slice(&[0u8; 12]),
+                Payload {
+                    msg: &plaintext,
+                    aad: &frame.aad(),
+                },
+            )
+            .map_err(|_| NetworkError::Transport("Relay sealing failed".into()))?;
+        frame.sealed = hex::encode(sealed);
+        frame.sign(keypair);
+        Ok(frame)
+    }
+
+    /// The bytes covered by the signature: the frame with the TTL and
+    /// signature cleared, prefixed with [`RELAY_SIGNING_LABEL`].
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let unsigned = Self {
+            ttl: 0,
+            signature: String::new(),
+            ..self.clone()
+        };
+        let mut bytes = RELAY_SIGNING_LABEL.to_vec();
+        bytes.extend(serde_json::to_vec(&unsigned).unwrap_or_default());
+        bytes
+    }
+
+    fn sign(&mut self, keypair: &NodeKeypair) {
+        self.signature = keypair.sign_hex(&self.signing_bytes());
+    }
+
+    /// Whether the frame is recent and signed by the key its origin ID is
+    /// derived from. Checked by every hop before forwarding.
+    pub fn verify(&self) -> bool {
+        let age = Utc::now()
+            .signed_duration_since(self.issued_at)
+            .num_seconds();
+        (-RELAY_MAX_AGE_SECS..=RELAY_MAX_AGE_SECS).contains(&age)
+            && decode_public_key(&self.origin_key).is_some_and(|key| self.origin.matches_key(&key))
+            && verify_signature(&self.origin_key, &self.signing_bytes(), &self.signature)
+    }
+
+    /// Decrypt the inner envelope with the target's key. The envelope must
+    /// claim to come from the frame's origin.
+    pub fn open(&self, keypair: &NodeKeypair) -> Result<Envelope, NetworkError> {
+        let malformed = || NetworkError::Transport("Malformed relay frame".into());
+        let ephemeral: [u8; 32] = hex::decode(&self.ephemeral)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(malformed)?;
+        let sealed = hex::decode(&self.sealed).map_err(|_| malformed())?;
+
+        let shared = keypair
+            .x25519_secret()
+            .diffie_hellman(&PublicKey::from(ephemeral));
+        if !shared.was_contributory() {
+            return Err(malformed());
+        }
+        let plaintext = self
+            .cipher(shared.as_bytes())
+            .decrypt(
+                Nonce::from_slice(&[0u8; 12]),
+                Payload {
+                    msg: &sealed,
+                    aad: &self.aad(),
+                },
+            )
+            .map_err(|_| NetworkError::Transport("Relay frame failed to decrypt".into()))?;
+
+        let envelope: Envelope = serde_json::from_slice(&plaintext)?;
+        if envelope.from != self.origin {
+            return Err(NetworkError::Untrusted(format!(
+                "relayed envelope from {} inside a frame from {}",
+                envelope.from, self.origin
+            )));
+        }
+        Ok(envelope)
+    }
+
+    /// The origin as a remote identity, for the trust store.
+    pub fn origin_identity(&self) -> NodeIdentity {
+        NodeIdentity::remote(
+            self.origin.clone(),
+            self.origin_name.clone(),
+            "unknown",
+            self.origin_key.clone(),
+        )
+    }
+
+    /// A fresh key per frame (the ephemeral key is never reused), so a fixed
+    /// nonce is safe.
+    fn cipher(&self, shared: &[u8; 32]) -> Aes256Gcm {
+        let mut key = [0u8; 32];
+        Hkdf::<Sha256>::new(Some(self.id.as_bytes()), shared)
+            .expand(SEAL_INFO, &mut key)
+            .expect("32 bytes is within HKDF-SHA256 limit");
+        Aes256Gcm::new_from_slice(&key).expect("32-byte key is valid for AES-256-GCM")
+    }
+
+    /// Binds the ciphertext to the frame's routing header.
+    fn aad(&self) -> Vec<u8> {
+        [
+            self.id.as_bytes(),
+            self.origin.as_str().as_bytes(),
+            self.target.as_str().as_bytes(),
+        ]
+        .join(&b'\n')
+    }
+}
+
+/// Recently seen frame IDs, so frames arriving over several paths are
+/// handled once.
+#[derive(Debug)]
+pub struct SeenFrames {
+    seen: HashMap<String, Instant>,
+    max_age: Duration,
+}
+
+impl SeenFrames {
+    pub fn new() -> Self {
+        Self {
+            seen: HashMap::new(),
+            max_age: Duration::from_secs(2 * RELAY_MAX_AGE_SECS as u64),
+        }
+    }
+
+    /// Record `id`, returning `false` if it was already seen.
+    pub fn insert(&mut self, id: &str) -> bool {
+        if self.seen.contains_key(id) {
+            return false;
+        }
+        if self.seen.len() >= SEEN_CAPACITY {
+            let max_age = self.max_age;
+            self.seen.retain(|_, at| at.elapsed() < max_age);
+            if self.seen.len() >= SEEN_CAPACITY
+                && let Some(oldest) = self
+                    .seen
+                    .iter()
+                    .min_by_key(|(_, at)| **at)
+                    .map(|(id, _)| id.clone())
+            {
+                self.seen.remove(&oldest);
+            }
+        }
+        self.seen.insert(id.to_string(), Instant::now());
+        true
+    }
+}
+
+impl Default for SeenFrames {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::MessageKind;
+
+    fn frame_between(origin: &NodeIdentity, target: &NodeIdentity) -> (Envelope, RelayFrame) {
+        let envelope = Envelope::new(
+            origin.peer_id.clone(),
+            Some(target.peer_id.clone()),
+            MessageKind::TaskRequest,
+            serde_json::json!({"objective": "build"}),
+        );
+        let frame =
+            RelayFrame::seal(origin, &target.peer_id, &target.public_key, &envelope, 4).unwrap();
+        (envelope, frame)
+    }
+
+    #[test]
+    fn target_opens_what_origin_sealed() {
+        let origin = NodeIdentity::generate("origin");
+        let target = NodeIdentity::generate("target");
+        let (envelope, frame) = frame_between(&origin, &target);
+
+        assert!(frame.verify());
+        let opened = frame.open(target.keypair().unwrap()).unwrap();
+        assert_eq!(opened.id, envelope.id);
+        assert_eq!(opened.payload, envelope.payload);
+    }
+
+    #[test]
+    fn relays_cannot_open_frames() {
+        let origin = NodeIdentity::generate("origin");
+        let target = NodeIdentity::generate("target");
+        let relay = NodeIdentity::generate("relay");
+        let (_, frame) = frame_between(&origin, &target);
+
+        assert!(frame.open(relay.keypair().unwrap()).is_err());
+    }
+
+    #[test]
+    fn ttl_is_mutable_but_routing_header_is_signed() {
+        let origin = NodeIdentity::generate("origin");
+        let target = NodeIdentity::generate("target");
+        let (_, frame) = frame_between(&origin, &target);
+
+        let mut forwarded = frame.clone();
+        forwarded.ttl -= 1;
+        assert!(forwarded.verify());
+
+        let mut redirected = frame.clone();
+        redirected.target = NodeIdentity::generate("other").peer_id;
+        assert!(!redirected.verify());
+
+        let mut stale = frame;
+        stale.issued_at = Utc::now() - chrono::Duration::seconds(RELAY_MAX_AGE_SECS + 1);
+        assert!(!stale.verify());
+    }
+
+    #[test]
+    fn seen_frames_deduplicate() {
+        let mut seen = SeenFrames::new();
+        assert!(seen.insert("frame-1"));
+        assert!(!seen.insert("frame-1"));
+        assert!(seen.insert("frame-2"));
+    }
+}
@@ -95,16 +95,18 @@ pub fn hello_handler(our_peer_id: crate::identity::PeerId) -> MessageHandler {
     })
 }
 
-/// Create a handler that responds to Heartbeat with HeartbeatAck.
+/// Create a handler that responds to Heartbeat with HeartbeatAck, echoing the
+/// heartbeat's timestamp so the sender can measure round-trip latency.
 pub fn heartbeat_handler(our_peer_id: crate::identity::PeerId) -> MessageHandler {
     Arc::new(move |envelope: Envelope| {
         let peer_id = our_peer_id.clone();
         Box::pin(async move {
+            let ts = envelope.payload.get("ts").cloned();
             Some(Envelope::new(
                 peer_id,
                 Some(envelope.from),
                 MessageKind::HeartbeatAck,
-                serde_json::json!({}),
+                serde_json::json!({ "ts": ts }),
             ))
         })
     })
@@ -217,10 +219,11 @@ mod tests {
         let our_id = PeerId::from_string("our-node");
         let handler = heartbeat_handler(our_id);
 
-        let envelope = make_envelope(MessageKind::Heartbeat);
-        let response = handler(envelope).await;
-        assert!(response.is_some());
-        assert_eq!(response.unwrap().kind, MessageKind::HeartbeatAck);
+        let mut envelope = make_envelope(MessageKind::Heartbeat);
+        envelope.payload = serde_json::json!({"ts": "2026-01-01T00:00:00Z"});
+        let response = handler(envelope).await.unwrap();
+        assert_eq!(response.kind, MessageKind::HeartbeatAck);
+        assert_eq!(response.payload["ts"], "2026-01-01T00:00:00Z");
     }
 
     #[tokio::test]
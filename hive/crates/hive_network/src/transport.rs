@@ -1,23 +1,32 @@
 //! WebSocket transport — server and client connections.
 //!
 //! Provides the low-level WebSocket plumbing for peer-to-peer communication.
-//! The server accepts incoming connections and forwards received envelopes
-//! into an mpsc channel. The client connects to a remote peer and returns a
-//! [`PeerConnection`] handle.
+//! Every connection first runs the [`handshake`](crate::handshake) and is
+//! checked against the [`TrustStore`](crate::trust::TrustStore); only then
+//! are envelopes exchanged, as encrypted binary frames. The server accepts
+//! incoming connections and forwards received envelopes into an mpsc channel.
+//! The client connects to a remote peer and returns a [`PeerConnection`]
+//! handle.
 
 use std::net::SocketAddr;
+use std::time::Duration;
 
-use futures::stream::SplitSink;
-use futures::{SinkExt, StreamExt};
+use futures::stream::{SplitSink, SplitStream};
+use futures::{Sink, SinkExt, Stream, StreamExt};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::mpsc;
-use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, accept_async, connect_async};
 use tracing::{debug, error, info, warn};
 
 use crate::error::NetworkError;
-use crate::identity::PeerId;
+use crate::handshake::{CipherState, HandshakeMessage, Initiator, Responder, SecureSession};
+use crate::identity::{NodeIdentity, PeerId};
 use crate::message::Envelope;
+use crate::trust::SharedTrustStore;
+
+/// How long a peer has to complete the handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Type alias for the write half of a server-side WebSocket.
 type ServerWsSink = SplitSink<WebSocketStream<TcpStream>, Message>;
@@ -25,14 +34,24 @@ type ServerWsSink = SplitSink<WebSocketStream<TcpStream>, Message>;
 /// Type alias for the write half of a client-side WebSocket.
 type ClientWsSink = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
 
-/// A handle to an active WebSocket connection with a peer.
+/// The local identity and trust store used to secure every connection.
+#[derive(Clone)]
+pub struct SecurityContext {
+    /// Our identity; must carry a signing key.
+    pub identity: NodeIdentity,
+    /// Decides which authenticated peers may connect.
+    pub trust: SharedTrustStore,
+}
+
+/// A handle to an active, authenticated WebSocket connection with a peer.
 ///
 /// Wraps the write-half of the WebSocket stream, providing a simple
 /// `send(envelope)` API. The read-half is consumed by a background task
 /// that forwards incoming envelopes to the node's central channel.
 pub struct PeerConnection {
-    peer_id: PeerId,
+    remote: NodeIdentity,
     sink: PeerSink,
+    cipher: CipherState,
 }
 
 /// The write side can be either a server-accepted or client-initiated socket.
@@ -43,33 +62,41 @@ enum PeerSink {
 
 impl PeerConnection {
     /// Create a connection wrapping a server-accepted WebSocket sink.
-    pub fn from_server(peer_id: PeerId, sink: ServerWsSink) -> Self {
+    fn from_server(remote: NodeIdentity, sink: ServerWsSink, cipher: CipherState) -> Self {
         Self {
-            peer_id,
+            remote,
             sink: PeerSink::Server(sink),
+            cipher,
         }
     }
 
     /// Create a connection wrapping a client-initiated WebSocket sink.
-    pub fn from_client(peer_id: PeerId, sink: ClientWsSink) -> Self {
+    fn from_client(remote: NodeIdentity, sink: ClientWsSink, cipher: CipherState) -> Self {
         Self {
-            peer_id,
+            remote,
             sink: PeerSink::Client(sink),
+            cipher,
         }
     }
 
-    /// The peer ID this connection is associated with.
+    /// The authenticated peer ID this connection is associated with.
     pub fn peer_id(&self) -> &PeerId {
-        &self.peer_id
+        &self.remote.peer_id
     }
 
-    /// Send an envelope over the WebSocket connection.
+    /// The identity the peer proved during the handshake.
+    pub fn remote_identity(&self) -> &NodeIdentity {
+        &self.remote
+    }
+
+    /// Encrypt and send an envelope over the WebSocket connection.
     pub async fn send(&mut self, envelope: &Envelope) -> Result<(), NetworkError> {
         let json = envelope
             .to_json()
             .map_err(|e| NetworkError::Transport(format!("Serialize error: {e}")))?;
+        let frame = self.cipher.encrypt(json.as_bytes(), &[])?;
 
-        let msg = Message::Text(json.into());
+        let msg = Message::Binary(frame.into());
         match &mut self.sink {
             PeerSink::Server(sink) => sink
                 .send(msg)
@@ -101,28 +128,33 @@ impl PeerConnection {
 /// An incoming event from the transport layer.
 #[derive(Debug)]
 pub enum TransportEvent {
-    /// A new inbound connection was accepted from the given address.
-    InboundConnection { addr: SocketAddr, peer_id: PeerId },
-    /// An envelope was received from a peer.
+    /// A new inbound connection completed the handshake and was authorized.
+    InboundConnection {
+        addr: SocketAddr,
+        identity: NodeIdentity,
+    },
+    /// An envelope was received from an authenticated peer.
     Message {
         from_addr: SocketAddr,
+        peer_id: PeerId,
         envelope: Envelope,
     },
     /// A peer disconnected.
-    Disconnected { addr: SocketAddr },
+    Disconnected { addr: SocketAddr, peer_id: PeerId },
 }
 
 /// Start the WebSocket server on the given address.
 ///
-/// Accepted connections spawn a read-loop task that forwards received
-/// envelopes (and connection/disconnection events) into the provided
-/// `event_tx` channel. The returned `JoinHandle` can be used to await
-/// or abort the server.
+/// Accepted connections must complete the handshake and pass the trust
+/// store before anything else happens; they then spawn a read-loop task that
+/// forwards received envelopes (and connection/disconnection events) into
+/// the provided `event_tx` channel.
 ///
 /// Server-side `PeerConnection` handles (write sinks) are sent through
 /// `conn_tx` so the node can track and write to them.
 pub async fn start_server(
     addr: SocketAddr,
+    security: SecurityContext,
     event_tx: mpsc::Sender<TransportEvent>,
     conn_tx: mpsc::Sender<(SocketAddr, PeerConnection)>,
     mut shutdown: tokio::sync::broadcast::Receiver<()>,
@@ -137,64 +169,44 @@ pub async fn start_server(
                     Ok((stream, peer_addr)) => {
                         let event_tx = event_tx.clone();
                         let conn_tx = conn_tx.clone();
+                        let security = security.clone();
                         tokio::spawn(async move {
-                            match accept_async(stream).await {
-                                Ok(ws_stream) => {
-                                    let (sink, mut stream) = ws_stream.split();
-                                    let temp_peer_id = PeerId::from_string(format!("pending-{peer_addr}"));
-
-                                    // Send the connection handle to the node.
-                                    let conn = PeerConnection::from_server(
-                                        temp_peer_id.clone(),
-                                        sink,
-                                    );
-                                    let _ = conn_tx.send((peer_addr, conn)).await;
-
-                                    let _ = event_tx
-                                        .send(TransportEvent::InboundConnection {
-                                            addr: peer_addr,
-                                            peer_id: temp_peer_id,
-                                        })
-                                        .await;
-
-                                    // Read loop.
-                                    while let Some(msg) = stream.next().await {
-                                        match msg {
-                                            Ok(Message::Text(text)) => {
-                                                match Envelope::from_json(&text) {
-                                                    Ok(envelope) => {
-                                                        let _ = event_tx
-                                                            .send(TransportEvent::Message {
-                                                                from_addr: peer_addr,
-                                                                envelope,
-                                                            })
-                                                            .await;
-                                                    }
-                                                    Err(e) => {
-                                                        warn!("Bad envelope from {peer_addr}: {e}");
-                                                    }
-                                                }
-                                            }
-                                            Ok(Message::Close(_)) => {
-                                                debug!("Peer {peer_addr} sent close");
-                                                break;
-                                            }
-                                            Ok(_) => {} // Ignore binary/ping/pong
-                                            Err(e) => {
-                                                debug!("Read error from {peer_addr}: {e}");
-                                                break;
-                                            }
-                                        }
-                                    }
-
-                                    let _ = event_tx
-                                        .send(TransportEvent::Disconnected { addr: peer_addr })
-                                        .await;
-                                }
+                            let mut ws_stream = match accept_async(stream).await {
+                                Ok(ws_stream) => ws_stream,
                                 Err(e) => {
                                     error!("WebSocket accept failed for {peer_addr}: {e}");
+                                    return;
+                                }
+                            };
+
+                            let session = match accept_handshake(&mut ws_stream, &security).await {
+                                Ok(session) => session,
+                                Err(e) => {
+                                    warn!("Rejected connection from {peer_addr}: {e}");
+                                    let _ = ws_stream.close(None).await;
+                                    return;
                                 }
-                            }
+                            };
+
+                            let (sink, stream) = ws_stream.split();
+                            let identity = session.remote.clone();
+                            let conn = PeerConnection::from_server(
+                                session.remote,
+                                sink,
+                                session.send,
+                            );
+
+                            // Send the connection handle to the node.
+                            let _ = conn_tx.send((peer_addr, conn)).await;
+                            let peer_id = identity.peer_id.clone();
+                            let _ = event_tx
+                                .send(TransportEvent::InboundConnection {
+                                    addr: peer_addr,
+                                    identity,
+                                })
+                                .await;
+
+                            read_loop(stream, peer_addr, peer_id, session.recv, event_tx).await;
                         });
                     }
                     Err(e) => {
@@ -214,10 +226,14 @@ pub async fn start_server(
 
 /// Connect to a remote peer as a client.
 ///
-/// Returns a `PeerConnection` (write handle) and spawns a read-loop task
-/// that forwards incoming envelopes to `event_tx`.
+/// Runs the handshake, refuses the peer if it is not trusted or (when
+/// `expected_peer` is given) if it proves a different identity, then returns
+/// a `PeerConnection` (write handle) and spawns a read-loop task that
+/// forwards incoming envelopes to `event_tx`.
 pub async fn connect_to_peer(
     addr: &str,
+    security: &SecurityContext,
+    expected_peer: Option<&PeerId>,
     event_tx: mpsc::Sender<TransportEvent>,
 ) -> Result<PeerConnection, NetworkError> {
     let url = if addr.starts_with("ws://") || addr.starts_with("wss://") {
@@ -226,30 +242,72 @@ pub async fn connect_to_peer(
         format!("ws://{addr}")
     };
 
-    let (ws_stream, _) = connect_async(&url)
+    let (mut ws_stream, _) = connect_async(&url)
         .await
         .map_err(|e| NetworkError::Transport(format!("Connect to {addr} failed: {e}")))?;
 
+    let session = match initiate_handshake(&mut ws_stream, security, expected_peer).await {
+        Ok(session) => session,
+        Err(e) => {
+            let _ = ws_stream.close(None).await;
+            return Err(e);
+        }
+    };
+
     let peer_addr: SocketAddr = addr
         .trim_start_matches("ws://")
         .trim_start_matches("wss://")
         .parse()
         .unwrap_or_else(|_| "0.0.0.0:0".parse().expect("valid fallback address"));
 
-    let (sink, mut stream) = ws_stream.split();
-    let temp_peer_id = PeerId::from_string(format!("outbound-{addr}"));
-
-    let conn = PeerConnection::from_client(temp_peer_id, sink);
+    let (sink, stream) = ws_stream.split();
+    let peer_id = session.remote.peer_id.clone();
+    let conn = PeerConnection::from_client(session.remote, sink, session.send);
 
     // Spawn read loop.
-    tokio::spawn(async move {
-        while let Some(msg) = stream.next().await {
-            match msg {
-                Ok(Message::Text(text)) => match Envelope::from_json(&text) {
+    tokio::spawn(read_loop(
+        stream,
+        peer_addr,
+        peer_id,
+        session.recv,
+        event_tx,
+    ));
+
+    Ok(conn)
+}
+
+/// Decrypt incoming frames and forward envelopes until the peer goes away.
+async fn read_loop<S>(
+    mut stream: SplitStream<WebSocketStream<S>>,
+    peer_addr: SocketAddr,
+    peer_id: PeerId,
+    mut cipher: CipherState,
+    event_tx: mpsc::Sender<TransportEvent>,
+) where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    while let Some(msg) = stream.next().await {
+        match msg {
+            Ok(Message::Binary(frame)) => {
+                let plaintext = match cipher.decrypt(&frame, &[]) {
+                    Ok(plaintext) => plaintext,
+                    Err(e) => {
+                        warn!("Dropping connection to {peer_addr}: {e}");
+                        break;
+                    }
+                };
+                match serde_json::from_slice::<Envelope>(&plaintext) {
+                    Ok(envelope) if envelope.from != peer_id => {
+                        warn!(
+                            "Peer {peer_id} sent envelope claiming to be from {}",
+                            envelope.from
+                        );
+                    }
                     Ok(envelope) => {
                         let _ = event_tx
                             .send(TransportEvent::Message {
                                 from_addr: peer_addr,
+                                peer_id: peer_id.clone(),
                                 envelope,
                             })
                             .await;
@@ -257,25 +315,109 @@ pub async fn connect_to_peer(
                     Err(e) => {
                         warn!("Bad envelope from {peer_addr}: {e}");
                     }
-                },
-                Ok(Message::Close(_)) => {
-                    debug!("Remote {peer_addr} sent close");
-                    break;
-                }
-                Ok(_) => {}
-                Err(e) => {
-                    debug!("Read error from {peer_addr}: {e}");
-                    break;
                 }
             }
+            Ok(Message::Text(_)) => {
+                warn!("Ignoring plaintext frame from {peer_addr}");
+            }
+            Ok(Message::Close(_)) => {
+                debug!("Peer {peer_addr} sent close");
+                break;
+            }
+            Ok(_) => {} // Ignore ping/pong
+            Err(e) => {
+                debug!("Read error from {peer_addr}: {e}");
+                break;
+            }
         }
+    }
 
-        let _ = event_tx
-            .send(TransportEvent::Disconnected { addr: peer_addr })
-            .await;
-    });
+    let _ = event_tx
+        .send(TransportEvent::Disconnected {
+            addr: peer_addr,
+            peer_id,
+        })
+        .await;
+}
 
-    Ok(conn)
+// ---------------------------------------------------------------------------
+// Handshake over WebSocket
+// ---------------------------------------------------------------------------
+
+async fn send_handshake<W>(ws: &mut W, msg: &HandshakeMessage) -> Result<(), NetworkError>
+where
+    W: Sink<Message, Error = WsError> + Unpin,
+{
+    ws.send(Message::Text(msg.to_json()?.into()))
+        .await
+        .map_err(|e| NetworkError::Transport(format!("Send error: {e}")))
+}
+
+async fn recv_handshake<W>(ws: &mut W) -> Result<HandshakeMessage, NetworkError>
+where
+    W: Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    loop {
+        let msg = tokio::time::timeout(HANDSHAKE_TIMEOUT, ws.next())
+            .await
+            .map_err(|_| NetworkError::Timeout(HANDSHAKE_TIMEOUT))?;
+        match msg {
+            Some(Ok(Message::Text(text))) => {
+                return HandshakeMessage::from_json(&text)
+                    .map_err(|e| NetworkError::Handshake(format!("bad handshake message: {e}")));
+            }
+            Some(Ok(Message::Ping(_) | Message::Pong(_))) => continue,
+            Some(Ok(_)) => {
+                return Err(NetworkError::Handshake("unexpected frame".into()));
+            }
+            Some(Err(e)) => return Err(NetworkError::Transport(format!("Read error: {e}"))),
+            None => return Err(NetworkError::Handshake("connection closed".into())),
+        }
+    }
+}
+
+async fn initiate_handshake<W>(
+    ws: &mut W,
+    security: &SecurityContext,
+    expected_peer: Option<&PeerId>,
+) -> Result<SecureSession, NetworkError>
+where
+    W: Sink<Message, Error = WsError> + Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    let (initiator, first) = Initiator::start(&security.identity)?;
+    send_handshake(ws, &first).await?;
+    let reply = recv_handshake(ws).await?;
+    let (last, session) = initiator.finish(reply)?;
+
+    if let Some(expected) = expected_peer
+        && *expected != session.remote.peer_id
+    {
+        return Err(NetworkError::Handshake(format!(
+            "expected peer {expected}, got {}",
+            session.remote.peer_id
+        )));
+    }
+    security.trust.write().await.authorize(&session.remote)?;
+
+    send_handshake(ws, &last).await?;
+    Ok(session)
+}
+
+async fn accept_handshake<W>(
+    ws: &mut W,
+    security: &SecurityContext,
+) -> Result<SecureSession, NetworkError>
+where
+    W: Sink<Message, Error = WsError> + Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    let first = recv_handshake(ws).await?;
+    let (responder, reply) = Responder::respond(&security.identity, first)?;
+    send_handshake(ws, &reply).await?;
+    let last = recv_handshake(ws).await?;
+    let session = responder.finish(last)?;
+
+    security.trust.write().await.authorize(&session.remote)?;
+    Ok(session)
 }
 
 // ---------------------------------------------------------------------------
@@ -285,11 +427,40 @@ pub async fn connect_to_peer(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::MessageKind;
+    use crate::trust::{TrustPolicy, TrustStore};
+
+    fn security(name: &str, policy: TrustPolicy) -> SecurityContext {
+        SecurityContext {
+            identity: NodeIdentity::generate(name),
+            trust: TrustStore::new(policy).shared(),
+        }
+    }
+
+    async fn spawn_server(
+        security: SecurityContext,
+    ) -> (
+        SocketAddr,
+        mpsc::Receiver<TransportEvent>,
+        mpsc::Receiver<(SocketAddr, PeerConnection)>,
+        tokio::sync::broadcast::Sender<()>,
+    ) {
+        let (event_tx, event_rx) = mpsc::channel(32);
+        let (conn_tx, conn_rx) = mpsc::channel(32);
+        let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+
+        // Bind server to a random port.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        drop(listener);
 
-    #[test]
-    fn test_peer_connection_creation() {
-        // Just verify the types compile correctly.
-        let _peer_id = PeerId::from_string("test-peer");
+        tokio::spawn(async move {
+            let _ = start_server(server_addr, security, event_tx, conn_tx, shutdown_rx).await;
+        });
+
+        // Give server time to start.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        (server_addr, event_rx, conn_rx, shutdown_tx)
     }
 
     #[tokio::test]
@@ -305,52 +476,114 @@ mod tests {
 
     #[tokio::test]
     async fn test_connect_to_server() {
-        let (event_tx, mut event_rx) = mpsc::channel(32);
-        let (conn_tx, mut conn_rx) = mpsc::channel(32);
-        let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
-
-        // Bind server to a random port.
-        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let server_addr = listener.local_addr().unwrap();
-        drop(listener);
-
-        // Start server.
-        let event_tx_clone = event_tx.clone();
-        let server_handle = tokio::spawn(async move {
-            let _ = start_server(server_addr, event_tx_clone, conn_tx, shutdown_rx).await;
-        });
-
-        // Give server time to start.
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let server = security("server", TrustPolicy::TrustOnFirstUse);
+        let server_id = server.identity.peer_id.clone();
+        let (server_addr, mut event_rx, mut conn_rx, shutdown_tx) =
+            spawn_server(server.clone()).await;
 
         // Connect as client.
-        let client_result = connect_to_peer(&server_addr.to_string(), event_tx).await;
-        assert!(client_result.is_ok());
-
-        let mut client_conn = client_result.unwrap();
+        let client = security("client", TrustPolicy::TrustOnFirstUse);
+        let (client_event_tx, _client_event_rx) = mpsc::channel(32);
+        let mut client_conn = connect_to_peer(
+            &server_addr.to_string(),
+            &client,
+            Some(&server_id),
+            client_event_tx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(client_conn.peer_id(), &server_id);
+
+        // Server should authenticate the client.
+        let event = tokio::time::timeout(Duration::from_secs(2), event_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        match event {
+            TransportEvent::InboundConnection { identity, .. } => {
+                assert_eq!(identity.peer_id, client.identity.peer_id);
+            }
+            other => panic!("Expected InboundConnection, got {other:?}"),
+        }
+        let (_, server_conn) = tokio::time::timeout(Duration::from_secs(2), conn_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(server_conn.peer_id(), &client.identity.peer_id);
+
+        // Both sides pinned each other on first use.
+        assert!(
+            server
+                .trust
+                .read()
+                .await
+                .get(&client.identity.peer_id)
+                .is_some()
+        );
+        assert!(client.trust.read().await.get(&server_id).is_some());
 
-        // Send a message from client.
+        // Send a message from client; the server decrypts it.
         let envelope = Envelope::new(
-            PeerId::from_string("client-peer"),
+            client.identity.peer_id.clone(),
             None,
-            crate::message::MessageKind::Hello,
+            MessageKind::Hello,
             serde_json::json!({"name": "test-client"}),
         );
         client_conn.send(&envelope).await.unwrap();
 
-        // Server should receive the connection and message.
-        // Wait for inbound connection event.
-        let event = tokio::time::timeout(std::time::Duration::from_secs(2), event_rx.recv()).await;
-        assert!(event.is_ok());
-
-        // Wait for server-side connection handle.
-        let server_conn =
-            tokio::time::timeout(std::time::Duration::from_secs(2), conn_rx.recv()).await;
-        assert!(server_conn.is_ok());
+        let event = tokio::time::timeout(Duration::from_secs(2), event_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        match event {
+            TransportEvent::Message {
+                peer_id, envelope, ..
+            } => {
+                assert_eq!(peer_id, client.identity.peer_id);
+                assert_eq!(envelope.kind, MessageKind::Hello);
+            }
+            other => panic!("Expected Message, got {other:?}"),
+        }
 
         // Cleanup.
         client_conn.close().await.unwrap();
         let _ = shutdown_tx.send(());
-        let _ = server_handle.await;
+    }
+
+    #[tokio::test]
+    async fn test_allow_list_server_rejects_unknown_client() {
+        let server = security("strict-server", TrustPolicy::AllowList);
+        let (server_addr, _event_rx, mut conn_rx, shutdown_tx) = spawn_server(server).await;
+
+        let client = security("stranger", TrustPolicy::TrustOnFirstUse);
+        let (client_event_tx, _client_event_rx) = mpsc::channel(32);
+        // The client side completes, but the server never hands the
+        // connection to the node.
+        let _ = connect_to_peer(&server_addr.to_string(), &client, None, client_event_tx).await;
+        let accepted = tokio::time::timeout(Duration::from_millis(500), conn_rx.recv()).await;
+        assert!(accepted.is_err());
+
+        let _ = shutdown_tx.send(());
+    }
+
+    #[tokio::test]
+    async fn test_connect_rejects_unexpected_peer() {
+        let server = security("server", TrustPolicy::TrustOnFirstUse);
+        let (server_addr, _event_rx, _conn_rx, shutdown_tx) = spawn_server(server).await;
+
+        let client = security("client", TrustPolicy::TrustOnFirstUse);
+        let someone_else = NodeIdentity::generate("someone-else").peer_id;
+        let (client_event_tx, _client_event_rx) = mpsc::channel(32);
+        let result = connect_to_peer(
+            &server_addr.to_string(),
+            &client,
+            Some(&someone_else),
+            client_event_tx,
+        )
+        .await;
+        assert!(matches!(result, Err(NetworkError::Handshake(_))));
+        assert!(client.trust.read().await.list().is_empty());
+
+        let _ = shutdown_tx.send(());
     }
 }
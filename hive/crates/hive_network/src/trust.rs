@@ -0,0 +1,477 @@
+//! Peer trust store — which authenticated peers may connect and which
+//! message kinds each of them may send.
+//!
+//! Peers are identified by key-derived [`PeerId`]s, so once a peer's key is
+//! recorded nobody else can connect under its ID. Unknown peers are either
+//! accepted and remembered on first contact ([`TrustPolicy::TrustOnFirstUse`])
+//! or refused until explicitly allowed ([`TrustPolicy::AllowList`]).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::error::NetworkError;
+use crate::identity::{NodeIdentity, PeerId, decode_public_key};
+use crate::message::MessageKind;
+
+/// A trust store shared between the transport and the node's event loop.
+pub type SharedTrustStore = Arc<RwLock<TrustStore>>;
+
+/// How peers that are not yet in the store are treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustPolicy {
+    /// Accept any peer that completes the handshake and pin its key.
+    #[default]
+    TrustOnFirstUse,
+    /// Only accept peers that were explicitly allowed.
+    AllowList,
+}
+
+/// How a peer entered the trust store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustSource {
+    /// Pinned automatically the first time it connected.
+    FirstUse,
+    /// Added by the user.
+    Explicit,
+}
+
+/// The message kinds a peer may send.
+///
+/// Connection-management kinds (hello, welcome, goodbye, heartbeats) are
+/// always permitted for an authenticated peer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerPermissions {
+    pub allowed: Vec<MessageKind>,
+}
+
+impl PeerPermissions {
+    /// Only connection-management messages.
+    pub fn none() -> Self {
+        Self {
+            allowed: Vec::new(),
+        }
+    }
+
+    /// Passive synchronization and replies, but no remotely triggered work.
+    pub fn sync_only() -> Self {
+        Self {
+            allowed: vec![
+                MessageKind::ChannelSync,
+                MessageKind::FleetLearn,
+                MessageKind::StateSync,
+                MessageKind::TaskResult,
+                MessageKind::RelayResponse,
+            ],
+        }
+    }
+
+    /// Every built-in message kind, including task submission and relaying.
+    pub fn all() -> Self {
+        let mut permissions = Self::sync_only();
+        permissions.allowed.extend([
+            MessageKind::TaskRequest,
+            MessageKind::AgentRelay,
+            MessageKind::RelayRequest,
+        ]);
+        permissions
+    }
+
+    /// Additionally allow `kind`.
+    pub fn allow(mut self, kind: MessageKind) -> Self {
+        if !self.allowed.contains(&kind) {
+            self.allowed.push(kind);
+        }
+        self
+    }
+
+    /// Whether a peer with these permissions may send `kind`.
+    pub fn permits(&self, kind: &MessageKind) -> bool {
+        is_connection_kind(kind) || self.allowed.contains(kind)
+    }
+}
+
+impl Default for PeerPermissions {
+    fn default() -> Self {
+        Self::sync_only()
+    }
+}
+
+/// Whether `kind` is part of connection management.
+fn is_connection_kind(kind: &MessageKind) -> bool {
+    matches!(
+        kind,
+        MessageKind::Hello
+            | MessageKind::Welcome
+            | MessageKind::Goodbye
+            | MessageKind::Heartbeat
+            | MessageKind::HeartbeatAck
+    )
+}
+
+/// A peer whose key is pinned in the trust store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedPeer {
+    pub peer_id: PeerId,
+    /// Hex-encoded ed25519 public key.
+    pub public_key: String,
+    /// Last name the peer announced.
+    pub name: String,
+    pub source: TrustSource,
+    pub permissions: PeerPermissions,
+    /// Blocked peers are refused even though their key is known.
+    #[serde(default)]
+    pub blocked: bool,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Pinned peer keys and their permissions.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrustStore {
+    /// How unknown peers are treated. Comes from [`NetworkConfig`](crate::NetworkConfig).
+    #[serde(skip)]
+    policy: TrustPolicy,
+    /// Permissions granted to peers pinned on first use.
+    #[serde(default)]
+    pub default_permissions: PeerPermissions,
+    peers: HashMap<String, TrustedPeer>,
+    /// File the store is persisted to after every change, if any.
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl TrustStore {
+    /// Create an empty, in-memory store.
+    pub fn new(policy: TrustPolicy) -> Self {
+        Self {
+            policy,
+            default_permissions: PeerPermissions::default(),
+            peers: HashMap::new(),
+            path: None,
+        }
+    }
+
+    /// Wrap the store for sharing with the transport.
+    pub fn shared(self) -> SharedTrustStore {
+        Arc::new(RwLock::new(self))
+    }
+
+    /// The policy for unknown peers.
+    pub fn policy(&self) -> TrustPolicy {
+        self.policy
+    }
+
+    /// Change the policy for unknown peers.
+    pub fn set_policy(&mut self, policy: TrustPolicy) {
+        self.policy = policy;
+    }
+
+    /// Get a pinned peer by ID.
+    pub fn get(&self, peer_id: &PeerId) -> Option<&TrustedPeer> {
+        self.peers.get(peer_id.as_str())
+    }
+
+    /// List all pinned peers.
+    pub fn list(&self) -> Vec<&TrustedPeer> {
+        self.peers.values().collect()
+    }
+
+    /// Explicitly allow a peer. The peer ID must be derived from `public_key`.
+    pub fn allow(
+        &mut self,
+        peer_id: PeerId,
+        public_key: impl Into<String>,
+        name: impl Into<String>,
+        permissions: PeerPermissions,
+    ) -> Result<(), NetworkError> {
+        let public_key = public_key.into();
+        let key = decode_public_key(&public_key)
+            .ok_or_else(|| NetworkError::Untrusted(format!("{peer_id}: invalid public key")))?;
+        if !peer_id.matches_key(&key) {
+            return Err(NetworkError::Untrusted(format!(
+                "{peer_id}: peer ID does not match public key"
+            )));
+        }
+
+        let now = Utc::now();
+        let first_seen = self.get(&peer_id).map_or(now, |p| p.first_seen);
+        self.peers.insert(
+            peer_id.as_str().to_string(),
+            TrustedPeer {
+                peer_id,
+                public_key,
+                name: name.into(),
+                source: TrustSource::Explicit,
+                permissions,
+                blocked: false,
+                first_seen,
+                last_seen: now,
+            },
+        );
+        self.persist();
+        Ok(())
+    }
+
+    /// Replace a pinned peer's permissions. Returns `false` if unknown.
+    pub fn set_permissions(&mut self, peer_id: &PeerId, permissions: PeerPermissions) -> bool {
+        let Some(peer) = self.peers.get_mut(peer_id.as_str()) else {
+            return false;
+        };
+        peer.permissions = permissions;
+        self.persist();
+        true
+    }
+
+    /// Block or unblock a pinned peer. Returns `false` if unknown.
+    pub fn set_blocked(&mut self, peer_id: &PeerId, blocked: bool) -> bool {
+        let Some(peer) = self.peers.get_mut(peer_id.as_str()) else {
+            return false;
+        };
+        peer.blocked = blocked;
+        self.persist();
+        true
+    }
+
+    /// Forget a peer's pinned key.
+    pub fn remove(&mut self, peer_id: &PeerId) -> Option<TrustedPeer> {
+        let removed = self.peers.remove(peer_id.as_str());
+        if removed.is_some() {
+            self.persist();
+        }
+        removed
+    }
+
+    /// Decide whether a peer that completed the handshake may connect,
+    /// pinning its key on first use when the policy allows it.
+    pub fn authorize(&mut self, identity: &NodeIdentity) -> Result<(), NetworkError> {
+        let peer_id = &identity.peer_id;
+        if !identity.is_self_certifying() {
+            return Err(NetworkError::Untrusted(format!(
+                "{peer_id}: peer ID does not match public key"
+            )));
+        }
+
+        if let Some(peer) = self.peers.get_mut(peer_id.as_str()) {
+            if peer.blocked {
+                return Err(NetworkError::Untrusted(format!("{peer_id}: blocked")));
+            }
+            if peer.public_key != identity.public_key {
+                warn!("Peer {peer_id} presented a different key than the pinned one");
+                return Err(NetworkError::Untrusted(format!("{peer_id}: key mismatch")));
+            }
+            peer.name = identity.name.clone();
+            peer.last_seen = Utc::now();
+            return Ok(());
+        }
+
+        match self.policy {
+            TrustPolicy::AllowList => Err(NetworkError::Untrusted(format!(
+                "{peer_id} ('{}') is not on the allow list",
+                identity.name
+            ))),
+            TrustPolicy::TrustOnFirstUse => {
+                info!(
+                    "Pinning key for new peer '{}' ({peer_id}) on first use",
+                    identity.name
+                );
+                let now = Utc::now();
+                self.peers.insert(
+                    peer_id.as_str().to_string(),
+                    TrustedPeer {
+                        peer_id: peer_id.clone(),
+                        public_key: identity.public_key.clone(),
+                        name: identity.name.clone(),
+                        source: TrustSource::FirstUse,
+                        permissions: self.default_permissions.clone(),
+                        blocked: false,
+                        first_seen: now,
+                        last_seen: now,
+                    },
+                );
+                self.persist();
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether `peer_id` may send a message of `kind`.
+    pub fn permits(&self, peer_id: &PeerId, kind: &MessageKind) -> bool {
+        self.get(peer_id)
+            .is_some_and(|p| !p.blocked && p.permissions.permits(kind))
+    }
+
+    /// Save the store to a JSON file.
+    pub fn save_to_file(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory: {e}"))?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize trust store: {e}"))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write trust store file: {e}"))
+    }
+
+    /// Load the store from a JSON file, or return an empty one. Later
+    /// changes are saved back to `path`.
+    pub fn load_or_default(path: &Path, policy: TrustPolicy) -> Self {
+        let mut store = Self::new(policy);
+        if path.exists() {
+            match std::fs::read_to_string(path) {
+                Ok(data) => match serde_json::from_str::<TrustStore>(&data) {
+                    Ok(loaded) => store = loaded,
+                    Err(e) => {
+                        tracing::warn!("Corrupt trust store file: {e}");
+                    }
+                },
+                Err(e) => {
+                    tracing::warn!("Cannot read trust store file: {e}");
+                }
+            }
+        }
+        store.policy = policy;
+        store.path = Some(path.to_path_buf());
+        store
+    }
+
+    fn persist(&self) {
+        if let Some(path) = &self.path
+            && let Err(e) = self.save_to_file(path)
+        {
+            warn!("Failed to persist trust store: {e}");
+        }
+    }
+}
+
+impl Default for TrustStore {
+    fn default() -> Self {
+        Self::new(TrustPolicy::default())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tofu_pins_and_grants_default_permissions() {
+        let mut store = TrustStore::new(TrustPolicy::TrustOnFirstUse);
+        let peer = NodeIdentity::generate("tofu-peer");
+
+        assert!(!store.permits(&peer.peer_id, &MessageKind::Heartbeat));
+        store.authorize(&peer).unwrap();
+
+        let pinned = store.get(&peer.peer_id).unwrap();
+        assert_eq!(pinned.source, TrustSource::FirstUse);
+        assert_eq!(pinned.public_key, peer.public_key);
+        assert!(store.permits(&peer.peer_id, &MessageKind::Heartbeat));
+        assert!(store.permits(&peer.peer_id, &MessageKind::ChannelSync));
+        assert!(!store.permits(&peer.peer_id, &MessageKind::TaskRequest));
+    }
+
+    #[test]
+    fn test_allow_list_rejects_unknown_peers() {
+        let mut store = TrustStore::new(TrustPolicy::AllowList);
+        let stranger = NodeIdentity::generate("stranger");
+        assert!(matches!(
+            store.authorize(&stranger),
+            Err(NetworkError::Untrusted(_))
+        ));
+        assert!(store.get(&stranger.peer_id).is_none());
+
+        let friend = NodeIdentity::generate("friend");
+        store
+            .allow(
+                friend.peer_id.clone(),
+                friend.public_key.clone(),
+                "friend",
+                PeerPermissions::all(),
+            )
+            .unwrap();
+        store.authorize(&friend).unwrap();
+        assert!(store.permits(&friend.peer_id, &MessageKind::TaskRequest));
+    }
+
+    #[test]
+    fn test_rejects_key_not_matching_peer_id() {
+        let mut store = TrustStore::new(TrustPolicy::TrustOnFirstUse);
+        let real = NodeIdentity::generate("real");
+        let imposter = NodeIdentity::generate("imposter");
+
+        let forged = NodeIdentity::remote(
+            real.peer_id.clone(),
+            "forged",
+            "0.1.0",
+            imposter.public_key.clone(),
+        );
+        assert!(store.authorize(&forged).is_err());
+        assert!(
+            store
+                .allow(
+                    real.peer_id.clone(),
+                    imposter.public_key.clone(),
+                    "forged",
+                    PeerPermissions::none(),
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_blocked_peer_is_refused() {
+        let mut store = TrustStore::new(TrustPolicy::TrustOnFirstUse);
+        let peer = NodeIdentity::generate("noisy");
+        store.authorize(&peer).unwrap();
+
+        assert!(store.set_blocked(&peer.peer_id, true));
+        assert!(store.authorize(&peer).is_err());
+        assert!(!store.permits(&peer.peer_id, &MessageKind::Heartbeat));
+    }
+
+    #[test]
+    fn test_set_permissions() {
+        let mut store = TrustStore::new(TrustPolicy::TrustOnFirstUse);
+        let peer = NodeIdentity::generate("worker");
+        store.authorize(&peer).unwrap();
+
+        store.set_permissions(
+            &peer.peer_id,
+            PeerPermissions::none().allow(MessageKind::TaskRequest),
+        );
+        assert!(store.permits(&peer.peer_id, &MessageKind::TaskRequest));
+        assert!(!store.permits(&peer.peer_id, &MessageKind::ChannelSync));
+    }
+
+    #[test]
+    fn test_store_persists_changes() {
+        let dir = std::env::temp_dir().join("hive_network_test_trust");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let path = dir.join("trust.json");
+        let peer = NodeIdentity::generate("persisted");
+        {
+            let mut store = TrustStore::load_or_default(&path, TrustPolicy::TrustOnFirstUse);
+            store.authorize(&peer).unwrap();
+        }
+
+        let loaded = TrustStore::load_or_default(&path, TrustPolicy::AllowList);
+        assert_eq!(loaded.policy(), TrustPolicy::AllowList);
+        assert_eq!(
+            loaded.get(&peer.peer_id).unwrap().public_key,
+            peer.public_key
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,5 +1,7 @@
 use anyhow::Result;
+use hive_remote::auth::WebAuth;
 use hive_remote::daemon::{DaemonConfig, HiveDaemon};
+use hive_remote::pairing::DeviceScope;
 use hive_remote::web_server::build_router;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -20,6 +22,9 @@ async fn main() -> Result<()> {
     std::fs::create_dir_all(&data_dir)?;
     std::fs::create_dir_all(&config_root)?;
 
+    let auth = Arc::new(WebAuth::load(&data_dir.join("remote_devices.json"))?);
+    let invite = auth.create_invite(DeviceScope::Full)?;
+
     let daemon = HiveDaemon::new(DaemonConfig {
         data_dir,
         config_root: Some(config_root),
@@ -27,11 +32,15 @@ async fn main() -> Result<()> {
         web_port,
         shutdown_grace_secs: 30,
     })?;
-    let router = build_router(Arc::new(RwLock::new(daemon)));
+    let router = build_router(Arc::new(RwLock::new(daemon)), auth);
     let addr = format!("127.0.0.1:{web_port}");
     let listener = tokio::net::TcpListener::bind(&addr).await?;
 
     println!("HIVE_REMOTE_PREVIEW_URL=http://{addr}");
+    println!(
+        "HIVE_REMOTE_PAIRING_URL=http://{addr}/#pair={}",
+        invite.url.replace('%', "%25").replace('&', "%26")
+    );
     axum::serve(listener, router).await?;
     Ok(())
 }
@@ -0,0 +1,756 @@
+//! Authentication and authorization for the remote web server.
+//!
+//! Devices pair by completing the X25519 handshake from [`crate::pairing`]
+//! against a one-time [`PairingInvite`] issued by the desktop. Both sides
+//! then hold the same [`SessionCredential`], which native clients present as
+//! a bearer token. Browsers exchange it once for an `HttpOnly` session cookie
+//! and must echo the session's CSRF token on every state-changing request and
+//! WebSocket upgrade.
+//!
+//! Every API route and the WebSocket sit behind [`require_scope`], which
+//! checks the caller's [`DeviceScope`] and rate-limits sensitive routes.
+
+use crate::pairing::{
+    DevicePermissions, DeviceScope, PairedDevice, PairedDeviceStore, PairingKeypair,
+    SessionCredential, SessionKeys, token_hash,
+};
+use crate::qr::{PairingQrPayload, generate_pairing_qr};
+use anyhow::{Result, anyhow};
+use axum::Extension;
+use axum::extract::{Path, Request, State};
+use axum::http::{HeaderMap, HeaderValue, Method, StatusCode, Uri, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Json, Response};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Cookie carrying a browser session ID.
+pub const SESSION_COOKIE: &str = "hive_session";
+
+/// Header browsers echo the session's CSRF token in.
+pub const CSRF_HEADER: &str = "x-hive-csrf";
+
+/// How long a pairing invite can be redeemed.
+const PAIRING_INVITE_TTL_SECS: i64 = 600;
+
+/// How long a browser session lasts before the device must log in again.
+const BROWSER_SESSION_TTL_SECS: i64 = 12 * 60 * 60;
+
+/// Burst and per-minute budget for sensitive routes, per device.
+const SENSITIVE_REQUESTS_PER_MINUTE: u32 = 30;
+
+/// Budget for pairing attempts, shared by all callers.
+const PAIRING_ATTEMPTS_PER_MINUTE: u32 = 10;
+
+/// Rate-limiter key for pairing attempts.
+const PAIRING_RATE_KEY: &str = "pairing";
+
+/// Shared authentication state for the router.
+pub type AuthState = Arc<WebAuth>;
+
+// ---------------------------------------------------------------------------
+// Pairing invites and sessions
+// ---------------------------------------------------------------------------
+
+/// A one-time pairing invite, shown to the user as a QR code or link.
+#[derive(Debug, Clone, Serialize)]
+pub struct PairingInvite {
+    pub session_id: String,
+    /// `hive://pair?...` URL encoding the invite.
+    pub url: String,
+    pub qr_svg: String,
+    pub scope: DeviceScope,
+    pub expires_at: DateTime<Utc>,
+}
+
+struct PendingPairing {
+    keypair: PairingKeypair,
+    scope: DeviceScope,
+    expires_at: DateTime<Utc>,
+}
+
+struct BrowserSession {
+    device_id: String,
+    csrf_token: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// The device behind an authenticated request.
+#[derive(Debug, Clone)]
+pub struct Principal {
+    pub device_id: String,
+    pub name: String,
+    pub scope: DeviceScope,
+    /// Set when the request was authenticated by a browser session cookie.
+    pub browser_session: Option<String>,
+}
+
+/// Why a request was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthError {
+    Unauthenticated,
+    Forbidden(String),
+    RateLimited { retry_after_secs: u64 },
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Unauthenticated => (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+                Json(serde_json::json!({ "error": "Authentication required" })),
+            )
+                .into_response(),
+            Self::Forbidden(reason) => (
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({ "error": reason })),
+            )
+                .into_response(),
+            Self::RateLimited { retry_after_secs } => (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                Json(serde_json::json!({ "error": "Too many requests" })),
+            )
+                .into_response(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Rate limiting
+// ---------------------------------------------------------------------------
+
+/// Token-bucket rate limiter keyed by device (or any other string).
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: HashMap<String, (f64, Instant)>,
+}
+
+impl RateLimiter {
+    /// Allow bursts of `capacity` requests, refilled at `capacity` per
+    /// `period`.
+    pub fn new(capacity: u32, period: Duration) -> Self {
+        Self {
+            capacity: f64::from(capacity),
+            refill_per_sec: f64::from(capacity) / period.as_secs_f64(),
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Take one token for `key`, or return how long until one is available.
+    pub fn check(&mut self, key: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        if self.buckets.len() > 1024 {
+            let (capacity, refill) = (self.capacity, self.refill_per_sec);
+            self.buckets.retain(|_, (tokens, at)| {
+                *tokens + now.duration_since(*at).as_secs_f64() * refill < capacity
+            });
+        }
+
+        let (tokens, at) = self
+            .buckets
+            .entry(key.to_string())
+            .or_insert((self.capacity, now));
+        *tokens = (*tokens + now.duration_since(*at).as_secs_f64() * self.refill_per_sec)
+            .min(self.capacity);
+        *at = now;
+
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - *tokens) / self.refill_per_sec,
+            ))
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// WebAuth
+// ---------------------------------------------------------------------------
+
+/// Paired devices, pending invites, browser sessions and rate limits for the
+/// remote web server.
+pub struct WebAuth {
+    store_path: PathBuf,
+    lan_addr: Option<String>,
+    devices: Mutex<PairedDeviceStore>,
+    invites: Mutex<HashMap<String, PendingPairing>>,
+    sessions: Mutex<HashMap<String, BrowserSession>>,
+    sensitive_limiter: Mutex<RateLimiter>,
+    pairing_limiter: Mutex<RateLimiter>,
+    /// Bumped on every revocation so open WebSockets can re-check their
+    /// device.
+    revisions: watch::Sender<u64>,
+}
+
+impl WebAuth {
+    /// Load paired devices from `store_path`, starting empty if the file
+    /// does not exist.
+    pub fn load(store_path: &FsPath) -> Result<Self> {
+        let devices = PairedDeviceStore::load(store_path)?;
+        Ok(Self {
+            store_path: store_path.to_path_buf(),
+            lan_addr: None,
+            devices: Mutex::new(devices),
+            invites: Mutex::new(HashMap::new()),
+            sessions: Mutex::new(HashMap::new()),
+            sensitive_limiter: Mutex::new(RateLimiter::new(
+                SENSITIVE_REQUESTS_PER_MINUTE,
+                Duration::from_secs(60),
+            )),
+            pairing_limiter: Mutex::new(RateLimiter::new(
+                PAIRING_ATTEMPTS_PER_MINUTE,
+                Duration::from_secs(60),
+            )),
+            revisions: watch::Sender::new(0),
+        })
+    }
+
+    /// Advertise `addr` (e.g. `192.168.1.20:9481`) in pairing invites.
+    pub fn with_lan_addr(mut self, addr: impl Into<String>) -> Self {
+        self.lan_addr = Some(addr.into());
+        self
+    }
+
+    /// Issue a one-time invite granting `scope` to the device that redeems it.
+    pub fn create_invite(&self, scope: DeviceScope) -> Result<PairingInvite> {
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let keypair = PairingKeypair::generate();
+        let payload = PairingQrPayload {
+            session_id: session_id.clone(),
+            public_key_b64: BASE64.encode(keypair.public_key_bytes()),
+            lan_addr: self.lan_addr.clone(),
+            relay_url: None,
+            version: 1,
+        };
+        let invite = PairingInvite {
+            session_id: session_id.clone(),
+            url: payload.to_url(),
+            qr_svg: generate_pairing_qr(&payload)?,
+            scope,
+            expires_at: Utc::now() + ChronoDuration::seconds(PAIRING_INVITE_TTL_SECS),
+        };
+
+        let mut invites = self.invites.lock().unwrap();
+        let now = Utc::now();
+        invites.retain(|_, pending| pending.expires_at > now);
+        invites.insert(
+            session_id,
+            PendingPairing {
+                keypair,
+                scope,
+                expires_at: invite.expires_at,
+            },
+        );
+        Ok(invite)
+    }
+
+    /// Redeem an invite with the device's X25519 public key, registering the
+    /// device under the credential both sides derive.
+    pub fn complete_pairing(
+        &self,
+        session_id: &str,
+        public_key_b64: &str,
+        name: &str,
+    ) -> Result<PairedDevice> {
+        let public_key: [u8; 32] = BASE64
+            .decode(public_key_b64)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| anyhow!("Invalid device public key"))?;
+        let pending = self
+            .invites
+            .lock()
+            .unwrap()
+            .remove(session_id)
+            .filter(|pending| pending.expires_at > Utc::now())
+            .ok_or_else(|| anyhow!("Pairing invite is unknown or expired"))?;
+
+        let shared = pending.keypair.derive_shared_secret(&public_key);
+        let keys = SessionKeys::derive(&shared, session_id);
+        let credential = SessionCredential::from_keys(&keys);
+        let now = Utc::now();
+        let device = PairedDevice {
+            device_id: credential.device_id,
+            name: name.trim().chars().take(64).collect(),
+            public_key_b64: public_key_b64.to_string(),
+            paired_at: now,
+            last_seen: now,
+            permissions: DevicePermissions::default(),
+            scope: pending.scope,
+            token_hash: token_hash(&credential.token),
+            revoked_at: None,
+        };
+
+        let mut devices = self.devices.lock().unwrap();
+        devices.upsert(device.clone());
+        devices.save(&self.store_path)?;
+        Ok(device)
+    }
+
+    /// All paired devices, including revoked ones.
+    pub fn devices(&self) -> Vec<PairedDevice> {
+        self.devices.lock().unwrap().devices.clone()
+    }
+
+    /// Whether any device can currently log in.
+    pub fn has_active_devices(&self) -> bool {
+        self.devices
+            .lock()
+            .unwrap()
+            .devices
+            .iter()
+            .any(|device| !device.is_revoked())
+    }
+
+    /// Whether `device_id` is paired and not revoked.
+    pub fn is_active(&self, device_id: &str) -> bool {
+        self.devices
+            .lock()
+            .unwrap()
+            .find(device_id)
+            .is_some_and(|device| !device.is_revoked())
+    }
+
+    /// Revoke a device, ending its browser sessions and open WebSockets.
+    /// Returns `false` if no active device has that ID.
+    pub fn revoke(&self, device_id: &str) -> Result<bool> {
+        {
+            let mut devices = self.devices.lock().unwrap();
+            if !devices.revoke(device_id) {
+                return Ok(false);
+            }
+            devices.save(&self.store_path)?;
+        }
+        self.sessions
+            .lock()
+            .unwrap()
+            .retain(|_, session| session.device_id != device_id);
+        self.revisions.send_modify(|revision| *revision += 1);
+        Ok(true)
+    }
+
+    /// Notified whenever a device is revoked.
+    pub fn subscribe_revocations(&self) -> watch::Receiver<u64> {
+        self.revisions.subscribe()
+    }
+
+    /// Authenticate a bearer credential.
+    pub fn authenticate_bearer(&self, bearer: &str) -> Option<Principal> {
+        let credential = SessionCredential::parse(bearer)?;
+        let mut devices = self.devices.lock().unwrap();
+        let device = devices
+            .devices
+            .iter_mut()
+            .find(|device| device.device_id == credential.device_id)?;
+        if device.is_revoked() || !device.verify_token(&credential.token) {
+            return None;
+        }
+        device.last_seen = Utc::now();
+        Some(Principal {
+            device_id: device.device_id.clone(),
+            name: device.name.clone(),
+            scope: device.scope,
+            browser_session: None,
+        })
+    }
+
+    /// Authenticate a browser session ID, returning the principal and the
+    /// session's CSRF token.
+    pub fn authenticate_session(&self, session_id: &str) -> Option<(Principal, String)> {
+        let (device_id, csrf_token) = {
+            let mut sessions = self.sessions.lock().unwrap();
+            let now = Utc::now();
+            sessions.retain(|_, session| session.expires_at > now);
+            let session = sessions.get(session_id)?;
+            (session.device_id.clone(), session.csrf_token.clone())
+        };
+
+        let mut devices = self.devices.lock().unwrap();
+        let device = devices
+            .devices
+            .iter_mut()
+            .find(|device| device.device_id == device_id && !device.is_revoked())?;
+        device.last_seen = Utc::now();
+        let principal = Principal {
+            device_id,
+            name: device.name.clone(),
+            scope: device.scope,
+            browser_session: Some(session_id.to_string()),
+        };
+        Some((principal, csrf_token))
+    }
+
+    /// Start a browser session for `principal`, returning its ID and CSRF
+    /// token.
+    pub fn open_session(&self, principal: &Principal) -> (String, String) {
+        let session_id = random_token();
+        let csrf_token = random_token();
+        self.sessions.lock().unwrap().insert(
+            session_id.clone(),
+            BrowserSession {
+                device_id: principal.device_id.clone(),
+                csrf_token: csrf_token.clone(),
+                expires_at: Utc::now() + ChronoDuration::seconds(BROWSER_SESSION_TTL_SECS),
+            },
+        );
+        (session_id, csrf_token)
+    }
+
+    pub fn close_session(&self, session_id: &str) {
+        self.sessions.lock().unwrap().remove(session_id);
+    }
+
+    /// Take one request from `device_id`'s sensitive-route budget.
+    pub fn check_rate(&self, device_id: &str) -> Result<(), AuthError> {
+        self.sensitive_limiter
+            .lock()
+            .unwrap()
+            .check(device_id)
+            .map_err(rate_limited)
+    }
+
+    /// Authenticate a request and check it may act with `required` scope.
+    ///
+    /// Requests authenticated by session cookie must come from the same
+    /// origin and, unless they are safe reads, carry the session's CSRF
+    /// token in [`CSRF_HEADER`] (or a `csrf` query parameter for WebSocket
+    /// upgrades, which cannot set headers from a browser).
+    pub fn authorize(
+        &self,
+        method: &Method,
+        headers: &HeaderMap,
+        uri: &Uri,
+        required: DeviceScope,
+    ) -> Result<Principal, AuthError> {
+        let principal = if let Some(bearer) = bearer_token(headers) {
+            self.authenticate_bearer(bearer)
+                .ok_or(AuthError::Unauthenticated)?
+        } else if let Some(session_id) = cookie(headers, SESSION_COOKIE) {
+            let (principal, csrf_token) = self
+                .authenticate_session(session_id)
+                .ok_or(AuthError::Unauthenticated)?;
+            if !same_origin(headers) {
+                return Err(AuthError::Forbidden("Cross-origin request refused".into()));
+            }
+            if needs_csrf(method, headers) {
+                let presented = headers
+                    .get(CSRF_HEADER)
+                    .and_then(|value| value.to_str().ok())
+                    .or_else(|| query_param(uri, "csrf"));
+                if !presented.is_some_and(|token| constant_time_eq(token, &csrf_token)) {
+                    return Err(AuthError::Forbidden("Missing or invalid CSRF token".into()));
+                }
+            }
+            principal
+        } else {
+            return Err(AuthError::Unauthenticated);
+        };
+
+        if !principal.scope.allows(required) {
+            return Err(AuthError::Forbidden(format!(
+                "Device scope '{}' does not allow this action (requires '{}')",
+                principal.scope.as_str(),
+                required.as_str()
+            )));
+        }
+        Ok(principal)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Middleware
+// ---------------------------------------------------------------------------
+
+/// Middleware state: the scope a group of routes requires.
+#[derive(Clone)]
+pub struct ScopeGuard {
+    auth: AuthState,
+    scope: DeviceScope,
+    rate_limited: bool,
+}
+
+impl ScopeGuard {
+    pub fn new(auth: &AuthState, scope: DeviceScope) -> Self {
+        Self {
+            auth: auth.clone(),
+            scope,
+            rate_limited: false,
+        }
+    }
+
+    /// Also charge each request against the device's sensitive-route budget.
+    pub fn rate_limited(mut self) -> Self {
+        self.rate_limited = true;
+        self
+    }
+}
+
+/// Refuse requests that lack a credential with the guard's scope. On success
+/// the [`Principal`] and [`AuthState`] are added to the request extensions.
+pub async fn require_scope(
+    State(guard): State<ScopeGuard>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let principal = match guard.auth.authorize(
+        request.method(),
+        request.headers(),
+        request.uri(),
+        guard.scope,
+    ) {
+        Ok(principal) => principal,
+        Err(error) => return error.into_response(),
+    };
+    if guard.rate_limited
+        && let Err(error) = guard.auth.check_rate(&principal.device_id)
+    {
+        return error.into_response();
+    }
+
+    request.extensions_mut().insert(principal);
+    request.extensions_mut().insert(guard.auth.clone());
+    next.run(request).await
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+pub struct PairRequest {
+    pub session_id: String,
+    pub public_key_b64: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InviteRequest {
+    #[serde(default)]
+    pub scope: DeviceScope,
+}
+
+type ApiResult = Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)>;
+
+/// POST /api/pair -- redeem a pairing invite. The only unauthenticated API
+/// route; the caller proves itself by completing the key exchange.
+pub async fn pair_device(State(auth): State<AuthState>, Json(req): Json<PairRequest>) -> Response {
+    if let Err(wait) = auth.pairing_limiter.lock().unwrap().check(PAIRING_RATE_KEY) {
+        return rate_limited(wait).into_response();
+    }
+    match auth.complete_pairing(&req.session_id, &req.public_key_b64, &req.name) {
+        Ok(device) => Json(serde_json::json!({
+            "status": "paired",
+            "device_id": device.device_id,
+            "scope": device.scope,
+        }))
+        .into_response(),
+        Err(error) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": error.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /api/session -- exchange a credential for a browser session cookie.
+pub async fn open_session(
+    State(auth): State<AuthState>,
+    Extension(principal): Extension<Principal>,
+) -> Response {
+    if let Some(previous) = &principal.browser_session {
+        auth.close_session(previous);
+    }
+    let (session_id, csrf_token) = auth.open_session(&principal);
+    let cookie = format!(
+        "{SESSION_COOKIE}={session_id}; Path=/; HttpOnly; SameSite=Strict; Max-Age={BROWSER_SESSION_TTL_SECS}"
+    );
+    let mut response = Json(session_json(&principal, Some(&csrf_token))).into_response();
+    if let Ok(value) = HeaderValue::from_str(&cookie) {
+        response.headers_mut().insert(header::SET_COOKIE, value);
+    }
+    response
+}
+
+/// GET /api/session -- describe the current device and, for browser
+/// sessions, return the CSRF token to echo.
+pub async fn get_session(
+    State(auth): State<AuthState>,
+    Extension(principal): Extension<Principal>,
+) -> Json<serde_json::Value> {
+    let csrf_token = principal
+        .browser_session
+        .as_deref()
+        .and_then(|session_id| auth.authenticate_session(session_id))
+        .map(|(_, csrf_token)| csrf_token);
+    Json(session_json(&principal, csrf_token.as_deref()))
+}
+
+/// DELETE /api/session -- end the browser session.
+pub async fn close_session(
+    State(auth): State<AuthState>,
+    Extension(principal): Extension<Principal>,
+) -> Response {
+    if let Some(session_id) = &principal.browser_session {
+        auth.close_session(session_id);
+    }
+    (
+        [(
+            header::SET_COOKIE,
+            format!("{SESSION_COOKIE}=; Path=/; HttpOnly; SameSite=Strict; Max-Age=0"),
+        )],
+        Json(serde_json::json!({ "status": "closed" })),
+    )
+        .into_response()
+}
+
+/// GET /api/devices -- list paired devices.
+pub async fn list_devices(State(auth): State<AuthState>) -> Json<serde_json::Value> {
+    let devices: Vec<_> = auth
+        .devices()
+        .into_iter()
+        .map(|device| {
+            serde_json::json!({
+                "device_id": device.device_id,
+                "name": device.name,
+                "scope": device.scope,
+                "paired_at": device.paired_at,
+                "last_seen": device.last_seen,
+                "revoked_at": device.revoked_at,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({ "devices": devices }))
+}
+
+/// POST /api/devices/{device_id}/revoke -- revoke a paired device.
+pub async fn revoke_device(
+    State(auth): State<AuthState>,
+    Path(device_id): Path<String>,
+) -> ApiResult {
+    match auth.revoke(&device_id) {
+        Ok(true) => Ok(Json(serde_json::json!({
+            "status": "revoked",
+            "device_id": device_id,
+        }))),
+        Ok(false) => Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": format!("No active device '{device_id}'") })),
+        )),
+        Err(error) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": error.to_string() })),
+        )),
+    }
+}
+
+/// POST /api/pairing/invites -- invite another device.
+pub async fn create_invite(
+    State(auth): State<AuthState>,
+    Json(req): Json<InviteRequest>,
+) -> ApiResult {
+    auth.create_invite(req.scope)
+        .map(|invite| Json(serde_json::to_value(invite).unwrap_or_default()))
+        .map_err(|error| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": error.to_string() })),
+            )
+        })
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn session_json(principal: &Principal, csrf_token: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "device_id": principal.device_id,
+        "name": principal.name,
+        "scope": principal.scope,
+        "csrf_token": csrf_token,
+    })
+}
+
+fn rate_limited(wait: Duration) -> AuthError {
+    AuthError::RateLimited {
+        retry_after_secs: wait.as_secs().max(1),
+    }
+}
+
+fn random_token() -> String {
+    hex::encode(rand::random::<[u8; 32]>())
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+fn cookie<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+fn query_param<'a>(uri: &'a Uri, name: &str) -> Option<&'a str> {
+    uri.query()?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Safe reads need no CSRF token; writes and WebSocket upgrades do.
+fn needs_csrf(method: &Method, headers: &HeaderMap) -> bool {
+    let upgrade = headers
+        .get(header::UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    upgrade || !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// A present `Origin` header must name the host the request was sent to.
+fn same_origin(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    let host = headers
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok());
+    let origin_host = origin
+        .to_str()
+        .ok()
+        .and_then(|origin| origin.split_once("://"))
+        .map(|(_, rest)| rest.trim_end_matches('/'));
+    matches!((origin_host, host), (Some(a), Some(b)) if a.eq_ignore_ascii_case(b))
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
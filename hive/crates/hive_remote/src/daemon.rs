@@ -2,12 +2,12 @@ use crate::protocol::{
     AgentRunSummary, DaemonEvent, ObserveView, PanelPayload, PanelResponse, SessionSnapshot,
     ShellDestination, WorkspaceSummary,
 };
-use crate::session::SessionJournal;
+use crate::session::{EventStream, Resume, SequencedEvent, SessionJournal, should_journal};
 use anyhow::{Result, anyhow};
 use chrono::Utc;
 use hive_agents::{
     ActivityEntry, ActivityEvent, ActivityFilter, ActivityLog, ApprovalDecision, ApprovalGate,
-    ApprovalRequest, ApprovalRule, OperationType, RuleTrigger,
+    ApprovalProgress, ApprovalRequest, ApprovalRule, OperationType, RuleTrigger,
     automation::{AutomationService, TriggerType, Workflow, WorkflowRunResult},
     skill_format::SkillLoader,
     skills::{SkillSource, SkillsRegistry},
@@ -115,6 +115,17 @@ pub enum AgentDisposition {
     },
 }
 
+/// What one approver's decision did to a pending action.
+#[derive(Debug, Clone)]
+pub enum ApprovalOutcome {
+    /// The request reached its quorum; the action may run.
+    Approved(Option<PendingAction>),
+    /// The request was denied or expired; the action was dropped.
+    Denied(Option<PendingAction>),
+    /// The approval was recorded but more approvers must sign off.
+    Waiting { remaining: u32 },
+}
+
 #[derive(Debug, Clone)]
 pub struct WorkflowRunState {
     pub run_id: String,
@@ -160,7 +171,7 @@ pub struct HiveDaemon {
     activity_log: Arc<ActivityLog>,
     approval_gate: Arc<ApprovalGate>,
     ai_service: Arc<Mutex<AiService>>,
-    event_tx: broadcast::Sender<DaemonEvent>,
+    events: EventStream,
     cortex_event_tx: Option<hive_learn::cortex::event_bus::CortexEventSender>,
     interaction_tracker: Option<std::sync::Arc<std::sync::atomic::AtomicI64>>,
 }
@@ -176,7 +187,6 @@ impl HiveDaemon {
     ) -> Result<Self> {
         std::fs::create_dir_all(&config.data_dir)?;
         let journal = SessionJournal::new(&config.data_dir.join("session_journal.jsonl"))?;
-        let (event_tx, _) = broadcast::channel(256);
 
         let current_dir = std::env::current_dir().unwrap_or_else(|_| config.data_dir.clone());
         let current_workspace = workspace_from_path(&current_dir, true, true);
@@ -246,7 +256,7 @@ impl HiveDaemon {
                 config_snapshot.as_ref(),
                 &current_model,
             )))),
-            event_tx,
+            events: EventStream::default(),
             cortex_event_tx: None,
             interaction_tracker: None,
         };
@@ -271,12 +281,28 @@ impl HiveDaemon {
         }
     }
 
-    pub fn subscribe(&self) -> broadcast::Receiver<DaemonEvent> {
-        self.event_tx.subscribe()
+    pub fn subscribe(&self) -> broadcast::Receiver<SequencedEvent> {
+        self.events.subscribe()
     }
 
     pub fn broadcast_event(&self, event: DaemonEvent) {
-        let _ = self.event_tx.send(event);
+        self.events.publish(event);
+    }
+
+    /// Identifies this daemon run in the sequence numbers clients track.
+    pub fn event_epoch(&self) -> &str {
+        self.events.epoch()
+    }
+
+    /// Sequence number of the last broadcast event.
+    pub fn last_event_seq(&self) -> u64 {
+        self.events.last_seq()
+    }
+
+    /// The events a client that last saw `last_seq` in `epoch` has missed,
+    /// or [`Resume::Snapshot`] if it must start over.
+    pub fn resume_events(&self, epoch: &str, last_seq: u64) -> Resume {
+        self.events.resume(epoch, last_seq)
     }
 
     pub fn set_cortex_event_tx(&mut self, tx: hive_learn::cortex::event_bus::CortexEventSender) {
@@ -324,6 +350,14 @@ impl HiveDaemon {
         if let Some(operation) = classify_chat_operation(&content)
             && let Some(request) = self.approval_gate.check_sync("remote-chat", &operation)
         {
+            if let Some(reason) = request.denial {
+                self.record_activity(ActivityEvent::ApprovalDenied {
+                    request_id: request.id,
+                    reason: Some(reason.clone()),
+                });
+                self.broadcast_state_and_panels();
+                return Err(anyhow!(reason));
+            }
             self.pending_actions.insert(
                 request.id.clone(),
                 PendingAction::Chat {
@@ -504,7 +538,7 @@ impl HiveDaemon {
         }
 
         let cwd = self.workspace_root();
-        let shell = InteractiveShell::new(Some(&cwd))?;
+        let shell = InteractiveShell::new_recorded(Some(&cwd), &self.config_snapshot())?;
         self.terminal_shell = Some(Arc::new(Mutex::new(shell)));
         self.terminal_last_exit_code = None;
         self.active_destination = ShellDestination::Build;
@@ -940,6 +974,14 @@ impl HiveDaemon {
         if let Some(operation) = classify_agent_operation(&goal)
             && let Some(request) = self.approval_gate.check_sync("remote-agent", &operation)
         {
+            if let Some(reason) = request.denial {
+                self.record_activity(ActivityEvent::ApprovalDenied {
+                    request_id: request.id,
+                    reason: Some(reason.clone()),
+                });
+                self.broadcast_state_and_panels();
+                return Err(anyhow!(reason));
+            }
             run.status = "pending_approval".into();
             run.detail = "Waiting for approval before execution".into();
             self.agent_runs.push(run);
@@ -1093,7 +1135,7 @@ impl HiveDaemon {
             }
         }
 
-        let _ = self.event_tx.send(DaemonEvent::AgentStatus {
+        self.events.publish(DaemonEvent::AgentStatus {
             run_id: run_id.to_string(),
             status: status.to_string(),
             detail,
@@ -1166,23 +1208,30 @@ impl HiveDaemon {
     pub fn apply_approval_decision(
         &mut self,
         request_id: &str,
+        approver: &str,
         approved: bool,
         reason: Option<String>,
-    ) -> Result<Option<PendingAction>> {
+    ) -> Result<ApprovalOutcome> {
         if !self.pending_actions.contains_key(request_id) {
             return Err(anyhow!("Unknown approval request '{request_id}'"));
         }
 
-        self.approval_gate.respond(
-            request_id,
-            if approved {
-                ApprovalDecision::Approved
-            } else {
-                ApprovalDecision::Denied {
-                    reason: reason.clone(),
-                }
-            },
-        );
+        let decision = if approved {
+            ApprovalDecision::Approved
+        } else {
+            ApprovalDecision::Denied {
+                reason: reason.clone(),
+            }
+        };
+        let (approved, reason) = match self.approval_gate.respond(request_id, approver, decision) {
+            ApprovalProgress::Waiting { remaining } => {
+                self.broadcast_state_and_panels();
+                return Ok(ApprovalOutcome::Waiting { remaining });
+            }
+            ApprovalProgress::Granted => (true, reason),
+            ApprovalProgress::Expired => (false, Some("Approval request expired".into())),
+            ApprovalProgress::Rejected | ApprovalProgress::Unknown => (false, reason),
+        };
 
         let pending = self.pending_actions.remove(request_id);
         if approved {
@@ -1233,7 +1282,11 @@ impl HiveDaemon {
             reason,
         });
         self.broadcast_state_and_panels();
-        Ok(pending)
+        Ok(if approved {
+            ApprovalOutcome::Approved(pending)
+        } else {
+            ApprovalOutcome::Denied(pending)
+        })
     }
 
     pub fn panel_response(&self, panel_id: &str) -> Result<PanelResponse> {
@@ -1285,13 +1338,31 @@ impl HiveDaemon {
                 }
             }
             DaemonEvent::Ping => {
-                let _ = self.event_tx.send(DaemonEvent::Pong);
+                self.events.publish(DaemonEvent::Pong);
             }
             _ => {}
         }
     }
 
     pub fn replay_journal(&mut self) -> Result<()> {
+        if let Some(record) = SessionJournal::load_snapshot(self.journal.path())? {
+            let snapshot = record.snapshot;
+            self.active_conversation = snapshot.active_conversation;
+            self.active_destination = snapshot.active_destination;
+            self.active_panel = snapshot.active_panel;
+            self.current_model = snapshot.current_model;
+            self.observe_view = snapshot.observe_view;
+            let workspace = snapshot.current_workspace;
+            self.files_current_path = PathBuf::from(&workspace.path);
+            for candidate in &mut self.workspaces {
+                candidate.is_current = candidate.path == workspace.path;
+            }
+            if !self.workspaces.iter().any(|candidate| candidate.path == workspace.path) {
+                self.workspaces.push(workspace.clone());
+            }
+            self.current_workspace = workspace;
+        }
+
         let events = SessionJournal::replay(self.journal.path())?;
         for event in events {
             match event {
@@ -1406,9 +1477,8 @@ impl HiveDaemon {
     }
 
     pub fn broadcast_state_and_panels(&self) {
-        let _ = self
-            .event_tx
-            .send(DaemonEvent::StateSnapshot(self.get_snapshot()));
+        self.events
+            .publish(DaemonEvent::StateSnapshot(self.get_snapshot()));
         for panel in [
             PANEL_HOME,
             PANEL_CHAT,
@@ -1416,7 +1486,7 @@ impl HiveDaemon {
             self.active_panel.as_str(),
         ] {
             if let Ok(response) = self.panel_response(panel) {
-                let _ = self.event_tx.send(DaemonEvent::PanelData {
+                self.events.publish(DaemonEvent::PanelData {
                     panel: response.panel,
                     data: serde_json::to_value(response.data).unwrap_or_default(),
                 });
@@ -1425,11 +1495,25 @@ impl HiveDaemon {
     }
 
     fn append_journal(&mut self, event: &DaemonEvent) {
+        if !should_journal(event) {
+            return;
+        }
+        if self.journal.needs_compaction()
+            && let Err(error) = self.compact_journal()
+        {
+            tracing::warn!("Failed to compact daemon journal: {error}");
+        }
         if let Err(error) = self.journal.append(event) {
             tracing::error!("Failed to append daemon journal event: {error}");
         }
     }
 
+    /// Snapshot the current shell state and start a fresh journal.
+    pub fn compact_journal(&mut self) -> Result<()> {
+        let snapshot = self.get_snapshot();
+        self.journal.compact(&snapshot)
+    }
+
     fn record_activity(&self, event: ActivityEvent) {
         if let Err(error) = self.activity_log.record(&event) {
             tracing::warn!("Failed to record activity event: {error}");
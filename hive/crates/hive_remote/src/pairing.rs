@@ -4,7 +4,7 @@ use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
 use hkdf::Hkdf;
 use serde::{Deserialize, Serialize};
-use sha2::Sha256;
+use sha2::{Digest, Sha256};
 use std::path::Path;
 use x25519_dalek::{PublicKey, StaticSecret};
 
@@ -144,6 +144,42 @@ impl Default for DevicePermissions {
     }
 }
 
+/// How much of the remote web server a paired device may use.
+///
+/// Scopes are ordered: each one includes everything the previous one allows.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceScope {
+    /// Read-only: state, panels and the live event stream.
+    #[default]
+    Observe,
+    /// Chat, navigation and other conversational actions.
+    Chat,
+    /// Resolve pending approvals.
+    Approve,
+    /// Everything, including agents, terminals, git, settings and devices.
+    Full,
+}
+
+impl DeviceScope {
+    /// Whether a device with this scope may perform an action requiring
+    /// `required`.
+    pub fn allows(self, required: DeviceScope) -> bool {
+        self >= required
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Observe => "observe",
+            Self::Chat => "chat",
+            Self::Approve => "approve",
+            Self::Full => "full",
+        }
+    }
+}
+
 /// Metadata for a device that has completed the pairing handshake.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PairedDevice {
@@ -153,6 +189,85 @@ pub struct PairedDevice {
     pub paired_at: DateTime<Utc>,
     pub last_seen: DateTime<Utc>,
     pub permissions: DevicePermissions,
+    /// What the web server lets this device do.
+    #[serde(default)]
+    pub scope: DeviceScope,
+    /// Hex-encoded SHA-256 of the pairing token. The token itself is never
+    /// stored; devices paired before tokens were recorded cannot log in.
+    #[serde(default)]
+    pub token_hash: String,
+    #[serde(default)]
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl PairedDevice {
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    /// Check `token` against the stored hash in constant time.
+    pub fn verify_token(&self, token: &[u8; 32]) -> bool {
+        let expected = token_hash(token);
+        !self.token_hash.is_empty()
+            && self.token_hash.len() == expected.len()
+            && self
+                .token_hash
+                .bytes()
+                .zip(expected.bytes())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+/// Hex-encoded SHA-256 of a pairing token, as stored in [`PairedDevice`].
+pub fn token_hash(token: &[u8; 32]) -> String {
+    hex::encode(Sha256::digest(token))
+}
+
+/// The credential a paired device presents to the web server:
+/// `<device_id>.<pairing_token>`, both hex-encoded.
+///
+/// Both sides derive it from the pairing handshake, so it never crosses the
+/// wire during pairing.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SessionCredential {
+    pub device_id: String,
+    pub token: [u8; 32],
+}
+
+impl SessionCredential {
+    pub fn from_keys(keys: &SessionKeys) -> Self {
+        Self {
+            device_id: hex::encode(keys.device_id),
+            token: keys.pairing_token,
+        }
+    }
+
+    /// Encode as a bearer token.
+    pub fn to_bearer(&self) -> String {
+        format!("{}.{}", self.device_id, hex::encode(self.token))
+    }
+
+    /// Parse a bearer token produced by [`Self::to_bearer`].
+    pub fn parse(bearer: &str) -> Option<Self> {
+        let (device_id, token) = bearer.split_once('.')?;
+        let token: [u8; 32] = hex::decode(token).ok()?.try_into().ok()?;
+        if device_id.is_empty() {
+            return None;
+        }
+        Some(Self {
+            device_id: device_id.to_string(),
+            token,
+        })
+    }
+}
+
+impl std::fmt::Debug for SessionCredential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionCredential")
+            .field("device_id", &self.device_id)
+            .finish_non_exhaustive()
+    }
 }
 
 /// Persistent store for paired devices, serialized as JSON on disk.
@@ -211,4 +326,20 @@ impl PairedDeviceStore {
     pub fn find(&self, device_id: &str) -> Option<&PairedDevice> {
         self.devices.iter().find(|d| d.device_id == device_id)
     }
+
+    /// Mark a device as revoked, keeping its record for auditing. Returns
+    /// `true` if an active device was revoked.
+    pub fn revoke(&mut self, device_id: &str) -> bool {
+        match self
+            .devices
+            .iter_mut()
+            .find(|d| d.device_id == device_id && !d.is_revoked())
+        {
+            Some(device) => {
+                device.revoked_at = Some(Utc::now());
+                true
+            }
+            None => false,
+        }
+    }
 }
@@ -78,6 +78,14 @@ pub enum DaemonEvent {
         code: u16,
         message: String,
     },
+    /// First event on every WebSocket connection. When `snapshot` is true the
+    /// client must discard its state and rebuild it from the snapshot events
+    /// that follow; otherwise only the events it missed follow.
+    SyncStarted {
+        epoch: String,
+        last_seq: u64,
+        snapshot: bool,
+    },
 
     // Bidirectional
     Ping,
@@ -1,15 +1,47 @@
-use crate::protocol::DaemonEvent;
+use crate::protocol::{DaemonEvent, SessionSnapshot};
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Journal entries written before the journal is compacted into a snapshot.
+pub const DEFAULT_COMPACT_AFTER_ENTRIES: usize = 500;
+
+/// Longest a non-empty journal goes without a snapshot.
+pub const DEFAULT_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Broadcast events kept in memory for clients resuming after a disconnect.
+pub const DEFAULT_BACKLOG_CAPACITY: usize = 1024;
 
 /// Append-only event journal for session persistence.
 /// Each line is a JSON-serialized DaemonEvent.
-/// On recovery, replay the journal to reconstruct state.
+/// On recovery, load the latest snapshot and replay the journal on top of it.
+///
+/// The journal is compacted periodically: the current [`SessionSnapshot`] is
+/// written next to it, the journal is rotated to `<name>.1` (replacing the
+/// previous generation) and a fresh journal is started. Replayed events only
+/// set shell state, so replaying an event already covered by the snapshot
+/// (after a crash mid-compaction) is harmless.
 pub struct SessionJournal {
     path: PathBuf,
     writer: File,
+    entries_since_snapshot: usize,
+    last_snapshot: Instant,
+    compact_after_entries: usize,
+    snapshot_interval: Duration,
+}
+
+/// A compacted journal: the session state as of `taken_at`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalSnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub snapshot: SessionSnapshot,
 }
 
 impl SessionJournal {
@@ -18,6 +50,10 @@ impl SessionJournal {
             fs::create_dir_all(parent)
                 .with_context(|| format!("Failed to create journal dir: {}", parent.display()))?;
         }
+        let entries_since_snapshot = match File::open(path) {
+            Ok(file) => BufReader::new(file).lines().count(),
+            Err(_) => 0,
+        };
         let writer = OpenOptions::new()
             .create(true)
             .append(true)
@@ -26,13 +62,26 @@ impl SessionJournal {
         Ok(Self {
             path: path.to_path_buf(),
             writer,
+            entries_since_snapshot,
+            last_snapshot: Instant::now(),
+            compact_after_entries: DEFAULT_COMPACT_AFTER_ENTRIES,
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
         })
     }
 
+    /// Compact after `entries` appended events, or once a non-empty journal
+    /// is older than `interval`.
+    pub fn with_compaction_policy(mut self, entries: usize, interval: Duration) -> Self {
+        self.compact_after_entries = entries;
+        self.snapshot_interval = interval;
+        self
+    }
+
     pub fn append(&mut self, event: &DaemonEvent) -> Result<()> {
         let json = serde_json::to_string(event)?;
         writeln!(self.writer, "{}", json)?;
         self.writer.flush()?;
+        self.entries_since_snapshot += 1;
         Ok(())
     }
 
@@ -61,6 +110,70 @@ impl SessionJournal {
         Ok(events)
     }
 
+    /// Whether the journal should be compacted before the next append.
+    pub fn needs_compaction(&self) -> bool {
+        self.entries_since_snapshot >= self.compact_after_entries
+            || (self.entries_since_snapshot > 0
+                && self.last_snapshot.elapsed() >= self.snapshot_interval)
+    }
+
+    /// Write `snapshot` and start a fresh journal, keeping the previous one
+    /// as `<name>.1`.
+    pub fn compact(&mut self, snapshot: &SessionSnapshot) -> Result<()> {
+        let snapshot_path = Self::snapshot_path(&self.path);
+        let record = JournalSnapshot {
+            taken_at: Utc::now(),
+            snapshot: snapshot.clone(),
+        };
+        let tmp_path = snapshot_path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(&record)?)
+            .with_context(|| format!("Failed to write snapshot: {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, &snapshot_path)
+            .with_context(|| format!("Failed to write snapshot: {}", snapshot_path.display()))?;
+
+        self.writer.flush()?;
+        fs::rename(&self.path, Self::rotated_path(&self.path))
+            .with_context(|| format!("Failed to rotate journal: {}", self.path.display()))?;
+        self.writer = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open journal: {}", self.path.display()))?;
+        self.entries_since_snapshot = 0;
+        self.last_snapshot = Instant::now();
+        Ok(())
+    }
+
+    /// Load the snapshot written by the last compaction of the journal at
+    /// `path`, if any.
+    pub fn load_snapshot(path: &Path) -> Result<Option<JournalSnapshot>> {
+        let snapshot_path = Self::snapshot_path(path);
+        if !snapshot_path.exists() {
+            return Ok(None);
+        }
+        let data = fs::read(&snapshot_path)
+            .with_context(|| format!("Failed to read snapshot: {}", snapshot_path.display()))?;
+        match serde_json::from_slice(&data) {
+            Ok(snapshot) => Ok(Some(snapshot)),
+            Err(e) => {
+                tracing::warn!("Ignoring corrupt session snapshot: {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// `session_journal.jsonl` → `session_journal.snapshot.json`.
+    pub fn snapshot_path(path: &Path) -> PathBuf {
+        path.with_extension("snapshot.json")
+    }
+
+    /// `session_journal.jsonl` → `session_journal.jsonl.1`.
+    pub fn rotated_path(path: &Path) -> PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push(".1");
+        PathBuf::from(name)
+    }
+
     pub fn truncate(&mut self) -> Result<()> {
         drop(std::mem::replace(
             &mut self.writer,
@@ -74,6 +187,7 @@ impl SessionJournal {
             .create(true)
             .append(true)
             .open(&self.path)?;
+        self.entries_since_snapshot = 0;
         Ok(())
     }
 
@@ -81,3 +195,143 @@ impl SessionJournal {
         &self.path
     }
 }
+
+/// Whether `event` belongs in the journal: shell-state changes replayed on
+/// recovery, plus user actions kept for the record. Streaming output, panel
+/// payloads and keep-alives are not journaled.
+pub fn should_journal(event: &DaemonEvent) -> bool {
+    matches!(
+        event,
+        DaemonEvent::SendMessage { .. }
+            | DaemonEvent::SwitchPanel { .. }
+            | DaemonEvent::SwitchDestination { .. }
+            | DaemonEvent::SetModel { .. }
+            | DaemonEvent::SetObserveView { .. }
+            | DaemonEvent::SwitchWorkspace { .. }
+            | DaemonEvent::ResumeConversation { .. }
+            | DaemonEvent::ApprovalDecision { .. }
+            | DaemonEvent::StartAgentTask { .. }
+            | DaemonEvent::CancelAgentTask { .. }
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Sequenced broadcast stream for multi-client sync
+// ---------------------------------------------------------------------------
+
+/// A broadcast event tagged with its position in the daemon's event stream.
+///
+/// Serialized flat, so clients see `{"seq": 42, "type": "stream_chunk", ...}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencedEvent {
+    pub seq: u64,
+    #[serde(flatten)]
+    pub event: DaemonEvent,
+}
+
+/// What a reconnecting client needs to catch up.
+#[derive(Debug, Clone)]
+pub enum Resume {
+    /// The events the client missed, in order (possibly none).
+    Delta(Vec<SequencedEvent>),
+    /// The client is too far behind, or from another daemon run, and must
+    /// start over from a snapshot.
+    Snapshot,
+}
+
+/// The daemon's outgoing event stream. Every broadcast gets the next
+/// sequence number and is kept in a bounded backlog so clients that
+/// reconnect can be sent only what they missed.
+///
+/// Sequence numbers start at 1 and restart with each daemon run, which is
+/// identified by [`Self::epoch`].
+pub struct EventStream {
+    epoch: String,
+    tx: broadcast::Sender<SequencedEvent>,
+    backlog: Mutex<Backlog>,
+}
+
+struct Backlog {
+    last_seq: u64,
+    events: VecDeque<SequencedEvent>,
+    capacity: usize,
+}
+
+impl EventStream {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(256);
+        Self {
+            epoch: uuid::Uuid::new_v4().to_string(),
+            tx,
+            backlog: Mutex::new(Backlog {
+                last_seq: 0,
+                events: VecDeque::with_capacity(capacity),
+                capacity,
+            }),
+        }
+    }
+
+    /// Identifies this daemon run; sequence numbers from another epoch are
+    /// meaningless.
+    pub fn epoch(&self) -> &str {
+        &self.epoch
+    }
+
+    /// Sequence number of the most recent event, or 0 if none was sent.
+    pub fn last_seq(&self) -> u64 {
+        self.backlog.lock().unwrap().last_seq
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SequencedEvent> {
+        self.tx.subscribe()
+    }
+
+    /// Assign the next sequence number to `event`, record it and broadcast
+    /// it. Returns the sequence number.
+    pub fn publish(&self, event: DaemonEvent) -> u64 {
+        // Hold the lock while sending so subscribers see events in
+        // sequence order.
+        let mut backlog = self.backlog.lock().unwrap();
+        backlog.last_seq += 1;
+        let sequenced = SequencedEvent {
+            seq: backlog.last_seq,
+            event,
+        };
+        if backlog.capacity > 0 {
+            if backlog.events.len() == backlog.capacity {
+                backlog.events.pop_front();
+            }
+            backlog.events.push_back(sequenced.clone());
+        }
+        let _ = self.tx.send(sequenced);
+        backlog.last_seq
+    }
+
+    /// What a client that last saw `last_seq` in `epoch` has missed.
+    pub fn resume(&self, epoch: &str, last_seq: u64) -> Resume {
+        let backlog = self.backlog.lock().unwrap();
+        if epoch != self.epoch || last_seq > backlog.last_seq {
+            return Resume::Snapshot;
+        }
+        if last_seq == backlog.last_seq {
+            return Resume::Delta(Vec::new());
+        }
+        match backlog.events.front() {
+            Some(oldest) if oldest.seq <= last_seq + 1 => Resume::Delta(
+                backlog
+                    .events
+                    .iter()
+                    .filter(|event| event.seq > last_seq)
+                    .cloned()
+                    .collect(),
+            ),
+            _ => Resume::Snapshot,
+        }
+    }
+}
+
+impl Default for EventStream {
+    fn default() -> Self {
+        Self::new(DEFAULT_BACKLOG_CAPACITY)
+    }
+}
@@ -1,7 +1,13 @@
-use crate::daemon::{AgentDisposition, HiveDaemon, PendingAction, SendDisposition};
+use crate::auth::{AuthState, Principal};
+use crate::daemon::{
+    AgentDisposition, ApprovalOutcome, HiveDaemon, PendingAction, SendDisposition,
+};
+use crate::pairing::DeviceScope;
 use crate::protocol::{DaemonEvent, ObserveView, PanelResponse, SessionSnapshot, ShellDestination};
+use crate::session::{Resume, SequencedEvent};
+use axum::Extension;
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json};
 use futures::SinkExt;
@@ -10,6 +16,7 @@ use hive_terminal::ShellOutput;
 use serde::Deserialize;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::time::{Duration, sleep};
 
 /// Shared daemon state wrapped for concurrent access by axum handlers.
@@ -216,11 +223,18 @@ pub async fn resume_conversation(
 pub async fn approval_decision(
     State(daemon): State<DaemonState>,
     Path(request_id): Path<String>,
+    Extension(principal): Extension<Principal>,
     Json(req): Json<ApprovalDecisionRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    process_approval_decision(daemon, request_id, req.approved, req.reason)
-        .await
-        .map(Json)
+    process_approval_decision(
+        daemon,
+        request_id,
+        principal.device_id,
+        req.approved,
+        req.reason,
+    )
+    .await
+    .map(Json)
 }
 
 pub async fn agent_action(
@@ -421,44 +435,81 @@ pub async fn skills_remove(
     process_skill_remove(daemon, req.name).await.map(Json)
 }
 
+/// Where a reconnecting client left off: the epoch and sequence number of
+/// the last event it saw.
+#[derive(Debug, Default, Deserialize)]
+pub struct ResumeQuery {
+    pub epoch: Option<String>,
+    pub since: Option<u64>,
+}
+
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(daemon): State<DaemonState>,
+    Query(resume): Query<ResumeQuery>,
+    Extension(principal): Extension<Principal>,
+    Extension(auth): Extension<AuthState>,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_websocket(socket, daemon))
+    ws.on_upgrade(move |socket| handle_websocket(socket, daemon, resume, principal, auth))
 }
 
-async fn handle_websocket(socket: WebSocket, daemon: DaemonState) {
+async fn handle_websocket(
+    socket: WebSocket,
+    daemon: DaemonState,
+    resume: ResumeQuery,
+    principal: Principal,
+    auth: AuthState,
+) {
     let (mut sender, mut receiver) = socket.split();
 
-    if send_initial_payloads(&mut sender, daemon.clone()).await.is_err() {
-        return;
-    }
-
-    let mut rx = {
+    // Subscribe before working out the catch-up so nothing published in
+    // between is lost; anything sent twice is skipped by sequence number.
+    let (mut rx, catch_up) = {
         let daemon = daemon.read().await;
-        daemon.subscribe()
+        let rx = daemon.subscribe();
+        (rx, catch_up_events(&daemon, &resume))
+    };
+    let Ok(mut last_sent) = send_events(&mut sender, &catch_up).await else {
+        return;
     };
 
-    let send_task = tokio::spawn(async move {
-        while let Ok(event) = rx.recv().await {
-            if let Ok(json) = serde_json::to_string(&event)
-                && sender.send(Message::Text(json.into())).await.is_err()
-            {
-                break;
+    let send_daemon = daemon.clone();
+    let mut send_task = tokio::spawn(async move {
+        loop {
+            let events = match rx.recv().await {
+                Ok(event) if event.seq <= last_sent => continue,
+                Ok(event) => vec![event],
+                // Fell too far behind the broadcast: start over from a
+                // snapshot rather than silently dropping events.
+                Err(RecvError::Lagged(_)) => {
+                    rx = rx.resubscribe();
+                    snapshot_events(&*send_daemon.read().await)
+                }
+                Err(RecvError::Closed) => break,
+            };
+            match send_events(&mut sender, &events).await {
+                Ok(seq) => last_sent = last_sent.max(seq),
+                Err(()) => break,
             }
         }
     });
 
     let daemon_clone = daemon.clone();
-    let recv_task = tokio::spawn(async move {
+    let recv_auth = auth.clone();
+    let recv_principal = principal.clone();
+    let mut recv_task = tokio::spawn(async move {
         while let Some(Ok(message)) = receiver.next().await {
             if let Message::Text(text) = message {
                 match serde_json::from_str::<DaemonEvent>(&text) {
                     Ok(event) => {
-                        if let Err((status, payload)) =
-                            dispatch_client_event(daemon_clone.clone(), event).await
-                        {
+                        let result = match authorize_event(&recv_auth, &recv_principal, &event) {
+                            Ok(()) => {
+                                dispatch_client_event(daemon_clone.clone(), &recv_principal, event)
+                                    .await
+                            }
+                            Err(error) => Err(error),
+                        };
+                        if let Err((status, payload)) = result {
                             let daemon = daemon_clone.read().await;
                             daemon.broadcast_event(DaemonEvent::Error {
                                 code: status.as_u16(),
@@ -481,48 +532,161 @@ async fn handle_websocket(socket: WebSocket, daemon: DaemonState) {
         }
     });
 
+    // Close the socket as soon as its device is revoked.
+    let mut revocations = auth.subscribe_revocations();
+    let revoked = async {
+        while revocations.changed().await.is_ok() {
+            if !auth.is_active(&principal.device_id) {
+                break;
+            }
+        }
+    };
+
     tokio::select! {
-        _ = send_task => {}
-        _ = recv_task => {}
+        _ = &mut send_task => {}
+        _ = &mut recv_task => {}
+        _ = revoked => {}
     }
+    send_task.abort();
+    recv_task.abort();
 }
 
-async fn send_initial_payloads(
-    sender: &mut futures::stream::SplitSink<WebSocket, Message>,
-    daemon: DaemonState,
-) -> Result<(), ()> {
-    let (snapshot, panels) = {
-        let daemon = daemon.read().await;
-        let snapshot = daemon.get_snapshot();
-        let mut panels = Vec::new();
-        for panel in ["home", "chat", "observe", snapshot.active_panel.as_str()] {
-            if let Ok(response) = daemon.panel_response(panel) {
-                panels.push(DaemonEvent::PanelData {
-                    panel: response.panel,
-                    data: serde_json::to_value(response.data).unwrap_or_default(),
-                });
-            }
+/// The scope a client event sent over the WebSocket requires.
+///
+/// Every variant is listed so a new event has to be classified before it
+/// compiles. Daemon -> client events have no business coming from a client
+/// and require the most restrictive scope.
+pub fn event_scope(event: &DaemonEvent) -> DeviceScope {
+    match event {
+        DaemonEvent::Ping | DaemonEvent::Pong => DeviceScope::Observe,
+        DaemonEvent::SendMessage { .. }
+        | DaemonEvent::SwitchPanel { .. }
+        | DaemonEvent::SwitchDestination { .. }
+        | DaemonEvent::SetModel { .. }
+        | DaemonEvent::SetObserveView { .. }
+        | DaemonEvent::SwitchWorkspace { .. }
+        | DaemonEvent::LaunchHomeMission { .. }
+        | DaemonEvent::ResumeConversation { .. }
+        | DaemonEvent::ResponseFeedback { .. } => DeviceScope::Chat,
+        DaemonEvent::ApprovalDecision { .. } => DeviceScope::Approve,
+        DaemonEvent::StartAgentTask { .. } | DaemonEvent::CancelAgentTask { .. } => {
+            DeviceScope::Full
         }
-        (snapshot, panels)
-    };
+        DaemonEvent::StreamChunk { .. }
+        | DaemonEvent::StreamComplete { .. }
+        | DaemonEvent::AgentStatus { .. }
+        | DaemonEvent::StateSnapshot(_)
+        | DaemonEvent::PanelData { .. }
+        | DaemonEvent::Error { .. }
+        | DaemonEvent::SyncStarted { .. } => DeviceScope::Full,
+    }
+}
 
-    let snapshot_event = DaemonEvent::StateSnapshot(snapshot);
-    let snapshot_json = serde_json::to_string(&snapshot_event).map_err(|_| ())?;
-    sender
-        .send(Message::Text(snapshot_json.into()))
-        .await
-        .map_err(|_| ())?;
+/// Apply the same scope and rate-limit checks to WebSocket events as the
+/// HTTP routes they mirror.
+fn authorize_event(
+    auth: &AuthState,
+    principal: &Principal,
+    event: &DaemonEvent,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let required = event_scope(event);
+    if !auth.is_active(&principal.device_id) {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({ "error": "Device has been revoked" })),
+        ));
+    }
+    if !principal.scope.allows(required) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": format!(
+                    "Device scope '{}' does not allow this action (requires '{}')",
+                    principal.scope.as_str(),
+                    required.as_str()
+                ),
+            })),
+        ));
+    }
+    if required >= DeviceScope::Approve && auth.check_rate(&principal.device_id).is_err() {
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(serde_json::json!({ "error": "Too many requests" })),
+        ));
+    }
+    Ok(())
+}
 
-    for event in panels {
-        let json = serde_json::to_string(&event).map_err(|_| ())?;
-        sender.send(Message::Text(json.into())).await.map_err(|_| ())?;
+/// What a newly connected client is sent first: the events it missed if it
+/// is resuming and they are still in the backlog, otherwise a snapshot.
+fn catch_up_events(daemon: &HiveDaemon, resume: &ResumeQuery) -> Vec<SequencedEvent> {
+    let (Some(epoch), Some(since)) = (resume.epoch.as_deref(), resume.since) else {
+        return snapshot_events(daemon);
+    };
+    match daemon.resume_events(epoch, since) {
+        Resume::Delta(missed) => {
+            let mut events = vec![SequencedEvent {
+                seq: since,
+                event: DaemonEvent::SyncStarted {
+                    epoch: daemon.event_epoch().to_string(),
+                    last_seq: since,
+                    snapshot: false,
+                },
+            }];
+            events.extend(missed);
+            events
+        }
+        Resume::Snapshot => snapshot_events(daemon),
     }
+}
 
-    Ok(())
+/// The current shell state and core panels, stamped with the latest
+/// sequence number so later events apply on top of them.
+fn snapshot_events(daemon: &HiveDaemon) -> Vec<SequencedEvent> {
+    let seq = daemon.last_event_seq();
+    let snapshot = daemon.get_snapshot();
+    let mut events = vec![
+        DaemonEvent::SyncStarted {
+            epoch: daemon.event_epoch().to_string(),
+            last_seq: seq,
+            snapshot: true,
+        },
+        DaemonEvent::StateSnapshot(snapshot.clone()),
+    ];
+    for panel in ["home", "chat", "observe", snapshot.active_panel.as_str()] {
+        if let Ok(response) = daemon.panel_response(panel) {
+            events.push(DaemonEvent::PanelData {
+                panel: response.panel,
+                data: serde_json::to_value(response.data).unwrap_or_default(),
+            });
+        }
+    }
+    events
+        .into_iter()
+        .map(|event| SequencedEvent { seq, event })
+        .collect()
+}
+
+/// Send `events` in order, returning the highest sequence number sent.
+async fn send_events(
+    sender: &mut futures::stream::SplitSink<WebSocket, Message>,
+    events: &[SequencedEvent],
+) -> Result<u64, ()> {
+    let mut last_seq = 0;
+    for event in events {
+        let json = serde_json::to_string(event).map_err(|_| ())?;
+        sender
+            .send(Message::Text(json.into()))
+            .await
+            .map_err(|_| ())?;
+        last_seq = last_seq.max(event.seq);
+    }
+    Ok(last_seq)
 }
 
 async fn dispatch_client_event(
     daemon: DaemonState,
+    principal: &Principal,
     event: DaemonEvent,
 ) -> Result<serde_json::Value, (StatusCode, Json<serde_json::Value>)> {
     match event {
@@ -593,7 +757,16 @@ async fn dispatch_client_event(
             request_id,
             approved,
             reason,
-        } => process_approval_decision(daemon, request_id, approved, reason).await,
+        } => {
+            process_approval_decision(
+                daemon,
+                request_id,
+                principal.device_id.clone(),
+                approved,
+                reason,
+            )
+            .await
+        }
         DaemonEvent::StartAgentTask {
             goal,
             orchestration_mode,
@@ -780,16 +953,35 @@ async fn process_resume_conversation(
 async fn process_approval_decision(
     daemon: DaemonState,
     request_id: String,
+    approver: String,
     approved: bool,
     reason: Option<String>,
 ) -> Result<serde_json::Value, (StatusCode, Json<serde_json::Value>)> {
-    let pending = {
+    let outcome = {
         let mut daemon = daemon.write().await;
         daemon
-            .apply_approval_decision(&request_id, approved, reason.clone())
+            .apply_approval_decision(&request_id, &approver, approved, reason.clone())
             .map_err(|error| api_error(StatusCode::BAD_REQUEST, error.to_string()))?
     };
 
+    let (approved, pending) = match outcome {
+        ApprovalOutcome::Approved(pending) => (true, pending),
+        ApprovalOutcome::Denied(pending) => (false, pending),
+        ApprovalOutcome::Waiting { remaining } => {
+            let snapshot = {
+                let daemon = daemon.read().await;
+                daemon.get_snapshot()
+            };
+            return Ok(serde_json::json!({
+                "status": "waiting",
+                "request_id": request_id,
+                "approved": false,
+                "remaining": remaining,
+                "snapshot": snapshot,
+            }));
+        }
+    };
+
     let mut status = if approved { "approved" } else { "denied" };
     let mut run_id = None;
     let mut conversation_id = None;
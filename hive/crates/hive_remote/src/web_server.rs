@@ -1,5 +1,8 @@
+use crate::auth::{self, AuthState, ScopeGuard};
+use crate::pairing::DeviceScope;
 use crate::web_api::{self, DaemonState};
 use axum::Router;
+use axum::middleware::from_fn_with_state;
 use axum::routing::{any, get, post};
 
 // ---------------------------------------------------------------------------
@@ -37,27 +40,57 @@ pub async fn serve_js() -> (
 // Router
 // ---------------------------------------------------------------------------
 
-pub fn build_router(daemon: DaemonState) -> Router {
-    Router::new()
-        .route("/", get(serve_index))
-        .route("/style.css", get(serve_css))
-        .route("/app.js", get(serve_js))
+/// Build the web server router.
+///
+/// Static assets and `POST /api/pair` are public. Every other route, and the
+/// WebSocket upgrade, requires a paired device whose [`DeviceScope`] covers
+/// the route's group; approval and full-control routes are also rate limited.
+pub fn build_router(daemon: DaemonState, auth: AuthState) -> Router {
+    let observe = Router::new()
         .route("/api/state", get(web_api::get_state))
+        .route("/api/panels/{panel_id}", get(web_api::get_panel))
+        .route("/ws", any(web_api::websocket_handler))
+        .route_layer(from_fn_with_state(
+            ScopeGuard::new(&auth, DeviceScope::Observe),
+            auth::require_scope,
+        ));
+
+    let chat = Router::new()
         .route("/api/chat", post(web_api::send_message))
         .route("/api/navigation", post(web_api::navigate_shell))
         .route("/api/home/launch", post(web_api::launch_home_mission))
         .route("/api/workspaces/switch", post(web_api::switch_workspace))
-        .route("/api/conversations/resume", post(web_api::resume_conversation))
+        .route(
+            "/api/conversations/resume",
+            post(web_api::resume_conversation),
+        )
+        .route("/api/files/navigate", post(web_api::file_navigate))
+        .route("/api/files/open", post(web_api::file_open))
+        .route("/api/specs/select", post(web_api::spec_select))
+        .route("/api/channels/select", post(web_api::channel_select))
+        .route("/api/channels/message", post(web_api::channel_message))
+        .route_layer(from_fn_with_state(
+            ScopeGuard::new(&auth, DeviceScope::Chat),
+            auth::require_scope,
+        ));
+
+    let approve = Router::new()
         .route(
             "/api/approvals/{request_id}/decision",
             post(web_api::approval_decision),
         )
-        .route("/api/panels/{panel_id}", get(web_api::get_panel))
+        .route(
+            "/api/assistant/approvals/{approval_id}/decision",
+            post(web_api::assistant_decision),
+        )
+        .route_layer(from_fn_with_state(
+            ScopeGuard::new(&auth, DeviceScope::Approve).rate_limited(),
+            auth::require_scope,
+        ));
+
+    let full = Router::new()
         .route("/api/agents", post(web_api::agent_action))
         .route("/api/agents/{run_id}/cancel", post(web_api::agent_cancel))
-        .route("/api/files/navigate", post(web_api::file_navigate))
-        .route("/api/files/open", post(web_api::file_open))
-        .route("/api/specs/select", post(web_api::spec_select))
         .route("/api/git/stage-all", post(web_api::git_stage_all))
         .route("/api/git/unstage-all", post(web_api::git_unstage_all))
         .route("/api/git/commit", post(web_api::git_commit))
@@ -66,12 +99,6 @@ pub fn build_router(daemon: DaemonState) -> Router {
         .route("/api/terminal/clear", post(web_api::terminal_clear))
         .route("/api/terminal/kill", post(web_api::terminal_kill))
         .route("/api/workflows/run", post(web_api::workflow_run))
-        .route("/api/channels/select", post(web_api::channel_select))
-        .route("/api/channels/message", post(web_api::channel_message))
-        .route(
-            "/api/assistant/approvals/{approval_id}/decision",
-            post(web_api::assistant_decision),
-        )
         .route("/api/settings/update", post(web_api::settings_update))
         .route("/api/settings/text", post(web_api::settings_text))
         .route("/api/models/default", post(web_api::models_default))
@@ -88,6 +115,46 @@ pub fn build_router(daemon: DaemonState) -> Router {
         .route("/api/skills/toggle", post(web_api::skills_toggle))
         .route("/api/skills/install", post(web_api::skills_install))
         .route("/api/skills/remove", post(web_api::skills_remove))
-        .route("/ws", any(web_api::websocket_handler))
+        .route_layer(from_fn_with_state(
+            ScopeGuard::new(&auth, DeviceScope::Full).rate_limited(),
+            auth::require_scope,
+        ));
+
+    let session = Router::new()
+        .route(
+            "/api/session",
+            get(auth::get_session)
+                .post(auth::open_session)
+                .delete(auth::close_session),
+        )
+        .route_layer(from_fn_with_state(
+            ScopeGuard::new(&auth, DeviceScope::Observe),
+            auth::require_scope,
+        ));
+
+    let devices = Router::new()
+        .route("/api/devices", get(auth::list_devices))
+        .route("/api/devices/{device_id}/revoke", post(auth::revoke_device))
+        .route("/api/pairing/invites", post(auth::create_invite))
+        .route_layer(from_fn_with_state(
+            ScopeGuard::new(&auth, DeviceScope::Full).rate_limited(),
+            auth::require_scope,
+        ));
+
+    let auth_routes = Router::new()
+        .route("/api/pair", post(auth::pair_device))
+        .merge(session)
+        .merge(devices)
+        .with_state(auth);
+
+    Router::new()
+        .route("/", get(serve_index))
+        .route("/style.css", get(serve_css))
+        .route("/app.js", get(serve_js))
+        .merge(observe)
+        .merge(chat)
+        .merge(approve)
+        .merge(full)
+        .merge(auth_routes)
         .with_state(daemon)
 }
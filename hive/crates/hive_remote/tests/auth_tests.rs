@@ -0,0 +1,480 @@
+use axum::Router;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use hive_remote::auth::{AuthState, CSRF_HEADER, WebAuth};
+use hive_remote::daemon::{DaemonConfig, HiveDaemon};
+use hive_remote::pairing::{DeviceScope, PairingKeypair, SessionCredential, SessionKeys};
+use hive_remote::qr::PairingQrPayload;
+use hive_remote::web_api::DaemonState;
+use hive_remote::web_server::build_router;
+use http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tempfile::tempdir;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+
+fn temp_dir() -> PathBuf {
+    let dir = tempdir().unwrap();
+    let path = dir.path().to_path_buf();
+    std::mem::forget(dir);
+    path
+}
+
+fn make_daemon() -> DaemonState {
+    let data_dir = temp_dir();
+    let config = DaemonConfig {
+        config_root: Some(data_dir.join("config")),
+        data_dir,
+        ..DaemonConfig::default()
+    };
+    Arc::new(RwLock::new(HiveDaemon::new(config).unwrap()))
+}
+
+fn make_app() -> (Router, AuthState) {
+    let auth = Arc::new(WebAuth::load(&temp_dir().join("devices.json")).unwrap());
+    (build_router(make_daemon(), auth.clone()), auth)
+}
+
+/// Play the device's side of the handshake: scan the invite, derive keys.
+fn accept_invite(url: &str) -> (PairingQrPayload, PairingKeypair, SessionCredential) {
+    let payload = PairingQrPayload::from_url(url).unwrap();
+    let desktop_key: [u8; 32] = BASE64
+        .decode(&payload.public_key_b64)
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let device = PairingKeypair::generate();
+    let shared = device.derive_shared_secret(&desktop_key);
+    let keys = SessionKeys::derive(&shared, &payload.session_id);
+    (payload, device, SessionCredential::from_keys(&keys))
+}
+
+fn pair(auth: &AuthState, scope: DeviceScope) -> SessionCredential {
+    let invite = auth.create_invite(scope).unwrap();
+    let (payload, device, credential) = accept_invite(&invite.url);
+    auth.complete_pairing(
+        &payload.session_id,
+        &BASE64.encode(device.public_key_bytes()),
+        "Test device",
+    )
+    .unwrap();
+    credential
+}
+
+fn get(uri: &str) -> http::request::Builder {
+    Request::builder().uri(uri).method("GET")
+}
+
+fn post(uri: &str) -> http::request::Builder {
+    Request::builder()
+        .uri(uri)
+        .method("POST")
+        .header("content-type", "application/json")
+}
+
+fn bearer(credential: &SessionCredential) -> String {
+    format!("Bearer {}", credential.to_bearer())
+}
+
+async fn send(app: &Router, request: Request<axum::body::Body>) -> (StatusCode, serde_json::Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn pairing_over_http_yields_a_working_bearer_credential() {
+    let (app, auth) = make_app();
+    let invite = auth.create_invite(DeviceScope::Chat).unwrap();
+    let (payload, device, credential) = accept_invite(&invite.url);
+
+    let pair_request = serde_json::json!({
+        "session_id": payload.session_id,
+        "public_key_b64": BASE64.encode(device.public_key_bytes()),
+        "name": "Phone",
+    });
+    let (status, json) = send(
+        &app,
+        post("/api/pair")
+            .body(pair_request.to_string().into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["device_id"], credential.device_id.as_str());
+    assert_eq!(json["scope"], "chat");
+
+    let (status, json) = send(
+        &app,
+        get("/api/state")
+            .header("authorization", bearer(&credential))
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["active_destination"], "home");
+
+    // Invites are single use.
+    let (status, _) = send(
+        &app,
+        post("/api/pair")
+            .body(pair_request.to_string().into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn requests_without_a_valid_credential_are_rejected() {
+    let (app, auth) = make_app();
+    let mut credential = pair(&auth, DeviceScope::Full);
+
+    let (status, _) = send(
+        &app,
+        get("/api/state").body(axum::body::Body::empty()).unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = send(
+        &app,
+        get("/ws")
+            .header("connection", "upgrade")
+            .header("upgrade", "websocket")
+            .header("sec-websocket-version", "13")
+            .header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    credential.token[0] ^= 0xff;
+    let (status, _) = send(
+        &app,
+        get("/api/state")
+            .header("authorization", bearer(&credential))
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Static assets stay public so the pairing screen can load.
+    let (status, _) = send(&app, get("/").body(axum::body::Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn device_scopes_gate_route_groups() {
+    let (app, auth) = make_app();
+    let navigation = serde_json::json!({ "panel": "files" }).to_string();
+
+    let observer = pair(&auth, DeviceScope::Observe);
+    let (status, _) = send(
+        &app,
+        get("/api/panels/home")
+            .header("authorization", bearer(&observer))
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, json) = send(
+        &app,
+        post("/api/navigation")
+            .header("authorization", bearer(&observer))
+            .body(navigation.clone().into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(json["error"].as_str().unwrap().contains("requires 'chat'"));
+
+    let chatter = pair(&auth, DeviceScope::Chat);
+    let (status, _) = send(
+        &app,
+        post("/api/navigation")
+            .header("authorization", bearer(&chatter))
+            .body(navigation.into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        post("/api/approvals/missing/decision")
+            .header("authorization", bearer(&chatter))
+            .body(serde_json::json!({ "approved": true }).to_string().into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let approver = pair(&auth, DeviceScope::Approve);
+    let (status, _) = send(
+        &app,
+        get("/api/devices")
+            .header("authorization", bearer(&approver))
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let admin = pair(&auth, DeviceScope::Full);
+    let (status, json) = send(
+        &app,
+        get("/api/devices")
+            .header("authorization", bearer(&admin))
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["devices"].as_array().unwrap().len(), 4);
+    assert!(json["devices"][0].get("token_hash").is_none());
+}
+
+#[tokio::test]
+async fn browser_sessions_require_csrf_tokens_for_writes() {
+    let (app, auth) = make_app();
+    let credential = pair(&auth, DeviceScope::Chat);
+
+    let response = app
+        .clone()
+        .oneshot(
+            post("/api/session")
+                .header("authorization", bearer(&credential))
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let set_cookie = response.headers()["set-cookie"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(set_cookie.contains("HttpOnly"));
+    assert!(set_cookie.contains("SameSite=Strict"));
+    let cookie = set_cookie.split(';').next().unwrap().to_string();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let csrf = json["csrf_token"].as_str().unwrap().to_string();
+
+    let (status, json) = send(
+        &app,
+        get("/api/session")
+            .header("cookie", &cookie)
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["csrf_token"], csrf.as_str());
+
+    let navigation = serde_json::json!({ "panel": "files" }).to_string();
+    let (status, _) = send(
+        &app,
+        post("/api/navigation")
+            .header("cookie", &cookie)
+            .body(navigation.clone().into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send(
+        &app,
+        post("/api/navigation")
+            .header("cookie", &cookie)
+            .header(CSRF_HEADER, &csrf)
+            .header("host", "hive.local:9481")
+            .header("origin", "http://evil.example")
+            .body(navigation.clone().into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send(
+        &app,
+        post("/api/navigation")
+            .header("cookie", &cookie)
+            .header(CSRF_HEADER, &csrf)
+            .header("host", "hive.local:9481")
+            .header("origin", "http://hive.local:9481")
+            .body(navigation.into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // WebSocket upgrades from a browser must carry the token as well.
+    let (status, _) = send(
+        &app,
+        get("/ws")
+            .header("cookie", &cookie)
+            .header("connection", "upgrade")
+            .header("upgrade", "websocket")
+            .header("sec-websocket-version", "13")
+            .header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn revoked_devices_lose_access() {
+    let (app, auth) = make_app();
+    let admin = pair(&auth, DeviceScope::Full);
+    let phone = pair(&auth, DeviceScope::Chat);
+
+    let response = app
+        .clone()
+        .oneshot(
+            post("/api/session")
+                .header("authorization", bearer(&phone))
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let cookie = response.headers()["set-cookie"]
+        .to_str()
+        .unwrap()
+        .split(';')
+        .next()
+        .unwrap()
+        .to_string();
+
+    let (status, json) = send(
+        &app,
+        post(&format!("/api/devices/{}/revoke", phone.device_id))
+            .header("authorization", bearer(&admin))
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["status"], "revoked");
+
+    for request in [
+        get("/api/state").header("authorization", bearer(&phone)),
+        get("/api/state").header("cookie", &cookie),
+    ] {
+        let (status, _) = send(&app, request.body(axum::body::Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+    assert!(!auth.is_active(&phone.device_id));
+    assert!(auth.is_active(&admin.device_id));
+
+    let (status, _) = send(
+        &app,
+        post(&format!("/api/devices/{}/revoke", phone.device_id))
+            .header("authorization", bearer(&admin))
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn sensitive_routes_are_rate_limited_per_device() {
+    let (app, auth) = make_app();
+    let admin = pair(&auth, DeviceScope::Full);
+    let other = pair(&auth, DeviceScope::Full);
+
+    let mut limited = None;
+    for _ in 0..100 {
+        let response = app
+            .clone()
+            .oneshot(
+                get("/api/devices")
+                    .header("authorization", bearer(&admin))
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            limited = Some(response);
+            break;
+        }
+    }
+    let limited = limited.expect("requests should eventually be rate limited");
+    assert!(limited.headers().contains_key("retry-after"));
+
+    // Other devices have their own budget, and reads are not limited.
+    let (status, _) = send(
+        &app,
+        get("/api/devices")
+            .header("authorization", bearer(&other))
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        get("/api/state")
+            .header("authorization", bearer(&admin))
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[test]
+fn paired_devices_survive_a_restart_without_storing_the_token() {
+    let path = temp_dir().join("devices.json");
+    let credential = {
+        let auth = Arc::new(WebAuth::load(&path).unwrap());
+        pair(&auth, DeviceScope::Approve)
+    };
+
+    let stored = std::fs::read_to_string(&path).unwrap();
+    assert!(!stored.contains(&hex::encode(credential.token)));
+
+    let reloaded = WebAuth::load(&path).unwrap();
+    let principal = reloaded
+        .authenticate_bearer(&credential.to_bearer())
+        .unwrap();
+    assert_eq!(principal.scope, DeviceScope::Approve);
+    assert!(reloaded.has_active_devices());
+}
+
+#[test]
+fn daemon_to_client_events_need_full_control() {
+    use hive_remote::protocol::DaemonEvent;
+    use hive_remote::web_api::event_scope;
+
+    let chunk = DaemonEvent::StreamChunk {
+        conversation_id: "c".into(),
+        chunk: "hi".into(),
+    };
+    assert_eq!(event_scope(&chunk), DeviceScope::Full);
+    let error = DaemonEvent::Error {
+        code: 500,
+        message: "boom".into(),
+    };
+    assert_eq!(event_scope(&error), DeviceScope::Full);
+
+    let message = DaemonEvent::SendMessage {
+        conversation_id: "c".into(),
+        content: "hi".into(),
+        model: "auto".into(),
+    };
+    assert_eq!(event_scope(&message), DeviceScope::Chat);
+    assert_eq!(event_scope(&DaemonEvent::Pong), DeviceScope::Observe);
+}
@@ -1,5 +1,6 @@
 use hive_remote::daemon::{DaemonConfig, HiveDaemon, PendingAction};
-use hive_remote::protocol::{ObserveView, PanelPayload, ShellDestination};
+use hive_remote::protocol::{DaemonEvent, ObserveView, PanelPayload, ShellDestination};
+use hive_remote::session::{Resume, SessionJournal};
 use tempfile::tempdir;
 
 fn make_daemon() -> HiveDaemon {
@@ -125,10 +126,12 @@ fn risky_agent_task_requires_approval_and_returns_pending_action_on_approval() {
 
     assert_eq!(daemon.get_snapshot().pending_approval_count, 1);
 
-    let pending = daemon
-        .apply_approval_decision(&request_id, true, None)
-        .unwrap()
-        .expect("approved request should yield pending action");
+    let outcome = daemon
+        .apply_approval_decision(&request_id, "device-1", true, None)
+        .unwrap();
+    let hive_remote::daemon::ApprovalOutcome::Approved(Some(pending)) = outcome else {
+        panic!("approved request should yield pending action, got {outcome:?}");
+    };
 
     match pending {
         PendingAction::Agent {
@@ -168,6 +171,54 @@ fn journal_replay_restores_last_shell_state() {
     assert_eq!(snapshot.active_panel, "monitor");
 }
 
+#[test]
+fn compacted_journal_restores_shell_state_from_snapshot() {
+    let dir = tempdir().unwrap();
+    let config = DaemonConfig {
+        data_dir: dir.path().to_path_buf(),
+        config_root: Some(dir.path().join("config")),
+        ..DaemonConfig::default()
+    };
+    let journal_path = dir.path().join("session_journal.jsonl");
+
+    {
+        let mut daemon = HiveDaemon::new(config.clone()).unwrap();
+        daemon.switch_destination(ShellDestination::Observe);
+        daemon.set_observe_view(ObserveView::Safety);
+        daemon.compact_journal().unwrap();
+        daemon.switch_panel("monitor");
+    }
+
+    assert!(SessionJournal::snapshot_path(&journal_path).exists());
+    assert!(SessionJournal::rotated_path(&journal_path).exists());
+    assert_eq!(SessionJournal::replay(&journal_path).unwrap().len(), 1);
+
+    let mut replayed = HiveDaemon::new(config).unwrap();
+    replayed.replay_journal().unwrap();
+    let snapshot = replayed.get_snapshot();
+
+    assert_eq!(snapshot.active_destination, ShellDestination::Observe);
+    assert_eq!(snapshot.observe_view, ObserveView::Safety);
+    assert_eq!(snapshot.active_panel, "monitor");
+}
+
+#[test]
+fn broadcast_events_carry_sequence_numbers() {
+    let daemon = make_daemon();
+    let mut rx = daemon.subscribe();
+    let before = daemon.last_event_seq();
+
+    daemon.broadcast_event(DaemonEvent::Ping);
+
+    let event = rx.try_recv().unwrap();
+    assert_eq!(event.seq, before + 1);
+    assert_eq!(daemon.last_event_seq(), event.seq);
+    assert!(matches!(
+        daemon.resume_events(daemon.event_epoch(), before),
+        Resume::Delta(events) if events.len() == 1
+    ));
+}
+
 #[test]
 fn channel_actions_update_selected_channel_and_messages() {
     let mut daemon = make_daemon();
@@ -1,6 +1,7 @@
 use chrono::Utc;
 use hive_remote::pairing::{
-    DevicePermissions, PairedDevice, PairedDeviceStore, PairingKeypair, SessionKeys,
+    DevicePermissions, DeviceScope, PairedDevice, PairedDeviceStore, PairingKeypair,
+    SessionCredential, SessionKeys, token_hash,
 };
 
 // ---------------------------------------------------------------------------
@@ -141,6 +142,9 @@ fn test_paired_device_store_save_load() {
         paired_at: now,
         last_seen: now,
         permissions: DevicePermissions::default(),
+        scope: DeviceScope::default(),
+        token_hash: String::new(),
+        revoked_at: None,
     });
     store.upsert(PairedDevice {
         device_id: "dev-002".into(),
@@ -154,6 +158,9 @@ fn test_paired_device_store_save_load() {
             can_view_files: true,
             can_execute_commands: false,
         },
+        scope: DeviceScope::Chat,
+        token_hash: String::new(),
+        revoked_at: None,
     });
 
     store.save(&path).unwrap();
@@ -181,6 +188,9 @@ fn test_paired_device_store_remove() {
         paired_at: now,
         last_seen: now,
         permissions: DevicePermissions::default(),
+        scope: DeviceScope::default(),
+        token_hash: String::new(),
+        revoked_at: None,
     });
     store.upsert(PairedDevice {
         device_id: "dev-to-keep".into(),
@@ -189,6 +199,9 @@ fn test_paired_device_store_remove() {
         paired_at: now,
         last_seen: now,
         permissions: DevicePermissions::default(),
+        scope: DeviceScope::default(),
+        token_hash: String::new(),
+        revoked_at: None,
     });
 
     assert_eq!(store.devices.len(), 2);
@@ -238,6 +251,9 @@ fn test_paired_device_store_upsert_updates() {
         paired_at: now,
         last_seen: now,
         permissions: DevicePermissions::default(),
+        scope: DeviceScope::default(),
+        token_hash: String::new(),
+        revoked_at: None,
     });
     assert_eq!(store.devices.len(), 1);
     assert_eq!(store.devices[0].name, "Original Name");
@@ -250,6 +266,9 @@ fn test_paired_device_store_upsert_updates() {
         paired_at: now,
         last_seen: now,
         permissions: DevicePermissions::default(),
+        scope: DeviceScope::default(),
+        token_hash: String::new(),
+        revoked_at: None,
     });
     assert_eq!(store.devices.len(), 1);
     assert_eq!(store.devices[0].name, "Updated Name");
@@ -283,6 +302,9 @@ fn test_paired_device_store_find() {
         paired_at: now,
         last_seen: now,
         permissions: DevicePermissions::default(),
+        scope: DeviceScope::default(),
+        token_hash: String::new(),
+        revoked_at: None,
     });
 
     assert!(store.find("find-me").is_some());
@@ -303,3 +325,99 @@ fn test_decrypt_truncated_data() {
     let result = keys.decrypt(&[0u8; 5]);
     assert!(result.is_err());
 }
+
+// ---------------------------------------------------------------------------
+// 14. Device scopes are ordered
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_device_scopes_are_ordered() {
+    assert!(DeviceScope::Full.allows(DeviceScope::Approve));
+    assert!(DeviceScope::Approve.allows(DeviceScope::Chat));
+    assert!(DeviceScope::Chat.allows(DeviceScope::Observe));
+    assert!(!DeviceScope::Observe.allows(DeviceScope::Chat));
+    assert!(!DeviceScope::Approve.allows(DeviceScope::Full));
+    assert_eq!(DeviceScope::default(), DeviceScope::Observe);
+}
+
+// ---------------------------------------------------------------------------
+// 15. Session credentials round-trip and verify against the stored hash
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_session_credential_round_trip() {
+    let keys = SessionKeys::derive(&[9u8; 32], "credential-session");
+    let credential = SessionCredential::from_keys(&keys);
+    assert_eq!(credential.device_id, hex::encode(keys.device_id));
+
+    let parsed = SessionCredential::parse(&credential.to_bearer()).unwrap();
+    assert!(parsed == credential);
+    assert!(SessionCredential::parse("no-dot").is_none());
+    assert!(SessionCredential::parse("dev.nothex").is_none());
+
+    let now = Utc::now();
+    let device = PairedDevice {
+        device_id: credential.device_id.clone(),
+        name: "Phone".into(),
+        public_key_b64: "AAAA".into(),
+        paired_at: now,
+        last_seen: now,
+        permissions: DevicePermissions::default(),
+        scope: DeviceScope::Chat,
+        token_hash: token_hash(&credential.token),
+        revoked_at: None,
+    };
+    assert!(device.verify_token(&credential.token));
+    assert!(!device.verify_token(&[0u8; 32]));
+}
+
+// ---------------------------------------------------------------------------
+// 16. Revoking keeps the record but marks it revoked
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_paired_device_store_revoke() {
+    let mut store = PairedDeviceStore::default();
+    let now = Utc::now();
+    store.upsert(PairedDevice {
+        device_id: "revoke-me".into(),
+        name: "Lost Phone".into(),
+        public_key_b64: "RRRR".into(),
+        paired_at: now,
+        last_seen: now,
+        permissions: DevicePermissions::default(),
+        scope: DeviceScope::Full,
+        token_hash: String::new(),
+        revoked_at: None,
+    });
+
+    assert!(store.revoke("revoke-me"));
+    assert!(store.find("revoke-me").unwrap().is_revoked());
+    assert!(!store.revoke("revoke-me"));
+    assert!(!store.revoke("not-there"));
+}
+
+// ---------------------------------------------------------------------------
+// 17. Stores written before scopes existed still load
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_legacy_device_store_loads_with_defaults() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("devices.json");
+    std::fs::write(
+        &path,
+        r#"{"devices":[{"device_id":"old","name":"Old Phone","public_key_b64":"AAAA",
+            "paired_at":"2025-01-01T00:00:00Z","last_seen":"2025-01-01T00:00:00Z",
+            "permissions":{"can_chat":true,"can_run_agents":false,
+            "can_view_files":true,"can_execute_commands":false}}]}"#,
+    )
+    .unwrap();
+
+    let store = PairedDeviceStore::load(&path).unwrap();
+    let device = store.find("old").unwrap();
+    assert_eq!(device.scope, DeviceScope::Observe);
+    assert!(!device.is_revoked());
+    // No recorded token, so no credential can match.
+    assert!(!device.verify_token(&[0u8; 32]));
+}
@@ -1,5 +1,6 @@
 use hive_remote::protocol::DaemonEvent;
-use hive_remote::session::SessionJournal;
+use hive_remote::session::{EventStream, Resume, SessionJournal, should_journal};
+use std::time::Duration;
 use tempfile::tempdir;
 
 #[tokio::test]
@@ -85,3 +86,100 @@ async fn test_journal_nonexistent_path_creates_file() {
 
     assert!(path.exists());
 }
+
+#[test]
+fn test_journal_needs_compaction_after_entry_limit() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("journal.jsonl");
+
+    let mut journal = SessionJournal::new(&path)
+        .unwrap()
+        .with_compaction_policy(2, Duration::from_secs(3600));
+    assert!(!journal.needs_compaction());
+    journal.append(&DaemonEvent::Ping).unwrap();
+    assert!(!journal.needs_compaction());
+    journal.append(&DaemonEvent::Ping).unwrap();
+    assert!(journal.needs_compaction());
+
+    // Existing entries count towards the limit when the journal is reopened.
+    drop(journal);
+    let reopened = SessionJournal::new(&path)
+        .unwrap()
+        .with_compaction_policy(2, Duration::from_secs(3600));
+    assert!(reopened.needs_compaction());
+}
+
+#[test]
+fn test_journal_snapshot_and_rotated_paths() {
+    let path = std::path::Path::new("/data/session_journal.jsonl");
+    assert_eq!(
+        SessionJournal::snapshot_path(path),
+        std::path::Path::new("/data/session_journal.snapshot.json")
+    );
+    assert_eq!(
+        SessionJournal::rotated_path(path),
+        std::path::Path::new("/data/session_journal.jsonl.1")
+    );
+}
+
+#[test]
+fn test_should_journal_skips_streaming_and_keepalives() {
+    assert!(should_journal(&DaemonEvent::SwitchPanel {
+        panel: "chat".into()
+    }));
+    assert!(!should_journal(&DaemonEvent::Ping));
+    assert!(!should_journal(&DaemonEvent::StreamChunk {
+        conversation_id: "c1".into(),
+        chunk: "hi".into(),
+    }));
+}
+
+#[test]
+fn test_event_stream_assigns_increasing_sequence_numbers() {
+    let stream = EventStream::new(8);
+    let mut rx = stream.subscribe();
+
+    assert_eq!(stream.publish(DaemonEvent::Ping), 1);
+    assert_eq!(stream.publish(DaemonEvent::Pong), 2);
+    assert_eq!(stream.last_seq(), 2);
+    assert_eq!(rx.try_recv().unwrap().seq, 1);
+    assert_eq!(rx.try_recv().unwrap().seq, 2);
+}
+
+#[test]
+fn test_event_stream_resume_returns_missed_delta() {
+    let stream = EventStream::new(8);
+    for _ in 0..5 {
+        stream.publish(DaemonEvent::Ping);
+    }
+
+    match stream.resume(stream.epoch(), 3) {
+        Resume::Delta(events) => {
+            let seqs: Vec<u64> = events.iter().map(|event| event.seq).collect();
+            assert_eq!(seqs, vec![4, 5]);
+        }
+        Resume::Snapshot => panic!("expected a delta"),
+    }
+    assert!(matches!(
+        stream.resume(stream.epoch(), 5),
+        Resume::Delta(events) if events.is_empty()
+    ));
+}
+
+#[test]
+fn test_event_stream_resume_falls_back_to_snapshot() {
+    let stream = EventStream::new(3);
+    for _ in 0..10 {
+        stream.publish(DaemonEvent::Ping);
+    }
+
+    // Only events 8..=10 are still in the backlog.
+    assert!(matches!(stream.resume(stream.epoch(), 7), Resume::Delta(_)));
+    assert!(matches!(stream.resume(stream.epoch(), 6), Resume::Snapshot));
+    // A sequence number from a previous daemon run means nothing here.
+    assert!(matches!(stream.resume("other-epoch", 9), Resume::Snapshot));
+    assert!(matches!(
+        stream.resume(stream.epoch(), 11),
+        Resume::Snapshot
+    ));
+}
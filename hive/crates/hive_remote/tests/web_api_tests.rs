@@ -1,4 +1,10 @@
+use axum::Router;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use hive_remote::auth::WebAuth;
 use hive_remote::daemon::{DaemonConfig, HiveDaemon};
+use hive_remote::pairing::{DeviceScope, PairingKeypair, SessionCredential, SessionKeys};
+use hive_remote::qr::PairingQrPayload;
 use hive_remote::web_api::DaemonState;
 use hive_remote::web_server::build_router;
 use http::Request;
@@ -20,9 +26,49 @@ fn make_daemon() -> DaemonState {
     Arc::new(RwLock::new(HiveDaemon::new(config).unwrap()))
 }
 
+/// Build the router with a paired full-control device whose credential is
+/// attached to every request, so these tests exercise the API itself.
+fn authorized_router(daemon: DaemonState) -> Router {
+    let dir = tempdir().unwrap();
+    let auth = Arc::new(WebAuth::load(&dir.path().join("devices.json")).unwrap());
+    std::mem::forget(dir);
+
+    let invite = auth.create_invite(DeviceScope::Full).unwrap();
+    let payload = PairingQrPayload::from_url(&invite.url).unwrap();
+    let desktop_key: [u8; 32] = BASE64
+        .decode(&payload.public_key_b64)
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let device = PairingKeypair::generate();
+    let keys = SessionKeys::derive(
+        &device.derive_shared_secret(&desktop_key),
+        &payload.session_id,
+    );
+    auth.complete_pairing(
+        &payload.session_id,
+        &BASE64.encode(device.public_key_bytes()),
+        "Test device",
+    )
+    .unwrap();
+    let authorization = format!("Bearer {}", SessionCredential::from_keys(&keys).to_bearer());
+
+    build_router(daemon, auth).layer(axum::middleware::map_request(
+        move |mut request: Request<axum::body::Body>| {
+            let authorization = authorization.clone();
+            async move {
+                request
+                    .headers_mut()
+                    .insert("authorization", authorization.parse().unwrap());
+                request
+            }
+        },
+    ))
+}
+
 #[tokio::test]
 async fn get_state_returns_shell_snapshot() {
-    let app = build_router(make_daemon());
+    let app = authorized_router(make_daemon());
 
     let request = Request::builder()
         .uri("/api/state")
@@ -42,7 +88,7 @@ async fn get_state_returns_shell_snapshot() {
 
 #[tokio::test]
 async fn get_panel_home_returns_typed_payload() {
-    let app = build_router(make_daemon());
+    let app = authorized_router(make_daemon());
 
     let request = Request::builder()
         .uri("/api/panels/home")
@@ -60,7 +106,7 @@ async fn get_panel_home_returns_typed_payload() {
 
 #[tokio::test]
 async fn build_core_panels_return_typed_payloads() {
-    let app = build_router(make_daemon());
+    let app = authorized_router(make_daemon());
 
     for panel in ["history", "files", "specs", "agents", "git_ops", "terminal"] {
         let request = Request::builder()
@@ -80,7 +126,7 @@ async fn build_core_panels_return_typed_payloads() {
 
 #[tokio::test]
 async fn automate_and_assist_panels_return_typed_payloads() {
-    let app = build_router(make_daemon());
+    let app = authorized_router(make_daemon());
 
     for panel in ["workflows", "channels", "network", "assistant"] {
         let request = Request::builder()
@@ -100,7 +146,7 @@ async fn automate_and_assist_panels_return_typed_payloads() {
 
 #[tokio::test]
 async fn utility_panels_return_typed_payloads() {
-    let app = build_router(make_daemon());
+    let app = authorized_router(make_daemon());
 
     for panel in ["settings", "models", "routing", "skills", "launch", "help"] {
         let request = Request::builder()
@@ -120,7 +166,7 @@ async fn utility_panels_return_typed_payloads() {
 
 #[tokio::test]
 async fn post_navigation_switches_destination_and_panel() {
-    let app = build_router(make_daemon());
+    let app = authorized_router(make_daemon());
 
     let payload = serde_json::json!({
         "destination": "observe",
@@ -145,7 +191,7 @@ async fn post_navigation_switches_destination_and_panel() {
 
 #[tokio::test]
 async fn post_home_launch_moves_into_chat() {
-    let app = build_router(make_daemon());
+    let app = authorized_router(make_daemon());
 
     let payload = serde_json::json!({
         "template_id": "resume",
@@ -171,7 +217,7 @@ async fn post_home_launch_moves_into_chat() {
 #[tokio::test]
 async fn post_chat_sets_active_conversation() {
     let daemon = make_daemon();
-    let app = build_router(daemon.clone());
+    let app = authorized_router(daemon.clone());
 
     let payload = serde_json::json!({
         "conversation_id": "conv-42",
@@ -199,7 +245,7 @@ async fn post_chat_sets_active_conversation() {
 
 #[tokio::test]
 async fn approvals_can_be_resolved_over_http() {
-    let app = build_router(make_daemon());
+    let app = authorized_router(make_daemon());
 
     let agent_payload = serde_json::json!({
         "goal": "Deploy the latest release",
@@ -238,7 +284,7 @@ async fn approvals_can_be_resolved_over_http() {
 
 #[tokio::test]
 async fn terminal_lifecycle_is_available_over_http() {
-    let app = build_router(make_daemon());
+    let app = authorized_router(make_daemon());
 
     let start = Request::builder()
         .uri("/api/terminal/start")
@@ -263,7 +309,7 @@ async fn terminal_lifecycle_is_available_over_http() {
 
 #[tokio::test]
 async fn workflow_run_endpoint_starts_automate_flow() {
-    let app = build_router(make_daemon());
+    let app = authorized_router(make_daemon());
 
     let get_workflows = Request::builder()
         .uri("/api/panels/workflows")
@@ -297,7 +343,7 @@ async fn workflow_run_endpoint_starts_automate_flow() {
 
 #[tokio::test]
 async fn channel_select_and_message_routes_update_channel_state() {
-    let app = build_router(make_daemon());
+    let app = authorized_router(make_daemon());
 
     let get_channels = Request::builder()
         .uri("/api/panels/channels")
@@ -362,7 +408,7 @@ async fn channel_select_and_message_routes_update_channel_state() {
 
 #[tokio::test]
 async fn utility_mutation_routes_update_panel_state() {
-    let app = build_router(make_daemon());
+    let app = authorized_router(make_daemon());
 
     let update_setting = Request::builder()
         .uri("/api/settings/update")
@@ -28,8 +28,23 @@ const state = {
     pendingConversationId: null,
     optimisticMessages: [],
     reconnectDelay: 1000,
+    sync: {
+        epoch: null,
+        seq: 0,
+    },
+    auth: {
+        paired: false,
+        csrfToken: null,
+        device: null,
+        pairingLink: "",
+        deviceName: "",
+        pairing: false,
+    },
 };
 
+const CREDENTIAL_KEY = "hive.remote.credential";
+const SESSION_KEY_INFO = "hive-remote-session-keys-v1";
+
 let socket = null;
 let reconnectTimer = null;
 let pingTimer = null;
@@ -96,22 +111,125 @@ function uuid4() {
 }
 
 async function api(path, options) {
-    const init = { method: "GET", ...options };
+    const init = { method: "GET", credentials: "same-origin", ...options };
     init.headers = {
         Accept: "application/json",
         ...(options && options.body ? { "Content-Type": "application/json" } : {}),
+        ...(init.method !== "GET" && state.auth.csrfToken ? { "X-Hive-CSRF": state.auth.csrfToken } : {}),
         ...(options && options.headers ? options.headers : {}),
     };
 
     const response = await fetch(path, init);
     const text = await response.text();
     const data = text ? JSON.parse(text) : null;
+    if (response.status === 401 && state.auth.paired) {
+        state.auth.paired = false;
+        state.auth.csrfToken = null;
+        render();
+    }
     if (!response.ok) {
         throw new Error((data && data.error) || `Request failed (${response.status})`);
     }
     return data;
 }
 
+function toHex(bytes) {
+    return Array.from(bytes, (byte) => byte.toString(16).padStart(2, "0")).join("");
+}
+
+function fromBase64(value) {
+    return Uint8Array.from(atob(value), (char) => char.charCodeAt(0));
+}
+
+function toBase64(bytes) {
+    return btoa(String.fromCharCode(...bytes));
+}
+
+// Exchange a paired device credential for an HttpOnly session cookie.
+async function openBrowserSession(credential) {
+    const session = await api("/api/session", {
+        method: "POST",
+        headers: { Authorization: `Bearer ${credential}` },
+    });
+    state.auth.paired = true;
+    state.auth.csrfToken = session.csrf_token;
+    state.auth.device = session;
+}
+
+async function ensureSession() {
+    try {
+        const session = await api("/api/session");
+        if (session.csrf_token) {
+            state.auth.paired = true;
+            state.auth.csrfToken = session.csrf_token;
+            state.auth.device = session;
+            return true;
+        }
+    } catch (_error) {
+        // No live session cookie; fall back to the stored credential.
+    }
+
+    const credential = localStorage.getItem(CREDENTIAL_KEY);
+    if (credential) {
+        try {
+            await openBrowserSession(credential);
+            return true;
+        } catch (_error) {
+            localStorage.removeItem(CREDENTIAL_KEY);
+        }
+    }
+    state.auth.paired = false;
+    return false;
+}
+
+// Complete the X25519 pairing handshake for a hive://pair link. Both sides
+// derive the same credential, so only our public key is sent.
+async function pairDevice(link, name) {
+    if (!window.crypto || !window.crypto.subtle) {
+        throw new Error("Pairing from a browser needs HTTPS or localhost.");
+    }
+    const query = link.trim().replace(/^hive:\/\/pair\?/, "");
+    const params = new URLSearchParams(query);
+    const sessionId = params.get("id");
+    const desktopKey = params.get("pk");
+    if (!sessionId || !desktopKey) {
+        throw new Error("That is not a Hive pairing link.");
+    }
+
+    const subtle = window.crypto.subtle;
+    const keyPair = await subtle.generateKey({ name: "X25519" }, true, ["deriveBits"]);
+    const publicKey = await subtle.importKey("raw", fromBase64(desktopKey), { name: "X25519" }, true, []);
+    const shared = await subtle.deriveBits({ name: "X25519", public: publicKey }, keyPair.privateKey, 256);
+    const hkdfKey = await subtle.importKey("raw", shared, "HKDF", false, ["deriveBits"]);
+    const encoder = new TextEncoder();
+    const material = new Uint8Array(
+        await subtle.deriveBits(
+            {
+                name: "HKDF",
+                hash: "SHA-256",
+                salt: encoder.encode(sessionId),
+                info: encoder.encode(SESSION_KEY_INFO),
+            },
+            hkdfKey,
+            640,
+        ),
+    );
+    const ownKey = new Uint8Array(await subtle.exportKey("raw", keyPair.publicKey));
+
+    await api("/api/pair", {
+        method: "POST",
+        body: JSON.stringify({
+            session_id: sessionId,
+            public_key_b64: toBase64(ownKey),
+            name: name || "Browser",
+        }),
+    });
+
+    const credential = `${toHex(material.slice(64, 80))}.${toHex(material.slice(32, 64))}`;
+    localStorage.setItem(CREDENTIAL_KEY, credential);
+    await openBrowserSession(credential);
+}
+
 function showToast(message, tone) {
     let container = document.querySelector(".toast-stack");
     if (!container) {
@@ -168,7 +286,12 @@ async function ensurePanels(panelIds) {
 
 function connectSocket() {
     const protocol = location.protocol === "https:" ? "wss:" : "ws:";
-    socket = new WebSocket(`${protocol}//${location.host}/ws`);
+    const csrf = encodeURIComponent(state.auth.csrfToken || "");
+    let resume = "";
+    if (state.sync.epoch) {
+        resume = `&epoch=${encodeURIComponent(state.sync.epoch)}&since=${state.sync.seq}`;
+    }
+    socket = new WebSocket(`${protocol}//${location.host}/ws?csrf=${csrf}${resume}`);
 
     socket.addEventListener("open", () => {
         state.connected = true;
@@ -192,9 +315,13 @@ function connectSocket() {
 
 function scheduleReconnect() {
     if (reconnectTimer) return;
-    reconnectTimer = window.setTimeout(() => {
+    reconnectTimer = window.setTimeout(async () => {
         reconnectTimer = null;
-        connectSocket();
+        if (await ensureSession()) {
+            connectSocket();
+        } else {
+            render();
+        }
     }, state.reconnectDelay);
     state.reconnectDelay = Math.min(state.reconnectDelay * 2, 30000);
 }
@@ -216,7 +343,16 @@ function stopPing() {
 }
 
 async function handleSocketEvent(event) {
+    if (typeof event.seq === "number") {
+        state.sync.seq = Math.max(state.sync.seq, event.seq);
+    }
     switch (event.type) {
+        case "sync_started":
+            state.sync = { epoch: event.epoch, seq: event.last_seq };
+            if (event.snapshot) {
+                state.streaming = { conversationId: null, content: "" };
+            }
+            break;
         case "state_snapshot":
             syncSnapshot(event);
             ensurePanels(["home", "observe", "chat", event.active_panel]);
@@ -320,7 +456,32 @@ function isSupportedPanel(panel) {
     return !!panel?.supported;
 }
 
+function renderPairing() {
+    return `
+        <div class="loading-shell">
+            <div class="empty-state">
+                <div class="empty-mark"></div>
+                <div class="eyebrow">Pair this device</div>
+                <h2>Connect to Hive</h2>
+                <p>Paste the pairing link shown by Hive on your desktop. Links expire after ten minutes.</p>
+                <div class="stack-list">
+                    <label class="field-label" for="pairing-link">Pairing link</label>
+                    <textarea id="pairing-link" placeholder="hive://pair?id=...">${esc(state.auth.pairingLink)}</textarea>
+                    <label class="field-label" for="pairing-name">Device name</label>
+                    <textarea id="pairing-name" placeholder="My phone">${esc(state.auth.deviceName)}</textarea>
+                    <button class="primary-button" data-action="pair-device" ${state.auth.pairing ? "disabled" : ""}>
+                        ${state.auth.pairing ? "Pairing..." : "Pair device"}
+                    </button>
+                </div>
+            </div>
+        </div>
+    `;
+}
+
 function renderApp() {
+    if (!state.loading && !state.auth.paired) {
+        return renderPairing();
+    }
     if (state.loading || !state.snapshot) {
         return `
             <div class="loading-shell">
@@ -2542,6 +2703,15 @@ function autoResize(element) {
 
 async function boot() {
     render();
+    if (location.hash.startsWith("#pair=")) {
+        state.auth.pairingLink = decodeURIComponent(location.hash.slice("#pair=".length));
+        history.replaceState(null, "", location.pathname);
+    }
+    if (!(await ensureSession())) {
+        state.loading = false;
+        render();
+        return;
+    }
     try {
         await loadState();
         await ensurePanels(["home", "observe", "chat", state.snapshot.active_panel]);
@@ -2562,6 +2732,19 @@ document.addEventListener("click", async (event) => {
 
     try {
         switch (action) {
+            case "pair-device":
+                state.auth.pairing = true;
+                render();
+                try {
+                    await pairDevice(state.auth.pairingLink, state.auth.deviceName.trim());
+                    state.auth.pairingLink = "";
+                    state.loading = true;
+                    await boot();
+                } finally {
+                    state.auth.pairing = false;
+                    render();
+                }
+                break;
             case "toggle-utility":
                 state.utilityOpen = !state.utilityOpen;
                 render();
@@ -2961,6 +3144,12 @@ document.addEventListener("click", async (event) => {
 });
 
 document.addEventListener("input", (event) => {
+    if (event.target.id === "pairing-link") {
+        state.auth.pairingLink = event.target.value;
+    }
+    if (event.target.id === "pairing-name") {
+        state.auth.deviceName = event.target.value;
+    }
     if (event.target.id === "home-detail-input") {
         state.homeDetail = event.target.value;
         autoResize(event.target);
@@ -10,33 +10,7 @@ use tracing::debug;
 // Public types
 // ---------------------------------------------------------------------------
 
-/// Result status for a single doctor health check.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum CheckStatus {
-    Pass,
-    Warn,
-    Fail,
-}
-
-impl std::fmt::Display for CheckStatus {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            CheckStatus::Pass => write!(f, "PASS"),
-            CheckStatus::Warn => write!(f, "WARN"),
-            CheckStatus::Fail => write!(f, "FAIL"),
-        }
-    }
-}
-
-/// A single health-check result produced by the doctor command.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DoctorCheck {
-    pub name: String,
-    pub description: String,
-    pub status: CheckStatus,
-    pub message: String,
-    pub fix_suggestion: Option<String>,
-}
+pub use hive_core::diagnostics::{CheckStatus, DoctorCheck, DoctorSummary};
 
 /// A registered CLI command.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,19 +39,6 @@ pub struct CliOutput {
     pub exit_code: i32,
 }
 
-// ---------------------------------------------------------------------------
-// DoctorSummary
-// ---------------------------------------------------------------------------
-
-/// Summarised pass/warn/fail counts from a doctor run.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DoctorSummary {
-    pub pass: usize,
-    pub warn: usize,
-    pub fail: usize,
-    pub total: usize,
-}
-
 // ---------------------------------------------------------------------------
 // CliService
 // ---------------------------------------------------------------------------
@@ -259,6 +220,10 @@ impl CliService {
     // -- Doctor checks -------------------------------------------------------
 
     /// Run all built-in doctor health checks and return the results.
+    ///
+    /// `hive doctor` runs a wider set through
+    /// [`hive_core::diagnostics::DiagnosticRegistry`]; see
+    /// [`crate::diagnostics`] for the checks this crate contributes there.
     pub fn run_doctor(&self) -> Vec<DoctorCheck> {
         let checks = vec![
             self.check_config_file(),
@@ -274,27 +239,10 @@ impl CliService {
 
     /// Produce a summary of pass/warn/fail counts from a set of checks.
     pub fn doctor_summary(checks: &[DoctorCheck]) -> DoctorSummary {
-        let pass = checks
-            .iter()
-            .filter(|c| c.status == CheckStatus::Pass)
-            .count();
-        let warn = checks
-            .iter()
-            .filter(|c| c.status == CheckStatus::Warn)
-            .count();
-        let fail = checks
-            .iter()
-            .filter(|c| c.status == CheckStatus::Fail)
-            .count();
-        DoctorSummary {
-            pass,
-            warn,
-            fail,
-            total: checks.len(),
-        }
+        DoctorSummary::from_checks(checks)
     }
 
-    // -- Individual checks (private) -----------------------------------------
+    // -- Individual checks ---------------------------------------------------
 
     fn hive_dir() -> PathBuf {
         dirs::home_dir()
@@ -348,7 +296,7 @@ impl CliService {
         }
     }
 
-    fn check_git_available(&self) -> DoctorCheck {
+    pub(crate) fn check_git_available(&self) -> DoctorCheck {
         let security = SecurityGateway::new();
         if let Err(reason) = security.check_command("git --version") {
             return DoctorCheck {
@@ -388,7 +336,7 @@ impl CliService {
         }
     }
 
-    fn check_disk_space(&self) -> DoctorCheck {
+    pub(crate) fn check_disk_space(&self) -> DoctorCheck {
         #[cfg(unix)]
         {
             // Check available disk space on the Hive data directory or current dir.
@@ -512,7 +460,7 @@ impl CliService {
         }
     }
 
-    fn check_network(&self) -> DoctorCheck {
+    pub(crate) fn check_network(&self) -> DoctorCheck {
         let target = std::net::SocketAddr::from(([1, 1, 1, 1], 443));
         let timeout = std::time::Duration::from_secs(2);
 
@@ -0,0 +1,1360 @@
+//! Dev container support for agent sandboxes.
+//!
+//! Reads a project's `.devcontainer/devcontainer.json` and turns it into an
+//! [`EnvironmentPlan`] that [`AgentSandbox`](crate::AgentSandbox) brings up:
+//! the image to run or build, compose services to start alongside it,
+//! environment variables, forwarded ports and the setup commands to run
+//! once the container exists.
+//!
+//! Only a subset of the spec is understood. Features are mapped to install
+//! commands for a handful of common toolchains; anything else is skipped
+//! with a warning.
+//!
+//! Compose files are only used when `devcontainer.json` names them, and
+//! they are sanitized first ([`sanitize_compose`]): settings that give a
+//! container host access — privileges, capabilities, devices, host
+//! namespaces, bind mounts outside the workspace — are dropped.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use tracing::{debug, warn};
+
+/// Where dev container configs are looked for, relative to the workspace.
+const DEVCONTAINER_PATHS: &[&str] = &[".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+/// Workspace mount point when the config does not set `workspaceFolder`.
+pub const DEFAULT_WORKSPACE_FOLDER: &str = "/workspace";
+
+// ---------------------------------------------------------------------------
+// devcontainer.json
+// ---------------------------------------------------------------------------
+
+/// The parts of `devcontainer.json` the sandbox understands.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevContainerConfig {
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub build: Option<BuildConfig>,
+    pub docker_compose_file: Option<OneOrMany>,
+    /// Compose service the agent runs in.
+    pub service: Option<String>,
+    /// Compose services to start; all of them when unset.
+    pub run_services: Option<Vec<String>>,
+    pub workspace_folder: Option<String>,
+    #[serde(default)]
+    pub features: HashMap<String, serde_json::Value>,
+    pub on_create_command: Option<LifecycleCommand>,
+    pub post_create_command: Option<LifecycleCommand>,
+    #[serde(default)]
+    pub forward_ports: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub container_env: HashMap<String, String>,
+    #[serde(default)]
+    pub remote_env: HashMap<String, String>,
+    pub remote_user: Option<String>,
+}
+
+/// `build` section of `devcontainer.json`. Paths are relative to the
+/// config file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildConfig {
+    pub dockerfile: Option<String>,
+    pub context: Option<String>,
+    #[serde(default)]
+    pub args: HashMap<String, String>,
+    pub target: Option<String>,
+}
+
+/// A value that may be a single string or a list of them.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    pub fn to_vec(&self) -> Vec<String> {
+        match self {
+            Self::One(value) => vec![value.clone()],
+            Self::Many(values) => values.clone(),
+        }
+    }
+}
+
+/// A lifecycle command: a shell string, an argv list, or named commands
+/// run in parallel (run one after another here).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum LifecycleCommand {
+    Shell(String),
+    Args(Vec<String>),
+    Named(HashMap<String, LifecycleCommand>),
+}
+
+impl LifecycleCommand {
+    /// The command as shell command lines.
+    pub fn to_shell(&self) -> Vec<String> {
+        match self {
+            Self::Shell(command) => vec![command.clone()],
+            Self::Args(args) => vec![
+                args.iter()
+                    .map(|arg| shell_quote(arg))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ],
+            Self::Named(commands) => {
+                let mut names: Vec<_> = commands.keys().collect();
+                names.sort();
+                names
+                    .into_iter()
+                    .flat_map(|name| commands[name].to_shell())
+                    .collect()
+            }
+        }
+    }
+}
+
+impl DevContainerConfig {
+    /// Parse a `devcontainer.json`, which may contain comments and
+    /// trailing commas.
+    pub fn parse(text: &str) -> Result<Self> {
+        serde_json::from_str(&strip_jsonc(text)).context("Invalid devcontainer.json")
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Find and load the workspace's dev container config, returning the
+    /// config file path with it.
+    pub fn discover(workspace: &Path) -> Result<Option<(PathBuf, Self)>> {
+        for relative in DEVCONTAINER_PATHS {
+            let path = workspace.join(relative);
+            if path.is_file() {
+                return Ok(Some((path.clone(), Self::load(&path)?)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Forwarded container ports. `"service:port"` entries are skipped;
+    /// those ports belong to other compose services.
+    pub fn ports(&self) -> Vec<u16> {
+        self.forward_ports
+            .iter()
+            .filter_map(|port| match port {
+                serde_json::Value::Number(n) => n.as_u64().and_then(|n| u16::try_from(n).ok()),
+                serde_json::Value::String(s) => s.parse().ok(),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Remove `//` and `/* */` comments and trailing commas from JSONC.
+fn strip_jsonc(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            (',', _) => {
+                // Drop the comma if only whitespace/comments precede a closer.
+                let rest: String = chars.clone().collect();
+                let next = strip_jsonc_lookahead(&rest);
+                if !matches!(next, Some('}' | ']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// First significant character of `rest`, skipping whitespace and comments.
+fn strip_jsonc_lookahead(rest: &str) -> Option<char> {
+    let mut rest = rest.trim_start();
+    loop {
+        if let Some(after) = rest.strip_prefix("//") {
+            rest = after
+                .split_once('\n')
+                .map_or("", |(_, tail)| tail)
+                .trim_start();
+        } else if let Some(after) = rest.strip_prefix("/*") {
+            rest = after
+                .split_once("*/")
+                .map_or("", |(_, tail)| tail)
+                .trim_start();
+        } else {
+            return rest.chars().next();
+        }
+    }
+}
+
+fn shell_quote(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@,+".contains(c))
+    {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Features
+// ---------------------------------------------------------------------------
+
+/// Install command for a dev container feature, for the features the
+/// sandbox knows how to provide. `None` for anything else.
+pub fn feature_install_command(id: &str, options: &serde_json::Value) -> Option<String> {
+    // "ghcr.io/devcontainers/features/node:1" -> "node"
+    let name = id
+        .rsplit('/')
+        .next()
+        .unwrap_or(id)
+        .split([':', '@'])
+        .next()
+        .unwrap_or(id);
+    let version = options
+        .get("version")
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.is_empty() && *v != "latest" && *v != "lts");
+
+    let command = match name {
+        "git" => apt_install("git"),
+        "common-utils" => apt_install("curl ca-certificates sudo"),
+        "node" => format!(
+            "{} && curl -fsSL https://deb.nodesource.com/setup_{}.x | bash - && apt-get install -y nodejs",
+            apt_install("curl ca-certificates"),
+            version.map_or("lts", |v| v.split('.').next().unwrap_or(v))
+        ),
+        "python" => apt_install("python3 python3-pip python3-venv"),
+        "rust" => format!(
+            "{} && curl -fsSL https://sh.rustup.rs | sh -s -- -y --profile minimal --default-toolchain {}",
+            apt_install("curl ca-certificates build-essential"),
+            version.unwrap_or("stable")
+        ),
+        "go" => format!(
+            "{} && curl -fsSL https://go.dev/dl/go{}.linux-amd64.tar.gz | tar -C /usr/local -xz",
+            apt_install("curl ca-certificates"),
+            version.unwrap_or("1.22.5")
+        ),
+        _ => return None,
+    };
+    Some(command)
+}
+
+fn apt_install(packages: &str) -> String {
+    format!("apt-get update && DEBIAN_FRONTEND=noninteractive apt-get install -y {packages}")
+}
+
+// ---------------------------------------------------------------------------
+// EnvironmentPlan
+// ---------------------------------------------------------------------------
+
+/// What the agent's own container runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrimaryContainer {
+    /// Run this image.
+    Image(String),
+    /// Build an image first.
+    Build {
+        dockerfile: PathBuf,
+        context: PathBuf,
+        args: HashMap<String, String>,
+        target: Option<String>,
+    },
+    /// Exec into this service of the compose project.
+    ComposeService(String),
+}
+
+/// Compose services started with the sandbox.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComposePlan {
+    pub files: Vec<PathBuf>,
+    /// Services to start; empty means all.
+    pub services: Vec<String>,
+}
+
+/// A resolved sandbox environment for a workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvironmentPlan {
+    /// Config file the plan came from, if any.
+    pub source: Option<PathBuf>,
+    pub primary: PrimaryContainer,
+    pub compose: Option<ComposePlan>,
+    pub workspace_folder: String,
+    pub env: HashMap<String, String>,
+    pub forward_ports: Vec<u16>,
+    pub remote_user: Option<String>,
+    /// Feature installs followed by `onCreateCommand` and
+    /// `postCreateCommand`, run once the container is up.
+    pub setup_commands: Vec<String>,
+}
+
+impl EnvironmentPlan {
+    /// A plain container from `image` with nothing else.
+    pub fn image(image: &str) -> Self {
+        Self {
+            source: None,
+            primary: PrimaryContainer::Image(image.to_string()),
+            compose: None,
+            workspace_folder: DEFAULT_WORKSPACE_FOLDER.to_string(),
+            env: HashMap::new(),
+            forward_ports: Vec::new(),
+            remote_user: None,
+            setup_commands: Vec::new(),
+        }
+    }
+
+    /// Work out the environment for `workspace`: its dev container config
+    /// if it has one, otherwise just `fallback_image`. A compose file
+    /// without a `devcontainer.json` naming it is not started.
+    pub fn resolve(workspace: &Path, fallback_image: &str) -> Result<Self> {
+        match DevContainerConfig::discover(workspace)? {
+            Some((path, config)) => {
+                Self::from_devcontainer(workspace, &path, &config, fallback_image)
+            }
+            None => Ok(Self::image(fallback_image)),
+        }
+    }
+
+    /// Plan from a loaded `devcontainer.json` at `path` in `workspace`.
+    /// A `build` whose Dockerfile or context lies outside the workspace is
+    /// refused, since the whole context is sent to the Docker daemon.
+    pub fn from_devcontainer(
+        workspace: &Path,
+        path: &Path,
+        config: &DevContainerConfig,
+        fallback_image: &str,
+    ) -> Result<Self> {
+        let config_dir = path.parent().unwrap_or(Path::new("."));
+
+        let (primary, compose) = if let Some(files) = &config.docker_compose_file {
+            let Some(service) = &config.service else {
+                bail!(
+                    "{} uses dockerComposeFile but does not name a service",
+                    path.display()
+                );
+            };
+            let mut services = config.run_services.clone().unwrap_or_default();
+            if !services.is_empty() && !services.contains(service) {
+                services.push(service.clone());
+            }
+            let compose = ComposePlan {
+                files: files.to_vec().iter().map(|f| config_dir.join(f)).collect(),
+                services,
+            };
+            (
+                PrimaryContainer::ComposeService(service.clone()),
+                Some(compose),
+            )
+        } else if let Some(build) = config
+            .build
+            .as_ref()
+            .filter(|build| build.dockerfile.is_some())
+        {
+            let dockerfile = contained_path(
+                workspace,
+                &config_dir.join(build.dockerfile.as_deref().unwrap_or("Dockerfile")),
+            )?;
+            let context = contained_path(
+                workspace,
+                &config_dir.join(build.context.as_deref().unwrap_or(".")),
+            )?;
+            let primary = PrimaryContainer::Build {
+                dockerfile,
+                context,
+                args: build.args.clone(),
+                target: build.target.clone(),
+            };
+            (primary, None)
+        } else {
+            let image = config.image.as_deref().unwrap_or(fallback_image);
+            (PrimaryContainer::Image(image.to_string()), None)
+        };
+
+        let mut features: Vec<_> = config.features.iter().collect();
+        features.sort_by(|a, b| a.0.cmp(b.0));
+        let mut setup_commands = Vec::new();
+        for (id, options) in features {
+            match feature_install_command(id, options) {
+                Some(command) => setup_commands.push(command),
+                None => warn!(feature = %id, "unsupported dev container feature; skipping"),
+            }
+        }
+        for command in [&config.on_create_command, &config.post_create_command]
+            .into_iter()
+            .flatten()
+        {
+            setup_commands.extend(command.to_shell());
+        }
+
+        let mut env = config.container_env.clone();
+        env.extend(config.remote_env.clone());
+
+        Ok(Self {
+            source: Some(path.to_path_buf()),
+            primary,
+            compose,
+            workspace_folder: config
+                .workspace_folder
+                .clone()
+                .unwrap_or_else(|| DEFAULT_WORKSPACE_FOLDER.to_string()),
+            env,
+            forward_ports: config.ports(),
+            remote_user: config.remote_user.clone(),
+            setup_commands,
+        })
+    }
+}
+
+/// `path` resolved through symlinks, refused unless it stays inside
+/// `workspace`.
+fn contained_path(workspace: &Path, path: &Path) -> Result<PathBuf> {
+    let workspace = workspace
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", workspace.display()))?;
+    let resolved = path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", path.display()))?;
+    if !resolved.starts_with(&workspace) {
+        bail!("{} is outside the workspace", path.display());
+    }
+    Ok(resolved)
+}
+
+// ---------------------------------------------------------------------------
+// Docker / compose commands
+// ---------------------------------------------------------------------------
+
+fn run(command: &mut Command, what: &str) -> Result<String> {
+    let output = command
+        .output()
+        .with_context(|| format!("Failed to run {what}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("{what} failed: {}", stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Build an image from a dev container `build` section and return its tag.
+pub fn build_image(
+    tag: &str,
+    dockerfile: &Path,
+    context: &Path,
+    args: &HashMap<String, String>,
+    target: Option<&str>,
+) -> Result<String> {
+    let mut command = Command::new("docker");
+    command
+        .arg("build")
+        .arg("-t")
+        .arg(tag)
+        .arg("-f")
+        .arg(dockerfile);
+    let mut args: Vec<_> = args.iter().collect();
+    args.sort();
+    for (key, value) in args {
+        command.arg("--build-arg").arg(format!("{key}={value}"));
+    }
+    if let Some(target) = target {
+        command.arg("--target").arg(target);
+    }
+    command.arg(context);
+    run(&mut command, "docker build")?;
+    debug!(tag, "built dev container image");
+    Ok(tag.to_string())
+}
+
+/// A compose project started for one sandbox.
+#[derive(Debug, Clone)]
+pub struct ComposeProject {
+    pub name: String,
+    pub files: Vec<PathBuf>,
+    /// Directory relative paths in `files` resolve against, when the files
+    /// are sanitized copies living elsewhere.
+    pub project_dir: Option<PathBuf>,
+}
+
+impl ComposeProject {
+    /// Sanitize `plan`'s compose files for `workspace` and write the copies
+    /// to a scratch directory for this project. Fails if a file lies
+    /// outside the workspace or uses a feature that cannot be sanitized.
+    pub fn sanitized(
+        name: &str,
+        plan: &ComposePlan,
+        workspace: &Path,
+        network_enabled: bool,
+    ) -> Result<Self> {
+        let workspace = workspace
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve {}", workspace.display()))?;
+        let scratch = std::env::temp_dir().join("hive-compose").join(name);
+        std::fs::create_dir_all(&scratch)
+            .with_context(|| format!("Failed to create {}", scratch.display()))?;
+
+        // Compose resolves relative paths in every file against the first.
+        let mut project_dir = None;
+        let mut files = Vec::new();
+        for (index, file) in plan.files.iter().enumerate() {
+            let resolved = file
+                .canonicalize()
+                .with_context(|| format!("Failed to resolve {}", file.display()))?;
+            if !resolved.starts_with(&workspace) {
+                bail!("Compose file {} is outside the workspace", file.display());
+            }
+            let dir = project_dir
+                .get_or_insert_with(|| resolved.parent().unwrap_or(&workspace).to_path_buf());
+            let text = std::fs::read_to_string(&resolved)
+                .with_context(|| format!("Failed to read {}", resolved.display()))?;
+            let sanitized = sanitize_compose(&text, dir, &workspace, network_enabled)
+                .with_context(|| format!("Cannot use {} in a sandbox", resolved.display()))?;
+            let copy = scratch.join(format!("{index}-compose.yaml"));
+            std::fs::write(&copy, sanitized)
+                .with_context(|| format!("Failed to write {}", copy.display()))?;
+            files.push(copy);
+        }
+        Ok(Self {
+            name: name.to_string(),
+            files,
+            project_dir,
+        })
+    }
+
+    fn command(&self) -> Command {
+        let mut command = Command::new("docker");
+        command.arg("compose").arg("-p").arg(&self.name);
+        if let Some(ref dir) = self.project_dir {
+            command.arg("--project-directory").arg(dir);
+        }
+        for file in &self.files {
+            command.arg("-f").arg(file);
+        }
+        command
+    }
+
+    /// Delete the sanitized copies written by [`Self::sanitized`].
+    pub fn remove_files(&self) {
+        for file in &self.files {
+            let _ = std::fs::remove_file(file);
+        }
+        if let Some(dir) = self.files.first().and_then(|file| file.parent()) {
+            let _ = std::fs::remove_dir(dir);
+        }
+    }
+
+    /// Start `services` (all when empty) in the background.
+    pub fn up(&self, services: &[String]) -> Result<()> {
+        let mut command = self.command();
+        command.args(["up", "-d", "--build"]).args(services);
+        run(&mut command, "docker compose up")?;
+        debug!(project = %self.name, "compose project started");
+        Ok(())
+    }
+
+    /// Container id of a running service.
+    pub fn service_container(&self, service: &str) -> Result<String> {
+        let mut command = self.command();
+        command.args(["ps", "-q", service]);
+        let id = run(&mut command, "docker compose ps")?;
+        match id.lines().next() {
+            Some(id) if !id.is_empty() => Ok(id.to_string()),
+            _ => bail!("Compose service '{service}' is not running"),
+        }
+    }
+
+    /// The project's default network, for attaching other containers.
+    pub fn default_network(&self) -> String {
+        format!("{}_default", self.name)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Compose sanitizing
+// ---------------------------------------------------------------------------
+
+/// Service keys that grant privileges or host devices; always dropped.
+const PRIVILEGE_KEYS: &[&str] = &["privileged", "cap_add", "devices", "security_opt"];
+
+/// Service keys that share a host namespace when set to `host`.
+const HOST_NAMESPACE_KEYS: &[&str] = &["pid", "ipc", "uts", "userns_mode", "cgroup"];
+
+/// Make a compose file safe to start from an untrusted repository.
+///
+/// Drops privileged mode, added capabilities, devices, security options,
+/// host namespaces, `container:` network and volume sharing, and any bind
+/// mount, `env_file`, secret or config file outside `workspace`. Named
+/// volumes lose their `driver_opts`, which can bind host paths. Published
+/// ports are rebound to `127.0.0.1`. With `network_enabled` off every
+/// network becomes internal-only. Relative paths resolve against
+/// `project_dir`. `include`, cross-file `extends` and builds from outside
+/// the workspace would pull in unsanitized services or host files, so they
+/// are rejected.
+pub fn sanitize_compose(
+    text: &str,
+    project_dir: &Path,
+    workspace: &Path,
+    network_enabled: bool,
+) -> Result<String> {
+    let mut doc: Value = serde_yaml::from_str(text).context("Invalid compose file")?;
+    let Some(root) = doc.as_mapping_mut() else {
+        bail!("Compose file is not a mapping");
+    };
+    if root.contains_key("include") {
+        bail!("`include` is not supported");
+    }
+    let allowed = |source: &str| path_in_workspace(source, project_dir, workspace);
+
+    if let Some(services) = root.get_mut("services").and_then(Value::as_mapping_mut) {
+        for (name, service) in services.iter_mut() {
+            let name = name.as_str().unwrap_or_default().to_string();
+            let Some(service) = service.as_mapping_mut() else {
+                continue;
+            };
+            if service
+                .get("extends")
+                .is_some_and(|extends| extends.get("file").is_some())
+            {
+                bail!("service '{name}' extends a service from another file");
+            }
+            check_build(&name, service, project_dir, workspace)?;
+            sanitize_service(&name, service, network_enabled, &allowed);
+        }
+    }
+
+    for section in ["secrets", "configs"] {
+        if let Some(entries) = root.get_mut(section).and_then(Value::as_mapping_mut) {
+            entries.retain(|key, entry| {
+                let keep = entry
+                    .get("file")
+                    .and_then(Value::as_str)
+                    .is_none_or(&allowed);
+                if !keep {
+                    warn!(section, name = ?key, "dropped host file from sandbox compose file");
+                }
+                keep
+            });
+        }
+    }
+
+    if let Some(volumes) = root.get_mut("volumes").and_then(Value::as_mapping_mut) {
+        for (_, volume) in volumes.iter_mut() {
+            if let Some(volume) = volume.as_mapping_mut() {
+                volume.remove("driver_opts");
+            }
+        }
+    }
+
+    if !network_enabled {
+        let networks = root
+            .entry("networks".into())
+            .or_insert_with(|| Value::Mapping(Mapping::new()));
+        if networks.is_null() {
+            *networks = Value::Mapping(Mapping::new());
+        }
+        let Some(networks) = networks.as_mapping_mut() else {
+            bail!("`networks` is not a mapping");
+        };
+        if !networks.contains_key("default") {
+            networks.insert("default".into(), Value::Null);
+        }
+        for (name, network) in networks.iter_mut() {
+            if network.get("external").and_then(Value::as_bool) == Some(true) {
+                bail!("external network {name:?} needs sandbox networking enabled");
+            }
+            if network.is_null() {
+                *network = Value::Mapping(Mapping::new());
+            }
+            if let Some(network) = network.as_mapping_mut() {
+                network.insert("internal".into(), true.into());
+            }
+        }
+    }
+
+    serde_yaml::to_string(&doc).context("Failed to write sanitized compose file")
+}
+
+fn sanitize_service(
+    name: &str,
+    service: &mut Mapping,
+    network_enabled: bool,
+    allowed: &impl Fn(&str) -> bool,
+) {
+    let mut dropped = Vec::new();
+    for key in PRIVILEGE_KEYS {
+        if service.remove(*key).is_some() {
+            dropped.push(key.to_string());
+        }
+    }
+    for key in HOST_NAMESPACE_KEYS {
+        if service.get(*key).and_then(Value::as_str) == Some("host") {
+            service.remove(*key);
+            dropped.push(format!("{key}: host"));
+        }
+    }
+    // Only modes that keep the service on the project's own networks.
+    if let Some(mode) = service.get("network_mode").and_then(Value::as_str)
+        && !(mode == "none" || mode.starts_with("service:") || network_enabled && mode == "bridge")
+    {
+        dropped.push(format!("network_mode: {mode}"));
+        service.remove("network_mode");
+    }
+    if let Some(volumes) = service.get_mut("volumes").and_then(Value::as_sequence_mut) {
+        volumes.retain(|volume| {
+            let keep = volume_allowed(volume, allowed);
+            if !keep {
+                dropped.push(format!("volume {}", describe(volume)));
+            }
+            keep
+        });
+    }
+    if let Some(shared) = service
+        .get_mut("volumes_from")
+        .and_then(Value::as_sequence_mut)
+    {
+        shared.retain(|from| {
+            let keep = !from
+                .as_str()
+                .is_some_and(|from| from.starts_with("container:"));
+            if !keep {
+                dropped.push(format!("volumes_from {}", describe(from)));
+            }
+            keep
+        });
+    }
+    if let Some(ports) = service.get_mut("ports").and_then(Value::as_sequence_mut) {
+        for port in ports.iter_mut() {
+            if let Some(rebound) = loopback_port(port) {
+                dropped.push(format!(
+                    "port {} (now {})",
+                    describe(port),
+                    describe(&rebound)
+                ));
+                *port = rebound;
+            }
+        }
+    }
+    match service.get_mut("env_file") {
+        Some(Value::String(file)) if !allowed(file) => {
+            dropped.push(format!("env_file {file}"));
+            service.remove("env_file");
+        }
+        Some(Value::Sequence(files)) => files.retain(|file| {
+            let path = file
+                .as_str()
+                .or_else(|| file.get("path").and_then(Value::as_str));
+            let keep = path.is_some_and(allowed);
+            if !keep {
+                dropped.push(format!("env_file {}", describe(file)));
+            }
+            keep
+        }),
+        _ => {}
+    }
+    for what in dropped {
+        warn!(service = name, dropped = %what, "sanitized sandbox compose service");
+    }
+}
+
+/// Refuse a service `build` whose context, Dockerfile or additional
+/// contexts are host paths outside the workspace. Remote contexts (URLs,
+/// images, other services) read nothing from the host.
+fn check_build(name: &str, service: &Mapping, project_dir: &Path, workspace: &Path) -> Result<()> {
+    let Some(build) = service.get("build") else {
+        return Ok(());
+    };
+    let (context, dockerfile, additional) = match build {
+        Value::String(context) => (context.as_str(), None, None),
+        Value::Mapping(build) => (
+            build.get("context").and_then(Value::as_str).unwrap_or("."),
+            build.get("dockerfile").and_then(Value::as_str),
+            build.get("additional_contexts"),
+        ),
+        _ => bail!("service '{name}' has an invalid `build`"),
+    };
+    let outside = |source: &str, base: &Path| {
+        !is_remote_context(source) && !path_in_workspace(source, base, workspace)
+    };
+    if outside(context, project_dir) {
+        bail!("service '{name}' builds from {context}, outside the workspace");
+    }
+    if let Some(dockerfile) = dockerfile
+        && !is_remote_context(context)
+        && outside(dockerfile, &project_dir.join(context))
+    {
+        bail!("service '{name}' uses Dockerfile {dockerfile}, outside the workspace");
+    }
+    let additional: Vec<&str> = match additional {
+        Some(Value::Mapping(contexts)) => contexts.values().filter_map(Value::as_str).collect(),
+        Some(Value::Sequence(contexts)) => contexts
+            .iter()
+            .filter_map(Value::as_str)
+            .filter_map(|entry| entry.split_once('=').map(|(_, source)| source))
+            .collect(),
+        _ => Vec::new(),
+    };
+    if let Some(source) = additional
+        .into_iter()
+        .find(|source| outside(source, project_dir))
+    {
+        bail!("service '{name}' has build context {source}, outside the workspace");
+    }
+    Ok(())
+}
+
+/// Build sources that are fetched rather than read from the host.
+fn is_remote_context(source: &str) -> bool {
+    source.contains("://") || source.starts_with("git@") || source.starts_with("service:")
+}
+
+/// A published port rebound to the loopback interface, or `None` if it
+/// already is. Short syntax is `[HOST_IP:][HOST_PORT:]CONTAINER_PORT[/PROTO]`.
+fn loopback_port(port: &Value) -> Option<Value> {
+    const LOOPBACK: &str = "127.0.0.1";
+    match port {
+        Value::Mapping(port) => {
+            if port.get("host_ip").and_then(Value::as_str) == Some(LOOPBACK) {
+                return None;
+            }
+            let mut port = port.clone();
+            port.insert("host_ip".into(), LOOPBACK.into());
+            Some(Value::Mapping(port))
+        }
+        Value::Number(container) => Some(format!("{LOOPBACK}::{container}").into()),
+        Value::String(spec) => {
+            let mut parts = spec.rsplitn(3, ':');
+            let container = parts.next()?;
+            let host_port = parts.next().unwrap_or_default();
+            if parts.next() == Some(LOOPBACK) {
+                return None;
+            }
+            Some(format!("{LOOPBACK}:{host_port}:{container}").into())
+        }
+        _ => None,
+    }
+}
+
+/// Whether a service volume is a named or anonymous volume, or a bind
+/// mount of a path inside the workspace.
+fn volume_allowed(volume: &Value, allowed: &impl Fn(&str) -> bool) -> bool {
+    match volume {
+        // "SOURCE:TARGET[:MODE]" or just "TARGET".
+        Value::String(spec) => match spec.split_once(':') {
+            None => true,
+            Some((source, _)) if is_volume_name(source) => true,
+            Some((source, _)) => allowed(source),
+        },
+        Value::Mapping(volume) => {
+            let source = volume.get("source").and_then(Value::as_str);
+            match volume
+                .get("type")
+                .and_then(Value::as_str)
+                .unwrap_or("volume")
+            {
+                "bind" => source.is_some_and(allowed),
+                "volume" => source.is_none_or(is_volume_name),
+                "tmpfs" => true,
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Named volumes are plain identifiers; anything path-like or interpolated
+/// is a host path.
+fn is_volume_name(source: &str) -> bool {
+    !source.is_empty()
+        && source
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+        && !source.starts_with('.')
+}
+
+/// Whether `source`, relative to `project_dir`, stays inside `workspace`.
+/// Home-relative and interpolated paths are never allowed.
+fn path_in_workspace(source: &str, project_dir: &Path, workspace: &Path) -> bool {
+    if source.starts_with('~') || source.contains('$') {
+        return false;
+    }
+    let mut path = PathBuf::new();
+    for component in project_dir.join(source).components() {
+        match component {
+            Component::ParentDir => {
+                path.pop();
+            }
+            Component::CurDir => {}
+            other => path.push(other),
+        }
+    }
+    let path = path.canonicalize().unwrap_or(path);
+    path.starts_with(workspace)
+}
+
+fn describe(value: &Value) -> String {
+    serde_yaml::to_string(value)
+        .map(|text| text.trim().replace('\n', " "))
+        .unwrap_or_default()
+}
+
+/// Stop a compose project and remove its containers, networks and volumes.
+/// Works from the project name alone.
+pub fn compose_down(project: &str) -> Result<()> {
+    run(
+        Command::new("docker").args(["compose", "-p", project, "down", "-v", "--remove-orphans"]),
+        "docker compose down",
+    )?;
+    debug!(project, "compose project removed");
+    Ok(())
+}
+
+/// Force-remove every container carrying `label` (`key=value`). Returns how
+/// many were removed.
+pub fn remove_labeled_containers(label: &str) -> Result<usize> {
+    let ids = run(
+        Command::new("docker").args(["ps", "-aq", "--filter", &format!("label={label}")]),
+        "docker ps",
+    )?;
+    let ids: Vec<&str> = ids.lines().filter(|id| !id.is_empty()).collect();
+    if !ids.is_empty() {
+        run(
+            Command::new("docker").args(["rm", "-f"]).args(&ids),
+            "docker rm",
+        )?;
+    }
+    Ok(ids.len())
+}
+
+/// Compose project name for a sandbox over `workspace`: the directory name
+/// plus a hash of the full path, so worktrees of one repo stay apart.
+pub fn project_name_for(workspace: &Path) -> String {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    workspace.hash(&mut hasher);
+    let base: String = workspace
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect();
+    format!("hive-{base}-{:08x}", hasher.finish() as u32)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEVCONTAINER: &str = r#"{
+        // Rust toolchain image
+        "name": "hive",
+        "image": "mcr.microsoft.com/devcontainers/rust:1", /* pinned */
+        "features": {
+            "ghcr.io/devcontainers/features/node:1": { "version": "20" },
+            "ghcr.io/devcontainers/features/docker-in-docker:2": {},
+        },
+        "postCreateCommand": "cargo fetch",
+        "forwardPorts": [3000, "8080", "db:5432"],
+        "containerEnv": { "RUST_LOG": "debug", "URL": "http://x//y" },
+        "remoteEnv": { "PATH_EXTRA": "/opt/bin" },
+    }"#;
+
+    #[test]
+    fn parses_jsonc_devcontainer() {
+        let config = DevContainerConfig::parse(DEVCONTAINER).unwrap();
+        assert_eq!(
+            config.image.as_deref(),
+            Some("mcr.microsoft.com/devcontainers/rust:1")
+        );
+        assert_eq!(config.features.len(), 2);
+        assert_eq!(config.ports(), [3000, 8080]);
+        // Comment markers inside strings are kept.
+        assert_eq!(config.container_env["URL"], "http://x//y");
+    }
+
+    #[test]
+    fn plan_from_image_devcontainer() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".devcontainer")).unwrap();
+        std::fs::write(
+            dir.path().join(".devcontainer/devcontainer.json"),
+            DEVCONTAINER,
+        )
+        .unwrap();
+
+        let plan = EnvironmentPlan::resolve(dir.path(), "fallback:latest").unwrap();
+        assert_eq!(
+            plan.primary,
+            PrimaryContainer::Image("mcr.microsoft.com/devcontainers/rust:1".into())
+        );
+        assert!(plan.compose.is_none());
+        assert_eq!(plan.workspace_folder, DEFAULT_WORKSPACE_FOLDER);
+        assert_eq!(plan.env["RUST_LOG"], "debug");
+        assert_eq!(plan.env["PATH_EXTRA"], "/opt/bin");
+        // Node is installed, docker-in-docker is skipped, then postCreate.
+        assert_eq!(plan.setup_commands.len(), 2);
+        assert!(plan.setup_commands[0].contains("setup_20.x"));
+        assert_eq!(plan.setup_commands[1], "cargo fetch");
+    }
+
+    #[test]
+    fn plan_from_dockerfile_build() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Dockerfile.dev"), "FROM rust:1.80\n").unwrap();
+        std::fs::create_dir(dir.path().join("ci")).unwrap();
+        std::fs::write(
+            dir.path().join(".devcontainer.json"),
+            r#"{ "build": { "dockerfile": "Dockerfile.dev", "context": "ci", "args": { "V": "1" } },
+                 "workspaceFolder": "/src",
+                 "postCreateCommand": ["make", "setup deps"] }"#,
+        )
+        .unwrap();
+
+        let plan = EnvironmentPlan::resolve(dir.path(), "fallback").unwrap();
+        let PrimaryContainer::Build {
+            dockerfile,
+            context,
+            args,
+            ..
+        } = &plan.primary
+        else {
+            panic!("expected a build, got {:?}", plan.primary);
+        };
+        let root = dir.path().canonicalize().unwrap();
+        assert_eq!(dockerfile, &root.join("Dockerfile.dev"));
+        assert_eq!(context, &root.join("ci"));
+        assert_eq!(args["V"], "1");
+        assert_eq!(plan.workspace_folder, "/src");
+        assert_eq!(plan.setup_commands, ["make 'setup deps'"]);
+    }
+
+    #[test]
+    fn dockerfile_build_outside_the_workspace_is_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("repo");
+        std::fs::create_dir_all(workspace.join(".devcontainer")).unwrap();
+        std::fs::write(workspace.join("Dockerfile"), "FROM rust:1.80\n").unwrap();
+        let devcontainer = workspace.join(".devcontainer/devcontainer.json");
+
+        std::fs::write(
+            &devcontainer,
+            r#"{ "build": { "dockerfile": "../Dockerfile", "context": "../.." } }"#,
+        )
+        .unwrap();
+        let err = EnvironmentPlan::resolve(&workspace, "fallback").unwrap_err();
+        assert!(err.to_string().contains("outside the workspace"), "{err}");
+
+        std::fs::write(
+            &devcontainer,
+            r#"{ "build": { "dockerfile": "../../Dockerfile", "context": ".." } }"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("Dockerfile"), "FROM rust:1.80\n").unwrap();
+        assert!(EnvironmentPlan::resolve(&workspace, "fallback").is_err());
+
+        std::fs::write(
+            &devcontainer,
+            r#"{ "build": { "dockerfile": "../Dockerfile", "context": ".." } }"#,
+        )
+        .unwrap();
+        assert!(EnvironmentPlan::resolve(&workspace, "fallback").is_ok());
+    }
+
+    #[test]
+    fn plan_from_compose_devcontainer() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".devcontainer")).unwrap();
+        std::fs::write(
+            dir.path().join(".devcontainer/devcontainer.json"),
+            r#"{ "dockerComposeFile": ["../docker-compose.yml", "compose.dev.yml"],
+                 "service": "app", "runServices": ["db"],
+                 "postCreateCommand": { "b": "npm ci", "a": "cargo fetch" } }"#,
+        )
+        .unwrap();
+
+        let plan = EnvironmentPlan::resolve(dir.path(), "fallback").unwrap();
+        assert_eq!(plan.primary, PrimaryContainer::ComposeService("app".into()));
+        let compose = plan.compose.unwrap();
+        assert_eq!(compose.files.len(), 2);
+        assert!(compose.files[0].ends_with(".devcontainer/../docker-compose.yml"));
+        assert_eq!(compose.services, ["db", "app"]);
+        assert_eq!(plan.setup_commands, ["cargo fetch", "npm ci"]);
+
+        let missing_service =
+            DevContainerConfig::parse(r#"{ "dockerComposeFile": "c.yml" }"#).unwrap();
+        assert!(
+            EnvironmentPlan::from_devcontainer(
+                Path::new("/p"),
+                Path::new("/p/devcontainer.json"),
+                &missing_service,
+                "x"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn root_compose_file_alone_is_not_used() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("docker-compose.yml"),
+            "services:\n  postgres:\n    image: postgres:16\n",
+        )
+        .unwrap();
+
+        let plan = EnvironmentPlan::resolve(dir.path(), "hive-sandbox:latest").unwrap();
+        assert_eq!(plan, EnvironmentPlan::image("hive-sandbox:latest"));
+    }
+
+    const HOSTILE_COMPOSE: &str = r#"
+services:
+  app:
+    image: rust:1.80
+    privileged: true
+    cap_add: [SYS_ADMIN]
+    devices: ["/dev/kvm:/dev/kvm"]
+    network_mode: host
+    pid: host
+    env_file: [.env, /etc/environment]
+    volumes:
+      - .:/workspace
+      - cache:/cache
+      - /var/run/docker.sock:/var/run/docker.sock
+      - ../outside:/outside
+      - ${HOME}:/home
+      - type: bind
+        source: /
+        target: /host
+      - type: volume
+        source: data
+        target: /data
+volumes:
+  cache:
+  data:
+    driver_opts: { type: none, o: bind, device: /etc }
+secrets:
+  token:
+    file: ~/.config/gh/hosts.yml
+"#;
+
+    #[test]
+    fn sanitize_strips_host_access() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let text = sanitize_compose(HOSTILE_COMPOSE, &workspace, &workspace, true).unwrap();
+        let doc: Value = serde_yaml::from_str(&text).unwrap();
+        let app = &doc["services"]["app"];
+
+        for key in ["privileged", "cap_add", "devices", "network_mode", "pid"] {
+            assert!(app.get(key).is_none(), "{key} should be dropped");
+        }
+        let volumes: Vec<String> = app["volumes"]
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map_or_else(|| v["target"].as_str().unwrap().into(), Into::into)
+            })
+            .collect();
+        assert_eq!(volumes, [".:/workspace", "cache:/cache", "/data"]);
+        assert_eq!(app["env_file"].as_sequence().unwrap().len(), 1);
+        assert!(doc["volumes"]["data"].get("driver_opts").is_none());
+        assert!(doc["secrets"].as_mapping().unwrap().is_empty());
+        assert!(doc.get("networks").is_none());
+    }
+
+    #[test]
+    fn sanitize_makes_networks_internal_without_networking() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let text = sanitize_compose(
+            "services:\n  db:\n    image: postgres:16\n    network_mode: bridge\nnetworks:\n  backend:\n",
+            &workspace,
+            &workspace,
+            false,
+        )
+        .unwrap();
+        let doc: Value = serde_yaml::from_str(&text).unwrap();
+        assert!(doc["services"]["db"].get("network_mode").is_none());
+        assert_eq!(doc["networks"]["default"]["internal"], Value::Bool(true));
+        assert_eq!(doc["networks"]["backend"]["internal"], Value::Bool(true));
+
+        let external = "services: {}\nnetworks:\n  shared:\n    external: true\n";
+        assert!(sanitize_compose(external, &workspace, &workspace, false).is_err());
+        assert!(sanitize_compose(external, &workspace, &workspace, true).is_ok());
+    }
+
+    #[test]
+    fn sanitize_rebinds_published_ports_to_loopback() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let compose = r#"
+services:
+  web:
+    image: nginx
+    ports:
+      - "80"
+      - 3000
+      - "8080:80"
+      - "0.0.0.0:8443:443/tcp"
+      - "::1:6000:6001"
+      - "127.0.0.1:9000:9000"
+      - target: 5432
+        published: 5432
+        host_ip: 0.0.0.0
+"#;
+        let text = sanitize_compose(compose, &workspace, &workspace, true).unwrap();
+        let doc: Value = serde_yaml::from_str(&text).unwrap();
+        let ports = doc["services"]["web"]["ports"].as_sequence().unwrap();
+        let short: Vec<&str> = ports.iter().filter_map(Value::as_str).collect();
+        assert_eq!(
+            short,
+            [
+                "127.0.0.1::80",
+                "127.0.0.1::3000",
+                "127.0.0.1:8080:80",
+                "127.0.0.1:8443:443/tcp",
+                "127.0.0.1:6000:6001",
+                "127.0.0.1:9000:9000",
+            ]
+        );
+        assert_eq!(ports[6]["host_ip"], "127.0.0.1");
+        assert_eq!(ports[6]["published"], 5432);
+    }
+
+    #[test]
+    fn sanitize_rejects_builds_from_outside_the_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let sanitize = |build: &str| {
+            let compose = format!("services:\n  app:\n    build: {build}\n");
+            sanitize_compose(&compose, &workspace, &workspace, true)
+        };
+
+        assert!(sanitize(".").is_ok());
+        assert!(sanitize("{ context: ., dockerfile: docker/Dockerfile }").is_ok());
+        assert!(sanitize("https://github.com/example/app.git#main").is_ok());
+        assert!(sanitize("{ context: ., additional_contexts: { base: service:base } }").is_ok());
+
+        assert!(sanitize("../..").is_err());
+        assert!(sanitize("/").is_err());
+        assert!(sanitize("~/src").is_err());
+        assert!(sanitize("{ context: ., dockerfile: ../../etc/Dockerfile }").is_err());
+        assert!(sanitize("{ context: ., additional_contexts: { home: /home } }").is_err());
+        assert!(sanitize("{ context: ., additional_contexts: [\"root=/\"] }").is_err());
+    }
+
+    #[test]
+    fn sanitize_rejects_unsanitizable_references() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().canonicalize().unwrap();
+        let include = "include:\n  - ../other/compose.yaml\nservices: {}\n";
+        assert!(sanitize_compose(include, &workspace, &workspace, true).is_err());
+        let extends =
+            "services:\n  app:\n    extends:\n      file: /tmp/base.yml\n      service: app\n";
+        assert!(sanitize_compose(extends, &workspace, &workspace, true).is_err());
+    }
+
+    #[test]
+    fn sanitized_project_refuses_files_outside_the_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("repo");
+        std::fs::create_dir(&workspace).unwrap();
+        std::fs::write(dir.path().join("compose.yaml"), "services: {}\n").unwrap();
+        let plan = ComposePlan {
+            files: vec![workspace.join("../compose.yaml")],
+            services: Vec::new(),
+        };
+        assert!(ComposeProject::sanitized("hive-test-outside", &plan, &workspace, false).is_err());
+
+        std::fs::write(workspace.join("compose.yaml"), "services: {}\n").unwrap();
+        let plan = ComposePlan {
+            files: vec![workspace.join("compose.yaml")],
+            services: Vec::new(),
+        };
+        let project =
+            ComposeProject::sanitized("hive-test-inside", &plan, &workspace, false).unwrap();
+        assert_eq!(
+            project.project_dir.as_deref(),
+            Some(workspace.canonicalize().unwrap().as_path())
+        );
+        let copy = std::fs::read_to_string(&project.files[0]).unwrap();
+        assert!(copy.contains("internal: true"));
+        project.remove_files();
+        assert!(!project.files[0].exists());
+    }
+
+    #[test]
+    fn feature_commands() {
+        let none = serde_json::json!({});
+        assert!(feature_install_command("ghcr.io/devcontainers/features/git:1", &none).is_some());
+        assert!(
+            feature_install_command(
+                "ghcr.io/devcontainers/features/rust:1",
+                &serde_json::json!({ "version": "1.80" })
+            )
+            .unwrap()
+            .contains("--default-toolchain 1.80")
+        );
+        assert!(feature_install_command("ghcr.io/acme/features/custom:1", &none).is_none());
+    }
+
+    #[test]
+    fn project_names_differ_per_worktree() {
+        let a = project_name_for(Path::new("/repo/.hive-worktrees/team-a"));
+        let b = project_name_for(Path::new("/other/.hive-worktrees/team-a"));
+        assert!(a.starts_with("hive-team-a-"));
+        assert_ne!(a, b);
+    }
+}
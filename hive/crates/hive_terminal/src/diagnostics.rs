@@ -0,0 +1,305 @@
+//! Doctor checks for the host environment: tools on PATH, disk space,
+//! connectivity, local model servers and leftover sandbox containers.
+
+use std::process::Command;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use hive_core::HiveConfig;
+use hive_core::diagnostics::{
+    CheckStatus, Diagnostic, DiagnosticContext, DiagnosticRegistry, DoctorCheck,
+};
+
+use crate::cli::CliService;
+use crate::local_ai::detection::{LocalAiDetector, LocalProviderKind};
+
+/// Register this crate's diagnostics.
+pub fn register(registry: &mut DiagnosticRegistry) -> Result<()> {
+    registry.register(HostCheck {
+        id: "terminal.git",
+        category: "environment",
+        check: CliService::check_git_available,
+    })?;
+    registry.register(HostCheck {
+        id: "terminal.disk_space",
+        category: "environment",
+        check: CliService::check_disk_space,
+    })?;
+    registry.register(HostCheck {
+        id: "terminal.network",
+        category: "network",
+        check: CliService::check_network,
+    })?;
+    registry.register(LocalModelsCheck)?;
+    registry.register(StaleSandboxesCheck)?;
+    Ok(())
+}
+
+/// One of the original blocking `CliService` checks.
+struct HostCheck {
+    id: &'static str,
+    category: &'static str,
+    check: fn(&CliService) -> DoctorCheck,
+}
+
+#[async_trait]
+impl Diagnostic for HostCheck {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn category(&self) -> &'static str {
+        self.category
+    }
+
+    async fn run(&self, _ctx: &DiagnosticContext) -> DoctorCheck {
+        let check = self.check;
+        tokio::task::spawn_blocking(move || check(&CliService::new()))
+            .await
+            .unwrap_or_else(|e| DoctorCheck::fail(self.id, "Host check", e.to_string()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Local model endpoints
+// ---------------------------------------------------------------------------
+
+/// The configured Ollama / LM Studio / custom endpoints answer.
+struct LocalModelsCheck;
+
+impl LocalModelsCheck {
+    const NAME: &str = "Local Model Endpoints";
+    const DESCRIPTION: &str = "Check that configured local model servers are reachable";
+}
+
+#[async_trait]
+impl Diagnostic for LocalModelsCheck {
+    fn id(&self) -> &'static str {
+        "terminal.local_models"
+    }
+
+    fn category(&self) -> &'static str {
+        "providers"
+    }
+
+    async fn run(&self, ctx: &DiagnosticContext) -> DoctorCheck {
+        let default_config;
+        let config = match ctx.config {
+            Some(ref config) => config,
+            None => {
+                default_config = HiveConfig::default();
+                &default_config
+            }
+        };
+
+        let mut endpoints = vec![
+            (LocalProviderKind::Ollama, config.ollama_url.clone()),
+            (LocalProviderKind::LMStudio, config.lmstudio_url.clone()),
+        ];
+        if let Some(url) = config.local_provider_url.clone().filter(|u| !u.is_empty()) {
+            endpoints.push((LocalProviderKind::Custom, url));
+        }
+
+        let detector = LocalAiDetector::new();
+        let probes = endpoints
+            .iter()
+            .map(|(kind, url)| detector.probe_endpoint(*kind, url.trim_end_matches('/')));
+        let results = futures::future::join_all(probes).await;
+
+        let online: Vec<String> = results
+            .iter()
+            .filter(|p| p.status.is_online())
+            .map(|p| format!("{} at {} ({} models)", p.kind, p.endpoint, p.model_count()))
+            .collect();
+        let offline: Vec<String> = results
+            .iter()
+            .filter(|p| !p.status.is_online())
+            .map(|p| format!("{} at {}", p.kind, p.endpoint))
+            .collect();
+
+        if offline.is_empty() {
+            DoctorCheck::pass(Self::NAME, Self::DESCRIPTION, online.join("; "))
+        } else if !online.is_empty() {
+            DoctorCheck::pass(
+                Self::NAME,
+                Self::DESCRIPTION,
+                format!("{}; not running: {}", online.join("; "), offline.join(", ")),
+            )
+        } else {
+            // Privacy mode routes everything locally, so nothing answering
+            // means nothing works.
+            let check = if config.privacy_mode {
+                DoctorCheck::fail
+            } else {
+                DoctorCheck::warn
+            };
+            check(
+                Self::NAME,
+                Self::DESCRIPTION,
+                format!("No local model server reachable: {}", offline.join(", ")),
+            )
+            .with_fix(
+                "Start Ollama ('ollama serve') or LM Studio, or correct ollama_url / lmstudio_url",
+            )
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Stale sandboxes
+// ---------------------------------------------------------------------------
+
+/// Sandbox containers (`hive-*`) that exited but were never removed.
+struct StaleSandboxesCheck;
+
+impl StaleSandboxesCheck {
+    const NAME: &str = "Stale Sandboxes";
+    const DESCRIPTION: &str = "Check for exited Hive sandbox containers";
+
+    /// `(id, name)` of stopped containers whose name starts with `hive-`.
+    /// `None` when Docker is not usable.
+    fn stale_containers() -> Option<Vec<(String, String)>> {
+        let output = Command::new("docker")
+            .args([
+                "ps",
+                "-a",
+                "--filter",
+                "name=hive-",
+                "--filter",
+                "status=exited",
+                "--filter",
+                "status=created",
+                "--filter",
+                "status=dead",
+                "--format",
+                "{{.ID}}\t{{.Names}}",
+            ])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Some(
+            stdout
+                .lines()
+                .filter_map(|line| line.split_once('\t'))
+                // The name filter matches anywhere in the name.
+                .filter(|(_, name)| name.starts_with("hive-"))
+                .map(|(id, name)| (id.to_string(), name.to_string()))
+                .collect(),
+        )
+    }
+}
+
+#[async_trait]
+impl Diagnostic for StaleSandboxesCheck {
+    fn id(&self) -> &'static str {
+        "terminal.sandboxes"
+    }
+
+    fn category(&self) -> &'static str {
+        "sandbox"
+    }
+
+    async fn run(&self, _ctx: &DiagnosticContext) -> DoctorCheck {
+        let stale = tokio::task::spawn_blocking(Self::stale_containers)
+            .await
+            .ok()
+            .flatten();
+        match stale {
+            None => DoctorCheck::pass(
+                Self::NAME,
+                Self::DESCRIPTION,
+                "Docker not available; no sandboxes to check",
+            ),
+            Some(stale) if stale.is_empty() => {
+                DoctorCheck::pass(Self::NAME, Self::DESCRIPTION, "No stale sandbox containers")
+            }
+            Some(stale) => {
+                let names: Vec<_> = stale.iter().map(|(_, name)| name.as_str()).collect();
+                DoctorCheck::warn(
+                    Self::NAME,
+                    Self::DESCRIPTION,
+                    format!(
+                        "{} stopped sandbox container(s): {}",
+                        stale.len(),
+                        names.join(", ")
+                    ),
+                )
+                .with_fix("Run 'hive doctor --fix' to remove them")
+            }
+        }
+    }
+
+    fn can_fix(&self, check: &DoctorCheck) -> bool {
+        check.status == CheckStatus::Warn
+    }
+
+    async fn fix(&self, _ctx: &DiagnosticContext) -> Result<String> {
+        let stale = tokio::task::spawn_blocking(Self::stale_containers)
+            .await?
+            .unwrap_or_default();
+        if stale.is_empty() {
+            return Ok("No stale sandbox containers".into());
+        }
+        let output = Command::new("docker")
+            .args(["rm", "-f"])
+            .args(stale.iter().map(|(id, _)| id))
+            .output()?;
+        if !output.status.success() {
+            bail!(
+                "docker rm failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(format!("Removed {} sandbox container(s)", stale.len()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_terminal_checks() {
+        let mut registry = DiagnosticRegistry::new();
+        register(&mut registry).unwrap();
+        assert_eq!(
+            registry.ids(),
+            [
+                "terminal.git",
+                "terminal.disk_space",
+                "terminal.network",
+                "terminal.local_models",
+                "terminal.sandboxes"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn unreachable_local_endpoints_warn() {
+        let config = HiveConfig {
+            ollama_url: "http://127.0.0.1:9".into(),
+            lmstudio_url: "http://127.0.0.1:9".into(),
+            ..Default::default()
+        };
+        let ctx = DiagnosticContext::new("/tmp/hive-doctor-test", "/tmp").with_config(config);
+        let check = LocalModelsCheck.run(&ctx).await;
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.fix_suggestion.is_some());
+
+        let mut private = ctx.clone();
+        if let Some(ref mut config) = private.config {
+            config.privacy_mode = true;
+        }
+        assert_eq!(
+            LocalModelsCheck.run(&private).await.status,
+            CheckStatus::Fail
+        );
+    }
+}
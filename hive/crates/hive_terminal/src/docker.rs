@@ -62,6 +62,16 @@ pub struct ContainerConfig {
     pub working_dir: Option<String>,
     /// Whether networking is enabled (default `false`).
     pub network_enabled: bool,
+    /// Named network to join instead of the default bridge. Only used when
+    /// networking is enabled.
+    #[serde(default)]
+    pub network: Option<String>,
+    /// Container ports published on an ephemeral localhost port.
+    #[serde(default)]
+    pub ports: Vec<u16>,
+    /// Labels attached to the container.
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
 }
 
 impl ContainerConfig {
@@ -75,6 +85,9 @@ impl ContainerConfig {
             resource_limits: ResourceLimits::default(),
             working_dir: None,
             network_enabled: false,
+            network: None,
+            ports: Vec::new(),
+            labels: HashMap::new(),
         }
     }
 }
@@ -253,6 +266,22 @@ impl DockerSandbox {
         if !config.network_enabled {
             args.push("--network".to_string());
             args.push("none".to_string());
+        } else {
+            if let Some(ref network) = config.network {
+                args.push("--network".to_string());
+                args.push(network.clone());
+            }
+            // Published ports (not allowed without a network)
+            for port in &config.ports {
+                args.push("-p".to_string());
+                args.push(format!("127.0.0.1::{}", port));
+            }
+        }
+
+        // Labels
+        for (key, value) in &config.labels {
+            args.push("--label".to_string());
+            args.push(format!("{}={}", key, value));
         }
 
         // Image (must be last positional argument)
@@ -282,6 +311,26 @@ impl DockerSandbox {
         Ok(id)
     }
 
+    /// Track a container that was started outside this manager (e.g. a
+    /// compose service) so commands can be executed in it.
+    ///
+    /// The container is assumed to be running.
+    pub fn adopt_container(&mut self, id: impl Into<String>, config: ContainerConfig) -> String {
+        let id = id.into();
+        debug!(id = %id, image = %config.image, "adopting running container");
+        let now = Utc::now();
+        let container = Container {
+            id: id.clone(),
+            config,
+            status: ContainerStatus::Running,
+            created_at: now,
+            started_at: Some(now),
+            stopped_at: None,
+        };
+        self.containers.insert(id.clone(), container);
+        id
+    }
+
     // -----------------------------------------------------------------------
     // Container lifecycle
     // -----------------------------------------------------------------------
@@ -739,6 +788,16 @@ mod tests {
         assert_eq!(c.config.resource_limits.cpu_cores, Some(2.0));
     }
 
+    #[test]
+    fn adopt_container_is_running_and_execable() {
+        let mut sandbox = DockerSandbox::new_simulated();
+        let id = sandbox.adopt_container("compose-app-1", ContainerConfig::new("app"));
+
+        assert_eq!(id, "compose-app-1");
+        assert_eq!(sandbox.running_count(), 1);
+        assert!(sandbox.exec_in_container(&id, "true").is_ok());
+    }
+
     // -- Lifecycle transitions -----------------------------------------------
 
     #[test]
@@ -0,0 +1,476 @@
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+// ---------------------------------------------------------------------------
+// CommandRecord
+// ---------------------------------------------------------------------------
+
+/// One command run in a recorded terminal session, delimited by the shell
+/// integration markers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandRecord {
+    pub session_id: String,
+    pub command: String,
+    /// Working directory the command ran in, when the shell reported it.
+    pub cwd: Option<String>,
+    /// Exit status, or `None` when the shell did not report one.
+    pub exit_code: Option<i32>,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// Output with escape sequences stripped. Long output keeps only its
+    /// tail, which is where errors usually are.
+    pub output: String,
+    /// Whether the start of `output` was dropped.
+    #[serde(default)]
+    pub output_truncated: bool,
+}
+
+impl CommandRecord {
+    /// A command failed when it reported a non-zero exit status.
+    pub fn failed(&self) -> bool {
+        self.exit_code.is_some_and(|code| code != 0)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// HistoryQuery
+// ---------------------------------------------------------------------------
+
+/// Filter for [`CommandHistory::search`]. The default matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryQuery {
+    /// Case-insensitive substring matched against the command and its output.
+    pub text: Option<String>,
+    pub session_id: Option<String>,
+    /// Only commands run in this directory or below it.
+    pub cwd: Option<String>,
+    pub failed_only: bool,
+    /// Maximum number of records to return; `0` means no limit.
+    pub limit: usize,
+}
+
+impl HistoryQuery {
+    fn matches(&self, record: &CommandRecord) -> bool {
+        if self.failed_only && !record.failed() {
+            return false;
+        }
+        if let Some(session) = &self.session_id
+            && &record.session_id != session
+        {
+            return false;
+        }
+        if let Some(cwd) = &self.cwd
+            && !record
+                .cwd
+                .as_deref()
+                .is_some_and(|dir| Path::new(dir).starts_with(cwd))
+        {
+            return false;
+        }
+        if let Some(text) = &self.text {
+            let needle = text.to_lowercase();
+            if !record.command.to_lowercase().contains(&needle)
+                && !record.output.to_lowercase().contains(&needle)
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CommandHistory
+// ---------------------------------------------------------------------------
+
+/// Append-only command history shared by every terminal session.
+///
+/// Records are stored one JSON object per line, so concurrent sessions can
+/// append without rewriting the file and a torn final line only loses that
+/// record. Once the file passes its size or age limit it is renamed to
+/// `<name>.1.jsonl`, replacing the previous rotation, and a fresh file is
+/// started.
+#[derive(Debug)]
+pub struct CommandHistory {
+    path: PathBuf,
+    max_bytes: u64,
+    max_age: Option<chrono::Duration>,
+    write_lock: Mutex<()>,
+}
+
+impl CommandHistory {
+    /// History file name inside the terminal data directory.
+    const FILE_NAME: &'static str = "history.jsonl";
+
+    /// Size at which the history is rotated unless configured otherwise.
+    pub const DEFAULT_MAX_BYTES: u64 = 32 * 1024 * 1024;
+
+    /// Use the history file at `path`. Nothing is created until the first
+    /// record is appended.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes: Self::DEFAULT_MAX_BYTES,
+            max_age: None,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Rotate once the file reaches `max_bytes`, or once its oldest record
+    /// is older than `max_age`. Rotated records are dropped at the next
+    /// rotation, so at most about twice the limit is kept.
+    pub fn with_limits(mut self, max_bytes: u64, max_age: Option<chrono::Duration>) -> Self {
+        self.max_bytes = max_bytes.max(1);
+        self.max_age = max_age;
+        self
+    }
+
+    /// The shared history at `~/.hive/terminal/history.jsonl`.
+    pub fn open_default() -> Result<Self> {
+        Ok(Self::new(terminal_dir()?.join(Self::FILE_NAME)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Where the previous file goes on rotation: `history.jsonl` becomes
+    /// `history.1.jsonl`.
+    pub fn rotated_path(&self) -> PathBuf {
+        let stem = self
+            .path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let name = match self.path.extension() {
+            Some(ext) => format!("{stem}.1.{}", ext.to_string_lossy()),
+            None => format!("{stem}.1"),
+        };
+        self.path.with_file_name(name)
+    }
+
+    /// Append a record, creating the file and its directory on first use.
+    pub fn append(&self, record: &CommandRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record).context("Failed to serialize command")?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        if let Err(e) = self.rotate_if_due() {
+            warn!(error = %e, "failed to rotate command history");
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        // A single write keeps lines from concurrent writers intact.
+        file.write_all(line.as_bytes())
+            .with_context(|| format!("Failed to append to {}", self.path.display()))
+    }
+
+    /// Rename the current file out of the way when it is over the size
+    /// limit or its first record is past the age limit, and delete a
+    /// rotated file whose records are all past the age limit.
+    fn rotate_if_due(&self) -> Result<()> {
+        let cutoff = self.max_age.map(|age| Utc::now() - age);
+        let rotated = self.rotated_path();
+
+        if let Some(cutoff) = cutoff
+            && let Ok(modified) = fs::metadata(&rotated).and_then(|meta| meta.modified())
+            && DateTime::<Utc>::from(modified) < cutoff
+        {
+            fs::remove_file(&rotated)
+                .with_context(|| format!("Failed to remove {}", rotated.display()))?;
+        }
+
+        let size = match fs::metadata(&self.path) {
+            Ok(meta) => meta.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to stat {}", self.path.display()));
+            }
+        };
+        let expired = match cutoff {
+            Some(cutoff) => self
+                .first_record()?
+                .is_some_and(|record| record.started_at < cutoff),
+            None => false,
+        };
+        if size >= self.max_bytes || expired {
+            fs::rename(&self.path, &rotated).with_context(|| {
+                format!(
+                    "Failed to rotate {} to {}",
+                    self.path.display(),
+                    rotated.display()
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    fn first_record(&self) -> Result<Option<CommandRecord>> {
+        let mut first = None;
+        Self::scan(&self.path, |record| {
+            first = Some(record);
+            false
+        })?;
+        Ok(first)
+    }
+
+    /// Feed every readable record in `path` to `visit`, oldest first, until
+    /// it returns `false`. A missing file has no records.
+    fn scan(path: &Path, mut visit: impl FnMut(CommandRecord) -> bool) -> Result<()> {
+        let file = match fs::File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to open {}", path.display()));
+            }
+        };
+
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(record) => {
+                    if !visit(record) {
+                        break;
+                    }
+                }
+                Err(e) => warn!(line = index + 1, error = %e, "skipping bad history record"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Every record, oldest first, including the last rotated file. A
+    /// missing file is an empty history; unreadable lines are skipped.
+    pub fn load(&self) -> Result<Vec<CommandRecord>> {
+        let mut records = Vec::new();
+        for path in [self.rotated_path(), self.path.clone()] {
+            Self::scan(&path, |record| {
+                records.push(record);
+                true
+            })?;
+        }
+        Ok(records)
+    }
+
+    /// Records matching `query`, most recent first.
+    ///
+    /// The files are read a line at a time and only the newest `limit`
+    /// matches are held, so a large history is never loaded whole.
+    pub fn search(&self, query: &HistoryQuery) -> Result<Vec<CommandRecord>> {
+        let mut matches = VecDeque::new();
+        for path in [self.rotated_path(), self.path.clone()] {
+            Self::scan(&path, |record| {
+                if query.matches(&record) {
+                    if query.limit != 0 && matches.len() == query.limit {
+                        matches.pop_front();
+                    }
+                    matches.push_back(record);
+                }
+                true
+            })?;
+        }
+        Ok(matches.into_iter().rev().collect())
+    }
+
+    /// The most recent command that exited with a non-zero status.
+    pub fn last_failed(&self) -> Result<Option<CommandRecord>> {
+        let query = HistoryQuery {
+            failed_only: true,
+            limit: 1,
+            ..HistoryQuery::default()
+        };
+        Ok(self.search(&query)?.into_iter().next())
+    }
+}
+
+/// Directory holding terminal recordings and history: `~/.hive/terminal/`.
+pub fn terminal_dir() -> Result<PathBuf> {
+    Ok(hive_core::config::HiveConfig::base_dir()?.join("terminal"))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(session: &str, command: &str, exit_code: Option<i32>, output: &str) -> CommandRecord {
+        CommandRecord {
+            session_id: session.into(),
+            command: command.into(),
+            cwd: Some("/work/project".into()),
+            exit_code,
+            started_at: Utc::now(),
+            duration_ms: 5,
+            output: output.into(),
+            output_truncated: false,
+        }
+    }
+
+    #[test]
+    fn missing_file_is_empty_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = CommandHistory::new(dir.path().join("none.jsonl"));
+        assert!(history.load().unwrap().is_empty());
+        assert!(history.last_failed().unwrap().is_none());
+    }
+
+    #[test]
+    fn append_and_search_across_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = CommandHistory::new(dir.path().join("terminal/history.jsonl"));
+        history
+            .append(&record("a", "cargo build", Some(0), "Finished"))
+            .unwrap();
+        history
+            .append(&record("a", "cargo test", Some(101), "test foo ... FAILED"))
+            .unwrap();
+        history
+            .append(&record("b", "ls", Some(0), "Cargo.toml"))
+            .unwrap();
+
+        let all = history.search(&HistoryQuery::default()).unwrap();
+        assert_eq!(
+            all.iter().map(|r| r.command.as_str()).collect::<Vec<_>>(),
+            ["ls", "cargo test", "cargo build"]
+        );
+
+        let cargo = history
+            .search(&HistoryQuery {
+                text: Some("CARGO".into()),
+                ..HistoryQuery::default()
+            })
+            .unwrap();
+        // Matches on output as well as the command.
+        assert_eq!(cargo.len(), 3);
+
+        let session_b = history
+            .search(&HistoryQuery {
+                session_id: Some("b".into()),
+                ..HistoryQuery::default()
+            })
+            .unwrap();
+        assert_eq!(session_b.len(), 1);
+
+        let limited = history
+            .search(&HistoryQuery {
+                limit: 2,
+                ..HistoryQuery::default()
+            })
+            .unwrap();
+        assert_eq!(limited.len(), 2);
+
+        let elsewhere = history
+            .search(&HistoryQuery {
+                cwd: Some("/work/other".into()),
+                ..HistoryQuery::default()
+            })
+            .unwrap();
+        assert!(elsewhere.is_empty());
+    }
+
+    #[test]
+    fn last_failed_returns_most_recent_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = CommandHistory::new(dir.path().join("history.jsonl"));
+        history.append(&record("a", "false", Some(1), "")).unwrap();
+        history
+            .append(&record("a", "make", Some(2), "error: no rule"))
+            .unwrap();
+        history.append(&record("a", "true", Some(0), "")).unwrap();
+        history.append(&record("a", "unknown", None, "")).unwrap();
+
+        let failed = history.last_failed().unwrap().unwrap();
+        assert_eq!(failed.command, "make");
+        assert_eq!(failed.output, "error: no rule");
+    }
+
+    #[test]
+    fn bad_lines_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let history = CommandHistory::new(&path);
+        history
+            .append(&record("a", "echo hi", Some(0), "hi"))
+            .unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"session_id\": \"torn\n").unwrap();
+
+        let records = history.load().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].command, "echo hi");
+    }
+
+    #[test]
+    fn rotates_when_the_size_limit_is_reached() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = CommandHistory::new(dir.path().join("history.jsonl")).with_limits(1, None);
+        history.append(&record("a", "first", Some(0), "")).unwrap();
+        history.append(&record("a", "second", Some(1), "")).unwrap();
+        history.append(&record("a", "third", Some(0), "")).unwrap();
+
+        assert_eq!(history.rotated_path(), dir.path().join("history.1.jsonl"));
+        // Each append rotates the previous file; only one rotation is kept.
+        let commands: Vec<_> = history
+            .load()
+            .unwrap()
+            .into_iter()
+            .map(|r| r.command)
+            .collect();
+        assert_eq!(commands, ["second", "third"]);
+        assert_eq!(history.last_failed().unwrap().unwrap().command, "second");
+    }
+
+    #[test]
+    fn rotates_and_drops_records_past_the_age_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = CommandHistory::new(dir.path().join("history.jsonl"))
+            .with_limits(u64::MAX, Some(chrono::Duration::days(1)));
+        let mut old = record("a", "old", Some(0), "");
+        old.started_at = Utc::now() - chrono::Duration::days(3);
+        history.append(&old).unwrap();
+        history.append(&record("a", "new", Some(0), "")).unwrap();
+
+        // The stale file was rotated rather than appended to.
+        assert!(history.rotated_path().exists());
+        let current = history
+            .search(&HistoryQuery {
+                limit: 1,
+                ..HistoryQuery::default()
+            })
+            .unwrap();
+        assert_eq!(current[0].command, "new");
+
+        // A rotated file last written before the cutoff is deleted.
+        let stale = std::time::SystemTime::now() - std::time::Duration::from_secs(3 * 86_400);
+        fs::File::options()
+            .write(true)
+            .open(history.rotated_path())
+            .unwrap()
+            .set_modified(stale)
+            .unwrap();
+        history.append(&record("a", "newer", Some(0), "")).unwrap();
+        assert!(!history.rotated_path().exists());
+        assert_eq!(history.load().unwrap().len(), 2);
+    }
+}
@@ -2,18 +2,30 @@
 
 pub mod browser;
 pub mod cli;
+pub mod devcontainer;
+pub mod diagnostics;
 pub mod docker;
 pub mod executor;
+pub mod history;
 pub mod local_ai;
+pub mod recording;
 pub mod sandbox;
 pub mod shell;
+pub mod vt;
 
 pub use cli::{CheckStatus, CliCommand, CliOutput, CliService, CommandArg, DoctorCheck};
+pub use devcontainer::{DevContainerConfig, EnvironmentPlan, PrimaryContainer};
 pub use docker::{
     Container, ContainerConfig, ContainerStatus, DockerSandbox, ExecResult, ResourceLimits,
     VolumeMount,
 };
 pub use executor::{CommandExecutor, CommandOutput};
+pub use history::{CommandHistory, CommandRecord, HistoryQuery};
 pub use sandbox::{AgentSandbox, SandboxConfig, SharedSandbox, shared_sandbox};
 pub use local_ai::{LocalAiDetector, LocalProviderInfo, OllamaManager, OllamaModelInfo, PullProgress};
+pub use recording::{
+    AsciicastEvent, AsciicastHeader, AsciicastWriter, EventKind, Recording, RecordingConfig,
+    SessionRecorder,
+};
 pub use shell::{InteractiveShell, ShellOutput};
+pub use vt::{Cell, CellStyle, Color, ScreenSnapshot, TerminalScreen};
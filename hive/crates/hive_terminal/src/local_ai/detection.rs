@@ -397,6 +397,88 @@ fn is_connection_error(err: &reqwest::Error) -> bool {
     err.is_connect() || err.is_timeout()
 }
 
+// ---------------------------------------------------------------------------
+// Hardware profile
+// ---------------------------------------------------------------------------
+
+/// A GPU usable by local model servers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpuInfo {
+    pub name: String,
+    pub memory_bytes: Option<u64>,
+}
+
+/// The hardware local models run on, as advertised to network peers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HardwareProfile {
+    pub cpu_cores: u32,
+    pub memory_bytes: Option<u64>,
+    pub gpus: Vec<GpuInfo>,
+}
+
+/// Detect CPU cores, total memory and NVIDIA GPUs on this machine.
+///
+/// Blocking: GPU detection shells out to `nvidia-smi`, so call this from a
+/// blocking task. Anything that cannot be determined is left empty.
+pub fn detect_hardware() -> HardwareProfile {
+    let cpu_cores = std::thread::available_parallelism()
+        .map(|n| n.get() as u32)
+        .unwrap_or(1);
+
+    let memory_bytes = std::fs::read_to_string("/proc/meminfo")
+        .ok()
+        .and_then(|s| parse_meminfo(&s));
+
+    let gpus = std::process::Command::new("nvidia-smi")
+        .args([
+            "--query-gpu=name,memory.total",
+            "--format=csv,noheader,nounits",
+        ])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| parse_nvidia_smi(&String::from_utf8_lossy(&out.stdout)))
+        .unwrap_or_default();
+
+    HardwareProfile {
+        cpu_cores,
+        memory_bytes,
+        gpus,
+    }
+}
+
+/// Parse total memory in bytes from Linux `/proc/meminfo`.
+///
+/// Expected line: `MemTotal:       16318228 kB`
+fn parse_meminfo(body: &str) -> Option<u64> {
+    let line = body.lines().find(|l| l.starts_with("MemTotal:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Parse `nvidia-smi --query-gpu=name,memory.total --format=csv,noheader,nounits`.
+///
+/// Expected lines: `NVIDIA GeForce RTX 4090, 24564` (memory in MiB)
+fn parse_nvidia_smi(body: &str) -> Vec<GpuInfo> {
+    body.lines()
+        .filter_map(|line| {
+            let (name, memory) = line.rsplit_once(',')?;
+            let name = name.trim();
+            if name.is_empty() {
+                return None;
+            }
+            Some(GpuInfo {
+                name: name.to_string(),
+                memory_bytes: memory
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+                    .map(|mib| mib * 1024 * 1024),
+            })
+        })
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -699,4 +781,28 @@ mod tests {
             assert_eq!(*kind, deserialized);
         }
     }
+
+    // -- Hardware profile ----------------------------------------------------
+
+    #[test]
+    fn parse_meminfo_reads_total() {
+        let body = "MemTotal:       16318228 kB\nMemFree:         1024 kB\n";
+        assert_eq!(parse_meminfo(body), Some(16318228 * 1024));
+        assert_eq!(parse_meminfo("MemFree: 1 kB"), None);
+    }
+
+    #[test]
+    fn parse_nvidia_smi_lists_gpus() {
+        let body = "NVIDIA GeForce RTX 4090, 24564\nTesla T4, [N/A]\n\n";
+        let gpus = parse_nvidia_smi(body);
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].name, "NVIDIA GeForce RTX 4090");
+        assert_eq!(gpus[0].memory_bytes, Some(24564 * 1024 * 1024));
+        assert_eq!(gpus[1].memory_bytes, None);
+    }
+
+    #[test]
+    fn detect_hardware_reports_cores() {
+        assert!(detect_hardware().cpu_cores >= 1);
+    }
 }
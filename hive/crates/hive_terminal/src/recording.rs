@@ -0,0 +1,870 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use hive_core::HiveConfig;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::history::{CommandHistory, CommandRecord, terminal_dir};
+use crate::vt::TerminalScreen;
+
+// ---------------------------------------------------------------------------
+// RecordingConfig
+// ---------------------------------------------------------------------------
+
+/// Where a recorded shell session writes its cast and command history.
+#[derive(Debug, Clone, Default)]
+pub struct RecordingConfig {
+    /// Directory for `<session-id>.cast` files. `None` records commands only.
+    pub cast_dir: Option<PathBuf>,
+    /// History that completed commands are appended to.
+    pub history: Option<Arc<CommandHistory>>,
+    /// Also record keystrokes sent to the terminal. Off by default because
+    /// input includes anything typed at a password prompt.
+    pub record_input: bool,
+}
+
+impl RecordingConfig {
+    /// Casts in `~/.hive/terminal/recordings/`, commands in the shared
+    /// history at `~/.hive/terminal/history.jsonl`.
+    pub fn default_location() -> Result<Self> {
+        Ok(Self {
+            cast_dir: Some(terminal_dir()?.join("recordings")),
+            history: Some(Arc::new(CommandHistory::open_default()?)),
+            record_input: false,
+        })
+    }
+
+    /// The default location with the limits from the user's settings, or
+    /// `None` when terminal recording is turned off. Casts past the age
+    /// limit are deleted here, once per new session.
+    pub fn from_settings(settings: &HiveConfig) -> Result<Option<Self>> {
+        if !settings.terminal_recording {
+            return Ok(None);
+        }
+        let max_age = (settings.terminal_history_max_days > 0)
+            .then(|| chrono::Duration::days(settings.terminal_history_max_days.into()));
+        let history = CommandHistory::open_default()?.with_limits(
+            settings.terminal_history_max_mb.saturating_mul(1024 * 1024),
+            max_age,
+        );
+        let cast_dir = terminal_dir()?.join("recordings");
+        if let Some(max_age) = max_age {
+            prune_casts(&cast_dir, Utc::now() - max_age);
+        }
+        Ok(Some(Self {
+            cast_dir: Some(cast_dir),
+            history: Some(Arc::new(history)),
+            record_input: false,
+        }))
+    }
+}
+
+/// Delete `.cast` files in `dir` last written before `cutoff`.
+fn prune_casts(dir: &Path, cutoff: DateTime<Utc>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "cast") {
+            continue;
+        }
+        let stale = entry
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .is_ok_and(|modified| DateTime::<Utc>::from(modified) < cutoff);
+        if stale && let Err(e) = fs::remove_file(&path) {
+            warn!(path = %path.display(), error = %e, "failed to delete old recording");
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// asciicast v2
+// ---------------------------------------------------------------------------
+
+/// First line of an asciicast v2 file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AsciicastHeader {
+    pub version: u8,
+    pub width: u16,
+    pub height: u16,
+    /// Unix time the recording started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+    /// Pauses longer than this many seconds are shortened on playback.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_time_limit: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+/// Kind of an asciicast event, from its one-letter code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// `o`: data written to the terminal.
+    Output,
+    /// `i`: data typed into the terminal.
+    Input,
+    /// `r`: the terminal was resized to `COLSxROWS`.
+    Resize,
+    /// `m`: a marker, e.g. a command boundary.
+    Marker,
+}
+
+impl EventKind {
+    fn code(self) -> &'static str {
+        match self {
+            Self::Output => "o",
+            Self::Input => "i",
+            Self::Resize => "r",
+            Self::Marker => "m",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "o" => Some(Self::Output),
+            "i" => Some(Self::Input),
+            "r" => Some(Self::Resize),
+            "m" => Some(Self::Marker),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsciicastEvent {
+    /// Seconds since the start of the recording.
+    pub time: f64,
+    pub kind: EventKind,
+    pub data: String,
+}
+
+impl AsciicastEvent {
+    /// The `(cols, rows)` of a resize event.
+    pub fn size(&self) -> Option<(u16, u16)> {
+        if self.kind != EventKind::Resize {
+            return None;
+        }
+        let (cols, rows) = self.data.split_once('x')?;
+        Some((cols.parse().ok()?, rows.parse().ok()?))
+    }
+}
+
+/// Writes a session as an [asciicast v2] file: a JSON header line followed by
+/// one `[time, code, data]` line per event.
+///
+/// [asciicast v2]: https://docs.asciinema.org/manual/asciicast/v2/
+pub struct AsciicastWriter {
+    out: BufWriter<File>,
+    started: Instant,
+    output: Utf8Stream,
+    input: Utf8Stream,
+}
+
+impl AsciicastWriter {
+    /// Create `path` (and its directory) and write the header.
+    pub fn create(path: &Path, cols: u16, rows: u16, title: Option<String>) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+
+        let mut env = BTreeMap::from([("TERM".to_string(), "xterm-256color".to_string())]);
+        if let Ok(shell) = std::env::var("SHELL") {
+            env.insert("SHELL".to_string(), shell);
+        }
+        let header = AsciicastHeader {
+            version: 2,
+            width: cols,
+            height: rows,
+            timestamp: Some(Utc::now().timestamp()),
+            idle_time_limit: None,
+            title,
+            env,
+        };
+
+        let mut writer = Self {
+            out: BufWriter::new(file),
+            started: Instant::now(),
+            output: Utf8Stream::default(),
+            input: Utf8Stream::default(),
+        };
+        serde_json::to_writer(&mut writer.out, &header).context("Failed to write cast header")?;
+        writer.out.write_all(b"\n")?;
+        writer.out.flush()?;
+        Ok(writer)
+    }
+
+    /// Record bytes written by the shell. A multi-byte character split
+    /// across reads is held back until it is complete.
+    pub fn output(&mut self, bytes: &[u8]) -> Result<()> {
+        let data = self.output.decode(bytes);
+        self.event(EventKind::Output, &data)
+    }
+
+    /// Record bytes typed into the terminal.
+    pub fn input(&mut self, bytes: &[u8]) -> Result<()> {
+        let data = self.input.decode(bytes);
+        self.event(EventKind::Input, &data)
+    }
+
+    pub fn resize(&mut self, cols: u16, rows: u16) -> Result<()> {
+        self.event(EventKind::Resize, &format!("{cols}x{rows}"))
+    }
+
+    pub fn marker(&mut self, label: &str) -> Result<()> {
+        self.event(EventKind::Marker, label)
+    }
+
+    fn event(&mut self, kind: EventKind, data: &str) -> Result<()> {
+        if data.is_empty() && kind != EventKind::Marker {
+            return Ok(());
+        }
+        // Microsecond precision, as written by asciinema itself.
+        let time = (self.started.elapsed().as_secs_f64() * 1e6).round() / 1e6;
+        serde_json::to_writer(&mut self.out, &(time, kind.code(), data))
+            .context("Failed to write cast event")?;
+        self.out.write_all(b"\n")?;
+        // Flushed per event so a cast can be followed while it is recorded
+        // and survives the process being killed.
+        self.out.flush().context("Failed to write cast event")
+    }
+}
+
+/// Incremental UTF-8 decoder that carries an incomplete trailing sequence
+/// over to the next chunk.
+#[derive(Default)]
+struct Utf8Stream {
+    pending: Vec<u8>,
+}
+
+impl Utf8Stream {
+    fn decode(&mut self, bytes: &[u8]) -> String {
+        self.pending.extend_from_slice(bytes);
+        let mut out = String::new();
+        loop {
+            match std::str::from_utf8(&self.pending) {
+                Ok(text) => {
+                    out.push_str(text);
+                    self.pending.clear();
+                    return out;
+                }
+                Err(e) => {
+                    let valid = e.valid_up_to();
+                    out.push_str(&String::from_utf8_lossy(&self.pending[..valid]));
+                    match e.error_len() {
+                        Some(len) => {
+                            out.push(char::REPLACEMENT_CHARACTER);
+                            self.pending.drain(..valid + len);
+                        }
+                        None => {
+                            self.pending.drain(..valid);
+                            return out;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording (replay)
+// ---------------------------------------------------------------------------
+
+/// A parsed asciicast v2 recording.
+#[derive(Debug, Clone)]
+pub struct Recording {
+    pub header: AsciicastHeader,
+    pub events: Vec<AsciicastEvent>,
+}
+
+impl Recording {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid recording {}", path.display()))
+    }
+
+    /// Parse a cast. Events with unknown codes are skipped, as the format
+    /// allows; a torn final line (from a session that was killed) is
+    /// ignored.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        let header: AsciicastHeader =
+            serde_json::from_str(lines.next().context("Recording is empty")?)
+                .context("Invalid asciicast header")?;
+        anyhow::ensure!(
+            header.version == 2,
+            "Unsupported asciicast version {}",
+            header.version
+        );
+
+        let lines: Vec<&str> = lines.collect();
+        let mut events = Vec::with_capacity(lines.len());
+        for (index, line) in lines.iter().enumerate() {
+            let (time, code, data): (f64, String, String) = match serde_json::from_str(line) {
+                Ok(event) => event,
+                Err(_) if index + 1 == lines.len() => break,
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Invalid event on line {}", index + 2));
+                }
+            };
+            if let Some(kind) = EventKind::from_code(&code) {
+                events.push(AsciicastEvent { time, kind, data });
+            }
+        }
+        Ok(Self { header, events })
+    }
+
+    /// Length of the recording in seconds.
+    pub fn duration(&self) -> f64 {
+        self.events.last().map_or(0.0, |event| event.time)
+    }
+
+    /// The screen as it looked `time` seconds into the recording.
+    pub fn screen_at(&self, time: f64) -> TerminalScreen {
+        let mut screen = TerminalScreen::new(self.header.width, self.header.height);
+        for event in self.events.iter().take_while(|event| event.time <= time) {
+            match event.kind {
+                EventKind::Output => screen.process(event.data.as_bytes()),
+                EventKind::Resize => {
+                    if let Some((cols, rows)) = event.size() {
+                        screen.resize(cols, rows);
+                    }
+                }
+                EventKind::Input | EventKind::Marker => {}
+            }
+        }
+        screen
+    }
+
+    /// Replay events in real time, scaled by `speed` (2.0 plays twice as
+    /// fast). Pauses are capped at the header's `idle_time_limit`.
+    pub async fn play(&self, speed: f64, mut on_event: impl FnMut(&AsciicastEvent)) {
+        let speed = if speed > 0.0 { speed } else { 1.0 };
+        let mut previous = 0.0;
+        for event in &self.events {
+            let mut pause = (event.time - previous).max(0.0);
+            if let Some(limit) = self.header.idle_time_limit {
+                pause = pause.min(limit);
+            }
+            previous = event.time;
+            if pause > 0.0 {
+                tokio::time::sleep(Duration::from_secs_f64(pause / speed)).await;
+            }
+            on_event(event);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Command tracking
+// ---------------------------------------------------------------------------
+
+/// Output kept per command; older output is dropped from the front.
+const COMMAND_OUTPUT_LIMIT: usize = 64 * 1024;
+
+/// Completed commands a session keeps in memory.
+const SESSION_COMMAND_LIMIT: usize = 200;
+
+/// Follows the shell integration markers in the output stream and turns
+/// them into [`CommandRecord`]s.
+///
+/// Understands the FinalTerm/VS Code sequences most shell integrations emit:
+/// `OSC 133;C` (command output starts), `OSC 133;D;<exit>` (command
+/// finished), `OSC 633;E;<command line>` and `OSC 7;file://host/<cwd>`.
+struct CommandTracker {
+    session_id: String,
+    cwd: Option<String>,
+    command_line: Option<String>,
+    running: Option<RunningCommand>,
+    finished: Vec<CommandRecord>,
+}
+
+struct RunningCommand {
+    command: String,
+    cwd: Option<String>,
+    started_at: DateTime<Utc>,
+    started: Instant,
+    output: String,
+    truncated: bool,
+}
+
+impl RunningCommand {
+    fn push(&mut self, ch: char) {
+        self.output.push(ch);
+        if self.output.len() > COMMAND_OUTPUT_LIMIT {
+            let mut cut = self.output.len() - COMMAND_OUTPUT_LIMIT;
+            while !self.output.is_char_boundary(cut) {
+                cut += 1;
+            }
+            self.output.drain(..cut);
+            self.truncated = true;
+        }
+    }
+}
+
+impl CommandTracker {
+    fn new(session_id: String) -> Self {
+        Self {
+            session_id,
+            cwd: None,
+            command_line: None,
+            running: None,
+            finished: Vec::new(),
+        }
+    }
+
+    fn start(&mut self) {
+        self.running = Some(RunningCommand {
+            command: self.command_line.take().unwrap_or_default(),
+            cwd: self.cwd.clone(),
+            started_at: Utc::now(),
+            started: Instant::now(),
+            output: String::new(),
+            truncated: false,
+        });
+    }
+
+    fn finish(&mut self, exit_code: Option<i32>) {
+        // A prompt finishing without a command having started (the first
+        // prompt, an empty line) is not a command.
+        let Some(running) = self.running.take() else {
+            return;
+        };
+        let mut output = running.output;
+        output.truncate(output.trim_end().len());
+        self.finished.push(CommandRecord {
+            session_id: self.session_id.clone(),
+            command: running.command,
+            cwd: running.cwd,
+            exit_code,
+            started_at: running.started_at,
+            duration_ms: running.started.elapsed().as_millis() as u64,
+            output,
+            output_truncated: running.truncated,
+        });
+    }
+}
+
+impl vte::Perform for CommandTracker {
+    fn print(&mut self, ch: char) {
+        if let Some(running) = &mut self.running {
+            running.push(ch);
+        }
+    }
+
+    fn execute(&mut self, byte: u8) {
+        let Some(running) = &mut self.running else {
+            return;
+        };
+        match byte {
+            b'\n' | b'\t' => running.push(byte as char),
+            0x08 => {
+                running.output.pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        let text =
+            |index: usize| String::from_utf8_lossy(params.get(index).copied().unwrap_or_default());
+        // Everything from `index` on, with the `;` separators put back.
+        let rest = |index: usize| {
+            params
+                .get(index..)
+                .unwrap_or_default()
+                .iter()
+                .map(|param| String::from_utf8_lossy(param))
+                .collect::<Vec<_>>()
+                .join(";")
+        };
+
+        match params.first().copied() {
+            Some(b"133") => match params.get(1).copied() {
+                Some(b"C") => self.start(),
+                Some(b"D") => self.finish(text(2).trim().parse().ok()),
+                _ => {}
+            },
+            Some(b"633") if params.get(1).copied() == Some(b"E") => {
+                self.command_line = Some(rest(2).trim().to_string());
+            }
+            Some(b"7") => {
+                if let Some(path) = file_url_path(&rest(1)) {
+                    self.cwd = Some(path);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The path of a `file://host/path` URL, percent-decoded.
+fn file_url_path(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("file://")?;
+    let path = &rest[rest.find('/')?..];
+
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut iter = path.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+// ---------------------------------------------------------------------------
+// SessionRecorder
+// ---------------------------------------------------------------------------
+
+/// Records one terminal session: the asciicast, and each command with its
+/// cwd, exit code, duration and output.
+pub struct SessionRecorder {
+    session_id: String,
+    cast: Option<AsciicastWriter>,
+    cast_path: Option<PathBuf>,
+    record_input: bool,
+    history: Option<Arc<CommandHistory>>,
+    parser: vte::Parser,
+    tracker: CommandTracker,
+    commands: VecDeque<CommandRecord>,
+}
+
+impl SessionRecorder {
+    pub fn new(config: &RecordingConfig, cols: u16, rows: u16) -> Result<Self> {
+        let session_id = uuid::Uuid::new_v4().to_string();
+
+        let (cast, cast_path) = match &config.cast_dir {
+            Some(dir) => {
+                let path = dir.join(format!("{session_id}.cast"));
+                let title = format!("Hive terminal {session_id}");
+                (
+                    Some(AsciicastWriter::create(&path, cols, rows, Some(title))?),
+                    Some(path),
+                )
+            }
+            None => (None, None),
+        };
+
+        Ok(Self {
+            tracker: CommandTracker::new(session_id.clone()),
+            session_id,
+            cast,
+            cast_path,
+            record_input: config.record_input,
+            history: config.history.clone(),
+            parser: vte::Parser::new(),
+            commands: VecDeque::new(),
+        })
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Path of the asciicast file, if one is being written.
+    pub fn cast_path(&self) -> Option<&Path> {
+        self.cast_path.as_deref()
+    }
+
+    /// Commands completed in this session, oldest first. Only the most
+    /// recent ones are kept; the full record is in the history.
+    pub fn commands(&self) -> Vec<CommandRecord> {
+        self.commands.iter().cloned().collect()
+    }
+
+    pub fn record_output(&mut self, bytes: &[u8]) {
+        self.write_cast(|cast| cast.output(bytes));
+
+        self.parser.advance(&mut self.tracker, bytes);
+        for record in std::mem::take(&mut self.tracker.finished) {
+            let label = format!("exit {}: {}", exit_label(record.exit_code), record.command);
+            self.write_cast(|cast| cast.marker(&label));
+            if let Some(history) = &self.history
+                && let Err(e) = history.append(&record)
+            {
+                warn!(error = %e, "failed to append to terminal history");
+            }
+            if self.commands.len() == SESSION_COMMAND_LIMIT {
+                self.commands.pop_front();
+            }
+            self.commands.push_back(record);
+        }
+    }
+
+    pub fn record_input(&mut self, bytes: &[u8]) {
+        if self.record_input {
+            self.write_cast(|cast| cast.input(bytes));
+        }
+    }
+
+    pub fn record_resize(&mut self, cols: u16, rows: u16) {
+        self.write_cast(|cast| cast.resize(cols, rows));
+    }
+
+    /// Write to the cast, giving up on it after the first failure so a full
+    /// disk does not produce a warning per read.
+    fn write_cast(&mut self, write: impl FnOnce(&mut AsciicastWriter) -> Result<()>) {
+        if let Some(cast) = &mut self.cast
+            && let Err(e) = write(cast)
+        {
+            warn!(session = %self.session_id, error = %e, "terminal recording stopped");
+            self.cast = None;
+        }
+    }
+}
+
+fn exit_label(code: Option<i32>) -> String {
+    code.map_or_else(|| "?".into(), |code| code.to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Shell integration
+// ---------------------------------------------------------------------------
+
+/// Bash startup file that loads the user's configuration and then marks
+/// prompts and commands with OSC 133 / 633 / 7 sequences. Terminals that do
+/// not understand the sequences ignore them.
+const BASH_INTEGRATION: &str = r#"# Hive terminal shell integration.
+if [ -f /etc/bash.bashrc ]; then . /etc/bash.bashrc; fi
+if [ -f "$HOME/.bashrc" ]; then . "$HOME/.bashrc"; fi
+
+__hive_prompt() {
+    local status=$?
+    printf '\033]133;D;%s\007\033]7;file://%s%s\007\033]133;A\007' \
+        "$status" "${HOSTNAME:-localhost}" "$PWD"
+    return $status
+}
+
+__hive_preexec() {
+    local line
+    line=$(HISTTIMEFORMAT= builtin history 1)
+    [[ $line =~ ^[[:space:]]*[0-9]+\*?[[:space:]]+(.*)$ ]] && line=${BASH_REMATCH[1]}
+    printf '\033]633;E;%s\007\033]133;C\007' "$line"
+}
+
+PROMPT_COMMAND="__hive_prompt${PROMPT_COMMAND:+; $PROMPT_COMMAND}"
+PS0='$(__hive_preexec)'
+"#;
+
+/// Write the bash integration script to the temp directory and return its
+/// path, for use with `bash --rcfile`.
+pub(crate) fn install_bash_integration() -> Result<PathBuf> {
+    let dir = std::env::temp_dir();
+    let path = dir.join("hive-bash-integration.sh");
+    if fs::read_to_string(&path).is_ok_and(|existing| existing == BASH_INTEGRATION) {
+        return Ok(path);
+    }
+    // Written under a unique name and renamed so a shell starting
+    // concurrently never sources a partial file.
+    let staging = dir.join(format!(
+        "hive-bash-integration.{}.tmp",
+        uuid::Uuid::new_v4()
+    ));
+    fs::write(&staging, BASH_INTEGRATION)
+        .with_context(|| format!("Failed to write {}", staging.display()))?;
+    fs::rename(&staging, &path).with_context(|| format!("Failed to install {}", path.display()))?;
+    Ok(path)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commands_only() -> RecordingConfig {
+        RecordingConfig::default()
+    }
+
+    #[test]
+    fn tracker_records_command_boundaries() {
+        let mut recorder = SessionRecorder::new(&commands_only(), 80, 24).unwrap();
+        recorder.record_output(b"\x1b]133;D;0\x07\x1b]7;file://host/home/me/my%20project\x07");
+        recorder.record_output(b"\x1b]133;A\x07$ ");
+        recorder.record_output(b"cargo test\r\n\x1b]633;E;cargo test\x07\x1b]133;C\x07");
+        recorder.record_output(b"\x1b[31merror\x1b[0m: could not compile\r\n");
+        recorder.record_output(b"\x1b]133;D;101\x07\x1b]133;A\x07$ ");
+
+        let commands = recorder.commands();
+        assert_eq!(commands.len(), 1);
+        let command = &commands[0];
+        assert_eq!(command.command, "cargo test");
+        assert_eq!(command.cwd.as_deref(), Some("/home/me/my project"));
+        assert_eq!(command.exit_code, Some(101));
+        assert_eq!(command.output, "error: could not compile");
+        assert!(command.failed());
+        assert_eq!(command.session_id, recorder.session_id());
+    }
+
+    #[test]
+    fn markers_split_across_reads_are_tracked() {
+        let mut recorder = SessionRecorder::new(&commands_only(), 80, 24).unwrap();
+        let stream = b"\x1b]633;E;echo a;b\x07\x1b]133;C\x07a\r\n\x1b]133;D;0\x07";
+        for byte in stream {
+            recorder.record_output(std::slice::from_ref(byte));
+        }
+        let commands = recorder.commands();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].command, "echo a;b");
+        assert_eq!(commands[0].output, "a");
+        assert!(!commands[0].failed());
+    }
+
+    #[test]
+    fn long_output_keeps_the_tail() {
+        let mut recorder = SessionRecorder::new(&commands_only(), 80, 24).unwrap();
+        recorder.record_output(b"\x1b]133;C\x07");
+        for _ in 0..2000 {
+            recorder.record_output(&[b'x'; 64]);
+            recorder.record_output(b"\n");
+        }
+        recorder.record_output(b"the end\x1b]133;D;1\x07");
+
+        let command = &recorder.commands()[0];
+        assert!(command.output_truncated);
+        assert!(command.output.len() <= COMMAND_OUTPUT_LIMIT);
+        assert!(command.output.ends_with("the end"));
+    }
+
+    #[test]
+    fn completed_commands_are_appended_to_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = Arc::new(CommandHistory::new(dir.path().join("history.jsonl")));
+        let config = RecordingConfig {
+            history: Some(Arc::clone(&history)),
+            ..RecordingConfig::default()
+        };
+        let mut recorder = SessionRecorder::new(&config, 80, 24).unwrap();
+        recorder.record_output(b"\x1b]633;E;false\x07\x1b]133;C\x07\x1b]133;D;1\x07");
+
+        let failed = history.last_failed().unwrap().unwrap();
+        assert_eq!(failed.command, "false");
+        assert_eq!(failed.session_id, recorder.session_id());
+    }
+
+    #[test]
+    fn cast_round_trips_through_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = RecordingConfig {
+            cast_dir: Some(dir.path().join("casts")),
+            record_input: true,
+            ..RecordingConfig::default()
+        };
+        let mut recorder = SessionRecorder::new(&config, 20, 5).unwrap();
+        recorder.record_input(b"ls\r");
+        // A multi-byte character split across two reads.
+        recorder.record_output(b"caf\xc3");
+        recorder.record_output(b"\xa9\r\n");
+        recorder.record_resize(30, 6);
+        recorder.record_output(b"\x1b[2J\x1b[Hdone");
+        let path = recorder.cast_path().unwrap().to_path_buf();
+        drop(recorder);
+
+        let recording = Recording::load(&path).unwrap();
+        assert_eq!(recording.header.version, 2);
+        assert_eq!((recording.header.width, recording.header.height), (20, 5));
+        let kinds: Vec<_> = recording.events.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                EventKind::Input,
+                EventKind::Output,
+                EventKind::Output,
+                EventKind::Resize,
+                EventKind::Output
+            ]
+        );
+        assert_eq!(recording.events[1].data, "caf");
+        assert_eq!(recording.events[2].data, "é\r\n");
+        assert_eq!(recording.events[3].size(), Some((30, 6)));
+
+        let before = recording.screen_at(recording.events[2].time);
+        assert_eq!(before.text().lines().next(), Some("café"));
+        let after = recording.screen_at(recording.duration());
+        assert_eq!((after.cols(), after.rows()), (30, 6));
+        assert_eq!(after.text().lines().next(), Some("done"));
+    }
+
+    #[test]
+    fn input_is_not_recorded_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = RecordingConfig {
+            cast_dir: Some(dir.path().to_path_buf()),
+            ..RecordingConfig::default()
+        };
+        let mut recorder = SessionRecorder::new(&config, 80, 24).unwrap();
+        recorder.record_input(b"hunter2\r");
+        let path = recorder.cast_path().unwrap().to_path_buf();
+        drop(recorder);
+
+        let recording = Recording::load(&path).unwrap();
+        assert!(recording.events.is_empty());
+    }
+
+    #[test]
+    fn parse_skips_unknown_events_and_torn_tail() {
+        let cast = concat!(
+            "{\"version\": 2, \"width\": 80, \"height\": 24}\n",
+            "[0.5, \"o\", \"hi\"]\n",
+            "[0.6, \"x\", \"future\"]\n",
+            "[1.0, \"o\", \"the\n",
+        );
+        let recording = Recording::parse(cast).unwrap();
+        assert_eq!(recording.events.len(), 1);
+        assert_eq!(recording.duration(), 0.5);
+
+        assert!(Recording::parse("{\"version\": 1, \"width\": 80, \"height\": 24}").is_err());
+        assert!(Recording::parse("").is_err());
+    }
+
+    #[tokio::test]
+    async fn play_caps_idle_time() {
+        let cast = concat!(
+            "{\"version\": 2, \"width\": 80, \"height\": 24, \"idle_time_limit\": 0.01}\n",
+            "[0.0, \"o\", \"a\"]\n",
+            "[30.0, \"o\", \"b\"]\n",
+        );
+        let recording = Recording::parse(cast).unwrap();
+        let mut seen = String::new();
+        let started = Instant::now();
+        recording
+            .play(1.0, |event| seen.push_str(&event.data))
+            .await;
+        assert_eq!(seen, "ab");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn file_url_paths_are_decoded() {
+        assert_eq!(
+            file_url_path("file://host/tmp/a%20b").as_deref(),
+            Some("/tmp/a b")
+        );
+        assert_eq!(file_url_path("file:///srv").as_deref(), Some("/srv"));
+        assert_eq!(file_url_path("https://example.com/"), None);
+        assert_eq!(file_url_path("file://host/bad%2"), None);
+    }
+}
@@ -7,13 +7,17 @@
 //! - Resource limits (memory, CPU, timeout)
 //! - Network isolation by default
 //! - Snapshot support for reproducibility
+//! - Project dev container / compose environments (see [`crate::devcontainer`])
 
 use anyhow::{Result, bail};
+use hive_core::config::HiveConfig;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tracing::{debug, warn};
 
+use crate::devcontainer::{self, ComposeProject, EnvironmentPlan, PrimaryContainer};
 use crate::docker::{ContainerConfig, DockerSandbox, ExecResult, ResourceLimits, VolumeMount};
 
 // ---------------------------------------------------------------------------
@@ -35,6 +39,18 @@ pub struct SandboxConfig {
     pub network_enabled: bool,
     /// Keep the container alive between exec calls within a session (default: true).
     pub persist_between_calls: bool,
+    /// Use the workspace's `devcontainer.json` when present, falling back
+    /// to `image` (default: false). The config comes from the repository,
+    /// so this is opted into per workspace; see [`Self::for_workspace`].
+    #[serde(default)]
+    pub use_project_environment: bool,
+    /// Compose project name for services started with the sandbox
+    /// (default: derived from the workspace path).
+    #[serde(default)]
+    pub compose_project: Option<String>,
+    /// Labels attached to the sandbox container.
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
 }
 
 impl Default for SandboxConfig {
@@ -46,6 +62,20 @@ impl Default for SandboxConfig {
             timeout_secs: 300,
             network_enabled: false,
             persist_between_calls: true,
+            use_project_environment: false,
+            compose_project: None,
+            labels: HashMap::new(),
+        }
+    }
+}
+
+impl SandboxConfig {
+    /// The default config for a sandbox on `workspace`, using its dev
+    /// container config only if the user opted the workspace in.
+    pub fn for_workspace(settings: &HiveConfig, workspace: &Path) -> Self {
+        Self {
+            use_project_environment: settings.allows_project_environment(workspace),
+            ..Self::default()
         }
     }
 }
@@ -69,6 +99,8 @@ pub struct AgentSandbox {
     container_id: Option<String>,
     workspace_mount: PathBuf,
     config: SandboxConfig,
+    environment: Option<EnvironmentPlan>,
+    compose: Option<ComposeProject>,
 }
 
 impl AgentSandbox {
@@ -80,6 +112,8 @@ impl AgentSandbox {
             container_id: None,
             workspace_mount: workspace.into(),
             config,
+            environment: None,
+            compose: None,
         }
     }
 
@@ -95,6 +129,8 @@ impl AgentSandbox {
             container_id: None,
             workspace_mount: workspace.into(),
             config,
+            environment: None,
+            compose: None,
         }
     }
 
@@ -113,21 +149,128 @@ impl AgentSandbox {
         self.container_id.as_deref()
     }
 
-    /// Start the sandbox by creating and starting a container.
+    /// Start the sandbox.
+    ///
+    /// When `use_project_environment` is set the workspace's dev container
+    /// config decides the image (or Dockerfile build), and the compose
+    /// services it references are sanitized and brought up first. With
+    /// `network_enabled` off their networks are internal-only. Setup
+    /// commands from the config run before this returns.
     pub fn start(&mut self) -> Result<()> {
         if self.container_id.is_some() {
             bail!("Sandbox already started");
         }
 
-        let workspace_str = self.workspace_mount.to_string_lossy().to_string();
+        let plan = if self.config.use_project_environment {
+            EnvironmentPlan::resolve(&self.workspace_mount, &self.config.image)?
+        } else {
+            EnvironmentPlan::image(&self.config.image)
+        };
+        if let Some(ref source) = plan.source {
+            debug!(source = %source.display(), "using project sandbox environment");
+        }
+
+        let project_name = self
+            .config
+            .compose_project
+            .clone()
+            .unwrap_or_else(|| devcontainer::project_name_for(&self.workspace_mount));
+
+        if let Some(ref compose) = plan.compose {
+            let project = ComposeProject::sanitized(
+                &project_name,
+                compose,
+                &self.workspace_mount,
+                self.config.network_enabled,
+            )?;
+            let up = if self.is_simulation() {
+                Ok(())
+            } else {
+                project.up(&compose.services)
+            };
+            self.compose = Some(project);
+            if let Err(e) = up {
+                // `up` may have started some services before failing.
+                self.teardown_compose();
+                return Err(e);
+            }
+        }
+
+        let mut container_config = self.container_config(&plan);
+        let started = match plan.primary {
+            PrimaryContainer::ComposeService(ref service) => {
+                let id = match self.compose {
+                    Some(ref project) if !self.is_simulation() => {
+                        project.service_container(service)
+                    }
+                    _ => Ok(format!("{project_name}-{service}-1")),
+                };
+                container_config.image = format!("{project_name}-{service}");
+                id.map(|id| self.docker.adopt_container(id, container_config))
+            }
+            PrimaryContainer::Build {
+                ref dockerfile,
+                ref context,
+                ref args,
+                ref target,
+            } => {
+                let tag = format!("{project_name}-dev:latest");
+                let built = if self.is_simulation() {
+                    Ok(tag)
+                } else {
+                    devcontainer::build_image(&tag, dockerfile, context, args, target.as_deref())
+                };
+                built.and_then(|tag| {
+                    container_config.image = tag;
+                    self.create_and_start(container_config)
+                })
+            }
+            PrimaryContainer::Image(ref image) => {
+                container_config.image = image.clone();
+                self.create_and_start(container_config)
+            }
+        };
+        let id = match started {
+            Ok(id) => id,
+            Err(e) => {
+                self.teardown_compose();
+                return Err(e);
+            }
+        };
+        debug!(container_id = %id, "sandbox started");
+        self.container_id = Some(id);
+
+        for command in &plan.setup_commands {
+            let command = format!("cd '{}' && {command}", plan.workspace_folder);
+            match self.exec(&command) {
+                Ok(result) if result.exit_code != 0 => warn!(
+                    command = %command,
+                    exit_code = result.exit_code,
+                    stderr = %result.stderr.trim(),
+                    "sandbox setup command failed"
+                ),
+                Ok(_) => {}
+                Err(e) => warn!(error = %e, command = %command, "sandbox setup command failed"),
+            }
+        }
+        self.environment = Some(plan);
+        Ok(())
+    }
 
-        let container_config = ContainerConfig {
+    fn container_config(&self, plan: &EnvironmentPlan) -> ContainerConfig {
+        let network_enabled = self.config.network_enabled;
+        let network = self
+            .compose
+            .as_ref()
+            .filter(|_| network_enabled)
+            .map(ComposeProject::default_network);
+        ContainerConfig {
             image: self.config.image.clone(),
             name: None,
-            env_vars: Default::default(),
+            env_vars: plan.env.clone(),
             volumes: vec![VolumeMount {
-                host_path: workspace_str,
-                container_path: "/workspace".into(),
+                host_path: self.workspace_mount.to_string_lossy().to_string(),
+                container_path: plan.workspace_folder.clone(),
                 read_only: false,
             }],
             resource_limits: ResourceLimits {
@@ -136,15 +279,33 @@ impl AgentSandbox {
                 disk_mb: None,
                 timeout_secs: Some(self.config.timeout_secs),
             },
-            working_dir: Some("/workspace".into()),
-            network_enabled: self.config.network_enabled,
-        };
+            working_dir: Some(plan.workspace_folder.clone()),
+            network_enabled,
+            network,
+            ports: plan.forward_ports.clone(),
+            labels: self.config.labels.clone(),
+        }
+    }
 
-        let id = self.docker.create_container(container_config)?;
-        self.docker.start_container(&id)?;
-        debug!(container_id = %id, "sandbox started");
-        self.container_id = Some(id);
-        Ok(())
+    fn create_and_start(&mut self, config: ContainerConfig) -> Result<String> {
+        let id = self.docker.create_container(config)?;
+        if let Err(e) = self.docker.start_container(&id) {
+            let _ = self.docker.remove_container(&id);
+            return Err(e);
+        }
+        Ok(id)
+    }
+
+    fn teardown_compose(&mut self) {
+        let Some(project) = self.compose.take() else {
+            return;
+        };
+        if !self.is_simulation()
+            && let Err(e) = devcontainer::compose_down(&project.name)
+        {
+            warn!(error = %e, project = %project.name, "failed to remove compose project");
+        }
+        project.remove_files();
     }
 
     /// Execute a command inside the running container.
@@ -170,6 +331,8 @@ impl AgentSandbox {
             debug!(container_id = %id, "sandbox stopped");
         }
         self.container_id = None;
+        self.environment = None;
+        self.teardown_compose();
         Ok(())
     }
 
@@ -205,14 +368,24 @@ impl AgentSandbox {
     pub fn config(&self) -> &SandboxConfig {
         &self.config
     }
+
+    /// The environment the running sandbox was started from.
+    pub fn environment(&self) -> Option<&EnvironmentPlan> {
+        self.environment.as_ref()
+    }
+
+    /// Name of the compose project started with the sandbox, if any.
+    pub fn compose_project(&self) -> Option<&str> {
+        self.compose.as_ref().map(|project| project.name.as_str())
+    }
 }
 
 impl Drop for AgentSandbox {
     fn drop(&mut self) {
-        if self.container_id.is_some() {
-            if let Err(e) = self.stop() {
-                warn!(error = %e, "failed to cleanup sandbox on drop");
-            }
+        if (self.container_id.is_some() || self.compose.is_some())
+            && let Err(e) = self.stop()
+        {
+            warn!(error = %e, "failed to cleanup sandbox on drop");
         }
     }
 }
@@ -336,6 +509,166 @@ mod tests {
         drop(sandbox);
     }
 
+    #[test]
+    fn starts_from_devcontainer_image() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".devcontainer.json"),
+            r#"{ "image": "rust:1.80", "workspaceFolder": "/src",
+                 "containerEnv": { "CI": "1" }, "postCreateCommand": "cargo fetch" }"#,
+        )
+        .unwrap();
+
+        let config = SandboxConfig {
+            use_project_environment: true,
+            ..Default::default()
+        };
+        let mut sandbox =
+            AgentSandbox::with_docker(DockerSandbox::new_simulated(), dir.path(), config);
+        sandbox.start().unwrap();
+
+        let env = sandbox.environment().unwrap();
+        assert_eq!(env.workspace_folder, "/src");
+        assert_eq!(env.setup_commands, ["cargo fetch"]);
+        assert!(sandbox.compose_project().is_none());
+
+        let id = sandbox.container_id().unwrap();
+        let container = sandbox.docker.get_container(id).unwrap();
+        assert_eq!(container.config.image, "rust:1.80");
+        assert_eq!(container.config.working_dir.as_deref(), Some("/src"));
+        assert_eq!(container.config.env_vars["CI"], "1");
+        assert!(!container.config.network_enabled);
+    }
+
+    #[test]
+    fn project_environment_is_off_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".devcontainer.json"),
+            r#"{ "image": "rust:1.80" }"#,
+        )
+        .unwrap();
+
+        let mut sandbox = AgentSandbox::with_docker(
+            DockerSandbox::new_simulated(),
+            dir.path(),
+            SandboxConfig::default(),
+        );
+        sandbox.start().unwrap();
+
+        let id = sandbox.container_id().unwrap();
+        let container = sandbox.docker.get_container(id).unwrap();
+        assert_eq!(container.config.image, "hive-sandbox:latest");
+    }
+
+    #[test]
+    fn bare_compose_file_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("docker-compose.yml"),
+            "services:\n  db:\n    image: postgres:16\n    privileged: true\n",
+        )
+        .unwrap();
+
+        let config = SandboxConfig {
+            use_project_environment: true,
+            ..Default::default()
+        };
+        let mut sandbox =
+            AgentSandbox::with_docker(DockerSandbox::new_simulated(), dir.path(), config);
+        sandbox.start().unwrap();
+        assert!(sandbox.compose_project().is_none());
+
+        let id = sandbox.container_id().unwrap();
+        let container = sandbox.docker.get_container(id).unwrap();
+        assert_eq!(container.config.image, "hive-sandbox:latest");
+        assert!(container.config.network.is_none());
+    }
+
+    #[test]
+    fn compose_service_is_adopted() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".devcontainer.json"),
+            r#"{ "dockerComposeFile": "docker-compose.yml", "service": "app" }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("docker-compose.yml"),
+            "services:\n  app:\n    image: rust:1.80\n  db:\n    image: postgres:16\n",
+        )
+        .unwrap();
+
+        let config = SandboxConfig {
+            use_project_environment: true,
+            compose_project: Some("hive-swarm-run1-team-a".into()),
+            labels: HashMap::from([("hive.swarm.team".into(), "team-a".into())]),
+            ..Default::default()
+        };
+        let mut sandbox =
+            AgentSandbox::with_docker(DockerSandbox::new_simulated(), dir.path(), config);
+        sandbox.start().unwrap();
+        assert_eq!(sandbox.compose_project(), Some("hive-swarm-run1-team-a"));
+
+        let id = sandbox.container_id().unwrap();
+        assert_eq!(id, "hive-swarm-run1-team-a-app-1");
+        let container = sandbox.docker.get_container(id).unwrap();
+        // Networking stays off unless configured, even with compose.
+        assert!(!container.config.network_enabled);
+        assert!(container.config.network.is_none());
+        assert_eq!(container.config.labels["hive.swarm.team"], "team-a");
+
+        assert_eq!(sandbox.exec("true").unwrap().exit_code, 0);
+        sandbox.stop().unwrap();
+        assert!(!sandbox.is_running());
+        assert!(sandbox.compose_project().is_none());
+    }
+
+    #[test]
+    fn compose_network_is_joined_when_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".devcontainer.json"),
+            r#"{ "dockerComposeFile": "compose.yaml", "service": "app" }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("compose.yaml"),
+            "services:\n  app:\n    image: rust:1.80\n",
+        )
+        .unwrap();
+
+        let config = SandboxConfig {
+            use_project_environment: true,
+            network_enabled: true,
+            compose_project: Some("proj".into()),
+            ..Default::default()
+        };
+        let mut sandbox =
+            AgentSandbox::with_docker(DockerSandbox::new_simulated(), dir.path(), config);
+        sandbox.start().unwrap();
+
+        let id = sandbox.container_id().unwrap();
+        let container = sandbox.docker.get_container(id).unwrap();
+        assert!(container.config.network_enabled);
+        assert_eq!(container.config.network.as_deref(), Some("proj_default"));
+    }
+
+    #[test]
+    fn project_environment_is_opt_in_per_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = HiveConfig::default();
+        assert!(!SandboxConfig::for_workspace(&settings, dir.path()).use_project_environment);
+
+        settings
+            .sandbox_project_environments
+            .push(dir.path().to_path_buf());
+        assert!(SandboxConfig::for_workspace(&settings, dir.path()).use_project_environment);
+        let nested = dir.path().join(".hive-worktrees").join("team-a");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert!(SandboxConfig::for_workspace(&settings, &nested).use_project_environment);
+    }
+
     #[test]
     fn config_serialization() {
         let config = SandboxConfig::default();
@@ -343,5 +676,14 @@ mod tests {
         let restored: SandboxConfig = serde_json::from_str(&json).unwrap();
         assert_eq!(restored.image, config.image);
         assert_eq!(restored.memory_mb, config.memory_mb);
+
+        // Configs saved before project environments existed still load.
+        let legacy: SandboxConfig = serde_json::from_str(
+            r#"{"image":"x","memory_mb":1,"cpu_cores":1.0,"timeout_secs":1,
+                "network_enabled":false,"persist_between_calls":true}"#,
+        )
+        .unwrap();
+        assert!(!legacy.use_project_environment);
+        assert!(legacy.compose_project.is_none());
     }
 }
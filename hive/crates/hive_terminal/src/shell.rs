@@ -1,12 +1,18 @@
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use anyhow::{Context, Result};
+use hive_core::HiveConfig;
+use portable_pty::{Child, CommandBuilder, MasterPty, PtySize, native_pty_system};
 use serde::{Deserialize, Serialize};
-use tokio::io::AsyncWriteExt;
-use tokio::process::{Child, ChildStdin, Command};
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, warn};
 
+use crate::history::CommandRecord;
+use crate::recording::{RecordingConfig, SessionRecorder, install_bash_integration};
+use crate::vt::{ScreenSnapshot, TerminalScreen};
+
 // ---------------------------------------------------------------------------
 // ShellOutput
 // ---------------------------------------------------------------------------
@@ -14,9 +20,10 @@ use tracing::{debug, warn};
 /// A single chunk of output from an interactive shell session.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ShellOutput {
-    /// Data received on the child's stdout.
+    /// A line of terminal output with escape sequences stripped. The PTY
+    /// merges the child's stdout and stderr into this stream.
     Stdout(String),
-    /// Data received on the child's stderr.
+    /// A diagnostic about the session itself (e.g. a failed PTY read).
     Stderr(String),
     /// The shell process exited with this code.
     Exit(i32),
@@ -26,19 +33,33 @@ pub enum ShellOutput {
 // InteractiveShell
 // ---------------------------------------------------------------------------
 
-/// Interactive shell backed by `tokio::process::Command`.
+/// Interactive shell running in a pseudo-terminal.
 ///
 /// Spawns a platform-appropriate shell (`cmd.exe` on Windows, `/bin/bash` or
-/// `/bin/sh` on Unix) and provides async read/write access to its
-/// stdin/stdout/stderr via an mpsc channel.
+/// `/bin/sh` on Unix) attached to a PTY (ConPTY on Windows), so programs see
+/// a real terminal: `vim`, `htop`, `git add -p`, progress bars and coloured
+/// prompts behave as they would in any terminal emulator.
+///
+/// Output is available three ways:
+/// - [`subscribe_raw`](Self::subscribe_raw): the raw byte stream, for UIs
+///   that render the terminal themselves.
+/// - [`screen`](Self::screen) / [`screen_text`](Self::screen_text): the
+///   screen as maintained by a [`TerminalScreen`] emulator, for agents that
+///   need to read what is displayed.
+/// - [`read`](Self::read) / [`read_async`](Self::read_async): completed
+///   lines with escape sequences stripped, for line-oriented consumers.
 ///
-/// This is intentionally a lightweight process wrapper. True PTY support
-/// (resize signalling, raw mode, etc.) would require `portable-pty` or
-/// similar, which can be added later.
+/// A shell started with [`with_recording`](Self::with_recording) also writes
+/// an asciicast of the session and records each command it runs; see
+/// [`commands`](Self::commands).
 pub struct InteractiveShell {
-    child: Child,
-    stdin: ChildStdin,
+    child: Box<dyn Child + Send + Sync>,
+    master: Box<dyn MasterPty + Send>,
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
     output_rx: mpsc::Receiver<ShellOutput>,
+    raw_tx: broadcast::Sender<Vec<u8>>,
+    screen: Arc<Mutex<TerminalScreen>>,
+    recorder: Option<Arc<Mutex<SessionRecorder>>>,
     cols: u16,
     rows: u16,
     cwd: PathBuf,
@@ -78,12 +99,44 @@ impl InteractiveShell {
     /// Channel buffer size for output messages.
     const OUTPUT_CHANNEL_SIZE: usize = 1024;
 
+    /// Raw output chunks buffered per subscriber before it starts lagging.
+    const RAW_CHANNEL_SIZE: usize = 256;
+
     /// Spawn a new interactive shell.
     ///
     /// If `cwd` is `None`, the current working directory of the parent process
     /// is used. If `cwd` is `Some(path)`, the shell starts in that directory
     /// (the path must exist and be a directory).
     pub fn new(cwd: Option<&Path>) -> Result<Self> {
+        Self::spawn(cwd, None)
+    }
+
+    /// Spawn a shell whose session is recorded as described by `config`.
+    ///
+    /// Under bash, a startup file that sources the user's `~/.bashrc` and
+    /// adds shell integration markers is loaded, so each command is recorded
+    /// with its working directory, exit code, duration and output. Other
+    /// shells are recorded to the cast only.
+    pub fn with_recording(cwd: Option<&Path>, config: RecordingConfig) -> Result<Self> {
+        Self::spawn(cwd, Some(config))
+    }
+
+    /// Spawn a shell recorded to `~/.hive/terminal/` within the limits in
+    /// `settings` (see [`RecordingConfig::from_settings`]). The shell is not
+    /// recorded when recording is turned off or the home directory cannot be
+    /// determined.
+    pub fn new_recorded(cwd: Option<&Path>, settings: &HiveConfig) -> Result<Self> {
+        match RecordingConfig::from_settings(settings) {
+            Ok(Some(config)) => Self::with_recording(cwd, config),
+            Ok(None) => Self::new(cwd),
+            Err(e) => {
+                warn!(error = %e, "terminal recording unavailable");
+                Self::new(cwd)
+            }
+        }
+    }
+
+    fn spawn(cwd: Option<&Path>, recording: Option<RecordingConfig>) -> Result<Self> {
         let working_dir = match cwd {
             Some(p) => {
                 anyhow::ensure!(
@@ -98,79 +151,117 @@ impl InteractiveShell {
         };
 
         let (program, args) = shell_program();
+        let (cols, rows) = (Self::DEFAULT_COLS, Self::DEFAULT_ROWS);
 
-        debug!(shell = program, dir = %working_dir.display(), "spawning interactive shell");
-
-        let mut cmd = Command::new(program);
-        cmd.args(&args)
-            .current_dir(&working_dir)
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped());
+        let recorder = match &recording {
+            Some(config) => Some(Arc::new(Mutex::new(SessionRecorder::new(
+                config, cols, rows,
+            )?))),
+            None => None,
+        };
+        let rcfile = match recording {
+            Some(_) if program.ends_with("bash") => match install_bash_integration() {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    warn!(error = %e, "shell integration unavailable; commands will not be recorded");
+                    None
+                }
+            },
+            _ => None,
+        };
 
-        // On Unix, create a new process group so `kill` targets the whole tree.
-        // tokio::process::Command implements std::os::unix::process::CommandExt.
-        #[cfg(unix)]
-        {
-            cmd.process_group(0);
-        }
+        debug!(shell = program, dir = %working_dir.display(), "spawning interactive shell");
 
-        // On Windows, create a new process group for job-object-style cleanup.
-        // tokio::process::Command re-exports creation_flags from CommandExt.
-        #[cfg(windows)]
-        {
-            cmd.creation_flags(0x00000200); // CREATE_NEW_PROCESS_GROUP
+        let pair = native_pty_system()
+            .openpty(pty_size(cols, rows))
+            .context("Failed to open a pseudo-terminal")?;
+
+        // The child becomes the session leader of the PTY, so job control and
+        // `kill` reach its whole process group.
+        let mut cmd = CommandBuilder::new(program);
+        cmd.args(&args);
+        if let Some(rcfile) = &rcfile {
+            cmd.arg("--rcfile");
+            cmd.arg(rcfile);
         }
+        cmd.cwd(&working_dir);
+        cmd.env("TERM", "xterm-256color");
 
-        let mut child = cmd
-            .spawn()
+        let child = pair
+            .slave
+            .spawn_command(cmd)
             .with_context(|| format!("Failed to spawn shell: {program}"))?;
-
-        let stdin = child.stdin.take().context("Failed to open shell stdin")?;
-        let stdout = child.stdout.take().context("Failed to open shell stdout")?;
-        let stderr = child.stderr.take().context("Failed to open shell stderr")?;
+        // Only the child holds the slave side; dropping ours lets the reader
+        // see EOF once the shell exits.
+        drop(pair.slave);
+
+        let reader = pair
+            .master
+            .try_clone_reader()
+            .context("Failed to open PTY reader")?;
+        let writer = pair
+            .master
+            .take_writer()
+            .context("Failed to open PTY writer")?;
 
         let (tx, rx) = mpsc::channel(Self::OUTPUT_CHANNEL_SIZE);
+        let (raw_tx, _) = broadcast::channel(Self::RAW_CHANNEL_SIZE);
+        let screen = Arc::new(Mutex::new(TerminalScreen::new(cols, rows)));
 
-        // Spawn background reader for stdout.
-        let tx_out = tx.clone();
-        tokio::spawn(async move {
-            read_stream_to_channel(stdout, tx_out, false).await;
-        });
-
-        // Spawn background reader for stderr.
-        let tx_err = tx;
-        tokio::spawn(async move {
-            read_stream_to_channel(stderr, tx_err, true).await;
-        });
+        // PTY reads block, so they run on a dedicated thread rather than the
+        // async runtime.
+        {
+            let screen = Arc::clone(&screen);
+            let raw_tx = raw_tx.clone();
+            let recorder = recorder.clone();
+            std::thread::Builder::new()
+                .name("hive-pty-reader".into())
+                .spawn(move || read_pty(reader, screen, raw_tx, recorder, tx))
+                .context("Failed to start PTY reader thread")?;
+        }
 
         Ok(Self {
             child,
-            stdin,
+            master: pair.master,
+            writer: Arc::new(Mutex::new(writer)),
             output_rx: rx,
-            cols: Self::DEFAULT_COLS,
-            rows: Self::DEFAULT_ROWS,
+            raw_tx,
+            screen,
+            recorder,
+            cols,
+            rows,
             cwd: working_dir,
         })
     }
 
-    /// Send input text to the shell's stdin.
+    /// Send input text to the shell's terminal.
     ///
     /// The caller is responsible for including a trailing newline (`\n`) if a
     /// command submission is intended.
     pub async fn write(&mut self, input: &str) -> Result<()> {
-        self.stdin
-            .write_all(input.as_bytes())
-            .await
-            .context("Failed to write to shell stdin")?;
-        self.stdin
-            .flush()
-            .await
-            .context("Failed to flush shell stdin")?;
+        self.write_bytes(input.as_bytes()).await
+    }
+
+    /// Send raw bytes to the terminal: control characters (`\x03` for
+    /// Ctrl-C), escape sequences for arrow and function keys, and so on.
+    pub async fn write_bytes(&mut self, input: &[u8]) -> Result<()> {
+        if let Some(recorder) = &self.recorder {
+            lock(recorder).record_input(input);
+        }
+        let writer = Arc::clone(&self.writer);
+        let input = input.to_vec();
+        tokio::task::spawn_blocking(move || {
+            let mut writer = lock(&writer);
+            writer.write_all(&input)?;
+            writer.flush()
+        })
+        .await
+        .context("PTY writer task failed")?
+        .context("Failed to write to shell terminal")?;
         Ok(())
     }
 
-    /// Non-blocking read of the next available output chunk.
+    /// Non-blocking read of the next available output line.
     ///
     /// Returns `None` if no output is currently available (the channel is
     /// empty but not closed) or if the channel has been closed.
@@ -178,22 +269,84 @@ impl InteractiveShell {
         self.output_rx.try_recv().ok()
     }
 
-    /// Async read that waits for the next output chunk.
+    /// Async read that waits for the next output line.
     ///
-    /// Returns `None` only when the channel is closed (both background reader
-    /// tasks have finished).
+    /// Returns `None` only when the channel is closed (the PTY reached EOF).
     pub async fn read_async(&mut self) -> Option<ShellOutput> {
         self.output_rx.recv().await
     }
 
-    /// Store new terminal dimensions.
+    /// Subscribe to the raw bytes written by the shell, escape sequences
+    /// included. Each subscriber sees output produced after it subscribed;
+    /// one that falls behind gets [`broadcast::error::RecvError::Lagged`] and
+    /// can resynchronise from [`screen`](Self::screen).
+    pub fn subscribe_raw(&self) -> broadcast::Receiver<Vec<u8>> {
+        self.raw_tx.subscribe()
+    }
+
+    /// Snapshot of the emulated screen.
+    pub fn screen(&self) -> ScreenSnapshot {
+        self.lock_screen().snapshot()
+    }
+
+    /// The visible screen as plain text.
+    pub fn screen_text(&self) -> String {
+        self.lock_screen().text()
+    }
+
+    /// Lines that have scrolled off the top of the screen, oldest first.
+    pub fn scrollback(&self) -> Vec<String> {
+        self.lock_screen().scrollback()
+    }
+
+    fn lock_screen(&self) -> std::sync::MutexGuard<'_, TerminalScreen> {
+        lock(&self.screen)
+    }
+
+    /// Id of the recorded session, used to name its cast and to tag its
+    /// commands in the history. `None` when the session is not recorded.
+    pub fn session_id(&self) -> Option<String> {
+        let recorder = self.recorder.as_ref()?;
+        Some(lock(recorder).session_id().to_string())
+    }
+
+    /// Path of the session's asciicast file, if one is being written.
+    pub fn recording_path(&self) -> Option<PathBuf> {
+        let recorder = self.recorder.as_ref()?;
+        lock(recorder).cast_path().map(Path::to_path_buf)
+    }
+
+    /// Commands completed in this session, oldest first. Empty unless the
+    /// session is recorded under a shell with integration support.
+    pub fn commands(&self) -> Vec<CommandRecord> {
+        self.recorder
+            .as_ref()
+            .map(|recorder| lock(recorder).commands())
+            .unwrap_or_default()
+    }
+
+    /// The most recently completed command.
+    pub fn last_command(&self) -> Option<CommandRecord> {
+        self.commands().pop()
+    }
+
+    /// Resize the terminal.
     ///
-    /// Without a real PTY, this only records the values for later use (e.g.
-    /// when upgrading to `portable-pty`). No signal is sent to the child.
-    pub fn resize(&mut self, cols: u16, rows: u16) {
+    /// Updates the PTY window size, which delivers `SIGWINCH` to the
+    /// foreground process, and reflows the emulated screen to match.
+    pub fn resize(&mut self, cols: u16, rows: u16) -> Result<()> {
+        anyhow::ensure!(cols > 0 && rows > 0, "Terminal size must be non-zero");
+        self.master
+            .resize(pty_size(cols, rows))
+            .context("Failed to resize PTY")?;
+        self.lock_screen().resize(cols, rows);
+        if let Some(recorder) = &self.recorder {
+            lock(recorder).record_resize(cols, rows);
+        }
         self.cols = cols;
         self.rows = rows;
-        debug!(cols, rows, "terminal dimensions updated (no PTY signal)");
+        debug!(cols, rows, "terminal resized");
+        Ok(())
     }
 
     /// Current terminal column count.
@@ -233,18 +386,36 @@ impl InteractiveShell {
             .try_wait()
             .context("Failed to query process status")?
         {
-            Some(status) => Ok(Some(status.code().unwrap_or(-1))),
+            Some(status) => Ok(Some(status.exit_code() as i32)),
             None => Ok(None),
         }
     }
 
     /// Kill the shell process.
     ///
-    /// On Unix this sends SIGKILL. On Windows this calls `TerminateProcess`.
+    /// On Unix this sends SIGKILL to the shell's process group, taking any
+    /// foreground job with it. On Windows this calls `TerminateProcess`.
     /// The method is idempotent: killing an already-exited process is not an
     /// error.
     pub async fn kill(&mut self) -> Result<()> {
-        match self.child.kill().await {
+        if self.try_wait()?.is_some() {
+            // Process already exited.
+            return Ok(());
+        }
+
+        #[cfg(unix)]
+        let result = match self.child.process_id() {
+            // The shell leads its own session, so its pid is also its
+            // process group id.
+            Some(pid) if unsafe { libc::kill(-(pid as libc::pid_t), libc::SIGKILL) } != 0 => {
+                Err(std::io::Error::last_os_error())
+            }
+            _ => Ok(()),
+        };
+        #[cfg(not(unix))]
+        let result = self.child.kill();
+
+        match result {
             Ok(()) => {
                 debug!("shell process killed");
                 Ok(())
@@ -258,39 +429,96 @@ impl InteractiveShell {
     }
 }
 
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn pty_size(cols: u16, rows: u16) -> PtySize {
+    PtySize {
+        rows,
+        cols,
+        pixel_width: 0,
+        pixel_height: 0,
+    }
+}
+
 // ---------------------------------------------------------------------------
-// Background reader helper
+// Background reader
 // ---------------------------------------------------------------------------
 
-/// Read from an async reader line-by-line and send each chunk to the channel.
-///
-/// When `is_stderr` is true, output is wrapped in [`ShellOutput::Stderr`];
-/// otherwise in [`ShellOutput::Stdout`].
-async fn read_stream_to_channel<R>(reader: R, tx: mpsc::Sender<ShellOutput>, is_stderr: bool)
-where
-    R: tokio::io::AsyncRead + Unpin,
-{
-    use tokio::io::AsyncBufReadExt;
-
-    let mut lines = tokio::io::BufReader::new(reader).lines();
+/// Pump PTY output into the screen emulator, the raw broadcast, the session
+/// recorder and the line channel until the PTY closes or the shell is
+/// dropped.
+fn read_pty(
+    mut reader: Box<dyn Read + Send>,
+    screen: Arc<Mutex<TerminalScreen>>,
+    raw_tx: broadcast::Sender<Vec<u8>>,
+    recorder: Option<Arc<Mutex<SessionRecorder>>>,
+    tx: mpsc::Sender<ShellOutput>,
+) {
+    let mut lines = LineCollector::default();
+    let mut parser = vte::Parser::new();
+    let mut buf = [0u8; 8192];
+
     loop {
-        match lines.next_line().await {
-            Ok(Some(line)) => {
-                let msg = if is_stderr {
-                    ShellOutput::Stderr(line)
-                } else {
-                    ShellOutput::Stdout(line)
-                };
-                if tx.send(msg).await.is_err() {
-                    // Receiver dropped; stop reading.
-                    break;
-                }
-            }
-            Ok(None) => break, // EOF
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            // Linux reports EIO on the master once the last slave fd closes.
+            Err(e) if e.raw_os_error() == Some(5) => break,
             Err(e) => {
-                warn!(error = %e, is_stderr, "error reading shell output");
+                warn!(error = %e, "error reading shell output");
+                let _ = tx.blocking_send(ShellOutput::Stderr(format!(
+                    "Failed to read terminal output: {e}"
+                )));
                 break;
             }
+        };
+        let chunk = &buf[..n];
+
+        lock(&screen).process(chunk);
+        if let Some(recorder) = &recorder {
+            lock(recorder).record_output(chunk);
+        }
+        // No subscribers is fine; the screen still records the output.
+        let _ = raw_tx.send(chunk.to_vec());
+
+        parser.advance(&mut lines, chunk);
+        for line in lines.completed.drain(..) {
+            if tx.blocking_send(ShellOutput::Stdout(line)).is_err() {
+                // Receiver dropped; the shell is gone.
+                return;
+            }
+        }
+    }
+
+    if !lines.current.is_empty() {
+        let _ = tx.blocking_send(ShellOutput::Stdout(std::mem::take(&mut lines.current)));
+    }
+}
+
+/// Splits terminal output into plain-text lines, dropping escape sequences
+/// and carriage returns.
+#[derive(Default)]
+struct LineCollector {
+    current: String,
+    completed: Vec<String>,
+}
+
+impl vte::Perform for LineCollector {
+    fn print(&mut self, ch: char) {
+        self.current.push(ch);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\n' => self.completed.push(std::mem::take(&mut self.current)),
+            b'\t' => self.current.push('\t'),
+            0x08 => {
+                self.current.pop();
+            }
+            _ => {}
         }
     }
 }
@@ -399,9 +627,18 @@ mod tests {
     #[tokio::test]
     async fn resize_updates_dimensions() {
         let mut shell = InteractiveShell::new(None).unwrap();
-        shell.resize(120, 40);
+        shell.resize(120, 40).unwrap();
         assert_eq!(shell.cols(), 120);
         assert_eq!(shell.rows(), 40);
+        assert_eq!((shell.screen().cols, shell.screen().rows), (120, 40));
+        let _ = shell.kill().await;
+    }
+
+    #[tokio::test]
+    async fn resize_rejects_zero_dimensions() {
+        let mut shell = InteractiveShell::new(None).unwrap();
+        assert!(shell.resize(0, 40).is_err());
+        assert_eq!(shell.cols(), InteractiveShell::DEFAULT_COLS);
         let _ = shell.kill().await;
     }
 
@@ -464,6 +701,137 @@ mod tests {
         let _ = shell.kill().await;
     }
 
+    // -- PTY behaviour -------------------------------------------------------
+
+    /// Wait until the rendered screen contains `needle`.
+    async fn wait_for_screen(shell: &InteractiveShell, needle: &str) -> String {
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+        loop {
+            let text = shell.screen_text();
+            if text.contains(needle) || tokio::time::Instant::now() >= deadline {
+                return text;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn shell_runs_attached_to_a_terminal() {
+        let mut shell = InteractiveShell::new(None).unwrap();
+        shell
+            .write("test -t 0 && test -t 1 && echo \"tty=$TERM\"\n")
+            .await
+            .unwrap();
+        let text = wait_for_screen(&shell, "tty=xterm-256color").await;
+        assert!(text.contains("tty=xterm-256color"), "screen was: {text}");
+        let _ = shell.kill().await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn recorded_shell_tracks_commands() {
+        if !Path::new("/bin/bash").exists() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let history = Arc::new(crate::history::CommandHistory::new(
+            dir.path().join("history.jsonl"),
+        ));
+        let config = RecordingConfig {
+            cast_dir: Some(dir.path().join("casts")),
+            history: Some(Arc::clone(&history)),
+            record_input: false,
+        };
+        let mut shell = InteractiveShell::with_recording(Some(dir.path()), config).unwrap();
+        shell
+            .write("echo recorded_ok; (exit 3)\nls no_such_file_here\n")
+            .await
+            .unwrap();
+
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+        while shell.commands().len() < 2 && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        let commands = shell.commands();
+        assert_eq!(commands.len(), 2, "screen was: {}", shell.screen_text());
+
+        assert_eq!(commands[0].command, "echo recorded_ok; (exit 3)");
+        assert_eq!(commands[0].exit_code, Some(3));
+        assert_eq!(commands[0].output, "recorded_ok");
+        let cwd = dir.path().canonicalize().unwrap();
+        assert_eq!(commands[0].cwd.as_deref(), cwd.to_str());
+
+        let last_failed = history.last_failed().unwrap().unwrap();
+        assert_eq!(last_failed.command, "ls no_such_file_here");
+        assert!(last_failed.output.contains("no_such_file_here"));
+        assert_eq!(last_failed.session_id, shell.session_id().unwrap());
+
+        let cast = crate::recording::Recording::load(&shell.recording_path().unwrap()).unwrap();
+        assert!(
+            cast.screen_at(cast.duration())
+                .text()
+                .contains("recorded_ok")
+        );
+        let _ = shell.kill().await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn resize_is_visible_to_the_child() {
+        let mut shell = InteractiveShell::new(None).unwrap();
+        shell.resize(132, 50).unwrap();
+        shell.write("echo \"size=$(stty size)\"\n").await.unwrap();
+        let text = wait_for_screen(&shell, "size=50 132").await;
+        assert!(text.contains("size=50 132"), "screen was: {text}");
+        let _ = shell.kill().await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn raw_output_keeps_escape_sequences() {
+        let mut shell = InteractiveShell::new(None).unwrap();
+        let mut raw = shell.subscribe_raw();
+        shell
+            .write("printf '\\033[31mred\\033[0m\\n'\n")
+            .await
+            .unwrap();
+
+        let mut received = Vec::new();
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+        while !received.windows(8).any(|w| w == b"\x1b[31mred")
+            && tokio::time::Instant::now() < deadline
+        {
+            if let Ok(Ok(chunk)) =
+                tokio::time::timeout(std::time::Duration::from_millis(200), raw.recv()).await
+            {
+                received.extend(chunk);
+            }
+        }
+        assert!(
+            received.windows(8).any(|w| w == b"\x1b[31mred"),
+            "raw output was: {:?}",
+            String::from_utf8_lossy(&received)
+        );
+
+        // The line stream and the screen carry the text without the escapes.
+        let text = wait_for_screen(&shell, "red").await;
+        assert!(text.lines().any(|line| line == "red"), "screen was: {text}");
+        let _ = shell.kill().await;
+    }
+
+    #[test]
+    fn line_collector_strips_escape_sequences() {
+        let mut lines = LineCollector::default();
+        let mut parser = vte::Parser::new();
+        parser.advance(
+            &mut lines,
+            b"\x1b[1;32mok\x1b[0m done\r\nab\x08c\r\npartial",
+        );
+        assert_eq!(lines.completed, vec!["ok done", "ac"]);
+        assert_eq!(lines.current, "partial");
+    }
+
     // -- Kill ----------------------------------------------------------------
 
     #[tokio::test]
@@ -0,0 +1,867 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+// ---------------------------------------------------------------------------
+// Cells
+// ---------------------------------------------------------------------------
+
+/// Foreground or background colour of a cell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Color {
+    /// The terminal's default colour.
+    #[default]
+    Default,
+    /// One of the 256 palette colours (0-15 are the ANSI colours).
+    Indexed(u8),
+    /// 24-bit colour.
+    Rgb(u8, u8, u8),
+}
+
+/// Rendition attributes set with SGR (`CSI ... m`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellStyle {
+    pub fg: Color,
+    pub bg: Color,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub inverse: bool,
+}
+
+/// A single character position on the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cell {
+    pub ch: char,
+    pub style: CellStyle,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            ch: ' ',
+            style: CellStyle::default(),
+        }
+    }
+}
+
+type Row = Vec<Cell>;
+
+/// An erased cell. Erasing keeps the current background colour (xterm's
+/// "background colour erase").
+fn blank_cell(style: CellStyle) -> Cell {
+    Cell {
+        ch: ' ',
+        style: CellStyle {
+            bg: style.bg,
+            ..CellStyle::default()
+        },
+    }
+}
+
+fn blank_row(cols: usize, style: CellStyle) -> Row {
+    vec![blank_cell(style); cols]
+}
+
+fn row_text(row: &[Cell]) -> String {
+    let text: String = row.iter().map(|cell| cell.ch).collect();
+    text.trim_end().to_string()
+}
+
+// ---------------------------------------------------------------------------
+// ScreenSnapshot
+// ---------------------------------------------------------------------------
+
+/// Plain-text rendering of the visible screen, for agents and tests that
+/// want to "look at" the terminal rather than parse its byte stream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenSnapshot {
+    pub cols: u16,
+    pub rows: u16,
+    pub cursor_row: u16,
+    pub cursor_col: u16,
+    pub cursor_visible: bool,
+    /// Whether a full-screen program (vim, htop, less) switched to the
+    /// alternate screen buffer.
+    pub alternate_screen: bool,
+    /// Window title set with OSC 0 / OSC 2.
+    pub title: String,
+    /// One entry per visible row, trailing blanks trimmed.
+    pub lines: Vec<String>,
+}
+
+impl ScreenSnapshot {
+    /// The visible rows joined with newlines, without trailing blank rows.
+    pub fn text(&self) -> String {
+        let end = self
+            .lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map_or(0, |index| index + 1);
+        self.lines[..end].join("\n")
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TerminalScreen
+// ---------------------------------------------------------------------------
+
+/// VT100/xterm emulator maintaining a screen grid and scrollback.
+///
+/// Feed it the raw bytes read from a PTY with [`process`](Self::process)
+/// and query the resulting screen. Covers the subset of xterm used by
+/// shells and common full-screen programs: cursor movement, erase, insert
+/// and delete, scroll regions, SGR colours (16, 256 and truecolor), the
+/// alternate screen and window titles. Every character occupies one cell;
+/// double-width glyphs are not special-cased.
+pub struct TerminalScreen {
+    parser: vte::Parser,
+    state: ScreenState,
+}
+
+impl std::fmt::Debug for TerminalScreen {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TerminalScreen")
+            .field("cols", &self.state.cols)
+            .field("rows", &self.state.rows)
+            .field("scrollback", &self.state.scrollback.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Cursor {
+    row: usize,
+    col: usize,
+    style: CellStyle,
+    /// Set after printing in the last column; the next printable character
+    /// wraps to a new line first (xterm's deferred wrap).
+    pending_wrap: bool,
+}
+
+struct ScreenState {
+    cols: usize,
+    rows: usize,
+    grid: Vec<Row>,
+    /// Primary grid and cursor, stashed while the alternate screen is shown.
+    primary: Option<(Vec<Row>, Cursor)>,
+    scrollback: VecDeque<Row>,
+    max_scrollback: usize,
+    cursor: Cursor,
+    saved_cursor: Option<Cursor>,
+    /// Scroll region, inclusive row bounds.
+    scroll_top: usize,
+    scroll_bottom: usize,
+    autowrap: bool,
+    cursor_visible: bool,
+    application_cursor_keys: bool,
+    bracketed_paste: bool,
+    title: String,
+}
+
+impl TerminalScreen {
+    /// Default number of scrollback lines retained.
+    pub const DEFAULT_SCROLLBACK: usize = 10_000;
+
+    pub fn new(cols: u16, rows: u16) -> Self {
+        Self::with_scrollback(cols, rows, Self::DEFAULT_SCROLLBACK)
+    }
+
+    pub fn with_scrollback(cols: u16, rows: u16, max_scrollback: usize) -> Self {
+        let cols = usize::from(cols.max(1));
+        let rows = usize::from(rows.max(1));
+        Self {
+            parser: vte::Parser::new(),
+            state: ScreenState {
+                cols,
+                rows,
+                grid: vec![blank_row(cols, CellStyle::default()); rows],
+                primary: None,
+                scrollback: VecDeque::new(),
+                max_scrollback,
+                cursor: Cursor::default(),
+                saved_cursor: None,
+                scroll_top: 0,
+                scroll_bottom: rows - 1,
+                autowrap: true,
+                cursor_visible: true,
+                application_cursor_keys: false,
+                bracketed_paste: false,
+                title: String::new(),
+            },
+        }
+    }
+
+    /// Interpret a chunk of terminal output. Escape sequences may be split
+    /// across calls.
+    pub fn process(&mut self, bytes: &[u8]) {
+        self.parser.advance(&mut self.state, bytes);
+    }
+
+    /// Change the screen size, keeping as much content as fits. Rows pushed
+    /// off the top of the primary screen go to scrollback.
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.state
+            .resize(usize::from(cols.max(1)), usize::from(rows.max(1)));
+    }
+
+    pub fn cols(&self) -> u16 {
+        self.state.cols as u16
+    }
+
+    pub fn rows(&self) -> u16 {
+        self.state.rows as u16
+    }
+
+    /// Cursor position as `(row, col)`, zero-based.
+    pub fn cursor(&self) -> (u16, u16) {
+        (self.state.cursor.row as u16, self.state.cursor.col as u16)
+    }
+
+    /// The cell at `(row, col)` of the visible screen.
+    pub fn cell(&self, row: u16, col: u16) -> Option<&Cell> {
+        self.state
+            .grid
+            .get(usize::from(row))
+            .and_then(|line| line.get(usize::from(col)))
+    }
+
+    pub fn title(&self) -> &str {
+        &self.state.title
+    }
+
+    pub fn is_alternate_screen(&self) -> bool {
+        self.state.primary.is_some()
+    }
+
+    /// Whether the program asked for application cursor keys (DECCKM), in
+    /// which case arrow keys should be sent as `ESC O A` rather than
+    /// `ESC [ A`.
+    pub fn application_cursor_keys(&self) -> bool {
+        self.state.application_cursor_keys
+    }
+
+    /// Whether pasted text should be wrapped in `ESC [200~` / `ESC [201~`.
+    pub fn bracketed_paste(&self) -> bool {
+        self.state.bracketed_paste
+    }
+
+    /// Lines that scrolled off the top of the primary screen, oldest first.
+    pub fn scrollback(&self) -> Vec<String> {
+        self.state
+            .scrollback
+            .iter()
+            .map(|row| row_text(row))
+            .collect()
+    }
+
+    pub fn snapshot(&self) -> ScreenSnapshot {
+        let state = &self.state;
+        ScreenSnapshot {
+            cols: state.cols as u16,
+            rows: state.rows as u16,
+            cursor_row: state.cursor.row as u16,
+            cursor_col: state.cursor.col as u16,
+            cursor_visible: state.cursor_visible,
+            alternate_screen: state.primary.is_some(),
+            title: state.title.clone(),
+            lines: state.grid.iter().map(|row| row_text(row)).collect(),
+        }
+    }
+
+    /// Shorthand for `snapshot().text()`.
+    pub fn text(&self) -> String {
+        self.snapshot().text()
+    }
+}
+
+impl ScreenState {
+    fn blank(&self) -> Row {
+        blank_row(self.cols, self.cursor.style)
+    }
+
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.cursor.row = row.min(self.rows - 1);
+        self.cursor.col = col.min(self.cols - 1);
+        self.cursor.pending_wrap = false;
+    }
+
+    fn print_char(&mut self, ch: char) {
+        if self.cursor.pending_wrap {
+            self.cursor.col = 0;
+            self.linefeed();
+        }
+        let Cursor {
+            row, col, style, ..
+        } = self.cursor;
+        self.grid[row][col] = Cell { ch, style };
+        if col + 1 < self.cols {
+            self.cursor.col += 1;
+            self.cursor.pending_wrap = false;
+        } else {
+            self.cursor.pending_wrap = self.autowrap;
+        }
+    }
+
+    fn linefeed(&mut self) {
+        self.cursor.pending_wrap = false;
+        if self.cursor.row == self.scroll_bottom {
+            self.scroll_up(1);
+        } else if self.cursor.row + 1 < self.rows {
+            self.cursor.row += 1;
+        }
+    }
+
+    fn reverse_index(&mut self) {
+        self.cursor.pending_wrap = false;
+        if self.cursor.row == self.scroll_top {
+            self.scroll_down(1);
+        } else {
+            self.cursor.row = self.cursor.row.saturating_sub(1);
+        }
+    }
+
+    /// Scroll the region up, keeping lines that leave the top of a
+    /// full-height primary screen as scrollback.
+    fn scroll_up(&mut self, count: usize) {
+        let count = count.min(self.scroll_bottom - self.scroll_top + 1);
+        let full_screen = self.scroll_top == 0 && self.scroll_bottom == self.rows - 1;
+        for _ in 0..count {
+            let row = self.grid.remove(self.scroll_top);
+            if full_screen {
+                self.push_scrollback(row);
+            }
+            self.grid.insert(self.scroll_bottom, self.blank());
+        }
+    }
+
+    fn push_scrollback(&mut self, row: Row) {
+        if self.primary.is_some() || self.max_scrollback == 0 {
+            return;
+        }
+        if self.scrollback.len() == self.max_scrollback {
+            self.scrollback.pop_front();
+        }
+        self.scrollback.push_back(row);
+    }
+
+    fn scroll_down(&mut self, count: usize) {
+        let count = count.min(self.scroll_bottom - self.scroll_top + 1);
+        for _ in 0..count {
+            self.grid.remove(self.scroll_bottom);
+            self.grid.insert(self.scroll_top, self.blank());
+        }
+    }
+
+    fn erase_cells(&mut self, row: usize, from: usize, to: usize) {
+        let blank = blank_cell(self.cursor.style);
+        let to = to.min(self.cols);
+        for cell in &mut self.grid[row][from.min(to)..to] {
+            *cell = blank;
+        }
+    }
+
+    fn erase_display(&mut self, mode: u16) {
+        let Cursor { row, col, .. } = self.cursor;
+        match mode {
+            0 => {
+                self.erase_cells(row, col, self.cols);
+                for line in row + 1..self.rows {
+                    self.grid[line] = self.blank();
+                }
+            }
+            1 => {
+                for line in 0..row {
+                    self.grid[line] = self.blank();
+                }
+                self.erase_cells(row, 0, col + 1);
+            }
+            2 | 3 => {
+                self.grid = vec![self.blank(); self.rows];
+                if mode == 3 {
+                    self.scrollback.clear();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn erase_line(&mut self, mode: u16) {
+        let Cursor { row, col, .. } = self.cursor;
+        match mode {
+            0 => self.erase_cells(row, col, self.cols),
+            1 => self.erase_cells(row, 0, col + 1),
+            2 => self.grid[row] = self.blank(),
+            _ => {}
+        }
+    }
+
+    fn insert_lines(&mut self, count: usize) {
+        let row = self.cursor.row;
+        if row < self.scroll_top || row > self.scroll_bottom {
+            return;
+        }
+        for _ in 0..count.min(self.scroll_bottom - row + 1) {
+            self.grid.remove(self.scroll_bottom);
+            self.grid.insert(row, self.blank());
+        }
+        self.cursor.col = 0;
+    }
+
+    fn delete_lines(&mut self, count: usize) {
+        let row = self.cursor.row;
+        if row < self.scroll_top || row > self.scroll_bottom {
+            return;
+        }
+        for _ in 0..count.min(self.scroll_bottom - row + 1) {
+            self.grid.remove(row);
+            self.grid.insert(self.scroll_bottom, self.blank());
+        }
+        self.cursor.col = 0;
+    }
+
+    fn insert_chars(&mut self, count: usize) {
+        let Cursor { row, col, .. } = self.cursor;
+        let blank = blank_cell(self.cursor.style);
+        let line = &mut self.grid[row];
+        for _ in 0..count.min(self.cols - col) {
+            line.pop();
+            line.insert(col, blank);
+        }
+    }
+
+    fn delete_chars(&mut self, count: usize) {
+        let Cursor { row, col, .. } = self.cursor;
+        let blank = blank_cell(self.cursor.style);
+        let line = &mut self.grid[row];
+        for _ in 0..count.min(self.cols - col) {
+            line.remove(col);
+            line.push(blank);
+        }
+    }
+
+    fn set_alternate_screen(&mut self, enabled: bool) {
+        if enabled && self.primary.is_none() {
+            let blank = vec![self.blank(); self.rows];
+            let primary = std::mem::replace(&mut self.grid, blank);
+            self.primary = Some((primary, self.cursor));
+        } else if !enabled && let Some((grid, cursor)) = self.primary.take() {
+            self.grid = grid;
+            self.cursor = cursor;
+        }
+        self.scroll_top = 0;
+        self.scroll_bottom = self.rows - 1;
+    }
+
+    fn set_private_mode(&mut self, mode: u16, enabled: bool) {
+        match mode {
+            1 => self.application_cursor_keys = enabled,
+            7 => self.autowrap = enabled,
+            25 => self.cursor_visible = enabled,
+            47 | 1047 => self.set_alternate_screen(enabled),
+            1049 => {
+                if enabled {
+                    self.saved_cursor = Some(self.cursor);
+                    self.set_alternate_screen(true);
+                } else {
+                    self.set_alternate_screen(false);
+                    if let Some(cursor) = self.saved_cursor {
+                        self.cursor = cursor;
+                    }
+                }
+            }
+            2004 => self.bracketed_paste = enabled,
+            _ => {}
+        }
+    }
+
+    fn select_graphic_rendition(&mut self, params: &vte::Params) {
+        let style = &mut self.cursor.style;
+        if params.is_empty() {
+            *style = CellStyle::default();
+            return;
+        }
+
+        let mut groups = params.iter();
+        while let Some(group) = groups.next() {
+            match group[0] {
+                0 => *style = CellStyle::default(),
+                1 => style.bold = true,
+                2 => style.dim = true,
+                3 => style.italic = true,
+                4 => style.underline = true,
+                7 => style.inverse = true,
+                22 => {
+                    style.bold = false;
+                    style.dim = false;
+                }
+                23 => style.italic = false,
+                24 => style.underline = false,
+                27 => style.inverse = false,
+                code @ 30..=37 => style.fg = Color::Indexed((code - 30) as u8),
+                39 => style.fg = Color::Default,
+                code @ 40..=47 => style.bg = Color::Indexed((code - 40) as u8),
+                49 => style.bg = Color::Default,
+                code @ 90..=97 => style.fg = Color::Indexed((code - 90 + 8) as u8),
+                code @ 100..=107 => style.bg = Color::Indexed((code - 100 + 8) as u8),
+                code @ (38 | 48) => {
+                    // Either `38:5:n` / `38:2:r:g:b` in one group, or the
+                    // same values as separate `;` parameters.
+                    let color = if group.len() > 1 {
+                        extended_color(&mut group[1..].iter().copied())
+                    } else {
+                        extended_color(&mut groups.by_ref().map(|next| next[0]))
+                    };
+                    if let Some(color) = color {
+                        if code == 38 {
+                            style.fg = color;
+                        } else {
+                            style.bg = color;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn resize(&mut self, cols: usize, rows: usize) {
+        let fit_columns = |grid: &mut Vec<Row>, style: CellStyle| {
+            for line in grid.iter_mut() {
+                line.resize(cols, blank_cell(style));
+            }
+        };
+        fit_columns(&mut self.grid, self.cursor.style);
+        if let Some((grid, _)) = &mut self.primary {
+            fit_columns(grid, CellStyle::default());
+        }
+
+        if rows < self.rows {
+            // Keep the cursor on screen by dropping rows from the top (into
+            // scrollback) before dropping blank rows from the bottom.
+            let overflow = (self.cursor.row + 1).saturating_sub(rows);
+            let dropped: Vec<Row> = self.grid.drain(..overflow).collect();
+            for row in dropped {
+                self.push_scrollback(row);
+            }
+            self.grid.truncate(rows);
+            self.cursor.row -= overflow;
+        } else {
+            self.grid
+                .resize(rows, blank_row(cols, CellStyle::default()));
+        }
+        if let Some((grid, cursor)) = &mut self.primary {
+            grid.resize(rows, blank_row(cols, CellStyle::default()));
+            cursor.row = cursor.row.min(rows - 1);
+            cursor.col = cursor.col.min(cols - 1);
+        }
+
+        self.cols = cols;
+        self.rows = rows;
+        self.scroll_top = 0;
+        self.scroll_bottom = rows - 1;
+        let (row, col) = (self.cursor.row, self.cursor.col);
+        self.move_to(row, col);
+    }
+}
+
+/// Parse the colour that follows a 38/48 SGR code.
+fn extended_color(values: &mut dyn Iterator<Item = u16>) -> Option<Color> {
+    match values.next()? {
+        5 => Some(Color::Indexed(values.next()? as u8)),
+        2 => {
+            let r = values.next()? as u8;
+            let g = values.next()? as u8;
+            let b = values.next()? as u8;
+            Some(Color::Rgb(r, g, b))
+        }
+        _ => None,
+    }
+}
+
+impl vte::Perform for ScreenState {
+    fn print(&mut self, ch: char) {
+        self.print_char(ch);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            // BS
+            0x08 => {
+                let col = self.cursor.col.saturating_sub(1);
+                self.move_to(self.cursor.row, col);
+            }
+            // HT
+            0x09 => {
+                let col = (self.cursor.col / 8 + 1) * 8;
+                self.move_to(self.cursor.row, col);
+            }
+            // LF, VT, FF
+            0x0a..=0x0c => self.linefeed(),
+            // CR
+            0x0d => self.move_to(self.cursor.row, 0),
+            _ => {}
+        }
+    }
+
+    fn csi_dispatch(
+        &mut self,
+        params: &vte::Params,
+        intermediates: &[u8],
+        _ignore: bool,
+        action: char,
+    ) {
+        let values: Vec<u16> = params.iter().map(|group| group[0]).collect();
+        // Numeric argument `index`, treating 0 and missing as `default`.
+        let arg = |index: usize, default: u16| {
+            usize::from(match values.get(index) {
+                Some(0) | None => default,
+                Some(value) => *value,
+            })
+        };
+        let Cursor { row, col, .. } = self.cursor;
+
+        if intermediates == b"?" {
+            if matches!(action, 'h' | 'l') {
+                for mode in &values {
+                    self.set_private_mode(*mode, action == 'h');
+                }
+            }
+            return;
+        }
+        if !intermediates.is_empty() {
+            return;
+        }
+
+        match action {
+            'A' => self.move_to(row.saturating_sub(arg(0, 1)), col),
+            'B' | 'e' => self.move_to(row + arg(0, 1), col),
+            'C' | 'a' => self.move_to(row, col + arg(0, 1)),
+            'D' => self.move_to(row, col.saturating_sub(arg(0, 1))),
+            'E' => self.move_to(row + arg(0, 1), 0),
+            'F' => self.move_to(row.saturating_sub(arg(0, 1)), 0),
+            'G' | '`' => self.move_to(row, arg(0, 1) - 1),
+            'H' | 'f' => self.move_to(arg(0, 1) - 1, arg(1, 1) - 1),
+            'd' => self.move_to(arg(0, 1) - 1, col),
+            'J' => self.erase_display(values.first().copied().unwrap_or(0)),
+            'K' => self.erase_line(values.first().copied().unwrap_or(0)),
+            'L' => self.insert_lines(arg(0, 1)),
+            'M' => self.delete_lines(arg(0, 1)),
+            '@' => self.insert_chars(arg(0, 1)),
+            'P' => self.delete_chars(arg(0, 1)),
+            'X' => self.erase_cells(row, col, col + arg(0, 1)),
+            'S' => self.scroll_up(arg(0, 1)),
+            'T' => self.scroll_down(arg(0, 1)),
+            'm' => self.select_graphic_rendition(params),
+            'r' => {
+                let top = arg(0, 1) - 1;
+                let bottom = arg(1, self.rows as u16).min(self.rows) - 1;
+                if top < bottom {
+                    self.scroll_top = top;
+                    self.scroll_bottom = bottom;
+                    self.move_to(0, 0);
+                }
+            }
+            's' => self.saved_cursor = Some(self.cursor),
+            'u' => {
+                if let Some(cursor) = self.saved_cursor {
+                    self.cursor = cursor;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], _ignore: bool, byte: u8) {
+        if !intermediates.is_empty() {
+            return;
+        }
+        match byte {
+            b'7' => self.saved_cursor = Some(self.cursor),
+            b'8' => {
+                if let Some(cursor) = self.saved_cursor {
+                    self.cursor = cursor;
+                }
+            }
+            b'D' => self.linefeed(),
+            b'E' => {
+                self.move_to(self.cursor.row, 0);
+                self.linefeed();
+            }
+            b'M' => self.reverse_index(),
+            b'c' => {
+                let (cols, rows, max_scrollback) = (self.cols, self.rows, self.max_scrollback);
+                *self =
+                    TerminalScreen::with_scrollback(cols as u16, rows as u16, max_scrollback).state;
+            }
+            _ => {}
+        }
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        if let [kind, rest @ ..] = params
+            && matches!(*kind, b"0" | b"2")
+        {
+            self.title = String::from_utf8_lossy(&rest.join(&b';')).into_owned();
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(cols: u16, rows: u16, input: &str) -> TerminalScreen {
+        let mut screen = TerminalScreen::new(cols, rows);
+        screen.process(input.as_bytes());
+        screen
+    }
+
+    #[test]
+    fn prints_text_and_handles_newlines() {
+        let screen = screen(20, 4, "hello\r\nworld");
+        assert_eq!(screen.text(), "hello\nworld");
+        assert_eq!(screen.cursor(), (1, 5));
+    }
+
+    #[test]
+    fn wraps_at_the_right_margin() {
+        let screen = screen(5, 3, "abcdefg");
+        assert_eq!(screen.snapshot().lines, vec!["abcde", "fg", ""]);
+    }
+
+    #[test]
+    fn deferred_wrap_keeps_cursor_in_last_column() {
+        let screen = screen(5, 3, "abcde");
+        assert_eq!(screen.cursor(), (0, 4));
+        assert_eq!(screen.text(), "abcde");
+    }
+
+    #[test]
+    fn scrolling_moves_lines_into_scrollback() {
+        let screen = screen(10, 2, "one\r\ntwo\r\nthree\r\nfour");
+        assert_eq!(screen.text(), "three\nfour");
+        assert_eq!(screen.scrollback(), vec!["one", "two"]);
+    }
+
+    #[test]
+    fn scrollback_is_bounded() {
+        let mut screen = TerminalScreen::with_scrollback(10, 1, 2);
+        screen.process(b"a\r\nb\r\nc\r\nd");
+        assert_eq!(screen.scrollback(), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn cursor_positioning_and_erase() {
+        let screen = screen(
+            10,
+            3,
+            "xxxxxxxxxx\r\nyyyyyyyyyy\x1b[1;4H\x1b[K\x1b[2;3H\x1b[1K",
+        );
+        assert_eq!(screen.snapshot().lines, vec!["xxx", "   yyyyyyy", ""]);
+        assert_eq!(screen.cursor(), (1, 2));
+    }
+
+    #[test]
+    fn clear_screen_blanks_every_row() {
+        let screen = screen(10, 3, "a\r\nb\r\nc\x1b[2J\x1b[H");
+        assert_eq!(screen.text(), "");
+        assert_eq!(screen.cursor(), (0, 0));
+    }
+
+    #[test]
+    fn insert_and_delete_characters() {
+        let deleted = screen(10, 1, "abcdef\x1b[1;2H\x1b[2P");
+        assert_eq!(deleted.text(), "adef");
+        let inserted = screen(10, 1, "abcdef\x1b[1;2H\x1b[2@");
+        assert_eq!(inserted.text(), "a  bcdef");
+    }
+
+    #[test]
+    fn scroll_region_limits_scrolling() {
+        // Status line on row 3 stays put while rows 1-2 scroll.
+        let screen = screen(10, 3, "\x1b[3;1Hstatus\x1b[1;2r\x1b[1;1Ha\r\nb\r\nc");
+        assert_eq!(screen.snapshot().lines, vec!["b", "c", "status"]);
+        assert!(screen.scrollback().is_empty());
+    }
+
+    #[test]
+    fn insert_and_delete_lines() {
+        let inserted = screen(10, 3, "a\r\nb\r\nc\x1b[2;1H\x1b[L");
+        assert_eq!(inserted.snapshot().lines, vec!["a", "", "b"]);
+        let deleted = screen(10, 3, "a\r\nb\r\nc\x1b[1;1H\x1b[M");
+        assert_eq!(deleted.snapshot().lines, vec!["b", "c", ""]);
+    }
+
+    #[test]
+    fn sgr_sets_colors_and_attributes() {
+        let screen = screen(
+            20,
+            1,
+            "\x1b[1;31mA\x1b[0;38;5;208mB\x1b[48;2;1;2;3mC\x1b[38:2:9:8:7mD\x1b[mE",
+        );
+        let style = |col| screen.cell(0, col).unwrap().style;
+        assert!(style(0).bold);
+        assert_eq!(style(0).fg, Color::Indexed(1));
+        assert!(!style(1).bold);
+        assert_eq!(style(1).fg, Color::Indexed(208));
+        assert_eq!(style(2).bg, Color::Rgb(1, 2, 3));
+        assert_eq!(style(3).fg, Color::Rgb(9, 8, 7));
+        assert_eq!(style(4), CellStyle::default());
+    }
+
+    #[test]
+    fn alternate_screen_preserves_primary_contents() {
+        let mut screen = screen(10, 3, "$ vim\r\n");
+        screen.process(b"\x1b[?1049h\x1b[H\x1b[2Jeditor");
+        assert!(screen.is_alternate_screen());
+        assert_eq!(screen.text(), "editor");
+
+        screen.process(b"\x1b[?1049l");
+        assert!(!screen.is_alternate_screen());
+        assert_eq!(screen.text(), "$ vim");
+        assert_eq!(screen.cursor(), (1, 0));
+    }
+
+    #[test]
+    fn tracks_title_and_modes() {
+        let screen = screen(10, 2, "\x1b]0;hive shell\x07\x1b[?25l\x1b[?1h\x1b[?2004h");
+        let snapshot = screen.snapshot();
+        assert_eq!(snapshot.title, "hive shell");
+        assert!(!snapshot.cursor_visible);
+        assert!(screen.application_cursor_keys());
+        assert!(screen.bracketed_paste());
+    }
+
+    #[test]
+    fn carriage_return_overwrites_progress_bars() {
+        let screen = screen(20, 1, "[#   ] 25%\r[### ] 75%\r[####] 100%");
+        assert_eq!(screen.text(), "[####] 100%");
+    }
+
+    #[test]
+    fn escape_sequences_split_across_chunks() {
+        let mut screen = TerminalScreen::new(10, 2);
+        screen.process(b"ab\x1b[");
+        screen.process(b"2;1Hc");
+        assert_eq!(screen.text(), "ab\nc");
+    }
+
+    #[test]
+    fn resize_keeps_cursor_row_visible() {
+        let mut screen = screen(10, 4, "1\r\n2\r\n3\r\n4");
+        screen.resize(5, 2);
+        assert_eq!(screen.text(), "3\n4");
+        assert_eq!(screen.scrollback(), vec!["1", "2"]);
+        assert_eq!(screen.cursor(), (1, 1));
+
+        screen.resize(8, 3);
+        assert_eq!(screen.snapshot().lines, vec!["3", "4", ""]);
+        assert_eq!((screen.cols(), screen.rows()), (8, 3));
+    }
+}
@@ -6,6 +6,7 @@
 //! reactively via `cx.notify()`.
 
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use chrono::{DateTime, Utc};
 use gpui::{AsyncApp, Context, EventEmitter, Task, WeakEntity};
@@ -13,6 +14,8 @@ use tokio::sync::{mpsc, oneshot};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use hive_agents::content_guard::{ContentGuard, Provenance, TrustLevel};
+use hive_agents::tool_use::{ReplaceInFilesTool, ToolExecutor};
 use hive_ai::providers::AiProvider;
 use hive_ai::types::{
     ChatMessage as AiChatMessage, ChatRequest, MessageRole as AiMessageRole, StopReason,
@@ -22,6 +25,7 @@ use hive_core::context::{ContextMessage, ContextWindow};
 use hive_core::conversations::{
     Conversation, ConversationStore, ConversationSummary, StoredMessage, generate_title,
 };
+use hive_fs::{Checkpoint, CheckpointStore};
 use hive_ui_panels::components::diff_viewer::DiffLine;
 
 fn stream_error_chunk(message: impl Into<String>) -> StreamChunk {
@@ -294,7 +298,8 @@ impl ChatMessage {
 // Tool Approval
 // ---------------------------------------------------------------------------
 
-/// Describes a pending write_file tool call awaiting user approval.
+/// Describes a pending tool call (write_file, or a high-risk tool after
+/// untrusted content entered the conversation) awaiting user approval.
 #[derive(Clone, Debug)]
 pub struct PendingToolApproval {
     pub tool_call_id: String,
@@ -304,6 +309,9 @@ pub struct PendingToolApproval {
     /// `None` means the file does not exist yet (new file creation).
     pub old_content: Option<String>,
     pub diff_lines: Vec<DiffLine>,
+    /// Why approval is required beyond the tool itself, e.g. because
+    /// untrusted content entered the conversation before this call.
+    pub reason: Option<String>,
 }
 
 /// Compute a simple line-by-line diff between old and new content.
@@ -402,13 +410,51 @@ pub struct ChatService {
     /// message list. Used by the UI to detect when cached display messages
     /// need to be rebuilt, avoiding per-frame string cloning.
     generation: u64,
-    /// Pending tool approval (write_file) awaiting user decision.
+    /// Pending tool approval awaiting user decision.
     pub pending_approval: Option<PendingToolApproval>,
     /// Sender to resume the tool loop after approval/rejection.
     approval_tx: Option<oneshot::Sender<bool>>,
+    /// Provenance and taint tracking for tool and retrieval content.
+    content_guard: ContentGuard,
+    /// Raised when external knowledge-base content was added to the request.
+    untrusted_context: Option<Arc<AtomicBool>>,
     /// Context window tracking token usage across conversation messages.
     /// Used to trigger proactive compaction before exceeding model limits.
     context_window: ContextWindow,
+    /// Checkpoints of the open project; agent tool rounds that write files
+    /// are checkpointed on the conversation's timeline.
+    checkpoints: Option<Arc<CheckpointStore>>,
+    /// The checkpoint taken before the most recent tool round that wrote files.
+    pub last_checkpoint: Option<Checkpoint>,
+}
+
+/// Describe a `replace_in_files` call for the approval prompt: the
+/// directory it targets and the line edits it would make.
+fn replace_approval_diff(input: &serde_json::Value) -> (String, Vec<DiffLine>) {
+    let target = input
+        .get("path")
+        .and_then(|v| v.as_str())
+        .unwrap_or(".")
+        .to_string();
+    let preview = match ReplaceInFilesTool::preview(input) {
+        Ok(preview) => preview,
+        Err(e) => return (target, vec![DiffLine::Context(e)]),
+    };
+
+    let mut lines = Vec::new();
+    for file in &preview.files {
+        lines.push(DiffLine::Context(format!(
+            "{} ({} replacement(s))",
+            file.path.display(),
+            file.replacements
+        )));
+        for edit in &file.edits {
+            let removed = edit.before.lines().map(|l| DiffLine::Removed(l.to_string()));
+            let added = edit.after.lines().map(|l| DiffLine::Added(l.to_string()));
+            lines.extend(removed.chain(added));
+        }
+    }
+    (target, lines)
 }
 
 /// Route any "Unknown tool" results through the MCP integration server.
@@ -464,7 +510,11 @@ impl ChatService {
             generation: 0,
             pending_approval: None,
             approval_tx: None,
+            content_guard: ContentGuard::new(),
+            untrusted_context: None,
             context_window: ContextWindow::new(128_000),
+            checkpoints: None,
+            last_checkpoint: None,
         }
     }
 
@@ -619,13 +669,44 @@ impl ChatService {
         self._stream_task = None;
         self.pending_approval = None;
         self.approval_tx = None;
+        self.content_guard.reset();
+        self.untrusted_context = None;
         self.context_window = ContextWindow::new(self.context_window.max_tokens());
         self.generation += 1;
     }
 
     // -- Tool Approval ------------------------------------------------------
 
-    /// Resolve a pending tool approval. If `approved` is true the write_file
+    /// Provenance and taint state for the current conversation.
+    pub fn content_guard(&self) -> &ContentGuard {
+        &self.content_guard
+    }
+
+    /// Scan and wrap context injected outside the tool loop (retrieved code,
+    /// knowledge files) so it is treated as data and taints the conversation
+    /// when it carries injection patterns.
+    pub fn guard_context(&mut self, content: &str, provenance: Provenance) -> String {
+        self.content_guard.guard(content, provenance).content
+    }
+
+    /// Watch a flag set by the background enrichment task when it injects
+    /// external knowledge-base content into the request.
+    pub fn watch_untrusted_context(&mut self, flag: Arc<AtomicBool>) {
+        self.untrusted_context = Some(flag);
+    }
+
+    /// Fold a raised enrichment flag into the content guard's taint.
+    fn sync_untrusted_context(&mut self) {
+        if let Some(flag) = &self.untrusted_context
+            && flag.load(Ordering::SeqCst)
+        {
+            self.content_guard
+                .taint(Provenance::new("knowledge_hub", TrustLevel::Untrusted));
+            self.untrusted_context = None;
+        }
+    }
+
+    /// Resolve a pending tool approval. If `approved` is true the gated
     /// tool will execute; if false the tool is skipped and the AI is informed.
     pub fn resolve_approval(&mut self, approved: bool, cx: &mut gpui::Context<Self>) {
         self.pending_approval = None;
@@ -646,6 +727,44 @@ impl ChatService {
         self.conversation_id = Some(Uuid::new_v4().to_string());
     }
 
+    /// Checkpoint agent edits to the project at `root`. Without a store the
+    /// tool loop still runs, it just takes no checkpoints.
+    pub fn set_workspace_root(&mut self, root: &std::path::Path) {
+        if self
+            .checkpoints
+            .as_ref()
+            .is_some_and(|store| root.canonicalize().is_ok_and(|r| r == store.workspace()))
+        {
+            return;
+        }
+        self.checkpoints = match CheckpointStore::open(root) {
+            Ok(store) => Some(Arc::new(store)),
+            Err(e) => {
+                warn!("Checkpoints unavailable for {}: {e}", root.display());
+                None
+            }
+        };
+    }
+
+    /// The checkpoint store for the attached workspace, if any.
+    pub fn checkpoints(&self) -> Option<Arc<CheckpointStore>> {
+        self.checkpoints.clone()
+    }
+
+    /// A tool executor for an agent turn, checkpointing on this
+    /// conversation's timeline when a workspace is attached.
+    fn tool_executor(&mut self, max_iterations: usize) -> ToolExecutor {
+        let executor = ToolExecutor::new(hive_agents::tool_use::builtin_registry(), max_iterations);
+        let Some(store) = self.checkpoints.clone() else {
+            return executor;
+        };
+        let id = self
+            .conversation_id
+            .get_or_insert_with(|| Uuid::new_v4().to_string())
+            .clone();
+        executor.with_checkpoints(store, &id)
+    }
+
     /// Save the current conversation to disk via [`ConversationStore`].
     ///
     /// If no `conversation_id` has been set yet, a new UUID is generated.
@@ -1011,8 +1130,10 @@ impl ChatService {
         initial_request: ChatRequest,
         cx: &mut Context<Self>,
     ) {
+        const MAX_TOOL_ITERATIONS: usize = 10;
         let assistant_idx = self.messages.len().saturating_sub(1);
         let model_clone = model.clone();
+        let mut executor = self.tool_executor(MAX_TOOL_ITERATIONS);
 
         let task = cx.spawn(
             async move |this: WeakEntity<ChatService>, app: &mut AsyncApp| {
@@ -1020,7 +1141,6 @@ impl ChatService {
                 let mut current_request = initial_request;
                 let mut current_assistant_idx = assistant_idx;
                 let mut iteration = 0usize;
-                const MAX_TOOL_ITERATIONS: usize = 10;
 
                 loop {
                     // --- Consume the current stream ---
@@ -1112,38 +1232,98 @@ impl ChatService {
                         final_tool_calls.len()
                     );
 
-                    // Check if any tool call is write_file — if so, gate it.
-                    let write_file_call = final_tool_calls.iter().find(|tc| tc.name == "write_file");
-
-                    if let Some(wf) = write_file_call {
-                        // Extract file_path and content from tool call input.
-                        let file_path = wf.input.get("file_path")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-                        let new_content = wf.input.get("content")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-
-                        // Read existing file for diff.
-                        let old_content = std::fs::read_to_string(&file_path).ok();
-                        let diff_lines = if let Some(ref old) = old_content {
-                            compute_diff_lines(old, &new_content)
-                        } else {
-                            new_content.lines()
-                                .map(|l| DiffLine::Added(l.to_string()))
+                    // Gate write_file, applied replace_in_files, and any
+                    // high-risk tool once untrusted content has entered the
+                    // conversation, behind user approval.
+                    let gated_calls: Vec<(AiToolCall, Option<String>)> = this
+                        .update(app, |svc: &mut ChatService, _cx| {
+                            svc.sync_untrusted_context();
+                            final_tool_calls
+                                .iter()
+                                .filter_map(|tc| {
+                                    let reason = svc.content_guard.approval_reason(&tc.name);
+                                    let applies_replace = tc.name == "replace_in_files"
+                                        && tc.input.get("apply").and_then(|v| v.as_bool())
+                                            == Some(true);
+                                    (tc.name == "write_file" || applies_replace || reason.is_some())
+                                        .then(|| (tc.clone(), reason))
+                                })
                                 .collect()
-                        };
+                        })
+                        .unwrap_or_default();
+
+                    let mut rejected: Vec<hive_agents::tool_use::ToolResult> = Vec::new();
+                    for (gated, reason) in gated_calls {
+                        let approval = if gated.name == "write_file" {
+                            // Extract file_path and content from tool call input.
+                            let file_path = gated
+                                .input
+                                .get("file_path")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("")
+                                .to_string();
+                            let new_content = gated
+                                .input
+                                .get("content")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("")
+                                .to_string();
+
+                            // Read existing file for diff.
+                            let old_content = std::fs::read_to_string(&file_path).ok();
+                            let diff_lines = if let Some(ref old) = old_content {
+                                compute_diff_lines(old, &new_content)
+                            } else {
+                                new_content
+                                    .lines()
+                                    .map(|l| DiffLine::Added(l.to_string()))
+                                    .collect()
+                            };
 
-                        let approval = PendingToolApproval {
-                            tool_call_id: wf.id.clone(),
-                            tool_name: wf.name.clone(),
-                            file_path: file_path.clone(),
-                            new_content: new_content.clone(),
-                            old_content: old_content.clone(),
-                            diff_lines,
+                            PendingToolApproval {
+                                tool_call_id: gated.id.clone(),
+                                tool_name: gated.name.clone(),
+                                file_path,
+                                new_content,
+                                old_content,
+                                diff_lines,
+                                reason,
+                            }
+                        } else if gated.name == "replace_in_files" {
+                            let (target, diff_lines) = replace_approval_diff(&gated.input);
+                            PendingToolApproval {
+                                tool_call_id: gated.id.clone(),
+                                tool_name: gated.name.clone(),
+                                file_path: target,
+                                new_content: serde_json::to_string_pretty(&gated.input)
+                                    .unwrap_or_default(),
+                                old_content: None,
+                                diff_lines,
+                                reason,
+                            }
+                        } else {
+                            let target = ["command", "url", "channel", "path"]
+                                .iter()
+                                .find_map(|k| gated.input.get(*k).and_then(|v| v.as_str()))
+                                .unwrap_or(gated.name.as_str())
+                                .to_string();
+                            let new_content =
+                                serde_json::to_string_pretty(&gated.input).unwrap_or_default();
+                            let diff_lines = new_content
+                                .lines()
+                                .map(|l| DiffLine::Added(l.to_string()))
+                                .collect();
+                            PendingToolApproval {
+                                tool_call_id: gated.id.clone(),
+                                tool_name: gated.name.clone(),
+                                file_path: target,
+                                new_content,
+                                old_content: None,
+                                diff_lines,
+                                reason,
+                            }
                         };
+                        let target = approval.file_path.clone();
 
                         // Create oneshot channel and set pending approval.
                         let (tx, rx) = oneshot::channel::<bool>();
@@ -1157,113 +1337,56 @@ impl ChatService {
                         // Wait for user decision.
                         let approved = rx.await.unwrap_or(false);
 
-                        if !approved {
-                            // User rejected — skip write_file, execute other tools,
-                            // and add a rejection result.
-                            let registry = hive_agents::tool_use::builtin_registry();
-                            let agent_calls: Vec<hive_agents::tool_use::ToolCall> = final_tool_calls
-                                .iter()
-                                .filter(|tc| tc.name != "write_file")
-                                .map(|tc| hive_agents::tool_use::ToolCall {
-                                    id: tc.id.clone(),
-                                    name: tc.name.clone(),
-                                    input: tc.input.clone(),
-                                })
-                                .collect();
-                            let mut results = registry.execute_all(&agent_calls);
-                            route_unknown_to_mcp(&this, app, &mut results, &agent_calls);
-
-                            // Add rejection result for write_file call.
-                            results.push(hive_agents::tool_use::ToolResult {
-                                tool_use_id: wf.id.clone(),
-                                content: format!("User rejected write to {file_path}. Do not retry without asking."),
-                                is_error: true,
-                            });
-
-                            // Continue with results below.
-                            let _ = this.update(app, |svc: &mut ChatService, cx| {
-                                svc.pending_approval = None;
-                                svc.approval_tx = None;
-                                cx.notify();
-                            });
-
-                            // Use these results for the rest of the loop.
-                            let registry_results = results;
-
-                            // --- Update conversation (rejected) ---
-                            let m = model_clone.clone();
-                            let tc_for_msg = final_tool_calls.clone();
-                            let update_result = this.update(app, |svc: &mut ChatService, cx| {
-                                if let Some(msg) = svc.messages.get_mut(current_assistant_idx) {
-                                    msg.content = accumulated.clone();
-                                    msg.model = Some(m.clone());
-                                    msg.tool_calls = Some(tc_for_msg);
-                                    if let Some(ref u) = final_usage {
-                                        let cost = hive_ai::cost::calculate_cost(
-                                            &m,
-                                            u.prompt_tokens as usize,
-                                            u.completion_tokens as usize,
-                                        );
-                                        msg.cost = Some(cost.total_cost);
-                                        msg.tokens = Some((u.prompt_tokens as usize, u.completion_tokens as usize));
-                                    }
-                                }
-                                for result in &registry_results {
-                                    let mut tool_msg = ChatMessage::new(MessageRole::Tool, &result.content);
-                                    tool_msg.tool_call_id = Some(result.tool_use_id.clone());
-                                    svc.messages.push(tool_msg);
-                                }
-                                svc.messages.push(ChatMessage::assistant_placeholder());
-                                svc.streaming_content.clear();
-                                svc.generation += 1;
-                                cx.notify();
-                                svc.messages.len() - 1
-                            });
-
-                            let Ok(new_idx) = update_result else { break; };
-                            current_assistant_idx = new_idx;
-
-                            // Rebuild request and continue loop.
-                            let msgs_result = this.update(app, |svc: &mut ChatService, _cx| svc.build_ai_messages());
-                            let Ok(ai_messages) = msgs_result else { break; };
-                            current_request = ChatRequest {
-                                messages: ai_messages,
-                                model: current_request.model.clone(),
-                                max_tokens: current_request.max_tokens,
-                                temperature: current_request.temperature,
-                                system_prompt: current_request.system_prompt.clone(),
-                                tools: current_request.tools.clone(),
-                                cache_system_prompt: false,
-                            };
-                            current_rx = spawn_provider_stream(
-                                provider.clone(),
-                                current_request.clone(),
-                                "tool re-send",
-                            );
-                            iteration += 1;
-                            continue;
-                        }
-
-                        // User approved — clear approval state and proceed normally.
                         let _ = this.update(app, |svc: &mut ChatService, cx| {
                             svc.pending_approval = None;
                             svc.approval_tx = None;
                             cx.notify();
                         });
+
+                        if !approved {
+                            let content = if gated.name == "write_file" {
+                                format!(
+                                    "User rejected write to {target}. Do not retry without asking."
+                                )
+                            } else {
+                                format!(
+                                    "User rejected {} ({target}). Do not retry without asking.",
+                                    gated.name
+                                )
+                            };
+                            rejected.push(hive_agents::tool_use::ToolResult {
+                                tool_use_id: gated.id.clone(),
+                                content,
+                                is_error: true,
+                            });
+                        }
                     }
 
-                    let registry = hive_agents::tool_use::builtin_registry();
                     let agent_calls: Vec<hive_agents::tool_use::ToolCall> = final_tool_calls
                         .iter()
+                        .filter(|tc| !rejected.iter().any(|r| r.tool_use_id == tc.id))
                         .map(|tc| hive_agents::tool_use::ToolCall {
                             id: tc.id.clone(),
                             name: tc.name.clone(),
                             input: tc.input.clone(),
                         })
                         .collect();
-                    let mut results = registry.execute_all(&agent_calls);
+                    let mut results = executor.execute_calls(agent_calls.clone());
+                    if let Some(checkpoint) = executor.last_checkpoint().cloned() {
+                        let _ = this.update(app, |svc: &mut ChatService, _cx| {
+                            svc.last_checkpoint = Some(checkpoint);
+                        });
+                    }
                     route_unknown_to_mcp(&this, app, &mut results, &agent_calls);
 
+                    // Tag, scan and wrap results before they enter the context.
+                    let _ = this.update(app, |svc: &mut ChatService, _cx| {
+                        for (result, call) in results.iter_mut().zip(agent_calls.iter()) {
+                            svc.content_guard.guard_tool_result(call, result);
+                        }
+                    });
+                    results.extend(rejected);
+
                     // --- Update conversation ---
                     let m = model_clone.clone();
                     let tc_for_msg = final_tool_calls.clone();
@@ -4,6 +4,7 @@ use gpui_component::input::{InputEvent, InputState};
 use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use tracing::{error, info, warn};
 
 use hive_agents::plugin_manager::PluginManager;
@@ -91,9 +92,10 @@ pub use hive_ui_core::{
     AppPluginManager, ApplyAllEdits, ApplyCodeBlock, ChannelSelect, ChatReadAloud, CheckCalendar,
     CheckEmail, ClearChat, ContextFormatChanged, CopyFullPrompt, CopyToClipboard,
     CostsClearHistory, CostsExportCsv, CostsResetToday, DailyBriefing, DestructiveCancel,
-    DestructiveConfirm, ExportConfig, ExportPrompt, FilesClearChecked, FilesCloseViewer,
-    FilesDeleteEntry, FilesNavigateBack, FilesNavigateTo, FilesNewFile, FilesNewFolder,
-    FilesOpenEntry, FilesRefresh, FilesSetSearchQuery, FilesToggleCheck, HistoryClearAll,
+    DestructiveConfirm, ExportConfig, ExportPrompt, FilesApplyReplace, FilesCancelReplace,
+    FilesClearChecked, FilesCloseViewer, FilesDeleteEntry, FilesNavigateBack, FilesNavigateTo,
+    FilesNewFile, FilesNewFolder, FilesOpenEntry, FilesPreviewReplace, FilesRefresh,
+    FilesSetSearchQuery, FilesToggleCheck, HistoryClearAll,
     HistoryClearAllCancel, HistoryClearAllConfirm, HistoryDeleteConversation,
     HistoryLoadConversation, HistoryRefresh, HistorySetSearchQuery, ImportConfig, KanbanAddTask,
     LogsClear, LogsSetFilter, LogsSetSearchQuery, LogsToggleAutoScroll, MonitorRefresh,
@@ -213,6 +215,7 @@ async fn enrich_request_with_memory(
     hive_mem: &Option<std::sync::Arc<tokio::sync::Mutex<hive_ai::memory::HiveMemory>>>,
     knowledge_hub: &Option<std::sync::Arc<hive_integrations::knowledge::KnowledgeHub>>,
     query_text: &str,
+    untrusted_seen: &std::sync::atomic::AtomicBool,
 ) {
     let mut extra_context = String::new();
     let mut memory_ctx = String::new();
@@ -240,9 +243,20 @@ async fn enrich_request_with_memory(
     if let Some(ref kb) = *knowledge_hub {
         let kb_context = kb.get_context_all(query_text).await;
         if !kb_context.trim().is_empty() {
+            // External knowledge bases are third-party content: wrap them as
+            // data and let the chat loop know the context is now tainted.
+            let provenance = hive_agents::content_guard::Provenance::new(
+                "knowledge_hub",
+                hive_agents::content_guard::TrustLevel::Untrusted,
+            );
             extra_context.push_str("# Knowledge Base Context\n\n");
-            extra_context.push_str(&kb_context);
+            extra_context.push_str(&hive_agents::content_guard::wrap_untrusted(
+                &kb_context,
+                &provenance,
+                None,
+            ));
             extra_context.push_str("\n\n");
+            untrusted_seen.store(true, std::sync::atomic::Ordering::SeqCst);
         }
     }
 
@@ -296,6 +310,9 @@ pub struct HiveWorkspace {
     command_palette_input: Entity<InputState>,
     destructive_confirmation_input: Entity<InputState>,
     files_search_input: Entity<InputState>,
+    files_replace_input: Entity<InputState>,
+    /// Stops the Files panel's running content search when set.
+    files_search_cancel: Arc<AtomicBool>,
     history_search_input: Entity<InputState>,
     logs_search_input: Entity<InputState>,
     skills_search_input: Entity<InputState>,
@@ -385,7 +402,7 @@ pub struct HiveWorkspace {
     /// reviewing the import preview screen. Consumed by confirm handler.
     pending_plugin_preview: Option<(PluginPreview, PluginSource)>,
     /// File watcher for incremental RAG indexing. Dropped on project switch.
-    _file_watcher: Option<hive_fs::FileWatcher>,
+    _file_watcher: Option<hive_fs::DebouncedWatcher>,
     /// Completed swarm task trees. Appended after `/swarm` execution and shown
     /// in the monitor panel's background tasks section alongside active runs.
     swarm_task_trees: Vec<hive_ui_panels::components::task_tree::TaskTreeState>,
@@ -675,6 +692,7 @@ impl HiveWorkspace {
         }
 
         let project_root = project_context::resolve_project_root_from_session(&session);
+        chat_service.update(cx, |svc, _| svc.set_workspace_root(&project_root));
         let recent_workspace_roots =
             project_context::load_recent_workspace_roots(&session, &project_root);
         let pinned_workspace_roots = project_context::load_pinned_workspace_roots(&session);
@@ -773,6 +791,25 @@ impl HiveWorkspace {
         )
         .detach();
 
+        let files_replace_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx);
+            state.set_placeholder("Replace with...", window, cx);
+            state
+        });
+        cx.subscribe_in(
+            &files_replace_input,
+            window,
+            |this, _state, event: &InputEvent, _window, cx| {
+                // A preview is only valid for the replacement it was built with.
+                if matches!(event, InputEvent::Change) && this.files_data.replace_preview.is_some()
+                {
+                    this.files_data.replace_preview = None;
+                    cx.notify();
+                }
+            },
+        )
+        .detach();
+
         let history_search_input = cx.new(|cx| {
             let mut state = InputState::new(window, cx);
             state.set_placeholder("Search conversations...", window, cx);
@@ -1302,6 +1339,8 @@ impl HiveWorkspace {
             command_palette_input,
             destructive_confirmation_input,
             files_search_input,
+            files_replace_input,
+            files_search_cancel: Arc::new(AtomicBool::new(false)),
             history_search_input,
             logs_search_input,
             skills_search_input,
@@ -1940,6 +1979,9 @@ impl Render for HiveWorkspace {
             .on_action(cx.listener(file_actions::handle_files_toggle_check))
             .on_action(cx.listener(file_actions::handle_files_clear_checked))
             .on_action(cx.listener(file_actions::handle_files_set_search_query))
+            .on_action(cx.listener(file_actions::handle_files_preview_replace))
+            .on_action(cx.listener(file_actions::handle_files_apply_replace))
+            .on_action(cx.listener(file_actions::handle_files_cancel_replace))
             .on_action(cx.listener(chat_actions::handle_chat_read_aloud))
             // Apply mode + clipboard
             .on_action(cx.listener(chat_actions::handle_apply_code_block))
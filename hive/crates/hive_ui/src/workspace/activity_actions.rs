@@ -46,12 +46,16 @@ pub(super) fn handle_activity_approve(
     cx: &mut Context<HiveWorkspace>,
 ) {
     info!("Activity: approve request_id={}", action.request_id);
-    if cx.has_global::<AppApprovalGate>() {
-        cx.global::<AppApprovalGate>()
-            .0
-            .respond(&action.request_id, hive_agents::ApprovalDecision::Approved);
-    }
-    if cx.has_global::<AppActivityService>() {
+    let progress = if cx.has_global::<AppApprovalGate>() {
+        cx.global::<AppApprovalGate>().0.respond(
+            &action.request_id,
+            hive_agents::LOCAL_APPROVER,
+            hive_agents::ApprovalDecision::Approved,
+        )
+    } else {
+        hive_agents::ApprovalProgress::Granted
+    };
+    if progress == hive_agents::ApprovalProgress::Granted && cx.has_global::<AppActivityService>() {
         cx.global::<AppActivityService>()
             .0
             .emit(hive_agents::ActivityEvent::ApprovalGranted {
@@ -59,12 +63,24 @@ pub(super) fn handle_activity_approve(
             });
     }
     data_refresh::refresh_activity_data(workspace, cx);
-    workspace.push_notification(
-        cx,
-        NotificationType::Success,
-        "Observe",
-        format!("Approved request {}.", action.request_id),
-    );
+    let (kind, message) = match progress {
+        hive_agents::ApprovalProgress::Granted => (
+            NotificationType::Success,
+            format!("Approved request {}.", action.request_id),
+        ),
+        hive_agents::ApprovalProgress::Waiting { remaining } => (
+            NotificationType::Info,
+            format!(
+                "Approval recorded for request {}; waiting on {remaining} more approver(s).",
+                action.request_id
+            ),
+        ),
+        _ => (
+            NotificationType::Warning,
+            format!("Request {} is no longer pending.", action.request_id),
+        ),
+    };
+    workspace.push_notification(cx, kind, "Observe", message);
     cx.notify();
 }
 
@@ -81,6 +97,7 @@ pub(super) fn handle_activity_deny(
     if cx.has_global::<AppApprovalGate>() {
         cx.global::<AppApprovalGate>().0.respond(
             &action.request_id,
+            hive_agents::LOCAL_APPROVER,
             hive_agents::ApprovalDecision::Denied {
                 reason: Some(action.reason.clone()),
             },
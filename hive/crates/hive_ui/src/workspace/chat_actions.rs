@@ -3,9 +3,10 @@ use std::sync::Arc;
 use gpui::*;
 use tracing::{error, info, warn};
 
+use hive_agents::content_guard::{Provenance, TrustLevel, UNTRUSTED_CONTENT_INSTRUCTIONS};
 use hive_ai::speculative::SpeculativeConfig;
 use hive_ai::types::{ChatRequest, StreamChunk, ToolDefinition as AiToolDefinition};
-use hive_ui_core::{AppCollectiveMemory, AppCortexInteractionTracker};
+use hive_ui_core::{AppCollectiveMemory, AppCortexInteractionTracker, AppTeamDispatcher};
 use hive_ui_panels::panels::settings::{
     ProviderKeyState, reconcile_project_model_selection, validate_model_selection,
 };
@@ -35,6 +36,7 @@ fn spawn_enriched_provider_stream(
     hive_mem: Option<Arc<tokio::sync::Mutex<hive_ai::memory::HiveMemory>>>,
     knowledge_hub: Option<Arc<hive_integrations::knowledge::KnowledgeHub>>,
     query_text: String,
+    untrusted_seen: Arc<std::sync::atomic::AtomicBool>,
 ) -> tokio::sync::mpsc::Receiver<StreamChunk> {
     let (tx, rx) = tokio::sync::mpsc::channel::<StreamChunk>(256);
     let tx_for_thread = tx.clone();
@@ -58,6 +60,7 @@ fn spawn_enriched_provider_stream(
                     &hive_mem,
                     &knowledge_hub,
                     &query_text,
+                    &untrusted_seen,
                 )
                 .await;
 
@@ -218,6 +221,7 @@ pub(super) fn handle_apply_code_block(
             new_content: new_content.clone(),
             old_content,
             diff_lines,
+            reason: None,
         });
         cx.notify();
     });
@@ -591,6 +595,12 @@ pub(super) fn handle_send_text(
             cx.set_global(AppKnowledgeFiles(fresh_sources));
 
             if !knowledge_text.trim().is_empty() {
+                let knowledge_text = workspace.chat_service.update(cx, |svc, _| {
+                    svc.guard_context(
+                        &knowledge_text,
+                        Provenance::new("knowledge_files", TrustLevel::Workspace),
+                    )
+                });
                 let kf_idx = augmented
                     .iter()
                     .position(|m| m.role != hive_ai::types::MessageRole::System)
@@ -673,6 +683,9 @@ pub(super) fn handle_send_text(
 
         // Inject retrieved code context
         if !all_context.trim().is_empty() {
+            let all_context = workspace.chat_service.update(cx, |svc, _| {
+                svc.guard_context(&all_context, Provenance::new("rag", TrustLevel::Workspace))
+            });
             let ctx_idx = augmented
                 .iter()
                 .position(|m| m.role != hive_ai::types::MessageRole::System)
@@ -846,6 +859,13 @@ pub(super) fn handle_send_text(
     } else {
         hive_ai::ContextFormat::Markdown
     };
+    // Tool results and retrieved context arrive wrapped in <untrusted_content>;
+    // tell the model to treat them as data.
+    system_prompt = Some(match system_prompt {
+        Some(s) => format!("{s}\n\n{UNTRUSTED_CONTENT_INSTRUCTIONS}"),
+        None => UNTRUSTED_CONTENT_INSTRUCTIONS.to_string(),
+    });
+
     if ctx_format_for_prompt == hive_ai::ContextFormat::Xml {
         let xml_instruction = "\n\nWhen suggesting code changes, wrap each file edit in an XML tag: <edit path=\"relative/path\" lang=\"language\">new file content</edit>";
         system_prompt = Some(
@@ -939,15 +959,18 @@ pub(super) fn handle_send_text(
         );
     }
 
+    let untrusted_seen = Arc::new(std::sync::atomic::AtomicBool::new(false));
     let rx = spawn_enriched_provider_stream(
         provider.clone(),
         request.clone(),
         hive_mem_for_async,
         knowledge_hub_for_async,
         query_for_memory,
+        untrusted_seen.clone(),
     );
     let task = cx.spawn(async move |_this, app: &mut AsyncApp| {
         let _ = chat_svc.update(app, |svc, cx| {
+            svc.watch_untrusted_context(untrusted_seen);
             svc.attach_tool_stream(
                 rx,
                 model_for_attach,
@@ -1060,6 +1083,13 @@ pub(super) fn try_handle_swarm_send(
     };
 
     let model_for_exec = model.to_string();
+    let workspace_root = workspace.current_project_root.display().to_string();
+    // Federated peers, when team delegation is enabled and the node is up.
+    let team_dispatcher = if cx.has_global::<AppTeamDispatcher>() {
+        cx.global::<AppTeamDispatcher>().0.get().cloned()
+    } else {
+        None
+    };
     // Honor the user's auto_routing setting for the swarm (defaults to true).
     let auto_routing = if cx.has_global::<AppConfig>() {
         cx.global::<AppConfig>().0.get().auto_routing
@@ -1096,6 +1126,7 @@ pub(super) fn try_handle_swarm_send(
 
         let swarm_config = hive_agents::swarm::SwarmConfig {
             auto_routing,
+            workspace: Some(workspace_root),
             ..hive_agents::swarm::SwarmConfig::default()
         };
         let mut queen = hive_agents::Queen::new(swarm_config, executor);
@@ -1114,6 +1145,9 @@ pub(super) fn try_handle_swarm_send(
         if let Some(ref notifications) = notification_service {
             queen = queen.with_notifications(notifications.clone());
         }
+        if let Some(dispatcher) = team_dispatcher {
+            queen = queen.with_dispatcher(dispatcher);
+        }
 
         let result_text = match queen.execute(&goal).await {
             Ok(result) => {
@@ -741,10 +741,13 @@ fn render_pending_tool_context(
             div()
                 .text_size(theme.font_size_xs)
                 .text_color(theme.text_secondary)
-                .child(format!(
-                    "{} wants to update {} ({} diff lines).",
-                    approval.tool_name, file_path, diff_lines
-                )),
+                .child(match &approval.reason {
+                    Some(reason) => reason.clone(),
+                    None => format!(
+                        "{} wants to update {} ({} diff lines).",
+                        approval.tool_name, file_path, diff_lines
+                    ),
+                }),
         )
         .child(
             div()
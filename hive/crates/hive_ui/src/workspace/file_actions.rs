@@ -1,16 +1,23 @@
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use gpui::*;
+use hive_fs::{ReplacePreview, SearchResult, SearchService};
 use hive_ui_core::{DestructiveActionKind, DestructiveConfirmation};
 use tracing::{error, info, warn};
 
 use super::{
-    AppContextSelection, AppSemanticSearch, FilesClearChecked, FilesCloseViewer, FilesData,
-    FilesDeleteEntry, FilesNavigateBack, FilesNavigateTo, FilesNewFile, FilesNewFolder,
-    FilesOpenEntry, FilesRefresh, FilesSetSearchQuery, FilesToggleCheck, HiveWorkspace,
-    destructive_actions,
+    AppContextSelection, AppSemanticSearch, FilesApplyReplace, FilesCancelReplace,
+    FilesClearChecked, FilesCloseViewer, FilesData, FilesDeleteEntry, FilesNavigateBack,
+    FilesNavigateTo, FilesNewFile, FilesNewFolder, FilesOpenEntry, FilesPreviewReplace,
+    FilesRefresh, FilesSetSearchQuery, FilesToggleCheck, HiveWorkspace, destructive_actions,
 };
 
+/// How often streamed content matches are moved into the Files panel.
+const CONTENT_SEARCH_POLL: Duration = Duration::from_millis(100);
+
 pub(super) fn handle_files_navigate_back(
     workspace: &mut HiveWorkspace,
     _action: &FilesNavigateBack,
@@ -33,7 +40,18 @@ pub(super) fn handle_files_navigate_to(
 ) {
     let path = PathBuf::from(&action.path);
     info!("Files: navigate to {}", path.display());
-    workspace.files_data = FilesData::from_path(&path);
+    // Search results point at files: open their directory and view them.
+    if path.is_file()
+        && let Some(parent) = path.parent()
+    {
+        workspace.files_data = FilesData::from_path(parent);
+        workspace.files_data.selected_file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+        workspace.files_data.open_file_viewer(&path);
+    } else {
+        workspace.files_data = FilesData::from_path(&path);
+    }
     cx.notify();
 }
 
@@ -137,9 +155,224 @@ pub(super) fn handle_files_set_search_query(
         }
     }
 
+    start_content_search(workspace, cx);
     cx.notify();
 }
 
+/// Stream literal content matches for the current query into the Files
+/// panel, cancelling any search still running for an earlier query.
+fn start_content_search(workspace: &mut HiveWorkspace, cx: &mut Context<HiveWorkspace>) {
+    workspace.files_search_cancel.store(true, Ordering::Relaxed);
+    workspace.files_data.clear_content_search();
+
+    let query = workspace.files_data.search_query.trim().to_string();
+    if query.is_empty() {
+        return;
+    }
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    workspace.files_search_cancel = Arc::clone(&cancel);
+    workspace.files_data.content_search_running = true;
+
+    let root = workspace.files_data.current_path.clone();
+    let found: Arc<Mutex<Vec<SearchResult>>> = Arc::new(Mutex::new(Vec::new()));
+    let done = Arc::new(AtomicBool::new(false));
+    {
+        let (cancel, found, done) = (Arc::clone(&cancel), Arc::clone(&found), Arc::clone(&done));
+        let spawned = std::thread::Builder::new()
+            .name("hive-files-search".into())
+            .spawn(move || {
+                let result = SearchService::search_streaming(
+                    &root,
+                    &query,
+                    FilesData::content_search_options(),
+                    |hit| {
+                        found.lock().unwrap_or_else(|e| e.into_inner()).push(hit);
+                        !cancel.load(Ordering::Relaxed)
+                    },
+                );
+                if let Err(e) = result {
+                    warn!("Files: content search failed: {e}");
+                }
+                done.store(true, Ordering::Release);
+            });
+        if let Err(e) = spawned {
+            warn!("Files: could not start content search: {e}");
+            workspace.files_data.content_search_running = false;
+            return;
+        }
+    }
+
+    cx.spawn(
+        async move |this: WeakEntity<HiveWorkspace>, app: &mut AsyncApp| {
+            loop {
+                // Read `done` before draining so the final batch is never missed.
+                let finished = done.load(Ordering::Acquire);
+                let batch = std::mem::take(&mut *found.lock().unwrap_or_else(|e| e.into_inner()));
+                if cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                let _ = this.update(app, |workspace, cx| {
+                    for hit in batch {
+                        workspace.files_data.push_content_result(hit);
+                    }
+                    workspace.files_data.content_search_running = !finished;
+                    cx.notify();
+                });
+                if finished {
+                    break;
+                }
+                app.background_executor().timer(CONTENT_SEARCH_POLL).await;
+            }
+        },
+    )
+    .detach();
+}
+
+/// Run `job` on its own thread and hand its result to `on_done` on the
+/// workspace once it finishes.
+fn run_in_background<T: Send + 'static>(
+    cx: &mut Context<HiveWorkspace>,
+    name: &str,
+    job: impl FnOnce() -> T + Send + 'static,
+    on_done: impl FnOnce(&mut HiveWorkspace, T, &mut Context<HiveWorkspace>) + 'static,
+) {
+    let slot: Arc<Mutex<Option<T>>> = Arc::new(Mutex::new(None));
+    let slot_for_thread = Arc::clone(&slot);
+    if let Err(e) = std::thread::Builder::new()
+        .name(name.to_string())
+        .spawn(move || {
+            let result = job();
+            *slot_for_thread.lock().unwrap_or_else(|e| e.into_inner()) = Some(result);
+        })
+    {
+        warn!("Files: could not start {name}: {e}");
+        return;
+    }
+
+    cx.spawn(
+        async move |this: WeakEntity<HiveWorkspace>, app: &mut AsyncApp| {
+            loop {
+                let result = slot.lock().unwrap_or_else(|e| e.into_inner()).take();
+                if let Some(result) = result {
+                    let _ = this.update(app, |workspace, cx| {
+                        on_done(workspace, result, cx);
+                        cx.notify();
+                    });
+                    break;
+                }
+                app.background_executor().timer(CONTENT_SEARCH_POLL).await;
+            }
+        },
+    )
+    .detach();
+}
+
+pub(super) fn handle_files_preview_replace(
+    workspace: &mut HiveWorkspace,
+    _action: &FilesPreviewReplace,
+    _window: &mut Window,
+    cx: &mut Context<HiveWorkspace>,
+) {
+    let query = workspace.files_data.search_query.trim().to_string();
+    if query.is_empty() {
+        return;
+    }
+    let replacement = workspace.files_replace_input.read(cx).value().to_string();
+    let root = workspace.files_data.current_path.clone();
+    workspace.files_data.replace_preview = None;
+    workspace.files_data.replace_status = Some("Building preview...".into());
+    cx.notify();
+
+    run_in_background(
+        cx,
+        "hive-files-replace-preview",
+        move || {
+            SearchService::preview_replace(
+                &root,
+                &query,
+                &replacement,
+                FilesData::content_search_options(),
+            )
+        },
+        |workspace, result, _cx| match result {
+            Ok(preview) if preview.files.is_empty() => {
+                workspace.files_data.replace_status = Some("No matches to replace.".into());
+            }
+            Ok(preview) => {
+                workspace.files_data.replace_status = None;
+                workspace.files_data.replace_preview = Some(preview);
+            }
+            Err(e) => {
+                warn!("Files: replace preview failed: {e}");
+                workspace.files_data.replace_status = Some(format!("Preview failed: {e}"));
+            }
+        },
+    );
+}
+
+pub(super) fn handle_files_apply_replace(
+    workspace: &mut HiveWorkspace,
+    _action: &FilesApplyReplace,
+    _window: &mut Window,
+    cx: &mut Context<HiveWorkspace>,
+) {
+    let Some(preview) = workspace.files_data.replace_preview.take() else {
+        return;
+    };
+    // Checkpoint through the chat's store so the replace can be undone like
+    // an agent edit, as long as the files live inside its workspace.
+    let checkpoints = workspace
+        .chat_service
+        .read(cx)
+        .checkpoints()
+        .filter(|store| covers_preview(store.workspace(), &preview));
+    workspace.files_data.replace_status = Some("Replacing...".into());
+    cx.notify();
+
+    run_in_background(
+        cx,
+        "hive-files-replace-apply",
+        move || SearchService::apply_replace(&preview, checkpoints.as_deref()),
+        |workspace, result, cx| {
+            match result {
+                Ok(outcome) => {
+                    info!(
+                        "Files: replaced {} match(es) in {} file(s)",
+                        outcome.replacements, outcome.files_changed
+                    );
+                    workspace.files_data.replace_status = Some(format!(
+                        "Replaced {} match(es) in {} file(s).",
+                        outcome.replacements, outcome.files_changed
+                    ));
+                }
+                Err(e) => {
+                    warn!("Files: replace failed: {e}");
+                    workspace.files_data.replace_status = Some(format!("Replace failed: {e}"));
+                }
+            }
+            let status = workspace.files_data.replace_status.take();
+            start_content_search(workspace, cx);
+            workspace.files_data.replace_status = status;
+        },
+    );
+}
+
+pub(super) fn handle_files_cancel_replace(
+    workspace: &mut HiveWorkspace,
+    _action: &FilesCancelReplace,
+    _window: &mut Window,
+    cx: &mut Context<HiveWorkspace>,
+) {
+    workspace.files_data.replace_preview = None;
+    workspace.files_data.replace_status = None;
+    cx.notify();
+}
+
+fn covers_preview(root: &std::path::Path, preview: &ReplacePreview) -> bool {
+    preview.files.iter().all(|file| file.path.starts_with(root))
+}
+
 pub(super) fn handle_files_delete_entry(
     workspace: &mut HiveWorkspace,
     action: &FilesDeleteEntry,
@@ -1,3 +1,4 @@
+use gpui::prelude::FluentBuilder;
 use gpui::*;
 use gpui_component::scroll::ScrollableElement;
 
@@ -31,10 +32,13 @@ pub(super) fn render_active_panel(
             theme,
         )
         .into_any_element(),
-        Panel::Files => {
-            FilesPanel::render(&workspace.files_data, &workspace.files_search_input, theme)
-                .into_any_element()
-        }
+        Panel::Files => FilesPanel::render(
+            &workspace.files_data,
+            &workspace.files_search_input,
+            &workspace.files_replace_input,
+            theme,
+        )
+        .into_any_element(),
         Panel::CodeMap => {
             hive_ui_panels::panels::code_map::render_code_map(&workspace.code_map_data, theme)
                 .into_any_element()
@@ -194,11 +198,14 @@ fn render_approval_card(
         })
         .unwrap_or("text");
 
-    let diff_or_code: AnyElement = if is_new_file {
-        render_code_block(&approval.new_content, lang, theme).into_any_element()
-    } else {
-        render_diff(&approval.diff_lines, theme).into_any_element()
-    };
+    let diff_or_code: AnyElement =
+        if approval.reason.is_some() && approval.tool_name != "write_file" {
+            render_code_block(&approval.new_content, "JSON", theme).into_any_element()
+        } else if is_new_file {
+            render_code_block(&approval.new_content, lang, theme).into_any_element()
+        } else {
+            render_diff(&approval.diff_lines, theme).into_any_element()
+        };
 
     div()
         .id("tool-approval-card")
@@ -229,7 +236,9 @@ fn render_approval_card(
                                 .text_size(theme.font_size_sm)
                                 .font_weight(FontWeight::SEMIBOLD)
                                 .text_color(theme.accent_yellow)
-                                .child(if is_new_file {
+                                .child(if approval.reason.is_some() {
+                                    "Untrusted context"
+                                } else if is_new_file {
                                     "Create file"
                                 } else {
                                     "Modify file"
@@ -255,6 +264,16 @@ fn render_approval_card(
                         .child(lang),
                 ),
         )
+        .when_some(approval.reason.clone(), |el, reason| {
+            el.child(
+                div()
+                    .px(theme.space_4)
+                    .py(theme.space_2)
+                    .text_size(theme.font_size_xs)
+                    .text_color(theme.accent_yellow)
+                    .child(reason),
+            )
+        })
         .child(
             div()
                 .max_h(px(300.0))
@@ -181,6 +181,58 @@ const MAX_BACKGROUND_INDEX_FILES: usize = 400;
 const MAX_BACKGROUND_INDEX_FILE_BYTES: u64 = 256 * 1024;
 const MAX_BACKGROUND_INDEX_TOTAL_BYTES: u64 = 24 * 1024 * 1024;
 
+/// Re-index one changed file in the RAG service if it is a reasonably sized
+/// source or text file.
+fn index_changed_file(
+    rag_svc: &std::sync::Mutex<hive_ai::RagService>,
+    project_root: &Path,
+    path: &Path,
+) {
+    // Only index files with common code extensions.
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let indexable = matches!(
+        ext,
+        "rs" | "py"
+            | "js"
+            | "ts"
+            | "tsx"
+            | "jsx"
+            | "go"
+            | "java"
+            | "c"
+            | "cpp"
+            | "h"
+            | "hpp"
+            | "rb"
+            | "swift"
+            | "kt"
+            | "md"
+            | "txt"
+            | "toml"
+            | "yaml"
+            | "yml"
+            | "json"
+    );
+    let within_size_budget = path
+        .metadata()
+        .map(|meta| meta.len() <= MAX_BACKGROUND_INDEX_FILE_BYTES)
+        .unwrap_or(false);
+    if indexable
+        && within_size_budget
+        && let Ok(content) = std::fs::read_to_string(path)
+    {
+        let rel = path
+            .strip_prefix(project_root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
+        if let Ok(mut rag) = rag_svc.lock() {
+            rag.index_file(&rel, &content);
+            tracing::debug!("RAG watcher: indexed {rel}");
+        }
+    }
+}
+
 pub(super) fn start_background_project_indexing(
     workspace: &HiveWorkspace,
     cx: &mut Context<HiveWorkspace>,
@@ -309,6 +361,11 @@ pub(super) fn apply_project_context(
         workspace.session_dirty = true;
         workspace.save_session(cx);
 
+        let root = workspace.current_project_root.clone();
+        workspace
+            .chat_service
+            .update(cx, |svc, _| svc.set_workspace_root(&root));
+
         // Re-scan knowledge files for the new project root.
         let knowledge_sources =
             hive_ai::KnowledgeFileScanner::scan(&workspace.current_project_root);
@@ -370,62 +427,50 @@ pub(super) fn apply_project_context(
             .has_global::<AppRagService>()
             .then(|| cx.global::<AppRagService>().0.clone());
         if let Some(rag_svc) = rag_for_watcher {
-            let project_root = workspace.current_project_root.clone();
-            match hive_fs::FileWatcher::new(&workspace.current_project_root, move |event| {
-                let path = match &event {
-                    hive_fs::WatchEvent::Created(p) | hive_fs::WatchEvent::Modified(p) => {
-                        Some(p.clone())
-                    }
-                    hive_fs::WatchEvent::Renamed { to, .. } => Some(to.clone()),
-                    hive_fs::WatchEvent::Deleted(_) => None,
-                };
-                if let Some(path) = path {
-                    // Only index files with common code extensions.
-                    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-                    let indexable = matches!(
-                        ext,
-                        "rs" | "py"
-                            | "js"
-                            | "ts"
-                            | "tsx"
-                            | "jsx"
-                            | "go"
-                            | "java"
-                            | "c"
-                            | "cpp"
-                            | "h"
-                            | "hpp"
-                            | "rb"
-                            | "swift"
-                            | "kt"
-                            | "md"
-                            | "txt"
-                            | "toml"
-                            | "yaml"
-                            | "yml"
-                            | "json"
-                    );
-                    let within_size_budget = path
-                        .metadata()
-                        .map(|meta| meta.len() <= MAX_BACKGROUND_INDEX_FILE_BYTES)
-                        .unwrap_or(false);
-                    if indexable
-                        && within_size_budget
-                        && let Ok(content) = std::fs::read_to_string(&path)
-                    {
-                        let rel = path
-                            .strip_prefix(&project_root)
-                            .unwrap_or(&path)
-                            .to_string_lossy()
-                            .to_string();
-                        if let Ok(mut rag) = rag_svc.lock() {
-                            rag.index_file(&rel, &content);
-                            tracing::debug!("RAG watcher: indexed {rel}");
-                        }
-                    }
-                }
-            }) {
+            match hive_fs::DebouncedWatcher::new(
+                &workspace.current_project_root,
+                hive_fs::WatcherConfig::default(),
+            ) {
                 Ok(watcher) => {
+                    let project_root = watcher.root().to_path_buf();
+                    let mut changes = watcher.subscribe();
+                    std::thread::Builder::new()
+                        .name("hive-rag-watcher".into())
+                        .spawn(move || {
+                            use tokio::sync::broadcast::error::RecvError;
+                            loop {
+                                match changes.blocking_recv() {
+                                    Ok(hive_fs::ChangeSet::Files(events)) => {
+                                        for event in events {
+                                            let path = match event {
+                                                hive_fs::WatchEvent::Created(p)
+                                                | hive_fs::WatchEvent::Modified(p)
+                                                | hive_fs::WatchEvent::Renamed { to: p, .. } => p,
+                                                hive_fs::WatchEvent::Deleted(_) => continue,
+                                            };
+                                            index_changed_file(&rag_svc, &project_root, &path);
+                                        }
+                                    }
+                                    Ok(hive_fs::ChangeSet::Bulk { reason, changed }) => {
+                                        info!(
+                                            "RAG watcher: bulk change ({reason:?}, {changed} files), reindexing"
+                                        );
+                                        let entries =
+                                            hive_ai::memory::BackgroundIndexer::collect_indexable_files(
+                                                &project_root,
+                                            );
+                                        for path in entries.iter().take(MAX_BACKGROUND_INDEX_FILES) {
+                                            index_changed_file(&rag_svc, &project_root, path);
+                                        }
+                                    }
+                                    Err(RecvError::Lagged(missed)) => {
+                                        warn!("RAG watcher: missed {missed} change batches");
+                                    }
+                                    Err(RecvError::Closed) => break,
+                                }
+                            }
+                        })
+                        .ok();
                     workspace._file_watcher = Some(watcher);
                     info!(
                         "RAG file watcher started for {}",
@@ -453,6 +453,7 @@ pub(super) fn handle_settings_save_from_view(
             cfg.speculative_show_metrics = snapshot.speculative_show_metrics;
             cfg.auto_update = snapshot.auto_update;
             cfg.notifications_enabled = snapshot.notifications_enabled;
+            cfg.terminal_recording = snapshot.terminal_recording;
             cfg.tts_enabled = snapshot.tts_enabled;
             cfg.tts_auto_speak = snapshot.tts_auto_speak;
             cfg.clawdtalk_enabled = snapshot.clawdtalk_enabled;
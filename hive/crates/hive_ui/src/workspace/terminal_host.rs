@@ -1,8 +1,8 @@
 use gpui::*;
 
 use super::{
-    HiveWorkspace, InteractiveShell, ShellOutput, TerminalClear, TerminalCmd, TerminalKill,
-    TerminalRestart, TerminalSubmitCommand,
+    AppConfig, HiveConfig, HiveWorkspace, InteractiveShell, ShellOutput, TerminalClear,
+    TerminalCmd, TerminalKill, TerminalRestart, TerminalSubmitCommand,
 };
 
 pub(super) fn ensure_terminal_shell(
@@ -14,13 +14,18 @@ pub(super) fn ensure_terminal_shell(
     }
 
     let cwd = std::path::PathBuf::from(&workspace.terminal_data.cwd);
+    let settings = if cx.has_global::<AppConfig>() {
+        cx.global::<AppConfig>().0.get()
+    } else {
+        HiveConfig::default()
+    };
     let (cmd_tx, mut cmd_rx) = tokio::sync::mpsc::unbounded_channel::<TerminalCmd>();
     workspace.terminal_cmd_tx = Some(cmd_tx);
     workspace.terminal_data.is_running = true;
     workspace.terminal_data.push_system("Shell starting...");
 
     let task = cx.spawn(async move |this: WeakEntity<HiveWorkspace>, app: &mut AsyncApp| {
-        let mut shell = match InteractiveShell::new(Some(&cwd)) {
+        let mut shell = match InteractiveShell::new_recorded(Some(&cwd), &settings) {
             Ok(s) => s,
             Err(e) => {
                 let msg = format!("Failed to start shell: {e}");
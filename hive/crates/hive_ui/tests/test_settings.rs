@@ -22,6 +22,7 @@ fn test_settings_data_default() {
     assert_eq!(d.monthly_budget_usd, 100.0);
     assert!(d.auto_update);
     assert!(d.notifications_enabled);
+    assert!(d.terminal_recording);
 }
 
 #[test]
@@ -113,12 +114,14 @@ fn test_settings_data_preserves_toggles() {
     cfg.auto_routing = false;
     cfg.auto_update = false;
     cfg.notifications_enabled = false;
+    cfg.terminal_recording = false;
 
     let d = SettingsData::from_config(&cfg);
     assert!(d.privacy_mode);
     assert!(!d.auto_routing);
     assert!(!d.auto_update);
     assert!(!d.notifications_enabled);
+    assert!(!d.terminal_recording);
 }
 
 #[test]
@@ -51,6 +51,9 @@ actions!(
         FilesNewFolder,
         FilesCloseViewer,
         FilesClearChecked,
+        FilesPreviewReplace,
+        FilesApplyReplace,
+        FilesCancelReplace,
         // History panel
         HistoryRefresh,
         HistoryClearAll,
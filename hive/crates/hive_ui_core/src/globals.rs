@@ -4,7 +4,7 @@
 //! (which reads them) and the bootstrap code (which sets them) share the same
 //! types.  Each wrapper is a newtype around the service it wraps.
 
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use gpui::Global;
 
@@ -16,6 +16,7 @@ use hive_agents::competence_detection::CompetenceDetector;
 use hive_agents::mcp_server::McpServer;
 use hive_agents::personas::PersonaRegistry;
 use hive_agents::plugin_manager::PluginManager;
+use hive_agents::remote_team::NetworkTeamDispatcher;
 use hive_agents::skill_marketplace::SkillMarketplace;
 use hive_agents::skills::{SkillManager, SkillsRegistry};
 use hive_agents::specs::SpecManager;
@@ -32,6 +33,7 @@ use hive_blockchain::rpc_config::RpcConfigStore;
 use hive_blockchain::wallet_store::WalletStore;
 use hive_core::channels::ChannelStore;
 use hive_core::config::ConfigManager;
+use hive_core::enterprise::EnterpriseService;
 use hive_core::notifications::NotificationStore;
 use hive_core::persistence::Database;
 use hive_core::scheduler::Scheduler;
@@ -165,6 +167,13 @@ impl Global for AppChannels {}
 pub struct AppNetwork(pub Arc<HiveNodeHandle>);
 impl Global for AppNetwork {}
 
+/// Global slot for the dispatcher that delegates swarm teams to peers.
+///
+/// Filled by the P2P runtime once the node has started, and only when team
+/// delegation is enabled in `network.json`.
+pub struct AppTeamDispatcher(pub Arc<OnceLock<Arc<NetworkTeamDispatcher>>>);
+impl Global for AppTeamDispatcher {}
+
 /// Global wrapper for the messaging hub (Slack, Discord, Teams, etc.).
 pub struct AppMessaging(pub Arc<MessagingHub>);
 impl Global for AppMessaging {}
@@ -348,6 +357,11 @@ impl Global for AppReminderRx {}
 pub struct AppActivityService(pub Arc<hive_agents::ActivityService>);
 impl Global for AppActivityService {}
 
+/// Global wrapper for the enterprise service (teams, members, usage), mirrored
+/// into the hash-chained audit log.
+pub struct AppEnterprise(pub Arc<Mutex<EnterpriseService>>);
+impl Global for AppEnterprise {}
+
 /// Global wrapper for the agent notification service (approval requests, budget warnings).
 pub struct AppAgentNotifications(pub Arc<hive_agents::NotificationService>);
 impl Global for AppAgentNotifications {}
@@ -9,8 +9,9 @@ use std::time::SystemTime;
 
 use hive_ui_core::HiveTheme;
 use hive_ui_core::{
-    FilesClearChecked, FilesCloseViewer, FilesDeleteEntry, FilesNavigateBack, FilesNavigateTo,
-    FilesNewFile, FilesNewFolder, FilesOpenEntry, FilesRefresh, FilesToggleCheck,
+    FilesApplyReplace, FilesCancelReplace, FilesClearChecked, FilesCloseViewer, FilesDeleteEntry,
+    FilesNavigateBack, FilesNavigateTo, FilesNewFile, FilesNewFolder, FilesOpenEntry,
+    FilesPreviewReplace, FilesRefresh, FilesToggleCheck,
 };
 
 use crate::components::code_block::render_code_block;
@@ -94,6 +95,15 @@ pub struct FilesData {
     /// Semantic search results — populated when the filename filter has no
     /// matches and the `SemanticSearchService` global is available.
     pub semantic_results: Vec<FileSearchMatch>,
+    /// Literal content matches for `search_query`, streamed in by
+    /// `SearchService::search_streaming` as files are scanned.
+    pub content_results: Vec<FileSearchMatch>,
+    /// Whether a content search is still scanning.
+    pub content_search_running: bool,
+    /// Pending search-and-replace preview, shown until applied or cancelled.
+    pub replace_preview: Option<hive_fs::ReplacePreview>,
+    /// Result of the last replace, or why it failed.
+    pub replace_status: Option<String>,
 }
 
 impl Default for FilesData {
@@ -114,6 +124,10 @@ impl Default for FilesData {
                 viewed_file_size: 0,
                 checked_files: HashSet::new(),
                 semantic_results: Vec::new(),
+                content_results: Vec::new(),
+                content_search_running: false,
+                replace_preview: None,
+                replace_status: None,
             },
         }
     }
@@ -149,6 +163,10 @@ impl FilesData {
             viewed_file_size: 0,
             checked_files: HashSet::new(),
             semantic_results: Vec::new(),
+            content_results: Vec::new(),
+            content_search_running: false,
+            replace_preview: None,
+            replace_status: None,
         }
     }
 
@@ -279,8 +297,43 @@ impl FilesData {
     pub fn has_semantic_results(&self) -> bool {
         !self.semantic_results.is_empty()
     }
+
+    /// Search options for the Files panel's content search and replace:
+    /// a case-insensitive literal match of `search_query`.
+    pub fn content_search_options() -> hive_fs::SearchOptions {
+        hive_fs::SearchOptions {
+            mode: hive_fs::SearchMode::Literal,
+            case_sensitive: false,
+            max_results: MAX_CONTENT_RESULTS,
+            ..Default::default()
+        }
+    }
+
+    /// Append a streamed content match, keeping the list bounded.
+    pub fn push_content_result(&mut self, result: hive_fs::SearchResult) {
+        if self.content_results.len() >= MAX_CONTENT_RESULTS {
+            return;
+        }
+        self.content_results.push(FileSearchMatch {
+            file_path: result.path.to_string_lossy().into_owned(),
+            line_number: result.line_number,
+            snippet: result.line_content.trim().to_string(),
+            score: 1.0,
+        });
+    }
+
+    /// Drop content matches and any pending replace preview.
+    pub fn clear_content_search(&mut self) {
+        self.content_results.clear();
+        self.content_search_running = false;
+        self.replace_preview = None;
+        self.replace_status = None;
+    }
 }
 
+/// Most content matches the Files panel collects for one query.
+pub const MAX_CONTENT_RESULTS: usize = 200;
+
 /// Map file extension to language name for the code block header.
 pub fn extension_to_language(ext: &str) -> &str {
     match ext {
@@ -457,6 +510,7 @@ impl FilesPanel {
     pub fn render(
         data: &FilesData,
         search_input: &Entity<InputState>,
+        replace_input: &Entity<InputState>,
         theme: &HiveTheme,
     ) -> impl IntoElement {
         let entries = data.filtered_sorted_entries();
@@ -465,6 +519,7 @@ impl FilesPanel {
         let now = Utc::now();
 
         let show_semantic = entries.is_empty() && data.has_semantic_results();
+        let has_query = !data.search_query.trim().is_empty();
 
         let mut file_browser = div()
             .flex()
@@ -479,6 +534,14 @@ impl FilesPanel {
             .child(Self::header(data, theme))
             // 2. Search bar
             .child(Self::search_bar(search_input, theme))
+            .when(has_query, |el| {
+                el.child(Self::replace_bar(
+                    replace_input,
+                    data.replace_preview.is_some(),
+                    data.replace_status.as_deref(),
+                    theme,
+                ))
+            })
             // 3. File tree (scrollable)
             .child(Self::file_tree(
                 &entries,
@@ -492,12 +555,35 @@ impl FilesPanel {
         // 3b. Semantic search results — shown when filename filter has no
         // matches but content-based search found relevant files.
         if show_semantic {
-            file_browser = file_browser.child(Self::semantic_results_section(
+            file_browser = file_browser.child(Self::results_section(
+                "files-semantic-results",
+                format!("Semantic matches ({})", data.semantic_results.len()),
                 &data.semantic_results,
                 theme,
             ));
         }
 
+        // 3c. Literal content matches, streamed in while the search runs,
+        // or the pending replace preview in their place.
+        if let Some(ref preview) = data.replace_preview {
+            file_browser = file_browser.child(Self::replace_preview_section(preview, theme));
+        } else if has_query && (data.content_search_running || !data.content_results.is_empty()) {
+            let title = if data.content_search_running {
+                format!(
+                    "Content matches ({}, searching...)",
+                    data.content_results.len()
+                )
+            } else {
+                format!("Content matches ({})", data.content_results.len())
+            };
+            file_browser = file_browser.child(Self::results_section(
+                "files-content-results",
+                title,
+                &data.content_results,
+                theme,
+            ));
+        }
+
         // 4. Action bar
         let file_browser = file_browser.child(Self::action_bar(
             dir_count,
@@ -817,15 +903,112 @@ impl FilesPanel {
     }
 
     // ------------------------------------------------------------------
-    // 3b. Semantic search results (content-based fallback)
+    // 2b. Replace bar
+    // ------------------------------------------------------------------
+
+    fn replace_bar(
+        replace_input: &Entity<InputState>,
+        has_preview: bool,
+        status: Option<&str>,
+        theme: &HiveTheme,
+    ) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap(theme.space_1)
+            .px(theme.space_3)
+            .py(theme.space_2)
+            .border_b_1()
+            .border_color(theme.border)
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(theme.space_2)
+                    .child(
+                        div()
+                            .flex_1()
+                            .px(theme.space_3)
+                            .py(theme.space_1)
+                            .rounded(theme.radius_md)
+                            .bg(theme.bg_primary)
+                            .border_1()
+                            .border_color(theme.border)
+                            .child(
+                                Input::new(replace_input)
+                                    .appearance(false)
+                                    .cleanable(true)
+                                    .text_size(theme.font_size_sm),
+                            ),
+                    )
+                    .child(
+                        Self::bottom_action_btn(
+                            IconName::Eye,
+                            "Preview",
+                            "files-replace-preview-btn",
+                            theme,
+                        )
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            move |_event, window, cx| {
+                                window.dispatch_action(Box::new(FilesPreviewReplace), cx);
+                            },
+                        ),
+                    )
+                    .when(has_preview, |el| {
+                        el.child(
+                            Self::bottom_action_btn(
+                                IconName::Check,
+                                "Replace All",
+                                "files-replace-apply-btn",
+                                theme,
+                            )
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                move |_event, window, cx| {
+                                    window.dispatch_action(Box::new(FilesApplyReplace), cx);
+                                },
+                            ),
+                        )
+                        .child(
+                            Self::bottom_action_btn(
+                                IconName::Close,
+                                "Cancel",
+                                "files-replace-cancel-btn",
+                                theme,
+                            )
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                move |_event, window, cx| {
+                                    window.dispatch_action(Box::new(FilesCancelReplace), cx);
+                                },
+                            ),
+                        )
+                    }),
+            )
+            .when_some(status, |el, status| {
+                el.child(
+                    div()
+                        .text_size(theme.font_size_xs)
+                        .text_color(theme.text_muted)
+                        .child(status.to_string()),
+                )
+            })
+    }
+
+    // ------------------------------------------------------------------
+    // 3b. Search results (semantic fallback and literal content matches)
     // ------------------------------------------------------------------
 
-    fn semantic_results_section(
+    fn results_section(
+        id: &'static str,
+        title: String,
         results: &[FileSearchMatch],
         theme: &HiveTheme,
     ) -> impl IntoElement {
         let mut list = div()
-            .id("files-semantic-results")
+            .id(id)
             .flex()
             .flex_col()
             .overflow_y_scroll()
@@ -850,7 +1033,7 @@ impl FilesPanel {
                         .text_size(theme.font_size_xs)
                         .text_color(theme.accent_cyan)
                         .font_weight(FontWeight::SEMIBOLD)
-                        .child(format!("Content matches ({})", results.len())),
+                        .child(title),
                 ),
         );
 
@@ -861,8 +1044,9 @@ impl FilesPanel {
                 .map(|n| n.to_string_lossy().to_string())
                 .unwrap_or_else(|| result.file_path.clone());
 
-            let snippet = if result.snippet.len() > 120 {
-                format!("{}...", &result.snippet[..120])
+            let snippet = if result.snippet.chars().count() > 120 {
+                let head: String = result.snippet.chars().take(120).collect();
+                format!("{head}...")
             } else {
                 result.snippet.clone()
             };
@@ -870,7 +1054,10 @@ impl FilesPanel {
             let file_path = result.file_path.clone();
             list = list.child(
                 div()
-                    .id(SharedString::from(format!("semantic-{}", result.file_path)))
+                    .id(SharedString::from(format!(
+                        "{id}-{}:{}",
+                        result.file_path, result.line_number
+                    )))
                     .flex()
                     .flex_col()
                     .px(theme.space_2)
@@ -917,6 +1104,64 @@ impl FilesPanel {
         list
     }
 
+    // ------------------------------------------------------------------
+    // 3c. Replace preview
+    // ------------------------------------------------------------------
+
+    fn replace_preview_section(
+        preview: &hive_fs::ReplacePreview,
+        theme: &HiveTheme,
+    ) -> impl IntoElement {
+        let mut list = div()
+            .id("files-replace-preview")
+            .flex()
+            .flex_col()
+            .overflow_y_scroll()
+            .px(theme.space_3)
+            .py(theme.space_2)
+            .gap(theme.space_1)
+            .child(
+                div()
+                    .text_size(theme.font_size_xs)
+                    .text_color(theme.accent_cyan)
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .child(format!(
+                        "Replace {} match(es) in {} file(s)",
+                        preview.total_replacements(),
+                        preview.files.len()
+                    )),
+            );
+
+        for file in &preview.files {
+            list = list.child(
+                div()
+                    .mt(theme.space_1)
+                    .text_size(theme.font_size_sm)
+                    .text_color(theme.text_primary)
+                    .child(format!("{} ({})", file.path.display(), file.replacements)),
+            );
+            for edit in &file.edits {
+                for (line, color, sign) in edit
+                    .before
+                    .lines()
+                    .map(|l| (l, theme.accent_red, '-'))
+                    .chain(edit.after.lines().map(|l| (l, theme.accent_green, '+')))
+                {
+                    list = list.child(
+                        div()
+                            .text_size(theme.font_size_xs)
+                            .font_family(theme.font_mono.clone())
+                            .text_color(color)
+                            .overflow_hidden()
+                            .child(format!("{:>5} {sign} {line}", edit.line_number)),
+                    );
+                }
+            }
+        }
+
+        list
+    }
+
     // ------------------------------------------------------------------
     // 3. File tree (scrollable list)
     // ------------------------------------------------------------------
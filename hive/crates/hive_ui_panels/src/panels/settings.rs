@@ -27,6 +27,7 @@ actions!(
         SettingsToggleAutoRouting,
         SettingsToggleAutoUpdate,
         SettingsToggleNotifications,
+        SettingsToggleTerminalRecording,
         SettingsToggleTts,
         SettingsToggleTtsAutoSpeak,
         SettingsToggleClawdTalk,
@@ -311,6 +312,7 @@ pub struct SettingsData {
     pub font_size: u32,
     pub auto_update: bool,
     pub notifications_enabled: bool,
+    pub terminal_recording: bool,
     pub log_level: String,
     // TTS
     pub has_elevenlabs_key: bool,
@@ -369,6 +371,7 @@ impl Default for SettingsData {
             font_size: 14,
             auto_update: true,
             notifications_enabled: true,
+            terminal_recording: true,
             log_level: "info".into(),
             has_elevenlabs_key: false,
             has_telnyx_key: false,
@@ -437,6 +440,7 @@ impl SettingsData {
             font_size: cfg.font_size,
             auto_update: cfg.auto_update,
             notifications_enabled: cfg.notifications_enabled,
+            terminal_recording: cfg.terminal_recording,
             log_level: cfg.log_level.clone(),
             has_elevenlabs_key: cfg
                 .elevenlabs_api_key
@@ -550,6 +554,7 @@ pub struct SettingsView {
     speculative_show_metrics: bool,
     auto_update: bool,
     notifications_enabled: bool,
+    terminal_recording: bool,
 
     // TTS key inputs
     elevenlabs_key_input: Entity<InputState>,
@@ -923,6 +928,7 @@ impl SettingsView {
             speculative_show_metrics: cfg.speculative_show_metrics,
             auto_update: cfg.auto_update,
             notifications_enabled: cfg.notifications_enabled,
+            terminal_recording: cfg.terminal_recording,
             elevenlabs_key_input,
             telnyx_key_input,
             tts_enabled: cfg.tts_enabled,
@@ -1536,6 +1542,7 @@ impl SettingsView {
             speculative_show_metrics: self.speculative_show_metrics,
             auto_update: self.auto_update,
             notifications_enabled: self.notifications_enabled,
+            terminal_recording: self.terminal_recording,
             tts_enabled: self.tts_enabled,
             tts_auto_speak: self.tts_auto_speak,
             clawdtalk_enabled: self.clawdtalk_enabled,
@@ -1795,6 +1802,7 @@ pub struct SettingsSnapshot {
     pub speculative_show_metrics: bool,
     pub auto_update: bool,
     pub notifications_enabled: bool,
+    pub terminal_recording: bool,
     pub tts_enabled: bool,
     pub tts_auto_speak: bool,
     pub clawdtalk_enabled: bool,
@@ -1915,6 +1923,13 @@ impl Render for SettingsView {
                     cx.notify();
                 }),
             )
+            .on_action(cx.listener(
+                |this: &mut Self, _: &SettingsToggleTerminalRecording, _, cx| {
+                    this.terminal_recording = !this.terminal_recording;
+                    cx.emit(SettingsSaved);
+                    cx.notify();
+                },
+            ))
             .on_action(cx.listener(
                 |this: &mut Self, _: &SettingsToggleSpeculativeDecoding, _, cx| {
                     this.speculative_decoding = !this.speculative_decoding;
@@ -3026,6 +3041,13 @@ impl SettingsView {
                 SettingsToggleNotifications,
                 theme,
             ))
+            .child(switch_row(
+                "Terminal Recording",
+                "terminal-recording-switch",
+                self.terminal_recording,
+                SettingsToggleTerminalRecording,
+                theme,
+            ))
             .into_any_element()
     }
 